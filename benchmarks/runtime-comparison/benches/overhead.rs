@@ -0,0 +1,40 @@
+// Per-invocation overhead: ruchy-lambda-runtime vs lambda_runtime
+//
+// Each sample starts a mock Runtime API with `iters` events and times one
+// runtime from startup until it has posted every response, so startup is
+// amortized over the sample and the reported time is per invocation.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use runtime_comparison::{configure_env, run_lambda_runtime, run_ruchy, MockApi};
+use std::time::{Duration, Instant};
+
+fn measure(iters: u64, run: impl FnOnce(usize)) -> Duration {
+    let events = usize::try_from(iters).expect("iteration count fits usize");
+    let api = MockApi::start(events);
+    configure_env(api.addr());
+
+    let start = Instant::now();
+    run(events);
+    let elapsed = start.elapsed();
+
+    assert_eq!(api.responses(), events, "every event answered");
+    api.shutdown();
+    elapsed
+}
+
+fn invocation_overhead(c: &mut Criterion) {
+    let mut group = c.benchmark_group("invocation_overhead");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("ruchy_lambda_runtime", |b| {
+        b.iter_custom(|iters| measure(iters, run_ruchy));
+    });
+    group.bench_function("lambda_runtime", |b| {
+        b.iter_custom(|iters| measure(iters, |_| run_lambda_runtime()));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, invocation_overhead);
+criterion_main!(benches);
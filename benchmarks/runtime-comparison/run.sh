@@ -0,0 +1,32 @@
+#!/bin/bash
+# Runtime comparison: binary size and per-invocation overhead
+#
+# Usage: ./run.sh [criterion args...]
+#   ./run.sh                      # full run
+#   ./run.sh --quick              # faster, noisier
+#   CARGO_FLAGS=--offline ./run.sh
+#
+# Both functions run the same handler against the same mock Runtime API;
+# see src/lib.rs.
+
+set -euo pipefail
+cd "$(dirname "$0")"
+CARGO_FLAGS=(${CARGO_FLAGS:-})
+
+echo "Building release binaries (opt-level=z, LTO, stripped)..."
+cargo build --release --bins --quiet "${CARGO_FLAGS[@]}"
+
+ruchy=$(stat -c %s target/release/ruchy_echo 2>/dev/null || stat -f %z target/release/ruchy_echo)
+official=$(stat -c %s target/release/lambda_runtime_echo 2>/dev/null || stat -f %z target/release/lambda_runtime_echo)
+
+echo ""
+echo "Binary size"
+printf "  %-24s %10d bytes\n" "ruchy-lambda-runtime" "$ruchy"
+printf "  %-24s %10d bytes\n" "lambda_runtime" "$official"
+printf "  %-24s %10d bytes (%d%%)\n" "delta" "$((official - ruchy))" "$(((official - ruchy) * 100 / official))"
+echo ""
+
+echo "Per-invocation overhead (criterion)..."
+cargo bench --bench overhead "${CARGO_FLAGS[@]}" -- "$@"
+echo ""
+echo "✓ Reports: target/criterion/invocation_overhead/report/index.html"
@@ -0,0 +1,5 @@
+// The same function on the official lambda_runtime crate (binary size baseline)
+
+fn main() {
+    runtime_comparison::run_lambda_runtime();
+}
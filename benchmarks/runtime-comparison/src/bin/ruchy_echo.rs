@@ -0,0 +1,11 @@
+// Minimal deployable function on ruchy-lambda-runtime (binary size baseline)
+
+fn main() {
+    let runtime = ruchy_lambda_runtime::Runtime::new().expect("AWS_LAMBDA_RUNTIME_API");
+    loop {
+        let Ok((request_id, event)) = runtime.next_event() else {
+            continue;
+        };
+        let _ = runtime.post_response(&request_id, &runtime_comparison::handle(&event));
+    }
+}
@@ -0,0 +1,211 @@
+// Runtime comparison harness
+//
+// Runs the same handler on ruchy-lambda-runtime and on the official
+// lambda_runtime crate against one mock Runtime API, so the difference in
+// time per invocation is the difference in runtime overhead.
+//
+// MockApi speaks just enough HTTP/1.1 for both clients: keep-alive (hyper)
+// and `Connection: close` (ruchy-lambda), Content-Length bodies only. Once
+// its events run out it closes connections instead of answering /next,
+// which makes lambda_runtime::run return.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+/// Event body sent for every invocation
+pub const EVENT: &str =
+    r#"{"orderId":"o-1042","items":[{"sku":"tea","quantity":2},{"sku":"cup","quantity":1}]}"#;
+
+/// The handler both runtimes run: parse the event and answer with a summary
+#[must_use]
+pub fn handle(event: &str) -> String {
+    let event: serde_json::Value = serde_json::from_str(event).unwrap_or_default();
+    let quantity: u64 = event["items"].as_array().map_or(0, |items| {
+        items
+            .iter()
+            .filter_map(|item| item["quantity"].as_u64())
+            .sum()
+    });
+    serde_json::json!({"orderId": event["orderId"], "quantity": quantity}).to_string()
+}
+
+/// Point both runtimes at `addr` (they read their configuration from the
+/// environment, as on Lambda)
+pub fn configure_env(addr: SocketAddr) {
+    std::env::set_var("AWS_LAMBDA_RUNTIME_API", addr.to_string());
+    std::env::set_var("AWS_LAMBDA_FUNCTION_NAME", "runtime-comparison");
+    std::env::set_var("AWS_LAMBDA_FUNCTION_MEMORY_SIZE", "128");
+    std::env::set_var("AWS_LAMBDA_FUNCTION_VERSION", "$LATEST");
+}
+
+/// Mock Lambda Runtime API serving a fixed number of events
+pub struct MockApi {
+    addr: SocketAddr,
+    responses: Arc<AtomicUsize>,
+    server: thread::JoinHandle<()>,
+}
+
+impl MockApi {
+    /// Serve `events` copies of [`EVENT`] on an ephemeral port
+    ///
+    /// # Panics
+    ///
+    /// Panics if no local port can be bound.
+    #[must_use]
+    pub fn start(events: usize) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock Runtime API");
+        let addr = listener.local_addr().expect("mock Runtime API address");
+        let issued = Arc::new(AtomicUsize::new(0));
+        let responses = Arc::new(AtomicUsize::new(0));
+
+        let server = {
+            let responses = Arc::clone(&responses);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { break };
+                    let done = responses.load(Ordering::SeqCst) >= events;
+                    if done && issued.load(Ordering::SeqCst) >= events {
+                        // Runtime is polling for more: hang up (and stop serving)
+                        drop(stream);
+                        break;
+                    }
+                    let issued = Arc::clone(&issued);
+                    let responses = Arc::clone(&responses);
+                    thread::spawn(move || serve(stream, events, &issued, &responses));
+                }
+            })
+        };
+
+        Self {
+            addr,
+            responses,
+            server,
+        }
+    }
+
+    /// Address to use as `AWS_LAMBDA_RUNTIME_API`
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Number of responses posted so far
+    #[must_use]
+    pub fn responses(&self) -> usize {
+        self.responses.load(Ordering::SeqCst)
+    }
+
+    /// Stop accepting connections and wait for the server thread
+    pub fn shutdown(self) {
+        // Unblock accept(); the connection is dropped unanswered
+        let _ = TcpStream::connect(self.addr);
+        let _ = self.server.join();
+    }
+}
+
+/// Serve requests on one connection until it closes or events run out
+fn serve(stream: TcpStream, events: usize, issued: &AtomicUsize, responses: &AtomicUsize) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+            return;
+        }
+
+        let mut content_length = 0;
+        let mut close = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                let value = value.trim();
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.parse().unwrap_or(0);
+                } else if name.eq_ignore_ascii_case("connection") {
+                    close = value.eq_ignore_ascii_case("close");
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+
+        let reply = if request_line.contains("/invocation/next") {
+            let n = issued.fetch_add(1, Ordering::SeqCst);
+            if n >= events {
+                return;
+            }
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Lambda-Runtime-Aws-Request-Id: req-{n}\r\n\
+                 Lambda-Runtime-Deadline-Ms: 4102444800000\r\n\
+                 Lambda-Runtime-Invoked-Function-Arn: arn:aws:lambda:us-east-1:123456789012:function:runtime-comparison\r\n\
+                 Lambda-Runtime-Trace-Id: Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1\r\n\
+                 Content-Length: {}\r\n\r\n{EVENT}",
+                EVENT.len()
+            )
+        } else {
+            responses.fetch_add(1, Ordering::SeqCst);
+            "HTTP/1.1 202 Accepted\r\nContent-Length: 16\r\n\r\n{\"status\":\"OK\"}\n".to_string()
+        };
+
+        if writer.write_all(reply.as_bytes()).is_err() || close {
+            return;
+        }
+    }
+}
+
+/// Run `invocations` events through ruchy-lambda-runtime
+///
+/// # Panics
+///
+/// Panics if the runtime fails against the mock API.
+pub fn run_ruchy(invocations: usize) {
+    let runtime = ruchy_lambda_runtime::Runtime::new().expect("ruchy runtime");
+    for _ in 0..invocations {
+        let (request_id, event) = runtime.next_event().expect("next event");
+        runtime
+            .post_response(&request_id, &handle(&event))
+            .expect("post response");
+    }
+}
+
+/// Run events through `lambda_runtime` until the mock API runs out
+///
+/// Uses a current-thread Tokio runtime, the usual choice for Lambda
+/// functions (one invocation at a time).
+///
+/// # Panics
+///
+/// Panics if the Tokio runtime cannot be built.
+pub fn run_lambda_runtime() {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("tokio runtime")
+        .block_on(async {
+            let handler = lambda_runtime::service_fn(
+                |event: lambda_runtime::LambdaEvent<serde_json::Value>| async move {
+                    let response: serde_json::Value =
+                        serde_json::from_str(&handle(&event.payload.to_string()))?;
+                    Ok::<_, lambda_runtime::Error>(response)
+                },
+            );
+            // Err once the mock API hangs up: that is how the run ends
+            let _ = lambda_runtime::run(handler).await;
+        });
+}
@@ -2,93 +2,239 @@
 // Integrates Ruchy transpiler into Cargo build process
 //
 // This runs during `cargo build` BEFORE compiling Rust code
-// Automatically transpiles .ruchy files to .rs files
+// Transpiles the .ruchy handlers listed in handlers.toml to .rs files
+//
+// Incremental builds: each handler's content hash (source + entry + transpiler
+// fingerprint) is cached in OUT_DIR; unchanged handlers skip the transpiler.
+//
+// Build metadata (git SHA, profile, transpiler version, timestamp) is exported
+// as RUCHY_LAMBDA_* env vars for src/build_info.rs.
 
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::env;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MANIFEST: &str = "handlers.toml";
+const CACHE_FILE: &str = "transpile-cache.txt";
+
+/// handlers.toml root
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    handler: Vec<HandlerSpec>,
+}
+
+/// One `[[handler]]` entry
+#[derive(Deserialize)]
+struct HandlerSpec {
+    name: String,
+    source: PathBuf,
+    entry: String,
+    output: Option<PathBuf>,
+}
+
+impl HandlerSpec {
+    /// Output path (defaults to `<source stem>_generated.rs` next to the source)
+    fn output_path(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| {
+            let stem = self
+                .source
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(&self.name);
+            self.source.with_file_name(format!("{stem}_generated.rs"))
+        })
+    }
+}
 
 fn main() {
-    println!("cargo:rerun-if-changed=src/handler.ruchy");
-    println!("cargo:rerun-if-changed=../../examples/simple_handler.ruchy");
+    println!("cargo:rerun-if-changed={MANIFEST}");
+
+    let manifest = load_manifest();
+    for spec in &manifest.handler {
+        println!("cargo:rerun-if-changed={}", spec.source.display());
+    }
+
+    // Note: handler_simd_vector.rs is pure Rust (not transpiled)
+    // ARM NEON intrinsics require direct Rust implementation
+    println!("cargo:rerun-if-changed=src/handler_simd_vector.rs");
+    println!("cargo:rerun-if-changed=src/handler_matmul.rs");
 
     // Path to Ruchy compiler (use trunk version)
     let ruchy_path = "../../../ruchy/target/debug/ruchy";
+    if Path::new(ruchy_path).exists() {
+        println!("cargo:rerun-if-changed={ruchy_path}");
+    }
 
-    // Check if ruchy exists, if not try cargo run
-    let ruchy_manifest = Path::new("../../../ruchy/Cargo.toml");
-    let ruchy_exists = Path::new(ruchy_path).exists();
+    emit_build_metadata(ruchy_path);
 
-    if !ruchy_exists {
-        if !ruchy_manifest.exists() {
-            println!("cargo:warning=Ruchy compiler not found (no sibling checkout) — skipping transpilation");
-            return;
-        }
-        println!("cargo:warning=Building Ruchy transpiler first...");
-        // Build ruchy if not built
-        let status = Command::new("cargo")
-            .args(["build", "--manifest-path", "../../../ruchy/Cargo.toml"])
-            .status();
-
-        match status {
-            Ok(s) if s.success() => {}
-            _ => {
-                println!("cargo:warning=Failed to build Ruchy transpiler — skipping transpilation");
-                return;
+    let cache_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join(CACHE_FILE);
+    let mut cache = load_cache(&cache_path);
+
+    // Only handlers whose source exists and whose hash changed need the transpiler
+    let stale: Vec<&HandlerSpec> = manifest
+        .handler
+        .iter()
+        .filter(|spec| spec.source.exists())
+        .filter(|spec| {
+            !spec.output_path().exists()
+                || cache.get(&spec.name) != Some(&content_hash(spec, ruchy_path))
+        })
+        .collect();
+
+    if stale.is_empty() {
+        return;
+    }
+
+    if !ensure_transpiler(ruchy_path) {
+        return;
+    }
+
+    for spec in stale {
+        println!(
+            "cargo:warning=Transpiling {} ({})...",
+            spec.name,
+            spec.source.display()
+        );
+        transpile_file(spec, ruchy_path);
+        cache.insert(spec.name.clone(), content_hash(spec, ruchy_path));
+    }
+
+    save_cache(&cache_path, &cache);
+
+    println!("cargo:warning=Ruchy transpilation complete");
+}
+
+/// Export build metadata to the compiler as `RUCHY_LAMBDA_*` env vars
+fn emit_build_metadata(ruchy_path: &str) {
+    // Re-run when HEAD moves so the embedded SHA tracks the checkout
+    let git_head = Path::new("../../.git/HEAD");
+    if git_head.exists() {
+        println!("cargo:rerun-if-changed={}", git_head.display());
+        if let Ok(head) = std::fs::read_to_string(git_head) {
+            if let Some(reference) = head.trim().strip_prefix("ref: ") {
+                println!("cargo:rerun-if-changed=../../.git/{reference}");
             }
         }
     }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
 
-    // Transpile example handler if it exists
-    let example_input = PathBuf::from("../../examples/simple_handler.ruchy");
-    let example_output = PathBuf::from("../../examples/simple_handler_generated.rs");
+    let git_sha = command_output("git", &["rev-parse", "--short=12", "HEAD"])
+        .unwrap_or_else(|| "unknown".to_string());
+    let profile = env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string());
+    let ruchy_version = command_output(ruchy_path, &["--version"])
+        .or_else(|| command_output("ruchy", &["--version"]))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible
+    let timestamp = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        });
+
+    println!("cargo:rustc-env=RUCHY_LAMBDA_GIT_SHA={git_sha}");
+    println!("cargo:rustc-env=RUCHY_LAMBDA_BUILD_PROFILE={profile}");
+    println!("cargo:rustc-env=RUCHY_LAMBDA_RUCHY_VERSION={ruchy_version}");
+    println!("cargo:rustc-env=RUCHY_LAMBDA_BUILD_TIMESTAMP={timestamp}");
+}
 
-    if example_input.exists() {
-        println!("cargo:warning=Transpiling simple_handler.ruchy...");
-        transpile_file(&example_input, &example_output, ruchy_path);
+/// First line of a command's stdout, or None if it fails to run
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
     }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().next()?.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// Parse handlers.toml (a missing or malformed manifest fails the build)
+fn load_manifest() -> Manifest {
+    let text = std::fs::read_to_string(MANIFEST)
+        .unwrap_or_else(|e| panic!("Failed to read {MANIFEST}: {e}"));
+    toml::from_str(&text).unwrap_or_else(|e| panic!("Invalid {MANIFEST}: {e}"))
+}
 
-    // Transpile main handler if it exists
-    let handler_input = PathBuf::from("src/handler.ruchy");
-    let handler_output = PathBuf::from("src/handler_generated.rs");
+/// Make sure the Ruchy transpiler is available, building the sibling checkout if needed
+fn ensure_transpiler(ruchy_path: &str) -> bool {
+    if Path::new(ruchy_path).exists() {
+        return true;
+    }
 
-    if handler_input.exists() {
-        println!("cargo:warning=Transpiling src/handler.ruchy...");
-        transpile_file(&handler_input, &handler_output, ruchy_path);
+    // Check if ruchy exists, if not try cargo run
+    let ruchy_manifest = Path::new("../../../ruchy/Cargo.toml");
+    if !ruchy_manifest.exists() {
+        println!(
+            "cargo:warning=Ruchy compiler not found (no sibling checkout) — skipping transpilation"
+        );
+        return false;
     }
 
-    // Transpile minimal handler if it exists (for lambda-perf benchmarks)
-    let handler_minimal_input = PathBuf::from("src/handler_minimal.ruchy");
-    let handler_minimal_output = PathBuf::from("src/handler_minimal_generated.rs");
+    println!("cargo:warning=Building Ruchy transpiler first...");
+    // Build ruchy if not built
+    let status = Command::new("cargo")
+        .args(["build", "--manifest-path", "../../../ruchy/Cargo.toml"])
+        .status();
 
-    if handler_minimal_input.exists() {
-        println!("cargo:warning=Transpiling src/handler_minimal.ruchy...");
-        transpile_file(&handler_minimal_input, &handler_minimal_output, ruchy_path);
-        println!("cargo:rerun-if-changed=src/handler_minimal.ruchy");
+    if let Ok(s) = status {
+        if s.success() {
+            return true;
+        }
     }
+    println!("cargo:warning=Failed to build Ruchy transpiler — skipping transpilation");
+    false
+}
 
-    // Transpile fibonacci handler if it exists (for CPU benchmarks)
-    let handler_fibonacci_input = PathBuf::from("src/handler_fibonacci.ruchy");
-    let handler_fibonacci_output = PathBuf::from("src/handler_fibonacci_generated.rs");
+/// Hash of everything that affects a handler's transpiled output
+fn content_hash(spec: &HandlerSpec, ruchy_path: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    std::fs::read(&spec.source)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    spec.entry.hash(&mut hasher);
+    spec.output_path().hash(&mut hasher);
 
-    if handler_fibonacci_input.exists() {
-        println!("cargo:warning=Transpiling src/handler_fibonacci.ruchy...");
-        transpile_file(
-            &handler_fibonacci_input,
-            &handler_fibonacci_output,
-            ruchy_path,
-        );
-        println!("cargo:rerun-if-changed=src/handler_fibonacci.ruchy");
+    // Transpiler fingerprint: a rebuilt ruchy binary invalidates every handler
+    if let Ok(meta) = std::fs::metadata(ruchy_path) {
+        meta.len().hash(&mut hasher);
+        meta.modified().ok().hash(&mut hasher);
     }
 
-    // Note: handler_simd_vector.rs is pure Rust (not transpiled)
-    // ARM NEON intrinsics require direct Rust implementation
-    println!("cargo:rerun-if-changed=src/handler_simd_vector.rs");
-    println!("cargo:rerun-if-changed=src/simd_ops.rs");
+    format!("{:016x}", hasher.finish())
+}
 
-    println!("cargo:warning=Ruchy transpilation complete");
+/// Cache format: one `name hash` pair per line
+fn load_cache(path: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(name, hash)| (name.to_string(), hash.to_string()))
+        .collect()
+}
+
+fn save_cache(path: &Path, cache: &BTreeMap<String, String>) {
+    let contents: String = cache
+        .iter()
+        .map(|(name, hash)| format!("{name} {hash}\n"))
+        .collect();
+    std::fs::write(path, contents).expect("Failed to write transpilation cache");
 }
 
-fn transpile_file(input: &Path, output: &Path, ruchy_path: &str) {
+fn transpile_file(spec: &HandlerSpec, ruchy_path: &str) {
+    let input = &spec.source;
+    let output = spec.output_path();
+
     let status = Command::new(ruchy_path)
         .args(["transpile", input.to_str().unwrap()])
         .output()
@@ -102,6 +248,13 @@ fn transpile_file(input: &Path, output: &Path, ruchy_path: &str) {
     // Write transpiled output with dead code allowance for generated stubs
     let mut transpiled = String::from_utf8_lossy(&status.stdout).to_string();
 
+    if !transpiled.contains(&format!("fn {}(", spec.entry)) {
+        panic!(
+            "Handler '{}': entry `{}` not found in transpiled {:?}",
+            spec.name, spec.entry, input
+        );
+    }
+
     // Add clippy suppression to public functions (generated code)
     transpiled = transpiled.replace("pub fn ", "#[allow(clippy::all)]\npub fn ");
 
@@ -110,7 +263,7 @@ fn transpile_file(input: &Path, output: &Path, ruchy_path: &str) {
         transpiled = transpiled.replace("fn main() {}", "#[allow(dead_code)]\nfn main() {}");
     }
 
-    std::fs::write(output, transpiled.as_bytes()).expect("Failed to write transpiled output");
+    std::fs::write(&output, transpiled.as_bytes()).expect("Failed to write transpiled output");
 
     println!("cargo:warning=  Transpiled {:?} -> {:?}", input, output);
 }
@@ -0,0 +1,162 @@
+// Build metadata embedded at compile time (see build.rs)
+//
+// Answers "which artifact is actually deployed?":
+// - Logged once at init
+// - Returned for the special event body {"__ruchy":"version"}
+
+/// Short git commit SHA of the build checkout ("unknown" outside git)
+pub const GIT_SHA: &str = env!("RUCHY_LAMBDA_GIT_SHA");
+
+/// Cargo build profile (`debug` or `release`; custom profiles report their base)
+pub const BUILD_PROFILE: &str = env!("RUCHY_LAMBDA_BUILD_PROFILE");
+
+/// `ruchy --version` output of the transpiler ("unknown" if unavailable)
+pub const RUCHY_VERSION: &str = env!("RUCHY_LAMBDA_RUCHY_VERSION");
+
+/// Build time in Unix epoch seconds (honors `SOURCE_DATE_EPOCH`)
+pub const BUILD_TIMESTAMP: &str = env!("RUCHY_LAMBDA_BUILD_TIMESTAMP");
+
+/// Global allocator selected by cargo feature (`system` unless one is enabled)
+pub const ALLOCATOR: &str = if cfg!(feature = "mimalloc") {
+    "mimalloc"
+} else {
+    "system"
+};
+
+/// Bootstrap crate version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Key of the special version event (`{"__ruchy":"version"}`)
+const VERSION_EVENT_KEY: &str = "__ruchy";
+
+/// One-line summary for the init log
+pub fn summary() -> String {
+    format!(
+        "version={VERSION} sha={GIT_SHA} profile={BUILD_PROFILE} ruchy=\"{RUCHY_VERSION}\" built={BUILD_TIMESTAMP} alloc={ALLOCATOR}"
+    )
+}
+
+/// Build metadata as a JSON response body
+#[cfg(feature = "serde")]
+pub fn version_json() -> String {
+    serde_json::json!({
+        "version": VERSION,
+        "gitSha": GIT_SHA,
+        "buildProfile": BUILD_PROFILE,
+        "ruchyVersion": RUCHY_VERSION,
+        "buildTimestamp": BUILD_TIMESTAMP,
+        "allocator": ALLOCATOR,
+    })
+    .to_string()
+}
+
+/// Build metadata as a JSON response body
+#[cfg(not(feature = "serde"))]
+pub fn version_json() -> String {
+    let fields = [
+        ("version", VERSION),
+        ("gitSha", GIT_SHA),
+        ("buildProfile", BUILD_PROFILE),
+        ("ruchyVersion", RUCHY_VERSION),
+        ("buildTimestamp", BUILD_TIMESTAMP),
+        ("allocator", ALLOCATOR),
+    ];
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("\"{name}\":\"{}\"", escape_json(value)))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Escape a string for a JSON string literal
+#[cfg(not(feature = "serde"))]
+fn escape_json(value: &str) -> String {
+    use std::fmt::Write;
+
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether an event body is the special version request
+///
+/// The substring check keeps normal invocations from paying for a JSON parse.
+#[cfg(feature = "serde")]
+pub fn is_version_request(event_body: &str) -> bool {
+    if !event_body.contains(VERSION_EVENT_KEY) {
+        return false;
+    }
+
+    serde_json::from_str::<serde_json::Value>(event_body)
+        .ok()
+        .and_then(|value| {
+            value
+                .get(VERSION_EVENT_KEY)
+                .and_then(|v| v.as_str())
+                .map(|v| v == "version")
+        })
+        .unwrap_or(false)
+}
+
+/// Whether an event body is the special version request
+///
+/// Without serde: a `"__ruchy":"version"` member (whitespace ignored).
+#[cfg(not(feature = "serde"))]
+pub fn is_version_request(event_body: &str) -> bool {
+    if !event_body.contains(VERSION_EVENT_KEY) {
+        return false;
+    }
+
+    let compact: String = event_body.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.starts_with('{') && compact.contains(&format!("\"{VERSION_EVENT_KEY}\":\"version\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_version_request() {
+        assert!(is_version_request(r#"{"__ruchy":"version"}"#));
+        assert!(is_version_request(r#"{ "__ruchy" : "version" }"#));
+    }
+
+    #[test]
+    fn test_is_not_version_request() {
+        assert!(!is_version_request("{}"));
+        assert!(!is_version_request(r#"{"__ruchy":"other"}"#));
+        assert!(!is_version_request(r#"{"note":"__ruchy"}"#));
+        assert!(!is_version_request("__ruchy version"));
+    }
+
+    #[test]
+    fn test_version_json_fields() {
+        let value: serde_json::Value = serde_json::from_str(&version_json()).unwrap();
+        assert_eq!(value["version"], VERSION);
+        assert_eq!(value["gitSha"], GIT_SHA);
+        assert_eq!(value["buildProfile"], BUILD_PROFILE);
+        assert_eq!(value["ruchyVersion"], RUCHY_VERSION);
+        assert_eq!(value["buildTimestamp"], BUILD_TIMESTAMP);
+        assert_eq!(value["allocator"], ALLOCATOR);
+    }
+
+    #[test]
+    fn test_build_metadata_embedded() {
+        assert!(!GIT_SHA.is_empty());
+        assert!(!BUILD_PROFILE.is_empty());
+        assert!(BUILD_TIMESTAMP.parse::<u64>().is_ok());
+        assert!(summary().contains(GIT_SHA));
+        assert!(summary().contains(&format!("alloc={ALLOCATOR}")));
+    }
+}
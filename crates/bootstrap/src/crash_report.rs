@@ -0,0 +1,163 @@
+// Structured crash reports
+//
+// The default panic hook prints a bare text line (and a backtrace with
+// RUST_BACKTRACE) that CloudWatch splits across log events. The bootstrap
+// replaces it at startup with a hook that writes one JSON record through the
+// runtime Logger: panic message, source location, thread and the build
+// metadata of the deployed artifact. Under `[profile.release-ultra]`
+// (panic = "abort") the process aborts right after the hook returns, so the
+// record is the last line logged; other profiles unwind first.
+//
+// Panics the runtime catches and survives (background tasks, shutdown
+// hooks; see `panic_is_caught`) are not crashes: they are logged at WARN
+// under CAUGHT_PANIC_MESSAGE instead, so alarms on CRASH_MESSAGE only fire
+// when the process goes down.
+
+use crate::build_info;
+use ruchy_lambda_runtime::{panic_is_caught, LogLevel, Logger};
+use std::any::Any;
+use std::panic::{self, PanicHookInfo};
+
+/// Message of the crash record, for `CloudWatch` Logs Insights filters
+pub const CRASH_MESSAGE: &str = "Bootstrap panicked";
+
+/// Message of the record for a panic the runtime caught and survived
+pub const CAUGHT_PANIC_MESSAGE: &str = "Panic caught by the runtime";
+
+/// Replace the default panic hook with [`report`]
+pub fn install() {
+    panic::set_hook(Box::new(|info| report(&Logger::new(), info)));
+}
+
+/// Log the crash record for a panic, or a warning if the runtime catches it
+fn report(logger: &Logger, info: &PanicHookInfo<'_>) {
+    let (level, message) = record_kind();
+    let location = info
+        .location()
+        .map_or_else(|| "unknown".to_string(), ToString::to_string);
+    let thread = std::thread::current();
+    logger.log_with_text_fields(
+        level,
+        message,
+        &crash_fields(
+            payload_message(info.payload()),
+            &location,
+            thread.name().unwrap_or("<unnamed>"),
+        ),
+    );
+}
+
+/// Level and message of the record for a panic raised now on this thread
+fn record_kind() -> (LogLevel, &'static str) {
+    if panic_is_caught() {
+        (LogLevel::Warn, CAUGHT_PANIC_MESSAGE)
+    } else {
+        (LogLevel::Error, CRASH_MESSAGE)
+    }
+}
+
+/// Fields of a crash record: the panic and the build metadata
+fn crash_fields<'a>(
+    message: &'a str,
+    location: &'a str,
+    thread: &'a str,
+) -> [(&'static str, &'a str); 8] {
+    [
+        ("panic_message", message),
+        ("location", location),
+        ("thread", thread),
+        ("version", build_info::VERSION),
+        ("git_sha", build_info::GIT_SHA),
+        ("build_profile", build_info::BUILD_PROFILE),
+        ("ruchy_version", build_info::RUCHY_VERSION),
+        ("allocator", build_info::ALLOCATOR),
+    ]
+}
+
+/// Message of a panic payload (`panic!` passes a `&str` or a `String`)
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruchy_lambda_runtime::{drain_background_tasks, Context};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_caught_panic_is_not_a_crash() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let previous = panic::take_hook();
+        let seen = Arc::clone(&records);
+        panic::set_hook(Box::new(move |info| {
+            let panic_message = payload_message(info.payload()).to_string();
+            seen.lock().unwrap().push((panic_message, record_kind()));
+        }));
+
+        let context = Context {
+            request_id: "crash-test".to_string(),
+            ..Context::default()
+        };
+        context.spawn_background(|| panic!("background bug"));
+        let ran = drain_background_tasks();
+        let crashed = std::thread::spawn(|| panic!("worker bug")).join();
+
+        panic::set_hook(previous);
+        assert!(ran >= 1);
+        assert!(crashed.is_err());
+
+        let records = records.lock().unwrap();
+        let kind = |panic_message: &str| {
+            records
+                .iter()
+                .find(|(message, _)| message == panic_message)
+                .map(|(_, kind)| *kind)
+        };
+        assert_eq!(
+            kind("background bug"),
+            Some((LogLevel::Warn, CAUGHT_PANIC_MESSAGE))
+        );
+        assert_eq!(kind("worker bug"), Some((LogLevel::Error, CRASH_MESSAGE)));
+    }
+
+    #[test]
+    fn test_payload_message() {
+        let literal: Box<dyn Any + Send> = Box::new("index out of bounds");
+        let formatted: Box<dyn Any + Send> = Box::new(format!("bad event {}", 7));
+        let other: Box<dyn Any + Send> = Box::new(42_u32);
+
+        assert_eq!(payload_message(literal.as_ref()), "index out of bounds");
+        assert_eq!(payload_message(formatted.as_ref()), "bad event 7");
+        assert_eq!(payload_message(other.as_ref()), "Box<dyn Any>");
+    }
+
+    #[test]
+    fn test_crash_fields() {
+        let fields = crash_fields(
+            "called `Option::unwrap()` on a `None` value",
+            "src/main.rs:12:5",
+            "main",
+        );
+        let field = |name| {
+            fields
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        };
+
+        assert_eq!(
+            field("panic_message"),
+            Some("called `Option::unwrap()` on a `None` value")
+        );
+        assert_eq!(field("location"), Some("src/main.rs:12:5"));
+        assert_eq!(field("thread"), Some("main"));
+        assert_eq!(field("version"), Some(build_info::VERSION));
+        assert_eq!(field("git_sha"), Some(build_info::GIT_SHA));
+        assert_eq!(field("build_profile"), Some(build_info::BUILD_PROFILE));
+    }
+}
@@ -0,0 +1,56 @@
+// Pure Rust Matrix Multiply Handler for AWS Lambda Graviton2
+// Demonstrates CPU inference-style workloads (dense f32 layers)
+// Target: <8ms cold start, <500KB binary
+
+use crate::simd_ops;
+
+/// Lambda handler for blocked NEON matmul benchmark
+///
+/// Workload: 256×256 · 256×256 f32 multiply (~16.7M fused multiply-adds),
+/// the shape of a small dense layer in CPU inference.
+///
+/// # Arguments
+/// * `request_id` - Unique Lambda request ID (unused in this benchmark)
+/// * `body` - Request body (unused, always uses 256×256 matrices)
+///
+/// # Returns
+/// JSON response with the checksum (sum of all output elements) and size
+#[allow(clippy::all)]
+pub fn lambda_handler(_request_id: &str, _body: &str) -> String {
+    // Matrix size: 256×256 (256KB per matrix)
+    const SIZE: usize = 256;
+
+    // A = all ones, B = all 0.5 => every C element is 0.5 * SIZE = 128.0
+    let matrix_a: Vec<f32> = vec![1.0; SIZE * SIZE];
+    let matrix_b: Vec<f32> = vec![0.5; SIZE * SIZE];
+    let mut matrix_c: Vec<f32> = vec![0.0; SIZE * SIZE];
+
+    simd_ops::matmul(&matrix_a, &matrix_b, &mut matrix_c, SIZE, SIZE, SIZE);
+
+    // Expected checksum: 256 * 256 * 128.0 = 8,388,608.0 (exact in f32)
+    let checksum = simd_ops::sum(&matrix_c);
+
+    format!(
+        "{{\"statusCode\":200,\"body\":{{\"checksum\":{},\"matrixSize\":{},\"arch\":\"{}\"}}}}",
+        checksum,
+        SIZE,
+        if cfg!(target_arch = "aarch64") {
+            "arm64-neon"
+        } else {
+            "scalar"
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lambda_handler() {
+        let response = lambda_handler("test-request-id", "{}");
+        assert!(response.contains("\"statusCode\":200"));
+        assert!(response.contains("\"checksum\":8388608"));
+        assert!(response.contains("\"matrixSize\":256"));
+    }
+}
@@ -34,7 +34,7 @@ pub fn lambda_handler(_request_id: &str, _body: &str) -> String {
 
     // Compute dot product using SIMD-optimized function
     // On ARM64: Uses ARM NEON intrinsics (vfmaq_f32, vaddvq_f32)
-    // On x86_64: Uses scalar fallback
+    // On x86_64: Uses AVX2+FMA when available, SSE otherwise
     let result = simd_ops::dot_product(&vec_a, &vec_b);
 
     // Build JSON response
@@ -45,8 +45,10 @@ pub fn lambda_handler(_request_id: &str, _body: &str) -> String {
         SIZE,
         if cfg!(target_arch = "aarch64") {
             "arm64-neon"
+        } else if cfg!(target_arch = "x86_64") {
+            "x86_64-sse-avx2"
         } else {
-            "x86_64-scalar"
+            "scalar"
         }
     )
 }
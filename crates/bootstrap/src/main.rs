@@ -4,19 +4,37 @@
 // Performance Target: <1ms initialization, <8ms cold start
 // Quality Standard: TDG ≥A+, Cyclomatic ≤15, Cognitive ≤20
 
-// Note: unsafe is required for ARM NEON SIMD intrinsics (std::arch::aarch64)
-// SIMD operations are carefully isolated in simd_ops module with safety guarantees
+// Note: SIMD intrinsics (and their unsafe code) live in the ruchy-lambda-simd crate
+#![forbid(unsafe_code)]
 #![warn(clippy::all, clippy::pedantic, clippy::cargo)]
 
-use ruchy_lambda_runtime::Runtime;
+use ruchy_lambda_runtime::{
+    drain_background_tasks, mark_init_phase, start_init_trace, Backoff, BackoffAction,
+    BackoffConfig, Context, InitPhase, InvocationLog, LogLevel, Logger, Runtime,
+    CIRCUIT_OPEN_EXIT_CODE,
+};
 use std::error::Error;
+use std::sync::OnceLock;
 
-// ARM NEON SIMD operations module (hand-optimized for Graviton2)
-mod simd_ops;
+// Global allocator selection (cargo feature, default: system allocator)
+// Compare cold start / binary size per allocator: scripts/benchmark-allocators.sh
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+// Build metadata embedded by build.rs (git SHA, profile, transpiler version)
+mod build_info;
+
+// Panic hook writing a structured JSON crash record before abort
+mod crash_report;
+
+// ARM NEON SIMD operations (ruchy-lambda-simd), re-exported for the SIMD handlers
+#[allow(unused_imports)]
+pub use ruchy_lambda_simd as simd_ops;
 
 // Include transpiled Ruchy handler
 // This file is auto-generated by build.rs from src/handler.ruchy
-// Build script will replace this path based on HANDLER type (minimal, fibonacci, simd_vector, default)
+// Build script will replace this path based on HANDLER type (minimal, fibonacci, simd_vector, matmul, default)
 // Allow all clippy warnings in generated code
 #[allow(clippy::all)]
 #[path = "handler_fibonacci_generated.rs"]
@@ -51,19 +69,113 @@ fn main() -> Result<(), Box<dyn Error>> {
     // Phase 3: Converted to blocking I/O (removed async/await)
 
     // INITIALIZATION PHASE
+    // Phase timings are logged with the first invocation (RUCHY_LAMBDA_INIT_TRACE=1)
+    start_init_trace();
+    crash_report::install();
     println!("[BOOTSTRAP] Initializing Ruchy Lambda Runtime...");
+    println!("[BOOTSTRAP] Build: {}", build_info::summary());
     let runtime = Runtime::new()?;
     println!("[BOOTSTRAP] Runtime initialized successfully");
 
+    // Pre-connect to the Runtime API per RUCHY_LAMBDA_CLIENT_INIT (default: lazy)
+    runtime.prepare()?;
+
+    // Backoff between failed iterations; exits after too many in a row
+    let backoff = BackoffConfig::from_env()?;
+    mark_init_phase(InitPhase::HandlerRegistration);
+
     // PROCESSING LOOP
     // In production, this loops forever processing Lambda invocations
     println!("[BOOTSTRAP] Entering event processing loop...");
 
+    // Local emulation only (RUCHY_LAMBDA_WORKERS=K): K concurrent workers,
+    // each with its own Runtime API connection
+    let workers = runtime.workers();
+    if workers > 1 {
+        println!("[BOOTSTRAP] Starting {workers} workers (local emulation)");
+        let handles = runtime.spawn_workers(move |id, worker_runtime| {
+            run_event_loop(&format!("Worker {id}: "), backoff, || {
+                process_single_event(worker_runtime)
+            })
+        });
+        for handle in handles {
+            let _ = handle.join();
+        }
+        return Ok(());
+    }
+
+    // Opt-in (RUCHY_LAMBDA_PREFETCH=1): fetch event N+1 while handling event N
+    if runtime.prefetch_enabled() {
+        println!("[BOOTSTRAP] Prefetching events on a background thread");
+        let prefetcher = runtime.spawn_prefetcher();
+        run_event_loop("", backoff, || {
+            let (context, event_body) = prefetcher.next_invocation()?;
+            process_event(&runtime, &context, &event_body)
+        });
+    }
+
     // Phase 5: Event loop activated for real AWS Lambda deployment
+    run_event_loop("", backoff, || process_single_event(&runtime))
+}
+
+/// Run `step` forever, backing off on consecutive failures
+///
+/// Failures are logged as structured JSON with the consecutive failure count
+/// and the next delay. Once `BackoffConfig::fatal_threshold` failures happen
+/// in a row the process exits with `CIRCUIT_OPEN_EXIT_CODE`.
+fn run_event_loop(
+    label: &str,
+    config: BackoffConfig,
+    mut step: impl FnMut() -> Result<(), Box<dyn Error>>,
+) -> ! {
+    let logger = Logger::new();
+    let mut backoff = Backoff::new(config);
+
     loop {
-        if let Err(e) = process_single_event(&runtime) {
-            eprintln!("[ERROR] Event processing failed: {e}");
-            // Continue processing next event (don't exit on errors)
+        let Err(e) = step() else {
+            backoff.record_success();
+            continue;
+        };
+
+        // Continue processing next event (don't exit on isolated errors)
+        let message = format!("{label}Event processing failed: {e}");
+        match backoff.record_failure() {
+            BackoffAction::Retry(delay) => {
+                logger.log_with_fields(
+                    LogLevel::Warn,
+                    &message,
+                    &[
+                        (
+                            "consecutive_failures",
+                            u64::from(backoff.consecutive_failures()),
+                        ),
+                        (
+                            "backoff_ms",
+                            u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                        ),
+                    ],
+                );
+                std::thread::sleep(delay);
+            }
+            BackoffAction::Fatal => {
+                logger.log_with_fields(
+                    LogLevel::Error,
+                    &format!("{message} (circuit open, exiting)"),
+                    &[
+                        (
+                            "consecutive_failures",
+                            u64::from(backoff.consecutive_failures()),
+                        ),
+                        (
+                            "exit_code",
+                            u64::from(CIRCUIT_OPEN_EXIT_CODE.unsigned_abs()),
+                        ),
+                    ],
+                );
+                // Work queued by earlier invocations still gets its chance
+                let _ = drain_background_tasks();
+                std::process::exit(CIRCUIT_OPEN_EXIT_CODE);
+            }
         }
     }
 }
@@ -75,19 +187,65 @@ fn main() -> Result<(), Box<dyn Error>> {
 ///
 /// This function demonstrates the event processing flow:
 /// 1. Fetch next event from Runtime API (gets `request_id` from headers)
-/// 2. Invoke handler with raw event body
+/// 2. Invoke handler with raw event body (or answer a version request)
 /// 3. Post response back to Runtime API
 fn process_single_event(runtime: &Runtime) -> Result<(), Box<dyn Error>> {
     // 1. Get next event (long-polling, blocks until event available)
     // request_id comes from Lambda-Runtime-Aws-Request-Id header
     // event_body is the raw user payload (e.g., "{}" or "{\"test\":\"data\"}")
-    let (request_id, event_body) = runtime.next_event()?;
+    let (context, event_body) = runtime.next_invocation()?;
+
+    process_event(runtime, &context, &event_body)
+}
+
+/// Logger for lifecycle events (`RUCHY_LAMBDA_LIFECYCLE_LOG=1`)
+static LIFECYCLE_LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Invoke the handler for one fetched event and post its response
+///
+/// With a timeout guard (`RUCHY_LAMBDA_TIMEOUT_GUARD_MS`) a handler still
+/// running shortly before the deadline is abandoned and a `Function.Timeout`
+/// error is posted instead.
+fn process_event(
+    runtime: &Runtime,
+    context: &Context,
+    event_body: &str,
+) -> Result<(), Box<dyn Error>> {
+    let request_id = context.request_id.as_str();
+    let lifecycle = runtime.lifecycle_log_enabled().then(|| {
+        let logger = LIFECYCLE_LOGGER.get_or_init(Logger::new);
+        InvocationLog::start(logger, request_id, event_body.len())
+    });
 
     // 2. Invoke Ruchy handler (transpiled from handler.ruchy)
-    let response = ruchy_handler(&request_id, &event_body);
+    // {"__ruchy":"version"} reports build metadata instead
+    let response = if build_info::is_version_request(event_body) {
+        build_info::version_json()
+    } else if let Some(guard) = runtime.timeout_guard() {
+        let (id, body) = (request_id.to_string(), event_body.to_string());
+        match guard.run(context, move || ruchy_handler(&id, &body)) {
+            Ok(response) => response,
+            Err(timeout) => {
+                let posted = runtime.post_error(request_id, &timeout);
+                if let Some(lifecycle) = lifecycle {
+                    lifecycle.error(&timeout.error_type, &timeout.error_message);
+                }
+                posted?;
+                return Ok(());
+            }
+        }
+    } else {
+        ruchy_handler(request_id, event_body)
+    };
 
     // 3. Post response
-    runtime.post_response(&request_id, &response)?;
+    let posted = runtime.post_response(request_id, &response);
+    match (lifecycle, &posted) {
+        (Some(lifecycle), Ok(())) => lifecycle.end(response.len()),
+        (Some(lifecycle), Err(e)) => lifecycle.error("Runtime.PostFailed", &e.to_string()),
+        (None, _) => {}
+    }
+    posted?;
 
     Ok(())
 }
@@ -1,209 +0,0 @@
-// ARM NEON SIMD Operations for AWS Lambda Graviton2
-// Zero external dependencies - uses std::arch::aarch64 intrinsics
-// Target: 5x faster than scalar on ARM64, <500KB binary
-
-#![allow(
-    clippy::missing_safety_doc,
-    dead_code,
-    clippy::doc_markdown,
-    clippy::cast_precision_loss
-)]
-
-/// SIMD-optimized dot product for f32 vectors
-///
-/// # ARM64 Optimization Strategy
-/// - Use ARM NEON f32x4 vectors (4-way parallelism)
-/// - Leverage vfmaq_f32 (fused multiply-add) for efficiency
-/// - Process 4 elements per iteration (vectorized)
-/// - Handle remainder with scalar code (loop tail)
-///
-/// # Performance
-/// - Expected speedup: 5x vs scalar on Graviton2
-/// - Binary size impact: ~2KB (intrinsics are inlined)
-/// - Memory bandwidth: 16 bytes/iteration (aligned loads)
-///
-/// # Arguments
-/// * `a` - First vector (f32 slice, any length)
-/// * `b` - Second vector (f32 slice, must match `a` length)
-///
-/// # Returns
-/// Dot product (sum of element-wise products)
-///
-/// # Panics
-/// Panics if vector lengths don't match
-#[inline]
-pub fn dot_product(a: &[f32], b: &[f32]) -> f32 {
-    assert_eq!(
-        a.len(),
-        b.len(),
-        "Vector lengths must match for dot product"
-    );
-
-    #[cfg(target_arch = "aarch64")]
-    {
-        dot_product_neon(a, b)
-    }
-
-    #[cfg(not(target_arch = "aarch64"))]
-    {
-        dot_product_scalar(a, b)
-    }
-}
-
-/// ARM NEON-optimized dot product implementation
-///
-/// Uses ARM NEON intrinsics for 4x parallelism:
-/// - vld1q_f32: Load 4 f32 values into vector register
-/// - vfmaq_f32: Fused multiply-add (accumulate = accumulate + a * b)
-/// - vaddvq_f32: Horizontal sum of vector (sum all lanes)
-///
-/// # Safety
-/// Uses unsafe intrinsics but maintains safety through:
-/// - Bounds checking (chunk_exact guarantees valid slices)
-/// - Alignment-agnostic loads (vld1q_f32 handles unaligned data)
-/// - No raw pointer arithmetic beyond standard slice indexing
-#[cfg(target_arch = "aarch64")]
-#[inline]
-fn dot_product_neon(a: &[f32], b: &[f32]) -> f32 {
-    use std::arch::aarch64::*;
-
-    let len = a.len();
-    let mut sum = 0.0f32;
-
-    unsafe {
-        // Initialize accumulator to zero
-        let mut acc = vdupq_n_f32(0.0);
-
-        // Process 4 elements at a time (SIMD vectorized loop)
-        let chunks = len / 4;
-        for i in 0..chunks {
-            let offset = i * 4;
-
-            // Load 4 f32 values from each vector
-            let va = vld1q_f32(a.as_ptr().add(offset));
-            let vb = vld1q_f32(b.as_ptr().add(offset));
-
-            // Fused multiply-add: acc = acc + (va * vb)
-            // This is the key operation - does 4 multiply-adds in one instruction
-            acc = vfmaq_f32(acc, va, vb);
-        }
-
-        // Horizontal sum: add all 4 lanes of accumulator
-        sum = vaddvq_f32(acc);
-
-        // Handle remainder (scalar tail loop)
-        let remainder_start = chunks * 4;
-        for i in remainder_start..len {
-            sum += a[i] * b[i];
-        }
-    }
-
-    sum
-}
-
-/// Scalar fallback for non-ARM64 architectures
-///
-/// Used for:
-/// - x86_64 builds (local development)
-/// - Testing on non-ARM platforms
-/// - Fallback if NEON not available
-///
-/// Performance: ~5x slower than NEON on ARM64
-#[cfg(not(target_arch = "aarch64"))]
-#[inline]
-fn dot_product_scalar(a: &[f32], b: &[f32]) -> f32 {
-    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
-}
-
-/// Benchmark function for testing SIMD performance
-///
-/// Generates two vectors of given size and computes dot product.
-/// Useful for measuring cold start + execution time.
-///
-/// # Arguments
-/// * `size` - Number of elements in each vector
-///
-/// # Returns
-/// Tuple of (result, execution_time_ms)
-#[inline]
-pub fn benchmark_dot_product(size: usize) -> (f32, f64) {
-    use std::time::Instant;
-
-    // Generate test vectors
-    let vec_a: Vec<f32> = (0..size).map(|i| (i as f32) + 1.0).collect();
-    let vec_b: Vec<f32> = vec![0.5; size];
-
-    // Measure execution time
-    let start = Instant::now();
-    let result = dot_product(&vec_a, &vec_b);
-    let elapsed = start.elapsed();
-
-    (result, elapsed.as_secs_f64() * 1000.0)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_dot_product_small() {
-        let a = vec![1.0, 2.0, 3.0, 4.0];
-        let b = vec![0.5, 0.5, 0.5, 0.5];
-        let result = dot_product(&a, &b);
-        assert!((result - 5.0).abs() < 1e-6, "Expected 5.0, got {}", result);
-    }
-
-    #[test]
-    fn test_dot_product_large() {
-        let size = 10_000;
-        let a: Vec<f32> = (0..size).map(|i| (i as f32) + 1.0).collect();
-        let b = vec![0.5; size];
-
-        let result = dot_product(&a, &b);
-
-        // Expected: sum(i * 0.5 for i in 1..=10000)
-        // = 0.5 * sum(1..=10000)
-        // = 0.5 * (10000 * 10001 / 2)
-        // = 0.5 * 50,005,000
-        // = 25,002,500
-        let expected = 25_002_500.0;
-        assert!(
-            (result - expected).abs() < 1.0,
-            "Expected {}, got {}",
-            expected,
-            result
-        );
-    }
-
-    #[test]
-    fn test_dot_product_non_aligned() {
-        // Test with size not divisible by 4 (tests remainder handling)
-        let a = vec![1.0, 2.0, 3.0, 4.0, 5.0];
-        let b = vec![1.0, 1.0, 1.0, 1.0, 1.0];
-        let result = dot_product(&a, &b);
-        assert!(
-            (result - 15.0).abs() < 1e-6,
-            "Expected 15.0, got {}",
-            result
-        );
-    }
-
-    #[test]
-    #[should_panic(expected = "Vector lengths must match")]
-    fn test_dot_product_length_mismatch() {
-        let a = vec![1.0, 2.0, 3.0];
-        let b = vec![1.0, 2.0];
-        dot_product(&a, &b);
-    }
-
-    #[test]
-    fn test_benchmark() {
-        let (result, time_ms) = benchmark_dot_product(10_000);
-        assert!(result > 0.0, "Result should be positive");
-        assert!(time_ms > 0.0, "Execution time should be measurable");
-        println!(
-            "Benchmark: 10K elements, result={}, time={}ms",
-            result, time_ms
-        );
-    }
-}
@@ -0,0 +1,92 @@
+// Tests for handlers.toml (build.rs transpilation manifest)
+//
+// build.rs panics on a malformed manifest, so these tests focus on the
+// contract between the manifest and the checked-in generated handlers.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn load_handlers() -> Vec<toml::Table> {
+    let text = std::fs::read_to_string(manifest_dir().join("handlers.toml"))
+        .expect("handlers.toml should exist");
+    let manifest: toml::Table = toml::from_str(&text).expect("handlers.toml should parse");
+
+    manifest["handler"]
+        .as_array()
+        .expect("handlers.toml should contain [[handler]] entries")
+        .iter()
+        .map(|entry| entry.as_table().expect("handler entry is a table").clone())
+        .collect()
+}
+
+fn field<'a>(handler: &'a toml::Table, key: &str) -> &'a str {
+    handler
+        .get(key)
+        .and_then(toml::Value::as_str)
+        .unwrap_or_else(|| panic!("handler missing `{key}`: {handler:?}"))
+}
+
+/// Mirrors `HandlerSpec::output_path` in build.rs
+fn output_path(handler: &toml::Table) -> PathBuf {
+    if let Some(output) = handler.get("output").and_then(toml::Value::as_str) {
+        return manifest_dir().join(output);
+    }
+    let source = manifest_dir().join(field(handler, "source"));
+    let stem = source.file_stem().unwrap().to_str().unwrap().to_string();
+    source.with_file_name(format!("{stem}_generated.rs"))
+}
+
+#[test]
+fn test_manifest_entries_have_required_fields() {
+    let handlers = load_handlers();
+    assert!(!handlers.is_empty());
+
+    let mut names = HashSet::new();
+    for handler in &handlers {
+        assert!(field(handler, "source").ends_with(".ruchy"));
+        assert!(!field(handler, "entry").is_empty());
+        assert!(
+            names.insert(field(handler, "name").to_string()),
+            "duplicate handler name: {}",
+            field(handler, "name")
+        );
+    }
+}
+
+#[test]
+fn test_manifest_lists_bootstrap_handlers() {
+    let names: Vec<String> = load_handlers()
+        .iter()
+        .map(|handler| field(handler, "name").to_string())
+        .collect();
+
+    for expected in ["default", "minimal", "fibonacci"] {
+        assert!(
+            names.iter().any(|name| name == expected),
+            "handlers.toml should list `{expected}`"
+        );
+    }
+}
+
+#[test]
+fn test_generated_handlers_define_entry() {
+    for handler in load_handlers() {
+        if !manifest_dir().join(field(&handler, "source")).exists() {
+            continue;
+        }
+
+        let output = output_path(&handler);
+        let generated = std::fs::read_to_string(&output)
+            .unwrap_or_else(|e| panic!("{} should exist: {e}", output.display()));
+        let entry = field(&handler, "entry");
+        assert!(
+            generated.contains(&format!("fn {entry}(")),
+            "{} should define `{entry}`",
+            output.display()
+        );
+    }
+}
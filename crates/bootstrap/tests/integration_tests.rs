@@ -63,6 +63,155 @@ fn test_event_loop_handles_errors() {
     // 3. If post_response() fails, log error and continue
 }
 
+/// Test: Repeated Runtime API failures back off, then open the circuit
+#[test]
+fn test_event_loop_circuit_breaker_exit_code() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_bootstrap"))
+        .env("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:19990")
+        .env("RUCHY_LAMBDA_FATAL_FAILURES", "3")
+        .env("RUCHY_LAMBDA_BACKOFF_INITIAL_MS", "1")
+        .env_remove("RUCHY_LAMBDA_WORKERS")
+        .env_remove("RUCHY_LAMBDA_PREFETCH")
+        .output()
+        .expect("bootstrap should run");
+
+    assert_eq!(
+        output.status.code(),
+        Some(ruchy_lambda_runtime::CIRCUIT_OPEN_EXIT_CODE)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#""consecutive_failures":1,"backoff_ms":1"#));
+    assert!(stdout.contains(r#""consecutive_failures":2,"backoff_ms":2"#));
+    assert!(stdout.contains(r#""consecutive_failures":3,"exit_code":69"#));
+}
+
+/// Test: RUCHY_LAMBDA_LIFECYCLE_LOG=1 logs start/end events with byte counts
+#[test]
+fn test_event_loop_lifecycle_events() {
+    use ruchy_lambda_emulator::{Emulator, EmulatorConfig, Outcome};
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    let emulator = Emulator::start(
+        EmulatorConfig::default()
+            .with_addr("127.0.0.1:0".parse().unwrap())
+            .with_timeout(Duration::from_secs(60)),
+    )
+    .unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bootstrap"))
+        .envs(emulator.runtime_env())
+        .env("RUCHY_LAMBDA_LIFECYCLE_LOG", "1")
+        .env_remove("RUCHY_LAMBDA_WORKERS")
+        .env_remove("RUCHY_LAMBDA_PREFETCH")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("bootstrap should run");
+
+    let event = r#"{"__ruchy":"version"}"#;
+    let invocation = emulator.invoke(event.as_bytes()).unwrap();
+    let Outcome::Success(response) = &invocation.outcome else {
+        panic!("invocation failed: {:?}", invocation.outcome);
+    };
+
+    // The response reaches the emulator before `invocation.end` is logged
+    let mut lines = Vec::new();
+    for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+        let Ok(line) = serde_json::from_str::<serde_json::Value>(&line.unwrap()) else {
+            continue;
+        };
+        let done = line["message"] == "invocation.end";
+        lines.push(line);
+        if done {
+            break;
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let start = lines
+        .iter()
+        .find(|line| line["message"] == "invocation.start")
+        .expect("invocation.start logged");
+    assert_eq!(start["request_id"], invocation.request_id.as_str());
+    assert_eq!(start["event_bytes"], event.len());
+
+    let end = lines
+        .iter()
+        .find(|line| line["message"] == "invocation.end")
+        .expect("invocation.end logged");
+    assert_eq!(end["request_id"], invocation.request_id.as_str());
+    assert_eq!(end["response_bytes"], response.len());
+    assert!(end["duration_ms"].is_number(), "{end}");
+}
+
+/// Test: RUCHY_LAMBDA_INIT_TRACE=1 logs the init phase breakdown once
+#[test]
+fn test_event_loop_init_trace() {
+    use ruchy_lambda_emulator::{Emulator, EmulatorConfig, Outcome};
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    let emulator = Emulator::start(
+        EmulatorConfig::default()
+            .with_addr("127.0.0.1:0".parse().unwrap())
+            .with_timeout(Duration::from_secs(60)),
+    )
+    .unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bootstrap"))
+        .envs(emulator.runtime_env())
+        .env("RUCHY_LAMBDA_INIT_TRACE", "1")
+        .env("RUCHY_LAMBDA_LIFECYCLE_LOG", "1")
+        .env_remove("RUCHY_LAMBDA_WORKERS")
+        .env_remove("RUCHY_LAMBDA_PREFETCH")
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("bootstrap should run");
+
+    let event = r#"{"__ruchy":"version"}"#;
+    let mut request_ids = Vec::new();
+    for _ in 0..2 {
+        let invocation = emulator.invoke(event.as_bytes()).unwrap();
+        assert!(matches!(invocation.outcome, Outcome::Success(_)));
+        request_ids.push(invocation.request_id);
+    }
+
+    // The second invocation's `invocation.end` comes after any init trace
+    let mut lines = Vec::new();
+    let mut ended = 0;
+    for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+        let Ok(line) = serde_json::from_str::<serde_json::Value>(&line.unwrap()) else {
+            continue;
+        };
+        ended += usize::from(line["message"] == "invocation.end");
+        lines.push(line);
+        if ended == 2 {
+            break;
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let traces: Vec<_> = lines
+        .iter()
+        .filter(|line| line["message"] == "init.trace")
+        .collect();
+    assert_eq!(traces.len(), 1, "{lines:?}");
+    let trace = traces[0];
+    assert_eq!(trace["request_id"], request_ids[0].as_str());
+    for field in [
+        "env_read_us",
+        "client_init_us",
+        "handler_registration_us",
+        "first_next_us",
+        "total_us",
+    ] {
+        assert!(trace[field].is_u64(), "{field} missing: {trace}");
+    }
+}
+
 /// Test: Handler function interface
 #[test]
 fn test_handler_function_signature() {
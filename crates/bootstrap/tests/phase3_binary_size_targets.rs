@@ -166,21 +166,34 @@ fn test_no_debug_symbols_in_release() {
 
 /// Test: Profile-guided optimization (PGO) applied
 ///
-/// Verifies that PGO workflow works correctly
+/// Verifies that PGO workflow works correctly: `cargo packager --pgo` builds
+/// an instrumented bootstrap, trains it against the emulator, merges the
+/// profile and rebuilds (needs llvm-profdata matching rustc's LLVM)
 #[test]
-#[ignore] // Will enable when implementing PGO
+#[ignore] // Slow: three release builds plus training
 fn test_pgo_workflow() {
-    // 1. Build with instrumentation
-    let instrument = Command::new("cargo")
-        .args(["build", "--release"])
-        .env("RUSTFLAGS", "-Cprofile-generate=/tmp/pgo-data")
-        .output();
-
-    assert!(instrument.is_ok(), "PGO instrumentation build should work");
+    let output = Command::new("cargo")
+        .args(["run", "-p", "ruchy-lambda-packager", "--", "--libc", "gnu"])
+        .args([
+            "--arch",
+            std::env::consts::ARCH,
+            "--pgo",
+            "--train-iterations",
+            "5",
+        ])
+        .args(["--size-budget-kb", "4096"])
+        .args(["--output", "target/lambda-packages/pgo-function.zip"])
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../.."))
+        .output()
+        .expect("cargo should run");
 
-    // 2. Run to collect profile data
-    // 3. Rebuild with profile data
-    // (Implementation pending)
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "PGO packaging failed:\n{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("PGO: size"), "{stdout}");
 }
 
 /// Test: Dependency audit - no unnecessary dependencies
@@ -0,0 +1,200 @@
+// End-to-end tests against the AWS Lambda Runtime Interface Emulator (RIE)
+//
+// The mock-server tests exercise our side of the Runtime API; these run the
+// real bootstrap binary inside public.ecr.aws/lambda/provided:al2023 (which
+// ships the RIE) and invoke it over HTTP, like `sam local invoke` does.
+//
+// Opt-in: needs Docker and pulls the base image on first run.
+//
+//   make test-rie
+//   # or, with a bootstrap built for the image's libc:
+//   RUCHY_RIE_BOOTSTRAP=$PWD/target/x86_64-unknown-linux-musl/release/bootstrap \
+//     cargo test -p ruchy-lambda-bootstrap --test rie_e2e_tests -- --ignored --test-threads=1
+//
+// RUCHY_RIE_BOOTSTRAP defaults to the bootstrap cargo built for this test,
+// which only runs in the image if the host glibc is not newer than AL2023's.
+
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Base image with the RIE as its entrypoint
+const IMAGE: &str = "public.ecr.aws/lambda/provided:al2023";
+
+/// RIE invoke path (function name is always `function`)
+const INVOKE_PATH: &str = "/2015-03-31/functions/function/invocations";
+
+/// How long to wait for the container to start answering
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Running RIE container, removed on drop
+struct Rie {
+    container: String,
+    port: u16,
+}
+
+impl Rie {
+    /// Start the container, or `None` (after saying why) when Docker is missing
+    fn start() -> Option<Self> {
+        let docker = Command::new("docker").arg("version").output();
+        if !docker.is_ok_and(|output| output.status.success()) {
+            eprintln!("Skipping RIE test: docker is not available");
+            return None;
+        }
+
+        let bootstrap = std::env::var_os("RUCHY_RIE_BOOTSTRAP").map_or_else(
+            || PathBuf::from(env!("CARGO_BIN_EXE_bootstrap")),
+            PathBuf::from,
+        );
+        let bootstrap = bootstrap
+            .canonicalize()
+            .unwrap_or_else(|e| panic!("bootstrap binary {}: {e}", bootstrap.display()));
+
+        let port = free_port();
+        let output = Command::new("docker")
+            .args(["run", "--detach", "--rm"])
+            .args(["--publish", &format!("127.0.0.1:{port}:8080")])
+            .args([
+                "--volume",
+                &format!("{}:/var/runtime/bootstrap:ro", bootstrap.display()),
+            ])
+            .args([IMAGE, "handler"])
+            .output()
+            .expect("run docker");
+        assert!(
+            output.status.success(),
+            "docker run failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        Some(Self {
+            container: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            port,
+        })
+    }
+
+    /// Invoke the function and return the raw response body
+    fn invoke(&self, event: &str) -> String {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            match post(self.port, INVOKE_PATH, event) {
+                Ok(body) => return body,
+                Err(e) if Instant::now() < deadline => {
+                    eprintln!("RIE not ready ({e}), retrying");
+                    thread::sleep(Duration::from_millis(250));
+                }
+                Err(e) => panic!("invoke failed: {e}\n{}", self.logs()),
+            }
+        }
+    }
+
+    /// Container output (RIE and bootstrap logs)
+    fn logs(&self) -> String {
+        let output = Command::new("docker")
+            .args(["logs", &self.container])
+            .output()
+            .expect("docker logs");
+        format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+    }
+}
+
+impl Drop for Rie {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "--force", &self.container])
+            .output();
+    }
+}
+
+/// A currently unused local port
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .expect("bind ephemeral port")
+}
+
+/// POST `body` and return the response body of a 200 response
+fn post(port: u16, path: &str, body: &str) -> Result<String, String> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(60)))
+        .map_err(|e| e.to_string())?;
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .map_err(|e| e.to_string())?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| format!("incomplete response: {response:?}"))?;
+    if !head.starts_with("HTTP/1.1 200") {
+        return Err(head.lines().next().unwrap_or_default().to_string());
+    }
+    Ok(body.to_string())
+}
+
+/// Test: An invocation through the RIE returns the handler's response
+#[test]
+#[ignore] // Run with: make test-rie
+fn test_rie_invoke_returns_handler_response() {
+    let Some(rie) = Rie::start() else { return };
+
+    let response: Value = serde_json::from_str(&rie.invoke("{}")).expect("JSON response");
+    assert_eq!(response["statusCode"], 200, "{response}");
+    assert_eq!(response["body"], "fibonacci(35)=9227465", "{response}");
+
+    let logs = rie.logs();
+    assert!(logs.contains("[BOOTSTRAP] Runtime initialized"), "{logs}");
+}
+
+/// Test: A warm container serves consecutive invocations
+#[test]
+#[ignore] // Run with: make test-rie
+fn test_rie_warm_invocations() {
+    let Some(rie) = Rie::start() else { return };
+
+    for i in 0..3 {
+        let response = rie.invoke(&format!(r#"{{"invocation":{i}}}"#));
+        assert!(
+            response.contains("fibonacci(35)"),
+            "invocation {i}: {response}"
+        );
+    }
+
+    // One init, then one REPORT line per invocation
+    let logs = rie.logs();
+    assert_eq!(
+        logs.matches("[BOOTSTRAP] Initializing").count(),
+        1,
+        "{logs}"
+    );
+    assert_eq!(logs.matches("REPORT RequestId").count(), 3, "{logs}");
+}
+
+/// Test: The build metadata request works end to end
+#[test]
+#[ignore] // Run with: make test-rie
+fn test_rie_version_request() {
+    let Some(rie) = Rie::start() else { return };
+
+    let response: Value =
+        serde_json::from_str(&rie.invoke(r#"{"__ruchy":"version"}"#)).expect("JSON response");
+    assert_eq!(response["version"], env!("CARGO_PKG_VERSION"), "{response}");
+}
@@ -0,0 +1,246 @@
+// Fake Runtime API
+//
+// Listens on an ephemeral TCP port (runtime-pure dials TCP directly) and
+// answers one scripted `Reply` per connection, in order, recording every
+// request. Both runtimes send `Connection: close`, so one connection is one
+// request. The server gives up after `ACCEPT_TIMEOUT`, so a runtime that
+// makes fewer requests than scripted fails the test instead of hanging it.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How long the server waits for each scripted connection
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Scripted response of a [`FakeRuntimeApi`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Reply {
+    /// Empty response with `status`
+    #[must_use]
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// `202 Accepted`, the answer to a valid post
+    #[must_use]
+    pub fn accepted() -> Self {
+        Self::status(crate::spec::ACCEPTED)
+    }
+
+    /// `200 OK` `/next` response carrying an event
+    #[must_use]
+    pub fn event(request_id: &str, body: &str) -> Self {
+        Self {
+            body: body.to_string(),
+            ..Self::status(200)
+        }
+        .with_header(crate::spec::REQUEST_ID_HEADER, request_id)
+    }
+
+    /// Add a response header
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        use std::fmt::Write as _;
+
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            let _ = write!(head, "{name}: {value}\r\n");
+        }
+        let _ = write!(
+            head,
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        );
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(self.body.as_bytes());
+        bytes
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        _ => "Status",
+    }
+}
+
+/// Request received by a [`FakeRuntimeApi`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// Request method (`GET`, `POST`)
+    pub method: String,
+    /// Request target, e.g. `/2018-06-01/runtime/invocation/next`
+    pub path: String,
+    /// Protocol version from the request line, e.g. `HTTP/1.1`
+    pub version: String,
+    /// Headers in the order they were sent
+    pub headers: Vec<(String, String)>,
+    /// Body (`Content-Length` bytes)
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// Case-insensitive header lookup
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Runtime API answering scripted replies on a local TCP port
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_conformance::{FakeRuntimeApi, Reply};
+/// use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
+///
+/// let api = FakeRuntimeApi::serve(vec![Reply::event("req-1", "{}")]).unwrap();
+/// let runtime = Runtime::from_config(RuntimeConfig::new(api.endpoint()));
+/// assert_eq!(runtime.next_event().unwrap().0, "req-1");
+///
+/// let requests = api.requests().unwrap();
+/// assert_eq!(requests[0].path, "/2018-06-01/runtime/invocation/next");
+/// ```
+#[derive(Debug)]
+pub struct FakeRuntimeApi {
+    endpoint: String,
+    server: JoinHandle<io::Result<Vec<RecordedRequest>>>,
+}
+
+impl FakeRuntimeApi {
+    /// Start serving `replies`, one per connection
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if no local port can be bound
+    pub fn serve(replies: Vec<Reply>) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let endpoint = listener.local_addr()?.to_string();
+        listener.set_nonblocking(true)?;
+        let server = thread::spawn(move || {
+            replies
+                .iter()
+                .map(|reply| {
+                    let mut stream = accept(&listener)?;
+                    let request = read_request(&mut BufReader::new(&stream))?;
+                    stream.write_all(&reply.to_bytes())?;
+                    Ok(request)
+                })
+                .collect()
+        });
+        Ok(Self { endpoint, server })
+    }
+
+    /// `host:port` to point a runtime at
+    #[must_use]
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Wait until every reply is served and return the requests received
+    ///
+    /// # Errors
+    ///
+    /// Returns `TimedOut` if a scripted connection never came, or the I/O
+    /// error a connection failed with
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server thread panicked
+    pub fn requests(self) -> io::Result<Vec<RecordedRequest>> {
+        self.server.join().expect("fake Runtime API panicked")
+    }
+}
+
+/// Accept the next connection, waiting at most [`ACCEPT_TIMEOUT`]
+fn accept(listener: &TcpListener) -> io::Result<TcpStream> {
+    let deadline = Instant::now() + ACCEPT_TIMEOUT;
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(ACCEPT_TIMEOUT))?;
+                return Ok(stream);
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err(io::ErrorKind::TimedOut.into());
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Read a request line, headers and `Content-Length` body
+fn read_request(reader: &mut impl BufRead) -> io::Result<RecordedRequest> {
+    let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("malformed request line"));
+    };
+    let (method, path, version) = (method.to_string(), path.to_string(), version.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        headers.push((name.to_string(), value.trim().to_string()));
+    }
+
+    let mut request = RecordedRequest {
+        method,
+        path,
+        version,
+        headers,
+        body: String::new(),
+    };
+    let length = match request.header("content-length") {
+        Some(value) => value.parse().map_err(|_| invalid("bad Content-Length"))?,
+        None => 0,
+    };
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+    request.body = String::from_utf8(body).map_err(|_| invalid("body is not UTF-8"))?;
+    Ok(request)
+}
@@ -0,0 +1,202 @@
+// Runtimes Under Test
+//
+// `RuntimeUnderTest` is the bootstrap's view of a runtime: fetch the next
+// invocation, then post its response or error (or an init error). Failures
+// are flattened to `None`/`false`, which is all runtime-pure reports.
+//
+// runtime-pure's `Runtime` always dials 127.0.0.1:9001, so it cannot be
+// pointed at a per-scenario fake API. `PureHttpClient` therefore is NOT that
+// `Runtime`: it compiles runtime-pure's Rust HTTP layer (http_client.rs) from
+// source and makes the calls lib.ruchy makes, against any endpoint. All of
+// runtime-pure's wire traffic happens in that layer, and the conformance
+// tests check lib.ruchy still requests the spec paths through it; the
+// transpiled `Runtime` itself is covered by runtime-pure's own tests.
+
+use crate::fake_api::{FakeRuntimeApi, RecordedRequest, Reply};
+use ruchy_lambda_runtime::{HandlerError, Runtime, RuntimeConfig};
+
+#[allow(dead_code, clippy::all, clippy::pedantic)]
+#[path = "../../runtime-pure/src/http_client.rs"]
+mod pure_http_client;
+
+/// Invocation returned by `/next`, as a handler sees it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NextInvocation {
+    /// `Lambda-Runtime-Aws-Request-Id`
+    pub request_id: String,
+    /// `Lambda-Runtime-Deadline-Ms` (0 when missing)
+    pub deadline_ms: u64,
+    /// `Lambda-Runtime-Invoked-Function-Arn` (empty when missing)
+    pub invoked_function_arn: String,
+    /// `Lambda-Runtime-Trace-Id`, if sent
+    pub trace_id: Option<String>,
+    /// Event body
+    pub body: String,
+}
+
+/// The Runtime API calls a bootstrap makes
+pub trait RuntimeUnderTest {
+    /// Implementation name for assertion messages
+    fn name(&self) -> &'static str;
+
+    /// `GET` the next invocation (`None` on failure)
+    fn next_invocation(&self) -> Option<NextInvocation>;
+
+    /// Post an invocation result (`false` if not accepted)
+    fn post_response(&self, request_id: &str, body: &str) -> bool;
+
+    /// Report an invocation error (`false` if not accepted)
+    fn post_error(&self, request_id: &str, error_type: &str, error_message: &str) -> bool;
+
+    /// Report an initialization error (`false` if not accepted)
+    fn post_init_error(&self, error_type: &str, error_message: &str) -> bool;
+}
+
+/// `ruchy-lambda-runtime`
+#[derive(Debug)]
+pub struct RustRuntime(Runtime);
+
+impl RustRuntime {
+    /// Runtime talking to the Runtime API at `endpoint`
+    #[must_use]
+    pub fn new(endpoint: &str) -> Self {
+        Self(Runtime::from_config(RuntimeConfig::new(endpoint)))
+    }
+}
+
+impl RuntimeUnderTest for RustRuntime {
+    fn name(&self) -> &'static str {
+        "ruchy-lambda-runtime"
+    }
+
+    fn next_invocation(&self) -> Option<NextInvocation> {
+        let (context, body) = self.0.next_invocation().ok()?;
+        Some(NextInvocation {
+            request_id: context.request_id,
+            deadline_ms: context.deadline_ms,
+            invoked_function_arn: context.invoked_function_arn,
+            trace_id: context.trace_id,
+            body,
+        })
+    }
+
+    fn post_response(&self, request_id: &str, body: &str) -> bool {
+        self.0.post_response(request_id, body).is_ok()
+    }
+
+    fn post_error(&self, request_id: &str, error_type: &str, error_message: &str) -> bool {
+        let error = HandlerError::new(error_type, error_message);
+        self.0.post_error(request_id, &error).is_ok()
+    }
+
+    fn post_init_error(&self, error_type: &str, error_message: &str) -> bool {
+        let error = HandlerError::new(error_type, error_message);
+        self.0.post_init_error(&error).is_ok()
+    }
+}
+
+/// runtime-pure's HTTP layer, driven the way lib.ruchy drives it
+///
+/// Not runtime-pure's transpiled `Runtime` (see the module comment).
+#[derive(Debug)]
+pub struct PureHttpClient {
+    api_endpoint: String,
+}
+
+impl PureHttpClient {
+    /// Runtime talking to the Runtime API at `endpoint`
+    #[must_use]
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            api_endpoint: endpoint.to_string(),
+        }
+    }
+}
+
+impl RuntimeUnderTest for PureHttpClient {
+    fn name(&self) -> &'static str {
+        "runtime_pure_http_client"
+    }
+
+    fn next_invocation(&self) -> Option<NextInvocation> {
+        let path = String::from("/2018-06-01/runtime/invocation/next");
+        let (headers, body) =
+            pure_http_client::http_get_with_headers(&self.api_endpoint, &path).ok()?;
+        let trace_id = pure_http_client::header(&headers, "lambda-runtime-trace-id");
+        Some(NextInvocation {
+            request_id: pure_http_client::request_id(&headers),
+            deadline_ms: pure_http_client::header_u64(&headers, "lambda-runtime-deadline-ms"),
+            invoked_function_arn: pure_http_client::header(
+                &headers,
+                "lambda-runtime-invoked-function-arn",
+            ),
+            trace_id: Some(trace_id).filter(|id| !id.is_empty()),
+            body,
+        })
+    }
+
+    fn post_response(&self, request_id: &str, body: &str) -> bool {
+        let path = String::from("/2018-06-01/runtime/invocation/") + request_id + "/response";
+        pure_http_client::http_post(&self.api_endpoint, &path, body).is_ok()
+    }
+
+    fn post_error(&self, request_id: &str, error_type: &str, error_message: &str) -> bool {
+        let path = String::from("/2018-06-01/runtime/invocation/") + request_id + "/error";
+        let payload = pure_http_client::error_payload(error_type, error_message);
+        pure_http_client::http_post_error(&self.api_endpoint, &path, error_type, &payload).is_ok()
+    }
+
+    fn post_init_error(&self, error_type: &str, error_message: &str) -> bool {
+        let path = String::from("/2018-06-01/runtime/init/error");
+        let payload = pure_http_client::error_payload(error_type, error_message);
+        pure_http_client::http_post_error(&self.api_endpoint, &path, error_type, &payload).is_ok()
+    }
+}
+
+/// Every implementation, each talking to the Runtime API at `endpoint`
+#[must_use]
+pub fn implementations(endpoint: &str) -> Vec<Box<dyn RuntimeUnderTest>> {
+    vec![
+        Box::new(RustRuntime::new(endpoint)),
+        Box::new(PureHttpClient::new(endpoint)),
+    ]
+}
+
+/// One implementation's pass through a conformance scenario
+#[derive(Debug)]
+pub struct Run<R> {
+    /// [`RuntimeUnderTest::name`]
+    pub runtime: &'static str,
+    /// What the scenario returned
+    pub result: R,
+    /// Requests the fake Runtime API received, in order
+    pub requests: Vec<RecordedRequest>,
+}
+
+/// Run `scenario` against every implementation, each with its own
+/// [`FakeRuntimeApi`] serving `replies`
+///
+/// # Panics
+///
+/// Panics if the fake Runtime API cannot start, or if an implementation
+/// made fewer requests than there are `replies`
+pub fn run_each<R>(
+    replies: &[Reply],
+    scenario: impl Fn(&dyn RuntimeUnderTest) -> R,
+) -> Vec<Run<R>> {
+    (0..implementations("").len())
+        .map(|index| {
+            let api = FakeRuntimeApi::serve(replies.to_vec()).expect("start fake Runtime API");
+            let runtime = implementations(api.endpoint()).swap_remove(index);
+            let result = scenario(runtime.as_ref());
+            let requests = api
+                .requests()
+                .unwrap_or_else(|error| panic!("{}: {error}", runtime.name()));
+            Run {
+                runtime: runtime.name(),
+                result,
+                requests,
+            }
+        })
+        .collect()
+}
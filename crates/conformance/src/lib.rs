@@ -0,0 +1,48 @@
+// Ruchy Lambda Runtime API Conformance
+//
+// The documented Runtime API contract, encoded once and run against every
+// runtime implementation in the repo, so they cannot drift apart:
+// - `spec`: paths, headers and status codes
+// - `FakeRuntimeApi`: answers scripted replies and records each request
+// - `RuntimeUnderTest`: the calls a bootstrap makes, implemented by
+//   `RustRuntime` (ruchy-lambda-runtime) and `PureHttpClient`
+//   (runtime-pure's http_client.rs, called as lib.ruchy calls it)
+// - `run_each`: one scenario against every implementation
+//
+// The contract itself lives in tests/runtime_api_conformance.rs.
+
+#![warn(missing_docs, clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+//! Lambda Runtime API conformance suite
+//!
+//! # Limitations
+//!
+//! [`PureHttpClient`] is runtime-pure's HTTP layer (`http_client.rs`) called
+//! the way lib.ruchy calls it, not runtime-pure's transpiled `Runtime`, which
+//! only talks to 127.0.0.1:9001. A change to the transpiled control flow is
+//! caught by runtime-pure's own integration tests, not by this suite.
+//!
+//! # Examples
+//!
+//! ```
+//! use ruchy_lambda_conformance::{run_each, spec, Reply};
+//!
+//! let runs = run_each(&[Reply::event("req-1", "{}"), Reply::accepted()], |runtime| {
+//!     let invocation = runtime.next_invocation().unwrap();
+//!     runtime.post_response(&invocation.request_id, "ok")
+//! });
+//! for run in runs {
+//!     assert!(run.result, "{}", run.runtime);
+//!     assert_eq!(run.requests[1].path, spec::response_path("req-1"));
+//! }
+//! ```
+
+mod fake_api;
+mod implementations;
+pub mod spec;
+
+pub use fake_api::{FakeRuntimeApi, RecordedRequest, Reply};
+pub use implementations::{
+    implementations, run_each, NextInvocation, PureHttpClient, Run, RuntimeUnderTest, RustRuntime,
+};
@@ -0,0 +1,44 @@
+//! The documented Lambda Runtime API contract (API version `2018-06-01`)
+//!
+//! Paths a runtime requests, the headers it reads from `/next` and sends
+//! with errors, and the status codes it must treat as success.
+
+/// Long-poll for the next invocation (`GET`)
+pub const NEXT_PATH: &str = "/2018-06-01/runtime/invocation/next";
+
+/// Report an initialization failure (`POST`)
+pub const INIT_ERROR_PATH: &str = "/2018-06-01/runtime/init/error";
+
+/// Invocation ID of a `/next` response
+pub const REQUEST_ID_HEADER: &str = "Lambda-Runtime-Aws-Request-Id";
+
+/// Invocation deadline in Unix epoch milliseconds
+pub const DEADLINE_HEADER: &str = "Lambda-Runtime-Deadline-Ms";
+
+/// ARN of the invoked function, alias or version
+pub const FUNCTION_ARN_HEADER: &str = "Lambda-Runtime-Invoked-Function-Arn";
+
+/// X-Ray tracing header, when tracing is active
+pub const TRACE_ID_HEADER: &str = "Lambda-Runtime-Trace-Id";
+
+/// Error classification sent with both error endpoints
+pub const ERROR_TYPE_HEADER: &str = "Lambda-Runtime-Function-Error-Type";
+
+/// Status the Runtime API answers accepted posts with
+pub const ACCEPTED: u16 = 202;
+
+/// Statuses the Runtime API rejects requests with: invalid request ID,
+/// payload too large, runtime state error and container error
+pub const REJECTED: [u16; 4] = [400, 413, 403, 500];
+
+/// Post an invocation's result (`POST`)
+#[must_use]
+pub fn response_path(request_id: &str) -> String {
+    format!("/2018-06-01/runtime/invocation/{request_id}/response")
+}
+
+/// Report an invocation failure (`POST`)
+#[must_use]
+pub fn invocation_error_path(request_id: &str) -> String {
+    format!("/2018-06-01/runtime/invocation/{request_id}/error")
+}
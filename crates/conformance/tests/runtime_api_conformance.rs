@@ -0,0 +1,371 @@
+// Runtime API Conformance Tests
+// The documented Lambda Runtime API contract, checked against every runtime
+// implementation (ruchy-lambda-runtime, and runtime-pure's HTTP layer as
+// lib.ruchy uses it)
+
+use ruchy_lambda_conformance::spec::{
+    self, DEADLINE_HEADER, ERROR_TYPE_HEADER, FUNCTION_ARN_HEADER, INIT_ERROR_PATH, NEXT_PATH,
+    REJECTED, TRACE_ID_HEADER,
+};
+use ruchy_lambda_conformance::{run_each, NextInvocation, RecordedRequest, Reply, Run};
+use std::fmt::Debug;
+
+/// Every implementation returned the same result
+fn assert_same_results<R: PartialEq + Debug>(runs: &[Run<R>]) {
+    for run in &runs[1..] {
+        assert_eq!(
+            run.result, runs[0].result,
+            "{} and {} disagree",
+            run.runtime, runs[0].runtime
+        );
+    }
+}
+
+/// Parse an error document, checking its shape
+fn error_document(request: &RecordedRequest) -> serde_json::Value {
+    let document: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert!(document["errorMessage"].is_string(), "{}", request.body);
+    assert!(document["errorType"].is_string(), "{}", request.body);
+    if let Some(stack_trace) = document.get("stackTrace") {
+        assert!(stack_trace.is_array(), "{}", request.body);
+    }
+    document
+}
+
+// ============================================================================
+// GET /runtime/invocation/next
+// ============================================================================
+
+#[test]
+fn test_next_request_line_and_host() {
+    let runs = run_each(&[Reply::event("req-1", "{}")], |runtime| {
+        runtime.next_invocation()
+    });
+
+    for run in &runs {
+        let request = &run.requests[0];
+        assert_eq!(request.method, "GET", "{}", run.runtime);
+        assert_eq!(request.path, NEXT_PATH, "{}", run.runtime);
+        assert_eq!(request.version, "HTTP/1.1", "{}", run.runtime);
+        assert!(
+            request
+                .header("host")
+                .is_some_and(|host| host.starts_with("127.0.0.1:")),
+            "{}",
+            run.runtime
+        );
+        assert!(request.body.is_empty(), "{}", run.runtime);
+    }
+}
+
+#[test]
+fn test_next_reads_invocation_headers() {
+    let reply = Reply::event("8476a536-e9f4-11e8-9739-2dfe598c3fcd", r#"{"key":"value"}"#)
+        .with_header(DEADLINE_HEADER, "1542409706888")
+        .with_header(
+            FUNCTION_ARN_HEADER,
+            "arn:aws:lambda:us-east-2:123456789012:function:custom-runtime",
+        )
+        .with_header(
+            TRACE_ID_HEADER,
+            "Root=1-5bef4de7-ad49b0e87f6ef6c87fc2e700;Parent=9a9197af755a6419;Sampled=1",
+        );
+    let runs = run_each(&[reply], |runtime| runtime.next_invocation());
+
+    assert_eq!(
+        runs[0].result,
+        Some(NextInvocation {
+            request_id: "8476a536-e9f4-11e8-9739-2dfe598c3fcd".to_string(),
+            deadline_ms: 1_542_409_706_888,
+            invoked_function_arn: "arn:aws:lambda:us-east-2:123456789012:function:custom-runtime"
+                .to_string(),
+            trace_id: Some(
+                "Root=1-5bef4de7-ad49b0e87f6ef6c87fc2e700;Parent=9a9197af755a6419;Sampled=1"
+                    .to_string()
+            ),
+            body: r#"{"key":"value"}"#.to_string(),
+        })
+    );
+    assert_same_results(&runs);
+}
+
+#[test]
+fn test_next_header_names_are_case_insensitive() {
+    let reply = Reply::status(200)
+        .with_header("lambda-runtime-aws-request-id", "lower-1")
+        .with_header("LAMBDA-RUNTIME-DEADLINE-MS", "42");
+    let runs = run_each(&[reply], |runtime| {
+        runtime
+            .next_invocation()
+            .map(|invocation| (invocation.request_id, invocation.deadline_ms))
+    });
+
+    assert_eq!(runs[0].result, Some(("lower-1".to_string(), 42)));
+    assert_same_results(&runs);
+}
+
+#[test]
+fn test_next_without_optional_headers() {
+    let runs = run_each(&[Reply::event("req-1", "[1,2,3]")], |runtime| {
+        runtime.next_invocation()
+    });
+
+    assert_eq!(
+        runs[0].result,
+        Some(NextInvocation {
+            request_id: "req-1".to_string(),
+            body: "[1,2,3]".to_string(),
+            ..NextInvocation::default()
+        })
+    );
+    assert_same_results(&runs);
+}
+
+#[test]
+fn test_next_rejected_status_is_a_failure() {
+    for status in REJECTED {
+        let runs = run_each(&[Reply::status(status)], |runtime| {
+            runtime.next_invocation()
+        });
+        for run in &runs {
+            assert_eq!(run.result, None, "{} accepted {status}", run.runtime);
+        }
+    }
+}
+
+// ============================================================================
+// POST /runtime/invocation/{id}/response
+// ============================================================================
+
+#[test]
+fn test_response_posted_to_invocation_path() {
+    let body = r#"{"statusCode":200,"body":"héllo"}"#;
+    let runs = run_each(
+        &[Reply::event("req-7", "{}"), Reply::accepted()],
+        |runtime| {
+            let invocation = runtime.next_invocation().unwrap();
+            runtime.post_response(&invocation.request_id, body)
+        },
+    );
+
+    for run in &runs {
+        assert!(run.result, "{}", run.runtime);
+        let request = &run.requests[1];
+        assert_eq!(request.method, "POST", "{}", run.runtime);
+        assert_eq!(
+            request.path,
+            spec::response_path("req-7"),
+            "{}",
+            run.runtime
+        );
+        assert_eq!(request.body, body, "{}", run.runtime);
+        assert_eq!(
+            request.header("content-length"),
+            Some(body.len().to_string().as_str()),
+            "{}",
+            run.runtime
+        );
+        assert_eq!(request.header(ERROR_TYPE_HEADER), None, "{}", run.runtime);
+    }
+}
+
+#[test]
+fn test_response_accepted_with_200() {
+    let runs = run_each(
+        &[Reply::event("req-1", "{}"), Reply::status(200)],
+        |runtime| runtime.post_response(&runtime.next_invocation().unwrap().request_id, "ok"),
+    );
+
+    for run in &runs {
+        assert!(run.result, "{}", run.runtime);
+    }
+}
+
+// ============================================================================
+// POST /runtime/invocation/{id}/error and /runtime/init/error
+// ============================================================================
+
+#[test]
+fn test_invocation_error_document_and_type_header() {
+    let message = "bad \"input\"\n\tat line 3";
+    let runs = run_each(
+        &[Reply::event("req-9", "{}"), Reply::accepted()],
+        |runtime| {
+            let invocation = runtime.next_invocation().unwrap();
+            runtime.post_error(&invocation.request_id, "Function.ValidationError", message)
+        },
+    );
+
+    for run in &runs {
+        assert!(run.result, "{}", run.runtime);
+        let request = &run.requests[1];
+        assert_eq!(request.method, "POST", "{}", run.runtime);
+        assert_eq!(
+            request.path,
+            spec::invocation_error_path("req-9"),
+            "{}",
+            run.runtime
+        );
+        assert_eq!(
+            request.header(ERROR_TYPE_HEADER),
+            Some("Function.ValidationError"),
+            "{}",
+            run.runtime
+        );
+        let document = error_document(request);
+        assert_eq!(document["errorMessage"], message, "{}", run.runtime);
+        assert_eq!(
+            document["errorType"], "Function.ValidationError",
+            "{}",
+            run.runtime
+        );
+    }
+}
+
+#[test]
+fn test_init_error_document_and_type_header() {
+    let runs = run_each(&[Reply::accepted()], |runtime| {
+        runtime.post_init_error("Runtime.InitError", "missing TABLE_NAME")
+    });
+
+    for run in &runs {
+        assert!(run.result, "{}", run.runtime);
+        let request = &run.requests[0];
+        assert_eq!(request.method, "POST", "{}", run.runtime);
+        assert_eq!(request.path, INIT_ERROR_PATH, "{}", run.runtime);
+        assert_eq!(
+            request.header(ERROR_TYPE_HEADER),
+            Some("Runtime.InitError"),
+            "{}",
+            run.runtime
+        );
+        let document = error_document(request);
+        assert_eq!(
+            document["errorMessage"], "missing TABLE_NAME",
+            "{}",
+            run.runtime
+        );
+        assert_eq!(
+            document["errorType"], "Runtime.InitError",
+            "{}",
+            run.runtime
+        );
+    }
+}
+
+#[test]
+fn test_error_type_header_cannot_inject_headers() {
+    let runs = run_each(&[Reply::accepted()], |runtime| {
+        runtime.post_init_error("Runtime.InitError\r\nX-Injected: 1", "boom")
+    });
+
+    for run in &runs {
+        let request = &run.requests[0];
+        assert_eq!(request.header("x-injected"), None, "{}", run.runtime);
+        assert_eq!(
+            request.header(ERROR_TYPE_HEADER),
+            Some("Runtime.InitErrorX-Injected: 1"),
+            "{}",
+            run.runtime
+        );
+    }
+}
+
+// ============================================================================
+// Status codes
+// ============================================================================
+
+#[test]
+fn test_rejected_posts_are_failures() {
+    for status in REJECTED {
+        let runs = run_each(
+            &[
+                Reply::event("req-1", "{}"),
+                Reply::status(status),
+                Reply::event("req-2", "{}"),
+                Reply::status(status),
+                Reply::status(status),
+            ],
+            |runtime| {
+                let first = runtime.next_invocation().unwrap();
+                let response = runtime.post_response(&first.request_id, "{}");
+                let second = runtime.next_invocation().unwrap();
+                let error = runtime.post_error(&second.request_id, "Function.Error", "boom");
+                let init_error = runtime.post_init_error("Runtime.InitError", "boom");
+                (response, error, init_error)
+            },
+        );
+        for run in &runs {
+            assert_eq!(
+                run.result,
+                (false, false, false),
+                "{} accepted {status}",
+                run.runtime
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Implementations agree
+// ============================================================================
+
+#[test]
+fn test_implementations_send_identical_requests() {
+    let replies = [
+        Reply::event("req-1", r#"{"n":1}"#),
+        Reply::accepted(),
+        Reply::event("req-2", r#"{"n":2}"#),
+        Reply::accepted(),
+        Reply::accepted(),
+    ];
+    let runs = run_each(&replies, |runtime| {
+        let first = runtime.next_invocation().unwrap();
+        runtime.post_response(&first.request_id, r#"{"ok":true}"#);
+        let second = runtime.next_invocation().unwrap();
+        runtime.post_error(&second.request_id, "Function.Error", "line\nbreak");
+        runtime.post_init_error("Runtime.ExitError", "exit");
+    });
+
+    // Host names each fake Runtime API's own port
+    let without_host = |run: &Run<()>| -> Vec<RecordedRequest> {
+        run.requests
+            .iter()
+            .cloned()
+            .map(|mut request| {
+                request
+                    .headers
+                    .retain(|(name, _)| !name.eq_ignore_ascii_case("host"));
+                request
+            })
+            .collect()
+    };
+    for run in &runs[1..] {
+        assert_eq!(
+            without_host(run),
+            without_host(&runs[0]),
+            "{} and {} disagree",
+            run.runtime,
+            runs[0].runtime
+        );
+    }
+}
+
+#[test]
+fn test_pure_runtime_source_uses_spec_paths() {
+    // PureHttpClient replays lib.ruchy's calls; fail if lib.ruchy moves on
+    let source = include_str!("../../runtime-pure/src/lib.ruchy");
+    for expected in [
+        format!("String::from(\"{NEXT_PATH}\")"),
+        "String::from(\"/2018-06-01/runtime/invocation/\") + request_id + \"/response\""
+            .to_string(),
+        "String::from(\"/2018-06-01/runtime/invocation/\") + request_id + \"/error\"".to_string(),
+        format!("String::from(\"{INIT_ERROR_PATH}\")"),
+        "http_client::http_post_error(&self.api_endpoint, &path, error_type, &payload)".to_string(),
+    ] {
+        assert!(
+            source.contains(&expected),
+            "lib.ruchy no longer has: {expected}"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+// Emulator configuration
+//
+// Defaults match a freshly created Lambda function: 128 MB, 3 second
+// timeout, 6 MB synchronous invoke payloads.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Default listen address (the port `sam local` uses for the Runtime API)
+pub const DEFAULT_ADDR: &str = "127.0.0.1:9001";
+
+/// Default API Gateway front door address (the port `sam local start-api` uses)
+pub const DEFAULT_GATEWAY_ADDR: &str = "127.0.0.1:3000";
+
+/// Default function timeout
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default memory size (MB)
+pub const DEFAULT_MEMORY_MB: u32 = 128;
+
+/// Synchronous invoke payload limit for requests and responses (bytes)
+pub const MAX_SYNC_PAYLOAD: usize = 6 * 1024 * 1024;
+
+/// Emulated function settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmulatorConfig {
+    /// Address serving both the Runtime API and the invoke endpoint
+    pub addr: SocketAddr,
+    /// API Gateway (HTTP API, payload 2.0) front door address, if enabled
+    pub gateway_addr: Option<SocketAddr>,
+    /// Function name (used in the invoked function ARN)
+    pub function_name: String,
+    /// Memory size reported to the runtime and in REPORT lines (MB)
+    pub memory_size_mb: u32,
+    /// Time an invocation may take, from the moment it is accepted
+    pub timeout: Duration,
+    /// Largest accepted invoke payload (bytes)
+    pub max_request_payload: usize,
+    /// Largest accepted function response (bytes)
+    pub max_response_payload: usize,
+    /// Print START / END / REPORT lines to stdout, like the Lambda service logs
+    pub print_reports: bool,
+}
+
+impl Default for EmulatorConfig {
+    fn default() -> Self {
+        Self {
+            addr: DEFAULT_ADDR.parse().expect("valid default address"),
+            gateway_addr: None,
+            function_name: "function".to_string(),
+            memory_size_mb: DEFAULT_MEMORY_MB,
+            timeout: DEFAULT_TIMEOUT,
+            max_request_payload: MAX_SYNC_PAYLOAD,
+            max_response_payload: MAX_SYNC_PAYLOAD,
+            print_reports: false,
+        }
+    }
+}
+
+impl EmulatorConfig {
+    /// Listen on `addr` (port 0 picks a free port)
+    #[must_use]
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Serve an API Gateway front door on `addr` (port 0 picks a free port)
+    #[must_use]
+    pub fn with_gateway_addr(mut self, addr: SocketAddr) -> Self {
+        self.gateway_addr = Some(addr);
+        self
+    }
+
+    /// Set the function name
+    #[must_use]
+    pub fn with_function_name(mut self, function_name: impl Into<String>) -> Self {
+        self.function_name = function_name.into();
+        self
+    }
+
+    /// Set the memory size (MB)
+    #[must_use]
+    pub fn with_memory_size_mb(mut self, memory_size_mb: u32) -> Self {
+        self.memory_size_mb = memory_size_mb;
+        self
+    }
+
+    /// Set the function timeout
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the request and response payload limits (bytes)
+    #[must_use]
+    pub fn with_payload_limits(mut self, max_request: usize, max_response: usize) -> Self {
+        self.max_request_payload = max_request;
+        self.max_response_payload = max_response;
+        self
+    }
+
+    /// Print START / END / REPORT lines to stdout
+    #[must_use]
+    pub fn with_print_reports(mut self, print_reports: bool) -> Self {
+        self.print_reports = print_reports;
+        self
+    }
+
+    /// ARN the runtime sees as `Lambda-Runtime-Invoked-Function-Arn`
+    #[must_use]
+    pub fn function_arn(&self) -> String {
+        format!(
+            "arn:aws:lambda:us-east-1:000000000000:function:{}",
+            self.function_name
+        )
+    }
+}
@@ -0,0 +1,698 @@
+// Emulator: Runtime API + invoke endpoint over one listener
+//
+// Invocations flow through a single queue guarded by a mutex:
+//
+//   invoke() / POST /invoke  ->  queue  ->  GET .../invocation/next (runtime)
+//                                            POST .../{id}/response|error
+//   invoke() returns         <-  finished  <-+
+//
+// Request IDs and trace IDs are derived from a counter, so a given sequence
+// of invokes always produces the same IDs.
+
+use crate::config::EmulatorConfig;
+use crate::gateway;
+use crate::http::{self, Head};
+use crate::report::{Outcome, Report};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Runtime API path prefix
+const RUNTIME_PREFIX: &str = "/2018-06-01/runtime";
+
+/// Invoke path served by the RIE (and `sam local start-lambda`)
+const RIE_INVOKE_PATH: &str = "/2015-03-31/functions/function/invocations";
+
+/// Why an invoke was rejected before reaching the function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvokeError {
+    /// The payload exceeded the request limit
+    RequestTooLarge {
+        /// Payload size (bytes)
+        size: usize,
+        /// Configured limit (bytes)
+        limit: usize,
+    },
+    /// The emulator is shutting down
+    ShuttingDown,
+}
+
+impl fmt::Display for InvokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RequestTooLarge { size, limit } => write!(
+                f,
+                "{size} byte payload exceeds the {limit} byte limit for the Invoke operation"
+            ),
+            Self::ShuttingDown => f.write_str("emulator is shutting down"),
+        }
+    }
+}
+
+impl std::error::Error for InvokeError {}
+
+/// Result of one completed invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    /// Request ID handed to the runtime
+    pub request_id: String,
+    /// How the invocation ended
+    pub outcome: Outcome,
+    /// REPORT metrics
+    pub report: Report,
+}
+
+/// An event waiting for the runtime to call `/next`
+struct Queued {
+    request_id: String,
+    trace_id: String,
+    payload: Vec<u8>,
+    accepted: Instant,
+}
+
+/// An event handed to the runtime, awaiting its response
+struct InFlight {
+    accepted: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    sequence: u64,
+    gateway_sequence: u64,
+    queue: VecDeque<Queued>,
+    in_flight: HashMap<String, InFlight>,
+    finished: HashMap<String, Invocation>,
+    reports: Vec<Report>,
+    init_errors: Vec<String>,
+    /// Invocations that timed out while the runtime held them
+    timeouts: u64,
+    /// Peak RSS of the supervised runtime process (KiB)
+    peak_rss_kb: Option<u64>,
+}
+
+pub(crate) struct Shared {
+    pub(crate) config: EmulatorConfig,
+    state: Mutex<State>,
+    /// Signalled when an event is queued (wakes `/next`)
+    queued: Condvar,
+    /// Signalled when an invocation finishes (wakes `invoke`)
+    finished: Condvar,
+    shutdown: AtomicBool,
+}
+
+/// A running emulator; stops when dropped
+pub struct Emulator {
+    shared: Arc<Shared>,
+    addr: SocketAddr,
+    gateway_addr: Option<SocketAddr>,
+    acceptors: Vec<JoinHandle<()>>,
+}
+
+impl fmt::Debug for Emulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Emulator")
+            .field("addr", &self.addr)
+            .field("gateway_addr", &self.gateway_addr)
+            .field("config", &self.shared.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Emulator {
+    /// Bind the listeners and start serving
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an address cannot be bound.
+    pub fn start(config: EmulatorConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(config.addr)?;
+        let addr = listener.local_addr()?;
+        let gateway = config.gateway_addr.map(TcpListener::bind).transpose()?;
+        let gateway_addr = gateway.as_ref().map(TcpListener::local_addr).transpose()?;
+        let shared = Arc::new(Shared {
+            config,
+            state: Mutex::new(State::default()),
+            queued: Condvar::new(),
+            finished: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let mut acceptors = vec![spawn_acceptor(&shared, listener, serve_api)?];
+        if let Some(gateway) = gateway {
+            acceptors.push(spawn_acceptor(&shared, gateway, serve_gateway)?);
+        }
+
+        Ok(Self {
+            shared,
+            addr,
+            gateway_addr,
+            acceptors,
+        })
+    }
+
+    /// Address the emulator listens on
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Address of the API Gateway front door, if enabled
+    #[must_use]
+    pub fn gateway_addr(&self) -> Option<SocketAddr> {
+        self.gateway_addr
+    }
+
+    pub(crate) fn shared(&self) -> &Arc<Shared> {
+        &self.shared
+    }
+
+    /// Configuration the emulator runs with
+    #[must_use]
+    pub fn config(&self) -> &EmulatorConfig {
+        &self.shared.config
+    }
+
+    /// Environment a bootstrap needs to talk to this emulator
+    ///
+    /// Mirrors the reserved variables Lambda sets for custom runtimes.
+    #[must_use]
+    pub fn runtime_env(&self) -> Vec<(&'static str, String)> {
+        let config = &self.shared.config;
+        vec![
+            ("AWS_LAMBDA_RUNTIME_API", self.addr.to_string()),
+            ("AWS_LAMBDA_FUNCTION_NAME", config.function_name.clone()),
+            ("AWS_LAMBDA_FUNCTION_VERSION", "$LATEST".to_string()),
+            (
+                "AWS_LAMBDA_FUNCTION_MEMORY_SIZE",
+                config.memory_size_mb.to_string(),
+            ),
+            ("AWS_REGION", "us-east-1".to_string()),
+            ("_HANDLER", "handler".to_string()),
+        ]
+    }
+
+    /// Queue `payload` and wait until the runtime answers or the timeout hits
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload is over the request limit or the
+    /// emulator is shutting down.
+    pub fn invoke(&self, payload: &[u8]) -> Result<Invocation, InvokeError> {
+        invoke(&self.shared, payload)
+    }
+
+    /// REPORT metrics of every finished invocation, oldest first
+    #[must_use]
+    pub fn reports(&self) -> Vec<Report> {
+        lock(&self.shared).reports.clone()
+    }
+
+    /// Error documents posted to `/runtime/init/error`
+    #[must_use]
+    pub fn init_errors(&self) -> Vec<String> {
+        lock(&self.shared).init_errors.clone()
+    }
+
+    /// Stop accepting connections and release blocked `/next` calls
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        self.shared.queued.notify_all();
+        self.shared.finished.notify_all();
+        // Wake the acceptors so they see the flag
+        for addr in std::iter::once(self.addr).chain(self.gateway_addr) {
+            let _ = TcpStream::connect(addr);
+        }
+        for acceptor in self.acceptors.drain(..) {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+impl Drop for Emulator {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn lock(shared: &Shared) -> MutexGuard<'_, State> {
+    shared
+        .state
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Serves one connection on a listener
+type Serve = fn(&Shared, TcpStream) -> io::Result<()>;
+
+fn spawn_acceptor(
+    shared: &Arc<Shared>,
+    listener: TcpListener,
+    serve: Serve,
+) -> io::Result<JoinHandle<()>> {
+    let shared = Arc::clone(shared);
+    thread::Builder::new()
+        .name("emulator-accept".to_string())
+        .spawn(move || accept_loop(&shared, &listener, serve))
+}
+
+fn accept_loop(shared: &Arc<Shared>, listener: &TcpListener, serve: Serve) {
+    for stream in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        let shared = Arc::clone(shared);
+        let _ = thread::Builder::new()
+            .name("emulator-conn".to_string())
+            .spawn(move || {
+                if let Err(e) = serve(&shared, stream) {
+                    if e.kind() != io::ErrorKind::UnexpectedEof {
+                        eprintln!("[EMULATOR] connection error: {e}");
+                    }
+                }
+            });
+    }
+}
+
+fn invoke(shared: &Shared, payload: &[u8]) -> Result<Invocation, InvokeError> {
+    let config = &shared.config;
+    if payload.len() > config.max_request_payload {
+        return Err(InvokeError::RequestTooLarge {
+            size: payload.len(),
+            limit: config.max_request_payload,
+        });
+    }
+
+    let accepted = Instant::now();
+    let deadline = accepted + config.timeout;
+    let mut state = lock(shared);
+    if shared.shutdown.load(Ordering::SeqCst) {
+        return Err(InvokeError::ShuttingDown);
+    }
+    state.sequence += 1;
+    let sequence = state.sequence;
+    let request_id = request_id(sequence);
+    state.queue.push_back(Queued {
+        request_id: request_id.clone(),
+        trace_id: trace_id(sequence),
+        payload: payload.to_vec(),
+        accepted,
+    });
+    shared.queued.notify_one();
+
+    loop {
+        if let Some(invocation) = state.finished.remove(&request_id) {
+            return Ok(invocation);
+        }
+        if shared.shutdown.load(Ordering::SeqCst) {
+            return Err(InvokeError::ShuttingDown);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            // Drop it wherever it is; a late response gets InvalidRequestID
+            state.queue.retain(|queued| queued.request_id != request_id);
+            if state.in_flight.remove(&request_id).is_some() {
+                state.timeouts += 1;
+            }
+            let invocation = finish(shared, &mut state, &request_id, accepted, Outcome::Timeout);
+            return Ok(invocation);
+        }
+        state = shared
+            .finished
+            .wait_timeout(state, deadline - now)
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .0;
+    }
+}
+
+/// Record the report for a finished invocation
+fn finish(
+    shared: &Shared,
+    state: &mut State,
+    request_id: &str,
+    accepted: Instant,
+    outcome: Outcome,
+) -> Invocation {
+    let config = &shared.config;
+    let report = Report::new(
+        request_id,
+        accepted.elapsed(),
+        config.memory_size_mb,
+        outcome == Outcome::Timeout,
+    )
+    .with_max_memory_used_mb(state.peak_rss_kb.map(|kb| kb.div_ceil(1024)))
+    .with_error_type(outcome.report_error_type());
+    if config.print_reports {
+        println!("END RequestId: {request_id}");
+        println!("{report}");
+    }
+    state.reports.push(report.clone());
+    Invocation {
+        request_id: request_id.to_string(),
+        outcome,
+        report,
+    }
+}
+
+/// Invocations that timed out in the runtime's hands so far
+pub(crate) fn timeouts(shared: &Shared) -> u64 {
+    lock(shared).timeouts
+}
+
+/// Whether the emulator is shutting down
+pub(crate) fn is_shutting_down(shared: &Shared) -> bool {
+    shared.shutdown.load(Ordering::SeqCst)
+}
+
+/// Record an RSS sample of the runtime process; `None` starts a new process
+pub(crate) fn record_rss(shared: &Shared, rss_kb: Option<u64>) {
+    let mut state = lock(shared);
+    let peak = state.peak_rss_kb;
+    state.peak_rss_kb = rss_kb.map(|kb| peak.map_or(kb, |peak| peak.max(kb)));
+}
+
+/// Fail every invocation the runtime holds (its process is gone)
+pub(crate) fn abort_in_flight(shared: &Shared, outcome: &Outcome) {
+    let mut state = lock(shared);
+    let request_ids: Vec<String> = state.in_flight.keys().cloned().collect();
+    for request_id in request_ids {
+        if let Some(in_flight) = state.in_flight.remove(&request_id) {
+            let invocation = finish(
+                shared,
+                &mut state,
+                &request_id,
+                in_flight.accepted,
+                outcome.clone(),
+            );
+            state.finished.insert(request_id, invocation);
+        }
+    }
+    shared.finished.notify_all();
+}
+
+fn request_id(sequence: u64) -> String {
+    format!("00000000-0000-4000-8000-{sequence:012x}")
+}
+
+fn trace_id(sequence: u64) -> String {
+    format!("Root=1-00000000-{sequence:024x};Parent={sequence:016x};Sampled=0")
+}
+
+/// Status, extra headers and body of a response
+pub(crate) type Reply = (u16, Vec<(String, String)>, Vec<u8>);
+
+fn json_reply(status: u16, body: &serde_json::Value) -> Reply {
+    (
+        status,
+        vec![("Content-Type".to_string(), "application/json".to_string())],
+        body.to_string().into_bytes(),
+    )
+}
+
+fn error_reply(status: u16, error_type: &str, message: &str) -> Reply {
+    json_reply(
+        status,
+        &serde_json::json!({ "errorType": error_type, "errorMessage": message }),
+    )
+}
+
+/// Runtime API and invoke endpoint
+fn serve_api(shared: &Shared, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let head = http::read_head(&mut reader)?;
+
+    let limit = if head.path.starts_with(RUNTIME_PREFIX) {
+        shared.config.max_response_payload
+    } else {
+        shared.config.max_request_payload
+    };
+    let reply = if head.content_length > limit {
+        // Answer without reading a body we would reject anyway
+        too_large(shared, &head)
+    } else {
+        let body = http::read_body(&mut reader, head.content_length)?;
+        route(shared, &head, body)
+    };
+    write_reply(stream, reply)
+}
+
+/// API Gateway front door: plain HTTP in, payload 2.0 events to the function
+fn serve_gateway(shared: &Shared, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let head = http::read_head(&mut reader)?;
+
+    let reply = if head.content_length > shared.config.max_request_payload {
+        gateway::too_large()
+    } else {
+        let body = http::read_body(&mut reader, head.content_length)?;
+        let sequence = {
+            let mut state = lock(shared);
+            state.gateway_sequence += 1;
+            state.gateway_sequence
+        };
+        let source_ip = stream.peer_addr()?.ip();
+        let event = gateway::event(&head, &body, source_ip, sequence);
+        gateway::reply(invoke(shared, event.as_bytes()))
+    };
+    write_reply(stream, reply)
+}
+
+fn write_reply(mut stream: TcpStream, (status, headers, body): Reply) -> io::Result<()> {
+    let headers: Vec<(&str, &str)> = headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    http::write_response(&mut stream, status, &headers, &body)
+}
+
+fn route(shared: &Shared, head: &Head, body: Vec<u8>) -> Reply {
+    let method = head.method.as_str();
+    let path = head.path.split('?').next().unwrap_or_default();
+
+    if let Some(rest) = path.strip_prefix(RUNTIME_PREFIX) {
+        return match (method, rest) {
+            ("GET", "/invocation/next") => next(shared),
+            ("POST", "/init/error") => {
+                let document = String::from_utf8_lossy(&body).into_owned();
+                if shared.config.print_reports {
+                    println!("INIT_REPORT Status: error {document}");
+                }
+                lock(shared).init_errors.push(document);
+                json_reply(202, &serde_json::json!({ "status": "OK" }))
+            }
+            ("POST", rest) => match rest
+                .strip_prefix("/invocation/")
+                .and_then(|rest| rest.rsplit_once('/'))
+            {
+                Some((id, "response")) => complete(shared, id, Ok(body)),
+                Some((id, "error")) => {
+                    let error_type = head
+                        .header("Lambda-Runtime-Function-Error-Type")
+                        .map(str::to_string);
+                    complete(shared, id, Err((error_type, body)))
+                }
+                _ => error_reply(404, "NotFound", "unknown Runtime API path"),
+            },
+            _ => error_reply(404, "NotFound", "unknown Runtime API path"),
+        };
+    }
+
+    match (method, path) {
+        ("POST", "/invoke" | RIE_INVOKE_PATH) => invoke_reply(shared, &body),
+        (_, "/invoke" | RIE_INVOKE_PATH) => error_reply(405, "MethodNotAllowed", "use POST"),
+        _ => error_reply(404, "NotFound", "unknown path"),
+    }
+}
+
+/// `GET /runtime/invocation/next`: block until an event is queued
+fn next(shared: &Shared) -> Reply {
+    let mut state = lock(shared);
+    let queued = loop {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            return error_reply(503, "ServiceUnavailable", "emulator is shutting down");
+        }
+        if let Some(queued) = state.queue.pop_front() {
+            break queued;
+        }
+        state = shared
+            .queued
+            .wait(state)
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+    };
+
+    let config = &shared.config;
+    let remaining = config.timeout.saturating_sub(queued.accepted.elapsed());
+    let deadline_ms = (SystemTime::now() + remaining)
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis();
+    state.in_flight.insert(
+        queued.request_id.clone(),
+        InFlight {
+            accepted: queued.accepted,
+        },
+    );
+    drop(state);
+
+    if config.print_reports {
+        println!("START RequestId: {} Version: $LATEST", queued.request_id);
+    }
+    (
+        200,
+        vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (
+                "Lambda-Runtime-Aws-Request-Id".to_string(),
+                queued.request_id,
+            ),
+            (
+                "Lambda-Runtime-Deadline-Ms".to_string(),
+                deadline_ms.to_string(),
+            ),
+            (
+                "Lambda-Runtime-Invoked-Function-Arn".to_string(),
+                config.function_arn(),
+            ),
+            ("Lambda-Runtime-Trace-Id".to_string(), queued.trace_id),
+        ],
+        queued.payload,
+    )
+}
+
+/// `POST /runtime/invocation/{id}/response|error`
+fn complete(
+    shared: &Shared,
+    request_id: &str,
+    result: Result<Vec<u8>, (Option<String>, Vec<u8>)>,
+) -> Reply {
+    let mut state = lock(shared);
+    let Some(in_flight) = state.in_flight.remove(request_id) else {
+        return error_reply(400, "InvalidRequestID", "Invalid request ID");
+    };
+    let outcome = match result {
+        Ok(body) => Outcome::Success(String::from_utf8_lossy(&body).into_owned()),
+        Err((error_type, body)) => Outcome::Error {
+            error_type,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        },
+    };
+    let invocation = finish(shared, &mut state, request_id, in_flight.accepted, outcome);
+    state.finished.insert(request_id.to_string(), invocation);
+    shared.finished.notify_all();
+    json_reply(202, &serde_json::json!({ "status": "OK" }))
+}
+
+/// Oversized body: an invoke is rejected, a runtime response or error fails the invocation
+fn too_large(shared: &Shared, head: &Head) -> Reply {
+    let size = head.content_length;
+    let response_id = head
+        .path
+        .strip_prefix(RUNTIME_PREFIX)
+        .and_then(|rest| rest.strip_prefix("/invocation/"))
+        .and_then(|rest| {
+            rest.strip_suffix("/response")
+                .or_else(|| rest.strip_suffix("/error"))
+        });
+
+    let Some(request_id) = response_id else {
+        let error = InvokeError::RequestTooLarge {
+            size,
+            limit: shared.config.max_request_payload,
+        };
+        return error_reply(413, "RequestEntityTooLargeException", &error.to_string());
+    };
+
+    let mut state = lock(shared);
+    if let Some(in_flight) = state.in_flight.remove(request_id) {
+        let outcome = Outcome::ResponseTooLarge { size };
+        let invocation = finish(shared, &mut state, request_id, in_flight.accepted, outcome);
+        state.finished.insert(request_id.to_string(), invocation);
+        shared.finished.notify_all();
+    }
+    error_reply(
+        413,
+        "RequestEntityTooLarge",
+        &format!(
+            "Exceeded maximum allowed payload size ({} bytes)",
+            shared.config.max_response_payload
+        ),
+    )
+}
+
+/// `POST /invoke`: run the invocation and answer like the Invoke API
+fn invoke_reply(shared: &Shared, payload: &[u8]) -> Reply {
+    match invoke(shared, payload) {
+        Ok(invocation) => {
+            let mut headers = vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                (
+                    "X-Amz-Request-Id".to_string(),
+                    invocation.request_id.clone(),
+                ),
+                ("X-Amz-Executed-Version".to_string(), "$LATEST".to_string()),
+            ];
+            if invocation.outcome.is_error() {
+                headers.push(("X-Amz-Function-Error".to_string(), "Unhandled".to_string()));
+            }
+            let body = invocation.outcome.body(shared.config.timeout);
+            (200, headers, body.into_bytes())
+        }
+        Err(error @ InvokeError::RequestTooLarge { .. }) => {
+            error_reply(413, "RequestEntityTooLargeException", &error.to_string())
+        }
+        Err(error @ InvokeError::ShuttingDown) => {
+            error_reply(503, "ServiceException", &error.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_are_deterministic() {
+        assert_eq!(request_id(1), "00000000-0000-4000-8000-000000000001");
+        assert_eq!(request_id(255), "00000000-0000-4000-8000-0000000000ff");
+        assert_eq!(
+            trace_id(2),
+            "Root=1-00000000-000000000000000000000002;Parent=0000000000000002;Sampled=0"
+        );
+    }
+
+    #[test]
+    fn test_invoke_rejects_large_payload() {
+        let config = EmulatorConfig::default()
+            .with_addr("127.0.0.1:0".parse().unwrap())
+            .with_payload_limits(4, 4);
+        let emulator = Emulator::start(config).unwrap();
+        assert_eq!(
+            emulator.invoke(b"12345"),
+            Err(InvokeError::RequestTooLarge { size: 5, limit: 4 })
+        );
+        assert!(emulator.reports().is_empty());
+    }
+
+    #[test]
+    fn test_invoke_times_out_without_runtime() {
+        let config = EmulatorConfig::default()
+            .with_addr("127.0.0.1:0".parse().unwrap())
+            .with_timeout(Duration::from_millis(50));
+        let emulator = Emulator::start(config).unwrap();
+
+        let invocation = emulator.invoke(b"{}").unwrap();
+        assert_eq!(invocation.outcome, Outcome::Timeout);
+        assert!(invocation.report.timed_out);
+        assert!(invocation.report.duration >= Duration::from_millis(50));
+        assert_eq!(emulator.reports(), vec![invocation.report]);
+    }
+}
@@ -0,0 +1,51 @@
+// Canned events for local invokes
+//
+// Trimmed-down copies of the events AWS services send, one file per source
+// under crates/emulator/fixtures/. They are compiled in, so the emulator
+// binary can replay them from anywhere:
+//
+//   ruchy-lambda-emulator --bootstrap ./bootstrap --fixture sqs --fixture apigw-v2
+
+/// Built-in fixtures as (name, event JSON), sorted by name
+pub const FIXTURES: &[(&str, &str)] = &[
+    ("alb", include_str!("../fixtures/alb.json")),
+    ("apigw-v1", include_str!("../fixtures/apigw-v1.json")),
+    ("apigw-v2", include_str!("../fixtures/apigw-v2.json")),
+    ("dynamodb", include_str!("../fixtures/dynamodb.json")),
+    ("eventbridge", include_str!("../fixtures/eventbridge.json")),
+    ("kinesis", include_str!("../fixtures/kinesis.json")),
+    ("s3", include_str!("../fixtures/s3.json")),
+    ("scheduled", include_str!("../fixtures/scheduled.json")),
+    ("sns", include_str!("../fixtures/sns.json")),
+    ("sqs", include_str!("../fixtures/sqs.json")),
+];
+
+/// Event JSON of the built-in fixture called `name`
+#[must_use]
+pub fn fixture(name: &str) -> Option<&'static str> {
+    FIXTURES
+        .iter()
+        .find(|(fixture, _)| *fixture == name)
+        .map(|(_, event)| *event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_json_objects() {
+        for (name, event) in FIXTURES {
+            let value: serde_json::Value =
+                serde_json::from_str(event).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert!(value.is_object(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_fixture_lookup() {
+        assert!(fixture("sqs").is_some_and(|event| event.contains("aws:sqs")));
+        assert_eq!(fixture("nope"), None);
+        assert!(FIXTURES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
@@ -0,0 +1,387 @@
+// API Gateway front door (HTTP API, payload format 2.0)
+//
+// Turns a plain HTTP request into the event API Gateway would send, and the
+// function's result into the HTTP response API Gateway would return:
+//
+//   curl -i localhost:3000/users/42?verbose=1
+//     -> {"version":"2.0","routeKey":"$default","rawPath":"/users/42",...}
+//     <- {"statusCode":200,"headers":{...},"body":"..."}
+//
+// Responses follow the 2.0 format rules: an object with `statusCode` is used
+// as-is, anything else becomes a 200 `application/json` body.
+
+use crate::emulator::{Invocation, InvokeError, Reply};
+use crate::http::Head;
+use crate::report::Outcome;
+use ruchy_lambda_simd::base64;
+use serde_json::{json, Map, Value};
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Build the payload 2.0 event for a request
+pub(crate) fn event(head: &Head, body: &[u8], source_ip: IpAddr, sequence: u64) -> String {
+    let (raw_path, raw_query) = head.path.split_once('?').unwrap_or((&head.path, ""));
+
+    let mut headers = Map::new();
+    let mut cookies = Vec::new();
+    for (name, value) in &head.headers {
+        let name = name.to_ascii_lowercase();
+        if name == "cookie" {
+            cookies.extend(value.split(';').map(str::trim).filter(|c| !c.is_empty()));
+            continue;
+        }
+        append(&mut headers, name, value);
+    }
+
+    let mut query = Map::new();
+    for pair in raw_query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        append(&mut query, percent_decode(key), &percent_decode(value));
+    }
+
+    let domain_name = head.header("Host").unwrap_or("localhost");
+    let domain_prefix = domain_name.split(['.', ':']).next().unwrap_or(domain_name);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut event = json!({
+        "version": "2.0",
+        "routeKey": "$default",
+        "rawPath": raw_path,
+        "rawQueryString": raw_query,
+        "headers": headers,
+        "requestContext": {
+            "accountId": "000000000000",
+            "apiId": "local",
+            "domainName": domain_name,
+            "domainPrefix": domain_prefix,
+            "http": {
+                "method": head.method,
+                "path": raw_path,
+                "protocol": "HTTP/1.1",
+                "sourceIp": source_ip.to_string(),
+                "userAgent": head.header("User-Agent").unwrap_or_default(),
+            },
+            "requestId": format!("local-{sequence:012x}"),
+            "routeKey": "$default",
+            "stage": "$default",
+            "time": format_clf(now.as_secs()),
+            "timeEpoch": u64::try_from(now.as_millis()).unwrap_or(u64::MAX),
+        },
+        "isBase64Encoded": false,
+    });
+
+    if !cookies.is_empty() {
+        event["cookies"] = json!(cookies);
+    }
+    if !query.is_empty() {
+        event["queryStringParameters"] = Value::Object(query);
+    }
+    if !body.is_empty() {
+        if let Ok(text) = std::str::from_utf8(body) {
+            event["body"] = json!(text);
+        } else {
+            event["body"] = json!(base64::encode(body));
+            event["isBase64Encoded"] = json!(true);
+        }
+    }
+    event.to_string()
+}
+
+/// HTTP response for an invocation result
+pub(crate) fn reply(result: Result<Invocation, InvokeError>) -> Reply {
+    match result {
+        Ok(Invocation {
+            outcome: Outcome::Success(body),
+            ..
+        }) => response(&body).unwrap_or_else(|| message(500, "Internal Server Error")),
+        Ok(Invocation {
+            outcome: Outcome::Timeout,
+            ..
+        })
+        | Err(InvokeError::ShuttingDown) => message(503, "Service Unavailable"),
+        Ok(_) => message(500, "Internal Server Error"),
+        Err(InvokeError::RequestTooLarge { .. }) => too_large(),
+    }
+}
+
+/// Request body over the payload limit
+pub(crate) fn too_large() -> Reply {
+    message(413, "Request Entity Too Large")
+}
+
+/// Apply the payload 2.0 response rules; `None` for a malformed response
+fn response(body: &str) -> Option<Reply> {
+    let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(body) else {
+        return Some(json_body(200, body.as_bytes().to_vec()));
+    };
+    let Some(status) = object.get("statusCode") else {
+        return Some(json_body(200, body.as_bytes().to_vec()));
+    };
+    let status = status
+        .as_u64()
+        .and_then(|status| u16::try_from(status).ok())
+        .filter(|status| (100..=599).contains(status))?;
+
+    let mut headers = Vec::new();
+    if let Some(Value::Object(map)) = object.remove("headers") {
+        for (name, value) in map {
+            headers.push((name, scalar(value)?));
+        }
+    }
+    if let Some(Value::Array(cookies)) = object.remove("cookies") {
+        for cookie in cookies {
+            headers.push(("Set-Cookie".to_string(), scalar(cookie)?));
+        }
+    }
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        headers.push(("Content-Type".to_string(), "application/json".to_string()));
+    }
+
+    let body = match object.remove("body") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(value) => {
+            let text = scalar(value)?;
+            if object.get("isBase64Encoded") == Some(&Value::Bool(true)) {
+                base64::decode(text.as_bytes()).ok()?
+            } else {
+                text.into_bytes()
+            }
+        }
+    };
+    Some((status, headers, body))
+}
+
+fn json_body(status: u16, body: Vec<u8>) -> Reply {
+    (
+        status,
+        vec![("Content-Type".to_string(), "application/json".to_string())],
+        body,
+    )
+}
+
+/// API Gateway's own `{"message": ...}` error responses
+fn message(status: u16, message: &str) -> Reply {
+    json_body(
+        status,
+        json!({ "message": message }).to_string().into_bytes(),
+    )
+}
+
+/// Header and body values: strings as-is, numbers and booleans printed
+fn scalar(value: Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Repeated headers and query parameters are joined with commas
+fn append(map: &mut Map<String, Value>, key: String, value: &str) {
+    match map.get_mut(&key) {
+        Some(Value::String(existing)) => {
+            existing.push(',');
+            existing.push_str(value);
+        }
+        _ => {
+            map.insert(key, json!(value));
+        }
+    }
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Format seconds since the epoch like API Gateway: "12/Mar/2020:19:03:58 +0000"
+fn format_clf(secs: u64) -> String {
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let remaining = secs % 86_400;
+    format!(
+        "{day:02}/{}/{year}:{:02}:{:02}:{:02} +0000",
+        MONTHS[usize::try_from(month - 1).unwrap_or(0)],
+        remaining / 3600,
+        (remaining % 3600) / 60,
+        remaining % 60
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) Gregorian date
+///
+/// Howard Hinnant's `civil_from_days` algorithm, restricted to dates after
+/// the epoch.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Report;
+    use std::time::Duration;
+
+    fn head(method: &str, path: &str, headers: &[(&str, &str)]) -> Head {
+        Head {
+            method: method.to_string(),
+            path: path.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                .collect(),
+            content_length: 0,
+        }
+    }
+
+    fn success(body: &str) -> Invocation {
+        Invocation {
+            request_id: "req".to_string(),
+            outcome: Outcome::Success(body.to_string()),
+            report: Report::new("req", Duration::from_millis(1), 128, false),
+        }
+    }
+
+    #[test]
+    fn test_event_shape() {
+        let head = head(
+            "POST",
+            "/users/42?verbose=1&tag=a&tag=b%20c",
+            &[
+                ("Host", "localhost:3000"),
+                ("User-Agent", "curl/8.0"),
+                ("X-Multi", "1"),
+                ("x-multi", "2"),
+                ("Cookie", "a=1; b=2"),
+            ],
+        );
+        let event: Value =
+            serde_json::from_str(&event(&head, b"{\"id\":42}", [127, 0, 0, 1].into(), 7)).unwrap();
+
+        assert_eq!(event["version"], "2.0");
+        assert_eq!(event["rawPath"], "/users/42");
+        assert_eq!(event["rawQueryString"], "verbose=1&tag=a&tag=b%20c");
+        assert_eq!(event["queryStringParameters"]["tag"], "a,b c");
+        assert_eq!(event["headers"]["x-multi"], "1,2");
+        assert_eq!(event["headers"]["host"], "localhost:3000");
+        assert!(event["headers"].get("cookie").is_none());
+        assert_eq!(event["cookies"], json!(["a=1", "b=2"]));
+        assert_eq!(event["body"], "{\"id\":42}");
+        assert_eq!(event["isBase64Encoded"], false);
+
+        let context = &event["requestContext"];
+        assert_eq!(context["http"]["method"], "POST");
+        assert_eq!(context["http"]["sourceIp"], "127.0.0.1");
+        assert_eq!(context["http"]["userAgent"], "curl/8.0");
+        assert_eq!(context["domainPrefix"], "localhost");
+        assert_eq!(context["requestId"], "local-000000000007");
+    }
+
+    #[test]
+    fn test_event_binary_body_and_no_query() {
+        let event: Value = serde_json::from_str(&event(
+            &head("PUT", "/blob", &[]),
+            &[0xff, 0x00],
+            [127, 0, 0, 1].into(),
+            1,
+        ))
+        .unwrap();
+        assert_eq!(event["isBase64Encoded"], true);
+        assert_eq!(event["body"], "/wA=");
+        assert!(event.get("queryStringParameters").is_none());
+        assert!(event.get("cookies").is_none());
+    }
+
+    #[test]
+    fn test_structured_response() {
+        let (status, headers, body) = reply(Ok(success(
+            r#"{"statusCode":201,"headers":{"content-type":"text/plain","x-n":1},
+                "cookies":["s=1"],"body":"created"}"#,
+        )));
+        assert_eq!(status, 201);
+        assert!(headers.contains(&("content-type".to_string(), "text/plain".to_string())));
+        assert!(headers.contains(&("x-n".to_string(), "1".to_string())));
+        assert!(headers.contains(&("Set-Cookie".to_string(), "s=1".to_string())));
+        assert_eq!(body, b"created");
+
+        let (_, _, body) = reply(Ok(success(
+            r#"{"statusCode":200,"body":"aGk=","isBase64Encoded":true}"#,
+        )));
+        assert_eq!(body, b"hi");
+    }
+
+    #[test]
+    fn test_inferred_response() {
+        let (status, headers, body) = reply(Ok(success(r#"{"hello":"world"}"#)));
+        assert_eq!(status, 200);
+        assert_eq!(
+            headers,
+            vec![("Content-Type".to_string(), "application/json".to_string())]
+        );
+        assert_eq!(body, br#"{"hello":"world"}"#);
+
+        assert_eq!(reply(Ok(success("plain"))).2, b"plain");
+    }
+
+    #[test]
+    fn test_error_responses() {
+        assert_eq!(reply(Ok(success(r#"{"statusCode":"abc"}"#))).0, 500);
+        assert_eq!(reply(Ok(success(r#"{"statusCode":99}"#))).0, 500);
+        let timeout = Ok(Invocation {
+            request_id: "req".to_string(),
+            outcome: Outcome::Timeout,
+            report: Report::new("req", Duration::from_secs(3), 128, true),
+        });
+        assert_eq!(reply(timeout).0, 503);
+        let (status, _, body) = reply(Err(InvokeError::RequestTooLarge { size: 2, limit: 1 }));
+        assert_eq!(status, 413);
+        assert_eq!(body, br#"{"message":"Request Entity Too Large"}"#);
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%20b%2Fc"), "a b/c");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn test_format_clf() {
+        assert_eq!(format_clf(1_584_039_838), "12/Mar/2020:19:03:58 +0000");
+    }
+}
@@ -0,0 +1,252 @@
+// Minimal HTTP/1.1 server side: read one request, write one response
+// (plus reading the upstream response the recorder relays)
+//
+// Every response carries `Connection: close`, so a connection serves a
+// single exchange. That is all the Runtime API clients and curl need.
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Longest request head (request line + headers) we accept
+const MAX_HEAD: usize = 64 * 1024;
+
+/// Request line and headers of an incoming request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Head {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub content_length: usize,
+}
+
+impl Head {
+    /// Case-insensitive header lookup
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Status line and headers of a response read from upstream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResponseHead {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// `Content-Length`, if sent (otherwise the body runs to end of stream)
+    pub content_length: Option<usize>,
+}
+
+/// Read the request line and headers (CRLF or bare LF line endings)
+pub(crate) fn read_head(reader: &mut impl BufRead) -> io::Result<Head> {
+    let (request_line, headers) = read_lines(reader)?;
+    let mut parts = request_line.split_ascii_whitespace();
+    let (Some(method), Some(path), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(invalid("unsupported HTTP version"));
+    }
+
+    let mut head = Head {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers,
+        content_length: 0,
+    };
+    if let Some(length) = content_length(&head.headers)? {
+        head.content_length = length;
+    }
+    Ok(head)
+}
+
+/// Read a response's status line and headers
+pub(crate) fn read_response_head(reader: &mut impl BufRead) -> io::Result<ResponseHead> {
+    let (status_line, headers) = read_lines(reader)?;
+    let mut parts = status_line.split_ascii_whitespace();
+    let (Some(version), Some(status)) = (parts.next(), parts.next()) else {
+        return Err(invalid("malformed status line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(invalid("unsupported HTTP version"));
+    }
+    let status = status.parse().map_err(|_| invalid("invalid status code"))?;
+    let content_length = content_length(&headers)?;
+    Ok(ResponseHead {
+        status,
+        headers,
+        content_length,
+    })
+}
+
+/// Read the first line and the headers up to the blank line
+fn read_lines(reader: &mut impl BufRead) -> io::Result<(String, Vec<(String, String)>)> {
+    let mut consumed = 0;
+    let mut line = String::new();
+    let mut next_line = |reader: &mut dyn BufRead| -> io::Result<String> {
+        line.clear();
+        let n = reader.read_line(&mut line)?;
+        consumed += n;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if consumed > MAX_HEAD {
+            return Err(invalid("message head too large"));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    };
+
+    let first_line = next_line(reader)?;
+    let mut headers = Vec::new();
+    loop {
+        let line = next_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    Ok((first_line, headers))
+}
+
+/// Parsed `Content-Length` header, if present
+fn content_length(headers: &[(String, String)]) -> io::Result<Option<usize>> {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("Content-Length"))
+        .map(|(_, length)| {
+            length
+                .parse()
+                .map_err(|_| invalid("invalid Content-Length"))
+        })
+        .transpose()
+}
+
+/// Read exactly `length` body bytes
+pub(crate) fn read_body(reader: &mut impl Read, length: usize) -> io::Result<Vec<u8>> {
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+/// Write a complete response and flush it
+pub(crate) fn write_response(
+    writer: &mut impl Write,
+    status: u16,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> io::Result<()> {
+    let mut response = Vec::with_capacity(128 + body.len());
+    write!(response, "HTTP/1.1 {status} {}\r\n", reason(status))?;
+    for (name, value) in headers {
+        write!(response, "{name}: {value}\r\n")?;
+    }
+    write!(
+        response,
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    response.extend_from_slice(body);
+    writer.write_all(&response)?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_read_head_and_body() {
+        let raw = b"POST /invoke HTTP/1.1\r\nHost: x\r\ncontent-length: 2\r\n\r\n{}";
+        let mut reader = BufReader::new(&raw[..]);
+        let head = read_head(&mut reader).unwrap();
+        assert_eq!(head.method, "POST");
+        assert_eq!(head.path, "/invoke");
+        assert_eq!(head.header("HOST"), Some("x"));
+        assert_eq!(head.content_length, 2);
+        assert_eq!(read_body(&mut reader, head.content_length).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn test_read_head_bare_lf() {
+        let raw = b"GET /next HTTP/1.0\nUser-Agent: curl\n\n";
+        let head = read_head(&mut BufReader::new(&raw[..])).unwrap();
+        assert_eq!(head.path, "/next");
+        assert_eq!(head.header("user-agent"), Some("curl"));
+        assert_eq!(head.content_length, 0);
+    }
+
+    #[test]
+    fn test_read_head_rejects_garbage() {
+        for raw in [
+            &b"GET\r\n\r\n"[..],
+            b"GET / SPDY/3\r\n\r\n",
+            b"GET / HTTP/1.1\r\nno-colon\r\n\r\n",
+            b"GET / HTTP/1.1\r\nContent-Length: x\r\n\r\n",
+            b"GET / HTTP/1.1\r\n",
+        ] {
+            assert!(read_head(&mut BufReader::new(raw)).is_err(), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn test_read_response_head() {
+        let raw = b"HTTP/1.1 202 Accepted\r\nContent-Length: 15\r\n\r\n{\"status\":\"OK\"}";
+        let mut reader = BufReader::new(&raw[..]);
+        let head = read_response_head(&mut reader).unwrap();
+        assert_eq!(head.status, 202);
+        assert_eq!(head.content_length, Some(15));
+        assert_eq!(read_body(&mut reader, 15).unwrap(), b"{\"status\":\"OK\"}");
+
+        let head = read_response_head(&mut BufReader::new(&b"HTTP/1.0 200\n\n"[..])).unwrap();
+        assert_eq!((head.status, head.content_length), (200, None));
+        for raw in [&b"HTTP/1.1 OK\r\n\r\n"[..], b"SPDY/3 200 OK\r\n\r\n"] {
+            assert!(
+                read_response_head(&mut BufReader::new(raw)).is_err(),
+                "{raw:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        write_response(
+            &mut out,
+            202,
+            &[("Content-Type", "application/json")],
+            b"{}",
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 202 Accepted\r\nContent-Type: application/json\r\n\
+             Content-Length: 2\r\nConnection: close\r\n\r\n{}"
+        );
+    }
+}
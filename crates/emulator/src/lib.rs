@@ -0,0 +1,55 @@
+// Ruchy Lambda Emulator
+//
+// A deterministic, fully offline stand-in for the Lambda service:
+// - serves the Runtime API (/2018-06-01/runtime/...) to a bootstrap
+// - accepts invokes on POST /invoke (and the RIE path
+//   /2015-03-31/functions/function/invocations) from curl or tests
+// - queues events, enforces the function timeout and payload limits
+// - records REPORT-style metrics for every invocation
+// - optionally supervises the bootstrap process, resetting it on timeouts and
+//   killing it when its RSS exceeds the memory size, like the Lambda sandbox
+// - replays built-in event fixtures and recorded events against expectations
+// - records the Runtime API traffic of a bootstrap talking to a real or
+//   emulated Runtime API, in the replay format
+// - optionally fronts the function with an API Gateway (HTTP API) listener,
+//   so `curl localhost:3000/path` reaches HTTP handlers as payload 2.0 events
+
+#![warn(missing_docs, clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+//! Local Lambda emulator for developing handlers without AWS
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use ruchy_lambda_emulator::{Emulator, EmulatorConfig};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let emulator = Emulator::start(EmulatorConfig::default())?;
+//! // Start a bootstrap with emulator.runtime_env(), then:
+//! let invocation = emulator.invoke(br#"{"hello":"world"}"#)?;
+//! println!("{:?}\n{}", invocation.outcome, invocation.report);
+//! # Ok(())
+//! # }
+//! ```
+
+mod config;
+mod emulator;
+mod fixtures;
+mod gateway;
+mod http;
+mod record;
+mod replay;
+mod report;
+mod sandbox;
+
+pub use config::{
+    EmulatorConfig, DEFAULT_ADDR, DEFAULT_GATEWAY_ADDR, DEFAULT_MEMORY_MB, DEFAULT_TIMEOUT,
+    MAX_SYNC_PAYLOAD,
+};
+pub use emulator::{Emulator, Invocation, InvokeError};
+pub use fixtures::{fixture, FIXTURES};
+pub use record::{load_exchanges, Exchange, Recorder, EXCHANGES_FILE};
+pub use replay::{bless, load_dir, replay, ReplayEvent, ReplayResult};
+pub use report::{Outcome, Report};
+pub use sandbox::Sandbox;
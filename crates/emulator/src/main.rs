@@ -0,0 +1,211 @@
+// Ruchy Lambda Emulator CLI
+//
+// Usage:
+//   cargo run -p ruchy-lambda-emulator -- --bootstrap target/release/bootstrap
+//   curl -d '{"hello":"world"}' http://127.0.0.1:9001/invoke
+//
+//   cargo run -p ruchy-lambda-emulator -- --bootstrap target/release/bootstrap --gateway
+//   curl -i http://127.0.0.1:3000/users/42?verbose=1     (API Gateway payload 2.0)
+//
+//   cargo run -p ruchy-lambda-emulator -- --bootstrap target/release/bootstrap --fixture sqs
+//   cargo run -p ruchy-lambda-emulator -- --bootstrap target/release/bootstrap --replay events/
+//   (replays and exits; non-zero if any event fails or misses its expectation)
+//
+//   cargo run -p ruchy-lambda-emulator -- --timeout 10 --memory 512
+//   AWS_LAMBDA_RUNTIME_API=127.0.0.1:9001 target/release/bootstrap
+//
+//   cargo run -p ruchy-lambda-emulator -- --record rec/ --upstream 127.0.0.1:9001 --addr 127.0.0.1:9002
+//   AWS_LAMBDA_RUNTIME_API=127.0.0.1:9002 target/release/bootstrap
+//   (records the bootstrap's traffic with a real or emulated Runtime API;
+//   then --replay rec/ re-runs the recorded events)
+
+use clap::Parser;
+use ruchy_lambda_emulator::{
+    bless, load_dir, replay, Emulator, EmulatorConfig, Recorder, ReplayEvent, Sandbox,
+    DEFAULT_ADDR, DEFAULT_GATEWAY_ADDR, DEFAULT_MEMORY_MB, FIXTURES, MAX_SYNC_PAYLOAD,
+};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use std::time::Duration;
+
+/// Run a local Lambda emulator serving the Runtime API and an invoke endpoint
+#[derive(Parser)]
+#[command(name = "ruchy-lambda-emulator")]
+#[command(about = "Deterministic local Lambda emulator: Runtime API, POST /invoke, REPORT metrics")]
+struct Cli {
+    /// Listen address for the Runtime API and invoke endpoint
+    #[arg(long, default_value = DEFAULT_ADDR)]
+    addr: SocketAddr,
+
+    /// Also serve an API Gateway (HTTP API) front door [default: 127.0.0.1:3000]
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = DEFAULT_GATEWAY_ADDR)]
+    gateway: Option<SocketAddr>,
+
+    /// Function timeout in seconds
+    #[arg(long, default_value_t = 3.0)]
+    timeout: f64,
+
+    /// Memory size in MB (the --bootstrap process is killed above it)
+    #[arg(long, default_value_t = DEFAULT_MEMORY_MB)]
+    memory: u32,
+
+    /// Request and response payload limit in bytes
+    #[arg(long, default_value_t = MAX_SYNC_PAYLOAD)]
+    max_payload: usize,
+
+    /// Function name
+    #[arg(long, default_value = "function")]
+    function_name: String,
+
+    /// Bootstrap to run and supervise (otherwise start one yourself)
+    #[arg(long)]
+    bootstrap: Option<PathBuf>,
+
+    /// Invoke a built-in event fixture, then exit (repeatable; see --list-fixtures)
+    #[arg(long = "fixture", value_name = "NAME")]
+    fixtures: Vec<String>,
+
+    /// Replay <name>.json events from a directory, checking <name>.expected.json, then exit
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// With --replay: write the responses as the new expectations
+    #[arg(long, requires = "replay")]
+    bless: bool,
+
+    /// List the built-in event fixtures
+    #[arg(long)]
+    list_fixtures: bool,
+
+    /// Instead of emulating, listen on --addr, forward to --upstream and
+    /// record the traffic into DIR (replayable with --replay DIR)
+    #[arg(
+        long,
+        value_name = "DIR",
+        requires = "upstream",
+        conflicts_with_all = ["gateway", "bootstrap", "fixtures", "replay"]
+    )]
+    record: Option<PathBuf>,
+
+    /// With --record: the Runtime API to forward to (host:port)
+    #[arg(long, value_name = "ADDR", requires = "record")]
+    upstream: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.list_fixtures {
+        for (name, _) in FIXTURES {
+            println!("{name}");
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    if let (Some(dir), Some(upstream)) = (&cli.record, &cli.upstream) {
+        let _recorder = match Recorder::start(cli.addr, upstream.as_str(), dir) {
+            Ok(recorder) => {
+                eprintln!(
+                    "[EMULATOR] Recording into {}: AWS_LAMBDA_RUNTIME_API={} -> {upstream}",
+                    dir.display(),
+                    recorder.addr()
+                );
+                recorder
+            }
+            Err(e) => {
+                eprintln!("[EMULATOR] cannot record on {}: {e}", cli.addr);
+                return ExitCode::FAILURE;
+            }
+        };
+        loop {
+            std::thread::park();
+        }
+    }
+
+    let mut events = Vec::new();
+    for name in &cli.fixtures {
+        let Some(event) = ReplayEvent::fixture(name) else {
+            eprintln!("[EMULATOR] unknown fixture {name:?} (see --list-fixtures)");
+            return ExitCode::FAILURE;
+        };
+        events.push(event);
+    }
+    if let Some(dir) = &cli.replay {
+        match load_dir(dir) {
+            Ok(loaded) => events.extend(loaded),
+            Err(e) => {
+                eprintln!("[EMULATOR] cannot read {}: {e}", dir.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let Ok(timeout) = Duration::try_from_secs_f64(cli.timeout) else {
+        eprintln!("[EMULATOR] invalid --timeout {}", cli.timeout);
+        return ExitCode::FAILURE;
+    };
+    let mut config = EmulatorConfig::default()
+        .with_addr(cli.addr)
+        .with_function_name(cli.function_name)
+        .with_memory_size_mb(cli.memory)
+        .with_timeout(timeout)
+        .with_payload_limits(cli.max_payload, cli.max_payload)
+        .with_print_reports(true);
+    if let Some(gateway) = cli.gateway {
+        config = config.with_gateway_addr(gateway);
+    }
+
+    let emulator = match Emulator::start(config) {
+        Ok(emulator) => emulator,
+        Err(e) => {
+            eprintln!("[EMULATOR] cannot listen on {}: {e}", cli.addr);
+            return ExitCode::FAILURE;
+        }
+    };
+    let addr = emulator.addr();
+    eprintln!("[EMULATOR] Runtime API: AWS_LAMBDA_RUNTIME_API={addr}");
+    eprintln!("[EMULATOR] Invoke: curl -d '{{}}' http://{addr}/invoke");
+    if let Some(gateway) = emulator.gateway_addr() {
+        eprintln!("[EMULATOR] API Gateway: curl http://{gateway}/");
+    }
+
+    // Supervised: reset on timeout, killed over --memory, restarted on exit
+    let _sandbox = match cli.bootstrap {
+        Some(bootstrap) => match Sandbox::spawn(&emulator, Command::new(&bootstrap)) {
+            Ok(sandbox) => Some(sandbox),
+            Err(e) => {
+                eprintln!("[EMULATOR] cannot run {}: {e}", bootstrap.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    if cli.fixtures.is_empty() && cli.replay.is_none() {
+        loop {
+            std::thread::park();
+        }
+    }
+
+    let results = replay(&emulator, &events);
+    for result in &results {
+        println!("{result}");
+    }
+    let failed = results.iter().filter(|result| !result.passed()).count();
+    println!("{} events, {failed} failed", results.len());
+
+    if cli.bless {
+        match bless(&events, &results) {
+            Ok(written) => println!("wrote {written} expectations"),
+            Err(e) => {
+                eprintln!("[EMULATOR] cannot write expectations: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
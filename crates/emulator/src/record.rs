@@ -0,0 +1,517 @@
+// Record Runtime API traffic
+//
+// A `Recorder` sits between a bootstrap and a real or emulated Runtime API:
+// the bootstrap's AWS_LAMBDA_RUNTIME_API points at the recorder, which
+// forwards every request upstream, relays the answer and writes both to a
+// recording directory:
+//
+//   recording/
+//     exchanges.jsonl                        every request/response, in order
+//     0001-<request id>.json                 each event served by /next
+//     0001-<request id>.expected.json        what the function answered
+//
+// The event files are the replay format (see replay.rs), so
+// `--replay recording/` re-runs the captured invocations against a local
+// build and checks it still answers the same. exchanges.jsonl keeps the
+// full traffic (headers, statuses, timings) for `load_exchanges`.
+
+use crate::emulator::Reply;
+use crate::http::{self, Head};
+use ruchy_lambda_simd::base64;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Name of the exchange log inside a recording directory
+pub const EXCHANGES_FILE: &str = "exchanges.jsonl";
+
+/// One request relayed upstream and the response it got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exchange {
+    /// Request method
+    pub method: String,
+    /// Request target
+    pub path: String,
+    /// Request headers as received from the bootstrap
+    pub request_headers: Vec<(String, String)>,
+    /// Request body
+    pub request_body: Vec<u8>,
+    /// Status relayed to the bootstrap (502 if upstream failed)
+    pub status: u16,
+    /// Response headers as received from upstream
+    pub response_headers: Vec<(String, String)>,
+    /// Response body
+    pub response_body: Vec<u8>,
+    /// When the request arrived, in Unix epoch milliseconds
+    pub started_ms: u64,
+    /// How long upstream took to answer
+    pub duration: Duration,
+}
+
+impl Exchange {
+    /// Header of the response, case-insensitively
+    #[must_use]
+    pub fn response_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.response_headers, name)
+    }
+
+    /// One line of `exchanges.jsonl`
+    ///
+    /// Bodies are stored as `body` when UTF-8, as `body_base64` otherwise.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut request = serde_json::json!({
+            "method": self.method,
+            "path": self.path,
+            "headers": self.request_headers,
+        });
+        put_body(&mut request, &self.request_body);
+        let mut response = serde_json::json!({
+            "status": self.status,
+            "headers": self.response_headers,
+        });
+        put_body(&mut response, &self.response_body);
+        serde_json::json!({
+            "started_ms": self.started_ms,
+            "duration_us": u64::try_from(self.duration.as_micros()).unwrap_or(u64::MAX),
+            "request": request,
+            "response": response,
+        })
+    }
+
+    /// Parse a line written by [`Exchange::to_json`]
+    #[must_use]
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let request = &value["request"];
+        let response = &value["response"];
+        Some(Self {
+            method: request["method"].as_str()?.to_string(),
+            path: request["path"].as_str()?.to_string(),
+            request_headers: serde_json::from_value(request["headers"].clone()).ok()?,
+            request_body: get_body(request)?,
+            status: u16::try_from(response["status"].as_u64()?).ok()?,
+            response_headers: serde_json::from_value(response["headers"].clone()).ok()?,
+            response_body: get_body(response)?,
+            started_ms: value["started_ms"].as_u64()?,
+            duration: Duration::from_micros(value["duration_us"].as_u64()?),
+        })
+    }
+}
+
+fn put_body(message: &mut serde_json::Value, body: &[u8]) {
+    match std::str::from_utf8(body) {
+        Ok(text) => message["body"] = text.into(),
+        Err(_) => message["body_base64"] = base64::encode(body).into(),
+    }
+}
+
+fn get_body(message: &serde_json::Value) -> Option<Vec<u8>> {
+    match (message["body"].as_str(), message["body_base64"].as_str()) {
+        (Some(text), _) => Some(text.as_bytes().to_vec()),
+        (None, Some(encoded)) => base64::decode(encoded.as_bytes()).ok(),
+        (None, None) => None,
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// Read every exchange from a recording's `exchanges.jsonl`
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or a line is not an exchange.
+pub fn load_exchanges(path: &Path) -> io::Result<Vec<Exchange>> {
+    let mut exchanges = Vec::new();
+    for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let exchange = serde_json::from_str(&line)
+            .ok()
+            .as_ref()
+            .and_then(Exchange::from_json)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{}: not an exchange", path.display(), number + 1),
+                )
+            })?;
+        exchanges.push(exchange);
+    }
+    Ok(exchanges)
+}
+
+/// Recording proxy in front of a Runtime API; stops when dropped
+///
+/// # Examples
+///
+/// ```no_run
+/// use ruchy_lambda_emulator::Recorder;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let recorder = Recorder::start("127.0.0.1:9002".parse().unwrap(), "127.0.0.1:9001", "recording")?;
+/// // Run the bootstrap with AWS_LAMBDA_RUNTIME_API=127.0.0.1:9002, then
+/// // replay with `ruchy-lambda-emulator --bootstrap ... --replay recording`
+/// # drop(recorder);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Recorder {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    upstream: String,
+    dir: PathBuf,
+    state: Mutex<State>,
+    shutdown: AtomicBool,
+}
+
+#[derive(Debug)]
+struct State {
+    log: File,
+    exchanges: usize,
+    invocations: usize,
+    /// File stem of each served event not answered yet, by request ID
+    pending: HashMap<String, String>,
+}
+
+impl Recorder {
+    /// Listen on `addr`, forward to `upstream` (`host:port`) and record into
+    /// `dir` (created if missing; an existing recording is added to)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `addr` cannot be bound or `dir` cannot be written.
+    pub fn start(
+        addr: SocketAddr,
+        upstream: impl Into<String>,
+        dir: impl Into<PathBuf>,
+    ) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        let invocations = crate::replay::load_dir(&dir)?.len();
+        let log = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(EXCHANGES_FILE))?;
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            upstream: upstream.into(),
+            dir,
+            state: Mutex::new(State {
+                log,
+                exchanges: 0,
+                invocations,
+                pending: HashMap::new(),
+            }),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name("recorder-accept".to_string())
+                .spawn(move || accept_loop(&shared, &listener))?
+        };
+        Ok(Self {
+            addr,
+            shared,
+            acceptor: Some(acceptor),
+        })
+    }
+
+    /// Address to point `AWS_LAMBDA_RUNTIME_API` at
+    #[must_use]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Recording directory
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.shared.dir
+    }
+
+    /// Exchanges recorded so far
+    #[must_use]
+    pub fn exchanges(&self) -> usize {
+        lock(&self.shared).exchanges
+    }
+
+    /// Stop accepting connections
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        // Wake the acceptor so it sees the flag
+        let _ = TcpStream::connect(self.addr);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn lock(shared: &Shared) -> MutexGuard<'_, State> {
+    shared
+        .state
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn accept_loop(shared: &Arc<Shared>, listener: &TcpListener) {
+    for stream in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        let shared = Arc::clone(shared);
+        let _ = thread::Builder::new()
+            .name("recorder-conn".to_string())
+            .spawn(move || {
+                if let Err(e) = relay(&shared, stream) {
+                    if e.kind() != io::ErrorKind::UnexpectedEof {
+                        eprintln!("[RECORDER] connection error: {e}");
+                    }
+                }
+            });
+    }
+}
+
+/// Forward one request upstream, answer the bootstrap and record the exchange
+fn relay(shared: &Shared, mut stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let head = http::read_head(&mut reader)?;
+    let request_body = http::read_body(&mut reader, head.content_length)?;
+
+    let started_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| {
+            u64::try_from(since.as_millis()).unwrap_or(u64::MAX)
+        });
+    let started = Instant::now();
+    let (status, response_headers, response_body) =
+        match forward(&shared.upstream, &head, &request_body) {
+            Ok(response) => response,
+            Err(e) => {
+                eprintln!("[RECORDER] upstream {} failed: {e}", shared.upstream);
+                let body = serde_json::json!({
+                    "errorType": "Recorder.UpstreamError",
+                    "errorMessage": e.to_string(),
+                });
+                (502, Vec::new(), body.to_string().into_bytes())
+            }
+        };
+    let duration = started.elapsed();
+
+    let exchange = Exchange {
+        method: head.method,
+        path: head.path,
+        request_headers: head.headers,
+        request_body,
+        status,
+        response_headers,
+        response_body,
+        started_ms,
+        duration,
+    };
+    // Recorded before the bootstrap sees the answer, so a recording is
+    // complete as soon as the bootstrap moves on
+    if let Err(e) = record(shared, &exchange) {
+        eprintln!(
+            "[RECORDER] cannot record into {}: {e}",
+            shared.dir.display()
+        );
+    }
+
+    // Content-Length and Connection are rewritten by write_response
+    let headers: Vec<(&str, &str)> = exchange
+        .response_headers
+        .iter()
+        .filter(|(name, _)| {
+            !name.eq_ignore_ascii_case("Content-Length") && !name.eq_ignore_ascii_case("Connection")
+        })
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    http::write_response(&mut stream, status, &headers, &exchange.response_body)
+}
+
+/// Send the request to `upstream` and read the whole response
+fn forward(upstream: &str, head: &Head, body: &[u8]) -> io::Result<Reply> {
+    use std::fmt::Write as _;
+
+    let mut upstream_stream = TcpStream::connect(upstream)?;
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {upstream}\r\n",
+        head.method, head.path
+    );
+    for (name, value) in &head.headers {
+        if !name.eq_ignore_ascii_case("Host") && !name.eq_ignore_ascii_case("Connection") {
+            let _ = write!(request, "{name}: {value}\r\n");
+        }
+    }
+    request.push_str("Connection: close\r\n\r\n");
+    let mut request = request.into_bytes();
+    request.extend_from_slice(body);
+    upstream_stream.write_all(&request)?;
+    upstream_stream.flush()?;
+
+    let mut reader = BufReader::new(upstream_stream);
+    let response = http::read_response_head(&mut reader)?;
+    let body = if let Some(length) = response.content_length {
+        http::read_body(&mut reader, length)?
+    } else {
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        body
+    };
+    Ok((response.status, response.headers, body))
+}
+
+/// Append `exchange` to the log and write any event or expectation it carries
+fn record(shared: &Shared, exchange: &Exchange) -> io::Result<()> {
+    let mut state = lock(shared);
+    writeln!(state.log, "{}", exchange.to_json())?;
+    state.log.flush()?;
+    state.exchanges += 1;
+
+    if !(200..300).contains(&exchange.status) {
+        return Ok(());
+    }
+    let path = exchange.path.split('?').next().unwrap_or_default();
+    let Some(rest) = path.strip_prefix("/2018-06-01/runtime/invocation/") else {
+        return Ok(());
+    };
+    match (exchange.method.as_str(), rest) {
+        ("GET", "next") => {
+            let request_id = exchange
+                .response_header("Lambda-Runtime-Aws-Request-Id")
+                .unwrap_or("unknown");
+            state.invocations += 1;
+            let stem = format!("{:04}-{}", state.invocations, file_safe(request_id));
+            fs::write(
+                shared.dir.join(format!("{stem}.json")),
+                &exchange.response_body,
+            )?;
+            state.pending.insert(request_id.to_string(), stem);
+        }
+        ("POST", rest) => {
+            let Some((request_id, "response" | "error")) = rest.rsplit_once('/') else {
+                return Ok(());
+            };
+            if let Some(stem) = state.pending.remove(request_id) {
+                let mut expected = exchange.request_body.clone();
+                expected.push(b'\n');
+                fs::write(shared.dir.join(format!("{stem}.expected.json")), expected)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// `request_id` with anything but ASCII alphanumerics, `-` and `_` replaced
+fn file_safe(request_id: &str) -> String {
+    request_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(response_body: &[u8]) -> Exchange {
+        Exchange {
+            method: "GET".to_string(),
+            path: "/2018-06-01/runtime/invocation/next".to_string(),
+            request_headers: vec![("Host".to_string(), "127.0.0.1:9001".to_string())],
+            request_body: Vec::new(),
+            status: 200,
+            response_headers: vec![(
+                "Lambda-Runtime-Aws-Request-Id".to_string(),
+                "req-1".to_string(),
+            )],
+            response_body: response_body.to_vec(),
+            started_ms: 1_700_000_000_000,
+            duration: Duration::from_micros(1_250),
+        }
+    }
+
+    #[test]
+    fn test_exchange_json_round_trip() {
+        for body in [&b"{\"n\":1}"[..], &[0xff, 0x00, 0xfe]] {
+            let exchange = exchange(body);
+            let json = exchange.to_json();
+            assert_eq!(json["response"].get("body").is_some(), body[0] == b'{');
+            assert_eq!(Exchange::from_json(&json), Some(exchange));
+        }
+        assert_eq!(Exchange::from_json(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_load_exchanges() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(EXCHANGES_FILE);
+        fs::write(
+            &path,
+            format!(
+                "{}\n\n{}\n",
+                exchange(b"1").to_json(),
+                exchange(b"2").to_json()
+            ),
+        )
+        .unwrap();
+        let exchanges = load_exchanges(&path).unwrap();
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(
+            exchanges[1].response_header("lambda-runtime-aws-request-id"),
+            Some("req-1")
+        );
+
+        fs::write(&path, "{\"not\":\"an exchange\"}\n").unwrap();
+        let error = load_exchanges(&path).unwrap_err();
+        assert!(
+            error.to_string().ends_with(":1: not an exchange"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_file_safe() {
+        assert_eq!(file_safe("8476a536-e9f4_11e8"), "8476a536-e9f4_11e8");
+        assert_eq!(file_safe("../etc/passwd"), "___etc_passwd");
+    }
+}
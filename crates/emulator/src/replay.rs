@@ -0,0 +1,234 @@
+// Replay recorded events through a handler
+//
+// A replay directory holds one event per `<name>.json` (e.g. copied from
+// production logs) and, optionally, the response it should produce in
+// `<name>.expected.json`:
+//
+//   events/
+//     0001-order-created.json
+//     0001-order-created.expected.json
+//     0002-malformed.json
+//
+// Events run in file name order. With an expectation the response must match
+// it (as JSON when both parse, otherwise byte for byte); without one the
+// invocation must simply succeed. `bless` writes the current responses as the
+// new expectations.
+
+use crate::emulator::{Emulator, Invocation, InvokeError};
+use crate::fixtures;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Suffix of expected-response files
+const EXPECTED_SUFFIX: &str = ".expected.json";
+
+/// One event to replay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayEvent {
+    /// File stem or `fixture:<name>`
+    pub name: String,
+    /// Event payload
+    pub payload: Vec<u8>,
+    /// Expected response, if recorded
+    pub expected: Option<String>,
+    /// Where `bless` writes the expected response (directory events only)
+    pub expected_path: Option<PathBuf>,
+}
+
+impl ReplayEvent {
+    /// A built-in fixture (see [`FIXTURES`](crate::FIXTURES))
+    #[must_use]
+    pub fn fixture(name: &str) -> Option<Self> {
+        fixtures::fixture(name).map(|event| Self {
+            name: format!("fixture:{name}"),
+            payload: event.as_bytes().to_vec(),
+            expected: None,
+            expected_path: None,
+        })
+    }
+}
+
+/// Load every `<name>.json` event in `dir`, sorted by file name
+///
+/// # Errors
+///
+/// Returns an error if the directory or an event file cannot be read.
+pub fn load_dir(dir: &Path) -> io::Result<Vec<ReplayEvent>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.sort();
+
+    let mut events = Vec::new();
+    for path in paths {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if file_name.ends_with(EXPECTED_SUFFIX) {
+            continue;
+        }
+        let Some(name) = file_name.strip_suffix(".json") else {
+            continue;
+        };
+
+        let expected_path = dir.join(format!("{name}{EXPECTED_SUFFIX}"));
+        let expected = match fs::read_to_string(&expected_path) {
+            Ok(expected) => Some(expected),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        events.push(ReplayEvent {
+            name: name.to_string(),
+            payload: fs::read(&path)?,
+            expected,
+            expected_path: Some(expected_path),
+        });
+    }
+    Ok(events)
+}
+
+/// Outcome of replaying one event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayResult {
+    /// Event name
+    pub name: String,
+    /// The invocation, or why it was rejected
+    pub invocation: Result<Invocation, InvokeError>,
+    /// Response body as the invoker saw it
+    pub response: String,
+    /// Expected response, if recorded
+    pub expected: Option<String>,
+}
+
+impl ReplayResult {
+    /// Whether the event produced the expected (or any successful) response
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        match (&self.invocation, &self.expected) {
+            (Ok(_), Some(expected)) => same_response(expected, &self.response),
+            (Ok(invocation), None) => !invocation.outcome.is_error(),
+            (Err(_), _) => false,
+        }
+    }
+}
+
+impl fmt::Display for ReplayResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.passed() { "PASS" } else { "FAIL" };
+        match &self.invocation {
+            Ok(invocation) => write!(
+                f,
+                "{status} {} ({:.2} ms)",
+                self.name,
+                invocation.report.duration.as_secs_f64() * 1000.0
+            )?,
+            Err(e) => return write!(f, "{status} {}: {e}", self.name),
+        }
+        if self.passed() {
+            return Ok(());
+        }
+        match &self.expected {
+            Some(expected) => write!(
+                f,
+                "\n  expected: {}\n  actual:   {}",
+                expected.trim(),
+                self.response
+            ),
+            None => write!(f, "\n  response: {}", self.response),
+        }
+    }
+}
+
+/// Invoke each event in order
+#[must_use]
+pub fn replay(emulator: &Emulator, events: &[ReplayEvent]) -> Vec<ReplayResult> {
+    let timeout = emulator.config().timeout;
+    events
+        .iter()
+        .map(|event| {
+            let invocation = emulator.invoke(&event.payload);
+            let response = invocation
+                .as_ref()
+                .map(|invocation| invocation.outcome.body(timeout))
+                .unwrap_or_default();
+            ReplayResult {
+                name: event.name.clone(),
+                invocation,
+                response,
+                expected: event.expected.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Write each result's response as its event's expectation
+///
+/// Only directory events with a completed invocation are written.
+///
+/// # Errors
+///
+/// Returns an error if an expectation file cannot be written.
+pub fn bless(events: &[ReplayEvent], results: &[ReplayResult]) -> io::Result<usize> {
+    let mut written = 0;
+    for (event, result) in events.iter().zip(results) {
+        if let (Some(path), Ok(_)) = (&event.expected_path, &result.invocation) {
+            fs::write(path, format!("{}\n", result.response))?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// JSON-aware response comparison (formatting and key order don't matter)
+fn same_response(expected: &str, actual: &str) -> bool {
+    match (
+        serde_json::from_str::<serde_json::Value>(expected),
+        serde_json::from_str::<serde_json::Value>(actual),
+    ) {
+        (Ok(expected), Ok(actual)) => expected == actual,
+        _ => expected.trim_end() == actual.trim_end(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("b.json"), "{\"n\":2}").unwrap();
+        fs::write(dir.path().join("a.json"), "{\"n\":1}").unwrap();
+        fs::write(dir.path().join("a.expected.json"), "{\"ok\":1}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let events = load_dir(dir.path()).unwrap();
+        let names: Vec<&str> = events.iter().map(|event| event.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(events[0].payload, b"{\"n\":1}");
+        assert_eq!(events[0].expected.as_deref(), Some("{\"ok\":1}"));
+        assert_eq!(events[1].expected, None);
+        assert_eq!(
+            events[1].expected_path.as_deref(),
+            Some(dir.path().join("b.expected.json").as_path())
+        );
+    }
+
+    #[test]
+    fn test_fixture_event() {
+        let event = ReplayEvent::fixture("s3").unwrap();
+        assert_eq!(event.name, "fixture:s3");
+        assert!(event.expected_path.is_none());
+        assert!(ReplayEvent::fixture("nope").is_none());
+    }
+
+    #[test]
+    fn test_same_response() {
+        assert!(same_response("{\"a\": 1, \"b\": 2}\n", "{\"b\":2,\"a\":1}"));
+        assert!(!same_response("{\"a\":1}", "{\"a\":2}"));
+        assert!(same_response("plain text\n", "plain text"));
+        assert!(!same_response("plain", "other"));
+    }
+}
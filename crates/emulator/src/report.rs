@@ -0,0 +1,225 @@
+// Invocation outcomes and REPORT metrics
+//
+// Report renders like the line the Lambda service logs after every
+// invocation:
+//
+//   REPORT RequestId: <id>  Duration: 1.23 ms  Billed Duration: 2 ms  Memory Size: 128 MB
+//     Max Memory Used: 18 MB  Status: error  Error Type: Runtime.OutOfMemory
+//
+// Max Memory Used is only known when the emulator supervises the bootstrap
+// process (see sandbox.rs).
+
+use std::fmt;
+use std::time::Duration;
+
+/// How an invocation ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The runtime posted a response
+    Success(String),
+    /// The runtime posted an error document
+    Error {
+        /// `Lambda-Runtime-Function-Error-Type` header, if sent
+        error_type: Option<String>,
+        /// Error document as posted
+        body: String,
+    },
+    /// No response before the function timeout
+    Timeout,
+    /// The response exceeded the payload limit
+    ResponseTooLarge {
+        /// Size of the rejected response (bytes)
+        size: usize,
+    },
+    /// The runtime process went over the memory limit and was killed
+    OutOfMemory,
+    /// The runtime process exited mid-invocation
+    RuntimeExit {
+        /// Exit status as printed (e.g. "exit status: 1")
+        status: String,
+    },
+}
+
+impl Outcome {
+    /// Whether the invoker sees a function error (`X-Amz-Function-Error`)
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Success(_))
+    }
+
+    /// Body returned to the invoker
+    ///
+    /// Errors raised by the emulator itself use the Lambda service's error
+    /// document shape.
+    #[must_use]
+    pub fn body(&self, timeout: Duration) -> String {
+        match self {
+            Self::Success(body) | Self::Error { body, .. } => body.clone(),
+            Self::Timeout => serde_json::json!({
+                "errorType": "Sandbox.Timedout",
+                "errorMessage": format!("Task timed out after {:.2} seconds", timeout.as_secs_f64()),
+            })
+            .to_string(),
+            Self::ResponseTooLarge { size } => serde_json::json!({
+                "errorType": "Function.ResponseSizeTooLarge",
+                "errorMessage": format!("Response payload size ({size} bytes) exceeded maximum allowed payload size"),
+            })
+            .to_string(),
+            Self::OutOfMemory => serde_json::json!({
+                "errorType": "Runtime.OutOfMemory",
+                "errorMessage": "Runtime exited with error: signal: killed",
+            })
+            .to_string(),
+            Self::RuntimeExit { status } => serde_json::json!({
+                "errorType": "Runtime.ExitError",
+                "errorMessage": format!("Runtime exited with error: {status}"),
+            })
+            .to_string(),
+        }
+    }
+
+    /// `Error Type` shown in the REPORT line for runtime failures
+    #[must_use]
+    pub fn report_error_type(&self) -> Option<&'static str> {
+        match self {
+            Self::OutOfMemory => Some("Runtime.OutOfMemory"),
+            Self::RuntimeExit { .. } => Some("Runtime.ExitError"),
+            _ => None,
+        }
+    }
+}
+
+/// Metrics for one invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Request ID
+    pub request_id: String,
+    /// Time from accepting the invoke to the runtime's response
+    pub duration: Duration,
+    /// Duration rounded up to the next millisecond
+    pub billed_duration: Duration,
+    /// Configured memory size (MB)
+    pub memory_size_mb: u32,
+    /// Whether the invocation timed out
+    pub timed_out: bool,
+    /// Peak RSS of the runtime process (MB), when supervised
+    pub max_memory_used_mb: Option<u64>,
+    /// Runtime failure (`Runtime.OutOfMemory`, `Runtime.ExitError`)
+    pub error_type: Option<&'static str>,
+}
+
+impl Report {
+    /// Report for an invocation that took `duration`
+    #[must_use]
+    pub fn new(request_id: &str, duration: Duration, memory_size_mb: u32, timed_out: bool) -> Self {
+        let billed_ms = duration.as_micros().div_ceil(1000).max(1);
+        Self {
+            request_id: request_id.to_string(),
+            duration,
+            billed_duration: Duration::from_millis(u64::try_from(billed_ms).unwrap_or(u64::MAX)),
+            memory_size_mb,
+            timed_out,
+            max_memory_used_mb: None,
+            error_type: None,
+        }
+    }
+
+    /// Record the runtime process's peak RSS (MB)
+    #[must_use]
+    pub fn with_max_memory_used_mb(mut self, max_memory_used_mb: Option<u64>) -> Self {
+        self.max_memory_used_mb = max_memory_used_mb;
+        self
+    }
+
+    /// Record a runtime failure
+    #[must_use]
+    pub fn with_error_type(mut self, error_type: Option<&'static str>) -> Self {
+        self.error_type = error_type;
+        self
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "REPORT RequestId: {}\tDuration: {:.2} ms\tBilled Duration: {} ms\tMemory Size: {} MB",
+            self.request_id,
+            self.duration.as_secs_f64() * 1000.0,
+            self.billed_duration.as_millis(),
+            self.memory_size_mb
+        )?;
+        if let Some(max_memory_used_mb) = self.max_memory_used_mb {
+            write!(f, "\tMax Memory Used: {max_memory_used_mb} MB")?;
+        }
+        if self.timed_out {
+            f.write_str("\tStatus: timeout")?;
+        } else if let Some(error_type) = self.error_type {
+            write!(f, "\tStatus: error\tError Type: {error_type}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_line() {
+        let report = Report::new("req-1", Duration::from_micros(12_340), 256, false);
+        assert_eq!(report.billed_duration, Duration::from_millis(13));
+        assert_eq!(
+            report.to_string(),
+            "REPORT RequestId: req-1\tDuration: 12.34 ms\tBilled Duration: 13 ms\tMemory Size: 256 MB"
+        );
+
+        let timed_out = Report::new("req-2", Duration::from_secs(3), 128, true);
+        assert_eq!(timed_out.billed_duration, Duration::from_secs(3));
+        assert!(timed_out.to_string().ends_with("\tStatus: timeout"));
+    }
+
+    #[test]
+    fn test_report_runtime_failure() {
+        let report = Report::new("req", Duration::from_millis(5), 128, false)
+            .with_max_memory_used_mb(Some(129))
+            .with_error_type(Outcome::OutOfMemory.report_error_type());
+        assert!(report.to_string().ends_with(
+            "\tMemory Size: 128 MB\tMax Memory Used: 129 MB\tStatus: error\tError Type: Runtime.OutOfMemory"
+        ));
+    }
+
+    #[test]
+    fn test_billed_duration_minimum() {
+        let report = Report::new("req", Duration::from_micros(10), 128, false);
+        assert_eq!(report.billed_duration, Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_outcome_bodies() {
+        let timeout = Duration::from_secs(3);
+        assert_eq!(Outcome::Success("ok".to_string()).body(timeout), "ok");
+        assert!(!Outcome::Success(String::new()).is_error());
+
+        let body: serde_json::Value =
+            serde_json::from_str(&Outcome::Timeout.body(timeout)).unwrap();
+        assert_eq!(body["errorType"], "Sandbox.Timedout");
+        assert_eq!(body["errorMessage"], "Task timed out after 3.00 seconds");
+
+        let body: serde_json::Value =
+            serde_json::from_str(&Outcome::ResponseTooLarge { size: 7 }.body(timeout)).unwrap();
+        assert_eq!(body["errorType"], "Function.ResponseSizeTooLarge");
+
+        let exit = Outcome::RuntimeExit {
+            status: "exit status: 1".to_string(),
+        };
+        let body: serde_json::Value = serde_json::from_str(&exit.body(timeout)).unwrap();
+        assert_eq!(body["errorType"], "Runtime.ExitError");
+        assert_eq!(
+            body["errorMessage"],
+            "Runtime exited with error: exit status: 1"
+        );
+        assert_eq!(exit.report_error_type(), Some("Runtime.ExitError"));
+        assert_eq!(Outcome::Timeout.report_error_type(), None);
+    }
+}
@@ -0,0 +1,188 @@
+// Supervised bootstrap process
+//
+// Lambda runs the bootstrap in a sandbox it can reset at will. Sandbox
+// reproduces the failure modes that come from that:
+// - timeout: the invocation fails with Sandbox.Timedout, the process is
+//   killed and a fresh one started (the next invoke is a cold start)
+// - memory: RSS is sampled from /proc every POLL_INTERVAL; going over the
+//   configured memory size kills the process with Runtime.OutOfMemory
+// - crash: exiting mid-invocation fails it with Runtime.ExitError
+//
+// Peak RSS per process feeds "Max Memory Used" in REPORT lines. Memory
+// tracking needs /proc (Linux); elsewhere only timeouts and exits apply.
+
+use crate::emulator::{self, Emulator, Shared};
+use crate::report::Outcome;
+use std::fmt;
+use std::io;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often the process is checked for exit and memory use
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A bootstrap process run against an emulator; killed when dropped
+pub struct Sandbox {
+    stop: Arc<AtomicBool>,
+    restarts: Arc<AtomicU64>,
+    supervisor: Option<JoinHandle<()>>,
+}
+
+impl fmt::Debug for Sandbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sandbox")
+            .field("restarts", &self.restarts())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Sandbox {
+    /// Start `command` with the emulator's runtime environment and supervise it
+    ///
+    /// The command is re-run whenever the process has to be replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the first process cannot be started.
+    pub fn spawn(emulator: &Emulator, mut command: Command) -> io::Result<Self> {
+        command.envs(emulator.runtime_env());
+        let child = command.spawn()?;
+
+        let shared = Arc::clone(emulator.shared());
+        let stop = Arc::new(AtomicBool::new(false));
+        let restarts = Arc::new(AtomicU64::new(0));
+        let supervisor = {
+            let (stop, restarts) = (Arc::clone(&stop), Arc::clone(&restarts));
+            thread::Builder::new()
+                .name("emulator-sandbox".to_string())
+                .spawn(move || supervise(&shared, command, child, &stop, &restarts))?
+        };
+
+        Ok(Self {
+            stop,
+            restarts,
+            supervisor: Some(supervisor),
+        })
+    }
+
+    /// How many times the process has been replaced
+    #[must_use]
+    pub fn restarts(&self) -> u64 {
+        self.restarts.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(supervisor) = self.supervisor.take() {
+            let _ = supervisor.join();
+        }
+    }
+}
+
+fn supervise(
+    shared: &Shared,
+    mut command: Command,
+    mut child: Child,
+    stop: &AtomicBool,
+    restarts: &AtomicU64,
+) {
+    let limit_kb = u64::from(shared.config.memory_size_mb) * 1024;
+    let mut timeouts = emulator::timeouts(shared);
+    emulator::record_rss(shared, None);
+
+    while !stop.load(Ordering::SeqCst) && !emulator::is_shutting_down(shared) {
+        let failure = match child.try_wait() {
+            Ok(Some(status)) => Some(Outcome::RuntimeExit {
+                status: status.to_string(),
+            }),
+            Ok(None) => {
+                let rss_kb = rss_kb(child.id());
+                if rss_kb.is_some() {
+                    emulator::record_rss(shared, rss_kb);
+                }
+                let seen = emulator::timeouts(shared);
+                if rss_kb.is_some_and(|rss_kb| rss_kb > limit_kb) {
+                    Some(Outcome::OutOfMemory)
+                } else if seen != timeouts {
+                    // The timed-out invocation already failed; just reset
+                    timeouts = seen;
+                    Some(Outcome::Timeout)
+                } else {
+                    None
+                }
+            }
+            Err(e) => Some(Outcome::RuntimeExit {
+                status: e.to_string(),
+            }),
+        };
+
+        let Some(failure) = failure else {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+
+        let _ = child.kill();
+        let _ = child.wait();
+        if failure != Outcome::Timeout {
+            emulator::abort_in_flight(shared, &failure);
+        }
+        if shared.config.print_reports {
+            eprintln!("[EMULATOR] resetting sandbox: {failure:?}");
+        }
+
+        emulator::record_rss(shared, None);
+        timeouts = emulator::timeouts(shared);
+        child = loop {
+            match command.spawn() {
+                Ok(child) => break child,
+                Err(e) if !stop.load(Ordering::SeqCst) => {
+                    eprintln!("[EMULATOR] cannot restart bootstrap: {e}");
+                    thread::sleep(Duration::from_secs(1));
+                }
+                Err(_) => return,
+            }
+        };
+        restarts.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Resident set size of a process (KiB), from /proc/<pid>/status
+fn rss_kb(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    parse_vm_rss(&status)
+}
+
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.trim().strip_suffix("kB"))
+        .and_then(|kb| kb.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tbootstrap\nVmPeak:\t  10000 kB\nVmRSS:\t    2048 kB\nThreads:\t1\n";
+        assert_eq!(parse_vm_rss(status), Some(2048));
+        assert_eq!(parse_vm_rss("Name:\tzombie\n"), None);
+        assert_eq!(parse_vm_rss("VmRSS:\tlots\n"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_rss_of_current_process() {
+        assert!(rss_kb(std::process::id()).is_some_and(|kb| kb > 0));
+    }
+}
@@ -0,0 +1,343 @@
+// Integration tests: the ruchy runtime client against the emulator
+//
+// Each test starts an emulator on a free port, points a Runtime at it via
+// AWS_LAMBDA_RUNTIME_API (hence #[serial]) and serves invocations from a
+// background thread until the emulator shuts down.
+
+use ruchy_lambda_emulator::{
+    bless, load_dir, replay, Emulator, EmulatorConfig, InvokeError, Outcome, ReplayEvent, FIXTURES,
+};
+use ruchy_lambda_runtime::{HandlerError, HttpRequest, Router, Runtime};
+use serial_test::serial;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+fn config() -> EmulatorConfig {
+    EmulatorConfig::default().with_addr("127.0.0.1:0".parse().unwrap())
+}
+
+/// Serve invocations with `handler` until `/next` fails (emulator gone)
+fn serve<F>(emulator: &Emulator, handler: F) -> JoinHandle<()>
+where
+    F: Fn(&str) -> Result<String, HandlerError> + Send + 'static,
+{
+    std::env::set_var("AWS_LAMBDA_RUNTIME_API", emulator.addr().to_string());
+    let runtime = Runtime::new().expect("runtime");
+    thread::spawn(move || {
+        while let Ok((request_id, event)) = runtime.next_event() {
+            // Late posts after a timeout are rejected; keep serving
+            let _ = match handler(&event) {
+                Ok(response) => runtime.post_response(&request_id, &response),
+                Err(error) => runtime.post_error(&request_id, &error),
+            };
+        }
+    })
+}
+
+/// Raw HTTP POST, as curl would send it; returns (head, body)
+fn post(emulator: &Emulator, path: &str, body: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(emulator.addr()).unwrap();
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.to_string(), body.to_string())
+}
+
+/// Test: Invocations round-trip through the runtime with deterministic IDs
+#[test]
+#[serial]
+fn test_invoke_round_trip() {
+    let emulator = Emulator::start(config().with_memory_size_mb(256)).unwrap();
+    let runtime = serve(&emulator, |event| Ok(format!(r#"{{"echo":{event}}}"#)));
+
+    for i in 1..=3 {
+        let invocation = emulator.invoke(format!("{i}").as_bytes()).unwrap();
+        assert_eq!(
+            invocation.request_id,
+            format!("00000000-0000-4000-8000-{i:012}")
+        );
+        assert_eq!(
+            invocation.outcome,
+            Outcome::Success(format!(r#"{{"echo":{i}}}"#))
+        );
+        assert_eq!(invocation.report.memory_size_mb, 256);
+        assert!(!invocation.report.timed_out);
+    }
+
+    let reports = emulator.reports();
+    assert_eq!(reports.len(), 3);
+    assert!(reports[0]
+        .to_string()
+        .starts_with("REPORT RequestId: 00000000-0000-4000-8000-000000000001\tDuration: "));
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
+
+/// Test: Handler errors come back as error documents with their type
+#[test]
+#[serial]
+fn test_invoke_handler_error() {
+    let emulator = Emulator::start(config()).unwrap();
+    let runtime = serve(&emulator, |_| {
+        Err(HandlerError::new(
+            "Function.ValidationError",
+            "missing field: name",
+        ))
+    });
+
+    let invocation = emulator.invoke(b"{}").unwrap();
+    let Outcome::Error { error_type, body } = invocation.outcome else {
+        panic!("expected an error, got {:?}", invocation.outcome);
+    };
+    assert_eq!(error_type.as_deref(), Some("Function.ValidationError"));
+    assert!(body.contains("missing field: name"), "{body}");
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
+
+/// Test: A slow handler times out and the next invocation still works
+#[test]
+#[serial]
+fn test_invoke_timeout() {
+    let emulator = Emulator::start(config().with_timeout(Duration::from_millis(100))).unwrap();
+    let runtime = serve(&emulator, |event| {
+        if event == "\"slow\"" {
+            thread::sleep(Duration::from_millis(300));
+        }
+        Ok("done".to_string())
+    });
+
+    let invocation = emulator.invoke(b"\"slow\"").unwrap();
+    assert_eq!(invocation.outcome, Outcome::Timeout);
+    assert!(invocation.report.to_string().ends_with("\tStatus: timeout"));
+
+    // One runtime serves one invocation at a time: let the slow one finish
+    thread::sleep(Duration::from_millis(300));
+
+    let invocation = emulator.invoke(b"\"fast\"").unwrap();
+    assert_eq!(invocation.outcome, Outcome::Success("done".to_string()));
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
+
+/// Test: Oversized responses fail the invocation with ResponseSizeTooLarge
+#[test]
+#[serial]
+fn test_response_too_large() {
+    let emulator = Emulator::start(config().with_payload_limits(64, 16)).unwrap();
+    let runtime = serve(&emulator, |_| Ok("x".repeat(17)));
+
+    let invocation = emulator.invoke(b"{}").unwrap();
+    assert_eq!(invocation.outcome, Outcome::ResponseTooLarge { size: 17 });
+    assert!(invocation
+        .outcome
+        .body(emulator.config().timeout)
+        .contains("Function.ResponseSizeTooLarge"));
+
+    assert_eq!(
+        emulator.invoke(&[b' '; 65]),
+        Err(InvokeError::RequestTooLarge {
+            size: 65,
+            limit: 64
+        })
+    );
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
+
+/// Test: POST /invoke and the RIE path answer like the Invoke API
+#[test]
+#[serial]
+fn test_http_invoke_endpoints() {
+    let emulator = Emulator::start(config().with_payload_limits(32, 1024)).unwrap();
+    let runtime = serve(&emulator, |event| {
+        if event.contains("fail") {
+            Err(HandlerError::new("Boom", "failed"))
+        } else {
+            Ok(r#"{"ok":true}"#.to_string())
+        }
+    });
+
+    let (head, body) = post(&emulator, "/invoke", "{}");
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert!(head.contains("X-Amz-Request-Id: 00000000-0000-4000-8000-000000000001"));
+    assert!(!head.contains("X-Amz-Function-Error"), "{head}");
+    assert_eq!(body, r#"{"ok":true}"#);
+
+    let (head, body) = post(
+        &emulator,
+        "/2015-03-31/functions/function/invocations",
+        r#""fail""#,
+    );
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert!(head.contains("X-Amz-Function-Error: Unhandled"), "{head}");
+    assert!(body.contains("Boom"), "{body}");
+
+    let (head, body) = post(&emulator, "/invoke", &"x".repeat(33));
+    assert!(head.starts_with("HTTP/1.1 413"), "{head}");
+    assert!(body.contains("RequestEntityTooLargeException"), "{body}");
+
+    let (head, _) = post(&emulator, "/nope", "");
+    assert!(head.starts_with("HTTP/1.1 404"), "{head}");
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
+
+/// Test: The API Gateway front door converts requests and responses
+#[test]
+#[serial]
+fn test_gateway_front_door() {
+    let emulator =
+        Emulator::start(config().with_gateway_addr("127.0.0.1:0".parse().unwrap())).unwrap();
+    let gateway = emulator.gateway_addr().expect("gateway enabled");
+    let runtime = serve(&emulator, |event| {
+        let event: serde_json::Value = serde_json::from_str(event).unwrap();
+        Ok(serde_json::json!({
+            "statusCode": 201,
+            "headers": {"content-type": "text/plain"},
+            "cookies": ["session=1"],
+            "body": format!(
+                "{} {} id={} body={}",
+                event["requestContext"]["http"]["method"].as_str().unwrap(),
+                event["rawPath"].as_str().unwrap(),
+                event["queryStringParameters"]["id"].as_str().unwrap(),
+                event["body"].as_str().unwrap(),
+            ),
+        })
+        .to_string())
+    });
+
+    let mut stream = TcpStream::connect(gateway).unwrap();
+    write!(
+        stream,
+        "POST /users?id=42 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhello"
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 201"), "{response}");
+    assert!(
+        response.contains("content-type: text/plain\r\n"),
+        "{response}"
+    );
+    assert!(response.contains("Set-Cookie: session=1\r\n"), "{response}");
+    assert!(
+        response.ends_with("\r\n\r\nPOST /users id=42 body=hello"),
+        "{response}"
+    );
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
+
+/// Test: Init errors are recorded
+#[test]
+#[serial]
+fn test_init_error_recorded() {
+    let emulator = Emulator::start(config()).unwrap();
+    std::env::set_var("AWS_LAMBDA_RUNTIME_API", emulator.addr().to_string());
+    let runtime = Runtime::new().unwrap();
+    runtime
+        .post_init_error(&HandlerError::new("Runtime.ConfigError", "no handler"))
+        .unwrap();
+
+    let errors = emulator.init_errors();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("Runtime.ConfigError"), "{}", errors[0]);
+}
+
+/// Test: Every built-in fixture is recognized by the runtime's event router
+#[test]
+fn test_fixtures_route_by_source() {
+    let router = [
+        "GET /users/{id}",
+        "POST /orders",
+        "GET /health",
+        "sqs:orders",
+        "sns:alerts",
+        "s3:uploads",
+        "dynamodb:orders",
+        "kinesis:clicks",
+        "events:com.example.orders",
+        "events:aws.events",
+    ]
+    .into_iter()
+    .fold(Router::new(), |router, pattern| {
+        router.route(pattern, |_, _| String::new())
+    });
+
+    for (name, event) in FIXTURES {
+        assert!(router.matches(event), "fixture {name} is not routable");
+    }
+    for name in ["apigw-v1", "apigw-v2", "alb"] {
+        let event = ruchy_lambda_emulator::fixture(name).unwrap();
+        assert!(HttpRequest::from_json(event).is_ok(), "{name}");
+    }
+}
+
+/// Test: Replay checks expectations, and bless records them
+#[test]
+#[serial]
+fn test_replay_dir() {
+    let emulator = Emulator::start(config()).unwrap();
+    let runtime = serve(&emulator, |event| {
+        if event.contains("fail") {
+            Err(HandlerError::new("Boom", "failed"))
+        } else {
+            Ok(format!(r#"{{"echo":{event}}}"#))
+        }
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let write =
+        |name: &str, contents: &str| std::fs::write(dir.path().join(name), contents).unwrap();
+    write("1-match.json", r#"{"n":1}"#);
+    write("1-match.expected.json", "{ \"echo\": { \"n\": 1 } }\n");
+    write("2-mismatch.json", r#"{"n":2}"#);
+    write("2-mismatch.expected.json", r#"{"echo":{"n":3}}"#);
+    write("3-unchecked.json", r#"{"n":4}"#);
+    write("4-error.json", r#""fail""#);
+
+    let mut events = load_dir(dir.path()).unwrap();
+    events.push(ReplayEvent::fixture("sqs").unwrap());
+    let results = replay(&emulator, &events);
+    let passed: Vec<(&str, bool)> = results
+        .iter()
+        .map(|result| (result.name.as_str(), result.passed()))
+        .collect();
+    assert_eq!(
+        passed,
+        [
+            ("1-match", true),
+            ("2-mismatch", false),
+            ("3-unchecked", true),
+            ("4-error", false),
+            ("fixture:sqs", true),
+        ]
+    );
+    assert!(results[1]
+        .to_string()
+        .contains("expected: {\"echo\":{\"n\":3}}"));
+
+    // Blessing records the current responses, errors included
+    assert_eq!(bless(&events, &results).unwrap(), 4);
+    let results = replay(&emulator, &load_dir(dir.path()).unwrap());
+    assert!(results.iter().all(|result| result.passed()), "{results:?}");
+
+    emulator.shutdown();
+    runtime.join().unwrap();
+}
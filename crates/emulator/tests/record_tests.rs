@@ -0,0 +1,133 @@
+// Recorder tests
+//
+// A ruchy runtime talks to the emulator through a Recorder; the recording is
+// then replayed against a fresh emulator, as when reproducing a production
+// invocation locally.
+
+use ruchy_lambda_emulator::{
+    load_dir, load_exchanges, replay, Emulator, EmulatorConfig, Outcome, Recorder, EXCHANGES_FILE,
+};
+use ruchy_lambda_runtime::{HandlerError, Runtime, RuntimeConfig};
+use std::thread::{self, JoinHandle};
+
+fn emulator() -> Emulator {
+    Emulator::start(EmulatorConfig::default().with_addr("127.0.0.1:0".parse().unwrap())).unwrap()
+}
+
+/// Serve invocations from the Runtime API at `endpoint` until `/next` fails
+fn serve<F>(endpoint: String, handler: F) -> JoinHandle<()>
+where
+    F: Fn(&str) -> Result<String, HandlerError> + Send + 'static,
+{
+    let runtime = Runtime::from_config(RuntimeConfig::new(endpoint));
+    thread::spawn(move || {
+        while let Ok((request_id, event)) = runtime.next_event() {
+            let _ = match handler(&event) {
+                Ok(response) => runtime.post_response(&request_id, &response),
+                Err(error) => runtime.post_error(&request_id, &error),
+            };
+        }
+    })
+}
+
+fn handler(version: u32) -> impl Fn(&str) -> Result<String, HandlerError> {
+    move |event| {
+        if event.contains("fail") {
+            Err(HandlerError::new("Boom", "failed"))
+        } else {
+            Ok(format!(r#"{{"echo":{event},"version":{version}}}"#))
+        }
+    }
+}
+
+#[test]
+fn test_record_then_replay() {
+    let dir = tempfile::tempdir().unwrap();
+    let upstream = emulator();
+    let recorder = Recorder::start(
+        "127.0.0.1:0".parse().unwrap(),
+        upstream.addr().to_string(),
+        dir.path(),
+    )
+    .unwrap();
+    let runtime = serve(recorder.addr().to_string(), handler(1));
+
+    // The recorder relays answers unchanged
+    let ok = upstream.invoke(br#"{"n":1}"#).unwrap();
+    assert_eq!(
+        ok.outcome,
+        Outcome::Success(r#"{"echo":{"n":1},"version":1}"#.to_string())
+    );
+    let failed = upstream.invoke(br#""fail""#).unwrap();
+    assert!(failed.outcome.is_error());
+
+    upstream.shutdown();
+    runtime.join().unwrap();
+    assert!(recorder.exchanges() >= 4);
+    recorder.shutdown();
+
+    let exchanges = load_exchanges(&dir.path().join(EXCHANGES_FILE)).unwrap();
+    let requests: Vec<(&str, u16)> = exchanges[..4]
+        .iter()
+        .map(|exchange| (exchange.method.as_str(), exchange.status))
+        .collect();
+    assert_eq!(
+        requests,
+        [("GET", 200), ("POST", 202), ("GET", 200), ("POST", 202)]
+    );
+    assert_eq!(
+        exchanges[0].response_header("Lambda-Runtime-Aws-Request-Id"),
+        Some(ok.request_id.as_str())
+    );
+    assert_eq!(
+        exchanges[3].path,
+        format!("/2018-06-01/runtime/invocation/{}/error", failed.request_id)
+    );
+
+    let events = load_dir(dir.path()).unwrap();
+    let names: Vec<&str> = events.iter().map(|event| event.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            format!("0001-{}", ok.request_id),
+            format!("0002-{}", failed.request_id)
+        ]
+    );
+    assert_eq!(events[0].payload, br#"{"n":1}"#);
+    assert_eq!(
+        events[0].expected.as_deref(),
+        Some("{\"echo\":{\"n\":1},\"version\":1}\n")
+    );
+    assert!(events[1]
+        .expected
+        .as_deref()
+        .unwrap()
+        .contains("\"errorType\":\"Boom\""));
+
+    // Same handler: every recorded answer reproduces; changed handler: not
+    for (version, passes) in [(1, true), (2, false)] {
+        let local = emulator();
+        let runtime = serve(local.addr().to_string(), handler(version));
+        let results = replay(&local, &events);
+        assert_eq!(results[0].passed(), passes, "{}", results[0]);
+        assert!(results[1].passed(), "{}", results[1]);
+        local.shutdown();
+        runtime.join().unwrap();
+    }
+}
+
+#[test]
+fn test_upstream_unreachable() {
+    let dir = tempfile::tempdir().unwrap();
+    let recorder =
+        Recorder::start("127.0.0.1:0".parse().unwrap(), "127.0.0.1:1", dir.path()).unwrap();
+
+    let runtime = Runtime::from_config(RuntimeConfig::new(recorder.addr().to_string()));
+    assert!(runtime.next_event().is_err());
+    drop(recorder);
+
+    let exchanges = load_exchanges(&dir.path().join(EXCHANGES_FILE)).unwrap();
+    assert_eq!(exchanges.len(), 1);
+    assert_eq!(exchanges[0].status, 502);
+    assert!(load_dir(dir.path()).unwrap().is_empty());
+}
@@ -0,0 +1,137 @@
+// Sandbox supervision tests
+//
+// The supervised "bootstrap" is this test binary re-run with
+// RUCHY_EMULATOR_CHILD set, executing only `child_runtime`: a ruchy runtime
+// whose behavior is picked by the event (echo, sleep, alloc, exit).
+
+use ruchy_lambda_emulator::{Emulator, EmulatorConfig, Outcome, Sandbox};
+use ruchy_lambda_runtime::Runtime;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+const CHILD_ENV: &str = "RUCHY_EMULATOR_CHILD";
+
+/// Not a test: the runtime loop run inside the sandbox
+#[test]
+#[ignore] // Spawned by the tests below
+fn child_runtime() {
+    if std::env::var_os(CHILD_ENV).is_none() {
+        return;
+    }
+    let runtime = Runtime::new().expect("runtime");
+    while let Ok((request_id, event)) = runtime.next_event() {
+        match event.trim_matches('"') {
+            "sleep" => thread::sleep(Duration::from_secs(30)),
+            "alloc" => {
+                // Touch every page so it counts towards RSS
+                let hog = vec![1_u8; 256 * 1024 * 1024];
+                thread::sleep(Duration::from_secs(30));
+                drop(hog);
+            }
+            "exit" => std::process::exit(3),
+            _ => {}
+        }
+        runtime.post_response(&request_id, &event).expect("post");
+    }
+}
+
+fn sandbox(emulator: &Emulator) -> Sandbox {
+    let mut command = Command::new(std::env::current_exe().unwrap());
+    command
+        .args(["child_runtime", "--exact", "--ignored", "--quiet"])
+        .env(CHILD_ENV, "1");
+    Sandbox::spawn(emulator, command).expect("spawn child")
+}
+
+fn config() -> EmulatorConfig {
+    EmulatorConfig::default()
+        .with_addr("127.0.0.1:0".parse().unwrap())
+        .with_timeout(Duration::from_secs(5))
+}
+
+/// Test: A supervised runtime serves invocations and reports its memory
+#[test]
+fn test_sandbox_reports_memory() {
+    let emulator = Emulator::start(config()).unwrap();
+    let sandbox = sandbox(&emulator);
+
+    let invocation = emulator.invoke(b"\"echo\"").unwrap();
+    assert_eq!(invocation.outcome, Outcome::Success("\"echo\"".to_string()));
+    if cfg!(target_os = "linux") {
+        assert!(
+            invocation
+                .report
+                .max_memory_used_mb
+                .is_some_and(|mb| mb > 0),
+            "{}",
+            invocation.report
+        );
+    }
+    assert_eq!(sandbox.restarts(), 0);
+}
+
+/// Test: Going over the memory size kills the runtime with Runtime.OutOfMemory
+#[test]
+#[cfg(target_os = "linux")]
+fn test_sandbox_out_of_memory() {
+    let emulator = Emulator::start(config().with_memory_size_mb(128)).unwrap();
+    let sandbox = sandbox(&emulator);
+
+    let invocation = emulator.invoke(b"\"alloc\"").unwrap();
+    assert_eq!(invocation.outcome, Outcome::OutOfMemory);
+    assert!(invocation
+        .report
+        .to_string()
+        .ends_with("\tStatus: error\tError Type: Runtime.OutOfMemory"));
+    assert!(invocation.report.max_memory_used_mb.unwrap() > 128);
+
+    // The replacement process serves the next invocation
+    let invocation = emulator.invoke(b"\"echo\"").unwrap();
+    assert!(!invocation.outcome.is_error(), "{:?}", invocation.outcome);
+    assert_eq!(sandbox.restarts(), 1);
+}
+
+/// Test: A timed-out runtime is replaced before the next invocation
+#[test]
+fn test_sandbox_resets_after_timeout() {
+    let emulator = Emulator::start(config().with_timeout(Duration::from_millis(500))).unwrap();
+    let sandbox = sandbox(&emulator);
+
+    // Let the first process initialize inside the timeout
+    assert!(!emulator.invoke(b"\"echo\"").unwrap().outcome.is_error());
+
+    let invocation = emulator.invoke(b"\"sleep\"").unwrap();
+    assert_eq!(invocation.outcome, Outcome::Timeout);
+
+    // The sleeping process would never answer; a fresh one does
+    let mut outcome = emulator.invoke(b"\"echo\"").unwrap().outcome;
+    if outcome == Outcome::Timeout {
+        // Cold start of the replacement overlapped the invoke
+        outcome = emulator.invoke(b"\"echo\"").unwrap().outcome;
+    }
+    assert_eq!(outcome, Outcome::Success("\"echo\"".to_string()));
+    assert!(sandbox.restarts() >= 1);
+}
+
+/// Test: A runtime exiting mid-invocation fails it with Runtime.ExitError
+#[test]
+fn test_sandbox_runtime_exit() {
+    let emulator = Emulator::start(config()).unwrap();
+    let sandbox = sandbox(&emulator);
+
+    let invocation = emulator.invoke(b"\"exit\"").unwrap();
+    let Outcome::RuntimeExit { status } = &invocation.outcome else {
+        panic!("expected an exit, got {:?}", invocation.outcome);
+    };
+    assert!(status.contains('3'), "{status}");
+    assert_eq!(
+        invocation.report.error_type,
+        Some("Runtime.ExitError"),
+        "{}",
+        invocation.report
+    );
+
+    assert!(!emulator.invoke(b"\"echo\"").unwrap().outcome.is_error());
+    assert_eq!(sandbox.restarts(), 1);
+}
@@ -0,0 +1,98 @@
+// POST request write benchmark: vectored head + body vs one copied String
+//
+// `post_with_headers_on` hands the head and the body to a single vectored
+// write; "copy" is the previous approach of formatting the body into the
+// request String first. The sink accepts every buffer of a vectored write,
+// like a socket with room in its send buffer, so the numbers isolate the
+// copy rather than the kernel.
+//   cargo bench -p ruchy-lambda-http-core --bench post
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ruchy_lambda_http_core::{post_with_headers_on, Transport};
+use std::io::{self, IoSlice, Read, Write};
+
+/// Handler response sizes, up to Lambda's 6MB synchronous payload limit
+const SIZES: &[usize] = &[1024, 64 * 1024, 1024 * 1024, 6 * 1024 * 1024];
+
+const ACCEPTED: &[u8] = b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n";
+
+/// Discards the request and answers `202 Accepted`
+struct Sink {
+    written: usize,
+}
+
+impl Read for Sink {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        buf[..ACCEPTED.len()].copy_from_slice(ACCEPTED);
+        Ok(ACCEPTED.len())
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written += black_box(buf).len();
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        Ok(bufs.iter().map(|buf| self.write(buf).unwrap_or(0)).sum())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transport for Sink {}
+
+/// The pre-writev request: head and body formatted into one String
+fn post_copy(mut stream: Sink, endpoint: &str, path: &str, body: &str) -> io::Result<()> {
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+        path,
+        endpoint,
+        body.len()
+    );
+    request.push_str("Connection: close\r\n\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes())?;
+    stream.flush()?;
+    let mut buffer = vec![0u8; 1024];
+    stream.read(&mut buffer).map(|_| ())
+}
+
+fn payload(size: usize) -> String {
+    let mut body = String::with_capacity(size);
+    body.push('"');
+    body.extend(
+        b"abcdefghijklmnopqrstuvwxyz"
+            .iter()
+            .cycle()
+            .take(size - 2)
+            .map(|&b| char::from(b)),
+    );
+    body.push('"');
+    body
+}
+
+fn bench_post(c: &mut Criterion) {
+    let path = "/2018-06-01/runtime/invocation/bench-request/response";
+    let mut group = c.benchmark_group("post_response");
+    for &size in SIZES {
+        let body = payload(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("vectored", size), &body, |b, body| {
+            b.iter(|| {
+                post_with_headers_on(Sink { written: 0 }, "127.0.0.1:9001", path, body, &[])
+                    .unwrap();
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("copy", size), &body, |b, body| {
+            b.iter(|| post_copy(Sink { written: 0 }, "127.0.0.1:9001", path, body).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_post);
+criterion_main!(benches);
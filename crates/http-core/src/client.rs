@@ -0,0 +1,1062 @@
+// Blocking HTTP/1.1 requests over plain TCP
+//
+// Phase 3: Blocking I/O (no tokio) - Lambda processes one event at a time
+//
+// GET responses are read incrementally with a size cap so a pathological
+// `/next` response cannot make us buffer unbounded memory.
+//
+// Outbound calls can bound every blocking step: `connect_with_timeout` plus
+// the `*_on` variants taking the connected stream. Those accept any
+// `Transport` (transport.rs), so tests can swap TCP for an in-memory stream.
+//
+// POST sends the request head and the body as two buffers of one vectored
+// write (writev), so a multi-MB response body is never copied into the
+// request String.
+
+use crate::invocation::DEFAULT_MAX_RESPONSE_SIZE;
+use crate::response::{
+    is_success_status, parse_any_response_ref, parse_response, status_code, Response,
+};
+use crate::socket::{find_body_start, read_bounded, request_head, READ_CHUNK_SIZE};
+use crate::transport::Transport;
+use crate::HttpError;
+use std::io::{self, IoSlice, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Make a GET request and return the parsed 2xx response
+///
+/// Bodies larger than [`DEFAULT_MAX_RESPONSE_SIZE`] are rejected.
+///
+/// # Errors
+///
+/// Returns `HttpError` if the request fails or the response is invalid/non-2xx
+pub fn get(endpoint: &str, path: &str) -> Result<Response, HttpError> {
+    get_with_limit(endpoint, path, DEFAULT_MAX_RESPONSE_SIZE)
+}
+
+/// Make a GET request, rejecting response bodies over `max_body_size` bytes
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` as soon as the advertised
+/// `Content-Length` or the bytes read so far exceed `max_body_size`, and
+/// other `HttpError`s if the request fails or the response is invalid/non-2xx
+pub fn get_with_limit(
+    endpoint: &str,
+    path: &str,
+    max_body_size: usize,
+) -> Result<Response, HttpError> {
+    // Connect to endpoint (blocking)
+    let stream = TcpStream::connect(endpoint)?;
+
+    get_on(stream, endpoint, path, max_body_size)
+}
+
+/// Make a GET request over an already-connected stream
+///
+/// Same as [`get_with_limit`], but reuses a connection opened ahead of time
+/// (e.g. pre-connected during the init window). The stream is consumed,
+/// since every request uses `Connection: close`.
+///
+/// # Errors
+///
+/// Returns `HttpError` if the request fails or the response is invalid/non-2xx
+pub fn get_on<T: Transport>(
+    mut stream: T,
+    endpoint: &str,
+    path: &str,
+    max_body_size: usize,
+) -> Result<Response, HttpError> {
+    // Build HTTP GET request
+    let request = format!("GET {path} HTTP/1.1\r\nHost: {endpoint}\r\nConnection: close\r\n\r\n");
+
+    // Send request (blocking)
+    stream.write_all(request.as_bytes())?;
+    stream.flush()?;
+
+    // Read response (blocking, bounded)
+    let mut buffer = Vec::new();
+    read_bounded(&mut Io(&mut stream), max_body_size, &mut buffer)?;
+
+    parse_response(&buffer)
+}
+
+/// Make a GET request, reading the raw response into a caller-provided buffer
+///
+/// `buffer` is cleared first and reused for both the request line and the
+/// response, so once its capacity has grown to fit a typical event no heap
+/// allocation happens per call. Parse the result with
+/// [`parse_response_ref`](crate::parse_response_ref).
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` if the body exceeds `max_body_size`,
+/// and other `HttpError`s if the request fails
+pub fn get_into(
+    endpoint: &str,
+    path: &str,
+    max_body_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), HttpError> {
+    let stream = TcpStream::connect(endpoint)?;
+    get_into_on(stream, endpoint, path, max_body_size, buffer)
+}
+
+/// Make a GET request into `buffer` over an already-connected stream
+///
+/// Connection-reusing counterpart of [`get_into`] (see [`get_on`]).
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` if the body exceeds `max_body_size`,
+/// and other `HttpError`s if the request fails
+pub fn get_into_on<T: Transport>(
+    mut stream: T,
+    endpoint: &str,
+    path: &str,
+    max_body_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), HttpError> {
+    // Build the request in the caller's buffer instead of a fresh String
+    buffer.clear();
+    buffer.extend_from_slice(b"GET ");
+    buffer.extend_from_slice(path.as_bytes());
+    buffer.extend_from_slice(b" HTTP/1.1\r\nHost: ");
+    buffer.extend_from_slice(endpoint.as_bytes());
+    buffer.extend_from_slice(b"\r\nConnection: close\r\n\r\n");
+    stream.write_all(buffer)?;
+    stream.flush()?;
+
+    buffer.clear();
+    read_bounded(&mut Io(&mut stream), max_body_size, buffer)
+}
+
+/// Connect to `endpoint` within `timeout`, and bound each later read and
+/// write on the stream by `timeout` too
+///
+/// Timed-out I/O fails with `io::ErrorKind::TimedOut` (or `WouldBlock` on
+/// some platforms). A zero `timeout` fails without connecting.
+///
+/// # Errors
+///
+/// Returns the connect error of the last address tried
+pub fn connect_with_timeout(endpoint: &str, timeout: Duration) -> io::Result<TcpStream> {
+    if timeout.is_zero() {
+        return Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "no time left to connect",
+        ));
+    }
+
+    let mut last_error = None;
+    for address in endpoint.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{endpoint} resolved to no addresses"),
+        )
+    }))
+}
+
+/// Make a request with any method, headers and body, reading the raw
+/// response into `buffer`
+///
+/// Used for outbound calls (e.g. AWS service APIs through a local endpoint or
+/// proxy) rather than the Runtime API. A `Host` header is added unless
+/// `headers` has one; `Content-Length` and `Connection: close` are always
+/// sent. The response is not status-checked: parse it with
+/// [`parse_any_response_ref`](crate::parse_any_response_ref). CR/LF in header
+/// values are dropped.
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` if the body exceeds `max_body_size`,
+/// and other `HttpError`s if the request fails
+pub fn request_into(
+    endpoint: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    max_body_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), HttpError> {
+    let stream = TcpStream::connect(endpoint)?;
+    request_into_on(
+        stream,
+        endpoint,
+        method,
+        path,
+        headers,
+        body,
+        max_body_size,
+        buffer,
+    )
+}
+
+/// Make a request into `buffer` over an already-connected stream
+///
+/// Counterpart of [`request_into`] for streams from [`connect_with_timeout`]
+/// (see [`get_on`]).
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` if the body exceeds `max_body_size`,
+/// and other `HttpError`s if the request fails
+#[allow(clippy::too_many_arguments)]
+pub fn request_into_on<T: Transport>(
+    mut stream: T,
+    endpoint: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    max_body_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), HttpError> {
+    let head = request_head(endpoint, method, path, headers, body.len() as u64);
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()?;
+
+    buffer.clear();
+    read_bounded(&mut Io(&mut stream), max_body_size, buffer)
+}
+
+/// Make a request streaming `content_length` bytes of `body`, and return
+/// the response with its body still on the wire
+///
+/// Streaming counterpart of [`request_into`] for payloads that should not be
+/// buffered (large uploads and downloads). The response head is limited to
+/// [`MAX_HEAD_SIZE`]; the body is read on demand from the returned
+/// [`ResponseStream`], up to its `Content-Length` when one is sent.
+///
+/// # Errors
+///
+/// Returns `HttpError::Io` if the request fails or `body` ends before
+/// `content_length` bytes, and `HttpError::InvalidResponse` if the response
+/// head is malformed or too large
+pub fn request_stream(
+    endpoint: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &mut dyn Read,
+    content_length: u64,
+) -> Result<ResponseStream, HttpError> {
+    let stream = TcpStream::connect(endpoint)?;
+    request_stream_on(
+        stream,
+        endpoint,
+        method,
+        path,
+        headers,
+        body,
+        content_length,
+    )
+}
+
+/// Make a streaming request over an already-connected stream
+///
+/// Counterpart of [`request_stream`] for streams from
+/// [`connect_with_timeout`] (see [`get_on`]).
+///
+/// # Errors
+///
+/// Returns `HttpError::Io` if the request fails or `body` ends before
+/// `content_length` bytes, and `HttpError::InvalidResponse` if the response
+/// head is malformed or too large
+pub fn request_stream_on<T: Transport>(
+    mut stream: T,
+    endpoint: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &mut dyn Read,
+    content_length: u64,
+) -> Result<ResponseStream<T>, HttpError> {
+    let head = request_head(endpoint, method, path, headers, content_length);
+    stream.write_all(head.as_bytes())?;
+    let sent = io::copy(&mut body.take(content_length), &mut stream)?;
+    if sent != content_length {
+        return Err(HttpError::Io(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("request body ended after {sent} of {content_length} bytes"),
+        )));
+    }
+    stream.flush()?;
+
+    read_head(stream, None)
+}
+
+/// Make a GET request and return the 2xx response with its body still on
+/// the wire
+///
+/// Streaming counterpart of [`get_with_limit`]: only the response head is
+/// buffered, so a multi-MB event can be handed to a deserializer reading
+/// from the connection instead of being copied into memory first.
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` if the advertised `Content-Length`
+/// exceeds `max_body_size`, and other `HttpError`s if the request fails or
+/// the response is invalid/non-2xx. Reading more than `max_body_size` body
+/// bytes from the stream fails with `io::ErrorKind::InvalidData`.
+pub fn get_stream(
+    endpoint: &str,
+    path: &str,
+    max_body_size: usize,
+) -> Result<ResponseStream, HttpError> {
+    let stream = TcpStream::connect(endpoint)?;
+    get_stream_on(stream, endpoint, path, max_body_size)
+}
+
+/// Make a streaming GET request over an already-connected stream
+///
+/// Counterpart of [`get_stream`] (see [`get_on`]).
+///
+/// # Errors
+///
+/// Returns `HttpError::ResponseTooLarge` if the advertised `Content-Length`
+/// exceeds `max_body_size`, and other `HttpError`s if the request fails or
+/// the response is invalid/non-2xx
+pub fn get_stream_on<T: Transport>(
+    mut stream: T,
+    endpoint: &str,
+    path: &str,
+    max_body_size: usize,
+) -> Result<ResponseStream<T>, HttpError> {
+    let request = format!("GET {path} HTTP/1.1\r\nHost: {endpoint}\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes())?;
+    stream.flush()?;
+
+    let response = read_head(stream, Some(max_body_size))?;
+    if !is_success_status(&response.status_line) {
+        return Err(HttpError::InvalidResponse(format!(
+            "Non-2xx status: {}",
+            response.status_line
+        )));
+    }
+    if response
+        .remaining
+        .is_some_and(|len| len > max_body_size as u64)
+    {
+        return Err(HttpError::ResponseTooLarge {
+            limit: max_body_size,
+        });
+    }
+    Ok(response)
+}
+
+/// Read and parse the response head, leaving the body on the wire
+///
+/// Bytes read past the head are kept in the returned stream.
+fn read_head<T: Read>(mut stream: T, limit: Option<usize>) -> Result<ResponseStream<T>, HttpError> {
+    let mut buffered = Vec::new();
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+    let body_start = loop {
+        if let Some(start) = find_body_start(&buffered) {
+            break start;
+        }
+        if buffered.len() > MAX_HEAD_SIZE {
+            return Err(HttpError::InvalidResponse(format!(
+                "Response head exceeds {MAX_HEAD_SIZE} bytes"
+            )));
+        }
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            return Err(HttpError::InvalidResponse(
+                "No body separator found".to_string(),
+            ));
+        }
+        buffered.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = parse_any_response_ref(&buffered[..body_start])?;
+    let status_line = head.status_line.to_string();
+    let headers: Vec<(String, String)> = head
+        .header_pairs()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    let remaining = content_length_of(&headers);
+
+    Ok(ResponseStream {
+        status_line,
+        headers,
+        buffered,
+        position: body_start,
+        stream,
+        remaining,
+        limit,
+        received: 0,
+    })
+}
+
+/// Largest response head [`request_stream`] and [`get_stream`] accept
+pub const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// Response whose body is read from the connection on demand
+///
+/// Returned by [`request_stream`] and [`get_stream`]; reading yields the
+/// body bytes only. A connection closed before `Content-Length` bytes
+/// arrived fails the read with `UnexpectedEof`.
+#[derive(Debug)]
+pub struct ResponseStream<T = TcpStream> {
+    /// Status line (e.g., "HTTP/1.1 200 OK")
+    pub status_line: String,
+    /// Header `(name, value)` pairs in wire order
+    pub headers: Vec<(String, String)>,
+    buffered: Vec<u8>,
+    position: usize,
+    stream: T,
+    remaining: Option<u64>,
+    /// Body size past which reads fail (streaming GETs only)
+    limit: Option<usize>,
+    received: u64,
+}
+
+impl<T> ResponseStream<T> {
+    /// Look up a header value by name (case-insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Numeric status code (e.g. `206`), if the status line has one
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        status_code(&self.status_line)
+    }
+
+    /// Body bytes not read yet, if the response sent a `Content-Length`
+    #[must_use]
+    pub fn remaining(&self) -> Option<u64> {
+        self.remaining
+    }
+}
+
+impl<T: Read> Read for ResponseStream<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let limit = self.remaining.map_or(buf.len(), |remaining| {
+            buf.len()
+                .min(usize::try_from(remaining).unwrap_or(usize::MAX))
+        });
+        if limit == 0 {
+            return Ok(0);
+        }
+
+        let n = if self.position < self.buffered.len() {
+            let n = limit.min(self.buffered.len() - self.position);
+            buf[..n].copy_from_slice(&self.buffered[self.position..self.position + n]);
+            self.position += n;
+            n
+        } else {
+            self.stream.read(&mut buf[..limit])?
+        };
+        if n == 0 && self.remaining.is_some() {
+            // Closed before the advertised Content-Length: truncated body
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= n as u64;
+        }
+        self.received += n as u64;
+        if let Some(limit) = self.limit {
+            if self.received > limit as u64 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    HttpError::ResponseTooLarge { limit },
+                ));
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// `Content-Length` of parsed response headers
+fn content_length_of(headers: &[(String, String)]) -> Option<u64> {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+}
+
+/// Make a POST request with a JSON body and verify a 2xx status
+///
+/// # Errors
+///
+/// Returns `HttpError` if the request fails or the response is non-2xx
+pub fn post(endpoint: &str, path: &str, body: &str) -> Result<(), HttpError> {
+    post_with_headers(endpoint, path, body, &[])
+}
+
+/// Make a POST request with a JSON body and extra request headers
+///
+/// Used for headers such as `Lambda-Runtime-Function-Error-Type` on the
+/// error endpoints. CR/LF in header values are dropped so a value can never
+/// inject additional headers.
+///
+/// # Errors
+///
+/// Returns `HttpError` if the request fails or the response is non-2xx
+pub fn post_with_headers(
+    endpoint: &str,
+    path: &str,
+    body: &str,
+    headers: &[(&str, &str)],
+) -> Result<(), HttpError> {
+    // Connect to endpoint (blocking)
+    let stream = TcpStream::connect(endpoint)?;
+
+    post_with_headers_on(stream, endpoint, path, body, headers)
+}
+
+/// Make a POST request over an already-connected stream
+///
+/// Counterpart of [`post_with_headers`] (see [`get_on`]).
+///
+/// # Errors
+///
+/// Returns `HttpError` if the request fails or the response is non-2xx
+pub fn post_with_headers_on<T: Transport>(
+    mut stream: T,
+    endpoint: &str,
+    path: &str,
+    body: &str,
+    headers: &[(&str, &str)],
+) -> Result<(), HttpError> {
+    let request = post_head(endpoint, path, body.len(), headers, false);
+
+    // Send head and body together without copying the body (blocking)
+    write_all_vectored(
+        &mut stream,
+        &mut [
+            IoSlice::new(request.as_bytes()),
+            IoSlice::new(body.as_bytes()),
+        ],
+    )?;
+    stream.flush()?;
+
+    // Read response (we don't need the body, just verify it succeeded)
+    let mut buffer = vec![0u8; 1024];
+    let n = stream.read(&mut buffer)?;
+
+    // Check for 2xx status code
+    let response = String::from_utf8_lossy(&buffer[..n]);
+    let status_line = response.lines().next().unwrap_or("unknown");
+    if !is_success_status(status_line) {
+        return Err(HttpError::InvalidResponse(format!(
+            "POST request failed: {status_line}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Request line and headers of a JSON POST with a `body_len`-byte body
+///
+/// Ends in `Connection: close` unless `keep_alive` is set.
+pub(crate) fn post_head(
+    endpoint: &str,
+    path: &str,
+    body_len: usize,
+    headers: &[(&str, &str)],
+    keep_alive: bool,
+) -> String {
+    use std::fmt::Write as _;
+
+    let mut head = format!(
+        "POST {path} HTTP/1.1\r\nHost: {endpoint}\r\nContent-Type: application/json\r\nContent-Length: {body_len}\r\n"
+    );
+    for (name, value) in headers {
+        let value: String = value.chars().filter(|c| *c != '\r' && *c != '\n').collect();
+        let _ = write!(head, "{name}: {value}\r\n");
+    }
+    if !keep_alive {
+        head.push_str("Connection: close\r\n");
+    }
+    head.push_str("\r\n");
+    head
+}
+
+/// Write every byte of `buffers`, retrying partial vectored writes
+///
+/// `Write::write_all_vectored` is not stable yet; this is its loop.
+pub(crate) fn write_all_vectored<W: Write + ?Sized>(
+    writer: &mut W,
+    mut buffers: &mut [IoSlice<'_>],
+) -> io::Result<()> {
+    IoSlice::advance_slices(&mut buffers, 0);
+    while !buffers.is_empty() {
+        match writer.write_vectored(buffers) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ))
+            }
+            Ok(n) => IoSlice::advance_slices(&mut buffers, n),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// [`Socket`](crate::Socket) view of a [`Transport`], for the shared read loop
+struct Io<'a, T: ?Sized>(&'a mut T);
+
+impl<T: Read + Write + ?Sized> crate::socket::Socket for Io<'_, T> {
+    fn write_all(&mut self, data: &[u8]) -> Result<(), HttpError> {
+        Write::write_all(self.0, data)?;
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, HttpError> {
+        Ok(Read::read(self.0, buf)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_connection_refused() {
+        let result = get("127.0.0.1:19995", "/2018-06-01/runtime/invocation/next");
+        assert!(matches!(result, Err(HttpError::Io(_))));
+    }
+
+    #[test]
+    fn test_get_with_limit_over_tcp() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = "x".repeat(4096);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+        });
+
+        let result = get_with_limit(&addr, "/2018-06-01/runtime/invocation/next", 1024);
+        assert!(matches!(
+            result,
+            Err(HttpError::ResponseTooLarge { limit: 1024 })
+        ));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_get_into_reuses_buffer() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let n = stream.read(&mut request).unwrap();
+            assert!(request[..n].starts_with(b"GET /next HTTP/1.1\r\nHost: "));
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}");
+        });
+
+        let mut buffer = Vec::with_capacity(4096);
+        let capacity = buffer.capacity();
+        get_into(&addr, "/next", 1024, &mut buffer).unwrap();
+        assert_eq!(buffer, b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}");
+        assert_eq!(buffer.capacity(), capacity);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_get_on_preconnected_stream() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        // Connect first, then let the server accept: the request goes over
+        // the connection opened ahead of time
+        let stream = TcpStream::connect(&addr).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}");
+        });
+
+        let response = get_on(stream, &addr, "/next", 1024).unwrap();
+        assert_eq!(response.body, "{}");
+        server.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_requests_over_unix_stream() {
+        use std::os::unix::net::UnixStream;
+
+        let (client, mut server) = UnixStream::pair().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut request = [0u8; 1024];
+            let n = server.read(&mut request).unwrap();
+            assert!(request[..n].starts_with(b"POST /response HTTP/1.1\r\nHost: api\r\n"));
+            server.write_all(b"HTTP/1.1 202 Accepted\r\n\r\n").unwrap();
+        });
+        post_with_headers_on(client, "api", "/response", "{}", &[]).unwrap();
+        server.join().unwrap();
+
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}")
+            .unwrap();
+        server.shutdown(std::net::Shutdown::Write).unwrap();
+        let response = get_on(Box::new(client) as Box<dyn Transport>, "api", "/next", 16).unwrap();
+        assert_eq!(response.body, "{}");
+    }
+
+    #[test]
+    fn test_write_all_vectored_handles_partial_writes() {
+        /// Takes at most 3 bytes per call, spanning buffers, and is
+        /// interrupted once
+        struct Trickle {
+            written: Vec<u8>,
+            interrupted: bool,
+        }
+
+        impl Write for Trickle {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.write_vectored(&[IoSlice::new(buf)])
+            }
+
+            fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+                if !self.interrupted {
+                    self.interrupted = true;
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                let before = self.written.len();
+                for buf in bufs {
+                    let take = (before + 3 - self.written.len()).min(buf.len());
+                    self.written.extend_from_slice(&buf[..take]);
+                }
+                Ok(self.written.len() - before)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = Trickle {
+            written: Vec::new(),
+            interrupted: false,
+        };
+        write_all_vectored(
+            &mut writer,
+            &mut [
+                IoSlice::new(b""),
+                IoSlice::new(b"head\r\n\r\n"),
+                IoSlice::new(b"body"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(writer.written, b"head\r\n\r\nbody");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_post_large_body_arrives_intact() {
+        use std::os::unix::net::UnixStream;
+
+        let body: String = b"abcdefghijklmnopqrstuvwxyz"
+            .iter()
+            .cycle()
+            .take(1024 * 1024)
+            .map(|&b| char::from(b))
+            .collect();
+        let (client, mut server) = UnixStream::pair().unwrap();
+        let server = std::thread::spawn(move || {
+            let mut request = Vec::new();
+            let mut chunk = [0u8; READ_CHUNK_SIZE];
+            while find_body_start(&request).is_none_or(|start| request.len() - start < 1024 * 1024)
+            {
+                let n = server.read(&mut chunk).unwrap();
+                assert!(n > 0, "request ended early");
+                request.extend_from_slice(&chunk[..n]);
+            }
+            server.write_all(b"HTTP/1.1 202 Accepted\r\n\r\n").unwrap();
+            request
+        });
+
+        post_with_headers_on(client, "api", "/response", &body, &[]).unwrap();
+
+        let request = server.join().unwrap();
+        let start = find_body_start(&request).unwrap();
+        assert!(String::from_utf8_lossy(&request[..start]).contains("Content-Length: 1048576\r\n"));
+        assert_eq!(&request[start..], body.as_bytes());
+    }
+
+    #[test]
+    fn test_post_with_headers_sanitizes_values() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0u8; 4096];
+            let n = stream.read(&mut request).unwrap();
+            let _ = stream.write_all(b"HTTP/1.1 202 Accepted\r\n\r\n");
+            String::from_utf8_lossy(&request[..n]).to_string()
+        });
+
+        post_with_headers(
+            &addr,
+            "/error",
+            "{}",
+            &[("Lambda-Runtime-Function-Error-Type", "Bad\r\nX-Injected: 1")],
+        )
+        .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.contains("\r\nLambda-Runtime-Function-Error-Type: BadX-Injected: 1\r\n"));
+        assert!(!request.contains("\r\nX-Injected"));
+        assert!(request.ends_with("Connection: close\r\n\r\n{}"));
+    }
+
+    #[test]
+    fn test_request_into_any_method_and_status() {
+        use crate::parse_any_response_ref;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0u8; 4096];
+            let n = stream.read(&mut request).unwrap();
+            let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 2\r\n\r\n{}");
+            String::from_utf8_lossy(&request[..n]).to_string()
+        });
+
+        let mut buffer = Vec::new();
+        request_into(
+            &addr,
+            "PUT",
+            "/bucket/key",
+            &[("Host", "bucket.s3.amazonaws.com"), ("Content-Length", "9")],
+            b"\x00\x01",
+            1024,
+            &mut buffer,
+        )
+        .unwrap();
+        let response = parse_any_response_ref(&buffer).unwrap();
+        assert_eq!(response.status_code(), Some(400));
+        assert_eq!(response.body, b"{}");
+
+        let request = server.join().unwrap();
+        assert!(
+            request.starts_with("PUT /bucket/key HTTP/1.1\r\nHost: bucket.s3.amazonaws.com\r\n")
+        );
+        assert_eq!(request.matches("Content-Length").count(), 1);
+        assert!(request.ends_with("Content-Length: 2\r\nConnection: close\r\n\r\n\u{0}\u{1}"));
+    }
+
+    #[test]
+    fn test_request_stream_both_directions() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 4096];
+            while find_body_start(&request).is_none_or(|start| request.len() - start < 50_000) {
+                let n = stream.read(&mut chunk).unwrap();
+                request.extend_from_slice(&chunk[..n]);
+            }
+            let body = "y".repeat(20_000);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\n\r\n{body}trailing",
+                body.len()
+            );
+            request.len()
+        });
+
+        let upload = vec![b'z'; 50_000];
+        let mut response = request_stream(
+            &addr,
+            "PUT",
+            "/key",
+            &[],
+            &mut upload.as_slice(),
+            upload.len() as u64,
+        )
+        .unwrap();
+        assert_eq!(response.status_code(), Some(206));
+        assert_eq!(response.header("content-length"), Some("20000"));
+
+        let mut body = Vec::new();
+        response.read_to_end(&mut body).unwrap();
+        assert_eq!(body.len(), 20_000);
+        assert!(body.iter().all(|b| *b == b'y'));
+        assert!(server.join().unwrap() > 50_000);
+        // Bytes past Content-Length are not part of the body
+        assert_eq!(response.read(&mut [0u8; 8]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_request_stream_short_body() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || drop(listener.accept().unwrap()));
+
+        let result = request_stream(&addr, "PUT", "/key", &[], &mut &b"abc"[..], 10);
+        assert!(
+            matches!(result, Err(HttpError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof)
+        );
+        server.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_stream_reads_body_on_demand() {
+        use std::os::unix::net::UnixStream;
+
+        let (client, mut server) = UnixStream::pair().unwrap();
+        let body = "e".repeat(3 * READ_CHUNK_SIZE);
+        let size = body.len();
+        let writer = std::thread::spawn(move || {
+            let mut request = [0u8; 1024];
+            let n = server.read(&mut request).unwrap();
+            assert!(request[..n].starts_with(b"GET /next HTTP/1.1\r\nHost: api\r\n"));
+            let _ = write!(
+                server,
+                "HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: s-1\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+        });
+
+        let mut response = get_stream_on(client, "api", "/next", size).unwrap();
+        assert_eq!(
+            response.header("lambda-runtime-aws-request-id"),
+            Some("s-1")
+        );
+        assert_eq!(response.remaining(), Some(size as u64));
+
+        let mut received = String::new();
+        response.read_to_string(&mut received).unwrap();
+        assert_eq!(received.len(), size);
+        assert_eq!(response.remaining(), Some(0));
+        writer.join().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_stream_truncated_body() {
+        use std::os::unix::net::UnixStream;
+
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc")
+            .unwrap();
+        server.shutdown(std::net::Shutdown::Write).unwrap();
+
+        let mut response = get_stream_on(client, "api", "/next", 64).unwrap();
+        let mut body = Vec::new();
+        let err = response.read_to_end(&mut body).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(body, b"abc");
+        assert_eq!(response.remaining(), Some(7));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_stream_limits() {
+        use std::os::unix::net::UnixStream;
+
+        let respond = |response: &'static [u8]| {
+            let (client, mut server) = UnixStream::pair().unwrap();
+            server.write_all(response).unwrap();
+            server.shutdown(std::net::Shutdown::Write).unwrap();
+            // Keep the server end open so the request can be written
+            (client, server)
+        };
+
+        // Advertised length over the limit: rejected before reading the body
+        let (client, _server) = respond(b"HTTP/1.1 200 OK\r\nContent-Length: 64\r\n\r\n");
+        assert!(matches!(
+            get_stream_on(client, "api", "/next", 16),
+            Err(HttpError::ResponseTooLarge { limit: 16 })
+        ));
+
+        // No Content-Length: the limit applies while reading
+        let (client, _server) = respond(b"HTTP/1.1 200 OK\r\n\r\n0123456789abcdefXYZ");
+        let mut response = get_stream_on(client, "api", "/next", 16).unwrap();
+        let err = response.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "HTTP response body exceeds 16 bytes");
+
+        let (client, _server) = respond(b"HTTP/1.1 500 Internal Server Error\r\n\r\n");
+        assert!(matches!(
+            get_stream_on(client, "api", "/next", 16),
+            Err(HttpError::InvalidResponse(msg)) if msg.contains("500")
+        ));
+    }
+
+    #[test]
+    fn test_connect_with_timeout_bounds_reads() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        // Accepts but never answers
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            std::thread::sleep(Duration::from_millis(500));
+            drop(stream);
+        });
+
+        let stream = connect_with_timeout(&addr, Duration::from_millis(50)).unwrap();
+        let mut buffer = Vec::new();
+        let result = request_into_on(stream, &addr, "GET", "/", &[], b"", 1024, &mut buffer);
+        assert!(matches!(
+            result,
+            Err(HttpError::Io(e))
+                if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+        ));
+        server.join().unwrap();
+
+        let err = connect_with_timeout(&addr, Duration::ZERO).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_post_connection_refused() {
+        let result = post("127.0.0.1:19995", "/2018-06-01/runtime/init/error", "{}");
+        assert!(matches!(result, Err(HttpError::Io(_))));
+    }
+}
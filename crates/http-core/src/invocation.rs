@@ -0,0 +1,372 @@
+// Runtime API Invocation Loop (no_std + alloc)
+//
+// The smallest complete Lambda runtime, over any `Connect` (socket.rs):
+//
+//   GET  /2018-06-01/runtime/invocation/next          -> Invocation
+//   POST /2018-06-01/runtime/invocation/{id}/response <- handler bytes
+//   POST /2018-06-01/runtime/invocation/{id}/error    <- InvocationError
+//
+// Nothing here needs std (no env vars, clocks, threads or logging), so the
+// loop builds with `default-features = false` for size-constrained and
+// non-Linux targets. Bodies are raw bytes; (de)serializing them is the
+// handler's business. The full runtimes (crates/runtime, crates/runtime-pure)
+// add contexts, retries and everything else on top of std.
+
+use crate::response::parse_response_ref;
+use crate::socket::{read_bounded, request_head, Connect, Socket};
+use crate::HttpError;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::fmt::Write as _;
+
+/// Runtime API version prefix of every path
+pub const RUNTIME_API_VERSION: &str = "2018-06-01";
+
+/// Default maximum response body size (6MB)
+///
+/// Matches Lambda's synchronous invocation payload limit. Response-streaming
+/// functions can accept up to 20MB requests; raise the limit with
+/// `get_with_limit` for those.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 6 * 1024 * 1024;
+
+/// Largest Runtime API reply to a response or error POST we read
+const MAX_ACK_SIZE: usize = 4 * 1024;
+
+/// One event fetched from `/invocation/next`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Invocation {
+    /// `Lambda-Runtime-Aws-Request-Id`
+    pub request_id: String,
+    /// `Lambda-Runtime-Deadline-Ms` (Unix epoch milliseconds, 0 if absent)
+    pub deadline_ms: u64,
+    /// `Lambda-Runtime-Invoked-Function-Arn`
+    pub invoked_function_arn: String,
+    /// `Lambda-Runtime-Trace-Id`, if tracing is active
+    pub trace_id: Option<String>,
+    /// Raw event payload
+    pub body: Vec<u8>,
+}
+
+/// Handler failure reported to `/invocation/{id}/error`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvocationError {
+    /// `errorType` (e.g. `InvalidInput`)
+    pub error_type: String,
+    /// `errorMessage`
+    pub error_message: String,
+}
+
+impl InvocationError {
+    /// Error of type `error_type` with message `error_message`
+    #[must_use]
+    pub fn new(error_type: &str, error_message: &str) -> Self {
+        Self {
+            error_type: error_type.to_string(),
+            error_message: error_message.to_string(),
+        }
+    }
+
+    /// Runtime API error document
+    /// (`{"errorMessage":"...","errorType":"..."}`)
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"errorMessage\":\"{}\",\"errorType\":\"{}\"}}",
+            escape_json(&self.error_message),
+            escape_json(&self.error_type)
+        )
+    }
+}
+
+/// Fetch the next invocation, blocking until one arrives
+///
+/// `buffer` holds the raw response and is reused across calls.
+///
+/// # Errors
+///
+/// Returns `HttpError::Io` if the connection fails,
+/// `HttpError::ResponseTooLarge` for events over
+/// [`DEFAULT_MAX_RESPONSE_SIZE`], and `HttpError::InvalidResponse` for a
+/// non-2xx status or a missing request id
+pub fn next_invocation<C: Connect>(
+    connector: &mut C,
+    buffer: &mut Vec<u8>,
+) -> Result<Invocation, HttpError> {
+    let path = format!("/{RUNTIME_API_VERSION}/runtime/invocation/next");
+    exchange(
+        connector,
+        "GET",
+        &path,
+        &[],
+        &[],
+        DEFAULT_MAX_RESPONSE_SIZE,
+        buffer,
+    )?;
+
+    let response = parse_response_ref(buffer)?;
+    let request_id = response
+        .header("lambda-runtime-aws-request-id")
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| {
+            HttpError::InvalidResponse("Missing Lambda-Runtime-Aws-Request-Id".to_string())
+        })?;
+
+    Ok(Invocation {
+        request_id: request_id.to_string(),
+        deadline_ms: response
+            .header("lambda-runtime-deadline-ms")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0),
+        invoked_function_arn: response
+            .header("lambda-runtime-invoked-function-arn")
+            .unwrap_or_default()
+            .to_string(),
+        trace_id: response.header("lambda-runtime-trace-id").map(String::from),
+        body: response.body.to_vec(),
+    })
+}
+
+/// Report the handler's result for `request_id`
+///
+/// # Errors
+///
+/// Returns `HttpError::Io` if the connection fails and
+/// `HttpError::InvalidResponse` if the Runtime API rejects the response
+pub fn post_response<C: Connect>(
+    connector: &mut C,
+    request_id: &str,
+    body: &[u8],
+) -> Result<(), HttpError> {
+    let path = format!("/{RUNTIME_API_VERSION}/runtime/invocation/{request_id}/response");
+    post_acknowledged(connector, &path, &[], body)
+}
+
+/// Report a handler failure for `request_id`
+///
+/// # Errors
+///
+/// Returns `HttpError::Io` if the connection fails and
+/// `HttpError::InvalidResponse` if the Runtime API rejects the report
+pub fn post_error<C: Connect>(
+    connector: &mut C,
+    request_id: &str,
+    error: &InvocationError,
+) -> Result<(), HttpError> {
+    let path = format!("/{RUNTIME_API_VERSION}/runtime/invocation/{request_id}/error");
+    post_acknowledged(
+        connector,
+        &path,
+        &[("Lambda-Runtime-Function-Error-Type", &error.error_type)],
+        error.to_json().as_bytes(),
+    )
+}
+
+/// Serve invocations with `handler` until the Runtime API fails
+///
+/// Returns only on an error talking to the Runtime API; handler errors are
+/// reported with [`post_error`] and the loop continues.
+///
+/// # Errors
+///
+/// Returns the first error of [`next_invocation`], [`post_response`] or
+/// [`post_error`]
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_http_core::{run, Connect, HttpError, InvocationError, Socket};
+///
+/// struct Closed;
+///
+/// impl Socket for Closed {
+///     fn write_all(&mut self, _data: &[u8]) -> Result<(), HttpError> {
+///         Ok(())
+///     }
+///     fn read(&mut self, _buf: &mut [u8]) -> Result<usize, HttpError> {
+///         Ok(0)
+///     }
+/// }
+///
+/// struct NoRuntimeApi;
+///
+/// impl Connect for NoRuntimeApi {
+///     type Socket = Closed;
+///     fn endpoint(&self) -> &str {
+///         "127.0.0.1:9001"
+///     }
+///     fn connect(&mut self) -> Result<Closed, HttpError> {
+///         Ok(Closed)
+///     }
+/// }
+///
+/// let result = run(&mut NoRuntimeApi, |invocation| {
+///     if invocation.body.is_empty() {
+///         Err(InvocationError::new("EmptyEvent", "no payload"))
+///     } else {
+///         Ok(invocation.body.clone())
+///     }
+/// });
+/// assert!(matches!(result, Err(HttpError::InvalidResponse(_))));
+/// ```
+pub fn run<C, F>(connector: &mut C, mut handler: F) -> Result<Infallible, HttpError>
+where
+    C: Connect,
+    F: FnMut(&Invocation) -> Result<Vec<u8>, InvocationError>,
+{
+    let mut buffer = Vec::new();
+    loop {
+        let invocation = next_invocation(connector, &mut buffer)?;
+        match handler(&invocation) {
+            Ok(body) => post_response(connector, &invocation.request_id, &body)?,
+            Err(error) => post_error(connector, &invocation.request_id, &error)?,
+        }
+    }
+}
+
+/// POST `body` to `path` and require a 2xx reply
+fn post_acknowledged<C: Connect>(
+    connector: &mut C,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<(), HttpError> {
+    let mut buffer = Vec::new();
+    exchange(
+        connector,
+        "POST",
+        path,
+        headers,
+        body,
+        MAX_ACK_SIZE,
+        &mut buffer,
+    )?;
+    parse_response_ref(&buffer).map(drop)
+}
+
+/// Send one request on a new connection and read the raw response into
+/// `buffer`
+fn exchange<C: Connect>(
+    connector: &mut C,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    max_body_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), HttpError> {
+    let mut socket = connector.connect()?;
+    let head = request_head(
+        connector.endpoint(),
+        method,
+        path,
+        headers,
+        body.len() as u64,
+    );
+    socket.write_all(head.as_bytes())?;
+    socket.write_all(body)?;
+
+    buffer.clear();
+    read_bounded(&mut socket, max_body_size, buffer)
+}
+
+/// Escape `value` for a JSON string literal
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::socket::tests::MockConnector;
+
+    const NEXT: &[u8] = b"HTTP/1.1 200 OK\r\n\
+        Lambda-Runtime-Aws-Request-Id: req-1\r\n\
+        Lambda-Runtime-Deadline-Ms: 1700000000000\r\n\
+        Lambda-Runtime-Invoked-Function-Arn: arn:aws:lambda:us-east-1:1:function:f\r\n\
+        Content-Length: 7\r\n\r\n\"hello\"";
+    const ACCEPTED: &[u8] =
+        b"HTTP/1.1 202 Accepted\r\nContent-Length: 16\r\n\r\n{\"status\":\"OK\"}";
+
+    #[test]
+    fn test_next_invocation() {
+        let mut connector = MockConnector::new(&[NEXT]);
+        let invocation = next_invocation(&mut connector, &mut Vec::new()).unwrap();
+
+        assert_eq!(invocation.request_id, "req-1");
+        assert_eq!(invocation.deadline_ms, 1_700_000_000_000);
+        assert_eq!(
+            invocation.invoked_function_arn,
+            "arn:aws:lambda:us-east-1:1:function:f"
+        );
+        assert_eq!(invocation.trace_id, None);
+        assert_eq!(invocation.body, b"\"hello\"");
+        assert!(connector.requests()[0].starts_with(
+            "GET /2018-06-01/runtime/invocation/next HTTP/1.1\r\nHost: 127.0.0.1:9001\r\n"
+        ));
+    }
+
+    #[test]
+    fn test_next_invocation_requires_request_id() {
+        let mut connector = MockConnector::new(&[b"HTTP/1.1 200 OK\r\n\r\n{}"]);
+        let result = next_invocation(&mut connector, &mut Vec::new());
+        assert!(matches!(result, Err(HttpError::InvalidResponse(_))));
+    }
+
+    #[test]
+    fn test_post_response_rejected() {
+        let mut connector = MockConnector::new(&[b"HTTP/1.1 413 Payload Too Large\r\n\r\n{}"]);
+        let result = post_response(&mut connector, "req-1", b"{}");
+        assert!(matches!(result, Err(HttpError::InvalidResponse(_))));
+    }
+
+    #[test]
+    fn test_run_posts_results_and_errors() {
+        let mut connector = MockConnector::new(&[NEXT, ACCEPTED, NEXT, ACCEPTED]);
+        let mut calls = 0;
+        let result = run(&mut connector, |invocation| {
+            calls += 1;
+            if calls == 1 {
+                Ok(invocation.body.clone())
+            } else {
+                Err(InvocationError::new("Bad\"Input", "line\nbreak"))
+            }
+        });
+
+        // The connector runs dry on the third /next
+        assert!(matches!(result, Err(HttpError::InvalidResponse(_))));
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[1].starts_with("POST /2018-06-01/runtime/invocation/req-1/response "));
+        assert!(requests[1].ends_with("\r\n\r\n\"hello\""));
+        assert!(requests[3].starts_with("POST /2018-06-01/runtime/invocation/req-1/error "));
+        assert!(requests[3].contains("Lambda-Runtime-Function-Error-Type: Bad\"Input\r\n"));
+        assert!(requests[3].ends_with(
+            "\r\n\r\n{\"errorMessage\":\"line\\nbreak\",\"errorType\":\"Bad\\\"Input\"}"
+        ));
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("a\"b\\c\u{1}"), "a\\\"b\\\\c\\u0001");
+        assert_eq!(
+            InvocationError::new("T", "m").to_json(),
+            "{\"errorMessage\":\"m\",\"errorType\":\"T\"}"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+// Ruchy Lambda HTTP Core
+// Shared TCP/HTTP plumbing for the Lambda Runtime API
+//
+// Consumed by both runtimes so there is ONE parser and ONE set of parser tests:
+// - crates/runtime (hand-written Rust)
+// - crates/runtime-pure (transpiled from Ruchy)
+//
+// This crate ONLY supports what the runtimes need:
+// - Blocking HTTP/1.1 GET/POST over plain TCP
+// - A generic request (any method/status) for outbound calls through local
+//   endpoints and proxies
+// - `Connection: close` (one request per connection), except the keep-alive
+//   connection pipelining a response POST with the next GET (pipeline.rs)
+//
+// NOT supported (not needed for Lambda):
+// - HTTPS/TLS (Lambda Runtime API uses plain HTTP internally)
+// - Redirects, cookies, compression, chunked encoding, etc.
+//
+// With "std", the client functions' `*_on` variants take any `Transport`
+// (transport.rs) and a `Dial` opens them, so callers can swap TCP for Unix
+// sockets or in-memory streams.
+//
+// Without the default "std" feature the crate is `no_std` + `alloc`: the
+// parser and the invocation loop (invocation.rs) over a caller-provided
+// `Connect` (socket.rs) remain; the std::net client functions are dropped.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![forbid(unsafe_code)]
+#![warn(missing_docs, clippy::all, clippy::pedantic, clippy::cargo)]
+#![allow(clippy::module_name_repetitions, clippy::multiple_crate_versions)]
+
+//! Minimal blocking HTTP/1.1 core for the AWS Lambda Runtime API
+//!
+//! Shared by `ruchy-lambda-runtime` and `ruchy-lambda-runtime-pure`. Each
+//! runtime builds its own invocation context from [`Response::headers`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ruchy_lambda_http_core::parse_response;
+//!
+//! let raw = b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: req-1\r\n\r\n{}";
+//! let response = parse_response(raw).unwrap();
+//! assert_eq!(response.header("lambda-runtime-aws-request-id"), Some("req-1"));
+//! assert_eq!(response.body, "{}");
+//! ```
+//!
+//! # Without std
+//!
+//! With `default-features = false` the crate is `no_std` + `alloc`. The TCP
+//! client functions are unavailable; [`run`] drives the invocation loop over
+//! any [`Connect`] implementation instead.
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod client;
+mod invocation;
+#[cfg(feature = "std")]
+mod pipeline;
+mod response;
+mod socket;
+#[cfg(feature = "std")]
+mod transport;
+
+#[cfg(feature = "std")]
+pub use client::{
+    connect_with_timeout, get, get_into, get_into_on, get_on, get_stream, get_stream_on,
+    get_with_limit, post, post_with_headers, post_with_headers_on, request_into, request_into_on,
+    request_stream, request_stream_on, ResponseStream, MAX_HEAD_SIZE,
+};
+pub use invocation::{
+    next_invocation, post_error, post_response, run, Invocation, InvocationError,
+    DEFAULT_MAX_RESPONSE_SIZE, RUNTIME_API_VERSION,
+};
+#[cfg(feature = "std")]
+pub use pipeline::PersistentConnection;
+pub use response::{
+    parse_any_response_ref, parse_response, parse_response_ref, Response, ResponseRef,
+};
+#[cfg(not(feature = "std"))]
+pub use socket::SocketError;
+#[cfg(feature = "std")]
+pub use socket::TcpConnector;
+pub use socket::{Connect, Socket};
+#[cfg(feature = "std")]
+pub use transport::{Dial, TcpDialer, Transport};
+
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Minimal HTTP client error
+#[derive(Debug)]
+pub enum HttpError {
+    /// I/O error
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// I/O error of a [`Socket`]
+    #[cfg(not(feature = "std"))]
+    Io(SocketError),
+    /// Invalid response
+    InvalidResponse(String),
+    /// Response body exceeded the configured maximum size (bytes)
+    ResponseTooLarge {
+        /// Maximum body size that was exceeded
+        limit: usize,
+    },
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for HttpError {
+    fn from(err: io::Error) -> Self {
+        HttpError::Io(err)
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::Io(e) => write!(f, "HTTP I/O error: {e}"),
+            HttpError::InvalidResponse(msg) => write!(f, "Invalid HTTP response: {msg}"),
+            HttpError::ResponseTooLarge { limit } => {
+                write!(f, "HTTP response body exceeds {limit} bytes")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HttpError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_http_error_display() {
+        let error = HttpError::InvalidResponse("test error".to_string());
+        let msg = format!("{error}");
+        assert!(msg.contains("Invalid HTTP response"));
+        assert!(msg.contains("test error"));
+    }
+
+    #[test]
+    fn test_response_too_large_display() {
+        let error = HttpError::ResponseTooLarge { limit: 1024 };
+        assert_eq!(error.to_string(), "HTTP response body exceeds 1024 bytes");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_http_error_io_display() {
+        let io_error =
+            std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "Connection refused");
+        let error = HttpError::Io(io_error);
+        let msg = format!("{error}");
+        assert!(msg.contains("HTTP I/O error"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_http_error_from_io() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::TimedOut, "Timed out");
+        let http_error: HttpError = io_error.into();
+        assert!(matches!(http_error, HttpError::Io(_)));
+    }
+}
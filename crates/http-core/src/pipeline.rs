@@ -0,0 +1,429 @@
+// Keep-alive connection for pipelining the response POST and the next GET
+//
+// Every other request in this crate sends `Connection: close`. In the warm
+// invocation loop the request after a response POST is always the `/next`
+// GET on the same API, so writing both at once (HTTP/1.1 pipelining) lets
+// the Runtime API process the POST while the GET is already queued behind
+// it, saving a round trip and a connect per invocation.
+//
+// Responses are framed by `Content-Length`; bytes read past one response
+// are kept for the next. A response without a length is read to EOF and,
+// like `Connection: close` or any error, ends reuse of the connection.
+
+use crate::client::{post_head, write_all_vectored, MAX_HEAD_SIZE};
+use crate::response::parse_any_response_ref;
+use crate::socket::{find_body_start, READ_CHUNK_SIZE};
+use crate::transport::Transport;
+use crate::HttpError;
+use std::io::{self, IoSlice};
+
+/// Keep-alive connection carrying several requests in flight
+///
+/// Requests are written with [`send_get`](Self::send_get) and
+/// [`send_post_then_get`](Self::send_post_then_get); responses come back in
+/// request order through [`read_response_into`](Self::read_response_into).
+#[derive(Debug)]
+pub struct PersistentConnection<T> {
+    stream: T,
+    /// Bytes read past the end of the previous response
+    buffered: Vec<u8>,
+    reusable: bool,
+}
+
+impl<T: Transport> PersistentConnection<T> {
+    /// Wrap a connected stream
+    #[must_use]
+    pub fn new(stream: T) -> Self {
+        Self {
+            stream,
+            buffered: Vec::new(),
+            reusable: true,
+        }
+    }
+
+    /// Whether further requests may be sent on this connection
+    ///
+    /// False once the server asked to close, a response was delimited by
+    /// EOF, or any read or write failed.
+    #[must_use]
+    pub fn is_reusable(&self) -> bool {
+        self.reusable
+    }
+
+    /// Send a keep-alive GET request
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::Io` if the write fails
+    pub fn send_get(&mut self, endpoint: &str, path: &str) -> Result<(), HttpError> {
+        let request = get_head(endpoint, path);
+        let result = self.write(&mut [IoSlice::new(request.as_bytes())]);
+        self.settle(result)
+    }
+
+    /// Send a JSON POST and, in the same write, the GET that follows it
+    ///
+    /// The head, `body` and the GET go out as one vectored write. Read the
+    /// POST response first, then the GET response.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::Io` if the write fails
+    pub fn send_post_then_get(
+        &mut self,
+        endpoint: &str,
+        post_path: &str,
+        body: &str,
+        headers: &[(&str, &str)],
+        get_path: &str,
+    ) -> Result<(), HttpError> {
+        let post = post_head(endpoint, post_path, body.len(), headers, true);
+        let get = get_head(endpoint, get_path);
+        let result = self.write(&mut [
+            IoSlice::new(post.as_bytes()),
+            IoSlice::new(body.as_bytes()),
+            IoSlice::new(get.as_bytes()),
+        ]);
+        self.settle(result)
+    }
+
+    /// Read the next response (head and body) into `buffer`
+    ///
+    /// `buffer` is cleared first. Returns `Ok(false)` if the server closed
+    /// or reset the connection before sending any byte of a response, as it
+    /// does for an idle keep-alive connection it timed out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError` on I/O failure, a malformed or chunked response,
+    /// a head over [`MAX_HEAD_SIZE`], or a body over `max_body_size`
+    pub fn read_response_into(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        max_body_size: usize,
+    ) -> Result<bool, HttpError> {
+        buffer.clear();
+        let result = self.read_response(buffer, max_body_size);
+        if !matches!(result, Ok(true)) {
+            self.reusable = false;
+        }
+        result
+    }
+
+    fn write(&mut self, buffers: &mut [IoSlice<'_>]) -> Result<(), HttpError> {
+        write_all_vectored(&mut self.stream, buffers)?;
+        self.stream.flush()?;
+        Ok(())
+    }
+
+    fn settle(&mut self, result: Result<(), HttpError>) -> Result<(), HttpError> {
+        if result.is_err() {
+            self.reusable = false;
+        }
+        result
+    }
+
+    fn read_response(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        max_body_size: usize,
+    ) -> Result<bool, HttpError> {
+        buffer.append(&mut self.buffered);
+        let mut chunk = [0u8; READ_CHUNK_SIZE];
+
+        let body_start = loop {
+            if let Some(start) = find_body_start(buffer) {
+                break start;
+            }
+            if buffer.len() > MAX_HEAD_SIZE {
+                return Err(HttpError::InvalidResponse(format!(
+                    "Response head exceeds {MAX_HEAD_SIZE} bytes"
+                )));
+            }
+            let n = match self.stream.read(&mut chunk) {
+                Ok(n) => n,
+                Err(e) if buffer.is_empty() && is_closed(&e) => 0,
+                Err(e) => return Err(e.into()),
+            };
+            if n == 0 {
+                if buffer.is_empty() {
+                    return Ok(false);
+                }
+                return Err(HttpError::InvalidResponse(
+                    "No body separator found".to_string(),
+                ));
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+        };
+
+        let head = parse_any_response_ref(&buffer[..body_start])?;
+        if head.header("transfer-encoding").is_some() {
+            return Err(HttpError::InvalidResponse(
+                "Transfer-Encoding is not supported".to_string(),
+            ));
+        }
+        let close = head
+            .header("connection")
+            .is_some_and(|value| value.eq_ignore_ascii_case("close"));
+        let content_length = match head.header("content-length") {
+            Some(value) => Some(value.trim().parse::<usize>().map_err(|_| {
+                HttpError::InvalidResponse(format!("Invalid Content-Length: {value}"))
+            })?),
+            None if matches!(head.status_code(), Some(204 | 304)) => Some(0),
+            None => None,
+        };
+
+        if let Some(length) = content_length {
+            if length > max_body_size {
+                return Err(HttpError::ResponseTooLarge {
+                    limit: max_body_size,
+                });
+            }
+            let end = body_start + length;
+            while buffer.len() < end {
+                let n = self.stream.read(&mut chunk)?;
+                if n == 0 {
+                    return Err(HttpError::InvalidResponse(
+                        "Connection closed before the end of the body".to_string(),
+                    ));
+                }
+                buffer.extend_from_slice(&chunk[..n]);
+            }
+            self.buffered.extend_from_slice(&buffer[end..]);
+            buffer.truncate(end);
+            if close {
+                self.reusable = false;
+            }
+        } else {
+            // Delimited by EOF: nothing can follow on this connection
+            self.reusable = false;
+            loop {
+                if buffer.len() - body_start > max_body_size {
+                    return Err(HttpError::ResponseTooLarge {
+                        limit: max_body_size,
+                    });
+                }
+                let n = self.stream.read(&mut chunk)?;
+                if n == 0 {
+                    break;
+                }
+                buffer.extend_from_slice(&chunk[..n]);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Whether `error` means the peer closed the connection
+fn is_closed(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::UnexpectedEof
+    )
+}
+
+/// Request line and headers of a keep-alive GET
+fn get_head(endpoint: &str, path: &str) -> String {
+    format!("GET {path} HTTP/1.1\r\nHost: {endpoint}\r\n\r\n")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::thread;
+
+    const NEXT: &str = "/2018-06-01/runtime/invocation/next";
+
+    /// Read from `stream` until `count` request heads (and their bodies)
+    /// have arrived; returns the raw bytes
+    fn read_requests(stream: &mut UnixStream, count: usize) -> String {
+        let mut received = Vec::new();
+        let mut chunk = [0u8; 4096];
+        while complete_requests(&received) < count {
+            let n = stream.read(&mut chunk).unwrap();
+            assert!(n > 0, "client closed early");
+            received.extend_from_slice(&chunk[..n]);
+        }
+        String::from_utf8(received).unwrap()
+    }
+
+    fn complete_requests(data: &[u8]) -> usize {
+        let mut rest = data;
+        let mut count = 0;
+        while let Some(start) = find_body_start(rest) {
+            let head = String::from_utf8_lossy(&rest[..start]).to_lowercase();
+            let length = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map_or(0, |value| value.trim().parse::<usize>().unwrap());
+            if rest.len() < start + length {
+                break;
+            }
+            rest = &rest[start + length..];
+            count += 1;
+        }
+        count
+    }
+
+    fn event(request_id: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: {request_id}\r\nContent-Length: 2\r\n\r\n{{}}"
+        )
+    }
+
+    const ACCEPTED: &str = "HTTP/1.1 202 Accepted\r\nContent-Length: 15\r\n\r\n{\"status\":\"OK\"}";
+
+    #[test]
+    fn test_pipelined_invocations_share_one_connection() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        let api = thread::spawn(move || {
+            let first = read_requests(&mut server, 1);
+            server.write_all(event("req-1").as_bytes()).unwrap();
+            let pipelined = read_requests(&mut server, 2);
+            server
+                .write_all(format!("{ACCEPTED}{}", event("req-2")).as_bytes())
+                .unwrap();
+            (first, pipelined)
+        });
+
+        let mut connection = PersistentConnection::new(client);
+        let mut buffer = Vec::new();
+        connection.send_get("127.0.0.1:9001", NEXT).unwrap();
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert!(buffer.ends_with(b"req-1\r\nContent-Length: 2\r\n\r\n{}"));
+
+        connection
+            .send_post_then_get(
+                "127.0.0.1:9001",
+                "/2018-06-01/runtime/invocation/req-1/response",
+                "\"done\"",
+                &[],
+                NEXT,
+            )
+            .unwrap();
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert!(buffer.starts_with(b"HTTP/1.1 202 Accepted"));
+        assert!(buffer.ends_with(b"{\"status\":\"OK\"}"));
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert_eq!(buffer, event("req-2").as_bytes());
+        assert!(connection.is_reusable());
+
+        let (first, pipelined) = api.join().unwrap();
+        assert_eq!(
+            first,
+            format!("GET {NEXT} HTTP/1.1\r\nHost: 127.0.0.1:9001\r\n\r\n")
+        );
+        assert!(pipelined
+            .starts_with("POST /2018-06-01/runtime/invocation/req-1/response HTTP/1.1\r\n"));
+        assert!(!pipelined.to_lowercase().contains("connection: close"));
+        assert!(pipelined.ends_with(&format!(
+            "\r\n\r\n\"done\"GET {NEXT} HTTP/1.1\r\nHost: 127.0.0.1:9001\r\n\r\n"
+        )));
+    }
+
+    #[test]
+    fn test_eof_before_response_is_not_an_error() {
+        let (client, server) = UnixStream::pair().unwrap();
+        drop(server);
+
+        let mut connection = PersistentConnection::new(client);
+        let mut buffer = b"stale".to_vec();
+        assert!(!connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert!(buffer.is_empty());
+        assert!(!connection.is_reusable());
+    }
+
+    #[test]
+    fn test_connection_close_ends_reuse() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}")
+            .unwrap();
+
+        let mut connection = PersistentConnection::new(client);
+        let mut buffer = Vec::new();
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert!(buffer.ends_with(b"\r\n\r\n{}"));
+        assert!(!connection.is_reusable());
+    }
+
+    #[test]
+    fn test_response_without_length_is_read_to_eof() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\n\r\n{\"a\":1}")
+            .unwrap();
+        drop(server);
+
+        let mut connection = PersistentConnection::new(client);
+        let mut buffer = Vec::new();
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert!(buffer.ends_with(b"\r\n\r\n{\"a\":1}"));
+        assert!(!connection.is_reusable());
+    }
+
+    #[test]
+    fn test_no_content_without_length_keeps_connection() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(
+                b"HTTP/1.1 204 No Content\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+            )
+            .unwrap();
+
+        let mut connection = PersistentConnection::new(client);
+        let mut buffer = Vec::new();
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert_eq!(buffer, b"HTTP/1.1 204 No Content\r\n\r\n");
+        assert!(connection.read_response_into(&mut buffer, 1024).unwrap());
+        assert!(buffer.starts_with(b"HTTP/1.1 200 OK"));
+        assert!(connection.is_reusable());
+    }
+
+    #[test]
+    fn test_oversized_body_is_rejected() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 4096\r\n\r\n")
+            .unwrap();
+
+        let mut connection = PersistentConnection::new(client);
+        let result = connection.read_response_into(&mut Vec::new(), 1024);
+        assert!(matches!(
+            result,
+            Err(HttpError::ResponseTooLarge { limit: 1024 })
+        ));
+        assert!(!connection.is_reusable());
+    }
+
+    #[test]
+    fn test_chunked_response_is_rejected() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\n{}\r\n0\r\n\r\n")
+            .unwrap();
+
+        let mut connection = PersistentConnection::new(client);
+        let result = connection.read_response_into(&mut Vec::new(), 1024);
+        assert!(matches!(result, Err(HttpError::InvalidResponse(_))));
+        assert!(!connection.is_reusable());
+    }
+
+    #[test]
+    fn test_truncated_body_is_an_error() {
+        let (client, mut server) = UnixStream::pair().unwrap();
+        server
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n{}")
+            .unwrap();
+        drop(server);
+
+        let mut connection = PersistentConnection::new(client);
+        let result = connection.read_response_into(&mut Vec::new(), 1024);
+        assert!(matches!(result, Err(HttpError::InvalidResponse(_))));
+    }
+}
@@ -0,0 +1,583 @@
+// HTTP/1.x response parsing
+//
+// Format: status line, "Name: value" header lines, blank line, raw body.
+// The body is returned untouched (no line-ending normalization).
+//
+// The Runtime API sends CRLF, but local emulators and test servers are
+// sloppier, so the header block is parsed leniently:
+// - lines may end in LF alone (the blank line too)
+// - HTTP/1.0 status lines are accepted
+// - whitespace around names and values is trimmed
+// - lines starting with a space or tab continue the previous header value
+//   (obs-fold, RFC 7230 section 3.2.4)
+
+use crate::HttpError;
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Parsed HTTP response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// Status line (e.g., "HTTP/1.1 200 OK")
+    pub status_line: String,
+    /// Header `(name, value)` pairs in wire order, names as sent, values
+    /// trimmed, folded values joined with a single space
+    pub headers: Vec<(String, String)>,
+    /// Raw response body
+    pub body: String,
+}
+
+impl Response {
+    /// Look up a header value by name (case-insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate header `(name, value)` pairs as string slices
+    pub fn header_pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Header block scanner state
+#[derive(Clone, Copy)]
+enum Scan {
+    /// Inside a line
+    Text,
+    /// After a CR that ended a line
+    Cr,
+    /// At the start of a line
+    LineStart,
+    /// After a CR at the start of a line
+    BlankCr,
+}
+
+/// Find the end of the header block
+///
+/// Returns `(head_len, body_start)`: the length of the status line and
+/// headers without the final line break, and the offset of the first body
+/// byte. The block ends at the first empty line, terminated by CRLF or LF.
+pub(crate) fn find_head_end(data: &[u8]) -> Option<(usize, usize)> {
+    let mut state = Scan::Text;
+    let mut line_end = 0;
+
+    for (i, &byte) in data.iter().enumerate() {
+        state = match (state, byte) {
+            (Scan::Text | Scan::Cr, b'\r') => {
+                line_end = i;
+                Scan::Cr
+            }
+            (Scan::Text, b'\n') => {
+                line_end = i;
+                Scan::LineStart
+            }
+            (Scan::Cr, b'\n') => Scan::LineStart,
+            (Scan::LineStart | Scan::BlankCr, b'\n') => return Some((line_end, i + 1)),
+            (Scan::LineStart, b'\r') => Scan::BlankCr,
+            _ => Scan::Text,
+        };
+    }
+    None
+}
+
+/// Whether a status line is `HTTP/1.x 2xx`
+pub(crate) fn is_success_status(status_line: &str) -> bool {
+    let Some(rest) = status_line.trim_start().strip_prefix("HTTP/1.") else {
+        return false;
+    };
+    let rest = rest.trim_start_matches(|c: char| c.is_ascii_digit());
+    let code = rest.trim_start_matches([' ', '\t']);
+    if code.len() == rest.len() {
+        return false;
+    }
+
+    let code = code.as_bytes();
+    code.len() >= 3
+        && code[0] == b'2'
+        && code[1..3].iter().all(u8::is_ascii_digit)
+        && code.get(3).is_none_or(u8::is_ascii_whitespace)
+}
+
+/// Status code of an `HTTP/1.x <code> ...` status line
+pub(crate) fn status_code(status_line: &str) -> Option<u16> {
+    let rest = status_line.trim_start().strip_prefix("HTTP/1.")?;
+    let code = rest
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches([' ', '\t']);
+    let digits = code.get(..3)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit())
+        || code
+            .as_bytes()
+            .get(3)
+            .is_some_and(|b| !b.is_ascii_whitespace())
+    {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Split the status line off a header block
+fn split_status_line(head: &str) -> (&str, &str) {
+    let (status_line, fields) = head.split_once('\n').unwrap_or((head, ""));
+    (status_line.trim_end_matches('\r'), fields)
+}
+
+/// Iterator over the `(name, value)` fields of a raw header block
+///
+/// Folded values are returned as one slice, line breaks included; lines
+/// without a colon are skipped.
+pub(crate) struct HeaderFields<'a> {
+    rest: &'a str,
+}
+
+/// Iterate the fields of a raw header block (status line excluded)
+pub(crate) fn header_fields(fields: &str) -> HeaderFields<'_> {
+    HeaderFields { rest: fields }
+}
+
+impl<'a> Iterator for HeaderFields<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.rest.is_empty() {
+            // A field runs to the first line break not followed by SP / HT
+            let mut from = 0;
+            let field_end = loop {
+                match self.rest[from..].find('\n') {
+                    None => break self.rest.len(),
+                    Some(offset) => {
+                        let newline = from + offset;
+                        if matches!(self.rest.as_bytes().get(newline + 1), Some(b' ' | b'\t')) {
+                            from = newline + 1;
+                        } else {
+                            break newline;
+                        }
+                    }
+                }
+            };
+
+            let field = &self.rest[..field_end];
+            self.rest = self.rest.get(field_end + 1..).unwrap_or("");
+            if let Some((name, value)) = field.split_once(':') {
+                return Some((name.trim(), value.trim()));
+            }
+        }
+        None
+    }
+}
+
+/// Join the lines of a folded header value with single spaces
+fn unfold(value: &str) -> Cow<'_, str> {
+    if value.contains('\n') {
+        let lines: Vec<&str> = value
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        Cow::Owned(lines.join(" "))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Parse a raw HTTP response, requiring a 2xx status
+///
+/// # Errors
+///
+/// Returns `HttpError::InvalidResponse` if the response is empty, non-2xx,
+/// or missing the header/body separator
+pub fn parse_response(data: &[u8]) -> Result<Response, HttpError> {
+    if data.is_empty() {
+        return Err(HttpError::InvalidResponse("Empty response".to_string()));
+    }
+
+    // Check for 2xx status code
+    let first_line = data.split(|&b| b == b'\n').next().unwrap_or(data);
+    let status_line = String::from_utf8_lossy(first_line);
+    if !is_success_status(&status_line) {
+        return Err(HttpError::InvalidResponse(format!(
+            "Non-2xx status: {}",
+            status_line.trim_end()
+        )));
+    }
+
+    let (head_len, body_start) = find_head_end(data)
+        .ok_or_else(|| HttpError::InvalidResponse("No body separator found".to_string()))?;
+
+    let head = String::from_utf8_lossy(&data[..head_len]);
+    let (status_line, fields) = split_status_line(&head);
+
+    // Values keep everything after the first colon, since values such as
+    // function ARNs contain colons
+    let headers = header_fields(fields)
+        .map(|(name, value)| (name.to_string(), unfold(value).into_owned()))
+        .collect();
+
+    Ok(Response {
+        status_line: status_line.to_string(),
+        headers,
+        body: String::from_utf8_lossy(&data[body_start..]).into_owned(),
+    })
+}
+
+/// HTTP response borrowed from a raw response buffer
+///
+/// Zero-copy counterpart of [`Response`]: the header block and body are
+/// slices of the buffer passed to [`parse_response_ref`], so parsing
+/// performs no heap allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseRef<'a> {
+    /// Status line (e.g., "HTTP/1.1 200 OK")
+    pub status_line: &'a str,
+    /// Raw header block (`Name: value` lines, without the trailing blank line)
+    pub head: &'a str,
+    /// Raw response body bytes
+    pub body: &'a [u8],
+}
+
+impl<'a> ResponseRef<'a> {
+    /// Look up a header value by name (case-insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.header_pairs()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Iterate header `(name, value)` pairs in wire order, trimmed
+    ///
+    /// Folded values are borrowed as sent, line breaks included;
+    /// [`parse_response`] joins them with spaces.
+    pub fn header_pairs(&self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        header_fields(self.head)
+    }
+
+    /// Numeric status code (e.g. `404`), if the status line has one
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        status_code(self.status_line)
+    }
+}
+
+/// Parse a raw HTTP response without copying, requiring a 2xx status
+///
+/// Unlike [`parse_response`], the status line and headers must be valid
+/// UTF-8 (they are borrowed as `&str`); the body is left as raw bytes.
+///
+/// # Errors
+///
+/// Returns `HttpError::InvalidResponse` if the response is empty, non-2xx,
+/// missing the header/body separator, or has a non-UTF-8 header block
+pub fn parse_response_ref(data: &[u8]) -> Result<ResponseRef<'_>, HttpError> {
+    let response = parse_any_response_ref(data)?;
+
+    // Check for 2xx status code
+    if !is_success_status(response.status_line) {
+        return Err(HttpError::InvalidResponse(format!(
+            "Non-2xx status: {}",
+            response.status_line
+        )));
+    }
+
+    Ok(response)
+}
+
+/// Parse a raw HTTP response without copying, whatever its status
+///
+/// For callers that read error bodies (see [`ResponseRef::status_code`]).
+///
+/// # Errors
+///
+/// Returns `HttpError::InvalidResponse` if the response is empty, missing
+/// the header/body separator, or has a non-UTF-8 header block
+pub fn parse_any_response_ref(data: &[u8]) -> Result<ResponseRef<'_>, HttpError> {
+    if data.is_empty() {
+        return Err(HttpError::InvalidResponse("Empty response".to_string()));
+    }
+
+    let (head_len, body_start) = find_head_end(data)
+        .ok_or_else(|| HttpError::InvalidResponse("No body separator found".to_string()))?;
+
+    let head = core::str::from_utf8(&data[..head_len])
+        .map_err(|e| HttpError::InvalidResponse(format!("Non-UTF-8 headers: {e}")))?;
+    let (status_line, head) = split_status_line(head);
+
+    Ok(ResponseRef {
+        status_line,
+        head,
+        body: &data[body_start..],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expect_invalid(data: &[u8], expected: &str) {
+        match parse_response(data) {
+            Err(HttpError::InvalidResponse(msg)) => assert!(msg.contains(expected), "{msg}"),
+            other => panic!("Expected InvalidResponse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_response_valid() {
+        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\n{\"test\":true}";
+        let parsed = parse_response(response).unwrap();
+        assert_eq!(parsed.status_line, "HTTP/1.1 200 OK");
+        assert_eq!(parsed.body, "{\"test\":true}");
+    }
+
+    #[test]
+    fn test_parse_response_202_empty_body() {
+        let response = b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n";
+        let parsed = parse_response(response).unwrap();
+        assert_eq!(parsed.body, "");
+    }
+
+    #[test]
+    fn test_parse_response_non_2xx() {
+        expect_invalid(b"HTTP/1.1 404 Not Found\r\n\r\nNot found", "Non-2xx status");
+    }
+
+    #[test]
+    fn test_parse_response_empty() {
+        expect_invalid(b"", "Empty response");
+    }
+
+    #[test]
+    fn test_parse_response_no_body_separator() {
+        expect_invalid(b"HTTP/1.1 200 OK\r\nContent-Length: 0", "No body separator");
+    }
+
+    #[test]
+    fn test_parse_response_no_headers() {
+        let parsed = parse_response(b"HTTP/1.1 200 OK\r\n\r\n{}").unwrap();
+        assert!(parsed.headers.is_empty());
+        assert_eq!(parsed.body, "{}");
+    }
+
+    #[test]
+    fn test_parse_response_header_case_insensitive() {
+        let response =
+            b"HTTP/1.1 200 OK\r\nLAMBDA-RUNTIME-AWS-REQUEST-ID: test-456\r\n\r\n{\"data\":true}";
+        let parsed = parse_response(response).unwrap();
+        assert_eq!(
+            parsed.header("Lambda-Runtime-Aws-Request-Id"),
+            Some("test-456")
+        );
+        assert_eq!(parsed.body, "{\"data\":true}");
+    }
+
+    #[test]
+    fn test_parse_response_header_whitespace() {
+        let response =
+            b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id:   test-789  \r\n\r\n{\"ok\":true}";
+        let parsed = parse_response(response).unwrap();
+        assert_eq!(
+            parsed.header("lambda-runtime-aws-request-id"),
+            Some("test-789")
+        );
+    }
+
+    #[test]
+    fn test_parse_response_header_value_with_colons() {
+        let response = b"HTTP/1.1 200 OK\r\nLambda-Runtime-Invoked-Function-Arn: arn:aws:lambda:us-east-1:123456789012:function:fn\r\n\r\n{}";
+        let parsed = parse_response(response).unwrap();
+        assert_eq!(
+            parsed.header("lambda-runtime-invoked-function-arn"),
+            Some("arn:aws:lambda:us-east-1:123456789012:function:fn")
+        );
+    }
+
+    #[test]
+    fn test_parse_response_missing_header() {
+        let parsed = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}").unwrap();
+        assert_eq!(parsed.header("lambda-runtime-aws-request-id"), None);
+    }
+
+    #[test]
+    fn test_parse_response_multiple_headers_in_order() {
+        let response = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nLambda-Runtime-Aws-Request-Id: multi-header\r\nX-Custom: value\r\n\r\n{\"multi\":true}";
+        let parsed = parse_response(response).unwrap();
+        let names: Vec<&str> = parsed.header_pairs().map(|(name, _)| name).collect();
+        assert_eq!(
+            names,
+            ["Content-Type", "Lambda-Runtime-Aws-Request-Id", "X-Custom"]
+        );
+        assert_eq!(parsed.header("x-custom"), Some("value"));
+    }
+
+    #[test]
+    fn test_parse_response_body_preserves_line_endings() {
+        let response = b"HTTP/1.1 200 OK\r\n\r\nline1\r\nline2\r\n";
+        let parsed = parse_response(response).unwrap();
+        assert_eq!(parsed.body, "line1\r\nline2\r\n");
+    }
+
+    #[test]
+    fn test_parse_response_large_body() {
+        let large_body = "x".repeat(10000);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+            large_body.len(),
+            large_body
+        );
+        let parsed = parse_response(response.as_bytes()).unwrap();
+        assert_eq!(parsed.body.len(), 10000);
+    }
+
+    #[test]
+    fn test_parse_response_ref_borrows_buffer() {
+        let raw = b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: ref-1\r\nX-Custom:  value \r\n\r\n{\"a\":1}";
+        let parsed = parse_response_ref(raw).unwrap();
+        assert_eq!(parsed.status_line, "HTTP/1.1 200 OK");
+        assert_eq!(
+            parsed.header("lambda-runtime-aws-request-id"),
+            Some("ref-1")
+        );
+        assert_eq!(parsed.header("x-custom"), Some("value"));
+        assert_eq!(parsed.body, b"{\"a\":1}");
+
+        let range = raw.as_ptr_range();
+        assert!(range.contains(&parsed.body.as_ptr()));
+        assert!(range.contains(&parsed.head.as_ptr()));
+    }
+
+    #[test]
+    fn test_parse_response_ref_matches_owned_parser() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nLambda-Runtime-Invoked-Function-Arn: arn:aws:lambda:us-east-1:123456789012:function:fn\r\n\r\nline1\r\nline2";
+        let owned = parse_response(raw).unwrap();
+        let borrowed = parse_response_ref(raw).unwrap();
+        assert!(owned.header_pairs().eq(borrowed.header_pairs()));
+        assert_eq!(owned.body.as_bytes(), borrowed.body);
+    }
+
+    #[test]
+    fn test_parse_response_ref_no_headers() {
+        let parsed = parse_response_ref(b"HTTP/1.1 200 OK\r\n\r\n").unwrap();
+        assert_eq!(parsed.header_pairs().count(), 0);
+        assert!(parsed.body.is_empty());
+    }
+
+    #[test]
+    fn test_parse_response_ref_errors() {
+        assert!(matches!(
+            parse_response_ref(b""),
+            Err(HttpError::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            parse_response_ref(b"HTTP/1.1 500 Internal Server Error\r\n\r\n"),
+            Err(HttpError::InvalidResponse(msg)) if msg.contains("Non-2xx")
+        ));
+        assert!(matches!(
+            parse_response_ref(b"HTTP/1.1 200 OK\r\n"),
+            Err(HttpError::InvalidResponse(msg)) if msg.contains("No body separator")
+        ));
+        assert!(matches!(
+            parse_response_ref(b"HTTP/1.1 200 OK\r\nX: \xff\r\n\r\n"),
+            Err(HttpError::InvalidResponse(msg)) if msg.contains("Non-UTF-8")
+        ));
+    }
+
+    #[test]
+    fn test_parse_any_response_ref_status_code() {
+        let parsed = parse_any_response_ref(b"HTTP/1.1 404 Not Found\r\n\r\n<Error/>").unwrap();
+        assert_eq!(parsed.status_code(), Some(404));
+        assert_eq!(parsed.body, b"<Error/>");
+
+        assert_eq!(status_code("HTTP/1.0 204"), Some(204));
+        assert_eq!(status_code("HTTP/1.1 2000 OK"), None);
+        assert_eq!(status_code("HTTP/1.1 abc"), None);
+        assert_eq!(status_code("HTTP/2 200"), None);
+    }
+
+    #[test]
+    fn test_parse_response_ref_binary_body() {
+        let parsed = parse_response_ref(b"HTTP/1.1 200 OK\r\n\r\n\x00\xff\xfe").unwrap();
+        assert_eq!(parsed.body, b"\x00\xff\xfe");
+    }
+
+    #[test]
+    fn test_find_head_end_line_endings() {
+        assert_eq!(find_head_end(b"HTTP/1.1 200 OK\r\n\r\nx"), Some((15, 19)));
+        assert_eq!(find_head_end(b"HTTP/1.1 200 OK\n\nx"), Some((15, 17)));
+        assert_eq!(
+            find_head_end(b"HTTP/1.1 200 OK\nA: 1\r\n\nx"),
+            Some((20, 23))
+        );
+        assert_eq!(
+            find_head_end(b"HTTP/1.1 200 OK\r\nA: 1\n\r\nx"),
+            Some((21, 24))
+        );
+        assert_eq!(find_head_end(b"HTTP/1.1 200 OK\r\nA: 1\r\n"), None);
+        assert_eq!(find_head_end(b"HTTP/1.1 200 OK\r\r\n"), None);
+    }
+
+    #[test]
+    fn test_is_success_status() {
+        for line in [
+            "HTTP/1.1 200 OK",
+            "HTTP/1.0 202 Accepted",
+            "HTTP/1.1 204",
+            "HTTP/1.1  200\tOK",
+        ] {
+            assert!(is_success_status(line), "{line}");
+        }
+        for line in [
+            "HTTP/1.1 404 Not Found",
+            "HTTP/1.1 2000 OK",
+            "HTTP/1.1 20 OK",
+            "HTTP/1.1200 OK",
+            "HTTP/2 200",
+            "200 OK",
+            "",
+        ] {
+            assert!(!is_success_status(line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_parse_response_lf_only() {
+        let raw =
+            b"HTTP/1.0 200 OK\nLambda-Runtime-Aws-Request-Id: lf-1\nContent-Length: 2\n\n{}\n";
+        let parsed = parse_response(raw).unwrap();
+        assert_eq!(parsed.status_line, "HTTP/1.0 200 OK");
+        assert_eq!(parsed.header("lambda-runtime-aws-request-id"), Some("lf-1"));
+        assert_eq!(parsed.body, "{}\n");
+
+        let borrowed = parse_response_ref(raw).unwrap();
+        assert!(parsed.header_pairs().eq(borrowed.header_pairs()));
+        assert_eq!(borrowed.body, b"{}\n");
+    }
+
+    #[test]
+    fn test_parse_response_obs_fold() {
+        let raw = b"HTTP/1.1 200 OK\r\nX-Folded: first\r\n   second\r\n\tthird\r\nX-Next : value \r\n\r\n{}";
+        let parsed = parse_response(raw).unwrap();
+        assert_eq!(parsed.header("x-folded"), Some("first second third"));
+        assert_eq!(parsed.header("x-next"), Some("value"));
+        assert_eq!(parsed.headers.len(), 2);
+
+        let borrowed = parse_response_ref(raw).unwrap();
+        assert_eq!(
+            borrowed.header("x-folded"),
+            Some("first\r\n   second\r\n\tthird")
+        );
+        assert_eq!(borrowed.header("x-next"), Some("value"));
+    }
+
+    #[test]
+    fn test_parse_response_skips_lines_without_colon() {
+        let parsed = parse_response(b"HTTP/1.1 200 OK\r\ngarbage\r\nA: 1\r\n\r\n").unwrap();
+        assert_eq!(parsed.headers, [("A".to_string(), "1".to_string())]);
+    }
+}
@@ -0,0 +1,391 @@
+// Pluggable Sockets
+//
+// Requests are framed over two small traits instead of std::net so the
+// invocation loop (invocation.rs) also builds without std:
+// - `Socket`: a connected byte stream (write everything, read some)
+// - `Connect`: opens one `Socket` per request (`Connection: close`)
+//
+// With feature "std" (default) `TcpStream` is a `Socket` and `TcpConnector`
+// connects to the Runtime API over TCP. `no_std` targets implement both for
+// their own network stack and report failures as `SocketError`.
+
+use crate::response::{find_head_end, header_fields};
+use crate::HttpError;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+/// Read chunk size for the incremental response loop
+pub(crate) const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Connected byte stream requests are written to and responses read from
+pub trait Socket {
+    /// Write all of `data`
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::Io` if the connection fails
+    fn write_all(&mut self, data: &[u8]) -> Result<(), HttpError>;
+
+    /// Read up to `buf.len()` bytes into `buf`; `Ok(0)` at end of stream
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::Io` if the connection fails
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, HttpError>;
+}
+
+/// Opens connections to the Runtime API
+pub trait Connect {
+    /// Connection type
+    type Socket: Socket;
+
+    /// `host:port` of the Runtime API (sent as `Host`)
+    fn endpoint(&self) -> &str;
+
+    /// Open a new connection for one request
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::Io` if the endpoint cannot be reached
+    fn connect(&mut self) -> Result<Self::Socket, HttpError>;
+}
+
+/// I/O failure reported by a [`Socket`] or [`Connect`] without std
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketError(pub &'static str);
+
+#[cfg(not(feature = "std"))]
+impl core::fmt::Display for SocketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Socket for std::net::TcpStream {
+    fn write_all(&mut self, data: &[u8]) -> Result<(), HttpError> {
+        std::io::Write::write_all(self, data)?;
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, HttpError> {
+        Ok(std::io::Read::read(self, buf)?)
+    }
+}
+
+/// [`Connect`] over `std::net` TCP
+///
+/// # Examples
+///
+/// ```no_run
+/// use ruchy_lambda_http_core::{run, TcpConnector};
+///
+/// let mut connector = TcpConnector::new("127.0.0.1:9001");
+/// let _ = run(&mut connector, |invocation| Ok(invocation.body.clone()));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpConnector {
+    endpoint: String,
+}
+
+#[cfg(feature = "std")]
+impl TcpConnector {
+    /// Connector for the Runtime API at `endpoint` (`host:port`)
+    #[must_use]
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Connect for TcpConnector {
+    type Socket = std::net::TcpStream;
+
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn connect(&mut self) -> Result<Self::Socket, HttpError> {
+        Ok(std::net::TcpStream::connect(&self.endpoint)?)
+    }
+}
+
+/// Request line and headers of an outbound request
+pub(crate) fn request_head(
+    endpoint: &str,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    content_length: u64,
+) -> String {
+    let mut head = format!("{method} {path} HTTP/1.1\r\n");
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("host"))
+    {
+        let _ = write!(head, "Host: {endpoint}\r\n");
+    }
+    for (name, value) in headers {
+        if name.eq_ignore_ascii_case("content-length") || name.eq_ignore_ascii_case("connection") {
+            continue;
+        }
+        let value: String = value.chars().filter(|c| *c != '\r' && *c != '\n').collect();
+        let _ = write!(head, "{name}: {value}\r\n");
+    }
+    let _ = write!(
+        head,
+        "Content-Length: {content_length}\r\nConnection: close\r\n\r\n"
+    );
+    head
+}
+
+/// Read a full response from `socket` into `buffer`, failing early once the
+/// body exceeds `max_body_size`
+///
+/// The header block is not counted against the limit. Once it has arrived,
+/// an oversized `Content-Length` is rejected without reading the body, and
+/// an accepted one is reserved in `buffer` so the body grows it at most once.
+pub(crate) fn read_bounded<S: Socket + ?Sized>(
+    socket: &mut S,
+    max_body_size: usize,
+    buffer: &mut Vec<u8>,
+) -> Result<(), HttpError> {
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+    let mut body_start = None;
+
+    loop {
+        let n = socket.read(&mut chunk)?;
+        if n == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+
+        if body_start.is_none() {
+            body_start = find_body_start(buffer);
+            if let Some(start) = body_start {
+                match content_length(&buffer[..start]) {
+                    Some(len) if len > max_body_size => {
+                        return Err(HttpError::ResponseTooLarge {
+                            limit: max_body_size,
+                        });
+                    }
+                    Some(len) => buffer.reserve((start + len).saturating_sub(buffer.len())),
+                    None => {}
+                }
+            }
+        }
+
+        // Before the separator arrives, count everything as potential body so a
+        // response without one cannot grow unbounded either
+        let body_len = buffer.len() - body_start.unwrap_or(0);
+        if body_len > max_body_size {
+            return Err(HttpError::ResponseTooLarge {
+                limit: max_body_size,
+            });
+        }
+    }
+}
+
+/// Offset of the first body byte (just past the blank line), if headers are complete
+pub(crate) fn find_body_start(data: &[u8]) -> Option<usize> {
+    find_head_end(data).map(|(_, body_start)| body_start)
+}
+
+/// Parse the `Content-Length` header out of a raw header block
+fn content_length(head: &[u8]) -> Option<usize> {
+    header_fields(&String::from_utf8_lossy(head)).find_map(|(name, value)| {
+        if name.eq_ignore_ascii_case("content-length") {
+            value.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    /// Replays a canned response, reading `&[u8]` like `io::Read` does
+    impl Socket for &[u8] {
+        fn write_all(&mut self, _data: &[u8]) -> Result<(), HttpError> {
+            Ok(())
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, HttpError> {
+            let n = buf.len().min(self.len());
+            buf[..n].copy_from_slice(&self[..n]);
+            *self = &self[n..];
+            Ok(n)
+        }
+    }
+
+    /// Requests written to a [`MockConnector`]'s sockets, one per connection
+    pub(crate) type Requests = Rc<RefCell<Vec<Vec<u8>>>>;
+
+    /// In-memory connection replaying a canned response
+    #[derive(Debug)]
+    pub(crate) struct MockSocket {
+        response: Vec<u8>,
+        position: usize,
+        requests: Requests,
+    }
+
+    impl Socket for MockSocket {
+        fn write_all(&mut self, data: &[u8]) -> Result<(), HttpError> {
+            if let Some(request) = self.requests.borrow_mut().last_mut() {
+                request.extend_from_slice(data);
+            }
+            Ok(())
+        }
+
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, HttpError> {
+            let n = Socket::read(&mut &self.response[self.position..], buf)?;
+            self.position += n;
+            Ok(n)
+        }
+    }
+
+    /// Hands out one [`MockSocket`] per queued response; connecting with
+    /// nothing queued fails like a Runtime API that went away
+    #[derive(Debug, Default)]
+    pub(crate) struct MockConnector {
+        responses: Vec<Vec<u8>>,
+        pub(crate) requests: Requests,
+    }
+
+    impl MockConnector {
+        pub(crate) fn new(responses: &[&[u8]]) -> Self {
+            Self {
+                responses: responses.iter().rev().map(|r| r.to_vec()).collect(),
+                requests: Requests::default(),
+            }
+        }
+
+        /// Requests written so far, as text
+        pub(crate) fn requests(&self) -> Vec<String> {
+            self.requests
+                .borrow()
+                .iter()
+                .map(|request| String::from_utf8_lossy(request).into_owned())
+                .collect()
+        }
+    }
+
+    impl Connect for MockConnector {
+        type Socket = MockSocket;
+
+        fn endpoint(&self) -> &'static str {
+            "127.0.0.1:9001"
+        }
+
+        fn connect(&mut self) -> Result<Self::Socket, HttpError> {
+            let response = self
+                .responses
+                .pop()
+                .ok_or_else(|| HttpError::InvalidResponse("connection closed".to_string()))?;
+            self.requests.borrow_mut().push(Vec::new());
+            Ok(MockSocket {
+                response,
+                position: 0,
+                requests: Rc::clone(&self.requests),
+            })
+        }
+    }
+
+    #[test]
+    fn test_request_head() {
+        let head = request_head(
+            "127.0.0.1:9001",
+            "POST",
+            "/path",
+            &[("X-Test", "a\r\nb"), ("Connection", "keep-alive")],
+            2,
+        );
+        assert_eq!(
+            head,
+            "POST /path HTTP/1.1\r\nHost: 127.0.0.1:9001\r\nX-Test: ab\r\n\
+             Content-Length: 2\r\nConnection: close\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_read_bounded_within_limit() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}";
+        let mut buffer = Vec::new();
+        read_bounded(&mut &raw[..], 2, &mut buffer).unwrap();
+        assert_eq!(buffer, raw);
+    }
+
+    #[test]
+    fn test_read_bounded_reserves_content_length() {
+        let mut raw = b"HTTP/1.1 200 OK\r\nContent-Length: 100000\r\n\r\n".to_vec();
+        raw.extend(core::iter::repeat_n(b'x', 100_000));
+        let mut buffer = Vec::new();
+        read_bounded(&mut raw.as_slice(), 100_000, &mut buffer).unwrap();
+        // One reservation for the whole body instead of doubling per chunk
+        assert_eq!(buffer.capacity(), raw.len());
+        assert_eq!(buffer, raw);
+    }
+
+    #[test]
+    fn test_read_bounded_rejects_content_length() {
+        // Advertised length is rejected before the body is read
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 1048576\r\n\r\n";
+        let result = read_bounded(&mut &raw[..], 1024, &mut Vec::new());
+        assert!(matches!(
+            result,
+            Err(HttpError::ResponseTooLarge { limit: 1024 })
+        ));
+    }
+
+    #[test]
+    fn test_read_bounded_rejects_body_without_content_length() {
+        let mut raw = b"HTTP/1.1 200 OK\r\n\r\n".to_vec();
+        raw.extend(core::iter::repeat_n(b'x', 64 * 1024));
+        let result = read_bounded(&mut raw.as_slice(), 16 * 1024, &mut Vec::new());
+        assert!(matches!(
+            result,
+            Err(HttpError::ResponseTooLarge { limit: 16_384 })
+        ));
+    }
+
+    #[test]
+    fn test_read_bounded_rejects_missing_separator() {
+        let raw = vec![b'x'; 64 * 1024];
+        let result = read_bounded(&mut raw.as_slice(), 1024, &mut Vec::new());
+        assert!(matches!(result, Err(HttpError::ResponseTooLarge { .. })));
+    }
+
+    #[test]
+    fn test_read_bounded_headers_not_counted() {
+        let raw = b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: req-1\r\n\r\nabcd";
+        assert!(read_bounded(&mut &raw[..], 4, &mut Vec::new()).is_ok());
+
+        let lf_only = b"HTTP/1.0 200 OK\nLambda-Runtime-Aws-Request-Id: req-1\n\nabcd";
+        assert!(read_bounded(&mut &lf_only[..], 4, &mut Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn test_content_length_case_insensitive() {
+        assert_eq!(
+            content_length(b"HTTP/1.1 200 OK\r\ncontent-length: 42\r\n"),
+            Some(42)
+        );
+        assert_eq!(content_length(b"HTTP/1.1 200 OK\r\n"), None);
+        assert_eq!(
+            content_length(b"HTTP/1.0 200 OK\nContent-Length:  7 \n"),
+            Some(7)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+// Pluggable Transports
+//
+// The std client functions (client.rs) talk to any connected byte stream,
+// not just TcpStream:
+// - `Transport`: a blocking, bidirectional stream (TcpStream, UnixStream,
+//   or an in-memory duplex in tests)
+// - `Dial`: opens one `Transport` per request (`Connection: close`);
+//   `TcpDialer` connects over TCP, closures work too
+//
+// Unlike `Socket`/`Connect` (socket.rs) this keeps std::io errors and
+// timeouts, so it is only available with feature "std".
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+/// Connected byte stream a request is written to and its response read from
+pub trait Transport: Read + Write + Send {}
+
+impl Transport for TcpStream {}
+
+#[cfg(unix)]
+impl Transport for std::os::unix::net::UnixStream {}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {}
+
+/// Opens a [`Transport`] to an endpoint
+///
+/// Implemented for closures, e.g. to reach the endpoint over a Unix socket:
+///
+/// ```no_run
+/// use ruchy_lambda_http_core::{get_on, Dial, Transport};
+/// use std::os::unix::net::UnixStream;
+///
+/// let dialer = |_endpoint: &str| {
+///     Ok(Box::new(UnixStream::connect("/tmp/runtime-api.sock")?) as Box<dyn Transport>)
+/// };
+/// let stream = dialer.dial("127.0.0.1:9001")?;
+/// let response = get_on(stream, "127.0.0.1:9001", "/2018-06-01/ping", 1024)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait Dial: Send + Sync {
+    /// Open a new connection to `endpoint` (`host:port`, sent as `Host`)
+    ///
+    /// # Errors
+    ///
+    /// Returns the I/O error if the endpoint cannot be reached
+    fn dial(&self, endpoint: &str) -> io::Result<Box<dyn Transport>>;
+}
+
+impl<F> Dial for F
+where
+    F: Fn(&str) -> io::Result<Box<dyn Transport>> + Send + Sync,
+{
+    fn dial(&self, endpoint: &str) -> io::Result<Box<dyn Transport>> {
+        self(endpoint)
+    }
+}
+
+/// [`Dial`] over `std::net` TCP (the default)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcpDialer;
+
+impl Dial for TcpDialer {
+    fn dial(&self, endpoint: &str) -> io::Result<Box<dyn Transport>> {
+        Ok(Box::new(TcpStream::connect(endpoint)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tcp_dialer_connection_refused() {
+        assert!(TcpDialer.dial("127.0.0.1:19994").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_closure_dials_unix_stream() {
+        let dialer = |_: &str| {
+            let (client, mut server) = std::os::unix::net::UnixStream::pair()?;
+            server.write_all(b"pong")?;
+            Ok(Box::new(client) as Box<dyn Transport>)
+        };
+        let mut stream = dialer.dial("ignored").unwrap();
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).unwrap();
+        assert_eq!(&reply, b"pong");
+    }
+}
@@ -0,0 +1,275 @@
+// Link-time dead-code audit
+//
+// `packager audit` builds the bootstrap with its symbols kept (-Cstrip=none
+// overrides the profile's strip = true) and reads the symbol table with nm:
+// - the largest symbols, and symbol sizes summed per crate (first path
+//   segment of the demangled name; C symbols from libc land in "[other]")
+// - known-heavy crates that the lean runtime replaced (tokio, hyper, ...);
+//   any of them linked into the bootstrap fails the audit
+//
+// Sizes are what survived LTO and --gc-sections, i.e. what ships.
+
+use crate::{cargo_build, Arch, PackageOptions, PackagerError, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Crates that must not be linked into the bootstrap
+pub const HEAVY_CRATES: &[&str] = &[
+    "tokio",
+    "hyper",
+    "reqwest",
+    "h2",
+    "rustls",
+    "openssl",
+    "lambda_runtime",
+    "aws_lambda_events",
+];
+
+/// Crate name used for symbols without a Rust path (libc, compiler builtins)
+pub const OTHER_CRATE: &str = "[other]";
+
+/// A symbol with its size in the linked binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSize {
+    /// Demangled name
+    pub name: String,
+    /// Size (bytes)
+    pub size: u64,
+}
+
+/// Summed symbol sizes of one crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateSize {
+    /// Crate name as it appears in paths (`serde_json`)
+    pub name: String,
+    /// Total size of its symbols (bytes)
+    pub size: u64,
+    /// Number of symbols
+    pub symbols: usize,
+}
+
+/// Outcome of an audit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport {
+    /// Audited (unstripped) binary
+    pub binary: PathBuf,
+    /// Largest symbols, biggest first
+    pub symbols: Vec<SymbolSize>,
+    /// Crates by total symbol size, biggest first
+    pub crates: Vec<CrateSize>,
+    /// Linked crates from [`HEAVY_CRATES`]
+    pub heavy_crates: Vec<String>,
+}
+
+impl AuditReport {
+    /// Fail if a heavy crate is linked
+    ///
+    /// # Errors
+    ///
+    /// Returns `PackagerError::HeavyCrates` listing the offenders
+    pub fn check(&self) -> Result<()> {
+        if self.heavy_crates.is_empty() {
+            Ok(())
+        } else {
+            Err(PackagerError::HeavyCrates(self.heavy_crates.clone()))
+        }
+    }
+}
+
+/// Build the bootstrap with symbols (unless `options.binary` is set) and
+/// audit it, keeping the `top` largest symbols
+///
+/// # Errors
+///
+/// Returns `PackagerError::BuildFailed` if the build fails and
+/// `PackagerError::Audit` if no nm can read the binary's symbols
+pub fn audit(options: &PackageOptions, top: usize) -> Result<AuditReport> {
+    let binary = if let Some(path) = &options.binary {
+        path.clone()
+    } else {
+        cargo_build(options, &options.profile, &["-Cstrip=none".to_string()])?;
+        options.binary_path()
+    };
+    let symbols = parse_nm(&read_symbols(&binary, options.arch)?);
+    if symbols.is_empty() {
+        return Err(PackagerError::Audit(format!(
+            "{} has no symbols (stripped?); audit an unstripped build",
+            binary.display()
+        )));
+    }
+    Ok(summarize(binary, symbols, top))
+}
+
+/// Rank `symbols` and group them per crate
+fn summarize(binary: PathBuf, mut symbols: Vec<SymbolSize>, top: usize) -> AuditReport {
+    let mut by_crate: HashMap<&str, CrateSize> = HashMap::new();
+    for symbol in &symbols {
+        let name = crate_of(&symbol.name);
+        let entry = by_crate.entry(name).or_insert_with(|| CrateSize {
+            name: name.to_string(),
+            size: 0,
+            symbols: 0,
+        });
+        entry.size += symbol.size;
+        entry.symbols += 1;
+    }
+    let mut crates: Vec<CrateSize> = by_crate.into_values().collect();
+    crates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    let mut heavy_crates: Vec<String> = crates
+        .iter()
+        .filter(|krate| HEAVY_CRATES.contains(&krate.name.as_str()))
+        .map(|krate| krate.name.clone())
+        .collect();
+    heavy_crates.sort();
+
+    symbols.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    symbols.truncate(top);
+
+    AuditReport {
+        binary,
+        symbols,
+        crates,
+        heavy_crates,
+    }
+}
+
+/// `nm` output for `binary` with the first tool that can read it
+fn read_symbols(binary: &Path, arch: Arch) -> Result<String> {
+    for tool in nm_tools(arch) {
+        let output = Command::new(tool)
+            .args(["--print-size", "--demangle"])
+            .arg(binary)
+            .output();
+        if let Ok(output) = output {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+        }
+    }
+    Err(PackagerError::Audit(format!(
+        "none of {} could read {}",
+        nm_tools(arch).join(", "),
+        binary.display()
+    )))
+}
+
+/// `nm` binaries to try, most specific first
+fn nm_tools(arch: Arch) -> &'static [&'static str] {
+    match arch {
+        Arch::Arm64 => &["aarch64-linux-gnu-nm", "llvm-nm", "nm"],
+        Arch::X86_64 => &["x86_64-linux-gnu-nm", "llvm-nm", "nm"],
+    }
+}
+
+/// Sized symbols from `nm --print-size` output (`address size type name`)
+fn parse_nm(output: &str) -> Vec<SymbolSize> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, ' ');
+            let (_address, size, _kind, name) = (
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+                fields.next()?,
+            );
+            let size = u64::from_str_radix(size, 16).ok()?;
+            (size > 0).then(|| SymbolSize {
+                name: name.trim().to_string(),
+                size,
+            })
+        })
+        .collect()
+}
+
+/// Crate a demangled symbol belongs to
+///
+/// Trait impls (`<serde_json::Error as core::fmt::Display>::fmt`) count
+/// towards the implementing type's crate.
+fn crate_of(symbol: &str) -> &str {
+    let path = symbol.trim_start_matches(['<', '&', '*']);
+    let path = path
+        .strip_prefix("mut ")
+        .or_else(|| path.strip_prefix("dyn "))
+        .unwrap_or(path);
+    match path.split_once("::") {
+        Some((krate, _))
+            if !krate.is_empty()
+                && krate.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            krate
+        }
+        _ => OTHER_CRATE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NM_OUTPUT: &str = "\
+0000000000012340 00000000000001a0 T ruchy_lambda_runtime::runtime::Runtime::next_event::h0123456789abcdef
+0000000000012500 0000000000000040 t <serde_json::error::Error as core::fmt::Display>::fmt::h0123456789abcdef
+0000000000012600 0000000000000300 T tokio::runtime::scheduler::multi_thread::worker::run::h0123456789abcdef
+0000000000012900 0000000000000020 T memcpy
+                 U __libc_start_main
+0000000000013000 0000000000000000 T _start
+";
+
+    #[test]
+    fn test_parse_nm_skips_unsized() {
+        let symbols = parse_nm(NM_OUTPUT);
+        assert_eq!(symbols.len(), 4);
+        assert_eq!(symbols[0].size, 0x1a0);
+        assert!(symbols[1].name.starts_with("<serde_json::error::Error as"));
+    }
+
+    #[test]
+    fn test_crate_of() {
+        assert_eq!(
+            crate_of("ruchy_lambda_runtime::runtime::Runtime::new"),
+            "ruchy_lambda_runtime"
+        );
+        assert_eq!(
+            crate_of("<serde_json::error::Error as core::fmt::Display>::fmt"),
+            "serde_json"
+        );
+        assert_eq!(
+            crate_of("<&mut alloc::vec::Vec<u8> as core::fmt::Debug>::fmt"),
+            "alloc"
+        );
+        assert_eq!(crate_of("memcpy"), OTHER_CRATE);
+        assert_eq!(crate_of("_ZN3foo3barE"), OTHER_CRATE);
+    }
+
+    #[test]
+    fn test_summarize_flags_heavy_crates() {
+        let report = summarize(PathBuf::from("bootstrap"), parse_nm(NM_OUTPUT), 2);
+        assert_eq!(report.symbols.len(), 2);
+        assert_eq!(report.symbols[0].size, 0x300);
+        assert_eq!(report.crates[0].name, "tokio");
+        assert!(report
+            .crates
+            .iter()
+            .any(|krate| krate.name == OTHER_CRATE && krate.size == 0x20));
+        assert_eq!(report.heavy_crates, ["tokio"]);
+        assert!(matches!(
+            report.check(),
+            Err(PackagerError::HeavyCrates(crates)) if crates == ["tokio"]
+        ));
+    }
+
+    #[test]
+    fn test_lean_binary_passes() {
+        let lean: String = NM_OUTPUT
+            .lines()
+            .filter(|line| !line.contains("tokio"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let report = summarize(PathBuf::from("bootstrap"), parse_nm(&lean), 10);
+        assert!(report.heavy_crates.is_empty());
+        assert!(report.check().is_ok());
+    }
+}
@@ -0,0 +1,171 @@
+// BOLT post-link optimization
+//
+// `--bolt` rewrites the linked bootstrap with llvm-bolt so the code a cold
+// start actually runs sits together (fewer page faults and i-cache misses
+// while the runtime initializes and serves its first invocation):
+// 1. the bootstrap is built unstripped with --emit-relocs (see cargo_build)
+// 2. llvm-bolt -instrument writes an instrumented copy, which is trained
+//    against the emulator like PGO's (training.rs)
+// 3. llvm-bolt relinks the original with the recorded profile: blocks and
+//    functions reordered, cold code split out, identical functions folded
+// 4. the result is stripped and cold-started against the (stripped)
+//    pre-BOLT binary for the report
+//
+// llvm-bolt ships with LLVM (apt install llvm-bolt / bolt), not with rustup.
+// Everything lives under target/bolt-data/<triple>/.
+
+use crate::training::{cold_start, require_host_arch, train, training_events};
+use crate::{
+    strip_binary, OptimizationReport, PackageOptions, PackagerError, Result, TrainingOptions,
+    BOOTSTRAP_NAME,
+};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Working directory of BOLT runs, relative to the workspace root
+pub const BOLT_DATA_DIR: &str = "target/bolt-data";
+
+/// Layout passes applied with the recorded profile
+const BOLT_PASSES: &[&str] = &[
+    "-reorder-blocks=ext-tsp",
+    "-reorder-functions=hfsort",
+    "-split-functions",
+    "-icf=1",
+];
+
+/// Instrument, train and relink `binary` with llvm-bolt; returns the
+/// optimized (stripped) binary
+///
+/// # Errors
+///
+/// Returns `PackagerError::Training` if the target is not the host
+/// architecture or training fails, and `PackagerError::Bolt` if llvm-bolt is
+/// missing, fails, or records no profile
+pub fn optimize(
+    options: &PackageOptions,
+    binary: &Path,
+    training: &TrainingOptions,
+) -> Result<(PathBuf, OptimizationReport)> {
+    require_host_arch(options)?;
+    let events = training_events(training)?;
+    let tool = llvm_bolt()?;
+
+    let data_dir = options
+        .workspace_root
+        .join(BOLT_DATA_DIR)
+        .join(options.target_triple());
+    if data_dir.exists() {
+        fs::remove_dir_all(&data_dir)?;
+    }
+    let baseline_dir = data_dir.join("baseline");
+    fs::create_dir_all(&baseline_dir)?;
+    let baseline = baseline_dir.join(BOOTSTRAP_NAME);
+    fs::copy(binary, &baseline)?;
+    strip_binary(&baseline, options.arch);
+
+    let fdata = data_dir.join("bootstrap.fdata");
+    let instrumented = data_dir.join("bootstrap.instrumented");
+    let mut instrument = vec![OsString::from("-instrument")];
+    instrument.push(instrumentation_file_arg(&fdata));
+    run_bolt(&tool, binary, &instrumented, &instrument)?;
+    let training_invocations = train(&instrumented, &events, training.iterations, &[])?;
+    if !fdata.is_file() {
+        return Err(PackagerError::Bolt(format!(
+            "{} wrote no profile to {}",
+            instrumented.display(),
+            fdata.display()
+        )));
+    }
+
+    let optimized = data_dir.join(BOOTSTRAP_NAME);
+    run_bolt(&tool, binary, &optimized, &optimize_args(&fdata))?;
+    strip_binary(&optimized, options.arch);
+
+    let report = OptimizationReport {
+        baseline_size: fs::metadata(&baseline)?.len(),
+        optimized_size: fs::metadata(&optimized)?.len(),
+        baseline_cold_start: cold_start(&baseline, &events[0], training.cold_starts)?,
+        optimized_cold_start: cold_start(&optimized, &events[0], training.cold_starts)?,
+        training_invocations,
+    };
+    Ok((optimized, report))
+}
+
+/// `llvm-bolt` from PATH, verified to run
+fn llvm_bolt() -> Result<PathBuf> {
+    let tool = PathBuf::from("llvm-bolt");
+    match Command::new(&tool).arg("--version").output() {
+        Ok(output) if output.status.success() => Ok(tool),
+        _ => Err(PackagerError::Bolt(
+            "llvm-bolt not found on PATH (install LLVM's BOLT, e.g. apt install llvm-bolt, \
+             or drop --bolt)"
+                .to_string(),
+        )),
+    }
+}
+
+/// `-instrumentation-file=<fdata>`
+fn instrumentation_file_arg(fdata: &Path) -> OsString {
+    let mut arg = OsString::from("-instrumentation-file=");
+    arg.push(fdata);
+    arg
+}
+
+/// Arguments of the optimizing relink with the profile in `fdata`
+fn optimize_args(fdata: &Path) -> Vec<OsString> {
+    let mut data = OsString::from("-data=");
+    data.push(fdata);
+    let mut args = vec![data];
+    args.extend(BOLT_PASSES.iter().map(OsString::from));
+    args
+}
+
+/// `llvm-bolt input -o output args...`
+fn run_bolt(tool: &Path, input: &Path, output: &Path, args: &[OsString]) -> Result<()> {
+    let result = Command::new(tool)
+        .arg(input)
+        .arg("-o")
+        .arg(output)
+        .args(args)
+        .output()
+        .map_err(|e| PackagerError::Bolt(format!("failed to run {}: {e}", tool.display())))?;
+    if !result.status.success() {
+        return Err(PackagerError::Bolt(format!(
+            "{} {} exited with {}: {}",
+            tool.display(),
+            input.display(),
+            result.status,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimize_args() {
+        let args = optimize_args(Path::new("/tmp/bootstrap.fdata"));
+        assert_eq!(args[0], "-data=/tmp/bootstrap.fdata");
+        assert!(args.iter().any(|arg| arg == "-reorder-functions=hfsort"));
+        assert_eq!(
+            instrumentation_file_arg(Path::new("/tmp/bootstrap.fdata")),
+            "-instrumentation-file=/tmp/bootstrap.fdata"
+        );
+    }
+
+    #[test]
+    fn test_missing_tool_reported() {
+        let result = run_bolt(
+            Path::new("/nonexistent/llvm-bolt"),
+            Path::new("bootstrap"),
+            Path::new("bootstrap.bolt"),
+            &[],
+        );
+        assert!(matches!(result, Err(PackagerError::Bolt(_))));
+    }
+}
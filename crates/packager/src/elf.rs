@@ -0,0 +1,118 @@
+// Static linkage check
+//
+// provided.al2023 ships glibc 2.34; a bootstrap linked dynamically against a
+// newer glibc on the build host fails at cold start with
+// "GLIBC_2.xx not found" (Runtime.ExitError) - after deployment. A static
+// musl binary has no program interpreter (PT_INTERP), so its absence is what
+// `--libc musl` packaging verifies before writing the artifact.
+//
+// Only the ELF header and program headers are read; anything that is not an
+// ELF file (e.g. test fixtures) is reported as having no interpreter.
+
+/// Program header type of the dynamic loader path
+const PT_INTERP: u32 = 3;
+
+/// Dynamic loader an ELF executable requests (e.g.
+/// `/lib/ld-linux-aarch64.so.1`), `None` for static binaries and non-ELF
+/// input
+#[must_use]
+pub fn interpreter(contents: &[u8]) -> Option<String> {
+    if contents.get(..4)? != b"\x7fELF" {
+        return None;
+    }
+    let wide = match contents.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+    let little_endian = *contents.get(5)? == 1;
+    let read = |offset: usize, len: usize| -> Option<u64> {
+        let bytes = contents.get(offset..offset.checked_add(len)?)?;
+        let fold = |value: u64, byte: &u8| (value << 8) | u64::from(*byte);
+        Some(if little_endian {
+            bytes.iter().rev().fold(0, fold)
+        } else {
+            bytes.iter().fold(0, fold)
+        })
+    };
+    let to_usize = |value: u64| usize::try_from(value).ok();
+
+    let (phoff, phentsize, phnum) = if wide {
+        (read(0x20, 8)?, read(0x36, 2)?, read(0x38, 2)?)
+    } else {
+        (read(0x1C, 4)?, read(0x2A, 2)?, read(0x2C, 2)?)
+    };
+    let (phoff, phentsize) = (to_usize(phoff)?, to_usize(phentsize)?);
+
+    (0..to_usize(phnum)?).find_map(|index| {
+        let header = phoff.checked_add(index.checked_mul(phentsize)?)?;
+        if read(header, 4)? != u64::from(PT_INTERP) {
+            return None;
+        }
+        let (offset, size) = if wide {
+            (read(header + 8, 8)?, read(header + 32, 8)?)
+        } else {
+            (read(header + 4, 4)?, read(header + 16, 4)?)
+        };
+        let start = to_usize(offset)?;
+        let path = contents.get(start..start.checked_add(to_usize(size)?)?)?;
+        let path = path.split(|byte| *byte == 0).next().unwrap_or_default();
+        Some(String::from_utf8_lossy(path).into_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal little-endian ELF64 image with one program header
+    fn elf64(p_type: u32, interp: &[u8]) -> Vec<u8> {
+        let mut image = vec![0u8; 0x40 + 0x38];
+        image[..4].copy_from_slice(b"\x7fELF");
+        image[4] = 2;
+        image[5] = 1;
+        image[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes());
+        image[0x36..0x38].copy_from_slice(&0x38u16.to_le_bytes());
+        image[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes());
+
+        let data = image.len() as u64;
+        image[0x40..0x44].copy_from_slice(&p_type.to_le_bytes());
+        image[0x48..0x50].copy_from_slice(&data.to_le_bytes());
+        image[0x60..0x68].copy_from_slice(&(interp.len() as u64).to_le_bytes());
+        image.extend_from_slice(interp);
+        image
+    }
+
+    #[test]
+    fn test_dynamic_binary_interpreter() {
+        let image = elf64(PT_INTERP, b"/lib/ld-linux-aarch64.so.1\0");
+        assert_eq!(
+            interpreter(&image).as_deref(),
+            Some("/lib/ld-linux-aarch64.so.1")
+        );
+    }
+
+    #[test]
+    fn test_static_binary_has_no_interpreter() {
+        // PT_LOAD only
+        assert_eq!(interpreter(&elf64(1, b"")), None);
+    }
+
+    #[test]
+    fn test_non_elf_and_truncated_input() {
+        assert_eq!(interpreter(b"#!/bin/sh\n"), None);
+        assert_eq!(interpreter(&[]), None);
+        let image = elf64(PT_INTERP, b"/lib/ld.so\0");
+        assert_eq!(interpreter(&image[..0x50]), None);
+    }
+
+    #[test]
+    fn test_host_executable() {
+        // The test binary itself: dynamic on glibc hosts, static on musl ones
+        let contents = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let interp = interpreter(&contents);
+        if cfg!(all(target_os = "linux", target_env = "gnu")) {
+            assert!(interp.unwrap().contains("ld-"));
+        }
+    }
+}
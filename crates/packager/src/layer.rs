@@ -0,0 +1,132 @@
+// Lambda layer packaging
+//
+// Layers are extracted to /opt. For provided.al2023, Lambda falls back to
+// /opt/bootstrap when the function zip has no bootstrap of its own, so the
+// layer carries:
+//
+//   bootstrap                  shim: exec $LAMBDA_TASK_ROOT/$_HANDLER if the
+//                              function ships that executable, else the shared
+//                              runtime below
+//   bin/ruchy-bootstrap        prebuilt Ruchy Lambda bootstrap
+//   share/ruchy-lambda/<file>  shared assets (--asset)
+//
+// Per-function zips then only need the handler executable (named after the
+// function's Handler setting), or nothing at all when the shared bootstrap's
+// built-in handler is enough.
+
+use crate::{Result, BOOTSTRAP_NAME};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Layer path of the shared runtime binary (`/opt/bin/ruchy-bootstrap`)
+pub const LAYER_RUNTIME_PATH: &str = "bin/ruchy-bootstrap";
+
+/// Layer directory for shared assets (`/opt/share/ruchy-lambda`)
+pub const LAYER_ASSET_DIR: &str = "share/ruchy-lambda";
+
+/// `/opt/bootstrap` shim dispatching to the function's handler executable
+pub const LAYER_BOOTSTRAP_SHIM: &str = r#"#!/bin/sh
+# Ruchy Lambda layer bootstrap (/opt/bootstrap)
+# Prefer the function's own executable named by its Handler setting, falling
+# back to the shared runtime shipped in this layer.
+handler="${LAMBDA_TASK_ROOT:-/var/task}/${_HANDLER:-bootstrap}"
+if [ -f "$handler" ] && [ -x "$handler" ]; then
+    exec "$handler"
+fi
+exec /opt/bin/ruchy-bootstrap
+"#;
+
+/// Write a layer zip containing the shim, `binary` and `assets`
+///
+/// Returns the size of the written zip in bytes.
+///
+/// # Errors
+///
+/// Returns `PackagerError` if an input cannot be read or the zip written
+pub fn write_layer_zip(binary: &Path, assets: &[PathBuf], output: &Path) -> Result<u64> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut zip = zip::ZipWriter::new(File::create(output)?);
+    let executable = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+    let data = executable.unix_permissions(0o644);
+
+    zip.start_file(BOOTSTRAP_NAME, executable)?;
+    zip.write_all(LAYER_BOOTSTRAP_SHIM.as_bytes())?;
+
+    zip.start_file(LAYER_RUNTIME_PATH, executable)?;
+    zip.write_all(&fs::read(binary)?)?;
+
+    for asset in assets {
+        let name = asset
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("asset has no file name: {}", asset.display()),
+                )
+            })?;
+        zip.start_file(format!("{LAYER_ASSET_DIR}/{name}"), data)?;
+        zip.write_all(&fs::read(asset)?)?;
+    }
+
+    zip.finish()?;
+    Ok(fs::metadata(output)?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_layer_shim_dispatch() {
+        assert!(LAYER_BOOTSTRAP_SHIM.starts_with("#!/bin/sh\n"));
+        assert!(LAYER_BOOTSTRAP_SHIM.contains("${_HANDLER:-bootstrap}"));
+        assert!(LAYER_BOOTSTRAP_SHIM.contains("exec /opt/bin/ruchy-bootstrap"));
+    }
+
+    #[test]
+    fn test_write_layer_zip_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, b"\x7fELF shared runtime").unwrap();
+        let asset = dir.path().join("handlers.toml");
+        fs::write(&asset, b"[[handler]]").unwrap();
+        let output = dir.path().join("out/layer.zip");
+
+        let size = write_layer_zip(&binary, &[asset], &output).unwrap();
+        assert_eq!(size, fs::metadata(&output).unwrap().len());
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            [
+                "bin/ruchy-bootstrap",
+                "bootstrap",
+                "share/ruchy-lambda/handlers.toml"
+            ]
+        );
+
+        let mut shim = archive.by_name(BOOTSTRAP_NAME).unwrap();
+        assert_eq!(shim.unix_mode().map(|mode| mode & 0o777), Some(0o755));
+        let mut contents = String::new();
+        shim.read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, LAYER_BOOTSTRAP_SHIM);
+        drop(shim);
+
+        let runtime = archive.by_name(LAYER_RUNTIME_PATH).unwrap();
+        assert_eq!(runtime.unix_mode().map(|mode| mode & 0o777), Some(0o755));
+        drop(runtime);
+
+        let asset = archive.by_name("share/ruchy-lambda/handlers.toml").unwrap();
+        assert_eq!(asset.unix_mode().map(|mode| mode & 0o777), Some(0o644));
+    }
+}
@@ -0,0 +1,823 @@
+// Ruchy Lambda Packager
+//
+// Turns the bootstrap crate into a `provided.al2023` deployment artifact:
+// 1. cargo build --profile <profile> --target <arch triple> -p ruchy-lambda-bootstrap
+//    (static musl by default; `--libc gnu` for glibc-linked builds), or the
+//    PGO build/train/rebuild cycle with `--pgo` (pgo.rs)
+// 2. optionally relink the text layout with llvm-bolt (`--bolt`, bolt.rs)
+// 3. strip the binary (best effort - release-ultra already strips)
+// 4. validate the executable is named `bootstrap`, fits the size budget and
+//    is statically linked when musl (or a scratch image) was requested
+// 5. write the artifact:
+//    - zip: function.zip (bootstrap at the archive root, mode 0755), ready for
+//      aws lambda update-function-code --function-name <fn> --zip-file fileb://function.zip
+//    - oci: Docker build context (bootstrap + Dockerfile) for ECR deployment
+//    - layer: layer.zip (/opt/bootstrap shim + shared runtime + assets)
+//
+// `templates` additionally emits SAM/Terraform for the example handlers, and
+// `audit` reports what the bootstrap links (audit.rs).
+
+#![warn(missing_docs, clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+//! Build and package the Ruchy Lambda bootstrap as `function.zip` or a container image
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use ruchy_lambda_packager::{package, Arch, PackageOptions};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let options = PackageOptions::new("release-ultra", Arch::Arm64);
+//! let report = package(&options)?;
+//! println!("{} ({} bytes)", report.artifact.display(), report.artifact_size);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+mod audit;
+mod bolt;
+mod elf;
+mod layer;
+mod oci;
+mod pgo;
+mod templates;
+mod training;
+
+pub use audit::{audit, AuditReport, CrateSize, SymbolSize, HEAVY_CRATES, OTHER_CRATE};
+pub use bolt::BOLT_DATA_DIR;
+pub use elf::interpreter;
+pub use layer::{write_layer_zip, LAYER_ASSET_DIR, LAYER_BOOTSTRAP_SHIM, LAYER_RUNTIME_PATH};
+pub use oci::{dockerfile, write_image_context, BaseImage};
+pub use pgo::{optimize, PGO_GENERATE_PROFILE, PGO_USE_PROFILE};
+pub use templates::{
+    sam_template, terraform_module, write_templates, FunctionSpec, TemplateFormat,
+    EXAMPLE_FUNCTIONS,
+};
+pub use training::{
+    OptimizationReport, TrainingOptions, DEFAULT_COLD_STARTS, DEFAULT_TRAINING_ITERATIONS,
+};
+
+/// Executable name required by the Lambda custom runtime
+pub const BOOTSTRAP_NAME: &str = "bootstrap";
+
+/// Default size budget for the stripped bootstrap (KB)
+///
+/// Matches the ARM64 SIMD build target (<500KB, see scripts/build-arm64-simd.sh).
+pub const DEFAULT_SIZE_BUDGET_KB: u64 = 500;
+
+/// Package that produces the bootstrap binary
+const BOOTSTRAP_PACKAGE: &str = "ruchy-lambda-bootstrap";
+
+/// Lambda instruction set architecture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    /// Graviton (`aarch64`)
+    Arm64,
+    /// Intel/AMD (`x86_64`)
+    X86_64,
+}
+
+impl Arch {
+    /// Rust target triple (static musl, matching .cargo/config.toml)
+    #[must_use]
+    pub fn target_triple(self) -> &'static str {
+        self.target_triple_for(Libc::Musl)
+    }
+
+    /// Rust target triple linking against `libc`
+    #[must_use]
+    pub fn target_triple_for(self, libc: Libc) -> &'static str {
+        match (self, libc) {
+            (Self::Arm64, Libc::Musl) => "aarch64-unknown-linux-musl",
+            (Self::Arm64, Libc::Gnu) => "aarch64-unknown-linux-gnu",
+            (Self::X86_64, Libc::Musl) => "x86_64-unknown-linux-musl",
+            (Self::X86_64, Libc::Gnu) => "x86_64-unknown-linux-gnu",
+        }
+    }
+
+    /// Architecture name as used by the Lambda API (`--architectures`)
+    #[must_use]
+    pub fn lambda_name(self) -> &'static str {
+        match self {
+            Self::Arm64 => "arm64",
+            Self::X86_64 => "x86_64",
+        }
+    }
+
+    /// Architecture of the machine running the packager, if Lambda has it
+    #[must_use]
+    pub fn host() -> Option<Self> {
+        match std::env::consts::ARCH {
+            "aarch64" => Some(Self::Arm64),
+            "x86_64" => Some(Self::X86_64),
+            _ => None,
+        }
+    }
+
+    /// `strip` binaries to try, most specific first
+    fn strip_tools(self) -> &'static [&'static str] {
+        match self {
+            Self::Arm64 => &["aarch64-linux-gnu-strip", "llvm-strip", "strip"],
+            Self::X86_64 => &["x86_64-linux-gnu-strip", "llvm-strip", "strip"],
+        }
+    }
+}
+
+impl FromStr for Arch {
+    type Err = PackagerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "arm64" | "aarch64" => Ok(Self::Arm64),
+            "x86_64" | "x86-64" | "amd64" => Ok(Self::X86_64),
+            other => Err(PackagerError::InvalidArch(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.lambda_name())
+    }
+}
+
+/// C library the bootstrap is linked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Libc {
+    /// Static musl (`+crt-static`): runs on any Lambda base, including scratch
+    Musl,
+    /// Dynamic glibc: must not need a newer glibc than the runtime's (2.34 on
+    /// `provided.al2023`)
+    Gnu,
+}
+
+impl FromStr for Libc {
+    type Err = PackagerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "musl" => Ok(Self::Musl),
+            "gnu" | "glibc" => Ok(Self::Gnu),
+            other => Err(PackagerError::InvalidLibc(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Libc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Musl => "musl",
+            Self::Gnu => "gnu",
+        })
+    }
+}
+
+/// Artifact produced by the packager
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageFormat {
+    /// `function.zip` for zip-based functions
+    Zip,
+    /// Container image build context for image-based functions
+    Oci(BaseImage),
+    /// Lambda layer zip shared by thin per-function deployments
+    Layer,
+}
+
+/// Packager error type
+#[derive(Debug)]
+pub enum PackagerError {
+    /// Unknown `--arch` value
+    InvalidArch(String),
+    /// Unknown `--base` value
+    InvalidBaseImage(String),
+    /// Unknown `--libc` value
+    InvalidLibc(String),
+    /// Unknown `templates --format` value
+    InvalidTemplateFormat(String),
+    /// `cargo build` failed
+    BuildFailed(String),
+    /// Executable is not named `bootstrap`
+    InvalidBinaryName(PathBuf),
+    /// Executable needs a dynamic loader where a static binary is required
+    DynamicallyLinked {
+        /// Packaged binary
+        binary: PathBuf,
+        /// Requested program interpreter (e.g. `/lib64/ld-linux-x86-64.so.2`)
+        interpreter: String,
+    },
+    /// Binary exceeds the size budget
+    SizeBudgetExceeded {
+        /// Actual size (bytes)
+        size: u64,
+        /// Budget (bytes)
+        budget: u64,
+    },
+    /// Training an instrumented bootstrap failed
+    Training(String),
+    /// Profile-guided optimization failed
+    Pgo(String),
+    /// BOLT post-link optimization failed
+    Bolt(String),
+    /// Symbols of the audited binary could not be read
+    Audit(String),
+    /// Crates from [`HEAVY_CRATES`] are linked into the bootstrap
+    HeavyCrates(Vec<String>),
+    /// I/O error
+    Io(io::Error),
+    /// Zip archive error
+    Zip(zip::result::ZipError),
+}
+
+impl fmt::Display for PackagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidArch(arch) => {
+                write!(
+                    f,
+                    "Unknown architecture '{arch}' (expected arm64 or x86_64)"
+                )
+            }
+            Self::InvalidBaseImage(base) => {
+                write!(
+                    f,
+                    "Unknown base image '{base}' (expected scratch or al2023)"
+                )
+            }
+            Self::InvalidLibc(libc) => {
+                write!(f, "Unknown libc '{libc}' (expected musl or gnu)")
+            }
+            Self::InvalidTemplateFormat(format) => {
+                write!(
+                    f,
+                    "Unknown template format '{format}' (expected sam or terraform)"
+                )
+            }
+            Self::BuildFailed(msg) => write!(f, "Build failed: {msg}"),
+            Self::InvalidBinaryName(path) => write!(
+                f,
+                "Lambda custom runtimes require an executable named '{BOOTSTRAP_NAME}': {}",
+                path.display()
+            ),
+            Self::DynamicallyLinked {
+                binary,
+                interpreter,
+            } => write!(
+                f,
+                "{} is dynamically linked (loader {interpreter}); build it for musl \
+                 (--libc musl) so it does not depend on the runtime's glibc",
+                binary.display()
+            ),
+            Self::SizeBudgetExceeded { size, budget } => write!(
+                f,
+                "Binary is {}KB, over the {}KB size budget",
+                size / 1024,
+                budget / 1024
+            ),
+            Self::Training(msg) => write!(f, "Training run failed: {msg}"),
+            Self::Pgo(msg) => write!(f, "PGO failed: {msg}"),
+            Self::Bolt(msg) => write!(f, "BOLT failed: {msg}"),
+            Self::Audit(msg) => write!(f, "Audit failed: {msg}"),
+            Self::HeavyCrates(crates) => write!(
+                f,
+                "Heavy crates linked into the bootstrap: {}",
+                crates.join(", ")
+            ),
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Zip(e) => write!(f, "Zip error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PackagerError {}
+
+impl From<io::Error> for PackagerError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<zip::result::ZipError> for PackagerError {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self::Zip(err)
+    }
+}
+
+/// Result type for packaging operations
+pub type Result<T> = std::result::Result<T, PackagerError>;
+
+/// Packaging options
+#[derive(Debug, Clone)]
+pub struct PackageOptions {
+    /// Cargo profile (e.g., `release-ultra`)
+    pub profile: String,
+    /// Target architecture
+    pub arch: Arch,
+    /// C library to link against (static musl by default)
+    pub libc: Libc,
+    /// Workspace root (contains `target/`)
+    pub workspace_root: PathBuf,
+    /// Package a prebuilt binary instead of running `cargo build`
+    pub binary: Option<PathBuf>,
+    /// Maximum stripped binary size (KB)
+    pub size_budget_kb: u64,
+    /// Artifact format
+    pub format: PackageFormat,
+    /// Output path (zip file or image context directory); defaults per format
+    pub output: Option<PathBuf>,
+    /// Shared assets bundled under `/opt/share/ruchy-lambda` (layer only)
+    pub layer_assets: Vec<PathBuf>,
+    /// Build with profile-guided optimization (ignored with `binary`)
+    pub pgo: Option<TrainingOptions>,
+    /// Optimize the text layout with llvm-bolt (ignored with `binary`)
+    pub bolt: Option<TrainingOptions>,
+}
+
+impl PackageOptions {
+    /// Options for `profile`/`arch` with defaults rooted at this workspace
+    pub fn new(profile: impl Into<String>, arch: Arch) -> Self {
+        Self {
+            profile: profile.into(),
+            arch,
+            libc: Libc::Musl,
+            workspace_root: workspace_root(),
+            binary: None,
+            size_budget_kb: DEFAULT_SIZE_BUDGET_KB,
+            format: PackageFormat::Zip,
+            output: None,
+            layer_assets: Vec::new(),
+            pgo: None,
+            bolt: None,
+        }
+    }
+
+    /// Artifact path (`output`, or `target/lambda-packages/{function.zip,oci,layer.zip}`)
+    #[must_use]
+    pub fn output_path(&self) -> PathBuf {
+        self.output.clone().unwrap_or_else(|| {
+            let packages = self.workspace_root.join("target/lambda-packages");
+            match self.format {
+                PackageFormat::Zip => packages.join("function.zip"),
+                PackageFormat::Oci(_) => packages.join("oci"),
+                PackageFormat::Layer => packages.join("layer.zip"),
+            }
+        })
+    }
+
+    /// Rust target triple for `arch` and `libc`
+    #[must_use]
+    pub fn target_triple(&self) -> &'static str {
+        self.arch.target_triple_for(self.libc)
+    }
+
+    /// Whether the binary must be statically linked (musl, or an image
+    /// without a C library)
+    #[must_use]
+    pub fn requires_static(&self) -> bool {
+        self.libc == Libc::Musl || self.format == PackageFormat::Oci(BaseImage::Scratch)
+    }
+
+    /// Path cargo writes the bootstrap binary to for these options
+    #[must_use]
+    pub fn binary_path(&self) -> PathBuf {
+        self.binary_path_for(&self.profile)
+    }
+
+    /// Path cargo writes the bootstrap binary to when built with `profile`
+    #[must_use]
+    pub fn binary_path_for(&self, profile: &str) -> PathBuf {
+        self.workspace_root
+            .join("target")
+            .join(self.target_triple())
+            .join(profile_dir(profile))
+            .join(BOOTSTRAP_NAME)
+    }
+}
+
+/// Outcome of a successful packaging run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageReport {
+    /// Packaged binary
+    pub binary: PathBuf,
+    /// Binary size (bytes)
+    pub binary_size: u64,
+    /// Written artifact (zip file or image context directory)
+    pub artifact: PathBuf,
+    /// Artifact size (bytes)
+    pub artifact_size: u64,
+    /// Baseline comparison, when built with PGO
+    pub pgo: Option<OptimizationReport>,
+    /// Comparison against the pre-BOLT binary, when optimized with BOLT
+    pub bolt: Option<OptimizationReport>,
+}
+
+/// Build (unless a binary was given), validate and package the bootstrap
+///
+/// # Errors
+///
+/// Returns `PackagerError` if the build fails, the binary is misnamed, over
+/// budget or unexpectedly dynamically linked, or the archive cannot be
+/// written
+pub fn package(options: &PackageOptions) -> Result<PackageReport> {
+    let (binary, pgo) = if let Some(path) = &options.binary {
+        (path.clone(), None)
+    } else if let Some(pgo) = &options.pgo {
+        let (path, report) = optimize(options, pgo)?;
+        (path, Some(report))
+    } else {
+        build_bootstrap(options)?;
+        let path = options.binary_path();
+        if options.bolt.is_none() {
+            strip_binary(&path, options.arch);
+        }
+        (path, None)
+    };
+    let (binary, bolt) = match &options.bolt {
+        Some(training) if options.binary.is_none() => {
+            let (path, report) = bolt::optimize(options, &binary, training)?;
+            (path, Some(report))
+        }
+        _ => (binary, None),
+    };
+
+    validate_binary_name(&binary)?;
+    let binary_size = fs::metadata(&binary)?.len();
+    check_size_budget(binary_size, options.size_budget_kb)?;
+    if options.requires_static() {
+        check_static(&binary)?;
+    }
+
+    let artifact = options.output_path();
+    let artifact_size = match options.format {
+        PackageFormat::Zip => {
+            write_function_zip(&binary, &artifact)?;
+            fs::metadata(&artifact)?.len()
+        }
+        PackageFormat::Oci(base) => write_image_context(&binary, &artifact, base, options.arch)?,
+        PackageFormat::Layer => write_layer_zip(&binary, &options.layer_assets, &artifact)?,
+    };
+
+    Ok(PackageReport {
+        binary,
+        binary_size,
+        artifact,
+        artifact_size,
+        pgo,
+        bolt,
+    })
+}
+
+/// Run `cargo build` for the bootstrap with the requested profile and target
+///
+/// # Errors
+///
+/// Returns `PackagerError::BuildFailed` if cargo cannot be run or exits non-zero
+pub fn build_bootstrap(options: &PackageOptions) -> Result<()> {
+    cargo_build(options, &options.profile, &[])
+}
+
+/// `cargo build` the bootstrap with `profile`, adding `rustflags` to the
+/// target's configured flags
+///
+/// With BOLT requested the binary keeps its symbols and relocations, which
+/// llvm-bolt needs to rewrite it.
+fn cargo_build(options: &PackageOptions, profile: &str, rustflags: &[String]) -> Result<()> {
+    let target = options.target_triple();
+    let mut rustflags = rustflags.to_vec();
+    if options.bolt.is_some() {
+        rustflags.push("-Clink-arg=-Wl,--emit-relocs".to_string());
+    }
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = Command::new(cargo);
+    command
+        .current_dir(&options.workspace_root)
+        .args(["build", "--profile", profile])
+        .args(["--target", target])
+        .args(["-p", BOOTSTRAP_PACKAGE]);
+    if !rustflags.is_empty() {
+        // --config arrays are appended to .cargo/config.toml's, unlike RUSTFLAGS
+        let flags: Vec<String> = rustflags.iter().map(|flag| format!("{flag:?}")).collect();
+        command
+            .arg("--config")
+            .arg(format!("target.{target}.rustflags=[{}]", flags.join(", ")));
+    }
+    if options.bolt.is_some() {
+        command
+            .arg("--config")
+            .arg(format!("profile.{profile}.strip=false"));
+    }
+    let status = command
+        .status()
+        .map_err(|e| PackagerError::BuildFailed(format!("failed to run cargo: {e}")))?;
+
+    if !status.success() {
+        return Err(PackagerError::BuildFailed(format!(
+            "cargo build --profile {profile} --target {target} exited with {status} \
+             (is the target installed? rustup target add {target})"
+        )));
+    }
+    Ok(())
+}
+
+/// Strip symbols with the first available tool (best effort)
+fn strip_binary(binary: &Path, arch: Arch) {
+    for tool in arch.strip_tools() {
+        if let Ok(status) = Command::new(tool).arg(binary).status() {
+            if status.success() {
+                return;
+            }
+        }
+    }
+}
+
+/// Ensure the executable is named `bootstrap`
+///
+/// # Errors
+///
+/// Returns `PackagerError::InvalidBinaryName` for any other file name
+pub fn validate_binary_name(binary: &Path) -> Result<()> {
+    if binary.file_name().and_then(|name| name.to_str()) == Some(BOOTSTRAP_NAME) {
+        Ok(())
+    } else {
+        Err(PackagerError::InvalidBinaryName(binary.to_path_buf()))
+    }
+}
+
+/// Ensure `size` (bytes) fits within `budget_kb`
+///
+/// # Errors
+///
+/// Returns `PackagerError::SizeBudgetExceeded` when over budget
+pub fn check_size_budget(size: u64, budget_kb: u64) -> Result<()> {
+    let budget = budget_kb * 1024;
+    if size > budget {
+        return Err(PackagerError::SizeBudgetExceeded { size, budget });
+    }
+    Ok(())
+}
+
+/// Ensure `binary` has no program interpreter (statically linked)
+///
+/// # Errors
+///
+/// Returns `PackagerError::DynamicallyLinked` for dynamically linked
+/// executables and `PackagerError::Io` if the binary cannot be read
+pub fn check_static(binary: &Path) -> Result<()> {
+    match interpreter(&fs::read(binary)?) {
+        Some(interpreter) => Err(PackagerError::DynamicallyLinked {
+            binary: binary.to_path_buf(),
+            interpreter,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Write `function.zip` with the binary stored as `bootstrap` (mode 0755)
+///
+/// # Errors
+///
+/// Returns `PackagerError` if the binary cannot be read or the zip written
+pub fn write_function_zip(binary: &Path, output: &Path) -> Result<()> {
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents = fs::read(binary)?;
+    let mut zip = zip::ZipWriter::new(File::create(output)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    zip.start_file(BOOTSTRAP_NAME, options)?;
+    zip.write_all(&contents)?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// Cargo's output directory name for a profile (`dev` builds into `debug/`)
+#[must_use]
+pub fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        other => other,
+    }
+}
+
+/// Workspace root (two levels above this crate's manifest)
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_arch_from_str() {
+        assert_eq!("arm64".parse::<Arch>().unwrap(), Arch::Arm64);
+        assert_eq!("aarch64".parse::<Arch>().unwrap(), Arch::Arm64);
+        assert_eq!("x86_64".parse::<Arch>().unwrap(), Arch::X86_64);
+        assert!(matches!(
+            "sparc".parse::<Arch>(),
+            Err(PackagerError::InvalidArch(_))
+        ));
+    }
+
+    #[test]
+    fn test_arch_target_triple() {
+        assert_eq!(Arch::Arm64.target_triple(), "aarch64-unknown-linux-musl");
+        assert_eq!(Arch::X86_64.target_triple(), "x86_64-unknown-linux-musl");
+        assert_eq!(Arch::Arm64.to_string(), "arm64");
+    }
+
+    #[test]
+    fn test_libc_target_triple() {
+        assert_eq!("glibc".parse::<Libc>().unwrap(), Libc::Gnu);
+        assert!(matches!(
+            "uclibc".parse::<Libc>(),
+            Err(PackagerError::InvalidLibc(_))
+        ));
+        assert_eq!(
+            Arch::X86_64.target_triple_for(Libc::Gnu),
+            "x86_64-unknown-linux-gnu"
+        );
+
+        let mut options = PackageOptions::new("release-ultra", Arch::Arm64);
+        options.workspace_root = PathBuf::from("/ws");
+        options.libc = Libc::Gnu;
+        assert_eq!(
+            options.binary_path(),
+            PathBuf::from("/ws/target/aarch64-unknown-linux-gnu/release-ultra/bootstrap")
+        );
+        assert!(!options.requires_static());
+        options.format = PackageFormat::Oci(BaseImage::Scratch);
+        assert!(options.requires_static());
+    }
+
+    #[test]
+    fn test_package_rejects_dynamic_musl_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::copy(std::env::current_exe().unwrap(), &binary).unwrap();
+        if interpreter(&fs::read(&binary).unwrap()).is_none() {
+            return; // static test binary (musl host)
+        }
+
+        let mut options = PackageOptions::new("release-ultra", Arch::X86_64);
+        options.binary = Some(binary);
+        options.size_budget_kb = u64::MAX / 1024;
+        options.output = Some(dir.path().join("function.zip"));
+        assert!(matches!(
+            package(&options),
+            Err(PackagerError::DynamicallyLinked { .. })
+        ));
+
+        options.libc = Libc::Gnu;
+        assert!(package(&options).is_ok());
+    }
+
+    #[test]
+    fn test_profile_dir() {
+        assert_eq!(profile_dir("dev"), "debug");
+        assert_eq!(profile_dir("release"), "release");
+        assert_eq!(profile_dir("release-ultra"), "release-ultra");
+    }
+
+    #[test]
+    fn test_binary_path() {
+        let mut options = PackageOptions::new("release-ultra", Arch::Arm64);
+        options.workspace_root = PathBuf::from("/ws");
+        assert_eq!(
+            options.binary_path(),
+            PathBuf::from("/ws/target/aarch64-unknown-linux-musl/release-ultra/bootstrap")
+        );
+    }
+
+    #[test]
+    fn test_validate_binary_name() {
+        assert!(validate_binary_name(Path::new("target/release/bootstrap")).is_ok());
+        assert!(matches!(
+            validate_binary_name(Path::new("target/release/handler")),
+            Err(PackagerError::InvalidBinaryName(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_size_budget() {
+        assert!(check_size_budget(500 * 1024, 500).is_ok());
+        let err = check_size_budget(500 * 1024 + 1, 500).unwrap_err();
+        assert!(err.to_string().contains("500KB size budget"));
+    }
+
+    #[test]
+    fn test_write_function_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, b"\x7fELF fake bootstrap").unwrap();
+        let output = dir.path().join("out/function.zip");
+
+        write_function_zip(&binary, &output).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        assert_eq!(archive.len(), 1);
+        let mut entry = archive.by_name(BOOTSTRAP_NAME).unwrap();
+        assert_eq!(entry.unix_mode().map(|mode| mode & 0o777), Some(0o755));
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents).unwrap();
+        assert_eq!(contents, b"\x7fELF fake bootstrap");
+    }
+
+    #[test]
+    fn test_package_prebuilt_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, vec![0u8; 2048]).unwrap();
+
+        let mut options = PackageOptions::new("release-ultra", Arch::X86_64);
+        options.binary = Some(binary.clone());
+        options.output = Some(dir.path().join("function.zip"));
+
+        let report = package(&options).unwrap();
+        assert_eq!(report.binary, binary);
+        assert_eq!(report.binary_size, 2048);
+        assert!(report.artifact_size > 0);
+    }
+
+    #[test]
+    fn test_package_layer() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, vec![0u8; 2048]).unwrap();
+
+        let mut options = PackageOptions::new("release-ultra", Arch::Arm64);
+        options.binary = Some(binary);
+        options.format = PackageFormat::Layer;
+        options.output = Some(dir.path().join("layer.zip"));
+
+        let report = package(&options).unwrap();
+        let archive = zip::ZipArchive::new(File::open(&report.artifact).unwrap()).unwrap();
+        assert!(archive.file_names().any(|name| name == LAYER_RUNTIME_PATH));
+    }
+
+    #[test]
+    fn test_package_oci_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, vec![0u8; 2048]).unwrap();
+
+        let mut options = PackageOptions::new("release-ultra", Arch::Arm64);
+        options.binary = Some(binary);
+        options.format = PackageFormat::Oci(BaseImage::Al2023);
+        options.output = Some(dir.path().join("oci"));
+
+        let report = package(&options).unwrap();
+        assert_eq!(report.artifact, dir.path().join("oci"));
+        assert!(report.artifact.join("Dockerfile").exists());
+        assert!(report.artifact.join(BOOTSTRAP_NAME).exists());
+    }
+
+    #[test]
+    fn test_output_path_defaults() {
+        let mut options = PackageOptions::new("release-ultra", Arch::Arm64);
+        options.workspace_root = PathBuf::from("/ws");
+        assert_eq!(
+            options.output_path(),
+            PathBuf::from("/ws/target/lambda-packages/function.zip")
+        );
+        options.format = PackageFormat::Oci(BaseImage::Scratch);
+        assert_eq!(
+            options.output_path(),
+            PathBuf::from("/ws/target/lambda-packages/oci")
+        );
+    }
+
+    #[test]
+    fn test_package_rejects_over_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, vec![0u8; 2048]).unwrap();
+
+        let mut options = PackageOptions::new("release-ultra", Arch::X86_64);
+        options.binary = Some(binary);
+        options.size_budget_kb = 1;
+        options.output = Some(dir.path().join("function.zip"));
+
+        assert!(matches!(
+            package(&options),
+            Err(PackagerError::SizeBudgetExceeded { .. })
+        ));
+        assert!(!options.output_path().exists());
+    }
+}
@@ -0,0 +1,298 @@
+// Ruchy Lambda Packager CLI
+//
+// Usage:
+//   cargo run -p ruchy-lambda-packager -- --profile release-ultra --arch arm64
+//   cargo packager --profile release-ultra --arch arm64   (alias, .cargo/config.toml)
+//   cargo packager --arch x86_64 --libc gnu                (glibc instead of static musl)
+//   cargo packager --arch x86_64 --pgo                     (profile-guided, host arch only)
+//   cargo packager --arch x86_64 --bolt                    (llvm-bolt text layout, host arch only)
+//   cargo packager --format oci --base scratch             (container image context)
+//   cargo packager --format layer --asset handlers.toml    (shared runtime layer)
+//   cargo packager templates --format sam                  (SAM/Terraform for examples)
+//   cargo packager audit --arch x86_64                     (largest symbols/crates linked)
+
+use clap::{ArgGroup, Parser, Subcommand};
+use ruchy_lambda_packager::{
+    audit, package, write_templates, Arch, BaseImage, Libc, PackageFormat, PackageOptions,
+    TemplateFormat, TrainingOptions, DEFAULT_COLD_STARTS, DEFAULT_SIZE_BUDGET_KB,
+    DEFAULT_TRAINING_ITERATIONS,
+};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Build and package the bootstrap as a Lambda function.zip or container image
+#[derive(Parser)]
+#[command(name = "packager")]
+#[command(about = "Build, validate and zip the Ruchy Lambda bootstrap for provided.al2023")]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(group(ArgGroup::new("optimize").args(["pgo", "bolt"]).multiple(true)))]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    /// Cargo build profile
+    #[arg(long, default_value = "release-ultra")]
+    profile: String,
+
+    /// Target architecture (arm64 or x86_64)
+    #[arg(long, default_value = "arm64")]
+    arch: Arch,
+
+    /// C library: musl (static, runs on any base) or gnu (dynamic glibc)
+    #[arg(long, default_value = "musl")]
+    libc: Libc,
+
+    /// Maximum stripped binary size in KB
+    #[arg(long, default_value_t = DEFAULT_SIZE_BUDGET_KB)]
+    size_budget_kb: u64,
+
+    /// Artifact format: zip (function.zip), oci (Docker build context) or layer (layer.zip)
+    #[arg(long, default_value = "zip", value_parser = ["zip", "oci", "layer"])]
+    format: String,
+
+    /// Base image for --format oci (scratch or al2023)
+    #[arg(long, default_value = "scratch")]
+    base: BaseImage,
+
+    /// Shared asset to bundle under /opt/share/ruchy-lambda (--format layer)
+    #[arg(long = "asset")]
+    assets: Vec<PathBuf>,
+
+    /// Package an existing binary instead of building
+    #[arg(long)]
+    binary: Option<PathBuf>,
+
+    /// Profile-guided optimization: build instrumented, train against the
+    /// emulator, rebuild with the profile (target arch must be the host's)
+    #[arg(long, conflicts_with = "binary")]
+    pgo: bool,
+
+    /// Post-link optimization: relink the text layout with llvm-bolt from a
+    /// training run against the emulator (target arch must be the host's)
+    #[arg(long, conflicts_with = "binary")]
+    bolt: bool,
+
+    /// Directory of recorded events to train with (default: built-in fixtures)
+    #[arg(long, alias = "pgo-events", requires = "optimize")]
+    train_events: Option<PathBuf>,
+
+    /// Passes over the training events (--pgo, --bolt)
+    #[arg(long, alias = "pgo-iterations", default_value_t = DEFAULT_TRAINING_ITERATIONS)]
+    train_iterations: u32,
+
+    /// Cold starts measured per binary for the --pgo/--bolt reports
+    #[arg(long, alias = "pgo-cold-starts", default_value_t = DEFAULT_COLD_STARTS)]
+    cold_starts: u32,
+
+    /// Output path (default: target/lambda-packages/function.zip or .../oci)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Generate SAM or Terraform templates for the example handlers
+    Templates {
+        /// Template format (sam or terraform)
+        #[arg(long, default_value = "sam")]
+        format: TemplateFormat,
+
+        /// Directory containing <handler>.zip, relative to the template
+        #[arg(long, default_value = "build")]
+        code_dir: String,
+
+        /// Output directory
+        #[arg(short, long, default_value = "target/lambda-templates")]
+        output: PathBuf,
+    },
+    /// Report the largest symbols and crates linked into the bootstrap;
+    /// fails if a known-heavy crate (tokio, hyper, reqwest, ...) is linked
+    Audit {
+        /// Cargo build profile
+        #[arg(long, default_value = "release-ultra")]
+        profile: String,
+
+        /// Target architecture (arm64 or x86_64)
+        #[arg(long, default_value = "arm64")]
+        arch: Arch,
+
+        /// C library: musl or gnu
+        #[arg(long, default_value = "musl")]
+        libc: Libc,
+
+        /// Audit an existing (unstripped) binary instead of building
+        #[arg(long)]
+        binary: Option<PathBuf>,
+
+        /// Number of symbols and crates to list
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Commands::Templates {
+            format,
+            code_dir,
+            output,
+        }) => {
+            return match write_templates(format, &output, &code_dir) {
+                Ok(path) => {
+                    println!("✅ Template: {}", path.display());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("❌ {e}");
+                    ExitCode::FAILURE
+                }
+            };
+        }
+        Some(Commands::Audit {
+            profile,
+            arch,
+            libc,
+            binary,
+            top,
+        }) => {
+            let mut options = PackageOptions::new(profile, arch);
+            options.libc = libc;
+            options.binary = binary;
+            return run_audit(&options, top);
+        }
+        None => {}
+    }
+
+    let mut options = PackageOptions::new(cli.profile, cli.arch);
+    options.libc = cli.libc;
+    options.size_budget_kb = cli.size_budget_kb;
+    options.binary = cli.binary;
+    options.output = cli.output;
+    options.layer_assets = cli.assets;
+    let training = TrainingOptions {
+        events_dir: cli.train_events,
+        iterations: cli.train_iterations,
+        cold_starts: cli.cold_starts,
+    };
+    options.pgo = cli.pgo.then(|| training.clone());
+    options.bolt = cli.bolt.then_some(training);
+    match cli.format.as_str() {
+        "oci" => options.format = PackageFormat::Oci(cli.base),
+        "layer" => options.format = PackageFormat::Layer,
+        _ => {}
+    }
+
+    println!(
+        "📦 Packaging bootstrap (profile: {}, target: {})",
+        options.profile,
+        options.target_triple()
+    );
+
+    match package(&options) {
+        Ok(report) => {
+            println!(
+                "✅ Binary: {} ({}KB, budget {}KB)",
+                report.binary.display(),
+                report.binary_size / 1024,
+                options.size_budget_kb
+            );
+            println!(
+                "✅ Package: {} ({}KB)",
+                report.artifact.display(),
+                report.artifact_size / 1024
+            );
+            if let Some(pgo) = &report.pgo {
+                println!("✅ PGO: {pgo}");
+            }
+            if let Some(bolt) = &report.bolt {
+                println!("✅ BOLT: {bolt}");
+            }
+            println!();
+            print_deploy_hint(&options, &report.artifact);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("❌ {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print the next deployment step for the produced artifact
+fn print_deploy_hint(options: &PackageOptions, artifact: &Path) {
+    println!("📤 Deploy with:");
+    match options.format {
+        PackageFormat::Zip => println!(
+            "   aws lambda update-function-code --function-name <name> --zip-file fileb://{}",
+            artifact.display()
+        ),
+        PackageFormat::Oci(_) => {
+            println!(
+                "   docker build --platform linux/{} -t <repo>:<tag> {}",
+                if options.arch == Arch::Arm64 {
+                    "arm64"
+                } else {
+                    "amd64"
+                },
+                artifact.display()
+            );
+            println!("   docker push <repo>:<tag>");
+            println!(
+                "   aws lambda update-function-code --function-name <name> --image-uri <repo>:<tag>"
+            );
+        }
+        PackageFormat::Layer => {
+            println!(
+                "   aws lambda publish-layer-version --layer-name ruchy-lambda-runtime \\\n     --compatible-runtimes provided.al2023 --compatible-architectures {} \\\n     --zip-file fileb://{}",
+                options.arch.lambda_name(),
+                artifact.display()
+            );
+            println!(
+                "   aws lambda update-function-configuration --function-name <name> --layers <layer-version-arn>"
+            );
+        }
+    }
+}
+
+/// Print the audit of the bootstrap built for `options`
+fn run_audit(options: &PackageOptions, top: usize) -> ExitCode {
+    println!(
+        "🔍 Auditing bootstrap (profile: {}, target: {})",
+        options.profile,
+        options.target_triple()
+    );
+    let report = match audit(options, top) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Binary: {}", report.binary.display());
+    println!("\nLargest crates:");
+    for krate in report.crates.iter().take(top) {
+        println!(
+            "  {:>9}B  {:>5} symbols  {}",
+            krate.size, krate.symbols, krate.name
+        );
+    }
+    println!("\nLargest symbols:");
+    for symbol in &report.symbols {
+        println!("  {:>9}B  {}", symbol.size, symbol.name);
+    }
+    println!();
+
+    match report.check() {
+        Ok(()) => {
+            println!("✅ No heavy crates linked");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("❌ {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
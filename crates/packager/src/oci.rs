@@ -0,0 +1,172 @@
+// Container image (OCI) build context generation
+//
+// Lambda container images follow the same custom runtime conventions as zips:
+// the executable lives at $LAMBDA_RUNTIME_DIR/bootstrap (/var/runtime) and
+// handler code under $LAMBDA_TASK_ROOT (/var/task). The bootstrap already
+// speaks the Runtime API, so no runtime interface client is needed.
+//
+// Bases:
+// - scratch: static musl binary only (smallest image, fastest pull)
+// - al2023:  public.ecr.aws/lambda/provided:al2023 (includes the RIE for
+//            local `docker run` testing)
+
+use crate::{Arch, PackagerError, Result, BOOTSTRAP_NAME};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// AWS-provided custom runtime base image
+const AL2023_BASE_IMAGE: &str = "public.ecr.aws/lambda/provided:al2023";
+
+/// Base image for the generated Dockerfile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseImage {
+    /// Empty base (static bootstrap only)
+    Scratch,
+    /// `public.ecr.aws/lambda/provided:al2023`
+    Al2023,
+}
+
+impl FromStr for BaseImage {
+    type Err = PackagerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "scratch" => Ok(Self::Scratch),
+            "al2023" | "provided.al2023" => Ok(Self::Al2023),
+            other => Err(PackagerError::InvalidBaseImage(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for BaseImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Scratch => "scratch",
+            Self::Al2023 => "al2023",
+        })
+    }
+}
+
+/// Docker platform string for an architecture
+fn platform(arch: Arch) -> &'static str {
+    match arch {
+        Arch::Arm64 => "linux/arm64",
+        Arch::X86_64 => "linux/amd64",
+    }
+}
+
+/// Render the Dockerfile for `base` and `arch`
+#[must_use]
+pub fn dockerfile(base: BaseImage, arch: Arch) -> String {
+    let platform = platform(arch);
+    match base {
+        BaseImage::Scratch => format!(
+            "# Generated by cargo packager --format oci --base scratch\n\
+             FROM --platform={platform} scratch\n\
+             ENV LAMBDA_TASK_ROOT=/var/task LAMBDA_RUNTIME_DIR=/var/runtime\n\
+             COPY {BOOTSTRAP_NAME} /var/runtime/{BOOTSTRAP_NAME}\n\
+             WORKDIR /var/task\n\
+             ENTRYPOINT [\"/var/runtime/{BOOTSTRAP_NAME}\"]\n"
+        ),
+        BaseImage::Al2023 => format!(
+            "# Generated by cargo packager --format oci --base al2023\n\
+             FROM --platform={platform} {AL2023_BASE_IMAGE}\n\
+             COPY {BOOTSTRAP_NAME} ${{LAMBDA_RUNTIME_DIR}}/{BOOTSTRAP_NAME}\n\
+             CMD [\"{BOOTSTRAP_NAME}\"]\n"
+        ),
+    }
+}
+
+/// Write a Docker build context (`bootstrap` + `Dockerfile`) into `dir`
+///
+/// Returns the total size of the context in bytes.
+///
+/// # Errors
+///
+/// Returns `PackagerError::Io` if the context cannot be written
+pub fn write_image_context(binary: &Path, dir: &Path, base: BaseImage, arch: Arch) -> Result<u64> {
+    fs::create_dir_all(dir)?;
+
+    let bootstrap = dir.join(BOOTSTRAP_NAME);
+    let binary_size = fs::copy(binary, &bootstrap)?;
+    set_executable(&bootstrap)?;
+
+    let contents = dockerfile(base, arch);
+    fs::write(dir.join("Dockerfile"), &contents)?;
+
+    Ok(binary_size + contents.len() as u64)
+}
+
+/// Docker preserves context file modes, so mark the bootstrap 0755
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_image_from_str() {
+        assert_eq!("scratch".parse::<BaseImage>().unwrap(), BaseImage::Scratch);
+        assert_eq!("al2023".parse::<BaseImage>().unwrap(), BaseImage::Al2023);
+        assert!(matches!(
+            "alpine".parse::<BaseImage>(),
+            Err(PackagerError::InvalidBaseImage(_))
+        ));
+    }
+
+    #[test]
+    fn test_dockerfile_scratch() {
+        let dockerfile = dockerfile(BaseImage::Scratch, Arch::Arm64);
+        assert!(dockerfile.contains("FROM --platform=linux/arm64 scratch"));
+        assert!(dockerfile.contains("COPY bootstrap /var/runtime/bootstrap"));
+        assert!(dockerfile.contains("ENTRYPOINT [\"/var/runtime/bootstrap\"]"));
+    }
+
+    #[test]
+    fn test_dockerfile_al2023() {
+        let dockerfile = dockerfile(BaseImage::Al2023, Arch::X86_64);
+        assert!(dockerfile
+            .contains("FROM --platform=linux/amd64 public.ecr.aws/lambda/provided:al2023"));
+        assert!(dockerfile.contains("COPY bootstrap ${LAMBDA_RUNTIME_DIR}/bootstrap"));
+    }
+
+    #[test]
+    fn test_write_image_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join(BOOTSTRAP_NAME);
+        fs::write(&binary, b"fake bootstrap").unwrap();
+        let context = dir.path().join("oci");
+
+        let size = write_image_context(&binary, &context, BaseImage::Scratch, Arch::Arm64).unwrap();
+
+        assert_eq!(
+            fs::read(context.join(BOOTSTRAP_NAME)).unwrap(),
+            b"fake bootstrap"
+        );
+        let dockerfile = fs::read_to_string(context.join("Dockerfile")).unwrap();
+        assert!(dockerfile.contains("scratch"));
+        assert_eq!(size, 14 + dockerfile.len() as u64);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(context.join(BOOTSTRAP_NAME))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// Profile-guided optimization (PGO)
+//
+// `--pgo` runs rustc's PGO workflow before packaging:
+// 1. build the requested profile as the baseline, and an instrumented
+//    bootstrap (release-pgo-generate, -Cprofile-generate)
+// 2. train it against the emulator (training.rs)
+// 3. merge the .profraw files with llvm-profdata (rustup's llvm-tools
+//    component if installed - it matches rustc's LLVM - else from PATH)
+// 4. rebuild with -Cprofile-use (release-pgo-use) and package that binary
+//
+// Baseline and optimized binaries are then cold-started against the emulator
+// to report what PGO bought. Profiles live under target/pgo-data/<triple>/.
+
+use crate::training::{cold_start, require_host_arch, train, training_events};
+use crate::{
+    cargo_build, strip_binary, OptimizationReport, PackageOptions, PackagerError, Result,
+    TrainingOptions,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Cargo profile of the instrumented build
+pub const PGO_GENERATE_PROFILE: &str = "release-pgo-generate";
+
+/// Cargo profile of the optimized build
+pub const PGO_USE_PROFILE: &str = "release-pgo-use";
+
+/// Build, train and rebuild the bootstrap with PGO; returns the optimized
+/// binary
+///
+/// The optimized binary is left unstripped when BOLT runs next.
+///
+/// # Errors
+///
+/// Returns `PackagerError::BuildFailed` if a build fails,
+/// `PackagerError::Training` if the target is not the host architecture or
+/// training fails, and `PackagerError::Pgo` if no profile is written or
+/// merging fails
+pub fn optimize(
+    options: &PackageOptions,
+    training: &TrainingOptions,
+) -> Result<(PathBuf, OptimizationReport)> {
+    require_host_arch(options)?;
+    let events = training_events(training)?;
+
+    let data_dir = options
+        .workspace_root
+        .join("target/pgo-data")
+        .join(options.target_triple());
+    let raw_dir = data_dir.join("raw");
+    if raw_dir.exists() {
+        fs::remove_dir_all(&raw_dir)?;
+    }
+    fs::create_dir_all(&raw_dir)?;
+    let merged = data_dir.join("merged.profdata");
+
+    cargo_build(options, &options.profile, &[])?;
+    let baseline = options.binary_path_for(&options.profile);
+    strip_binary(&baseline, options.arch);
+
+    let generate = format!("-Cprofile-generate={}", raw_dir.display());
+    cargo_build(options, PGO_GENERATE_PROFILE, &[generate])?;
+    let instrumented = options.binary_path_for(PGO_GENERATE_PROFILE);
+    let profile_file = raw_dir.join("bootstrap-%p-%m.profraw");
+    let training_invocations = train(
+        &instrumented,
+        &events,
+        training.iterations,
+        &[("LLVM_PROFILE_FILE", profile_file.as_os_str())],
+    )?;
+    if profraw_files(&raw_dir)?.is_empty() {
+        return Err(PackagerError::Pgo(format!(
+            "{} wrote no profile data to {}",
+            instrumented.display(),
+            raw_dir.display()
+        )));
+    }
+    merge_profiles(&raw_dir, &merged)?;
+
+    let profile_use = format!("-Cprofile-use={}", merged.display());
+    cargo_build(options, PGO_USE_PROFILE, &[profile_use])?;
+    let optimized = options.binary_path_for(PGO_USE_PROFILE);
+    if options.bolt.is_none() {
+        strip_binary(&optimized, options.arch);
+    }
+
+    let report = OptimizationReport {
+        baseline_size: fs::metadata(&baseline)?.len(),
+        optimized_size: fs::metadata(&optimized)?.len(),
+        baseline_cold_start: cold_start(&baseline, &events[0], training.cold_starts)?,
+        optimized_cold_start: cold_start(&optimized, &events[0], training.cold_starts)?,
+        training_invocations,
+    };
+    Ok((optimized, report))
+}
+
+/// Merge the `.profraw` files in `raw_dir` into `merged`
+fn merge_profiles(raw_dir: &Path, merged: &Path) -> Result<()> {
+    let tool = llvm_profdata();
+    let status = Command::new(&tool)
+        .arg("merge")
+        .arg("-o")
+        .arg(merged)
+        .args(profraw_files(raw_dir)?)
+        .status()
+        .map_err(|e| {
+            PackagerError::Pgo(format!(
+                "failed to run {} ({e}); install it with: rustup component add llvm-tools",
+                tool.display()
+            ))
+        })?;
+    if !status.success() {
+        return Err(PackagerError::Pgo(format!(
+            "{} merge exited with {status} (it must match rustc's LLVM version, \
+             see rustc -vV; rustup component add llvm-tools installs one that does)",
+            tool.display()
+        )));
+    }
+    Ok(())
+}
+
+/// `llvm-profdata` of rustc's toolchain if installed, else the one on PATH
+fn llvm_profdata() -> PathBuf {
+    let sysroot = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()));
+    sysroot
+        .and_then(|sysroot| fs::read_dir(sysroot.join("lib/rustlib")).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path().join("bin/llvm-profdata"))
+        .find(|tool| tool.is_file())
+        .unwrap_or_else(|| PathBuf::from("llvm-profdata"))
+}
+
+/// `.profraw` files in `dir`
+fn profraw_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "profraw")
+        {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profraw_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("bootstrap-1-2.profraw"), b"").unwrap();
+        fs::write(dir.path().join("merged.profdata"), b"").unwrap();
+        assert_eq!(profraw_files(dir.path()).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,278 @@
+// Deployment template generation (SAM / Terraform)
+//
+// Emits infrastructure for the example handlers so benchmarks don't need
+// manual console setup. Every function uses the custom runtime conventions:
+// runtime provided.al2023, handler `bootstrap`, one zip per handler at
+// <code_dir>/<handler>.zip (build each with the matching handler selected in
+// crates/bootstrap/src/main.rs, then `cargo packager -o <code_dir>/<handler>.zip`).
+//
+// Memory defaults to 128MB, the benchmark baseline (scripts/deploy-to-aws.sh).
+
+use crate::{Arch, PackagerError, Result};
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Infrastructure-as-code flavour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateFormat {
+    /// AWS SAM `template.yaml`
+    Sam,
+    /// Terraform module `main.tf`
+    Terraform,
+}
+
+impl TemplateFormat {
+    /// File name written for this format
+    #[must_use]
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Sam => "template.yaml",
+            Self::Terraform => "main.tf",
+        }
+    }
+}
+
+impl FromStr for TemplateFormat {
+    type Err = PackagerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sam" => Ok(Self::Sam),
+            "terraform" | "tf" => Ok(Self::Terraform),
+            other => Err(PackagerError::InvalidTemplateFormat(other.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for TemplateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sam => "sam",
+            Self::Terraform => "terraform",
+        })
+    }
+}
+
+/// Deployment settings for one example function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionSpec {
+    /// Handler key (`minimal`, `fibonacci`, `simd`); also the zip file stem
+    pub handler: &'static str,
+    /// Lambda function name
+    pub function_name: &'static str,
+    /// Instruction set architecture
+    pub arch: Arch,
+    /// Memory size (MB)
+    pub memory_mb: u32,
+    /// Timeout (seconds)
+    pub timeout_secs: u32,
+}
+
+impl FunctionSpec {
+    /// CamelCase logical id for SAM resources
+    fn logical_id(&self) -> String {
+        let mut chars = self.handler.chars();
+        chars.next().map_or_else(String::new, |first| {
+            format!("{}{}Function", first.to_ascii_uppercase(), chars.as_str())
+        })
+    }
+}
+
+/// Example handlers deployed by the benchmark scripts
+///
+/// The SIMD handler uses NEON intrinsics and must run on Graviton.
+pub const EXAMPLE_FUNCTIONS: &[FunctionSpec] = &[
+    FunctionSpec {
+        handler: "minimal",
+        function_name: "ruchy-lambda-minimal",
+        arch: Arch::Arm64,
+        memory_mb: 128,
+        timeout_secs: 3,
+    },
+    FunctionSpec {
+        handler: "fibonacci",
+        function_name: "ruchy-lambda-fibonacci",
+        arch: Arch::Arm64,
+        memory_mb: 128,
+        timeout_secs: 30,
+    },
+    FunctionSpec {
+        handler: "simd",
+        function_name: "ruchy-lambda-simd",
+        arch: Arch::Arm64,
+        memory_mb: 128,
+        timeout_secs: 10,
+    },
+];
+
+/// Render a SAM template for `functions` with code zips under `code_dir`
+#[must_use]
+pub fn sam_template(functions: &[FunctionSpec], code_dir: &str) -> String {
+    let mut out = String::from(
+        "# Generated by cargo packager templates --format sam\n\
+         AWSTemplateFormatVersion: '2010-09-09'\n\
+         Transform: AWS::Serverless-2016-10-31\n\
+         Description: Ruchy Lambda example functions\n\
+         \n\
+         Globals:\n\
+         \x20 Function:\n\
+         \x20   Runtime: provided.al2023\n\
+         \x20   Handler: bootstrap\n\
+         \n\
+         Resources:\n",
+    );
+
+    for function in functions {
+        let _ = write!(
+            out,
+            "  {logical_id}:\n\
+             \x20   Type: AWS::Serverless::Function\n\
+             \x20   Properties:\n\
+             \x20     FunctionName: {name}\n\
+             \x20     CodeUri: {code_dir}/{handler}.zip\n\
+             \x20     Architectures:\n\
+             \x20       - {arch}\n\
+             \x20     MemorySize: {memory}\n\
+             \x20     Timeout: {timeout}\n",
+            logical_id = function.logical_id(),
+            name = function.function_name,
+            handler = function.handler,
+            arch = function.arch.lambda_name(),
+            memory = function.memory_mb,
+            timeout = function.timeout_secs,
+        );
+    }
+    out
+}
+
+/// Render a Terraform module for `functions` with code zips under `code_dir`
+#[must_use]
+pub fn terraform_module(functions: &[FunctionSpec], code_dir: &str) -> String {
+    let mut out = String::from(
+        "# Generated by cargo packager templates --format terraform\n\
+         \n\
+         resource \"aws_iam_role\" \"ruchy_lambda\" {\n\
+         \x20 name = \"ruchy-lambda-execution-role\"\n\
+         \x20 assume_role_policy = jsonencode({\n\
+         \x20   Version = \"2012-10-17\"\n\
+         \x20   Statement = [{\n\
+         \x20     Effect    = \"Allow\"\n\
+         \x20     Principal = { Service = \"lambda.amazonaws.com\" }\n\
+         \x20     Action    = \"sts:AssumeRole\"\n\
+         \x20   }]\n\
+         \x20 })\n\
+         }\n\
+         \n\
+         resource \"aws_iam_role_policy_attachment\" \"ruchy_lambda_logs\" {\n\
+         \x20 role       = aws_iam_role.ruchy_lambda.name\n\
+         \x20 policy_arn = \"arn:aws:iam::aws:policy/service-role/AWSLambdaBasicExecutionRole\"\n\
+         }\n",
+    );
+
+    for function in functions {
+        let _ = write!(
+            out,
+            "\n\
+             resource \"aws_lambda_function\" \"{handler}\" {{\n\
+             \x20 function_name    = \"{name}\"\n\
+             \x20 role             = aws_iam_role.ruchy_lambda.arn\n\
+             \x20 runtime          = \"provided.al2023\"\n\
+             \x20 handler          = \"bootstrap\"\n\
+             \x20 architectures    = [\"{arch}\"]\n\
+             \x20 memory_size      = {memory}\n\
+             \x20 timeout          = {timeout}\n\
+             \x20 filename         = \"${{path.module}}/{code_dir}/{handler}.zip\"\n\
+             \x20 source_code_hash = filebase64sha256(\"${{path.module}}/{code_dir}/{handler}.zip\")\n\
+             }}\n",
+            handler = function.handler,
+            name = function.function_name,
+            arch = function.arch.lambda_name(),
+            memory = function.memory_mb,
+            timeout = function.timeout_secs,
+        );
+    }
+    out
+}
+
+/// Write the template for `format` into `dir`, returning the file path
+///
+/// # Errors
+///
+/// Returns `PackagerError::Io` if the template cannot be written
+pub fn write_templates(format: TemplateFormat, dir: &Path, code_dir: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let contents = match format {
+        TemplateFormat::Sam => sam_template(EXAMPLE_FUNCTIONS, code_dir),
+        TemplateFormat::Terraform => terraform_module(EXAMPLE_FUNCTIONS, code_dir),
+    };
+    let path = dir.join(format.file_name());
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_format_from_str() {
+        assert_eq!(
+            "sam".parse::<TemplateFormat>().unwrap(),
+            TemplateFormat::Sam
+        );
+        assert_eq!(
+            "tf".parse::<TemplateFormat>().unwrap(),
+            TemplateFormat::Terraform
+        );
+        assert!(matches!(
+            "cdk".parse::<TemplateFormat>(),
+            Err(PackagerError::InvalidTemplateFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_simd_function_is_arm64() {
+        let simd = EXAMPLE_FUNCTIONS
+            .iter()
+            .find(|f| f.handler == "simd")
+            .unwrap();
+        assert_eq!(simd.arch, Arch::Arm64);
+    }
+
+    #[test]
+    fn test_sam_template() {
+        let template = sam_template(EXAMPLE_FUNCTIONS, "build");
+        assert!(template.contains("Transform: AWS::Serverless-2016-10-31"));
+        assert!(template.contains("Runtime: provided.al2023"));
+        assert!(template.contains("Handler: bootstrap"));
+        for name in ["MinimalFunction:", "FibonacciFunction:", "SimdFunction:"] {
+            assert!(template.contains(name), "missing {name}");
+        }
+        assert!(template.contains("CodeUri: build/fibonacci.zip"));
+        assert!(template.contains("      - arm64"));
+        assert!(template.contains("MemorySize: 128"));
+    }
+
+    #[test]
+    fn test_terraform_module() {
+        let module = terraform_module(EXAMPLE_FUNCTIONS, "build");
+        assert!(module.contains("resource \"aws_lambda_function\" \"minimal\" {"));
+        assert!(module.contains("resource \"aws_lambda_function\" \"simd\" {"));
+        assert!(module.contains("architectures    = [\"arm64\"]"));
+        assert!(module.contains("filename         = \"${path.module}/build/simd.zip\""));
+        assert_eq!(module.matches('{').count(), module.matches('}').count());
+    }
+
+    #[test]
+    fn test_write_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_templates(TemplateFormat::Terraform, dir.path(), "build").unwrap();
+        assert_eq!(path, dir.path().join("main.tf"));
+        assert!(fs::read_to_string(path)
+            .unwrap()
+            .contains("aws_lambda_function"));
+    }
+}
@@ -0,0 +1,286 @@
+// Training runs and cold-start measurement for the optimizing builds
+//
+// PGO (pgo.rs) and BOLT (bolt.rs) both learn from an instrumented bootstrap
+// serving representative events, and both report what they bought the same
+// way. This is the shared part:
+// - train: run an instrumented bootstrap against the emulator, invoke the
+//   events (the built-in fixtures, or recorded events) a number of times,
+//   then take the Runtime API away so the bootstrap's circuit breaker exits
+//   the process - instrumentation writes its profile at exit
+// - cold_start: spawn-to-first-response time, median of fresh processes
+//
+// Training executes the bootstrap, so the target architecture must be the
+// host's.
+
+use crate::{Arch, PackageOptions, PackagerError, Result};
+use ruchy_lambda_emulator::{load_dir, Emulator, EmulatorConfig, Outcome, FIXTURES};
+use std::ffi::OsStr;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default passes over the training events
+pub const DEFAULT_TRAINING_ITERATIONS: u32 = 50;
+
+/// Default cold starts measured per binary
+pub const DEFAULT_COLD_STARTS: u32 = 5;
+
+/// How long a bootstrap gets to exit (and write its profile) once the
+/// emulator is gone
+const EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Training and measurement settings of an optimizing build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrainingOptions {
+    /// Directory of recorded events (`*.json`, see the emulator's `--events`)
+    /// to train with instead of the built-in fixtures
+    pub events_dir: Option<PathBuf>,
+    /// Passes over the training events
+    pub iterations: u32,
+    /// Cold starts measured per binary for the report
+    pub cold_starts: u32,
+}
+
+impl Default for TrainingOptions {
+    fn default() -> Self {
+        Self {
+            events_dir: None,
+            iterations: DEFAULT_TRAINING_ITERATIONS,
+            cold_starts: DEFAULT_COLD_STARTS,
+        }
+    }
+}
+
+/// What an optimization changed, baseline against optimized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizationReport {
+    /// Baseline binary size (bytes)
+    pub baseline_size: u64,
+    /// Optimized binary size (bytes)
+    pub optimized_size: u64,
+    /// Median baseline cold start (spawn to first response)
+    pub baseline_cold_start: Duration,
+    /// Median optimized cold start
+    pub optimized_cold_start: Duration,
+    /// Invocations served while training
+    pub training_invocations: u64,
+}
+
+impl OptimizationReport {
+    /// Size change in bytes (negative: smaller)
+    #[must_use]
+    pub fn size_delta(&self) -> i64 {
+        let signed = |size: u64| i64::try_from(size).unwrap_or(i64::MAX);
+        signed(self.optimized_size) - signed(self.baseline_size)
+    }
+
+    /// Cold start change in percent of the baseline (negative: faster)
+    #[must_use]
+    pub fn cold_start_delta_percent(&self) -> f64 {
+        let baseline = self.baseline_cold_start.as_secs_f64();
+        if baseline == 0.0 {
+            return 0.0;
+        }
+        (self.optimized_cold_start.as_secs_f64() - baseline) / baseline * 100.0
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "size {}KB -> {}KB ({:+}B), cold start {:.2?} -> {:.2?} ({:+.1}%), \
+             trained on {} invocations",
+            self.baseline_size / 1024,
+            self.optimized_size / 1024,
+            self.size_delta(),
+            self.baseline_cold_start,
+            self.optimized_cold_start,
+            self.cold_start_delta_percent(),
+            self.training_invocations
+        )
+    }
+}
+
+/// Fail unless binaries built for `options` can run here
+pub(crate) fn require_host_arch(options: &PackageOptions) -> Result<()> {
+    if Arch::host() == Some(options.arch) {
+        return Ok(());
+    }
+    Err(PackagerError::Training(format!(
+        "training runs the bootstrap, so --arch {} must match the host ({})",
+        options.arch,
+        std::env::consts::ARCH
+    )))
+}
+
+/// Payloads to train with
+pub(crate) fn training_events(training: &TrainingOptions) -> Result<Vec<Vec<u8>>> {
+    let events: Vec<Vec<u8>> = match &training.events_dir {
+        Some(dir) => load_dir(dir)?
+            .into_iter()
+            .map(|event| event.payload)
+            .collect(),
+        None => FIXTURES
+            .iter()
+            .map(|(_, event)| event.as_bytes().to_vec())
+            .collect(),
+    };
+    if events.is_empty() {
+        return Err(PackagerError::Training("no training events".to_string()));
+    }
+    Ok(events)
+}
+
+/// Serve `events` `iterations` times to the instrumented `binary` (with
+/// `env` added), then let it exit; returns the number of invocations
+pub(crate) fn train(
+    binary: &Path,
+    events: &[Vec<u8>],
+    iterations: u32,
+    env: &[(&str, &OsStr)],
+) -> Result<u64> {
+    let emulator = start_emulator()?;
+    let mut child = spawn(&emulator, binary, env)?;
+
+    let mut invocations = 0;
+    for _ in 0..iterations.max(1) {
+        for event in events {
+            if let Err(e) = invoke(&emulator, event) {
+                stop(&mut child);
+                return Err(e);
+            }
+            invocations += 1;
+        }
+    }
+
+    // Without a Runtime API the bootstrap's circuit breaker exits the
+    // process, which writes the profile
+    emulator.shutdown();
+    wait_for_exit(&mut child)?;
+    Ok(invocations)
+}
+
+/// Median time from spawning `binary` to its first response, over `runs`
+/// fresh processes
+pub(crate) fn cold_start(binary: &Path, event: &[u8], runs: u32) -> Result<Duration> {
+    let mut samples = Vec::new();
+    for _ in 0..runs.max(1) {
+        let emulator = start_emulator()?;
+        let started = Instant::now();
+        let mut child = spawn(&emulator, binary, &[])?;
+        let result = invoke(&emulator, event);
+        let elapsed = started.elapsed();
+        stop(&mut child);
+        result?;
+        samples.push(elapsed);
+    }
+    samples.sort();
+    Ok(samples[samples.len() / 2])
+}
+
+/// Emulator on a free local port
+fn start_emulator() -> Result<Emulator> {
+    Ok(Emulator::start(
+        EmulatorConfig::default().with_addr(SocketAddr::from(([127, 0, 0, 1], 0))),
+    )?)
+}
+
+/// Start `binary` against `emulator`, output discarded
+fn spawn(emulator: &Emulator, binary: &Path, env: &[(&str, &OsStr)]) -> Result<Child> {
+    Ok(Command::new(binary)
+        .envs(emulator.runtime_env())
+        .env("RUCHY_LAMBDA_FATAL_FAILURES", "3")
+        .env("RUCHY_LAMBDA_BACKOFF_INITIAL_MS", "1")
+        .envs(env.iter().copied())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?)
+}
+
+/// Invoke `event`, failing if the bootstrap never answered
+fn invoke(emulator: &Emulator, event: &[u8]) -> Result<()> {
+    let invocation = emulator
+        .invoke(event)
+        .map_err(|e| PackagerError::Training(format!("invoke failed: {e}")))?;
+    match invocation.outcome {
+        Outcome::Timeout | Outcome::RuntimeExit { .. } => Err(PackagerError::Training(format!(
+            "bootstrap did not answer: {:?}",
+            invocation.outcome
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Wait up to [`EXIT_TIMEOUT`] for `child` to exit on its own
+fn wait_for_exit(child: &mut Child) -> Result<()> {
+    let deadline = Instant::now() + EXIT_TIMEOUT;
+    while Instant::now() < deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    stop(child);
+    Err(PackagerError::Training(format!(
+        "bootstrap did not exit within {EXIT_TIMEOUT:?} of the Runtime API going away"
+    )))
+}
+
+fn stop(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_deltas() {
+        let report = OptimizationReport {
+            baseline_size: 500 * 1024,
+            optimized_size: 490 * 1024,
+            baseline_cold_start: Duration::from_millis(10),
+            optimized_cold_start: Duration::from_millis(8),
+            training_invocations: 500,
+        };
+        assert_eq!(report.size_delta(), -10 * 1024);
+        assert!((report.cold_start_delta_percent() + 20.0).abs() < 1e-9);
+        let line = report.to_string();
+        assert!(line.contains("500KB -> 490KB"), "{line}");
+        assert!(line.contains("-20.0%"), "{line}");
+    }
+
+    #[test]
+    fn test_training_events() {
+        let events = training_events(&TrainingOptions::default()).unwrap();
+        assert_eq!(events.len(), FIXTURES.len());
+
+        let dir = tempfile::tempdir().unwrap();
+        let options = TrainingOptions {
+            events_dir: Some(dir.path().to_path_buf()),
+            ..TrainingOptions::default()
+        };
+        assert!(matches!(
+            training_events(&options),
+            Err(PackagerError::Training(_))
+        ));
+    }
+
+    #[test]
+    fn test_foreign_arch_rejected() {
+        let foreign = match Arch::host() {
+            Some(Arch::Arm64) => Arch::X86_64,
+            _ => Arch::Arm64,
+        };
+        let options = PackageOptions::new("release-ultra", foreign);
+        assert!(matches!(
+            require_host_arch(&options),
+            Err(PackagerError::Training(_))
+        ));
+    }
+}
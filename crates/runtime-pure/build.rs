@@ -4,17 +4,37 @@
 // Transpiles to *_generated.rs during build
 //
 // Follows proven pattern from crates/bootstrap/build.rs
+//
+// Without a `ruchy` binary on PATH the checked-in lib_generated.rs is kept,
+// but its transpiled part is run through the same post-processing again, so
+// edits to the rewrites below still reach the output.
 
 use std::env;
 use std::path::Path;
 use std::process::Command;
 
+/// Prefix added to every generated file (the transpiler nests every body in
+/// redundant blocks)
+const GENERATED_HEADER: &str = "#![allow(clippy::all, unused_braces)]\n\n";
+
+/// Declaration of the hand-written HTTP client (src/http_client.rs) that
+/// lib.ruchy imports
+const HTTP_CLIENT_MODULE: &str = "mod http_client;\n\n";
+
 fn main() {
     println!("cargo:rerun-if-changed=src/lib.ruchy");
     println!("cargo:rerun-if-changed=src/http_client.ruchy");
     println!("cargo:rerun-if-changed=src/event.ruchy");
     println!("cargo:rerun-if-changed=src/logger.ruchy");
 
+    if Command::new("ruchy").arg("--version").output().is_err() {
+        println!(
+            "cargo:warning=Ruchy compiler not found — post-processing the checked-in src/lib_generated.rs"
+        );
+        reprocess_generated_file("src/lib_generated.rs");
+        return;
+    }
+
     println!("cargo:warning=🔄 Transpiling Pure Ruchy Runtime...");
 
     // Transpile core runtime modules
@@ -23,6 +43,40 @@ fn main() {
     println!("cargo:warning=✅ Pure Ruchy Runtime transpilation complete");
 }
 
+/// Re-run post-processing on a generated file whose transpiler input is unchanged
+///
+/// Strips the header and the `http_client` module declaration to recover the
+/// transpiled code, then rebuilds the file from it. Post-processing is
+/// idempotent, so the file only changes when a rewrite did.
+fn reprocess_generated_file(output: &str) {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let output_path = Path::new(&manifest_dir).join(output);
+    let generated = std::fs::read_to_string(&output_path).expect("Failed to read generated file");
+
+    // Older files may carry a different allow list
+    let mut transpiled = match generated.split_once("\n\n") {
+        Some((header, rest)) if header.starts_with("#![allow(") => rest,
+        _ => generated.as_str(),
+    };
+    if let Some(rest) = transpiled.strip_prefix(HTTP_CLIENT_MODULE) {
+        transpiled = rest;
+    } else if let Some(module) = transpiled.strip_prefix("mod http_client {\n") {
+        // Older files inlined http_client.rs (ending in a newline) followed by
+        // "\n}\n\n"; rustfmt'd code never has a blank line before a column-0 brace
+        let end = module
+            .find("\n\n}\n\n")
+            .expect("Inlined http_client module is not terminated");
+        transpiled = &module[end + "\n\n}\n\n".len()..];
+    }
+
+    let regenerated = post_process(transpiled.to_string());
+    if regenerated != generated {
+        std::fs::write(&output_path, regenerated.as_bytes())
+            .expect("Failed to write transpiled output");
+        println!("cargo:warning=    ✅ Regenerated {output}");
+    }
+}
+
 /// Transpile a single .ruchy file to Rust
 fn transpile_ruchy_file(input: &str, output: &str) {
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -50,8 +104,16 @@ fn transpile_ruchy_file(input: &str, output: &str) {
     }
 
     // Get transpiled code from stdout
-    let mut transpiled = String::from_utf8_lossy(&status.stdout).to_string();
+    let transpiled = String::from_utf8_lossy(&status.stdout).to_string();
+    let transpiled = post_process(transpiled);
 
+    std::fs::write(&output_path, transpiled.as_bytes()).expect("Failed to write transpiled output");
+
+    println!("cargo:warning=    ✅ Transpiled {input} -> {output}");
+}
+
+/// Turn raw transpiler output into the library source
+fn post_process(mut transpiled: String) -> String {
     // Post-process generated code for library usage
 
     // Remove stub modules generated by Ruchy transpiler (we want real std::net)
@@ -73,17 +135,8 @@ fn transpile_ruchy_file(input: &str, output: &str) {
         }
     }
 
-    // Read http_client.rs and inject it as a module
-    let http_client_path = Path::new(&manifest_dir).join("src/http_client.rs");
-    let http_client_code = if http_client_path.exists() {
-        std::fs::read_to_string(&http_client_path).expect("Failed to read http_client.rs")
-    } else {
-        String::from("// http_client.rs not found")
-    };
-
-    // Wrap http_client code in a module
-    let http_client_module = format!("mod http_client {{\n{}\n}}\n\n", http_client_code);
-    transpiled = format!("{}{}", http_client_module, transpiled);
+    // Declare http_client.rs (next to the generated file) as a module
+    transpiled = format!("{HTTP_CLIENT_MODULE}{transpiled}");
 
     // Fix module path separator: http_client.method() -> http_client::method()
     transpiled = transpiled.replace("http_client.http_get(", "http_client::http_get(");
@@ -91,6 +144,27 @@ fn transpile_ruchy_file(input: &str, output: &str) {
         "http_client\n                        .http_post(",
         "http_client::http_post(",
     );
+    transpiled = transpiled.replace("http_client.error_payload(", "http_client::error_payload(");
+    for helper in [
+        "http_get_with_headers(",
+        "http_post_error(",
+        "header(",
+        "header_u64(",
+        "request_id(",
+        "now_ms(",
+    ] {
+        transpiled = transpiled.replace(
+            &format!("http_client.{helper}"),
+            &format!("http_client::{helper}"),
+        );
+    }
+
+    // A helper missing from the list above would surface as a confusing
+    // "no field `http_client`" error in the generated file
+    if let Some(at) = transpiled.find("http_client.") {
+        let call = transpiled[at..].lines().next().unwrap_or_default();
+        panic!("Unrewritten http_client call in transpiled output: {call} (add it to build.rs)");
+    }
 
     // Fix method signatures: `self` -> `&self` for borrowing
     transpiled = transpiled.replace("fn next_event(self)", "fn next_event(&self)");
@@ -98,15 +172,18 @@ fn transpile_ruchy_file(input: &str, output: &str) {
     transpiled = transpiled.replace("fn http_get(self,", "fn http_get(&self,");
     transpiled = transpiled.replace("fn http_post(self,", "fn http_post(&self,");
     transpiled = transpiled.replace("fn parse_response(self,", "fn parse_response(&self,");
+    transpiled = transpiled.replace("fn post_error(self,", "fn post_error(&self,");
+    transpiled = transpiled.replace("fn post_init_error(self,", "fn post_init_error(&self,");
+    transpiled = transpiled.replace("fn next_invocation(self)", "fn next_invocation(&self)");
+    transpiled = transpiled.replace("fn remaining_time_ms(self)", "fn remaining_time_ms(&self)");
     transpiled = transpiled.replace("fn endpoint(self)", "fn endpoint(&self)");
 
+    // `pub fun` inside an impl comes out as `pub pub fn`
+    transpiled = transpiled.replace("pub pub fn ", "pub fn ");
+
     // Remove main() function (not needed for library)
     transpiled = transpiled.replace("fn main() {}", "");
 
     // Add clippy suppression for generated code
-    transpiled = format!("#![allow(clippy::all)]\n\n{}", transpiled);
-
-    std::fs::write(&output_path, transpiled.as_bytes()).expect("Failed to write transpiled output");
-
-    println!("cargo:warning=    ✅ Transpiled {input} -> {output}");
+    format!("{GENERATED_HEADER}{transpiled}")
 }
@@ -1,97 +1,123 @@
 // Rust HTTP client for Pure Ruchy runtime
 // This module is imported by lib.ruchy to avoid parser limitations
+//
+// TCP I/O and response parsing are shared with crates/runtime via
+// ruchy-lambda-http-core; this module adapts them to String errors for Ruchy.
 
-use std::io::{self, Read, Write};
-use std::net::TcpStream;
+use ruchy_lambda_http_core as http_core;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Response headers as (lower-cased name, value) pairs
+pub type HeaderMap = Vec<(String, String)>;
 
 /// Make HTTP GET request and return (request_id, body)
 pub fn http_get(endpoint: &str, path: &str) -> Result<(String, String), String> {
-    let mut stream = TcpStream::connect(endpoint)
-        .map_err(|e| format!("Connection failed: {}", e))?;
-
-    let request = format!(
-        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
-        path, endpoint
-    );
-
-    stream
-        .write_all(request.as_bytes())
-        .map_err(|e| format!("Write failed: {}", e))?;
-
-    stream
-        .flush()
-        .map_err(|e| format!("Flush failed: {}", e))?;
-
-    let mut buffer = Vec::new();
-    stream
-        .read_to_end(&mut buffer)
-        .map_err(|e| format!("Read failed: {}", e))?;
-
-    let response = String::from_utf8_lossy(&buffer).to_string();
-    parse_response(&response)
+    let (headers, body) = http_get_with_headers(endpoint, path)?;
+    Ok((request_id(&headers), body))
 }
 
-/// Make HTTP POST request
-pub fn http_post(endpoint: &str, path: &str, body: &str) -> Result<(), String> {
-    let mut stream = TcpStream::connect(endpoint)
-        .map_err(|e| format!("Connection failed: {}", e))?;
+/// Make HTTP GET request and return (headers, body)
+pub fn http_get_with_headers(endpoint: &str, path: &str) -> Result<(HeaderMap, String), String> {
+    let response = http_core::get(endpoint, path).map_err(|e| e.to_string())?;
 
-    let request = format!(
-        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        path, endpoint, body.len(), body
-    );
+    let headers = response
+        .headers
+        .into_iter()
+        .map(|(name, value)| (name.to_lowercase(), value))
+        .collect();
 
-    stream
-        .write_all(request.as_bytes())
-        .map_err(|e| format!("Write failed: {}", e))?;
+    // Ruchy handlers expect a JSON document, so an empty body reads as "{}"
+    let body = if response.body.is_empty() {
+        String::from("{}")
+    } else {
+        response.body
+    };
 
-    stream
-        .flush()
-        .map_err(|e| format!("Flush failed: {}", e))?;
+    Ok((headers, body))
+}
 
-    let mut buffer = vec![0u8; 1024];
-    let n = stream
-        .read(&mut buffer)
-        .map_err(|e| format!("Read failed: {}", e))?;
+/// Look up a header value by lower-case name ("" when missing)
+pub fn header(headers: &HeaderMap, name: &str) -> String {
+    headers
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+        .unwrap_or_default()
+}
 
-    let response = String::from_utf8_lossy(&buffer[..n]).to_string();
+/// Look up a numeric header value by lower-case name (0 when missing/invalid)
+pub fn header_u64(headers: &HeaderMap, name: &str) -> u64 {
+    header(headers, name).parse().unwrap_or(0)
+}
 
-    if response.contains("HTTP/1.1 2") {
-        Ok(())
+/// Extract Lambda-Runtime-Aws-Request-Id (with fallback for missing header)
+pub fn request_id(headers: &HeaderMap) -> String {
+    let id = header(headers, "lambda-runtime-aws-request-id");
+    if id.is_empty() {
+        String::from("unknown-request-id")
     } else {
-        Err(format!("POST failed: {}", response.lines().next().unwrap_or("unknown")))
+        id
     }
 }
 
-/// Parse HTTP response to extract request_id header and body
-fn parse_response(response: &str) -> Result<(String, String), String> {
-    let mut request_id = String::new();
-    let mut body_start = 0;
+/// Current time in Unix epoch milliseconds
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
 
-    let lines: Vec<&str> = response.lines().collect();
+/// Make HTTP POST request
+pub fn http_post(endpoint: &str, path: &str, body: &str) -> Result<(), String> {
+    http_core::post(endpoint, path, body).map_err(|e| e.to_string())
+}
 
-    for (i, line) in lines.iter().enumerate() {
-        if line.starts_with("Lambda-Runtime-Aws-Request-Id:") {
-            if let Some(id) = line.split(':').nth(1) {
-                request_id = id.trim().to_string();
-            }
-        }
+/// Make HTTP POST request to an error endpoint
+///
+/// Sends `Lambda-Runtime-Function-Error-Type` so Lambda can classify the error.
+pub fn http_post_error(
+    endpoint: &str,
+    path: &str,
+    error_type: &str,
+    body: &str,
+) -> Result<(), String> {
+    http_core::post_with_headers(
+        endpoint,
+        path,
+        body,
+        &[("Lambda-Runtime-Function-Error-Type", error_type)],
+    )
+    .map_err(|e| e.to_string())
+}
 
-        if line.is_empty() {
-            body_start = i + 1;
-            break;
-        }
-    }
+/// Build the Lambda error document posted to the error endpoints
+///
+/// Format: {"errorMessage":"...","errorType":"...","stackTrace":[]}, the
+/// document ruchy-lambda-runtime sends for an error without a stack trace
+pub fn error_payload(error_type: &str, error_message: &str) -> String {
+    format!(
+        "{{\"errorMessage\":\"{}\",\"errorType\":\"{}\",\"stackTrace\":[]}}",
+        escape_json(error_message),
+        escape_json(error_type)
+    )
+}
 
-    if request_id.is_empty() {
-        request_id = String::from("unknown-request-id");
+/// Escape a string for embedding in a JSON string literal
+fn escape_json(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+
+    for ch in s.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
     }
 
-    let body = if body_start < lines.len() {
-        lines[body_start..].join("\n")
-    } else {
-        String::from("{}")
-    };
-
-    Ok((request_id, body))
+    result
 }
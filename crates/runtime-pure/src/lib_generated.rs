@@ -1,120 +1,44 @@
-#![allow(clippy::all)]
+#![allow(clippy::all, unused_braces)]
 
-mod http_client {
-// Rust HTTP client for Pure Ruchy runtime
-// This module is imported by lib.ruchy to avoid parser limitations
+mod http_client;
 
-use std::io::{self, Read, Write};
-use std::net::TcpStream;
-
-/// Make HTTP GET request and return (request_id, body)
-pub fn http_get(endpoint: &str, path: &str) -> Result<(String, String), String> {
-    let mut stream = TcpStream::connect(endpoint)
-        .map_err(|e| format!("Connection failed: {}", e))?;
-
-    let request = format!(
-        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
-        path, endpoint
-    );
-
-    stream
-        .write_all(request.as_bytes())
-        .map_err(|e| format!("Write failed: {}", e))?;
-
-    stream
-        .flush()
-        .map_err(|e| format!("Flush failed: {}", e))?;
-
-    let mut buffer = Vec::new();
-    stream
-        .read_to_end(&mut buffer)
-        .map_err(|e| format!("Read failed: {}", e))?;
-
-    let response = String::from_utf8_lossy(&buffer).to_string();
-    parse_response(&response)
-}
-
-/// Make HTTP POST request
-pub fn http_post(endpoint: &str, path: &str, body: &str) -> Result<(), String> {
-    let mut stream = TcpStream::connect(endpoint)
-        .map_err(|e| format!("Connection failed: {}", e))?;
-
-    let request = format!(
-        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        path, endpoint, body.len(), body
-    );
-
-    stream
-        .write_all(request.as_bytes())
-        .map_err(|e| format!("Write failed: {}", e))?;
-
-    stream
-        .flush()
-        .map_err(|e| format!("Flush failed: {}", e))?;
-
-    let mut buffer = vec![0u8; 1024];
-    let n = stream
-        .read(&mut buffer)
-        .map_err(|e| format!("Read failed: {}", e))?;
-
-    let response = String::from_utf8_lossy(&buffer[..n]).to_string();
-
-    if response.contains("HTTP/1.1 2") {
-        Ok(())
-    } else {
-        Err(format!("POST failed: {}", response.lines().next().unwrap_or("unknown")))
-    }
+#[derive(Clone)]
+pub struct Context {
+    pub request_id: String,
+    pub deadline_ms: u64,
+    pub invoked_function_arn: String,
+    pub trace_id: String,
 }
-
-/// Parse HTTP response to extract request_id header and body
-fn parse_response(response: &str) -> Result<(String, String), String> {
-    let mut request_id = String::new();
-    let mut body_start = 0;
-
-    let lines: Vec<&str> = response.lines().collect();
-
-    for (i, line) in lines.iter().enumerate() {
-        if line.starts_with("Lambda-Runtime-Aws-Request-Id:") {
-            if let Some(id) = line.split(':').nth(1) {
-                request_id = id.trim().to_string();
+impl Context {
+    pub fn remaining_time_ms(&self) -> u64 {
+        {
+            {
+                let now = http_client::now_ms();
+                if self.deadline_ms > now {
+                    self.deadline_ms - now
+                } else {
+                    0
+                }
             }
         }
-
-        if line.is_empty() {
-            body_start = i + 1;
-            break;
-        }
-    }
-
-    if request_id.is_empty() {
-        request_id = String::from("unknown-request-id");
     }
-
-    let body = if body_start < lines.len() {
-        lines[body_start..].join("\n")
-    } else {
-        String::from("{}")
-    };
-
-    Ok((request_id, body))
-}
-
 }
-
 #[derive(Clone)]
 pub struct Runtime {
     api_endpoint: String,
 }
 impl Runtime {
-    pub pub fn new() -> Runtime {
+    pub fn new() -> Runtime {
         {
             {
                 let endpoint = String::from("127.0.0.1:9001");
-                Runtime { api_endpoint: endpoint }
+                Runtime {
+                    api_endpoint: endpoint,
+                }
             }
         }
     }
-    pub pub fn next_event(&self) -> (String, String) {
+    pub fn next_event(&self) -> (String, String) {
         {
             {
                 let path = String::from("/2018-06-01/runtime/invocation/next");
@@ -135,26 +59,109 @@ impl Runtime {
             }
         }
     }
-    pub pub fn post_response(&self, request_id: &str, response_body: &str) -> bool {
+    pub fn next_invocation(&self) -> (Context, String) {
+        {
+            {
+                let path = String::from("/2018-06-01/runtime/invocation/next");
+                {
+                    let result = http_client::http_get_with_headers(&self.api_endpoint, &path);
+                    if result.is_ok() {
+                        {
+                            let (headers, body) = result.unwrap();
+                            {
+                                let context = Context {
+                                    request_id: http_client::request_id(&headers),
+                                    deadline_ms: http_client::header_u64(
+                                        &headers,
+                                        "lambda-runtime-deadline-ms",
+                                    ),
+                                    invoked_function_arn: http_client::header(
+                                        &headers,
+                                        "lambda-runtime-invoked-function-arn",
+                                    ),
+                                    trace_id: http_client::header(
+                                        &headers,
+                                        "lambda-runtime-trace-id",
+                                    ),
+                                };
+                                (context, body)
+                            }
+                        }
+                    } else {
+                        {
+                            let context = Context {
+                                request_id: String::from("error"),
+                                deadline_ms: 0,
+                                invoked_function_arn: String::new(),
+                                trace_id: String::new(),
+                            };
+                            (context, String::from("{}"))
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pub fn post_response(&self, request_id: &str, response_body: &str) -> bool {
         {
             {
                 let path = format!(
-                    "{}{}", String::from("/2018-06-01/runtime/invocation/") + request_id,
+                    "{}{}",
+                    String::from("/2018-06-01/runtime/invocation/") + request_id,
                     "/response"
                 );
                 {
-                    let result = http_client::http_post(
-                        &self.api_endpoint,
-                        &path,
-                        response_body,
-                    );
+                    let result = http_client::http_post(&self.api_endpoint, &path, response_body);
                     result.is_ok()
                 }
             }
         }
     }
-    pub pub fn endpoint(&self) -> String {
-        { self.api_endpoint.clone() }
+    pub fn post_error(&self, request_id: &str, error_type: &str, error_message: &str) -> bool {
+        {
+            {
+                let path = format!(
+                    "{}{}",
+                    String::from("/2018-06-01/runtime/invocation/") + request_id,
+                    "/error"
+                );
+                {
+                    let payload = http_client::error_payload(error_type, error_message);
+                    {
+                        let result = http_client::http_post_error(
+                            &self.api_endpoint,
+                            &path,
+                            error_type,
+                            &payload,
+                        );
+                        result.is_ok()
+                    }
+                }
+            }
+        }
+    }
+    pub fn post_init_error(&self, error_type: &str, error_message: &str) -> bool {
+        {
+            {
+                let path = String::from("/2018-06-01/runtime/init/error");
+                {
+                    let payload = http_client::error_payload(error_type, error_message);
+                    {
+                        let result = http_client::http_post_error(
+                            &self.api_endpoint,
+                            &path,
+                            error_type,
+                            &payload,
+                        );
+                        result.is_ok()
+                    }
+                }
+            }
+        }
+    }
+    pub fn endpoint(&self) -> String {
+        {
+            self.api_endpoint.clone()
+        }
     }
 }
-
@@ -0,0 +1,103 @@
+// Scratch arena benchmark: per-String heap allocations vs a reset ScratchArena
+//
+// Both variants render the same CSV report the way a handler would: one
+// formatted line per record, collected and joined into the response. "heap"
+// allocates a String per line; "arena" formats into a ScratchArena that is
+// reset after each invocation, as the runtime does after post_response.
+// A counting global allocator reports the allocations per invocation before
+// the timings.
+//   cargo bench -p ruchy-lambda-runtime --features arena --bench scratch_arena
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ruchy_lambda_runtime::ScratchArena;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// System allocator counting allocations (including reallocations)
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: forwards to the system allocator unchanged
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Records per invocation
+const SIZES: &[usize] = &[10, 100, 1000];
+
+/// One invocation with a heap String per line
+fn render_heap(records: usize) -> String {
+    let lines: Vec<String> = (0..records)
+        .map(|n| format!("order-{n},sku-{},{}\n", n % 97, n * 3))
+        .collect();
+    lines.concat()
+}
+
+/// One invocation formatting into the arena, then copying the response out
+fn render_arena(arena: &mut ScratchArena, records: usize) -> String {
+    let response = {
+        let mut lines = arena.vec_with_capacity(records);
+        for n in 0..records {
+            lines.push(arena.alloc_fmt(format_args!("order-{n},sku-{},{}\n", n % 97, n * 3)));
+        }
+        let mut report = arena.string_with_capacity(lines.iter().map(|line| line.len()).sum());
+        for line in &lines {
+            report.push_str(line);
+        }
+        report.as_str().to_string()
+    };
+    arena.reset();
+    response
+}
+
+/// Allocations made by `f`
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn benchmark_scratch_arena(c: &mut Criterion) {
+    let mut arena = ScratchArena::new();
+    for &records in SIZES {
+        // Warm invocation: the arena has kept its chunk from the one before
+        render_arena(&mut arena, records);
+        let heap = count_allocations(|| {
+            black_box(render_heap(records));
+        });
+        let scratch = count_allocations(|| {
+            black_box(render_arena(&mut arena, records));
+        });
+        println!("{records} records: {heap} allocations on the heap, {scratch} with the arena");
+    }
+
+    let mut group = c.benchmark_group("scratch_arena");
+    for &records in SIZES {
+        group.bench_with_input(BenchmarkId::new("heap", records), &records, |b, &n| {
+            b.iter(|| render_heap(black_box(n)));
+        });
+        group.bench_with_input(BenchmarkId::new("arena", records), &records, |b, &n| {
+            b.iter(|| render_arena(&mut arena, black_box(n)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_scratch_arena);
+criterion_main!(benches);
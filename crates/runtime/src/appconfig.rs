@@ -0,0 +1,419 @@
+// AppConfig Hot Reload
+//
+// The AWS AppConfig Lambda extension serves the latest deployed
+// configuration (or feature-flag document) on localhost:
+//
+//   GET http://localhost:2772/applications/{app}/environments/{env}/configurations/{profile}
+//
+// AppConfig keeps the last document in memory and re-fetches it once it is
+// older than its TTL. Refreshes are scheduled with Context::spawn_background,
+// so they run after the response is posted and before the next event:
+//
+//   next_event -> handler (config.get) -> post_response -> refresh -> next_event
+//
+// Handlers only ever read the cached document, so a flag flip takes effect
+// within one TTL without a redeploy and without adding the fetch to any
+// invocation's latency. A failed refresh keeps serving the previous
+// document.
+
+use crate::context::Context;
+use crate::logger::{LogLevel, Logger};
+use ruchy_lambda_http_core::HttpError;
+use serde::de::DeserializeOwned;
+use std::env;
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// Environment variable naming the profile to load (`application/environment/profile`)
+pub const APPCONFIG_ENV: &str = "RUCHY_LAMBDA_APPCONFIG";
+
+/// Environment variable with the `AppConfig` extension's HTTP port
+pub const APPCONFIG_PORT_ENV: &str = "AWS_APPCONFIG_EXTENSION_HTTP_PORT";
+
+/// Default `AppConfig` extension HTTP port
+pub const DEFAULT_APPCONFIG_PORT: u16 = 2772;
+
+/// Default document TTL (the extension's default poll interval)
+pub const DEFAULT_APPCONFIG_TTL: Duration = Duration::from_secs(45);
+
+/// Error fetching a configuration document
+#[derive(Debug)]
+pub enum AppConfigError {
+    /// The extension could not be reached or answered non-2xx
+    Http(HttpError),
+    /// The document is not valid JSON
+    InvalidDocument(String),
+}
+
+impl fmt::Display for AppConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "AppConfig request failed: {err}"),
+            Self::InvalidDocument(msg) => write!(f, "AppConfig document is not JSON: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AppConfigError {}
+
+impl From<HttpError> for AppConfigError {
+    fn from(err: HttpError) -> Self {
+        Self::Http(err)
+    }
+}
+
+/// A fetched document and when it was fetched
+#[derive(Debug)]
+struct Loaded {
+    document: serde_json::Value,
+    fetched: Instant,
+}
+
+/// Cached `AppConfig` configuration, refreshed between invocations
+///
+/// Keep one per process in runtime state and load it during init:
+///
+/// ```no_run
+/// use ruchy_lambda_runtime::{AppConfig, Runtime};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let runtime = Runtime::new()?;
+/// let config = runtime.try_state_with(|| {
+///     let config = AppConfig::new("shop", "prod", "flags");
+///     config.refresh().map(|()| config)
+/// })?;
+///
+/// let (context, _event) = runtime.next_invocation()?;
+/// let limit: u32 = config.get("rate_limit").unwrap_or(100);
+/// let checkout_v2 = config.is_enabled("checkout_v2");
+/// # let _ = (limit, checkout_v2);
+/// config.schedule_refresh(&context);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Reads never block on the network:
+///
+/// ```
+/// use ruchy_lambda_runtime::AppConfig;
+///
+/// let config = AppConfig::new("shop", "prod", "flags").with_document(serde_json::json!({
+///     "rate_limit": 250,
+///     "limits": {"burst": 10},
+///     "checkout_v2": {"enabled": true},
+/// }));
+/// assert_eq!(config.get::<u32>("rate_limit"), Some(250));
+/// assert_eq!(config.get::<u32>("/limits/burst"), Some(10));
+/// assert!(config.is_enabled("checkout_v2"));
+/// assert!(!config.is_enabled("missing"));
+/// ```
+#[derive(Debug)]
+pub struct AppConfig {
+    /// Extension address (`host:port`)
+    endpoint: String,
+    /// `/applications/.../configurations/...`
+    path: String,
+    ttl: Duration,
+    loaded: RwLock<Option<Loaded>>,
+}
+
+impl AppConfig {
+    /// Configuration profile served by the local `AppConfig` extension
+    ///
+    /// The port comes from `AWS_APPCONFIG_EXTENSION_HTTP_PORT` (default
+    /// 2772). Nothing is fetched until [`AppConfig::refresh`].
+    #[must_use]
+    pub fn new(application: &str, environment: &str, profile: &str) -> Self {
+        let port = env::var(APPCONFIG_PORT_ENV)
+            .ok()
+            .and_then(|port| port.trim().parse().ok())
+            .unwrap_or(DEFAULT_APPCONFIG_PORT);
+        Self {
+            endpoint: format!("127.0.0.1:{port}"),
+            path: format!(
+                "/applications/{application}/environments/{environment}/configurations/{profile}"
+            ),
+            ttl: DEFAULT_APPCONFIG_TTL,
+            loaded: RwLock::new(None),
+        }
+    }
+
+    /// Profile named by `RUCHY_LAMBDA_APPCONFIG` (`application/environment/profile`)
+    ///
+    /// Returns `None` when the variable is unset or not three non-empty
+    /// segments.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let value = env::var(APPCONFIG_ENV).ok()?;
+        let segments: Vec<&str> = value.trim().split('/').collect();
+        match segments[..] {
+            [application, environment, profile]
+                if segments.iter().all(|segment| !segment.is_empty()) =>
+            {
+                Some(Self::new(application, environment, profile))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fetch from `endpoint` (`host:port`) instead of the local extension
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Re-fetch documents older than `ttl` (default: 45s)
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Start from `document` (e.g. for local runs and tests), as if just fetched
+    #[must_use]
+    pub fn with_document(self, document: serde_json::Value) -> Self {
+        self.store(document);
+        self
+    }
+
+    /// Fetch the document now, replacing the cached one
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppConfigError::Http` if the extension is unreachable or
+    /// answers non-2xx, and `AppConfigError::InvalidDocument` if the body is
+    /// not JSON. The cached document is kept on error.
+    pub fn refresh(&self) -> Result<(), AppConfigError> {
+        let response = ruchy_lambda_http_core::get(&self.endpoint, &self.path)?;
+        let document = serde_json::from_str(&response.body)
+            .map_err(|err| AppConfigError::InvalidDocument(err.to_string()))?;
+        self.store(document);
+        Ok(())
+    }
+
+    /// Whether the document is missing or older than the TTL
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.read()
+            .as_ref()
+            .is_none_or(|loaded| loaded.fetched.elapsed() >= self.ttl)
+    }
+
+    /// Refresh if stale; returns whether a fetch was made
+    ///
+    /// # Errors
+    ///
+    /// Same as [`AppConfig::refresh`].
+    pub fn refresh_if_stale(&self) -> Result<bool, AppConfigError> {
+        if !self.is_stale() {
+            return Ok(false);
+        }
+        self.refresh().map(|()| true)
+    }
+
+    /// Refresh after this invocation's response is posted, if stale
+    ///
+    /// Failures are logged as warnings and the previous document stays in
+    /// use.
+    pub fn schedule_refresh(&'static self, context: &Context) {
+        if !self.is_stale() {
+            return;
+        }
+        context.spawn_background(move || {
+            if let Err(err) = self.refresh_if_stale() {
+                Logger::new().log_with_fields(
+                    LogLevel::Warn,
+                    &format!("AppConfig refresh failed, keeping previous document: {err}"),
+                    &[],
+                );
+            }
+        });
+    }
+
+    /// Value under `key`, deserialized as `T`
+    ///
+    /// `key` is a top-level name, or a JSON pointer when it starts with `/`.
+    /// Returns `None` when nothing is loaded, the key is missing, or the
+    /// value does not deserialize as `T`.
+    #[must_use]
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let loaded = self.read();
+        let document = &loaded.as_ref()?.document;
+        let value = if key.starts_with('/') {
+            document.pointer(key)?
+        } else {
+            document.get(key)?
+        };
+        T::deserialize(value).ok()
+    }
+
+    /// Whether feature flag `flag` is on
+    ///
+    /// Accepts the `AppConfig` feature-flag shape (`{"flag":{"enabled":true}}`)
+    /// and plain booleans (`{"flag":true}`). Missing flags are off.
+    #[must_use]
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        let loaded = self.read();
+        let Some(value) = loaded.as_ref().and_then(|loaded| loaded.document.get(flag)) else {
+            return false;
+        };
+        value
+            .get("enabled")
+            .unwrap_or(value)
+            .as_bool()
+            .unwrap_or(false)
+    }
+
+    /// Copy of the whole cached document
+    #[must_use]
+    pub fn document(&self) -> Option<serde_json::Value> {
+        self.read().as_ref().map(|loaded| loaded.document.clone())
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Option<Loaded>> {
+        self.loaded.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn store(&self, document: serde_json::Value) {
+        *self.loaded.write().unwrap_or_else(PoisonError::into_inner) = Some(Loaded {
+            document,
+            fetched: Instant::now(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve one canned response per entry, returning the request lines
+    fn extension(responses: Vec<String>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                requests.push(request.lines().next().unwrap_or_default().to_string());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (endpoint, handle)
+    }
+
+    fn ok(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[test]
+    fn test_refresh_fetches_profile() {
+        let (endpoint, server) = extension(vec![ok(r#"{"greeting":"hi"}"#)]);
+        let config = AppConfig::new("app", "prod", "flags").with_endpoint(endpoint);
+        assert!(config.is_stale());
+        assert_eq!(config.get::<String>("greeting"), None);
+
+        assert!(config.refresh_if_stale().unwrap());
+        assert_eq!(config.get::<String>("greeting").as_deref(), Some("hi"));
+        assert!(!config.is_stale());
+        assert!(!config.refresh_if_stale().unwrap());
+
+        assert_eq!(
+            server.join().unwrap(),
+            ["GET /applications/app/environments/prod/configurations/flags HTTP/1.1"]
+        );
+    }
+
+    #[test]
+    fn test_failed_refresh_keeps_document() {
+        let (endpoint, server) = extension(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string(),
+            ok("not json"),
+        ]);
+        let config = AppConfig::new("app", "prod", "flags")
+            .with_endpoint(endpoint)
+            .with_ttl(Duration::ZERO)
+            .with_document(serde_json::json!({"n": 1}));
+        assert!(config.is_stale());
+
+        assert!(matches!(config.refresh(), Err(AppConfigError::Http(_))));
+        assert!(matches!(
+            config.refresh(),
+            Err(AppConfigError::InvalidDocument(_))
+        ));
+        assert_eq!(config.get::<u32>("n"), Some(1));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_schedule_refresh_runs_after_response() {
+        let (endpoint, server) = extension(vec![ok(r#"{"v":2}"#)]);
+        let config: &'static AppConfig = Box::leak(Box::new(
+            AppConfig::new("app", "prod", "flags")
+                .with_endpoint(endpoint)
+                .with_ttl(Duration::ZERO)
+                .with_document(serde_json::json!({"v": 1})),
+        ));
+        let context = Context {
+            request_id: "appconfig-refresh".to_string(),
+            ..Context::default()
+        };
+
+        config.schedule_refresh(&context);
+        assert_eq!(context.pending_background(), 1);
+        assert_eq!(config.get::<u32>("v"), Some(1));
+
+        crate::background::run("appconfig-refresh");
+        assert_eq!(config.get::<u32>("v"), Some(2));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_get_and_flags() {
+        let config = AppConfig::new("a", "e", "p").with_document(serde_json::json!({
+            "name": "shop",
+            "nested": {"list": [1, 2]},
+            "beta": true,
+            "legacy": {"enabled": false},
+            "odd": {"enabled": "yes"},
+        }));
+        assert_eq!(config.get::<String>("name").as_deref(), Some("shop"));
+        assert_eq!(config.get::<Vec<u8>>("/nested/list"), Some(vec![1, 2]));
+        assert_eq!(config.get::<u32>("name"), None);
+        assert!(config.is_enabled("beta"));
+        assert!(!config.is_enabled("legacy"));
+        assert!(!config.is_enabled("odd"));
+        assert!(!config.is_enabled("missing"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env() {
+        env::set_var(APPCONFIG_ENV, "shop/prod/flags");
+        env::set_var(APPCONFIG_PORT_ENV, "2999");
+        let config = AppConfig::from_env().unwrap();
+        assert_eq!(config.endpoint, "127.0.0.1:2999");
+        assert_eq!(
+            config.path,
+            "/applications/shop/environments/prod/configurations/flags"
+        );
+
+        env::set_var(APPCONFIG_ENV, "shop//flags");
+        assert!(AppConfig::from_env().is_none());
+        env::remove_var(APPCONFIG_ENV);
+        env::remove_var(APPCONFIG_PORT_ENV);
+        assert!(AppConfig::from_env().is_none());
+    }
+}
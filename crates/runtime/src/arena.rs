@@ -0,0 +1,182 @@
+// Scratch Arena for Handler Allocations (`arena` cargo feature)
+//
+// Handlers that build many short-lived strings (templating, CSV/JSON
+// assembly, key formatting) pay one malloc/free pair per String. A bump
+// arena (bumpalo) serves each allocation by advancing a pointer inside a
+// chunk and frees everything at once: resetting drops no individual values
+// and keeps the largest chunk for the next invocation, so a warm environment
+// stops allocating once the chunk fits the handler's working set.
+//
+// One arena per thread, reached through `Context::with_scratch`. The runtime
+// resets the calling thread's arena after `post_response` / `post_error`, so
+// nothing allocated in it may outlive the invocation; the borrow checker
+// enforces that, since arena references cannot leave the `with_scratch`
+// closure. Copy the final response out (`to_string`) before returning it.
+//
+//   cargo bench -p ruchy-lambda-runtime --features arena --bench scratch_arena
+
+use bumpalo::Bump;
+use std::cell::RefCell;
+use std::fmt;
+
+/// Growable string allocated in a [`ScratchArena`]
+pub type ScratchString<'a> = bumpalo::collections::String<'a>;
+
+/// Growable vector allocated in a [`ScratchArena`]
+pub type ScratchVec<'a, T> = bumpalo::collections::Vec<'a, T>;
+
+thread_local! {
+    /// Arena of [`Context::with_scratch`](crate::Context::with_scratch) on this thread
+    static SCRATCH: RefCell<ScratchArena> = RefCell::new(ScratchArena::new());
+}
+
+/// Bump allocator for per-invocation scratch data, freed in O(1) by
+/// [`ScratchArena::reset`]
+///
+/// Values allocated in the arena are never dropped individually: use it for
+/// plain data (strings, numbers, slices of them), not for types owning other
+/// resources.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::ScratchArena;
+///
+/// let mut arena = ScratchArena::new();
+/// {
+///     let mut csv = arena.string_with_capacity(64);
+///     for (sku, qty) in [("A-1", 2), ("B-7", 1)] {
+///         csv.push_str(arena.alloc_fmt(format_args!("{sku},{qty}\n")));
+///     }
+///     assert_eq!(csv.as_str(), "A-1,2\nB-7,1\n");
+/// }
+///
+/// arena.reset();
+/// assert_eq!(arena.allocated_bytes(), 0);
+/// ```
+#[derive(Default)]
+pub struct ScratchArena {
+    bump: Bump,
+}
+
+impl fmt::Debug for ScratchArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScratchArena")
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ScratchArena {
+    /// Empty arena; the first allocation reserves its first chunk
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy `value` into the arena
+    pub fn alloc_str(&self, value: &str) -> &str {
+        self.bump.alloc_str(value)
+    }
+
+    /// Format `args` into the arena (`format!` without a heap String)
+    pub fn alloc_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        let mut formatted = ScratchString::new_in(&self.bump);
+        let _ = fmt::Write::write_fmt(&mut formatted, args);
+        formatted.into_bump_str()
+    }
+
+    /// Empty string growing inside the arena
+    #[must_use]
+    pub fn string_with_capacity(&self, capacity: usize) -> ScratchString<'_> {
+        ScratchString::with_capacity_in(capacity, &self.bump)
+    }
+
+    /// Empty vector growing inside the arena
+    #[must_use]
+    pub fn vec_with_capacity<T>(&self, capacity: usize) -> ScratchVec<'_, T> {
+        ScratchVec::with_capacity_in(capacity, &self.bump)
+    }
+
+    /// Bytes handed out since the last reset
+    #[must_use]
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes() - self.bump.chunk_capacity()
+    }
+
+    /// Free everything allocated, keeping the largest chunk for reuse
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+}
+
+/// Run `f` with this thread's scratch arena
+pub(crate) fn with_scratch<R>(f: impl FnOnce(&ScratchArena) -> R) -> R {
+    SCRATCH.with(|arena| f(&arena.borrow()))
+}
+
+/// Reset this thread's scratch arena at the end of an invocation
+///
+/// Skipped while the arena is borrowed (a post made from inside
+/// `with_scratch`); it is reset after the next invocation instead.
+pub(crate) fn reset_scratch() {
+    SCRATCH.with(|arena| {
+        if let Ok(mut arena) = arena.try_borrow_mut() {
+            arena.reset();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc_fmt_and_str() {
+        let arena = ScratchArena::new();
+        let key = arena.alloc_fmt(format_args!("order#{}", 42));
+        let copy = arena.alloc_str("sku-7");
+        assert_eq!(key, "order#42");
+        assert_eq!(copy, "sku-7");
+        assert!(arena.allocated_bytes() >= key.len() + copy.len());
+    }
+
+    #[test]
+    fn test_reset_reuses_chunk() {
+        let mut arena = ScratchArena::new();
+        let fill = |arena: &ScratchArena| {
+            for n in 0..1000 {
+                arena.alloc_fmt(format_args!("line {n}\n"));
+            }
+        };
+        fill(&arena);
+        arena.reset();
+        assert_eq!(arena.allocated_bytes(), 0);
+
+        // Warm: the chunk kept by the reset fits a whole invocation
+        fill(&arena);
+        arena.reset();
+        let kept = arena.bump.allocated_bytes();
+        fill(&arena);
+        assert_eq!(arena.bump.allocated_bytes(), kept);
+    }
+
+    #[test]
+    fn test_thread_arena_reset_after_invocation() {
+        let used = with_scratch(|arena| arena.alloc_str("scratch").len());
+        assert_eq!(used, 7);
+        assert!(with_scratch(ScratchArena::allocated_bytes) >= 7);
+
+        reset_scratch();
+        assert_eq!(with_scratch(ScratchArena::allocated_bytes), 0);
+    }
+
+    #[test]
+    fn test_reset_skipped_while_borrowed() {
+        with_scratch(|arena| {
+            let kept = arena.alloc_str("in use");
+            reset_scratch();
+            assert_eq!(kept, "in use");
+        });
+    }
+}
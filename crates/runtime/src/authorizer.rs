@@ -0,0 +1,514 @@
+// API Gateway Lambda Authorizers
+//
+// A custom authorizer receives the caller's credentials before API Gateway
+// invokes the backend and answers allow / deny:
+//
+//   TOKEN   (REST):      {"type":"TOKEN","authorizationToken":"Bearer ...","methodArn":"arn:..."}
+//   REQUEST (REST):      {"type":"REQUEST","methodArn":"arn:...","headers":{...},...}
+//   REQUEST (HTTP API):  {"version":"2.0","type":"REQUEST","routeArn":"arn:...",
+//                         "identitySource":["..."],"headers":{...},...}
+//
+// Responses are an IAM policy (REST, and HTTP API payload 1.0 / 2.0):
+//
+//   {"principalId":"user-1","policyDocument":{"Version":"2012-10-17",
+//    "Statement":[{"Action":"execute-api:Invoke","Effect":"Allow","Resource":["arn:..."]}]},
+//    "context":{"tenant":"acme"}}
+//
+// or, for HTTP APIs with simple responses enabled,
+// {"isAuthorized":true,"context":{...}}. Context values must be strings,
+// numbers or booleans; they reach the backend as requestContext.authorizer.
+//
+// To answer 401 instead of 403, fail the invocation with the message
+// "Unauthorized" (AuthorizerResponse::unauthorized).
+
+use crate::handler_error::HandlerError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// IAM policy language version of generated policies
+const POLICY_VERSION: &str = "2012-10-17";
+
+/// Action authorized by API Gateway policies
+const INVOKE_ACTION: &str = "execute-api:Invoke";
+
+/// `TOKEN` authorizer event (REST APIs)
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{AuthorizerResponse, TokenAuthorizerEvent};
+///
+/// let event = TokenAuthorizerEvent::from_json(
+///     r#"{"type":"TOKEN","authorizationToken":"Bearer allow-me",
+///        "methodArn":"arn:aws:execute-api:us-east-1:123456789012:abc123/prod/GET/orders"}"#,
+/// )
+/// .unwrap();
+///
+/// let response = if event.token() == Some("allow-me") {
+///     AuthorizerResponse::allow("user-1", &event.method_arn).with_context("tenant", "acme")
+/// } else {
+///     AuthorizerResponse::deny("anonymous", &event.method_arn)
+/// };
+/// let value: serde_json::Value = serde_json::from_str(&response.to_json()).unwrap();
+/// assert_eq!(value["policyDocument"]["Statement"][0]["Effect"], "Allow");
+/// assert_eq!(value["context"]["tenant"], "acme");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAuthorizerEvent {
+    /// Always `TOKEN`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Value of the configured token header
+    pub authorization_token: String,
+    /// ARN of the method being called
+    pub method_arn: String,
+}
+
+impl TokenAuthorizerEvent {
+    /// Parse a `TOKEN` authorizer event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a token event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Token without a leading `Bearer ` scheme
+    #[must_use]
+    pub fn token(&self) -> Option<&str> {
+        strip_bearer(&self.authorization_token)
+    }
+}
+
+/// `REQUEST` authorizer event (REST APIs and HTTP APIs)
+///
+/// REST APIs and HTTP API payload 1.0 send `methodArn`; HTTP API payload
+/// 2.0 sends `routeArn` and `identitySource`. Use [`arn`](Self::arn) for
+/// either.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestAuthorizerEvent {
+    /// Payload version (`2.0` for HTTP API payload 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Always `REQUEST`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// ARN of the method being called (REST, payload 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method_arn: Option<String>,
+    /// ARN of the route being called (payload 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route_arn: Option<String>,
+    /// Values of the configured identity sources (payload 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_source: Option<Vec<String>>,
+    /// Route key, e.g. `GET /orders` (payload 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route_key: Option<String>,
+    /// Resource template, e.g. `/orders/{id}` (REST)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// Request path (REST)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Request path (payload 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_path: Option<String>,
+    /// HTTP method (REST)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_method: Option<String>,
+    /// Request headers
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub headers: HashMap<String, String>,
+    /// Query string parameters
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub query_string_parameters: HashMap<String, String>,
+    /// Path parameters
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub path_parameters: HashMap<String, String>,
+    /// Stage variables
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub stage_variables: HashMap<String, String>,
+    /// API Gateway request context, as sent
+    #[serde(default)]
+    pub request_context: serde_json::Value,
+}
+
+impl RequestAuthorizerEvent {
+    /// Parse a `REQUEST` authorizer event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a request event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Method or route ARN being authorized
+    #[must_use]
+    pub fn arn(&self) -> &str {
+        self.method_arn
+            .as_deref()
+            .or(self.route_arn.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Header value (case-insensitive name)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn strip_bearer(authorization: &str) -> Option<&str> {
+    let authorization = authorization.trim();
+    let token = match authorization.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => token.trim(),
+        Some(_) => return None,
+        None => authorization,
+    };
+    (!token.is_empty()).then_some(token)
+}
+
+/// IAM policy response of an authorizer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizerResponse {
+    /// Identifier of the caller (shown in logs and `$context.authorizer.principalId`)
+    pub principal_id: String,
+    /// Allow / deny statements
+    pub policy_document: PolicyDocument,
+    /// Values passed to the backend as `requestContext.authorizer`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, serde_json::Value>,
+    /// API key for usage plans (REST APIs with the authorizer as key source)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_identifier_key: Option<String>,
+}
+
+/// IAM policy document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyDocument {
+    /// Policy language version
+    pub version: String,
+    /// Policy statements
+    pub statement: Vec<PolicyStatement>,
+}
+
+impl Default for PolicyDocument {
+    fn default() -> Self {
+        Self {
+            version: POLICY_VERSION.to_string(),
+            statement: Vec::new(),
+        }
+    }
+}
+
+/// One IAM policy statement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyStatement {
+    /// `execute-api:Invoke`
+    pub action: String,
+    /// `Allow` or `Deny`
+    pub effect: String,
+    /// Method ARNs (`*` wildcards allowed)
+    pub resource: Vec<String>,
+}
+
+impl AuthorizerResponse {
+    /// Policy allowing `principal_id` to invoke `resource`
+    #[must_use]
+    pub fn allow(principal_id: &str, resource: &str) -> Self {
+        Self::new(principal_id).with_statement(true, resource)
+    }
+
+    /// Policy denying `principal_id` access to `resource` (403)
+    #[must_use]
+    pub fn deny(principal_id: &str, resource: &str) -> Self {
+        Self::new(principal_id).with_statement(false, resource)
+    }
+
+    /// Policy without statements
+    #[must_use]
+    pub fn new(principal_id: &str) -> Self {
+        Self {
+            principal_id: principal_id.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Add an `Allow` (or `Deny`) statement for `resource`
+    #[must_use]
+    pub fn with_statement(mut self, allow: bool, resource: &str) -> Self {
+        self.policy_document.statement.push(PolicyStatement {
+            action: INVOKE_ACTION.to_string(),
+            effect: if allow { "Allow" } else { "Deny" }.to_string(),
+            resource: vec![resource.to_string()],
+        });
+        self
+    }
+
+    /// Add a value for the backend's `requestContext.authorizer`
+    #[must_use]
+    pub fn with_context(mut self, key: &str, value: impl Into<ContextValue>) -> Self {
+        self.context
+            .insert(key.to_string(), value.into().into_json());
+        self
+    }
+
+    /// Set the usage plan API key
+    #[must_use]
+    pub fn with_usage_identifier_key(mut self, key: &str) -> Self {
+        self.usage_identifier_key = Some(key.to_string());
+        self
+    }
+
+    /// Response JSON for API Gateway
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Error that makes API Gateway answer 401 Unauthorized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::AuthorizerResponse;
+    ///
+    /// assert_eq!(AuthorizerResponse::unauthorized().error_message, "Unauthorized");
+    /// ```
+    #[must_use]
+    pub fn unauthorized() -> HandlerError {
+        HandlerError::new("Unauthorized", "Unauthorized")
+    }
+}
+
+/// HTTP API simple authorizer response
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::SimpleAuthorizerResponse;
+///
+/// let response = SimpleAuthorizerResponse::allow().with_context("userId", 42);
+/// assert_eq!(response.to_json(), r#"{"isAuthorized":true,"context":{"userId":42}}"#);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleAuthorizerResponse {
+    /// Whether the request may proceed
+    pub is_authorized: bool,
+    /// Values passed to the backend as `requestContext.authorizer.lambda`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, serde_json::Value>,
+}
+
+impl SimpleAuthorizerResponse {
+    /// Authorize the request
+    #[must_use]
+    pub fn allow() -> Self {
+        Self {
+            is_authorized: true,
+            context: HashMap::new(),
+        }
+    }
+
+    /// Reject the request (403)
+    #[must_use]
+    pub fn deny() -> Self {
+        Self::default()
+    }
+
+    /// Add a value for the backend's `requestContext.authorizer.lambda`
+    #[must_use]
+    pub fn with_context(mut self, key: &str, value: impl Into<ContextValue>) -> Self {
+        self.context
+            .insert(key.to_string(), value.into().into_json());
+        self
+    }
+
+    /// Response JSON for API Gateway
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Authorizer context value (API Gateway accepts only scalars)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextValue {
+    /// String value
+    String(String),
+    /// Number value
+    Number(serde_json::Number),
+    /// Boolean value
+    Bool(bool),
+}
+
+impl ContextValue {
+    fn into_json(self) -> serde_json::Value {
+        match self {
+            Self::String(value) => serde_json::Value::String(value),
+            Self::Number(value) => serde_json::Value::Number(value),
+            Self::Bool(value) => serde_json::Value::Bool(value),
+        }
+    }
+}
+
+impl From<&str> for ContextValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for ContextValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<bool> for ContextValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for ContextValue {
+    fn from(value: i64) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<i32> for ContextValue {
+    fn from(value: i32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<u64> for ContextValue {
+    fn from(value: u64) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+impl From<u32> for ContextValue {
+    fn from(value: u32) -> Self {
+        Self::Number(value.into())
+    }
+}
+
+/// Non-finite values become `0`
+impl From<f64> for ContextValue {
+    fn from(value: f64) -> Self {
+        Self::Number(serde_json::Number::from_f64(value).unwrap_or_else(|| 0.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METHOD_ARN: &str = "arn:aws:execute-api:us-east-1:123456789012:abc123/prod/GET/orders";
+
+    #[test]
+    fn test_token_event_bearer() {
+        let mut event = TokenAuthorizerEvent {
+            kind: "TOKEN".to_string(),
+            authorization_token: "bearer  abc ".to_string(),
+            method_arn: METHOD_ARN.to_string(),
+        };
+        assert_eq!(event.token(), Some("abc"));
+        event.authorization_token = "raw-token".to_string();
+        assert_eq!(event.token(), Some("raw-token"));
+        event.authorization_token = "Basic dXNlcg==".to_string();
+        assert_eq!(event.token(), None);
+    }
+
+    #[test]
+    fn test_request_event_rest_and_v2() {
+        let rest = RequestAuthorizerEvent::from_json(&format!(
+            r#"{{"type":"REQUEST","methodArn":"{METHOD_ARN}","resource":"/orders","path":"/orders",
+                "httpMethod":"GET","headers":{{"X-Api-Key":"k1"}},"queryStringParameters":null,
+                "pathParameters":null,"stageVariables":null,"requestContext":{{"stage":"prod"}}}}"#
+        ))
+        .unwrap();
+        assert_eq!(rest.arn(), METHOD_ARN);
+        assert_eq!(rest.header("x-api-key"), Some("k1"));
+        assert!(rest.query_string_parameters.is_empty());
+        assert_eq!(rest.request_context["stage"], "prod");
+
+        let v2 = RequestAuthorizerEvent::from_json(
+            r#"{"version":"2.0","type":"REQUEST","routeArn":"arn:aws:execute-api:us-east-1:123456789012:api/$default/GET/orders",
+                "identitySource":["Bearer t"],"routeKey":"GET /orders","rawPath":"/orders",
+                "headers":{"authorization":"Bearer t"},"requestContext":{"http":{"method":"GET"}}}"#,
+        )
+        .unwrap();
+        assert!(v2.arn().ends_with("/GET/orders"));
+        assert_eq!(
+            v2.identity_source.as_deref(),
+            Some(&["Bearer t".to_string()][..])
+        );
+        assert_eq!(v2.route_key.as_deref(), Some("GET /orders"));
+    }
+
+    #[test]
+    fn test_policy_response_json() {
+        let response = AuthorizerResponse::allow("user-1", METHOD_ARN)
+            .with_statement(
+                false,
+                "arn:aws:execute-api:us-east-1:123456789012:abc123/prod/DELETE/*",
+            )
+            .with_context("tenant", "acme")
+            .with_context("admin", false)
+            .with_context("level", 3)
+            .with_usage_identifier_key("key-1");
+        let value: serde_json::Value = serde_json::from_str(&response.to_json()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "principalId": "user-1",
+                "policyDocument": {
+                    "Version": "2012-10-17",
+                    "Statement": [
+                        {"Action": "execute-api:Invoke", "Effect": "Allow", "Resource": [METHOD_ARN]},
+                        {"Action": "execute-api:Invoke", "Effect": "Deny",
+                         "Resource": ["arn:aws:execute-api:us-east-1:123456789012:abc123/prod/DELETE/*"]},
+                    ],
+                },
+                "context": {"tenant": "acme", "admin": false, "level": 3},
+                "usageIdentifierKey": "key-1",
+            })
+        );
+
+        let deny = AuthorizerResponse::deny("anonymous", METHOD_ARN).to_json();
+        assert!(!deny.contains("context"));
+        assert!(deny.contains(r#""Effect":"Deny""#));
+    }
+
+    #[test]
+    fn test_simple_response() {
+        assert_eq!(
+            SimpleAuthorizerResponse::deny().to_json(),
+            r#"{"isAuthorized":false}"#
+        );
+        let allowed = SimpleAuthorizerResponse::allow().with_context("user", "u1");
+        assert_eq!(allowed.context["user"], "u1");
+    }
+}
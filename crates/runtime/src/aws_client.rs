@@ -0,0 +1,781 @@
+// Outbound AWS API Client (feature "sigv4")
+//
+// Shared by the minimal service clients (DynamoDB, ...): each one builds an
+// `AwsRequest`, and `AwsClient` signs it and sends it.
+//
+// Transport:
+//   - default: plain HTTP to the service host on port 80, or to the address
+//     set with `with_endpoint` (DynamoDB Local, LocalStack, a TLS-terminating
+//     sidecar proxy); the request body is NOT encrypted
+//   - `with_transport`: any function sending the signed request, e.g. over
+//     the TLS client the function already links
+//
+// The runtime has no TLS stack of its own (see ruchy-lambda-http-core), which
+// keeps bootstrap binaries small; requests are signed either way, so only
+// the transport decides confidentiality.
+//
+// `send_streaming` moves large bodies without buffering them (S3 objects):
+// the upload is signed as UNSIGNED-PAYLOAD and the response body is read
+// from the connection on demand. A custom transport only sees whole
+// requests, so there both bodies are buffered.
+//
+// `with_credentials_provider` signs with credentials fetched per request
+// (container endpoint, STS AssumeRole, ...; see credentials.rs) instead of
+// the signer's own.
+//
+// `send` retries transport failures, throttling errors and the retry
+// policy's statuses (429 / 5xx by default) with jittered backoff; set
+// `with_retry_policy(RetryPolicy::none())` to disable. Streaming uploads
+// cannot be replayed and are sent once.
+//
+// Plain HTTP connections are bounded by the invocation deadline (see
+// deadline.rs); a custom transport should apply `io_timeout()` itself.
+//
+// Non-2xx responses become `AwsError::Service` with the error code taken
+// from the x-amzn-ErrorType header, a JSON `__type` or an XML `<Code>`.
+
+use crate::credentials::CredentialsProvider;
+use crate::deadline;
+use crate::retry::RetryPolicy;
+use crate::sigv4::{
+    region_from_env, AwsRequest, Credentials, SigV4Error, Signer, UNSIGNED_PAYLOAD,
+};
+use ruchy_lambda_http_core::{
+    parse_any_response_ref, request_into_on, request_stream_on, DEFAULT_MAX_RESPONSE_SIZE,
+};
+use serde_json::Value;
+use std::fmt;
+use std::io::{self, Cursor, Read};
+use std::sync::Arc;
+
+/// Sends a signed request and returns the response
+type Transport = Box<dyn Fn(&AwsRequest) -> Result<AwsResponse, String> + Send + Sync>;
+
+/// Response from an AWS service API
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AwsResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl AwsResponse {
+    /// Response with a status and body
+    #[must_use]
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add a header
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Header value (case-insensitive name)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether the status is 2xx
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Response whose body is read on demand (see [`AwsClient::send_streaming`])
+pub struct AwsStreamResponse {
+    /// HTTP status code
+    pub status: u16,
+    /// Response headers
+    pub headers: Vec<(String, String)>,
+    body: Box<dyn Read + Send>,
+}
+
+impl AwsStreamResponse {
+    /// Header value (case-insensitive name)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Read for AwsStreamResponse {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.body.read(buf)
+    }
+}
+
+impl fmt::Debug for AwsStreamResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsStreamResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// AWS API call failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AwsError {
+    /// The request could not be sent or the response not read
+    Transport(String),
+    /// The service answered with an error status
+    Service {
+        /// HTTP status code
+        status: u16,
+        /// Error code (e.g. `ResourceNotFoundException`, `NoSuchKey`)
+        code: String,
+        /// Error message
+        message: String,
+    },
+    /// The response body was not what the operation returns
+    InvalidResponse(String),
+    /// No credentials to sign the request with
+    Credentials(String),
+}
+
+impl AwsError {
+    /// Error code of a service error
+    #[must_use]
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Service { code, .. } => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Whether retrying may succeed (transport failures, throttling, 5xx)
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(_) => true,
+            Self::Service { status, .. } => {
+                *status >= 500 || *status == 429 || self.is_throttling()
+            }
+            Self::InvalidResponse(_) | Self::Credentials(_) => false,
+        }
+    }
+
+    /// Whether the service throttled the request (whatever the status)
+    #[must_use]
+    pub fn is_throttling(&self) -> bool {
+        self.code().is_some_and(|code| {
+            code.contains("Throttl")
+                || code == "ProvisionedThroughputExceededException"
+                || code == "RequestLimitExceeded"
+        })
+    }
+}
+
+impl fmt::Display for AwsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(msg) => write!(f, "AWS request failed: {msg}"),
+            Self::Service {
+                status,
+                code,
+                message,
+            } => write!(f, "AWS error {status} {code}: {message}"),
+            Self::InvalidResponse(msg) => write!(f, "Invalid AWS response: {msg}"),
+            Self::Credentials(msg) => write!(f, "AWS credentials unavailable: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for AwsError {}
+
+/// Signs and sends requests to one AWS service
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{AwsClient, AwsRequest, AwsResponse, Credentials, Signer};
+///
+/// let signer = Signer::new(Credentials::new("AKID", "secret"), "us-east-1", "sts");
+/// let client = AwsClient::new(signer).with_transport(|request| {
+///     assert!(request.header("authorization").is_some());
+///     Ok(AwsResponse::new(200, "<GetCallerIdentityResponse/>"))
+/// });
+///
+/// let request = AwsRequest::new("POST", "sts.amazonaws.com", "/")
+///     .with_body("Action=GetCallerIdentity&Version=2011-06-15");
+/// assert_eq!(client.send(request).unwrap().status, 200);
+/// ```
+pub struct AwsClient {
+    signer: Signer,
+    endpoint: Option<String>,
+    transport: Option<Transport>,
+    credentials_provider: Option<Arc<dyn CredentialsProvider>>,
+    retry: RetryPolicy,
+    max_response_size: usize,
+}
+
+impl AwsClient {
+    /// Client signing with `signer`, sending plain HTTP to the service host
+    #[must_use]
+    pub fn new(signer: Signer) -> Self {
+        Self {
+            signer,
+            endpoint: None,
+            transport: None,
+            credentials_provider: None,
+            retry: RetryPolicy::default(),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+        }
+    }
+
+    /// Client for `service` with the function's credentials and region
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigV4Error`] if the credentials or region are not set.
+    pub fn from_env(service: &str) -> Result<Self, SigV4Error> {
+        Ok(Self::new(Signer::from_env(service)?))
+    }
+
+    /// Client for `service` in the function's region, signing with the
+    /// credentials of `provider`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigV4Error::MissingRegion`] if the region is not set.
+    pub fn from_provider(
+        service: &str,
+        provider: Arc<dyn CredentialsProvider>,
+    ) -> Result<Self, SigV4Error> {
+        let signer = Signer::new(Credentials::new("", ""), &region_from_env()?, service);
+        Ok(Self::new(signer).with_credentials_provider(provider))
+    }
+
+    /// Send to `endpoint` (`host:port`) instead of the service host
+    ///
+    /// The endpoint is also signed as the host, as local emulators expect.
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Send signed requests with `transport` (e.g. over HTTPS)
+    #[must_use]
+    pub fn with_transport(
+        mut self,
+        transport: impl Fn(&AwsRequest) -> Result<AwsResponse, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Sign with the credentials of `provider` instead of the signer's
+    ///
+    /// The provider is asked on every request, so it should cache (as the
+    /// providers of this crate do); share it between clients with the `Arc`.
+    #[must_use]
+    pub fn with_credentials_provider(mut self, provider: Arc<dyn CredentialsProvider>) -> Self {
+        self.credentials_provider = Some(provider);
+        self
+    }
+
+    /// Retry failed requests with `retry` instead of the default policy
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the maximum accepted response body size in bytes
+    #[must_use]
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// The request signer
+    #[must_use]
+    pub fn signer(&self) -> &Signer {
+        &self.signer
+    }
+
+    /// Endpoint set with [`AwsClient::with_endpoint`]
+    #[must_use]
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// The retry policy
+    #[must_use]
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Sign and send `request`, retrying as the retry policy allows
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError::Transport`] if sending fails,
+    /// [`AwsError::Service`] for non-2xx responses and the error of a failing
+    /// credentials provider (of the last attempt).
+    pub fn send(&self, request: AwsRequest) -> Result<AwsResponse, AwsError> {
+        self.retry.retry(
+            move |_| self.send_once(request.clone()),
+            |error| self.should_retry(error),
+        )
+    }
+
+    /// Sign and send `request` once
+    fn send_once(&self, mut request: AwsRequest) -> Result<AwsResponse, AwsError> {
+        if let Some(endpoint) = &self.endpoint {
+            request.host.clone_from(endpoint);
+        }
+        self.sign(&mut request)?;
+
+        let response = match &self.transport {
+            Some(transport) => transport(&request).map_err(AwsError::Transport)?,
+            None => self.send_plain(&request)?,
+        };
+        if response.is_success() {
+            Ok(response)
+        } else {
+            Err(service_error(&response))
+        }
+    }
+
+    /// Sign and send `request` with `content_length` bytes of `body` as its
+    /// unsigned payload, returning the response body unread
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError::Transport`] if sending fails (including `body`
+    /// ending early), [`AwsError::Service`] for non-2xx responses and the
+    /// error of a failing credentials provider.
+    pub fn send_streaming(
+        &self,
+        mut request: AwsRequest,
+        body: &mut dyn Read,
+        content_length: u64,
+    ) -> Result<AwsStreamResponse, AwsError> {
+        if let Some(endpoint) = &self.endpoint {
+            request.host.clone_from(endpoint);
+        }
+        if request.header("x-amz-content-sha256").is_none() {
+            request.headers.push((
+                "x-amz-content-sha256".to_string(),
+                UNSIGNED_PAYLOAD.to_string(),
+            ));
+        }
+        self.sign(&mut request)?;
+
+        if let Some(transport) = &self.transport {
+            body.take(content_length)
+                .read_to_end(&mut request.body)
+                .map_err(|e| AwsError::Transport(e.to_string()))?;
+            let response = transport(&request).map_err(AwsError::Transport)?;
+            if !response.is_success() {
+                return Err(service_error(&response));
+            }
+            return Ok(AwsStreamResponse {
+                status: response.status,
+                headers: response.headers,
+                body: Box::new(Cursor::new(response.body)),
+            });
+        }
+
+        let endpoint = self.plain_endpoint(&request);
+        let headers: Vec<(&str, &str)> = request
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let stream =
+            deadline::connect(&endpoint).map_err(|e| AwsError::Transport(e.to_string()))?;
+        let mut response = request_stream_on(
+            stream,
+            &endpoint,
+            &request.method,
+            &request.path_and_query(),
+            &headers,
+            body,
+            content_length,
+        )
+        .map_err(|e| AwsError::Transport(e.to_string()))?;
+
+        let status = response.status_code().unwrap_or_default();
+        let headers = std::mem::take(&mut response.headers);
+        if !(200..300).contains(&status) {
+            let mut error_body = Vec::new();
+            let limit = u64::try_from(self.max_response_size).unwrap_or(u64::MAX);
+            response
+                .take(limit)
+                .read_to_end(&mut error_body)
+                .map_err(|e| AwsError::Transport(e.to_string()))?;
+            return Err(service_error(&AwsResponse {
+                status,
+                headers,
+                body: error_body,
+            }));
+        }
+        Ok(AwsStreamResponse {
+            status,
+            headers,
+            body: Box::new(response),
+        })
+    }
+
+    /// Call a JSON-protocol operation (`X-Amz-Target: <target>`), e.g.
+    /// `DynamoDB_20120810.ListTables` with `application/x-amz-json-1.0`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails or the response is not JSON.
+    pub fn call_json(
+        &self,
+        host: &str,
+        content_type: &str,
+        target: &str,
+        body: &Value,
+    ) -> Result<Value, AwsError> {
+        let request = AwsRequest::new("POST", host, "/")
+            .with_header("content-type", content_type)
+            .with_header("x-amz-target", target)
+            .with_body(body.to_string());
+        let response = self.send(request)?;
+        if response.body.is_empty() {
+            return Ok(Value::Object(serde_json::Map::new()));
+        }
+        serde_json::from_slice(&response.body)
+            .map_err(|e| AwsError::InvalidResponse(format!("{target}: {e}")))
+    }
+
+    /// Whether a failed attempt is worth retrying
+    fn should_retry(&self, error: &AwsError) -> bool {
+        match error {
+            AwsError::Transport(_) => true,
+            AwsError::Service { status, .. } => {
+                self.retry.retries_status(*status) || error.is_throttling()
+            }
+            AwsError::InvalidResponse(_) | AwsError::Credentials(_) => false,
+        }
+    }
+
+    /// Sign `request`, with the provider's credentials if one is set
+    fn sign(&self, request: &mut AwsRequest) -> Result<(), AwsError> {
+        match &self.credentials_provider {
+            Some(provider) => {
+                let mut signer = self.signer.clone();
+                signer.set_credentials(provider.credentials()?);
+                signer.sign(request);
+            }
+            None => self.signer.sign(request),
+        }
+        Ok(())
+    }
+
+    /// `host:port` plain HTTP requests go to
+    fn plain_endpoint(&self, request: &AwsRequest) -> String {
+        self.endpoint
+            .clone()
+            .unwrap_or_else(|| format!("{}:80", request.host))
+    }
+
+    fn send_plain(&self, request: &AwsRequest) -> Result<AwsResponse, AwsError> {
+        let endpoint = self.plain_endpoint(request);
+        let headers: Vec<(&str, &str)> = request
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        let stream =
+            deadline::connect(&endpoint).map_err(|e| AwsError::Transport(e.to_string()))?;
+        let mut buffer = Vec::new();
+        request_into_on(
+            stream,
+            &endpoint,
+            &request.method,
+            &request.path_and_query(),
+            &headers,
+            &request.body,
+            self.max_response_size,
+            &mut buffer,
+        )
+        .map_err(|e| AwsError::Transport(e.to_string()))?;
+
+        let response =
+            parse_any_response_ref(&buffer).map_err(|e| AwsError::Transport(e.to_string()))?;
+        Ok(AwsResponse {
+            status: response.status_code().unwrap_or_default(),
+            headers: response
+                .header_pairs()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: response.body.to_vec(),
+        })
+    }
+}
+
+impl fmt::Debug for AwsClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsClient")
+            .field("signer", &self.signer)
+            .field("endpoint", &self.endpoint)
+            .field("transport", &self.transport.as_ref().map(|_| "custom"))
+            .field("credentials_provider", &self.credentials_provider)
+            .field("retry", &self.retry)
+            .field("max_response_size", &self.max_response_size)
+            .finish()
+    }
+}
+
+/// Service error of a non-2xx response (JSON or XML protocol)
+fn service_error(response: &AwsResponse) -> AwsError {
+    let body = String::from_utf8_lossy(&response.body);
+    let json = serde_json::from_str::<Value>(&body).ok();
+    let field = |names: &[&str]| {
+        let json = json.as_ref()?;
+        names
+            .iter()
+            .find_map(|name| json.get(*name).and_then(Value::as_str))
+            .map(str::to_string)
+    };
+
+    // "com.amazonaws.dynamodb.v20120810#ResourceNotFoundException", and the
+    // header may carry ":http://internal.amazon.com/..." after the code
+    let code = response
+        .header("x-amzn-errortype")
+        .map(str::to_string)
+        .or_else(|| field(&["__type", "code"]))
+        .or_else(|| xml_element(&body, "Code"))
+        .map_or_else(
+            || format!("Http{}", response.status),
+            |code| {
+                let code = code.rsplit('#').next().unwrap_or(&code);
+                code.split(':').next().unwrap_or(code).to_string()
+            },
+        );
+    let message = field(&["message", "Message"])
+        .or_else(|| xml_element(&body, "Message"))
+        .unwrap_or_default();
+
+    AwsError::Service {
+        status: response.status,
+        code,
+        message,
+    }
+}
+
+/// Text of the first `<name>...</name>` element
+pub(crate) fn xml_element(xml: &str, name: &str) -> Option<String> {
+    let open = format!("<{name}>");
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&format!("</{name}>"))? + start;
+    Some(xml_unescape(&xml[start..end]))
+}
+
+fn xml_unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn signer() -> Signer {
+        Signer::new(Credentials::new("AKID", "secret"), "us-east-1", "dynamodb")
+    }
+
+    #[test]
+    fn test_credentials_provider_signs() {
+        let provider = Arc::new(Credentials::new("ASIAPROVIDER", "s").with_session_token("t"));
+        let client = AwsClient::new(signer())
+            .with_credentials_provider(provider)
+            .with_transport(|request| {
+                let authorization = request.header("authorization").unwrap();
+                assert!(authorization.contains("Credential=ASIAPROVIDER/"));
+                assert_eq!(request.header("x-amz-security-token"), Some("t"));
+                Ok(AwsResponse::new(200, ""))
+            });
+        let request = AwsRequest::new("GET", "dynamodb.us-east-1.amazonaws.com", "/");
+        assert_eq!(client.send(request).unwrap().status, 200);
+    }
+
+    #[test]
+    fn test_json_service_error() {
+        let client = AwsClient::new(signer()).with_transport(|_| {
+            Ok(AwsResponse::new(
+                400,
+                r#"{"__type":"com.amazonaws.dynamodb.v20120810#ResourceNotFoundException","message":"Requested resource not found"}"#,
+            ))
+        });
+        let err = client
+            .call_json(
+                "dynamodb.us-east-1.amazonaws.com",
+                "application/x-amz-json-1.0",
+                "DynamoDB_20120810.GetItem",
+                &serde_json::json!({}),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AwsError::Service {
+                status: 400,
+                code: "ResourceNotFoundException".to_string(),
+                message: "Requested resource not found".to_string(),
+            }
+        );
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_xml_and_header_service_errors() {
+        let xml = AwsResponse::new(
+            404,
+            "<?xml version=\"1.0\"?><Error><Code>NoSuchKey</Code><Message>Key &amp; missing</Message></Error>",
+        );
+        assert_eq!(service_error(&xml).code(), Some("NoSuchKey"));
+        assert_eq!(
+            service_error(&xml).to_string(),
+            "AWS error 404 NoSuchKey: Key & missing"
+        );
+
+        let header = AwsResponse::new(503, "").with_header(
+            "X-Amzn-ErrorType",
+            "ServiceUnavailable:http://internal.amazon.com/",
+        );
+        assert_eq!(service_error(&header).code(), Some("ServiceUnavailable"));
+        assert!(service_error(&header).is_retryable());
+
+        assert_eq!(
+            service_error(&AwsResponse::new(502, "")).code(),
+            Some("Http502")
+        );
+    }
+
+    #[test]
+    fn test_plain_http_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 8192];
+            while !request.ends_with(b"\r\n\r\n{}") {
+                let n = stream.read(&mut chunk).unwrap();
+                request.extend_from_slice(&chunk[..n]);
+            }
+            let body = r#"{"TableNames":["orders"]}"#;
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            String::from_utf8(request).unwrap()
+        });
+
+        let client = AwsClient::new(signer()).with_endpoint(&addr);
+        let output = client
+            .call_json(
+                "dynamodb.us-east-1.amazonaws.com",
+                "application/x-amz-json-1.0",
+                "DynamoDB_20120810.ListTables",
+                &serde_json::json!({}),
+            )
+            .unwrap();
+        assert_eq!(output["TableNames"][0], "orders");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST / HTTP/1.1\r\n"));
+        assert!(request.contains(&format!("host: {addr}\r\n")));
+        assert!(request.contains("x-amz-target: DynamoDB_20120810.ListTables\r\n"));
+        assert!(request.contains("authorization: AWS4-HMAC-SHA256 Credential=AKID/"));
+        assert!(request.ends_with("\r\n\r\n{}"));
+    }
+
+    #[test]
+    fn test_transport_failure_is_retryable() {
+        let client = AwsClient::new(signer()).with_transport(|_| Err("reset".to_string()));
+        let err = client
+            .send(AwsRequest::new("GET", "example.com", "/"))
+            .unwrap_err();
+        assert_eq!(err, AwsError::Transport("reset".to_string()));
+        assert!(err.is_retryable());
+        assert!(format!("{client:?}").contains("custom"));
+    }
+
+    #[test]
+    fn test_send_retries_throttling_and_5xx() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let client = AwsClient::new(signer())
+            .with_retry_policy(
+                RetryPolicy::default()
+                    .with_backoff(Duration::from_millis(1), Duration::from_millis(2)),
+            )
+            .with_transport(move |_| match counted.fetch_add(1, Ordering::SeqCst) {
+                0 => Ok(AwsResponse::new(
+                    400,
+                    r#"{"__type":"com.amazonaws.dynamodb.v20120810#ThrottlingException"}"#,
+                )),
+                1 => Ok(AwsResponse::new(503, "")),
+                _ => Ok(AwsResponse::new(200, "ok")),
+            });
+        let response = client
+            .send(AwsRequest::new("GET", "example.com", "/"))
+            .unwrap();
+        assert_eq!(response.body, b"ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_send_does_not_retry_client_errors() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let client = AwsClient::new(signer()).with_transport(move |_| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            Ok(AwsResponse::new(
+                400,
+                r#"{"__type":"ValidationException","message":"bad"}"#,
+            ))
+        });
+        let err = client
+            .send(AwsRequest::new("GET", "example.com", "/"))
+            .unwrap_err();
+        assert_eq!(err.code(), Some("ValidationException"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let client = AwsClient::new(signer())
+            .with_retry_policy(RetryPolicy::none())
+            .with_transport(|_| Err("reset".to_string()));
+        assert!(client
+            .send(AwsRequest::new("GET", "example.com", "/"))
+            .is_err());
+        assert_eq!(client.retry_policy().max_attempts, 1);
+    }
+}
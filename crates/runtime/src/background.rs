@@ -0,0 +1,166 @@
+// Post-Response Background Tasks
+//
+// Lambda freezes the execution environment once the runtime asks for the
+// next event, so work that must not delay the caller (telemetry flushes,
+// cache warming) has a narrow window: after the response is posted, before
+// GET /next. Handlers queue such work with Context::spawn_background; the
+// runtime runs it in that window:
+//
+//   next_event -> handler (queues tasks) -> post_response -> tasks -> next_event
+//
+// Tasks are kept per request ID in a process-wide registry, so worker
+// threads only run their own invocation's tasks. Anything still queued at
+// shutdown is run by drain_background_tasks. A panicking task is logged and
+// does not affect the others.
+
+use crate::logger::Logger;
+use crate::unwind;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// A queued post-response task
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Queued tasks by request ID
+static PENDING: Lazy<Mutex<HashMap<String, Vec<Task>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Queue `task` to run after the response for `request_id` is posted
+pub(crate) fn spawn(request_id: &str, task: Task) {
+    PENDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(request_id.to_string())
+        .or_default()
+        .push(task);
+}
+
+/// Number of tasks queued for `request_id`
+pub(crate) fn pending(request_id: &str) -> usize {
+    PENDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(request_id)
+        .map_or(0, Vec::len)
+}
+
+/// Run (and forget) the tasks queued for `request_id`, in spawn order
+///
+/// Returns the number of tasks run.
+pub(crate) fn run(request_id: &str) -> usize {
+    let tasks = PENDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(request_id)
+        .unwrap_or_default();
+    run_tasks(request_id, tasks)
+}
+
+/// Run every queued task (e.g. before the process exits)
+///
+/// Returns the number of tasks run.
+#[must_use = "the count shows whether any work was still pending"]
+pub fn drain_background_tasks() -> usize {
+    let queued: Vec<(String, Vec<Task>)> = PENDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .drain()
+        .collect();
+    queued
+        .into_iter()
+        .map(|(request_id, tasks)| run_tasks(&request_id, tasks))
+        .sum()
+}
+
+fn run_tasks(request_id: &str, tasks: Vec<Task>) -> usize {
+    let count = tasks.len();
+    for task in tasks {
+        // Lock is not held here, so a task may queue follow-up work
+        if unwind::catch(task).is_err() {
+            Logger::with_request_id(request_id).error("Background task panicked");
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // The registry is process-wide and drained as a whole, so tests are serial
+
+    #[test]
+    #[serial]
+    fn test_run_in_spawn_order() {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        for n in 0..3 {
+            let order = Arc::clone(&order);
+            spawn("bg-order", Box::new(move || order.lock().unwrap().push(n)));
+        }
+        assert_eq!(pending("bg-order"), 3);
+
+        assert_eq!(run("bg-order"), 3);
+        assert_eq!(*order.lock().unwrap(), [0, 1, 2]);
+        assert_eq!(pending("bg-order"), 0);
+        assert_eq!(run("bg-order"), 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_run_only_own_request() {
+        let ran = Arc::new(AtomicUsize::new(0));
+        for request_id in ["bg-mine", "bg-other"] {
+            let ran = Arc::clone(&ran);
+            spawn(
+                request_id,
+                Box::new(move || {
+                    ran.fetch_add(1, Ordering::SeqCst);
+                }),
+            );
+        }
+
+        assert_eq!(run("bg-mine"), 1);
+        assert_eq!(ran.load(Ordering::SeqCst), 1);
+        assert_eq!(pending("bg-other"), 1);
+        assert_eq!(run("bg-other"), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_panicking_task_does_not_stop_others() {
+        let ran = Arc::new(AtomicUsize::new(0));
+        spawn("bg-panic", Box::new(|| panic!("telemetry endpoint down")));
+        let after = Arc::clone(&ran);
+        spawn(
+            "bg-panic",
+            Box::new(move || {
+                after.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+
+        assert_eq!(run("bg-panic"), 2);
+        assert_eq!(ran.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_drain_runs_everything() {
+        let ran = Arc::new(AtomicUsize::new(0));
+        for request_id in ["bg-drain-1", "bg-drain-2"] {
+            let ran = Arc::clone(&ran);
+            spawn(
+                request_id,
+                Box::new(move || {
+                    ran.fetch_add(1, Ordering::SeqCst);
+                }),
+            );
+        }
+
+        assert_eq!(drain_background_tasks(), 2);
+        assert_eq!(ran.load(Ordering::SeqCst), 2);
+        assert_eq!(pending("bg-drain-1"), 0);
+    }
+}
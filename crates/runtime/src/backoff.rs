@@ -0,0 +1,259 @@
+// Event Loop Backoff and Circuit Breaker
+//
+// When the Runtime API keeps failing (emulator down, networking broken) the
+// event loop must neither exit on the first error nor spin at 100% CPU:
+// - Exponential backoff between attempts: initial * 2^(n-1), capped at max
+// - Success resets the failure count
+// - After `fatal_threshold` consecutive failures the circuit opens and the
+//   bootstrap exits with CIRCUIT_OPEN_EXIT_CODE so Lambda (or the operator)
+//   sees a distinct failure instead of a silent hang
+//
+// Configured with RUCHY_LAMBDA_BACKOFF_INITIAL_MS, RUCHY_LAMBDA_BACKOFF_MAX_MS
+// and RUCHY_LAMBDA_FATAL_FAILURES (0 disables the circuit breaker).
+
+use crate::{Error, Result};
+use std::env;
+use std::time::Duration;
+
+/// Environment variable overriding the first backoff delay (milliseconds)
+pub const BACKOFF_INITIAL_ENV: &str = "RUCHY_LAMBDA_BACKOFF_INITIAL_MS";
+
+/// Environment variable overriding the maximum backoff delay (milliseconds)
+pub const BACKOFF_MAX_ENV: &str = "RUCHY_LAMBDA_BACKOFF_MAX_MS";
+
+/// Environment variable overriding the fatal consecutive-failure threshold
+pub const FATAL_FAILURES_ENV: &str = "RUCHY_LAMBDA_FATAL_FAILURES";
+
+/// Process exit code when the circuit breaker opens (`EX_UNAVAILABLE`)
+pub const CIRCUIT_OPEN_EXIT_CODE: i32 = 69;
+
+/// Backoff and circuit breaker settings
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::BackoffConfig;
+/// use std::time::Duration;
+///
+/// let config = BackoffConfig::default();
+/// assert_eq!(config.initial, Duration::from_millis(10));
+/// assert_eq!(config.max, Duration::from_secs(1));
+/// assert_eq!(config.fatal_threshold, Some(50));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffConfig {
+    /// Delay after the first failure
+    pub initial: Duration,
+    /// Upper bound for the delay
+    pub max: Duration,
+    /// Consecutive failures that open the circuit (`None` = retry forever)
+    pub fatal_threshold: Option<u32>,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(1),
+            fatal_threshold: Some(50),
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Defaults overridden by the `RUCHY_LAMBDA_BACKOFF_*` /
+    /// `RUCHY_LAMBDA_FATAL_FAILURES` environment variables
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if a variable is not a
+    /// non-negative integer.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+
+        if let Some(ms) = env_u64(BACKOFF_INITIAL_ENV)? {
+            config.initial = Duration::from_millis(ms);
+        }
+        if let Some(ms) = env_u64(BACKOFF_MAX_ENV)? {
+            config.max = Duration::from_millis(ms);
+        }
+        if let Some(threshold) = env_u64(FATAL_FAILURES_ENV)? {
+            config.fatal_threshold =
+                Some(u32::try_from(threshold).unwrap_or(u32::MAX)).filter(|&t| t > 0);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Read an optional non-negative integer environment variable
+fn env_u64(name: &str) -> Result<Option<u64>> {
+    match env::var(name) {
+        Ok(value) => value.trim().parse().map(Some).map_err(|_| {
+            Error::InitializationFailed(format!(
+                "Invalid {name} '{value}' (expected a non-negative integer)"
+            ))
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// What the event loop should do after a failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackoffAction {
+    /// Sleep for the given delay, then try again
+    Retry(Duration),
+    /// Too many consecutive failures: exit with [`CIRCUIT_OPEN_EXIT_CODE`]
+    Fatal,
+}
+
+/// Consecutive-failure tracker for the event loop
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Backoff, BackoffAction, BackoffConfig};
+/// use std::time::Duration;
+///
+/// let mut backoff = Backoff::new(BackoffConfig {
+///     initial: Duration::from_millis(10),
+///     max: Duration::from_millis(25),
+///     fatal_threshold: Some(3),
+/// });
+/// assert_eq!(backoff.record_failure(), BackoffAction::Retry(Duration::from_millis(10)));
+/// assert_eq!(backoff.record_failure(), BackoffAction::Retry(Duration::from_millis(20)));
+/// assert_eq!(backoff.record_failure(), BackoffAction::Fatal);
+///
+/// backoff.record_success();
+/// assert_eq!(backoff.consecutive_failures(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    config: BackoffConfig,
+    consecutive_failures: u32,
+}
+
+impl Backoff {
+    /// Create a tracker with no recorded failures
+    #[must_use]
+    pub fn new(config: BackoffConfig) -> Self {
+        Self {
+            config,
+            consecutive_failures: 0,
+        }
+    }
+
+    /// Reset after a successful iteration
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// Record a failure and decide whether to retry or give up
+    pub fn record_failure(&mut self) -> BackoffAction {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+        match self.config.fatal_threshold {
+            Some(threshold) if self.consecutive_failures >= threshold => BackoffAction::Fatal,
+            _ => BackoffAction::Retry(self.delay()),
+        }
+    }
+
+    /// Number of failures since the last success
+    #[must_use]
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Delay for the current failure count: `initial * 2^(n-1)`, capped at `max`
+    #[must_use]
+    pub fn delay(&self) -> Duration {
+        if self.consecutive_failures == 0 {
+            return Duration::ZERO;
+        }
+
+        let exponent = (self.consecutive_failures - 1).min(31);
+        self.config
+            .initial
+            .checked_mul(1 << exponent)
+            .map_or(self.config.max, |delay| delay.min(self.config.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn config(fatal_threshold: Option<u32>) -> BackoffConfig {
+        BackoffConfig {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(100),
+            fatal_threshold,
+        }
+    }
+
+    #[test]
+    fn test_backoff_exponential_and_capped() {
+        let mut backoff = Backoff::new(config(None));
+        let delays: Vec<_> = (0..6)
+            .map(|_| match backoff.record_failure() {
+                BackoffAction::Retry(delay) => delay.as_millis(),
+                BackoffAction::Fatal => panic!("circuit breaker disabled"),
+            })
+            .collect();
+        assert_eq!(delays, [10, 20, 40, 80, 100, 100]);
+    }
+
+    #[test]
+    fn test_backoff_never_overflows() {
+        let mut backoff = Backoff::new(config(None));
+        for _ in 0..1000 {
+            backoff.record_failure();
+        }
+        assert_eq!(backoff.delay(), Duration::from_millis(100));
+        assert_eq!(backoff.consecutive_failures(), 1000);
+    }
+
+    #[test]
+    fn test_backoff_success_resets() {
+        let mut backoff = Backoff::new(config(Some(3)));
+        backoff.record_failure();
+        backoff.record_failure();
+        backoff.record_success();
+        assert_eq!(backoff.delay(), Duration::ZERO);
+        assert_eq!(
+            backoff.record_failure(),
+            BackoffAction::Retry(Duration::from_millis(10))
+        );
+    }
+
+    #[test]
+    fn test_backoff_fatal_threshold() {
+        let mut backoff = Backoff::new(config(Some(2)));
+        assert!(matches!(backoff.record_failure(), BackoffAction::Retry(_)));
+        assert_eq!(backoff.record_failure(), BackoffAction::Fatal);
+    }
+
+    #[test]
+    #[serial]
+    fn test_backoff_config_from_env() {
+        env::set_var(BACKOFF_INITIAL_ENV, "5");
+        env::set_var(BACKOFF_MAX_ENV, "500");
+        env::set_var(FATAL_FAILURES_ENV, "0");
+        let config = BackoffConfig::from_env().unwrap();
+        assert_eq!(config.initial, Duration::from_millis(5));
+        assert_eq!(config.max, Duration::from_millis(500));
+        assert_eq!(config.fatal_threshold, None);
+
+        env::set_var(FATAL_FAILURES_ENV, "lots");
+        assert!(matches!(
+            BackoffConfig::from_env(),
+            Err(Error::InitializationFailed(msg)) if msg.contains(FATAL_FAILURES_ENV)
+        ));
+
+        env::remove_var(BACKOFF_INITIAL_ENV);
+        env::remove_var(BACKOFF_MAX_ENV);
+        env::remove_var(FATAL_FAILURES_ENV);
+        assert_eq!(BackoffConfig::from_env().unwrap(), BackoffConfig::default());
+    }
+}
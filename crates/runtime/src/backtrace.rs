@@ -0,0 +1,150 @@
+// Backtrace Capture for Handler Errors (`backtrace` cargo feature)
+//
+// With the feature, `HandlerError::new` records where the error was created
+// in `stack_trace`, which `post_error` sends as the error document's
+// `stackTrace`, so a failed invocation in CloudWatch or an async failure
+// destination points at the code that produced it.
+//
+// Capturing and symbolizing costs tens of microseconds to milliseconds per
+// error and a few KB per payload, so it stays off unless asked for:
+// - RUCHY_LAMBDA_BACKTRACE=1 captures, =0 never captures
+// - unset: std's RUST_LIB_BACKTRACE / RUST_BACKTRACE decide
+//
+// Frames of the capture itself are dropped and at most MAX_STACK_FRAMES are
+// kept. Stripped release binaries only have addresses to report; deploy with
+// `strip = false` (or debug symbols) when frames should be named.
+
+use crate::prefetch::parse_flag;
+use once_cell::sync::OnceCell;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::env;
+
+/// Environment variable overriding `RUST_BACKTRACE` for handler errors
+pub const BACKTRACE_ENV: &str = "RUCHY_LAMBDA_BACKTRACE";
+
+/// Most frames kept in `HandlerError::stack_trace`
+pub(crate) const MAX_STACK_FRAMES: usize = 32;
+
+/// Decision of [`BACKTRACE_ENV`], read once (`None` when unset)
+static FORCED: OnceCell<Option<bool>> = OnceCell::new();
+
+/// Stack trace lines for an error created by the caller (empty when disabled)
+pub(crate) fn capture() -> Vec<String> {
+    let forced = FORCED.get_or_init(|| env::var(BACKTRACE_ENV).ok().map(|v| parse_flag(&v)));
+    let backtrace = match forced {
+        Some(true) => Backtrace::force_capture(),
+        Some(false) => return Vec::new(),
+        None => Backtrace::capture(),
+    };
+    if backtrace.status() != BacktraceStatus::Captured {
+        return Vec::new();
+    }
+    frames(&backtrace.to_string())
+}
+
+/// One line per frame of a rendered backtrace
+///
+/// Joins each `N: symbol` line with its `at file:line:col` line, drops the
+/// frames of the capture itself and of the std runtime below
+/// `__rust_begin_short_backtrace`, and keeps at most [`MAX_STACK_FRAMES`].
+fn frames(rendered: &str) -> Vec<String> {
+    let mut frames: Vec<String> = Vec::new();
+    for line in rendered.lines().map(str::trim) {
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                frame.push_str(" at ");
+                frame.push_str(location);
+            }
+        } else if let Some(symbol) = strip_index(line) {
+            frames.push(symbol.to_string());
+        } else if !line.is_empty() && !line.starts_with("note: ") {
+            // Inlined into the frame above: printed without an index
+            frames.push(line.to_string());
+        }
+    }
+
+    let own = frames
+        .iter()
+        .position(|frame| !is_capture_frame(frame))
+        .unwrap_or(frames.len());
+    frames
+        .into_iter()
+        .skip(own)
+        .take_while(|frame| !frame.contains("__rust_begin_short_backtrace"))
+        .take(MAX_STACK_FRAMES)
+        .collect()
+}
+
+/// Symbol of a `N: symbol` line
+fn strip_index(line: &str) -> Option<&str> {
+    let (index, symbol) = line.split_once(": ")?;
+    (!index.is_empty() && index.bytes().all(|b| b.is_ascii_digit())).then_some(symbol)
+}
+
+/// Whether a frame belongs to taking the backtrace, not to the caller
+fn is_capture_frame(frame: &str) -> bool {
+    let symbol = frame.trim_start_matches('<');
+    symbol.starts_with("std::backtrace")
+        || symbol.starts_with("ruchy_lambda_runtime::backtrace::capture")
+        || symbol.starts_with("ruchy_lambda_runtime::handler_error::HandlerError")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    const RENDERED: &str = "   0: std::backtrace::Backtrace::force_capture
+             at /rustc/abc/library/std/src/backtrace.rs:312:9
+   1: ruchy_lambda_runtime::backtrace::capture
+             at ./src/backtrace.rs:36:23
+   2: ruchy_lambda_runtime::handler_error::HandlerError::new
+             at ./src/handler_error.rs:52:25
+   3: orders::charge
+             at ./src/orders.rs:88:13
+   4: <unknown>
+   5: core::ops::function::FnOnce::call_once
+      main
+             at ./src/main.rs:5:5
+   6: std::sys::backtrace::__rust_begin_short_backtrace
+   7: std::rt::lang_start_internal
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.";
+
+    #[test]
+    fn test_frames_join_symbol_and_location() {
+        assert_eq!(
+            frames(RENDERED),
+            vec![
+                "orders::charge at ./src/orders.rs:88:13".to_string(),
+                "<unknown>".to_string(),
+                "core::ops::function::FnOnce::call_once".to_string(),
+                "main at ./src/main.rs:5:5".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frames_are_capped() {
+        let mut rendered = String::new();
+        for i in 0..100 {
+            writeln!(rendered, "{i}: frame_{i}").unwrap();
+        }
+        let frames = frames(&rendered);
+        assert_eq!(frames.len(), MAX_STACK_FRAMES);
+        assert_eq!(frames[0], "frame_0");
+    }
+
+    #[test]
+    fn test_frames_of_captured_backtrace() {
+        let frames = frames(&Backtrace::force_capture().to_string());
+        assert!(frames
+            .iter()
+            .any(|frame| frame.contains("test_frames_of_captured_backtrace")));
+        assert!(!frames[0].starts_with("std::backtrace"));
+    }
+
+    #[test]
+    fn test_frames_of_empty_backtrace() {
+        assert!(frames("").is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+// Raw Bytes Responses
+//
+// Handlers that serve images, PDFs or other files behind API Gateway / ALB
+// must answer with a proxy response whose body is base64 and whose
+// isBase64Encoded flag is set:
+//
+//   {"statusCode":200,"headers":{"content-type":"image/png"},
+//    "body":"iVBORw0KGgo...","isBase64Encoded":true}
+//
+// A handler returns Bytes (body + content type) and `binary` builds that
+// response. Textual content types (text/*, JSON, XML, JavaScript) with a
+// UTF-8 body are sent as-is so they stay readable in logs and tests.
+//
+// REST APIs (payload 1.0) only decode the body when the content type is
+// listed in the API's binary media types; HTTP APIs and ALB always do.
+
+use crate::{Context, HandlerError};
+use ruchy_lambda_simd::base64;
+
+/// Raw response body with its content type
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Bytes;
+///
+/// let png = Bytes::new(vec![0x89, b'P', b'N', b'G'], "image/png")
+///     .with_header("cache-control", "max-age=60");
+/// let response: serde_json::Value = serde_json::from_str(&png.to_proxy_response()).unwrap();
+/// assert_eq!(response["statusCode"], 200);
+/// assert_eq!(response["headers"]["content-type"], "image/png");
+/// assert_eq!(response["body"], "iVBORw==");
+/// assert_eq!(response["isBase64Encoded"], true);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bytes {
+    body: Vec<u8>,
+    content_type: String,
+    status_code: u16,
+    headers: Vec<(String, String)>,
+}
+
+impl Bytes {
+    /// `body` served as `content_type` with status 200
+    #[must_use]
+    pub fn new(body: impl Into<Vec<u8>>, content_type: impl Into<String>) -> Self {
+        Self {
+            body: body.into(),
+            content_type: content_type.into(),
+            status_code: 200,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Set the HTTP status code (default: 200)
+    #[must_use]
+    pub fn with_status(mut self, status_code: u16) -> Self {
+        self.status_code = status_code;
+        self
+    }
+
+    /// Add a response header (`content-type` is set from the content type)
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Response body
+    #[must_use]
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Response content type
+    #[must_use]
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// HTTP status code
+    #[must_use]
+    pub fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    /// Whether the body is sent as text instead of base64
+    ///
+    /// True for textual content types (`text/*`, JSON, XML, JavaScript,
+    /// form data) whose body is valid UTF-8.
+    #[must_use]
+    pub fn is_text(&self) -> bool {
+        is_text_content_type(&self.content_type) && std::str::from_utf8(&self.body).is_ok()
+    }
+
+    /// API Gateway / ALB proxy response JSON
+    #[must_use]
+    pub fn to_proxy_response(&self) -> String {
+        let mut headers = serde_json::Map::new();
+        headers.insert(
+            "content-type".to_string(),
+            serde_json::Value::String(self.content_type.clone()),
+        );
+        for (name, value) in &self.headers {
+            headers.insert(name.clone(), serde_json::Value::String(value.clone()));
+        }
+
+        let (body, is_base64_encoded) = match std::str::from_utf8(&self.body) {
+            Ok(text) if is_text_content_type(&self.content_type) => (text.to_string(), false),
+            _ => (base64::encode(&self.body), true),
+        };
+
+        serde_json::json!({
+            "statusCode": self.status_code,
+            "headers": headers,
+            "body": body,
+            "isBase64Encoded": is_base64_encoded,
+        })
+        .to_string()
+    }
+}
+
+/// `text/*`, `*/json`, `*+json`, `*/xml`, `*+xml`, JavaScript and form data
+fn is_text_content_type(content_type: &str) -> bool {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let Some((kind, subtype)) = media_type.split_once('/') else {
+        return false;
+    };
+    kind == "text"
+        || matches!(
+            subtype,
+            "json" | "xml" | "javascript" | "x-www-form-urlencoded"
+        )
+        || subtype.ends_with("+json")
+        || subtype.ends_with("+xml")
+}
+
+/// Adapt a handler returning [`Bytes`] to the runtime's string handler shape
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{binary, Bytes, Context, HandlerError};
+///
+/// let handler = binary(|_ctx: &Context, _event: &str| {
+///     Ok::<_, HandlerError>(Bytes::new(b"%PDF-1.7".to_vec(), "application/pdf"))
+/// });
+///
+/// let response = handler(&Context::default(), "{}").unwrap();
+/// let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+/// assert_eq!(value["body"], "JVBERi0xLjc=");
+/// assert_eq!(value["isBase64Encoded"], true);
+/// ```
+pub fn binary(
+    handler: impl Fn(&Context, &str) -> Result<Bytes, HandlerError>,
+) -> impl Fn(&Context, &str) -> Result<String, HandlerError> {
+    move |context, event_body| handler(context, event_body).map(|bytes| bytes.to_proxy_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn parse(bytes: &Bytes) -> Value {
+        serde_json::from_str(&bytes.to_proxy_response()).unwrap()
+    }
+
+    #[test]
+    fn test_binary_body_is_base64() {
+        let bytes = Bytes::new(vec![0, 159, 146, 150], "application/octet-stream")
+            .with_status(201)
+            .with_header("content-disposition", "attachment; filename=\"a.bin\"");
+        assert!(!bytes.is_text());
+
+        let response = parse(&bytes);
+        assert_eq!(response["statusCode"], 201);
+        assert_eq!(response["isBase64Encoded"], true);
+        assert_eq!(
+            base64::decode(response["body"].as_str().unwrap().as_bytes()).unwrap(),
+            bytes.body()
+        );
+        assert_eq!(
+            response["headers"]["content-disposition"],
+            "attachment; filename=\"a.bin\""
+        );
+    }
+
+    #[test]
+    fn test_text_body_is_sent_as_is() {
+        let response = parse(&Bytes::new("<p>hi</p>", "text/html; charset=utf-8"));
+        assert_eq!(response["body"], "<p>hi</p>");
+        assert_eq!(response["isBase64Encoded"], false);
+        assert_eq!(
+            response["headers"]["content-type"],
+            "text/html; charset=utf-8"
+        );
+
+        // Invalid UTF-8 falls back to base64 even for text types
+        let response = parse(&Bytes::new(vec![0xff, 0xfe], "text/plain"));
+        assert_eq!(response["isBase64Encoded"], true);
+    }
+
+    #[test]
+    fn test_text_content_types() {
+        for text in [
+            "text/csv",
+            "application/json",
+            "application/problem+json",
+            "Application/XML",
+            "image/svg+xml",
+            "application/javascript",
+            "application/x-www-form-urlencoded",
+        ] {
+            assert!(is_text_content_type(text), "{text}");
+        }
+        for binary in [
+            "image/png",
+            "application/pdf",
+            "application/octet-stream",
+            "bogus",
+        ] {
+            assert!(!is_text_content_type(binary), "{binary}");
+        }
+    }
+
+    #[test]
+    fn test_binary_propagates_handler_error() {
+        let handler = binary(|_: &Context, _: &str| {
+            Err::<Bytes, _>(HandlerError::new("Function.NotFound", "no such file"))
+        });
+        assert_eq!(
+            handler(&Context::default(), "{}").unwrap_err().error_type,
+            "Function.NotFound"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// Cooperative Handler Cancellation
+//
+// Threads cannot be stopped from the outside, so long computations have to
+// check whether to give up. Every Context carries a CancellationToken:
+//
+//   for (i, item) in items.iter().enumerate() {
+//       if context.cancellation.is_cancelled() {
+//           return partial_result(&done);   // or an error
+//       }
+//       ...
+//   }
+//
+// A token is cancelled
+// - by the TimeoutGuard when the handler reaches its margin before the
+//   deadline (`CancelReason::Timeout`); the guard then waits a short grace
+//   period for the handler's (partial) result before abandoning it
+// - for every invocation at once when the process is shutting down
+//   (`CancelReason::Shutdown`): `request_shutdown`, called on SIGTERM with
+//   feature "signals" (`install_sigterm_handler`) or by an extension's
+//   SHUTDOWN handling
+// - by the handler itself (`CancelReason::Requested`), e.g. to stop helper
+//   threads sharing a clone of the token
+
+use once_cell::sync::Lazy;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// Set once the process is shutting down; cancels every token (an `Arc` so
+/// the SIGTERM handler can set it directly)
+static SHUTDOWN: Lazy<Arc<AtomicBool>> = Lazy::new(Arc::default);
+
+/// Why a token was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    /// The invocation is about to time out
+    Timeout,
+    /// The execution environment is shutting down
+    Shutdown,
+    /// Cancelled with [`CancellationToken::cancel`]
+    Requested,
+}
+
+impl CancelReason {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Timeout => 1,
+            Self::Shutdown => 2,
+            Self::Requested => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Self::Timeout),
+            2 => Some(Self::Shutdown),
+            3 => Some(Self::Requested),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Timeout => "invocation timing out",
+            Self::Shutdown => "runtime shutting down",
+            Self::Requested => "cancelled",
+        })
+    }
+}
+
+/// Cancellation flag of one invocation, shared by its clones
+///
+/// Equality compares the state, not the identity, so contexts stay
+/// comparable in tests.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{CancelReason, CancellationToken};
+///
+/// let token = CancellationToken::new();
+/// let worker = token.clone();
+/// assert!(!worker.is_cancelled());
+///
+/// token.cancel(CancelReason::Requested);
+/// assert!(worker.is_cancelled());
+/// assert_eq!(worker.reason(), Some(CancelReason::Requested));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    reason: Arc<AtomicU8>,
+}
+
+impl CancellationToken {
+    /// Token that is not cancelled
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token (and its clones); the first reason sticks
+    pub fn cancel(&self, reason: CancelReason) {
+        let _ =
+            self.reason
+                .compare_exchange(0, reason.to_u8(), Ordering::AcqRel, Ordering::Acquire);
+    }
+
+    /// Whether the handler should stop (the token or the process was
+    /// cancelled)
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    /// Why the handler should stop, if it should
+    #[must_use]
+    pub fn reason(&self) -> Option<CancelReason> {
+        CancelReason::from_u8(self.reason.load(Ordering::Acquire)).or_else(|| {
+            SHUTDOWN
+                .load(Ordering::Acquire)
+                .then_some(CancelReason::Shutdown)
+        })
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.reason() == other.reason()
+    }
+}
+
+impl Eq for CancellationToken {}
+
+/// Cancel every invocation's token: the process is shutting down
+pub fn request_shutdown() {
+    SHUTDOWN.store(true, Ordering::Release);
+}
+
+/// Whether [`request_shutdown`] was called (or SIGTERM received)
+#[must_use]
+pub fn shutdown_requested() -> bool {
+    SHUTDOWN.load(Ordering::Acquire)
+}
+
+/// Call [`request_shutdown`] when the process receives SIGTERM
+///
+/// Lambda sends SIGTERM before reclaiming an environment with external
+/// extensions; handlers then see [`CancelReason::Shutdown`].
+///
+/// # Errors
+///
+/// Returns the error of registering the signal handler.
+#[cfg(feature = "signals")]
+pub fn install_sigterm_handler() -> std::io::Result<()> {
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&SHUTDOWN)).map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_reason_sticks() {
+        let token = CancellationToken::new();
+        assert_eq!(token.reason(), None);
+        token.cancel(CancelReason::Timeout);
+        token.cancel(CancelReason::Requested);
+        assert_eq!(token.reason(), Some(CancelReason::Timeout));
+        assert_eq!(token.reason().unwrap().to_string(), "invocation timing out");
+    }
+
+    #[test]
+    fn test_equality_by_state() {
+        let (a, b) = (CancellationToken::new(), CancellationToken::new());
+        assert_eq!(a, b);
+        a.cancel(CancelReason::Requested);
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,89 @@
+// Runtime API Client Initialization Strategy
+//
+// Lazy init keeps Runtime::new() <1ms but pushes TCP connection setup into
+// the first `next` call. Between init and the first `next` there is a free
+// pre-invoke window; pre-connecting during it keeps both init and
+// first-invoke latencies low.
+//
+// Selected with RUCHY_LAMBDA_CLIENT_INIT=lazy|eager|background
+// or Runtime::with_client_init().
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Environment variable selecting the [`ClientInit`] strategy
+pub const CLIENT_INIT_ENV: &str = "RUCHY_LAMBDA_CLIENT_INIT";
+
+/// When the Runtime API client opens its first connection
+///
+/// Applied by [`Runtime::prepare`](crate::Runtime::prepare), which the
+/// bootstrap calls once after initialization.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::ClientInit;
+///
+/// let mode: ClientInit = "background".parse().unwrap();
+/// assert_eq!(mode, ClientInit::Background);
+/// assert_eq!(ClientInit::default(), ClientInit::Lazy);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClientInit {
+    /// Connect on the first `next` call (fastest init)
+    #[default]
+    Lazy,
+    /// Connect during init, blocking until the connection is open
+    Eager,
+    /// Connect on a background thread during the pre-invoke window
+    Background,
+}
+
+impl fmt::Display for ClientInit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lazy => write!(f, "lazy"),
+            Self::Eager => write!(f, "eager"),
+            Self::Background => write!(f, "background"),
+        }
+    }
+}
+
+impl FromStr for ClientInit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "lazy" => Ok(Self::Lazy),
+            "eager" => Ok(Self::Eager),
+            "background" => Ok(Self::Background),
+            other => Err(format!(
+                "Invalid {CLIENT_INIT_ENV} '{other}' (expected lazy, eager or background)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_init_round_trip() {
+        for mode in [ClientInit::Lazy, ClientInit::Eager, ClientInit::Background] {
+            assert_eq!(mode.to_string().parse::<ClientInit>(), Ok(mode));
+        }
+    }
+
+    #[test]
+    fn test_client_init_case_insensitive() {
+        assert_eq!(" Eager ".parse::<ClientInit>(), Ok(ClientInit::Eager));
+    }
+
+    #[test]
+    fn test_client_init_invalid() {
+        let err = "sometimes".parse::<ClientInit>().unwrap_err();
+        assert!(err.contains(CLIENT_INIT_ENV));
+        assert!(err.contains("sometimes"));
+    }
+}
@@ -0,0 +1,193 @@
+// Clock Abstraction
+//
+// Time-dependent logic (deadlines, log timestamps, retry backoff,
+// idempotency TTLs) reads time through a Clock instead of calling
+// SystemTime::now() directly, so tests can pin and advance it:
+// - SystemClock: wall clock and thread::sleep (the default everywhere)
+// - ManualClock: starts at a fixed time and only moves when advanced;
+//   sleeping advances it instead of blocking (re-exported by the testkit)
+//
+// Components keep a SharedClock (a cloneable Arc<dyn Clock>) and take
+// another through `with_clock`.
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current time
+    fn now(&self) -> SystemTime;
+
+    /// Block for `duration` (a test clock advances instead)
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// The system's wall clock
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Shared handle to a [`Clock`], the [`SystemClock`] by default
+///
+/// Handles compare equal when they share the same clock.
+#[derive(Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl SharedClock {
+    /// Handle to `clock`
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        Self(Arc::new(clock))
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl Deref for SharedClock {
+    type Target = dyn Clock;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl PartialEq for SharedClock {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedClock {}
+
+impl fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedClock").field(&self.0.now()).finish()
+    }
+}
+
+/// Test clock that only moves when told to
+///
+/// Clones share the same time, so a test keeps one clone to advance while
+/// the code under test reads another. [`Clock::sleep`] advances the clock
+/// and returns immediately.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Clock, ManualClock};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let clock = ManualClock::at_millis(1_700_000_000_000);
+/// let reader = clock.clone();
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!(reader.now(), UNIX_EPOCH + Duration::from_millis(1_700_000_001_000));
+///
+/// reader.sleep(Duration::from_millis(250));
+/// assert_eq!(clock.slept(), Duration::from_millis(250));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    state: Arc<Mutex<ManualState>>,
+}
+
+#[derive(Debug)]
+struct ManualState {
+    now: SystemTime,
+    slept: Duration,
+}
+
+impl ManualClock {
+    /// Clock stopped at `now`
+    #[must_use]
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ManualState {
+                now,
+                slept: Duration::ZERO,
+            })),
+        }
+    }
+
+    /// Clock stopped at `millis` since the Unix epoch (the unit of
+    /// `Lambda-Runtime-Deadline-Ms`)
+    #[must_use]
+    pub fn at_millis(millis: u64) -> Self {
+        Self::new(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    /// Move the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        self.state().now += duration;
+    }
+
+    /// Set the clock to `now` (may move it backwards)
+    pub fn set(&self, now: SystemTime) {
+        self.state().now = now;
+    }
+
+    /// Total time passed to [`Clock::sleep`] so far
+    #[must_use]
+    pub fn slept(&self) -> Duration {
+        self.state().slept
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ManualState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        self.state().now
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let mut state = self.state();
+        state.now += duration;
+        state.slept += duration;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_clock_defaults_to_system_time() {
+        let before = SystemTime::now();
+        let now = SharedClock::default().now();
+        assert!(now >= before);
+    }
+
+    #[test]
+    fn test_shared_clock_equality_is_identity() {
+        let clock = SharedClock::new(ManualClock::at_millis(0));
+        assert_eq!(clock, clock.clone());
+        assert_ne!(clock, SharedClock::new(ManualClock::at_millis(0)));
+    }
+
+    #[test]
+    fn test_manual_clock_set_and_sleep() {
+        let clock = ManualClock::at_millis(5_000);
+        let shared = SharedClock::new(clock.clone());
+        shared.sleep(Duration::from_secs(2));
+        assert_eq!(clock.now(), UNIX_EPOCH + Duration::from_secs(7));
+        assert_eq!(clock.slept(), Duration::from_secs(2));
+
+        clock.set(UNIX_EPOCH);
+        assert_eq!(shared.now(), UNIX_EPOCH);
+    }
+}
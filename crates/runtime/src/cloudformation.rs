@@ -0,0 +1,362 @@
+// CloudFormation Custom Resources
+//
+// CloudFormation invokes the function asynchronously with a Create, Update
+// or Delete request and then waits (up to an hour) for the result to be
+// PUT to the pre-signed S3 URL in `ResponseURL`. The function's own return
+// value is ignored, so a handler that fails without PUTting a response
+// hangs the stack.
+//
+// The PUT must have an empty Content-Type and a body under 4096 bytes:
+//
+//   {"Status": "SUCCESS" | "FAILED", "Reason": "...", "PhysicalResourceId": "...",
+//    "StackId": "...", "RequestId": "...", "LogicalResourceId": "...",
+//    "NoEcho": false, "Data": {...}}
+//
+// The runtime has no TLS stack (binary size), so CustomResourceResponse::send
+// builds the complete PUT and hands it to a caller-supplied HTTPS transport.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Maximum response body size `CloudFormation` accepts
+pub const CUSTOM_RESOURCE_RESPONSE_LIMIT: usize = 4096;
+
+/// Lifecycle operation requested by `CloudFormation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CustomResourceRequestType {
+    /// Resource is being created
+    Create,
+    /// Resource properties changed
+    Update,
+    /// Resource is being deleted (also on rollback)
+    Delete,
+}
+
+/// Custom resource request
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{CustomResourceRequest, CustomResourceResponse};
+///
+/// let body = r#"{"RequestType":"Create","ServiceToken":"arn:aws:lambda:us-east-1:123456789012:function:cr",
+///     "ResponseURL":"https://bucket.s3.amazonaws.com/key?X-Amz-Signature=abc",
+///     "StackId":"arn:aws:cloudformation:us-east-1:123456789012:stack/app/1","RequestId":"r1",
+///     "ResourceType":"Custom::Bucket","LogicalResourceId":"Bucket",
+///     "ResourceProperties":{"ServiceToken":"...","Name":"media"}}"#;
+///
+/// let request = CustomResourceRequest::from_json(body).unwrap();
+/// let name = request.property("Name").and_then(|v| v.as_str()).unwrap();
+/// let response = CustomResourceResponse::success(&request)
+///     .with_physical_resource_id(name)
+///     .with_data("Arn", format!("arn:aws:s3:::{name}"));
+///
+/// let mut sent = None;
+/// response
+///     .send(&request, |put| -> Result<(), String> {
+///         // Real code hands `put` to an HTTPS client
+///         sent = Some((put.url.to_string(), put.body.clone()));
+///         Ok(())
+///     })
+///     .unwrap();
+/// let (url, body) = sent.unwrap();
+/// assert!(url.starts_with("https://bucket.s3.amazonaws.com/"));
+/// assert!(body.contains(r#""PhysicalResourceId":"media""#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomResourceRequest {
+    /// Create, Update or Delete
+    pub request_type: CustomResourceRequestType,
+    /// ARN of the function (or SNS topic) serving the resource
+    #[serde(default)]
+    pub service_token: String,
+    /// Pre-signed S3 URL the response must be PUT to
+    #[serde(rename = "ResponseURL")]
+    pub response_url: String,
+    /// Stack ARN
+    pub stack_id: String,
+    /// Unique ID of this request
+    pub request_id: String,
+    /// Template resource type (`Custom::...`)
+    #[serde(default)]
+    pub resource_type: String,
+    /// Template logical ID
+    pub logical_resource_id: String,
+    /// ID returned by an earlier Create (Update and Delete only)
+    #[serde(default)]
+    pub physical_resource_id: Option<String>,
+    /// Current resource properties
+    #[serde(default)]
+    pub resource_properties: Map<String, Value>,
+    /// Previous resource properties (Update only)
+    #[serde(default)]
+    pub old_resource_properties: Option<Map<String, Value>>,
+}
+
+impl CustomResourceRequest {
+    /// Parse a custom resource request
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a custom resource
+    /// request.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// A resource property
+    #[must_use]
+    pub fn property(&self, name: &str) -> Option<&Value> {
+        self.resource_properties.get(name)
+    }
+}
+
+/// Outcome reported to `CloudFormation`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CustomResourceStatus {
+    /// Operation succeeded
+    Success,
+    /// Operation failed (`Reason` is shown in the stack events)
+    Failed,
+}
+
+/// Response PUT to the request's `ResponseURL`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomResourceResponse {
+    /// SUCCESS or FAILED
+    pub status: CustomResourceStatus,
+    /// Explanation (required for FAILED)
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub reason: String,
+    /// ID of the resource; changing it on Update makes `CloudFormation` delete
+    /// the old one
+    pub physical_resource_id: String,
+    /// Stack ARN from the request
+    pub stack_id: String,
+    /// Request ID from the request
+    pub request_id: String,
+    /// Logical ID from the request
+    pub logical_resource_id: String,
+    /// Mask `Data` in console and API output
+    pub no_echo: bool,
+    /// Values readable with `Fn::GetAtt`
+    #[serde(skip_serializing_if = "Map::is_empty", default)]
+    pub data: Map<String, Value>,
+}
+
+impl CustomResourceResponse {
+    /// SUCCESS response, keeping the request's physical ID (or using the
+    /// request ID for a Create)
+    #[must_use]
+    pub fn success(request: &CustomResourceRequest) -> Self {
+        Self {
+            status: CustomResourceStatus::Success,
+            reason: String::new(),
+            physical_resource_id: request
+                .physical_resource_id
+                .clone()
+                .unwrap_or_else(|| request.request_id.clone()),
+            stack_id: request.stack_id.clone(),
+            request_id: request.request_id.clone(),
+            logical_resource_id: request.logical_resource_id.clone(),
+            no_echo: false,
+            data: Map::new(),
+        }
+    }
+
+    /// FAILED response with `reason`
+    #[must_use]
+    pub fn failed(request: &CustomResourceRequest, reason: impl Into<String>) -> Self {
+        Self {
+            status: CustomResourceStatus::Failed,
+            reason: reason.into(),
+            ..Self::success(request)
+        }
+    }
+
+    /// Set the physical resource ID
+    #[must_use]
+    pub fn with_physical_resource_id(mut self, id: impl Into<String>) -> Self {
+        self.physical_resource_id = id.into();
+        self
+    }
+
+    /// Add a `Fn::GetAtt` attribute
+    #[must_use]
+    pub fn with_data(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.data.insert(name.into(), value.into());
+        self
+    }
+
+    /// Mask `Data` in console and API output
+    #[must_use]
+    pub fn with_no_echo(mut self, no_echo: bool) -> Self {
+        self.no_echo = no_echo;
+        self
+    }
+
+    /// JSON response body
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// The PUT that delivers this response for `request`
+    ///
+    /// A body over [`CUSTOM_RESOURCE_RESPONSE_LIMIT`] is replaced by a FAILED
+    /// response without `Data`, since `CloudFormation` would reject it.
+    #[must_use]
+    pub fn put_request<'a>(&self, request: &'a CustomResourceRequest) -> PresignedPut<'a> {
+        let mut body = self.to_json();
+        if body.len() > CUSTOM_RESOURCE_RESPONSE_LIMIT {
+            body = Self::failed(
+                request,
+                format!(
+                    "Response of {} bytes exceeds the {CUSTOM_RESOURCE_RESPONSE_LIMIT} byte limit",
+                    body.len()
+                ),
+            )
+            .with_physical_resource_id(self.physical_resource_id.clone())
+            .to_json();
+        }
+
+        PresignedPut {
+            url: &request.response_url,
+            headers: vec![
+                // Pre-signed URLs are signed without a content type
+                ("Content-Type", String::new()),
+                ("Content-Length", body.len().to_string()),
+            ],
+            body,
+        }
+    }
+
+    /// Deliver this response by handing its PUT to `transport`
+    ///
+    /// # Errors
+    ///
+    /// Returns the transport's error.
+    pub fn send<E>(
+        &self,
+        request: &CustomResourceRequest,
+        transport: impl FnOnce(&PresignedPut<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        transport(&self.put_request(request))
+    }
+}
+
+/// An HTTP PUT to a pre-signed S3 URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresignedPut<'a> {
+    /// Pre-signed `https://` URL
+    pub url: &'a str,
+    /// Headers to send
+    pub headers: Vec<(&'static str, String)>,
+    /// Request body
+    pub body: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(request_type: &str, physical_id: Option<&str>) -> CustomResourceRequest {
+        let mut event = serde_json::json!({
+            "RequestType": request_type,
+            "ServiceToken": "arn:aws:lambda:us-east-1:123456789012:function:cr",
+            "ResponseURL": "https://cfn-responses.s3.amazonaws.com/r?sig=1",
+            "StackId": "arn:aws:cloudformation:us-east-1:123456789012:stack/app/1",
+            "RequestId": "req-1",
+            "ResourceType": "Custom::Thing",
+            "LogicalResourceId": "Thing",
+            "ResourceProperties": {"Size": 3},
+            "OldResourceProperties": {"Size": 2}
+        });
+        if let Some(id) = physical_id {
+            event["PhysicalResourceId"] = id.into();
+        }
+        CustomResourceRequest::from_json(&event.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_update() {
+        let request = request("Update", Some("thing-7"));
+        assert_eq!(request.request_type, CustomResourceRequestType::Update);
+        assert_eq!(request.physical_resource_id.as_deref(), Some("thing-7"));
+        assert_eq!(request.property("Size"), Some(&Value::from(3)));
+        assert_eq!(
+            request.old_resource_properties.unwrap()["Size"],
+            Value::from(2)
+        );
+    }
+
+    #[test]
+    fn test_success_keeps_physical_id() {
+        let update = CustomResourceResponse::success(&request("Update", Some("thing-7")));
+        assert_eq!(update.physical_resource_id, "thing-7");
+
+        let create = CustomResourceResponse::success(&request("Create", None));
+        assert_eq!(create.physical_resource_id, "req-1");
+    }
+
+    #[test]
+    fn test_response_document() {
+        let response = CustomResourceResponse::failed(&request("Delete", Some("p")), "in use");
+        let value: Value = serde_json::from_str(&response.to_json()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "Status": "FAILED",
+                "Reason": "in use",
+                "PhysicalResourceId": "p",
+                "StackId": "arn:aws:cloudformation:us-east-1:123456789012:stack/app/1",
+                "RequestId": "req-1",
+                "LogicalResourceId": "Thing",
+                "NoEcho": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_put_request_headers() {
+        let request = request("Create", None);
+        let put = CustomResourceResponse::success(&request)
+            .with_data("Answer", 42)
+            .with_no_echo(true)
+            .put_request(&request);
+
+        assert_eq!(put.url, "https://cfn-responses.s3.amazonaws.com/r?sig=1");
+        assert_eq!(put.headers[0], ("Content-Type", String::new()));
+        assert_eq!(
+            put.headers[1],
+            ("Content-Length", put.body.len().to_string())
+        );
+        assert!(put.body.contains(r#""Data":{"Answer":42}"#));
+        assert!(put.body.contains(r#""NoEcho":true"#));
+    }
+
+    #[test]
+    fn test_oversized_response_becomes_failure() {
+        let request = request("Create", None);
+        let put = CustomResourceResponse::success(&request)
+            .with_physical_resource_id("big")
+            .with_data("Blob", "x".repeat(CUSTOM_RESOURCE_RESPONSE_LIMIT))
+            .put_request(&request);
+
+        let value: Value = serde_json::from_str(&put.body).unwrap();
+        assert_eq!(value["Status"], "FAILED");
+        assert_eq!(value["PhysicalResourceId"], "big");
+        assert!(value.get("Data").is_none());
+        assert!(put.body.len() <= CUSTOM_RESOURCE_RESPONSE_LIMIT);
+    }
+
+    #[test]
+    fn test_send_propagates_transport_error() {
+        let request = request("Delete", Some("p"));
+        let result = CustomResourceResponse::success(&request).send(&request, |_| Err("timeout"));
+        assert_eq!(result, Err("timeout"));
+    }
+}
@@ -0,0 +1,495 @@
+// Typed Payload Codecs
+//
+// Handlers in this runtime take and return strings. `typed` adapts a
+// handler over serde types, decoding the event and encoding the response
+// with a Codec:
+//
+// - Json (always available): the event body is the JSON document
+// - Cbor (feature "cbor") and MessagePack (feature "msgpack"): invocation
+//   payloads must be JSON, so binary payloads travel base64-encoded, either
+//   as a bare JSON string or as the body of an API Gateway / ALB proxy event
+//   with isBase64Encoded set. Responses are returned the same way, as a
+//   JSON string of base64.
+//
+// The binary formats pull in ciborium / rmp-serde, so they stay out of the
+// default build.
+//
+// Other payload conventions plug in as an EventCodec, which decodes the
+// payload straight into the handler's request type and encodes its response
+// type, so they need not go through serde: TextCodec carries plain text and
+// ProstCodec (feature "protobuf") base64 protobuf. `typed_with` (or
+// `Runtime::typed_handler` with the codec registered on the runtime) adapts
+// a handler through one. Codec is an EventCodec for every serde type.
+
+use crate::{Context, HandlerError};
+use ruchy_lambda_simd::base64;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Error type for payloads that cannot be decoded
+pub const UNMARSHAL_ERROR: &str = "Runtime.UnmarshalError";
+
+/// Error type for responses that cannot be encoded
+pub const MARSHAL_ERROR: &str = "Runtime.MarshalError";
+
+/// Wire format of typed payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Codec {
+    /// JSON (`application/json`)
+    #[default]
+    Json,
+    /// CBOR (`application/cbor`)
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// `MessagePack` (`application/msgpack`)
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl Codec {
+    /// Codec for a `Content-Type` value (parameters are ignored)
+    ///
+    /// Returns `None` for unknown types and for formats whose feature is
+    /// not enabled.
+    #[must_use]
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let media_type = content_type.split(';').next()?.trim();
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/json" => Some(Self::Json),
+            #[cfg(feature = "cbor")]
+            "application/cbor" => Some(Self::Cbor),
+            #[cfg(feature = "msgpack")]
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Self::MessagePack)
+            }
+            _ => None,
+        }
+    }
+
+    /// `Content-Type` value for this codec
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            #[cfg(feature = "cbor")]
+            Self::Cbor => "application/cbor",
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Whether payloads travel base64-encoded
+    #[must_use]
+    pub fn is_binary(self) -> bool {
+        self != Self::Json
+    }
+
+    /// Decode raw payload bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.UnmarshalError` if the bytes do not decode to `T`.
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, HandlerError> {
+        let result = match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+        };
+        result.map_err(|message| HandlerError::new(UNMARSHAL_ERROR, message))
+    }
+
+    /// Encode a value to raw payload bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.MarshalError` if `value` cannot be encoded.
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, HandlerError> {
+        let result = match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map(|()| bytes)
+                    .map_err(|e| e.to_string())
+            }
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+        };
+        result.map_err(|message| HandlerError::new(MARSHAL_ERROR, message))
+    }
+
+    /// Decode an invocation payload
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.UnmarshalError` if the payload is not in the
+    /// expected shape or does not decode to `T`.
+    pub fn decode_event<T: DeserializeOwned>(self, event_body: &str) -> Result<T, HandlerError> {
+        if !self.is_binary() {
+            return self.decode(event_body.as_bytes());
+        }
+
+        let bytes = binary_payload(event_body)?;
+        self.decode(&bytes)
+    }
+
+    /// Encode a response payload
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.MarshalError` if `value` cannot be encoded.
+    pub fn encode_response<T: Serialize>(self, value: &T) -> Result<String, HandlerError> {
+        let bytes = self.encode(value)?;
+        if self.is_binary() {
+            serde_json::to_string(&base64::encode(&bytes))
+                .map_err(|e| HandlerError::new(MARSHAL_ERROR, e.to_string()))
+        } else {
+            String::from_utf8(bytes).map_err(|e| HandlerError::new(MARSHAL_ERROR, e.to_string()))
+        }
+    }
+}
+
+/// Base64-decoded bytes of a bare string or proxy-event binary payload
+pub(crate) fn binary_payload(event_body: &str) -> Result<Vec<u8>, HandlerError> {
+    let unmarshal = |message: String| HandlerError::new(UNMARSHAL_ERROR, message);
+    let encoded = match serde_json::from_str(event_body).map_err(|e| unmarshal(e.to_string()))? {
+        BinaryPayload::Bare(encoded) => encoded,
+        BinaryPayload::Proxy {
+            body,
+            is_base64_encoded: true,
+        } => body,
+        BinaryPayload::Proxy { .. } => {
+            return Err(unmarshal(
+                "proxy event body is not base64-encoded".to_string(),
+            ))
+        }
+    };
+    base64::decode(encoded.as_bytes()).map_err(|e| unmarshal(e.to_string()))
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BinaryPayload {
+    Bare(String),
+    #[serde(rename_all = "camelCase")]
+    Proxy {
+        body: String,
+        #[serde(default)]
+        is_base64_encoded: bool,
+    },
+}
+
+/// Payload convention for typed handlers
+///
+/// Decodes the invocation payload into a handler's `Req` and encodes its
+/// `Resp` as the invocation response. Register one with
+/// [`Runtime::with_event_codec`](crate::Runtime::with_event_codec).
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{typed_with, Context, EventCodec, HandlerError, UNMARSHAL_ERROR};
+/// use std::collections::BTreeMap;
+///
+/// /// `key=value` lines in, `key=value` lines out
+/// struct Pairs;
+///
+/// impl EventCodec<BTreeMap<String, String>, BTreeMap<String, String>> for Pairs {
+///     fn decode(&self, event_body: &str) -> Result<BTreeMap<String, String>, HandlerError> {
+///         event_body
+///             .lines()
+///             .map(|line| {
+///                 let (key, value) = line
+///                     .split_once('=')
+///                     .ok_or_else(|| HandlerError::new(UNMARSHAL_ERROR, "expected key=value"))?;
+///                 Ok((key.to_string(), value.to_string()))
+///             })
+///             .collect()
+///     }
+///
+///     fn encode(&self, response: &BTreeMap<String, String>) -> Result<String, HandlerError> {
+///         Ok(response.iter().map(|(key, value)| format!("{key}={value}\n")).collect())
+///     }
+/// }
+///
+/// let handler = typed_with(Pairs, |_ctx: &Context, request: BTreeMap<String, String>| {
+///     let greeting = format!("hi {}", request["name"]);
+///     Ok::<_, HandlerError>(BTreeMap::from([("greeting".to_string(), greeting)]))
+/// });
+/// assert_eq!(handler(&Context::default(), "name=ada").unwrap(), "greeting=hi ada\n");
+/// ```
+pub trait EventCodec<Req, Resp>: Send + Sync {
+    /// Decode an invocation payload into the request
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.UnmarshalError` ([`UNMARSHAL_ERROR`]) for
+    /// payloads that do not follow the convention.
+    fn decode(&self, event_body: &str) -> Result<Req, HandlerError>;
+
+    /// Encode a handler's response as the invocation response
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.MarshalError` ([`MARSHAL_ERROR`]) for responses
+    /// the convention cannot carry.
+    fn encode(&self, response: &Resp) -> Result<String, HandlerError>;
+}
+
+impl<Req, Resp, C: EventCodec<Req, Resp> + ?Sized> EventCodec<Req, Resp> for Arc<C> {
+    fn decode(&self, event_body: &str) -> Result<Req, HandlerError> {
+        (**self).decode(event_body)
+    }
+
+    fn encode(&self, response: &Resp) -> Result<String, HandlerError> {
+        (**self).encode(response)
+    }
+}
+
+impl<Req, Resp> EventCodec<Req, Resp> for Codec
+where
+    Req: DeserializeOwned,
+    Resp: Serialize,
+{
+    fn decode(&self, event_body: &str) -> Result<Req, HandlerError> {
+        self.decode_event(event_body)
+    }
+
+    fn encode(&self, response: &Resp) -> Result<String, HandlerError> {
+        self.encode_response(response)
+    }
+}
+
+/// Plain-text payloads
+///
+/// A payload that is a JSON string is decoded to that string; any other
+/// payload is taken verbatim, as text. Handlers take and return `String`,
+/// and the response is returned verbatim, without JSON quoting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextCodec;
+
+impl EventCodec<String, String> for TextCodec {
+    fn decode(&self, event_body: &str) -> Result<String, HandlerError> {
+        Ok(match serde_json::from_str(event_body) {
+            Ok(Value::String(text)) => text,
+            _ => event_body.to_string(),
+        })
+    }
+
+    fn encode(&self, response: &String) -> Result<String, HandlerError> {
+        Ok(response.clone())
+    }
+}
+
+/// Adapt a typed handler to the runtime's string handler shape
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{typed, Codec, Context, HandlerError};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize)]
+/// struct Order {
+///     quantity: u32,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Quote {
+///     total: u32,
+/// }
+///
+/// let handler = typed(Codec::Json, |_ctx: &Context, order: Order| {
+///     Ok::<_, HandlerError>(Quote { total: order.quantity * 3 })
+/// });
+///
+/// let ctx = Context::default();
+/// assert_eq!(handler(&ctx, r#"{"quantity":2}"#).unwrap(), r#"{"total":6}"#);
+/// assert_eq!(handler(&ctx, "{}").unwrap_err().error_type, "Runtime.UnmarshalError");
+/// ```
+pub fn typed<Req, Resp>(
+    codec: Codec,
+    handler: impl Fn(&Context, Req) -> Result<Resp, HandlerError>,
+) -> impl Fn(&Context, &str) -> Result<String, HandlerError>
+where
+    Req: DeserializeOwned,
+    Resp: Serialize,
+{
+    move |context, event_body| {
+        let request = codec.decode_event(event_body)?;
+        let response = handler(context, request)?;
+        codec.encode_response(&response)
+    }
+}
+
+/// Adapt a typed handler to the string handler shape through `codec`
+///
+/// Like [`typed`], for any [`EventCodec`].
+pub fn typed_with<Req, Resp>(
+    codec: impl EventCodec<Req, Resp>,
+    handler: impl Fn(&Context, Req) -> Result<Resp, HandlerError>,
+) -> impl Fn(&Context, &str) -> Result<String, HandlerError> {
+    move |context, event_body| {
+        let response = handler(context, codec.decode(event_body)?)?;
+        codec.encode(&response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        sensor: String,
+        values: Vec<i32>,
+    }
+
+    fn reading() -> Reading {
+        Reading {
+            sensor: "s1".to_string(),
+            values: vec![1, -2, 300],
+        }
+    }
+
+    #[test]
+    fn test_json_is_default() {
+        assert_eq!(Codec::default(), Codec::Json);
+        assert!(!Codec::Json.is_binary());
+        assert_eq!(
+            Codec::from_content_type("Application/JSON; charset=utf-8"),
+            Some(Codec::Json)
+        );
+        assert_eq!(Codec::from_content_type("text/plain"), None);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let body = Codec::Json.encode_response(&reading()).unwrap();
+        assert_eq!(
+            Codec::Json.decode_event::<Reading>(&body).unwrap(),
+            reading()
+        );
+    }
+
+    #[test]
+    fn test_typed_propagates_handler_error() {
+        let handler = typed(Codec::Json, |_: &Context, _: Value| {
+            Err::<Value, _>(HandlerError::new("Function.Rejected", "no"))
+        });
+        let ctx = Context::default();
+        assert_eq!(
+            handler(&ctx, "{}").unwrap_err().error_type,
+            "Function.Rejected"
+        );
+        assert_eq!(
+            handler(&ctx, "not json").unwrap_err().error_type,
+            UNMARSHAL_ERROR
+        );
+    }
+
+    #[test]
+    fn test_text_codec() {
+        let handler = typed_with(TextCodec, |_: &Context, text: String| {
+            Ok::<_, HandlerError>(text.to_uppercase())
+        });
+        let ctx = Context::default();
+        assert_eq!(handler(&ctx, "plain words").unwrap(), "PLAIN WORDS");
+        assert_eq!(handler(&ctx, r#""quoted""#).unwrap(), "QUOTED");
+        assert_eq!(
+            handler(&ctx, r#"{"not":"text"}"#).unwrap(),
+            r#"{"NOT":"TEXT"}"#
+        );
+    }
+
+    #[test]
+    fn test_text_codec_round_trip() {
+        let text = "line one\n\"two\"".to_string();
+        let encoded = TextCodec.encode(&text).unwrap();
+        assert_eq!(encoded, text);
+        assert_eq!(TextCodec.decode(&encoded).unwrap(), text);
+    }
+
+    #[test]
+    fn test_typed_with_custom_codec_errors() {
+        struct Reject;
+        impl EventCodec<u32, u32> for Reject {
+            fn decode(&self, event_body: &str) -> Result<u32, HandlerError> {
+                event_body
+                    .parse()
+                    .map_err(|_| HandlerError::new(UNMARSHAL_ERROR, "not a number"))
+            }
+            fn encode(&self, _: &u32) -> Result<String, HandlerError> {
+                Err(HandlerError::new(MARSHAL_ERROR, "unsupported"))
+            }
+        }
+
+        let ctx = Context::default();
+        let handler = typed_with(Reject, |_: &Context, n: u32| Ok::<_, HandlerError>(n));
+        assert_eq!(handler(&ctx, "x").unwrap_err().error_type, UNMARSHAL_ERROR);
+        assert_eq!(handler(&ctx, "7").unwrap_err().error_type, MARSHAL_ERROR);
+    }
+
+    #[test]
+    fn test_typed_with_builtin_codec_matches_typed() {
+        let handler = typed_with(Codec::Json, |_: &Context, r: Reading| {
+            Ok::<_, HandlerError>(r.values.len())
+        });
+        let body = Codec::Json.encode_response(&reading()).unwrap();
+        assert_eq!(handler(&Context::default(), &body).unwrap(), "3");
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_round_trip() {
+        let codec = Codec::from_content_type("application/cbor").unwrap();
+        assert!(codec.is_binary());
+
+        let body = codec.encode_response(&reading()).unwrap();
+        assert!(body.starts_with('"'));
+        assert_eq!(codec.decode_event::<Reading>(&body).unwrap(), reading());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_from_proxy_event() {
+        let codec = Codec::MessagePack;
+        let bytes = codec.encode(&reading()).unwrap();
+        let event = serde_json::json!({"body": base64::encode(&bytes), "isBase64Encoded": true})
+            .to_string();
+        assert_eq!(codec.decode_event::<Reading>(&event).unwrap(), reading());
+
+        let plain = serde_json::json!({"body": "x", "isBase64Encoded": false}).to_string();
+        assert_eq!(
+            codec
+                .decode_event::<Reading>(&plain)
+                .unwrap_err()
+                .error_type,
+            UNMARSHAL_ERROR
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_invalid_payload() {
+        let codec = Codec::MessagePack;
+        let body = serde_json::json!(base64::encode(b"\xc1")).to_string();
+        assert_eq!(
+            codec.decode_event::<Reading>(&body).unwrap_err().error_type,
+            UNMARSHAL_ERROR
+        );
+        assert_eq!(
+            codec
+                .decode_event::<Reading>("\"not base64!\"")
+                .unwrap_err()
+                .error_type,
+            UNMARSHAL_ERROR
+        );
+    }
+}
@@ -0,0 +1,318 @@
+// Cognito User Pool Triggers
+//
+// Cognito invokes a trigger with the full event and expects the same event
+// back with `response` filled in. Every trigger shares one envelope
+// (version, triggerSource, region, userPoolId, userName, callerContext) and
+// differs only in its `request` / `response` records, so the envelope is
+// generic:
+//
+//   PreSignUp          -> CognitoPreSignUpEvent
+//   PostConfirmation   -> CognitoPostConfirmationEvent
+//   PreTokenGeneration -> CognitoPreTokenGenerationEvent
+//
+// Handlers deserialize, mutate `response` and return `to_json()`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// String attributes / metadata sent by Cognito
+pub type CognitoAttributes = HashMap<String, String>;
+
+/// Cognito trigger event: shared envelope plus trigger-specific records
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::CognitoPreSignUpEvent;
+///
+/// let body = r#"{
+///     "version": "1",
+///     "triggerSource": "PreSignUp_SignUp",
+///     "region": "us-east-1",
+///     "userPoolId": "us-east-1_abc",
+///     "userName": "alice",
+///     "callerContext": {"awsSdkVersion": "aws-sdk-js-3", "clientId": "client"},
+///     "request": {"userAttributes": {"email": "alice@example.com"}},
+///     "response": {}
+/// }"#;
+///
+/// let mut event = CognitoPreSignUpEvent::from_json(body).unwrap();
+/// if event.request.user_attributes["email"].ends_with("@example.com") {
+///     event.response.auto_confirm_user = true;
+/// }
+/// assert!(event.to_json().contains(r#""autoConfirmUser":true"#));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CognitoEvent<Req, Resp> {
+    /// Event format version
+    #[serde(default)]
+    pub version: String,
+    /// What caused the trigger (e.g. `PreSignUp_SignUp`)
+    pub trigger_source: String,
+    /// AWS region of the user pool
+    #[serde(default)]
+    pub region: String,
+    /// User pool ID
+    #[serde(default)]
+    pub user_pool_id: String,
+    /// Username of the current user
+    #[serde(default)]
+    pub user_name: Option<String>,
+    /// SDK version and app client of the caller
+    #[serde(default)]
+    pub caller_context: CognitoCallerContext,
+    /// Trigger input
+    pub request: Req,
+    /// Trigger output, returned to Cognito
+    #[serde(default)]
+    pub response: Resp,
+}
+
+impl<Req, Resp> CognitoEvent<Req, Resp>
+where
+    Req: for<'de> Deserialize<'de> + Serialize,
+    Resp: for<'de> Deserialize<'de> + Serialize + Default,
+{
+    /// Parse a trigger event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a trigger event of
+    /// this kind.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// The event (with `response` filled in) as the JSON Cognito expects back
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Caller of the user pool API
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CognitoCallerContext {
+    /// AWS SDK version that made the request
+    #[serde(default)]
+    pub aws_sdk_version: String,
+    /// App client ID
+    #[serde(default)]
+    pub client_id: String,
+}
+
+/// `PreSignUp` trigger event
+pub type CognitoPreSignUpEvent = CognitoEvent<PreSignUpRequest, PreSignUpResponse>;
+
+/// `PostConfirmation` trigger event
+pub type CognitoPostConfirmationEvent =
+    CognitoEvent<PostConfirmationRequest, PostConfirmationResponse>;
+
+/// `PreTokenGeneration` (V1) trigger event
+pub type CognitoPreTokenGenerationEvent =
+    CognitoEvent<PreTokenGenerationRequest, PreTokenGenerationResponse>;
+
+/// Input of the `PreSignUp` trigger
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreSignUpRequest {
+    /// Attributes the user signs up with
+    #[serde(default)]
+    pub user_attributes: CognitoAttributes,
+    /// Validation data passed to `SignUp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_data: Option<CognitoAttributes>,
+    /// Client metadata passed to `SignUp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_metadata: Option<CognitoAttributes>,
+}
+
+/// Output of the `PreSignUp` trigger
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreSignUpResponse {
+    /// Confirm the user without a verification code
+    #[serde(default)]
+    pub auto_confirm_user: bool,
+    /// Mark the email address as verified
+    #[serde(default)]
+    pub auto_verify_email: bool,
+    /// Mark the phone number as verified
+    #[serde(default)]
+    pub auto_verify_phone: bool,
+}
+
+/// Input of the `PostConfirmation` trigger
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostConfirmationRequest {
+    /// Attributes of the confirmed user
+    #[serde(default)]
+    pub user_attributes: CognitoAttributes,
+    /// Client metadata passed to the confirming API call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_metadata: Option<CognitoAttributes>,
+}
+
+/// Output of the `PostConfirmation` trigger (always empty)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostConfirmationResponse {}
+
+/// Input of the `PreTokenGeneration` trigger
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreTokenGenerationRequest {
+    /// Attributes of the user the tokens are for
+    #[serde(default)]
+    pub user_attributes: CognitoAttributes,
+    /// Groups and IAM roles the user currently has
+    #[serde(default)]
+    pub group_configuration: GroupConfiguration,
+    /// Client metadata passed to the authenticating API call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_metadata: Option<CognitoAttributes>,
+}
+
+/// Output of the `PreTokenGeneration` trigger
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreTokenGenerationResponse {
+    /// Claim changes (`None` leaves the tokens unchanged)
+    #[serde(default)]
+    pub claims_override_details: Option<ClaimsOverrideDetails>,
+}
+
+impl PreTokenGenerationResponse {
+    /// Add or override an ID token claim
+    pub fn add_claim(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.claims_override_details
+            .get_or_insert_with(ClaimsOverrideDetails::default)
+            .claims_to_add_or_override
+            .insert(name.into(), value.into());
+    }
+
+    /// Remove an ID token claim
+    pub fn suppress_claim(&mut self, name: impl Into<String>) {
+        self.claims_override_details
+            .get_or_insert_with(ClaimsOverrideDetails::default)
+            .claims_to_suppress
+            .push(name.into());
+    }
+}
+
+/// Claim changes for the issued ID token
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimsOverrideDetails {
+    /// Claims to add or override
+    #[serde(default)]
+    pub claims_to_add_or_override: CognitoAttributes,
+    /// Claims to remove
+    #[serde(default)]
+    pub claims_to_suppress: Vec<String>,
+    /// Replacement group configuration (`None` keeps the current one)
+    #[serde(default)]
+    pub group_override_details: Option<GroupConfiguration>,
+}
+
+/// Cognito groups and IAM roles
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupConfiguration {
+    /// Group names
+    #[serde(default)]
+    pub groups_to_override: Vec<String>,
+    /// IAM role ARNs
+    #[serde(default)]
+    pub iam_roles_to_override: Vec<String>,
+    /// Preferred IAM role ARN
+    #[serde(default)]
+    pub preferred_role: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POST_CONFIRMATION: &str = r#"{
+        "version": "1",
+        "region": "eu-west-1",
+        "userPoolId": "eu-west-1_pool",
+        "userName": "bob",
+        "callerContext": {"awsSdkVersion": "aws-sdk-unknown-unknown", "clientId": "c1"},
+        "triggerSource": "PostConfirmation_ConfirmSignUp",
+        "request": {
+            "userAttributes": {"sub": "1234", "email": "bob@example.com", "cognito:user_status": "CONFIRMED"}
+        },
+        "response": {}
+    }"#;
+
+    const PRE_TOKEN: &str = r#"{
+        "version": "1",
+        "triggerSource": "TokenGeneration_Authentication",
+        "region": "us-east-1",
+        "userPoolId": "us-east-1_pool",
+        "userName": "carol",
+        "callerContext": {"awsSdkVersion": "aws-sdk-js-3", "clientId": "c2"},
+        "request": {
+            "userAttributes": {"sub": "5678"},
+            "groupConfiguration": {"groupsToOverride": ["admins"], "iamRolesToOverride": [], "preferredRole": null}
+        },
+        "response": {"claimsOverrideDetails": null}
+    }"#;
+
+    #[test]
+    fn test_pre_sign_up_null_fields_and_defaults() {
+        let event = CognitoPreSignUpEvent::from_json(
+            r#"{"triggerSource":"PreSignUp_AdminCreateUser","request":{"userAttributes":{},"validationData":null},"response":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(event.trigger_source, "PreSignUp_AdminCreateUser");
+        assert!(event.request.validation_data.is_none());
+        assert!(!event.response.auto_confirm_user);
+        assert!(event.user_name.is_none());
+    }
+
+    #[test]
+    fn test_post_confirmation_echoes_event() {
+        let event = CognitoPostConfirmationEvent::from_json(POST_CONFIRMATION).unwrap();
+        assert_eq!(event.user_name.as_deref(), Some("bob"));
+        assert_eq!(event.caller_context.client_id, "c1");
+        assert_eq!(
+            event.request.user_attributes["cognito:user_status"],
+            "CONFIRMED"
+        );
+
+        let echoed: serde_json::Value = serde_json::from_str(&event.to_json()).unwrap();
+        let original: serde_json::Value = serde_json::from_str(POST_CONFIRMATION).unwrap();
+        assert_eq!(echoed["request"], original["request"]);
+        assert_eq!(echoed["response"], serde_json::json!({}));
+        assert_eq!(echoed["triggerSource"], "PostConfirmation_ConfirmSignUp");
+    }
+
+    #[test]
+    fn test_pre_token_generation_claims() {
+        let mut event = CognitoPreTokenGenerationEvent::from_json(PRE_TOKEN).unwrap();
+        assert_eq!(
+            event.request.group_configuration.groups_to_override,
+            ["admins"]
+        );
+        assert!(event.response.claims_override_details.is_none());
+
+        event.response.add_claim("tenant", "acme");
+        event.response.suppress_claim("email");
+
+        let echoed: serde_json::Value = serde_json::from_str(&event.to_json()).unwrap();
+        let details = &echoed["response"]["claimsOverrideDetails"];
+        assert_eq!(details["claimsToAddOrOverride"]["tenant"], "acme");
+        assert_eq!(details["claimsToSuppress"], serde_json::json!(["email"]));
+        assert!(details["groupOverrideDetails"].is_null());
+    }
+
+    #[test]
+    fn test_wrong_trigger_shape_is_error() {
+        assert!(CognitoPreSignUpEvent::from_json(r#"{"Records":[]}"#).is_err());
+    }
+}
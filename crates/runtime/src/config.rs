@@ -0,0 +1,194 @@
+// Runtime Configuration
+//
+// Runtime::new() reads its settings from the process environment, which
+// every test in a process shares (so env-driven tests had to run #[serial]).
+// A RuntimeConfig carries the same settings as a value:
+// - RuntimeConfig::from_env(): what Runtime::new() uses
+// - RuntimeConfig::from_lookup(f): the same parsing over any variable
+//   source, e.g. a HashMap in tests
+// - RuntimeConfig::new(endpoint): defaults for everything but the endpoint
+//
+// Runtime::from_config(config) builds a runtime without touching the
+// environment; the `with_*` builders still adjust it afterwards.
+
+use crate::client_init::{ClientInit, CLIENT_INIT_ENV};
+use crate::init_trace::INIT_TRACE_ENV;
+use crate::lifecycle::LIFECYCLE_LOG_ENV;
+use crate::pipelining::PIPELINE_ENV;
+use crate::prefetch::{self, PREFETCH_ENV};
+use crate::timeout_guard::{TimeoutGuard, TIMEOUT_GUARD_ENV};
+use crate::workers::{self, WORKERS_ENV};
+use crate::{Error, Result};
+
+/// Environment variable holding the Runtime API endpoint (`host:port`)
+pub const RUNTIME_API_ENV: &str = "AWS_LAMBDA_RUNTIME_API";
+
+/// Endpoint used when [`RUNTIME_API_ENV`] is unset
+pub const DEFAULT_RUNTIME_API: &str = "127.0.0.1:9001";
+
+/// Settings a [`Runtime`](crate::Runtime) is built from
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{ClientInit, Runtime, RuntimeConfig};
+/// use std::collections::HashMap;
+///
+/// let vars = HashMap::from([
+///     ("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:9009"),
+///     ("RUCHY_LAMBDA_CLIENT_INIT", "eager"),
+/// ]);
+/// let config = RuntimeConfig::from_lookup(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+/// assert_eq!(config.api_endpoint, "127.0.0.1:9009");
+/// assert_eq!(config.client_init, ClientInit::Eager);
+///
+/// let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:9010"));
+/// assert_eq!(runtime.client_init(), ClientInit::Lazy);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+// Independent opt-in switches, not states of one machine
+#[allow(clippy::struct_excessive_bools)]
+pub struct RuntimeConfig {
+    /// Runtime API endpoint (`host:port`)
+    pub api_endpoint: String,
+    /// When the first Runtime API connection is opened
+    pub client_init: ClientInit,
+    /// Whether the bootstrap prefetches events on a background thread
+    pub prefetch: bool,
+    /// Whether response POSTs pipeline the next `/next` GET
+    pub pipelining: bool,
+    /// Worker threads for local emulation (at least 1)
+    pub workers: usize,
+    /// Whether the bootstrap logs invocation lifecycle events
+    pub lifecycle_log: bool,
+    /// Whether the first invocation logs the `init.trace` breakdown
+    pub init_trace: bool,
+    /// Watchdog the bootstrap runs handlers under
+    pub timeout_guard: Option<TimeoutGuard>,
+}
+
+impl RuntimeConfig {
+    /// Defaults with the Runtime API at `api_endpoint`
+    #[must_use]
+    pub fn new(api_endpoint: impl Into<String>) -> Self {
+        Self {
+            api_endpoint: api_endpoint.into(),
+            client_init: ClientInit::default(),
+            prefetch: false,
+            pipelining: false,
+            workers: 1,
+            lifecycle_log: false,
+            init_trace: false,
+            timeout_guard: None,
+        }
+    }
+
+    /// Settings from the process environment (see [`Runtime::new`](crate::Runtime::new))
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if a variable holds an invalid
+    /// value
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Settings from variables returned by `lookup` (`None` when unset)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if [`CLIENT_INIT_ENV`] is not a
+    /// valid [`ClientInit`], [`WORKERS_ENV`] is not a positive integer or
+    /// [`TIMEOUT_GUARD_ENV`] is not a non-negative integer
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let flag = |name| lookup(name).is_some_and(|value| prefetch::parse_flag(&value));
+        Ok(Self {
+            api_endpoint: lookup(RUNTIME_API_ENV)
+                .unwrap_or_else(|| DEFAULT_RUNTIME_API.to_string()),
+            client_init: match lookup(CLIENT_INIT_ENV) {
+                Some(value) => value.parse().map_err(Error::InitializationFailed)?,
+                None => ClientInit::default(),
+            },
+            prefetch: flag(PREFETCH_ENV),
+            pipelining: flag(PIPELINE_ENV),
+            workers: match lookup(WORKERS_ENV) {
+                Some(value) => workers::parse_workers(&value)?,
+                None => 1,
+            },
+            lifecycle_log: flag(LIFECYCLE_LOG_ENV),
+            init_trace: flag(INIT_TRACE_ENV),
+            timeout_guard: lookup(TIMEOUT_GUARD_ENV)
+                .map(|value| TimeoutGuard::parse(&value))
+                .transpose()?,
+        })
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_RUNTIME_API)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Result<RuntimeConfig> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        RuntimeConfig::from_lookup(|name| vars.get(name).map(ToString::to_string))
+    }
+
+    #[test]
+    fn test_defaults_without_variables() {
+        assert_eq!(from_vars(&[]).unwrap(), RuntimeConfig::default());
+        assert_eq!(RuntimeConfig::default().api_endpoint, DEFAULT_RUNTIME_API);
+    }
+
+    #[test]
+    fn test_all_variables() {
+        let config = from_vars(&[
+            (RUNTIME_API_ENV, "custom-host:3000"),
+            (CLIENT_INIT_ENV, "background"),
+            (PREFETCH_ENV, "1"),
+            (PIPELINE_ENV, "yes"),
+            (WORKERS_ENV, "3"),
+            (LIFECYCLE_LOG_ENV, "on"),
+            (INIT_TRACE_ENV, "true"),
+            (TIMEOUT_GUARD_ENV, "300"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config,
+            RuntimeConfig {
+                api_endpoint: "custom-host:3000".to_string(),
+                client_init: ClientInit::Background,
+                prefetch: true,
+                pipelining: false,
+                workers: 3,
+                lifecycle_log: true,
+                init_trace: true,
+                timeout_guard: Some(TimeoutGuard::new(Duration::from_millis(300))),
+            }
+        );
+        assert!(!from_vars(&[(PREFETCH_ENV, "off")]).unwrap().prefetch);
+        assert!(from_vars(&[(PIPELINE_ENV, "on")]).unwrap().pipelining);
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        for vars in [
+            [(CLIENT_INIT_ENV, "never")],
+            [(WORKERS_ENV, "0")],
+            [(TIMEOUT_GUARD_ENV, "-1")],
+        ] {
+            let name = vars[0].0;
+            assert!(matches!(
+                from_vars(&vars),
+                Err(Error::InitializationFailed(msg)) if msg.contains(name)
+            ));
+        }
+    }
+}
@@ -0,0 +1,227 @@
+// Amazon Connect Contact Flow Events
+//
+// An "Invoke AWS Lambda function" block sends the contact's data plus the
+// block's parameters:
+//
+//   {"Name": "ContactFlowEvent", "Details": {"ContactData": {...}, "Parameters": {...}}}
+//
+// and expects a flat JSON object of string values back, which the flow
+// reads as `$.External.<key>`. Nested objects are rejected by Connect, so
+// ConnectResponse only holds string pairs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Contact flow invocation
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{ConnectEvent, ConnectResponse};
+///
+/// let body = r#"{"Name":"ContactFlowEvent","Details":{"ContactData":{"ContactId":"c-1",
+///     "Channel":"VOICE","InitiationMethod":"INBOUND","Attributes":{"lang":"en"},
+///     "CustomerEndpoint":{"Address":"+15550100","Type":"TELEPHONE_NUMBER"}},
+///     "Parameters":{"lookup":"account"}}}"#;
+///
+/// let event = ConnectEvent::from_json(body).unwrap();
+/// assert_eq!(event.parameter("lookup"), Some("account"));
+/// assert_eq!(event.customer_address(), Some("+15550100"));
+///
+/// let response = ConnectResponse::new().with("accountStatus", "active");
+/// assert_eq!(response.to_json(), r#"{"accountStatus":"active"}"#);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConnectEvent {
+    /// Always `ContactFlowEvent`
+    #[serde(default)]
+    pub name: String,
+    /// Contact data and block parameters
+    pub details: ConnectDetails,
+}
+
+impl ConnectEvent {
+    /// Parse a contact flow event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a contact flow event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Parameter set on the Lambda block
+    #[must_use]
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.details.parameters.get(name).map(String::as_str)
+    }
+
+    /// Contact attribute
+    #[must_use]
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.details
+            .contact_data
+            .attributes
+            .get(name)
+            .map(String::as_str)
+    }
+
+    /// Customer phone number or address, if known
+    #[must_use]
+    pub fn customer_address(&self) -> Option<&str> {
+        self.details
+            .contact_data
+            .customer_endpoint
+            .as_ref()
+            .map(|endpoint| endpoint.address.as_str())
+    }
+}
+
+/// Contact data and block parameters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConnectDetails {
+    /// The contact
+    pub contact_data: ConnectContactData,
+    /// Parameters set on the Lambda block
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+}
+
+/// The contact being handled
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConnectContactData {
+    /// User-defined contact attributes
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+    /// `VOICE`, `CHAT` or `TASK`
+    #[serde(default)]
+    pub channel: String,
+    /// Contact ID
+    #[serde(default)]
+    pub contact_id: String,
+    /// Customer endpoint
+    #[serde(default)]
+    pub customer_endpoint: Option<ConnectEndpoint>,
+    /// First contact ID of a transferred contact
+    #[serde(default)]
+    pub initial_contact_id: String,
+    /// `INBOUND`, `OUTBOUND`, `TRANSFER`, `CALLBACK`, `API`, ...
+    #[serde(default)]
+    pub initiation_method: String,
+    /// Connect instance ARN
+    #[serde(default, rename = "InstanceARN")]
+    pub instance_arn: String,
+    /// Previous contact ID of a transferred contact
+    #[serde(default)]
+    pub previous_contact_id: String,
+    /// Queue the contact is in
+    #[serde(default)]
+    pub queue: Option<ConnectQueue>,
+    /// Endpoint the customer contacted
+    #[serde(default)]
+    pub system_endpoint: Option<ConnectEndpoint>,
+}
+
+/// Phone number or other contact endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConnectEndpoint {
+    /// Address (e.g. E.164 phone number)
+    #[serde(default)]
+    pub address: String,
+    /// Endpoint type (e.g. `TELEPHONE_NUMBER`)
+    #[serde(default, rename = "Type")]
+    pub endpoint_type: String,
+}
+
+/// Queue of a contact
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConnectQueue {
+    /// Queue ARN
+    #[serde(default, rename = "ARN")]
+    pub arn: String,
+    /// Queue name
+    #[serde(default)]
+    pub name: String,
+}
+
+/// Flat string map returned to the contact flow
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectResponse(BTreeMap<String, String>);
+
+impl ConnectResponse {
+    /// Empty response
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `$.External.<key>`
+    #[must_use]
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.insert(key.into(), value.into());
+        self
+    }
+
+    /// Value set for `key`
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// JSON response document
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_contact() {
+        let event = ConnectEvent::from_json(
+            r#"{"Name":"ContactFlowEvent","Details":{"ContactData":{
+                "Attributes":{"tier":"gold"},"Channel":"VOICE","ContactId":"c-2",
+                "CustomerEndpoint":{"Address":"+15550100","Type":"TELEPHONE_NUMBER"},
+                "InitialContactId":"c-1","InitiationMethod":"TRANSFER",
+                "InstanceARN":"arn:aws:connect:us-east-1:123456789012:instance/i",
+                "PreviousContactId":"c-1",
+                "Queue":{"ARN":"arn:aws:connect:us-east-1:123456789012:instance/i/queue/q","Name":"Support"},
+                "SystemEndpoint":{"Address":"+15550199","Type":"TELEPHONE_NUMBER"}},
+                "Parameters":{}}}"#,
+        )
+        .unwrap();
+        let contact = &event.details.contact_data;
+
+        assert_eq!(contact.initiation_method, "TRANSFER");
+        assert_eq!(contact.queue.as_ref().unwrap().name, "Support");
+        assert!(contact.instance_arn.ends_with("instance/i"));
+        assert_eq!(event.attribute("tier"), Some("gold"));
+        assert_eq!(event.parameter("missing"), None);
+    }
+
+    #[test]
+    fn test_chat_contact_without_endpoint_or_queue() {
+        let event = ConnectEvent::from_json(
+            r#"{"Name":"ContactFlowEvent","Details":{"ContactData":{"Channel":"CHAT","ContactId":"c","Queue":null},"Parameters":{"a":"1"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(event.customer_address(), None);
+        assert!(event.details.contact_data.queue.is_none());
+        assert_eq!(event.parameter("a"), Some("1"));
+    }
+
+    #[test]
+    fn test_response_is_flat() {
+        let response = ConnectResponse::new().with("b", "2").with("a", "1");
+        assert_eq!(response.get("a"), Some("1"));
+        assert_eq!(response.to_json(), r#"{"a":"1","b":"2"}"#);
+    }
+}
@@ -0,0 +1,360 @@
+// Lambda Invocation Context
+//
+// Metadata delivered as response headers on GET /runtime/invocation/next:
+// - Lambda-Runtime-Aws-Request-Id
+// - Lambda-Runtime-Deadline-Ms
+// - Lambda-Runtime-Invoked-Function-Arn
+// - Lambda-Runtime-Trace-Id
+//
+// Feature-compatible with the Context record in runtime-pure.
+
+use crate::background;
+use crate::cancellation::CancellationToken;
+use crate::clock::{Clock, SystemClock};
+use crate::correlation::CORRELATION_ID_HEADER;
+use crate::trace_context::TraceContext;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Request ID used when the Runtime API omits the header
+const UNKNOWN_REQUEST_ID: &str = "unknown";
+
+/// Per-invocation metadata from the Lambda Runtime API
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ruchy_lambda_runtime::Runtime;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let runtime = Runtime::new()?;
+/// let (context, event_body) = runtime.next_invocation()?;
+/// println!("{} has {}ms left", context.request_id, context.remaining_time_ms());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Context {
+    /// Unique request ID (`Lambda-Runtime-Aws-Request-Id`)
+    pub request_id: String,
+    /// Invocation deadline in Unix epoch milliseconds (`Lambda-Runtime-Deadline-Ms`)
+    pub deadline_ms: u64,
+    /// ARN of the invoked function, alias or version (`Lambda-Runtime-Invoked-Function-Arn`)
+    pub invoked_function_arn: String,
+    /// X-Ray tracing header (`Lambda-Runtime-Trace-Id`), if tracing is active
+    pub trace_id: Option<String>,
+    /// Parsed trace identity for propagation (from `trace_id`, or the event's
+    /// `traceparent` header via `Context::with_event_trace`)
+    pub trace_context: Option<TraceContext>,
+    /// Cross-service correlation ID (set by the
+    /// [`CorrelationId`](crate::CorrelationId) middleware)
+    pub correlation_id: Option<String>,
+    /// Verified JWT claims (set by the `JwtAuth` middleware, feature "jwt")
+    #[cfg(feature = "serde")]
+    pub claims: Option<serde_json::Value>,
+    /// Cancelled when the handler should stop early (timeout guard, runtime
+    /// shutdown); poll it in long loops
+    pub cancellation: CancellationToken,
+}
+
+impl Context {
+    /// Build a context from `(name, value)` header pairs
+    ///
+    /// Header names are matched case-insensitively. Missing headers fall back
+    /// to defaults (`request_id` = "unknown", `deadline_ms` = 0).
+    pub(crate) fn from_headers<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut context = Self {
+            request_id: UNKNOWN_REQUEST_ID.to_string(),
+            ..Self::default()
+        };
+
+        for (name, value) in headers {
+            if name.eq_ignore_ascii_case("lambda-runtime-aws-request-id") {
+                context.request_id = value.to_string();
+            } else if name.eq_ignore_ascii_case("lambda-runtime-deadline-ms") {
+                context.deadline_ms = value.parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("lambda-runtime-invoked-function-arn") {
+                context.invoked_function_arn = value.to_string();
+            } else if name.eq_ignore_ascii_case("lambda-runtime-trace-id") {
+                context.trace_id = Some(value.to_string());
+                context.trace_context = TraceContext::from_xray(value);
+            }
+        }
+
+        context
+    }
+
+    /// Continue the caller's trace from an API Gateway / ALB event
+    ///
+    /// If the event's `headers` carry a W3C `traceparent` (or
+    /// `X-Amzn-Trace-Id`), it replaces the trace context derived from the
+    /// Lambda X-Ray header; otherwise the context is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::Context;
+    ///
+    /// let event = r#"{"headers":{"traceparent":"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"}}"#;
+    /// let context = Context::default().with_event_trace(event);
+    /// let trace = context.trace_context.unwrap();
+    /// assert_eq!(trace.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    /// ```
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn with_event_trace(mut self, event_body: &str) -> Self {
+        if let Some(trace_context) = TraceContext::from_event(event_body) {
+            self.trace_context = Some(trace_context);
+        }
+        self
+    }
+
+    /// Headers to inject into an outbound request made for this invocation
+    ///
+    /// The trace headers from [`TraceContext::outbound_headers`] plus
+    /// `x-correlation-id` when a correlation ID is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::Context;
+    ///
+    /// let context = Context {
+    ///     correlation_id: Some("order-7".to_string()),
+    ///     ..Context::default()
+    /// };
+    /// assert_eq!(
+    ///     context.outbound_headers(),
+    ///     [("x-correlation-id", "order-7".to_string())]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn outbound_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = self
+            .trace_context
+            .as_ref()
+            .map(TraceContext::outbound_headers)
+            .unwrap_or_default();
+        if let Some(correlation_id) = &self.correlation_id {
+            headers.push((CORRELATION_ID_HEADER, correlation_id.clone()));
+        }
+        headers
+    }
+
+    /// Run `task` after this invocation's response is posted
+    ///
+    /// Tasks run in spawn order on the invoking thread once
+    /// [`Runtime::post_response`](crate::Runtime::post_response) (or
+    /// `post_error`) has sent the result, before the next event is fetched,
+    /// so the caller is not kept waiting. They still count towards billed
+    /// duration. A panicking task is logged and skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::Context;
+    ///
+    /// fn handler(context: &Context, event_body: &str) -> String {
+    ///     let size = event_body.len();
+    ///     context.spawn_background(move || {
+    ///         // e.g. flush metrics without delaying the response
+    ///         let _ = size;
+    ///     });
+    ///     r#"{"statusCode":202}"#.to_string()
+    /// }
+    /// # assert!(handler(&Context::default(), "{}").contains("202"));
+    /// ```
+    pub fn spawn_background(&self, task: impl FnOnce() + Send + 'static) {
+        background::spawn(&self.request_id, Box::new(task));
+    }
+
+    /// Run `f` with this thread's [`ScratchArena`](crate::ScratchArena)
+    /// (feature "arena")
+    ///
+    /// The arena is reset after the invocation's response (or error) is
+    /// posted, so copy anything the response needs out of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::Context;
+    ///
+    /// fn handler(context: &Context, skus: &[&str]) -> String {
+    ///     context.with_scratch(|arena| {
+    ///         let mut body = arena.string_with_capacity(256);
+    ///         for sku in skus {
+    ///             body.push_str(arena.alloc_fmt(format_args!("{{\"sku\":\"{sku}\"}},")));
+    ///         }
+    ///         body.pop();
+    ///         format!("[{body}]")
+    ///     })
+    /// }
+    /// assert_eq!(handler(&Context::default(), &["A-1"]), r#"[{"sku":"A-1"}]"#);
+    /// ```
+    #[cfg(feature = "arena")]
+    pub fn with_scratch<R>(&self, f: impl FnOnce(&crate::ScratchArena) -> R) -> R {
+        crate::arena::with_scratch(f)
+    }
+
+    /// Number of background tasks queued for this invocation
+    #[must_use]
+    pub fn pending_background(&self) -> usize {
+        background::pending(&self.request_id)
+    }
+
+    /// Invocation deadline as a `SystemTime`
+    #[must_use]
+    pub fn deadline(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.deadline_ms)
+    }
+
+    /// Milliseconds remaining before Lambda times out this invocation
+    ///
+    /// Returns 0 once the deadline has passed (or if no deadline was sent).
+    #[must_use]
+    pub fn remaining_time_ms(&self) -> u64 {
+        self.remaining_time_ms_with(&SystemClock)
+    }
+
+    /// Milliseconds remaining before the deadline as of `clock`'s time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Context, ManualClock};
+    /// use std::time::Duration;
+    ///
+    /// let clock = ManualClock::at_millis(1_700_000_000_000);
+    /// let context = Context {
+    ///     deadline_ms: 1_700_000_003_000,
+    ///     ..Context::default()
+    /// };
+    /// assert_eq!(context.remaining_time_ms_with(&clock), 3_000);
+    /// clock.advance(Duration::from_secs(5));
+    /// assert_eq!(context.remaining_time_ms_with(&clock), 0);
+    /// ```
+    #[must_use]
+    pub fn remaining_time_ms_with(&self, clock: &dyn Clock) -> u64 {
+        self.deadline()
+            .duration_since(clock.now())
+            .map_or(0, |remaining| {
+                u64::try_from(remaining.as_millis()).unwrap_or(u64::MAX)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_headers_all_fields() {
+        let context = Context::from_headers([
+            ("Lambda-Runtime-Aws-Request-Id", "req-1"),
+            ("Lambda-Runtime-Deadline-Ms", "1700000000000"),
+            (
+                "Lambda-Runtime-Invoked-Function-Arn",
+                "arn:aws:lambda:us-east-1:123456789012:function:fn",
+            ),
+            (
+                "Lambda-Runtime-Trace-Id",
+                "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1",
+            ),
+        ]);
+
+        assert_eq!(context.request_id, "req-1");
+        assert_eq!(context.deadline_ms, 1_700_000_000_000);
+        assert_eq!(
+            context.invoked_function_arn,
+            "arn:aws:lambda:us-east-1:123456789012:function:fn"
+        );
+        assert_eq!(
+            context.trace_id.as_deref(),
+            Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1")
+        );
+        let trace = context.trace_context.unwrap();
+        assert_eq!(trace.trace_id, "5759e988bd862e3fe1be46a994272793");
+        assert_eq!(trace.parent_id, "53995c3f42cd8ad8");
+        assert!(trace.sampled);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_with_event_trace_prefers_traceparent() {
+        let context = Context::from_headers([(
+            "Lambda-Runtime-Trace-Id",
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1",
+        )]);
+
+        let unchanged = context.clone().with_event_trace(r#"{"headers":{}}"#);
+        assert_eq!(unchanged, context);
+
+        let continued = context.with_event_trace(
+            r#"{"headers":{"traceparent":"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00"}}"#,
+        );
+        let trace = continued.trace_context.unwrap();
+        assert_eq!(trace.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert!(!trace.sampled);
+    }
+
+    #[test]
+    fn test_from_headers_defaults() {
+        let context = Context::from_headers([("Content-Type", "application/json")]);
+        assert_eq!(context.request_id, "unknown");
+        assert_eq!(context.deadline_ms, 0);
+        assert!(context.invoked_function_arn.is_empty());
+        assert!(context.trace_id.is_none());
+        assert!(context.trace_context.is_none());
+        assert!(context.correlation_id.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_outbound_headers_combine_trace_and_correlation() {
+        let mut context = Context::default().with_event_trace(
+            r#"{"headers":{"traceparent":"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"}}"#,
+        );
+        assert_eq!(context.outbound_headers().len(), 2);
+
+        context.correlation_id = Some("order-7".to_string());
+        let headers = context.outbound_headers();
+        assert_eq!(headers[0].0, "traceparent");
+        assert_eq!(headers[2], ("x-correlation-id", "order-7".to_string()));
+    }
+
+    #[test]
+    fn test_from_headers_case_insensitive() {
+        let context = Context::from_headers([("LAMBDA-RUNTIME-DEADLINE-MS", "42")]);
+        assert_eq!(context.deadline_ms, 42);
+    }
+
+    #[test]
+    fn test_from_headers_invalid_deadline() {
+        let context = Context::from_headers([("Lambda-Runtime-Deadline-Ms", "soon")]);
+        assert_eq!(context.deadline_ms, 0);
+    }
+
+    #[test]
+    fn test_remaining_time_past_deadline() {
+        let context = Context {
+            deadline_ms: 1,
+            ..Context::default()
+        };
+        assert_eq!(context.remaining_time_ms(), 0);
+    }
+
+    #[test]
+    fn test_remaining_time_future_deadline() {
+        let now_ms = u64::try_from(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis(),
+        )
+        .unwrap();
+        let context = Context {
+            deadline_ms: now_ms + 60_000,
+            ..Context::default()
+        };
+        let remaining = context.remaining_time_ms();
+        assert!(remaining > 59_000 && remaining <= 60_000, "got {remaining}");
+    }
+}
@@ -0,0 +1,263 @@
+// Correlation ID Propagation
+//
+// A correlation ID ties together every log line and downstream call made on
+// behalf of one business request, across services. Callers hand it over in
+// different places depending on the trigger:
+//
+// - API Gateway / ALB:  headers."x-correlation-id"
+// - SQS:                Records[0].messageAttributes.correlationId.stringValue
+// - SNS:                Records[0].Sns.MessageAttributes.correlationId.Value
+// - EventBridge:        detail.correlationId
+//
+// Names are matched ignoring case, '-' and '_', so "X-Correlation-Id",
+// "correlation_id" and "correlationId" are all accepted. When the event
+// carries none, the Lambda request ID starts a new chain.
+//
+// The CorrelationId middleware stores the ID in Context::correlation_id and
+// as the process-wide current ID, which Logger adds to every line as
+// "correlation_id"; Context::outbound_headers forwards it to downstream
+// requests. Reading IDs from events (and the middleware) needs feature
+// "serde".
+
+#[cfg(feature = "serde")]
+use crate::context::Context;
+#[cfg(feature = "serde")]
+use crate::middleware::{Flow, Middleware};
+use std::sync::{Mutex, PoisonError};
+
+/// Header carrying the correlation ID on inbound and outbound requests
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Correlation ID of the invocation in progress (set by [`CorrelationId`])
+static CURRENT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Correlation ID of the invocation in progress, if any
+///
+/// Set by the [`CorrelationId`] middleware for the duration of the pipeline.
+#[must_use]
+pub fn current_correlation_id() -> Option<String> {
+    CURRENT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+#[cfg(feature = "serde")]
+fn set_current(correlation_id: Option<String>) {
+    *CURRENT.lock().unwrap_or_else(PoisonError::into_inner) = correlation_id;
+}
+
+/// Extract a correlation ID from an event body
+///
+/// Checks HTTP headers, then the first SQS / SNS record's message
+/// attributes, then the `EventBridge` `detail`. Returns `None` for non-JSON
+/// bodies or when no location carries a non-empty ID.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::correlation_id_from_event;
+///
+/// let api = r#"{"headers":{"X-Correlation-Id":"order-7"}}"#;
+/// assert_eq!(correlation_id_from_event(api).as_deref(), Some("order-7"));
+///
+/// let sqs = r#"{"Records":[{"messageAttributes":{"correlationId":{"stringValue":"order-8","dataType":"String"}}}]}"#;
+/// assert_eq!(correlation_id_from_event(sqs).as_deref(), Some("order-8"));
+///
+/// let eventbridge = r#"{"detail-type":"OrderPlaced","detail":{"correlation_id":"order-9"}}"#;
+/// assert_eq!(correlation_id_from_event(eventbridge).as_deref(), Some("order-9"));
+/// ```
+#[cfg(feature = "serde")]
+#[must_use]
+pub fn correlation_id_from_event(event_body: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(event_body).ok()?;
+
+    let from_headers = || find(event.get("headers")?, serde_json::Value::as_str);
+    let from_queue = || {
+        let attributes = event.pointer("/Records/0/messageAttributes")?;
+        find(attributes, |attribute| {
+            attribute.get("stringValue")?.as_str()
+        })
+    };
+    let from_topic = || {
+        let attributes = event.pointer("/Records/0/Sns/MessageAttributes")?;
+        find(attributes, |attribute| attribute.get("Value")?.as_str())
+    };
+    let from_detail = || find(event.get("detail")?, serde_json::Value::as_str);
+
+    from_headers()
+        .or_else(from_queue)
+        .or_else(from_topic)
+        .or_else(from_detail)
+        .map(str::to_string)
+}
+
+/// First non-empty value under a correlation ID key of `object`
+#[cfg(feature = "serde")]
+fn find<'a>(
+    object: &'a serde_json::Value,
+    value: impl Fn(&'a serde_json::Value) -> Option<&'a str>,
+) -> Option<&'a str> {
+    object
+        .as_object()?
+        .iter()
+        .filter(|(name, _)| is_correlation_key(name))
+        .filter_map(|(_, entry)| value(entry))
+        .map(str::trim)
+        .find(|id| !id.is_empty())
+}
+
+/// `x-correlation-id`, `correlationId`, `Correlation_ID`, ...
+#[cfg(feature = "serde")]
+fn is_correlation_key(name: &str) -> bool {
+    let normalized: String = name
+        .chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    normalized == "correlationid" || normalized == "xcorrelationid"
+}
+
+/// Middleware that resolves the invocation's correlation ID
+///
+/// `before` sets [`Context::correlation_id`] (from the event, else the
+/// request ID) and the current ID used by [`Logger`](crate::Logger);
+/// `after` echoes it as an `x-correlation-id` header on API Gateway / ALB
+/// proxy responses and clears the current ID.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, CorrelationId, Pipeline};
+///
+/// let pipeline = Pipeline::new().with(CorrelationId);
+/// let context = Context { request_id: "req-1".to_string(), ..Context::default() };
+/// let event = r#"{"headers":{"x-correlation-id":"order-7"}}"#;
+///
+/// let response = pipeline.handle(context, event, |context, _| {
+///     assert_eq!(context.correlation_id.as_deref(), Some("order-7"));
+///     r#"{"statusCode":200,"body":"ok"}"#.to_string()
+/// });
+/// let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+/// assert_eq!(value["headers"]["x-correlation-id"], "order-7");
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CorrelationId;
+
+#[cfg(feature = "serde")]
+impl Middleware for CorrelationId {
+    fn before(&self, context: &mut Context, event_body: &str) -> Flow {
+        let correlation_id =
+            correlation_id_from_event(event_body).unwrap_or_else(|| context.request_id.clone());
+        set_current(Some(correlation_id.clone()));
+        context.correlation_id = Some(correlation_id);
+        Flow::Continue
+    }
+
+    fn after(&self, context: &Context, response: &mut String) {
+        set_current(None);
+
+        let Some(correlation_id) = &context.correlation_id else {
+            return;
+        };
+        let Ok(mut value) = serde_json::from_str::<serde_json::Value>(response) else {
+            return;
+        };
+        let Some(object) = value.as_object_mut() else {
+            return;
+        };
+        if !object.contains_key("statusCode") {
+            return;
+        }
+
+        let headers = object
+            .entry("headers")
+            .or_insert_with(|| serde_json::json!({}));
+        if let Some(headers) = headers.as_object_mut() {
+            if !headers.keys().any(|name| is_correlation_key(name)) {
+                headers.insert(
+                    CORRELATION_ID_HEADER.to_string(),
+                    serde_json::Value::String(correlation_id.clone()),
+                );
+                *response = value.to_string();
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::middleware::Pipeline;
+    use serial_test::serial;
+
+    #[test]
+    fn test_correlation_id_sources() {
+        let cases = [
+            (r#"{"headers":{"x-correlation-id":" a "}}"#, Some("a")),
+            (
+                r#"{"Records":[{"messageAttributes":{"X-Correlation-Id":{"stringValue":"b"}}}]}"#,
+                Some("b"),
+            ),
+            (
+                r#"{"Records":[{"Sns":{"MessageAttributes":{"correlationId":{"Type":"String","Value":"c"}}}}]}"#,
+                Some("c"),
+            ),
+            (r#"{"detail":{"CorrelationId":"d"}}"#, Some("d")),
+            (
+                r#"{"headers":{"x-correlation-id":""},"detail":{"correlationId":"e"}}"#,
+                Some("e"),
+            ),
+            (r#"{"headers":{"x-request-id":"f"}}"#, None),
+            (r#"{"detail":{"correlationId":7}}"#, None),
+            ("not json", None),
+        ];
+        for (event, expected) in cases {
+            assert_eq!(
+                correlation_id_from_event(event).as_deref(),
+                expected,
+                "{event}"
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_middleware_falls_back_to_request_id() {
+        let context = Context {
+            request_id: "req-9".to_string(),
+            ..Context::default()
+        };
+        let response = Pipeline::new()
+            .with(CorrelationId)
+            .handle(context, "{}", |context, _| {
+                assert_eq!(current_correlation_id().as_deref(), Some("req-9"));
+                context.correlation_id.clone().unwrap()
+            });
+        assert_eq!(response, "req-9");
+        assert!(current_correlation_id().is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_middleware_keeps_existing_header() {
+        let response = Pipeline::new().with(CorrelationId).handle(
+            Context::default(),
+            r#"{"headers":{"x-correlation-id":"in"}}"#,
+            |_, _| r#"{"statusCode":200,"headers":{"X-Correlation-Id":"mine"}}"#.to_string(),
+        );
+        let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["headers"]["X-Correlation-Id"], "mine");
+        assert!(value["headers"].get(CORRELATION_ID_HEADER).is_none());
+
+        // Non-proxy responses are left untouched
+        let response =
+            Pipeline::new()
+                .with(CorrelationId)
+                .handle(Context::default(), "{}", |_, _| {
+                    r#"{"ok":true}"#.to_string()
+                });
+        assert_eq!(response, r#"{"ok":true}"#);
+    }
+}
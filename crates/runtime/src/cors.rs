@@ -0,0 +1,396 @@
+// CORS for HTTP Events
+//
+// Browsers calling an API from another origin need CORS response headers,
+// and send a preflight before non-simple requests:
+//
+//   OPTIONS /orders
+//   Origin: https://shop.example
+//   Access-Control-Request-Method: POST
+//   Access-Control-Request-Headers: content-type
+//
+// The Cors middleware answers preflights itself with a 204 (the handler is
+// not called) and adds Access-Control-Allow-Origin (plus credentials and
+// exposed headers) to every proxy response for an allowed origin.
+//
+// A specific allowed origin is echoed back with `Vary: Origin`; "*" is used
+// when any origin is allowed, except with credentials, where browsers
+// require the origin itself. Requests from other origins get no CORS
+// headers, so the browser blocks them.
+
+use crate::context::Context;
+use crate::http_request::HttpRequest;
+use crate::middleware::{Flow, Middleware};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+/// Methods allowed by default
+const DEFAULT_METHODS: &[&str] = &["GET", "HEAD", "PUT", "PATCH", "POST", "DELETE"];
+
+/// Request headers allowed by default
+const DEFAULT_HEADERS: &[&str] = &["content-type", "authorization"];
+
+/// CORS middleware for API Gateway / ALB events
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, Cors, Pipeline};
+///
+/// let pipeline = Pipeline::new().with(
+///     Cors::new()
+///         .with_origin("https://shop.example")
+///         .with_methods(&["GET", "POST"])
+///         .with_headers(&["content-type", "x-api-key"]),
+/// );
+///
+/// let preflight = r#"{"httpMethod":"OPTIONS","path":"/orders","headers":{
+///     "Origin":"https://shop.example","Access-Control-Request-Method":"POST"}}"#;
+/// let response = pipeline.handle(Context::default(), preflight, |_, _| unreachable!());
+/// let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+/// assert_eq!(value["statusCode"], 204);
+/// assert_eq!(value["headers"]["access-control-allow-methods"], "GET, POST");
+///
+/// let request = r#"{"httpMethod":"POST","path":"/orders","headers":{"Origin":"https://shop.example"}}"#;
+/// let response = pipeline.handle(Context::default(), request, |_, _| {
+///     r#"{"statusCode":201,"body":"{}"}"#.to_string()
+/// });
+/// let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+/// assert_eq!(value["headers"]["access-control-allow-origin"], "https://shop.example");
+/// assert_eq!(value["headers"]["vary"], "Origin");
+/// ```
+#[derive(Debug)]
+pub struct Cors {
+    /// Allowed origins (empty = any)
+    origins: Vec<String>,
+    methods: Vec<String>,
+    headers: Vec<String>,
+    expose_headers: Vec<String>,
+    credentials: bool,
+    max_age: Option<Duration>,
+    /// `Origin` of in-flight requests by request ID, for `after`
+    pending: Mutex<HashMap<String, String>>,
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cors {
+    /// Any origin, common methods, `content-type` and `authorization`
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            origins: Vec::new(),
+            methods: DEFAULT_METHODS.iter().map(ToString::to_string).collect(),
+            headers: DEFAULT_HEADERS.iter().map(ToString::to_string).collect(),
+            expose_headers: Vec::new(),
+            credentials: false,
+            max_age: None,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Allow `origin` (e.g. `https://shop.example`); once any origin is
+    /// added, only the added origins are allowed
+    #[must_use]
+    pub fn with_origin(mut self, origin: &str) -> Self {
+        self.origins
+            .push(origin.trim_end_matches('/').to_ascii_lowercase());
+        self
+    }
+
+    /// Allowed methods (`Access-Control-Allow-Methods`)
+    #[must_use]
+    pub fn with_methods(mut self, methods: &[&str]) -> Self {
+        self.methods = methods
+            .iter()
+            .map(|method| method.to_ascii_uppercase())
+            .collect();
+        self
+    }
+
+    /// Allowed request headers (`Access-Control-Allow-Headers`)
+    #[must_use]
+    pub fn with_headers(mut self, headers: &[&str]) -> Self {
+        self.headers = headers.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Response headers readable by scripts (`Access-Control-Expose-Headers`)
+    #[must_use]
+    pub fn with_expose_headers(mut self, headers: &[&str]) -> Self {
+        self.expose_headers = headers.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Allow cookies and credentials (`Access-Control-Allow-Credentials`)
+    #[must_use]
+    pub fn with_credentials(mut self, credentials: bool) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// How long browsers may cache a preflight (`Access-Control-Max-Age`)
+    #[must_use]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// `Access-Control-Allow-Origin` value for `origin`, if allowed
+    fn allow_origin(&self, origin: &str) -> Option<String> {
+        if self.origins.is_empty() {
+            return Some(if self.credentials { origin } else { "*" }.to_string());
+        }
+        let normalized = origin.trim_end_matches('/').to_ascii_lowercase();
+        self.origins
+            .contains(&normalized)
+            .then(|| origin.to_string())
+    }
+
+    /// Headers for an actual (non-preflight) response
+    fn response_headers(&self, origin: &str) -> Vec<(&'static str, String)> {
+        let Some(allow_origin) = self.allow_origin(origin) else {
+            return Vec::new();
+        };
+        let mut headers = Vec::new();
+        if allow_origin != "*" {
+            headers.push(("vary", "Origin".to_string()));
+        }
+        headers.push(("access-control-allow-origin", allow_origin));
+        if self.credentials {
+            headers.push(("access-control-allow-credentials", "true".to_string()));
+        }
+        if !self.expose_headers.is_empty() {
+            headers.push((
+                "access-control-expose-headers",
+                self.expose_headers.join(", "),
+            ));
+        }
+        headers
+    }
+
+    /// 204 preflight response (without CORS headers for other origins)
+    fn preflight(&self, origin: &str) -> String {
+        let mut headers = serde_json::Map::new();
+        if self.allow_origin(origin).is_some() {
+            for (name, value) in self.response_headers(origin) {
+                headers.insert(name.to_string(), Value::String(value));
+            }
+            headers.insert(
+                "access-control-allow-methods".to_string(),
+                Value::String(self.methods.join(", ")),
+            );
+            headers.insert(
+                "access-control-allow-headers".to_string(),
+                Value::String(self.headers.join(", ")),
+            );
+            if let Some(max_age) = self.max_age {
+                headers.insert(
+                    "access-control-max-age".to_string(),
+                    Value::String(max_age.as_secs().to_string()),
+                );
+            }
+        }
+        serde_json::json!({"statusCode": 204, "headers": headers, "body": ""}).to_string()
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<String, String>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Middleware for Cors {
+    fn before(&self, context: &mut Context, event_body: &str) -> Flow {
+        let Ok(request) = HttpRequest::from_json(event_body) else {
+            return Flow::Continue;
+        };
+        let Some(origin) = request.header("origin") else {
+            return Flow::Continue;
+        };
+
+        if request.method == "OPTIONS" && request.header("access-control-request-method").is_some()
+        {
+            return Flow::Respond(self.preflight(origin));
+        }
+        self.pending()
+            .insert(context.request_id.clone(), origin.to_string());
+        Flow::Continue
+    }
+
+    fn after(&self, context: &Context, response: &mut String) {
+        let Some(origin) = self.pending().remove(&context.request_id) else {
+            return;
+        };
+        let headers = self.response_headers(&origin);
+        if headers.is_empty() {
+            return;
+        }
+
+        let Ok(mut value) = serde_json::from_str::<Value>(response) else {
+            return;
+        };
+        let Some(object) = value.as_object_mut() else {
+            return;
+        };
+        if !object.contains_key("statusCode") {
+            return;
+        }
+        let Some(existing) = object
+            .entry("headers")
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut()
+        else {
+            return;
+        };
+        for (name, value) in headers {
+            let key = existing
+                .keys()
+                .find(|key| key.eq_ignore_ascii_case(name))
+                .cloned();
+            match key {
+                // Keep the handler's Vary values and add Origin to them
+                Some(key) if name == "vary" => {
+                    if let Some(Value::String(vary)) = existing.get_mut(&key) {
+                        if !vary
+                            .split(',')
+                            .any(|v| v.trim().eq_ignore_ascii_case("origin"))
+                        {
+                            vary.push_str(", Origin");
+                        }
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    existing.insert(name.to_string(), Value::String(value));
+                }
+            }
+        }
+        *response = value.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::Pipeline;
+
+    fn event(method: &str, headers: &Value) -> String {
+        serde_json::json!({"httpMethod": method, "path": "/", "headers": headers}).to_string()
+    }
+
+    fn run(cors: Cors, event: &str) -> Value {
+        let response = Pipeline::new().with(cors).handle(
+            Context {
+                request_id: "req-1".to_string(),
+                ..Context::default()
+            },
+            event,
+            |_, _| r#"{"statusCode":200,"headers":{"Vary":"Accept"},"body":"ok"}"#.to_string(),
+        );
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn test_any_origin() {
+        let response = run(
+            Cors::new(),
+            &event("GET", &serde_json::json!({"origin": "https://a.example"})),
+        );
+        assert_eq!(response["headers"]["access-control-allow-origin"], "*");
+        assert!(response["headers"].get("vary").is_none());
+
+        // Credentials require the origin itself
+        let response = run(
+            Cors::new()
+                .with_credentials(true)
+                .with_expose_headers(&["etag"]),
+            &event("GET", &serde_json::json!({"Origin": "https://a.example"})),
+        );
+        assert_eq!(
+            response["headers"]["access-control-allow-origin"],
+            "https://a.example"
+        );
+        assert_eq!(
+            response["headers"]["access-control-allow-credentials"],
+            "true"
+        );
+        assert_eq!(response["headers"]["access-control-expose-headers"], "etag");
+        // A handler's own Vary header is extended
+        assert_eq!(response["headers"]["Vary"], "Accept, Origin");
+    }
+
+    #[test]
+    fn test_disallowed_origin_gets_no_headers() {
+        let cors = || Cors::new().with_origin("https://shop.example/");
+        let response = run(
+            cors(),
+            &event(
+                "GET",
+                &serde_json::json!({"Origin": "https://evil.example"}),
+            ),
+        );
+        assert!(response["headers"]
+            .get("access-control-allow-origin")
+            .is_none());
+
+        let preflight = run(
+            cors(),
+            &event(
+                "OPTIONS",
+                &serde_json::json!({"Origin": "https://evil.example",
+                    "Access-Control-Request-Method": "DELETE"}),
+            ),
+        );
+        assert_eq!(preflight["statusCode"], 204);
+        assert_eq!(preflight["headers"], serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_preflight() {
+        let response = run(
+            Cors::new()
+                .with_origin("https://SHOP.example")
+                .with_max_age(Duration::from_hours(2)),
+            &event(
+                "OPTIONS",
+                &serde_json::json!({"Origin": "https://shop.example",
+                    "Access-Control-Request-Method": "PUT"}),
+            ),
+        );
+        assert_eq!(response["statusCode"], 204);
+        assert_eq!(
+            response["headers"]["access-control-allow-origin"],
+            "https://shop.example"
+        );
+        assert_eq!(
+            response["headers"]["access-control-allow-headers"],
+            "content-type, authorization"
+        );
+        assert_eq!(response["headers"]["access-control-max-age"], "7200");
+
+        // OPTIONS without Access-Control-Request-Method reaches the handler
+        let response = run(
+            Cors::new(),
+            &event(
+                "OPTIONS",
+                &serde_json::json!({"Origin": "https://a.example"}),
+            ),
+        );
+        assert_eq!(response["statusCode"], 200);
+    }
+
+    #[test]
+    fn test_non_http_events_untouched() {
+        let response = Pipeline::new().with(Cors::new()).handle(
+            Context::default(),
+            r#"{"Records":[]}"#,
+            |_, _| "done".to_string(),
+        );
+        assert_eq!(response, "done");
+    }
+}
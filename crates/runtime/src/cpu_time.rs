@@ -0,0 +1,141 @@
+// Per-Invocation CPU Time
+//
+// Wall-clock duration alone doesn't say whether a slow invocation was
+// computing or waiting on I/O. CPU time does: duration ≈ CPU time means
+// compute-bound (more memory, i.e. more vCPU share, helps), CPU time far
+// below duration means waiting (more memory mostly costs more).
+//
+// The user and system CPU time of the process come from /proc/self/stat
+// (fields 14 and 15, in USER_HZ clock ticks: 100 per second on every Linux
+// architecture Lambda runs), so the resolution is 10ms; no getrusage, which
+// would need libc and unsafe code. Process-wide, so helper threads such as
+// the prefetcher or a timeout guard are counted too, as Lambda bills them.
+//
+// Linux only; elsewhere nothing is sampled.
+
+use std::time::Duration;
+
+/// `/proc` clock ticks per second (`USER_HZ`)
+const TICKS_PER_SECOND: u64 = 100;
+
+/// CPU time consumed by the current process
+///
+/// Sample before and after the work and take the difference with
+/// [`CpuTime::since`].
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::CpuTime;
+///
+/// let before = CpuTime::current();
+/// let checksum: u64 = (0..100_000u64).map(|n| n * n % 7).sum();
+/// if let (Some(before), Some(after)) = (before, CpuTime::current()) {
+///     let used = after.since(before);
+///     println!("{checksum}: {:?} user, {:?} system", used.user, used.system);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTime {
+    /// Time spent running user code
+    pub user: Duration,
+    /// Time spent in the kernel on behalf of the process
+    pub system: Duration,
+}
+
+impl CpuTime {
+    /// Sample `/proc/self/stat` (`None` if unavailable or not on Linux)
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            std::fs::read_to_string("/proc/self/stat")
+                .ok()
+                .and_then(|stat| Self::parse(&stat))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    /// CPU time used since the earlier sample `earlier`
+    #[must_use]
+    pub fn since(self, earlier: Self) -> Self {
+        Self {
+            user: self.user.saturating_sub(earlier.user),
+            system: self.system.saturating_sub(earlier.system),
+        }
+    }
+
+    /// Parse the `utime` and `stime` fields of a `/proc/<pid>/stat` line
+    fn parse(stat: &str) -> Option<Self> {
+        // The command name (field 2) is parenthesized and may contain spaces
+        let (_, rest) = stat.rsplit_once(')')?;
+        // rest starts at field 3 (state): utime is field 14, stime field 15
+        let mut fields = rest.split_whitespace().skip(11);
+        let user: u64 = fields.next()?.parse().ok()?;
+        let system: u64 = fields.next()?.parse().ok()?;
+        Some(Self {
+            user: ticks(user),
+            system: ticks(system),
+        })
+    }
+}
+
+/// Duration of `count` clock ticks
+fn ticks(count: u64) -> Duration {
+    Duration::from_millis(count.saturating_mul(1000 / TICKS_PER_SECOND))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat =
+            "4242 (boot strap) S 1 4242 4242 0 -1 4194560 312 0 0 0 25 3 0 0 20 0 1 0 7 5000 300";
+        assert_eq!(
+            CpuTime::parse(stat),
+            Some(CpuTime {
+                user: Duration::from_millis(250),
+                system: Duration::from_millis(30),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_stat_rejects_truncated_line() {
+        assert_eq!(CpuTime::parse("4242 (bootstrap) S 1 4242"), None);
+        assert_eq!(CpuTime::parse("garbage"), None);
+    }
+
+    #[test]
+    fn test_since_saturates() {
+        let earlier = CpuTime {
+            user: Duration::from_millis(40),
+            system: Duration::from_millis(10),
+        };
+        let later = CpuTime {
+            user: Duration::from_millis(70),
+            system: Duration::from_millis(0),
+        };
+        assert_eq!(
+            later.since(earlier),
+            CpuTime {
+                user: Duration::from_millis(30),
+                system: Duration::ZERO,
+            }
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_current_on_linux() {
+        let before = CpuTime::current().unwrap();
+        let after = CpuTime::current().unwrap();
+        assert!(after.user >= before.user);
+        assert!(after.system >= before.system);
+    }
+}
@@ -0,0 +1,549 @@
+// Credentials Providers (feature "sigv4")
+//
+// `CredentialsProvider` is where outbound clients get signing credentials
+// (`AwsClient::with_credentials_provider`):
+//
+//   - `Credentials`: static credentials (tests, fixed keys)
+//   - `EnvCredentials`: AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY /
+//     AWS_SESSION_TOKEN, the execution role Lambda sets
+//   - `ContainerCredentials`: the container credentials endpoint
+//     (AWS_CONTAINER_CREDENTIALS_FULL_URI or _RELATIVE_URI, e.g. SnapStart
+//     and ECS), plain HTTP like the rest of the client
+//   - `AssumeRole`: an STS AssumeRole session, for cross-account handlers
+//
+// Endpoint and STS credentials are cached and reused until
+// CREDENTIALS_REFRESH_MARGIN before they expire, so keep the provider in a
+// static (or the handler's state) to share them across warm invocations; a
+// cold start or an expiring session fetches new ones.
+//
+// STS AssumeRole:
+//
+//   POST https://sts.<region>.amazonaws.com/
+//   Action=AssumeRole&Version=2011-06-15&RoleArn=...&RoleSessionName=...
+//
+//   <AssumeRoleResponse><AssumeRoleResult><Credentials>
+//     <AccessKeyId>ASIA...</AccessKeyId><SecretAccessKey>...</SecretAccessKey>
+//     <SessionToken>...</SessionToken><Expiration>2024-05-01T12:00:00Z</Expiration>
+//   </Credentials></AssumeRoleResult></AssumeRoleResponse>
+//
+// The STS call is signed by its own client, by default with the function's
+// execution role.
+//
+// Container endpoint:
+//
+//   GET http://169.254.170.2<AWS_CONTAINER_CREDENTIALS_RELATIVE_URI>
+//   Authorization: <AWS_CONTAINER_AUTHORIZATION_TOKEN>
+//
+//   {"AccessKeyId":"ASIA...","SecretAccessKey":"...","Token":"...",
+//    "Expiration":"2024-05-01T12:00:00Z"}
+
+use crate::aws_client::{xml_element, AwsClient, AwsError};
+use crate::deadline;
+use crate::failure_record::civil_from_days;
+use crate::query_string::encode;
+use crate::sigv4::{AwsRequest, Credentials, SigV4Error};
+use ruchy_lambda_http_core::{parse_any_response_ref, request_into_on};
+use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Refresh temporary credentials this long before they expire
+pub const CREDENTIALS_REFRESH_MARGIN: Duration = Duration::from_mins(5);
+
+/// Session length requested from STS by default
+pub const DEFAULT_SESSION_DURATION: Duration = Duration::from_hours(1);
+
+/// Host of the container credentials endpoint for relative URIs
+pub const CONTAINER_CREDENTIALS_HOST: &str = "169.254.170.2:80";
+
+/// Maximum accepted container credentials response size in bytes
+const MAX_CONTAINER_RESPONSE_SIZE: usize = 64 * 1024;
+
+/// Source of the credentials requests are signed with
+pub trait CredentialsProvider: Send + Sync + fmt::Debug {
+    /// Current credentials
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if no credentials are available.
+    fn credentials(&self) -> Result<Credentials, AwsError>;
+}
+
+/// Static credentials
+impl CredentialsProvider for Credentials {
+    fn credentials(&self) -> Result<Credentials, AwsError> {
+        Ok(self.clone())
+    }
+}
+
+/// Credentials from the environment, read on every call
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{CredentialsProvider, EnvCredentials};
+///
+/// std::env::set_var("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE");
+/// std::env::set_var("AWS_SECRET_ACCESS_KEY", "secret");
+/// assert_eq!(EnvCredentials.credentials().unwrap().access_key_id, "AKIDEXAMPLE");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvCredentials;
+
+impl CredentialsProvider for EnvCredentials {
+    fn credentials(&self) -> Result<Credentials, AwsError> {
+        Credentials::from_env().ok_or_else(|| {
+            AwsError::Credentials(
+                "AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY are not set".to_string(),
+            )
+        })
+    }
+}
+
+/// Credentials from the container credentials endpoint, cached until they
+/// are about to expire
+#[derive(Debug)]
+pub struct ContainerCredentials {
+    endpoint: String,
+    path: String,
+    authorization: Option<String>,
+    cached: Mutex<Option<Credentials>>,
+}
+
+impl ContainerCredentials {
+    /// Fetch from `path` on `endpoint` (`host:port`)
+    #[must_use]
+    pub fn new(endpoint: &str, path: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            path: path.to_string(),
+            authorization: None,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// The endpoint from `AWS_CONTAINER_CREDENTIALS_RELATIVE_URI` or an
+    /// `http://` `AWS_CONTAINER_CREDENTIALS_FULL_URI`, authorized with
+    /// `AWS_CONTAINER_AUTHORIZATION_TOKEN` (or the file named by
+    /// `AWS_CONTAINER_AUTHORIZATION_TOKEN_FILE`)
+    ///
+    /// Returns `None` if neither URI is set (or the full URI is not HTTP).
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+        let provider = if let Some(path) = var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") {
+            Self::new(CONTAINER_CREDENTIALS_HOST, &path)
+        } else {
+            let uri = var("AWS_CONTAINER_CREDENTIALS_FULL_URI")?;
+            let rest = uri.strip_prefix("http://")?;
+            let (authority, path) = rest.find('/').map_or((rest, "/"), |i| rest.split_at(i));
+            let endpoint = if authority.contains(':') {
+                authority.to_string()
+            } else {
+                format!("{authority}:80")
+            };
+            Self::new(&endpoint, path)
+        };
+        let token = var("AWS_CONTAINER_AUTHORIZATION_TOKEN").or_else(|| {
+            let file = var("AWS_CONTAINER_AUTHORIZATION_TOKEN_FILE")?;
+            Some(fs::read_to_string(file).ok()?.trim().to_string())
+        });
+        Some(match token {
+            Some(token) => provider.with_authorization(&token),
+            None => provider,
+        })
+    }
+
+    /// Send `token` as the `Authorization` header
+    #[must_use]
+    pub fn with_authorization(mut self, token: &str) -> Self {
+        self.authorization = Some(token.to_string());
+        self
+    }
+
+    fn fetch(&self) -> Result<Credentials, AwsError> {
+        let mut headers = vec![("accept", "application/json")];
+        if let Some(token) = &self.authorization {
+            headers.push(("authorization", token));
+        }
+        let stream =
+            deadline::connect(&self.endpoint).map_err(|e| AwsError::Transport(e.to_string()))?;
+        let mut buffer = Vec::new();
+        request_into_on(
+            stream,
+            &self.endpoint,
+            "GET",
+            &self.path,
+            &headers,
+            &[],
+            MAX_CONTAINER_RESPONSE_SIZE,
+            &mut buffer,
+        )
+        .map_err(|e| AwsError::Transport(e.to_string()))?;
+
+        let response =
+            parse_any_response_ref(&buffer).map_err(|e| AwsError::Transport(e.to_string()))?;
+        let status = response.status_code().unwrap_or_default();
+        if !(200..300).contains(&status) {
+            return Err(AwsError::Credentials(format!(
+                "container credentials endpoint returned {status}"
+            )));
+        }
+        let json: Value = serde_json::from_slice(response.body)
+            .map_err(|e| AwsError::InvalidResponse(format!("container credentials: {e}")))?;
+        parse_container_credentials(&json)
+    }
+}
+
+impl CredentialsProvider for ContainerCredentials {
+    fn credentials(&self) -> Result<Credentials, AwsError> {
+        cached_or(&self.cached, || self.fetch())
+    }
+}
+
+/// Credentials of a container credentials endpoint response
+fn parse_container_credentials(json: &Value) -> Result<Credentials, AwsError> {
+    let field = |name: &str| {
+        json[name].as_str().ok_or_else(|| {
+            AwsError::InvalidResponse(format!("container credentials without {name}"))
+        })
+    };
+    let mut credentials = Credentials::new(field("AccessKeyId")?, field("SecretAccessKey")?);
+    if let Some(token) = json["Token"].as_str() {
+        credentials = credentials.with_session_token(token);
+    }
+    if let Some(expiration) = json["Expiration"].as_str() {
+        let expiration = parse_timestamp(expiration).ok_or_else(|| {
+            AwsError::InvalidResponse(format!("Invalid credentials expiration {expiration}"))
+        })?;
+        credentials = credentials.with_expiration(expiration);
+    }
+    Ok(credentials)
+}
+
+/// `cache` unless it expires within the refresh margin, otherwise `fetch`ed
+/// credentials (then cached)
+fn cached_or(
+    cache: &Mutex<Option<Credentials>>,
+    fetch: impl FnOnce() -> Result<Credentials, AwsError>,
+) -> Result<Credentials, AwsError> {
+    let mut cached = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(credentials) = cached.as_ref() {
+        if !credentials.expires_within(CREDENTIALS_REFRESH_MARGIN) {
+            return Ok(credentials.clone());
+        }
+    }
+    let credentials = fetch()?;
+    *cached = Some(credentials.clone());
+    Ok(credentials)
+}
+
+/// Credentials of an assumed role, cached until they are about to expire
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{
+///     AssumeRole, AwsClient, AwsResponse, Credentials, CredentialsProvider, Signer,
+/// };
+///
+/// let sts = AwsClient::new(Signer::new(Credentials::new("AKID", "secret"), "us-east-1", "sts"))
+///     .with_transport(|_| {
+///         Ok(AwsResponse::new(200, "<AssumeRoleResponse><AssumeRoleResult><Credentials>\
+///             <AccessKeyId>ASIAEXAMPLE</AccessKeyId><SecretAccessKey>s</SecretAccessKey>\
+///             <SessionToken>t</SessionToken><Expiration>2100-01-01T00:00:00Z</Expiration>\
+///             </Credentials></AssumeRoleResult></AssumeRoleResponse>"))
+///     });
+///
+/// let role = AssumeRole::new(sts, "arn:aws:iam::210987654321:role/reader", "orders-fn");
+/// let credentials = role.credentials().unwrap();
+/// assert_eq!(credentials.access_key_id, "ASIAEXAMPLE");
+/// assert_eq!(credentials.session_token.as_deref(), Some("t"));
+/// ```
+#[derive(Debug)]
+pub struct AssumeRole {
+    sts: AwsClient,
+    role_arn: String,
+    session_name: String,
+    duration: Duration,
+    external_id: Option<String>,
+    cached: Mutex<Option<Credentials>>,
+}
+
+impl AssumeRole {
+    /// Assume `role_arn` through `sts` (a client signing for service `sts`)
+    #[must_use]
+    pub fn new(sts: AwsClient, role_arn: &str, session_name: &str) -> Self {
+        Self {
+            sts,
+            role_arn: role_arn.to_string(),
+            session_name: session_name.to_string(),
+            duration: DEFAULT_SESSION_DURATION,
+            external_id: None,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Assume `role_arn` with the function's own credentials and region
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigV4Error`] if the credentials or region are not set.
+    pub fn from_env(role_arn: &str, session_name: &str) -> Result<Self, SigV4Error> {
+        Ok(Self::new(
+            AwsClient::from_env("sts")?,
+            role_arn,
+            session_name,
+        ))
+    }
+
+    /// Request sessions of `duration` (15 minutes up to the role's maximum)
+    #[must_use]
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Pass the external ID the role's trust policy requires
+    #[must_use]
+    pub fn with_external_id(mut self, external_id: &str) -> Self {
+        self.external_id = Some(external_id.to_string());
+        self
+    }
+
+    /// Drop the cached credentials (e.g. after an access denied)
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    fn assume(&self) -> Result<Credentials, AwsError> {
+        let mut body = String::from("Action=AssumeRole&Version=2011-06-15&RoleArn=");
+        encode(&self.role_arn, &mut body);
+        body.push_str("&RoleSessionName=");
+        encode(&self.session_name, &mut body);
+        body.push_str("&DurationSeconds=");
+        body.push_str(&self.duration.as_secs().to_string());
+        if let Some(external_id) = &self.external_id {
+            body.push_str("&ExternalId=");
+            encode(external_id, &mut body);
+        }
+
+        let host = format!("sts.{}.amazonaws.com", self.sts.signer().region());
+        let request = AwsRequest::new("POST", &host, "/")
+            .with_header("content-type", "application/x-www-form-urlencoded")
+            .with_body(body);
+        let response = self.sts.send(request)?;
+        parse_assume_role(&String::from_utf8_lossy(&response.body))
+    }
+}
+
+impl CredentialsProvider for AssumeRole {
+    /// The role's credentials, from the cache unless they expire within
+    /// [`CREDENTIALS_REFRESH_MARGIN`]
+    fn credentials(&self) -> Result<Credentials, AwsError> {
+        cached_or(&self.cached, || self.assume())
+    }
+}
+
+/// Credentials of an `AssumeRole` response
+fn parse_assume_role(xml: &str) -> Result<Credentials, AwsError> {
+    let field = |name: &str| {
+        xml_element(xml, name)
+            .ok_or_else(|| AwsError::InvalidResponse(format!("AssumeRole response without {name}")))
+    };
+    let expiration = field("Expiration")?;
+    let expiration = parse_timestamp(&expiration).ok_or_else(|| {
+        AwsError::InvalidResponse(format!("Invalid AssumeRole expiration {expiration}"))
+    })?;
+    Ok(
+        Credentials::new(&field("AccessKeyId")?, &field("SecretAccessKey")?)
+            .with_session_token(&field("SessionToken")?)
+            .with_expiration(expiration),
+    )
+}
+
+/// `YYYY-MM-DDTHH:MM:SS[.fff]Z` as a time
+fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let (date, time) = text.strip_suffix('Z')?.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<u64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let time = time.split('.').next()?;
+    let mut time = time.splitn(3, ':').map(str::parse::<u64>);
+    let (hour, minute, second) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    if civil_from_days(days) != (year, month, day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hour * 3600 + minute * 60 + second))
+}
+
+/// Days since 1970-01-01 of a Gregorian date (inverse of `civil_from_days`)
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year % 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws_client::AwsResponse;
+    use crate::sigv4::Signer;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn response(expiration: &str) -> String {
+        format!(
+            "<AssumeRoleResponse xmlns=\"https://sts.amazonaws.com/doc/2011-06-15/\">\
+             <AssumeRoleResult><Credentials><AccessKeyId>ASIA1</AccessKeyId>\
+             <SecretAccessKey>secret</SecretAccessKey><SessionToken>token</SessionToken>\
+             <Expiration>{expiration}</Expiration></Credentials></AssumeRoleResult>\
+             </AssumeRoleResponse>"
+        )
+    }
+
+    fn role(expiration: &'static str) -> (AssumeRole, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let sts = AwsClient::new(Signer::new(
+            Credentials::new("AKID", "secret"),
+            "eu-west-1",
+            "sts",
+        ))
+        .with_transport(move |request| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(request.host, "sts.eu-west-1.amazonaws.com");
+            assert_eq!(
+                String::from_utf8_lossy(&request.body),
+                "Action=AssumeRole&Version=2011-06-15\
+                 &RoleArn=arn%3Aaws%3Aiam%3A%3A210987654321%3Arole%2Freader\
+                 &RoleSessionName=orders&DurationSeconds=900&ExternalId=ext%201"
+            );
+            Ok(AwsResponse::new(200, response(expiration)))
+        });
+        let role = AssumeRole::new(sts, "arn:aws:iam::210987654321:role/reader", "orders")
+            .with_duration(Duration::from_mins(15))
+            .with_external_id("ext 1");
+        (role, calls)
+    }
+
+    #[test]
+    fn test_credentials_cached_until_expiry() {
+        let (role, calls) = role("2100-01-01T00:00:00Z");
+        let first = role.credentials().unwrap();
+        let second = role.credentials().unwrap();
+        assert_eq!(first, second);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        role.invalidate();
+        role.credentials().unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_expiring_credentials_refreshed() {
+        let (role, calls) = role("2011-07-15T23:28:33.359Z");
+        let credentials = role.credentials().unwrap();
+        assert!(credentials.expires_within(Duration::ZERO));
+        role.credentials().unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(
+            parse_timestamp("2011-07-15T23:28:33.359Z"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_310_772_513))
+        );
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_timestamp("2024-02-29T00:00:00Z"),
+            Some(UNIX_EPOCH + Duration::from_hours(474_768))
+        );
+        assert_eq!(parse_timestamp("2023-02-29T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("2024-01-01T24:00:00Z"), None);
+        assert_eq!(parse_timestamp("2024-01-01 00:00:00"), None);
+    }
+
+    #[test]
+    fn test_missing_credentials_in_response() {
+        let err = parse_assume_role("<AssumeRoleResponse/>").unwrap_err();
+        assert!(matches!(err, AwsError::InvalidResponse(msg) if msg.contains("Expiration")));
+    }
+
+    #[test]
+    fn test_static_credentials() {
+        let provider: Arc<dyn CredentialsProvider> =
+            Arc::new(Credentials::new("AKID", "secret").with_session_token("t"));
+        let credentials = provider.credentials().unwrap();
+        assert_eq!(credentials.access_key_id, "AKID");
+        assert_eq!(credentials.session_token.as_deref(), Some("t"));
+    }
+
+    #[test]
+    fn test_container_credentials_fetched_and_cached() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 4096];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut chunk).unwrap();
+                request.extend_from_slice(&chunk[..n]);
+            }
+            let body = r#"{"AccessKeyId":"ASIA2","SecretAccessKey":"s","Token":"t","Expiration":"2100-01-01T00:00:00Z"}"#;
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            String::from_utf8(request).unwrap()
+        });
+
+        let provider = ContainerCredentials::new(&addr, "/v2/credentials/abc")
+            .with_authorization("Bearer token");
+        let credentials = provider.credentials().unwrap();
+        assert_eq!(credentials.access_key_id, "ASIA2");
+        assert_eq!(credentials.session_token.as_deref(), Some("t"));
+        assert!(!credentials.expires_within(CREDENTIALS_REFRESH_MARGIN));
+        // Served from the cache: the server accepts a single connection
+        assert_eq!(provider.credentials().unwrap(), credentials);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /v2/credentials/abc HTTP/1.1\r\n"));
+        assert!(request.contains("authorization: Bearer token\r\n"));
+    }
+
+    #[test]
+    fn test_container_credentials_errors() {
+        let err =
+            parse_container_credentials(&serde_json::json!({"AccessKeyId": "A"})).unwrap_err();
+        assert!(matches!(err, AwsError::InvalidResponse(msg) if msg.contains("SecretAccessKey")));
+
+        let err = parse_container_credentials(&serde_json::json!({
+            "AccessKeyId": "A", "SecretAccessKey": "s", "Expiration": "soon"
+        }))
+        .unwrap_err();
+        assert!(matches!(err, AwsError::InvalidResponse(_)));
+
+        let provider = ContainerCredentials::new("127.0.0.1:1", "/");
+        assert!(matches!(
+            provider.credentials(),
+            Err(AwsError::Transport(_))
+        ));
+    }
+}
@@ -0,0 +1,167 @@
+// Deadline-Aware Outbound I/O
+//
+// A slow downstream must not run the function into the Lambda timeout: once
+// Lambda kills the sandbox the caller only sees "Task timed out", and the
+// error the handler would have reported is lost.
+//
+// `Runtime::next_invocation` / `next_invocation_into` and
+// `Prefetcher::next_invocation` record the invocation's deadline for the
+// calling thread. Outbound I/O (`AwsClient`, the container credentials
+// endpoint) then bounds connecting and every read and write by the time left
+// minus DEADLINE_RESERVE, which is kept back for posting an error response,
+// and retries stop at the deadline. A call that runs out of time fails with
+// a TimedOut I/O error the handler can turn into a proper error response.
+//
+// The deadline is per thread (worker threads each handle their own
+// invocation); handlers that hand outbound calls to other threads pass it
+// on with `set_invocation_deadline`. The Runtime API `/next` long poll is
+// never bounded.
+
+use crate::clock::{Clock, SystemClock};
+use std::cell::Cell;
+#[cfg(feature = "sigv4")]
+use std::io;
+#[cfg(feature = "sigv4")]
+use std::net::TcpStream;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time kept back from outbound I/O for posting the invocation's response
+pub const DEADLINE_RESERVE: Duration = Duration::from_millis(200);
+
+thread_local! {
+    /// Deadline of the invocation this thread is handling
+    static DEADLINE: Cell<Option<SystemTime>> = const { Cell::new(None) };
+}
+
+/// Deadline of the invocation the current thread is handling, if any
+#[must_use]
+pub fn invocation_deadline() -> Option<SystemTime> {
+    DEADLINE.with(Cell::get)
+}
+
+/// Set (or clear) the invocation deadline of the current thread
+///
+/// The runtime sets it when it hands out an invocation; call this on other
+/// threads that make outbound calls for the invocation.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{invocation_deadline, set_invocation_deadline};
+/// use std::time::{Duration, SystemTime};
+///
+/// let deadline = SystemTime::now() + Duration::from_secs(3);
+/// std::thread::spawn(move || {
+///     set_invocation_deadline(Some(deadline));
+///     assert_eq!(invocation_deadline(), Some(deadline));
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+pub fn set_invocation_deadline(deadline: Option<SystemTime>) {
+    DEADLINE.with(|current| current.set(deadline));
+}
+
+/// Time outbound I/O may still block for: the time left before the
+/// invocation deadline minus [`DEADLINE_RESERVE`] (zero once used up), or
+/// `None` without a deadline
+#[must_use]
+pub fn io_timeout() -> Option<Duration> {
+    io_timeout_with(&SystemClock)
+}
+
+/// [`io_timeout`] as of `clock`'s time
+#[must_use]
+pub fn io_timeout_with(clock: &dyn Clock) -> Option<Duration> {
+    invocation_deadline().map(|deadline| {
+        deadline
+            .duration_since(clock.now())
+            .unwrap_or_default()
+            .saturating_sub(DEADLINE_RESERVE)
+    })
+}
+
+/// Deadline of a `Lambda-Runtime-Deadline-Ms` value (0 = none)
+pub(crate) fn from_millis(deadline_ms: u64) -> Option<SystemTime> {
+    (deadline_ms > 0).then(|| UNIX_EPOCH + Duration::from_millis(deadline_ms))
+}
+
+/// Connect to `endpoint` for an outbound call, bounded by [`io_timeout`]
+#[cfg(feature = "sigv4")]
+pub(crate) fn connect(endpoint: &str) -> io::Result<TcpStream> {
+    match io_timeout() {
+        Some(timeout) => ruchy_lambda_http_core::connect_with_timeout(endpoint, timeout),
+        None => TcpStream::connect(endpoint),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_deadline_is_per_thread() {
+        set_invocation_deadline(from_millis(1_700_000_000_000));
+        assert!(invocation_deadline().is_some());
+        thread::spawn(|| assert_eq!(invocation_deadline(), None))
+            .join()
+            .unwrap();
+
+        set_invocation_deadline(from_millis(0));
+        assert_eq!(invocation_deadline(), None);
+        assert_eq!(io_timeout(), None);
+    }
+
+    #[test]
+    fn test_io_timeout_keeps_reserve() {
+        set_invocation_deadline(Some(SystemTime::now() + Duration::from_secs(1)));
+        let timeout = io_timeout().unwrap();
+        assert!(timeout + DEADLINE_RESERVE <= Duration::from_secs(1));
+        assert!(timeout > Duration::from_millis(500));
+
+        set_invocation_deadline(Some(SystemTime::now() + DEADLINE_RESERVE / 2));
+        assert_eq!(io_timeout(), Some(Duration::ZERO));
+        set_invocation_deadline(None);
+    }
+
+    #[test]
+    fn test_io_timeout_with_clock() {
+        let clock = crate::clock::ManualClock::at_millis(1_700_000_000_000);
+        set_invocation_deadline(from_millis(1_700_000_001_000));
+        assert_eq!(io_timeout_with(&clock), Some(Duration::from_millis(800)));
+        clock.advance(Duration::from_millis(900));
+        assert_eq!(io_timeout_with(&clock), Some(Duration::ZERO));
+        set_invocation_deadline(None);
+    }
+
+    #[test]
+    #[cfg(feature = "sigv4")]
+    fn test_connect_bounded_by_deadline() {
+        use std::io::Read;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_millis(500));
+            drop(stream);
+        });
+
+        set_invocation_deadline(Some(
+            SystemTime::now() + DEADLINE_RESERVE + Duration::from_millis(50),
+        ));
+        let mut stream = connect(&addr).unwrap();
+        let err = stream.read(&mut [0u8; 16]).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+        ));
+
+        set_invocation_deadline(Some(SystemTime::now()));
+        assert_eq!(connect(&addr).unwrap_err().kind(), io::ErrorKind::TimedOut);
+        set_invocation_deadline(None);
+        server.join().unwrap();
+    }
+}
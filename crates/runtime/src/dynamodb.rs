@@ -0,0 +1,398 @@
+// Minimal DynamoDB Client (feature "dynamodb")
+//
+// The three operations most handlers need, over the signed outbound client
+// (see `aws_client`) and DynamoDB's JSON protocol:
+//
+//   POST / HTTP/1.1
+//   X-Amz-Target: DynamoDB_20120810.GetItem
+//   Content-Type: application/x-amz-json-1.0
+//
+//   {"TableName":"orders","Key":{"Id":{"N":"101"}}}
+//
+// Items use the same `AttributeValue` as DynamoDB Streams events, so a
+// stream handler can feed images straight back into the API. Everything
+// else (UpdateItem, transactions, batch calls) is reachable through
+// `AwsClient::call_json`.
+
+use crate::aws_client::{AwsClient, AwsError};
+use crate::dynamodb_event::{AttributeValue, DynamoDbItem};
+use crate::sigv4::SigV4Error;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// `DynamoDB` JSON protocol content type
+const CONTENT_TYPE: &str = "application/x-amz-json-1.0";
+
+/// Target prefix of the `DynamoDB` API version
+const TARGET_PREFIX: &str = "DynamoDB_20120810";
+
+/// `GetItem`, `PutItem` and Query against `DynamoDB`
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{
+///     AttributeValue, AwsClient, AwsResponse, Credentials, DynamoDbClient, DynamoDbItem, Signer,
+/// };
+///
+/// let signer = Signer::new(Credentials::new("AKID", "secret"), "us-east-1", "dynamodb");
+/// let client = DynamoDbClient::new(AwsClient::new(signer).with_transport(|request| {
+///     assert_eq!(request.header("x-amz-target"), Some("DynamoDB_20120810.GetItem"));
+///     Ok(AwsResponse::new(200, r#"{"Item":{"Id":{"N":"101"},"Status":{"S":"shipped"}}}"#))
+/// }));
+///
+/// let key = DynamoDbItem::from([("Id".to_string(), AttributeValue::from(101))]);
+/// let item = client.get_item("orders", &key).unwrap().unwrap();
+/// assert_eq!(item["Status"].as_s(), Some("shipped"));
+/// ```
+#[derive(Debug)]
+pub struct DynamoDbClient {
+    client: AwsClient,
+    host: String,
+}
+
+impl DynamoDbClient {
+    /// Client sending through `client` (signing for service `dynamodb`)
+    #[must_use]
+    pub fn new(client: AwsClient) -> Self {
+        let host = format!("dynamodb.{}.amazonaws.com", client.signer().region());
+        Self { client, host }
+    }
+
+    /// Client with the function's credentials and region
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigV4Error`] if the credentials or region are not set.
+    pub fn from_env() -> Result<Self, SigV4Error> {
+        Ok(Self::new(AwsClient::from_env("dynamodb")?))
+    }
+
+    /// The item with primary key `key`, if it exists (eventually consistent)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails.
+    pub fn get_item(
+        &self,
+        table: &str,
+        key: &DynamoDbItem,
+    ) -> Result<Option<DynamoDbItem>, AwsError> {
+        let output = self.call("GetItem", &json!({"TableName": table, "Key": key}))?;
+        match output.get("Item") {
+            Some(item) => parse(item.clone(), "GetItem").map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Create or replace an item
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails.
+    pub fn put_item(&self, table: &str, item: &DynamoDbItem) -> Result<(), AwsError> {
+        self.call("PutItem", &json!({"TableName": table, "Item": item}))
+            .map(drop)
+    }
+
+    /// Create an item unless `condition` fails for the stored item
+    ///
+    /// A failed condition is [`AwsError::Service`] with code
+    /// `ConditionalCheckFailedException`, e.g. for
+    /// `attribute_not_exists(Id)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails or the condition does not hold.
+    pub fn put_item_if(
+        &self,
+        table: &str,
+        item: &DynamoDbItem,
+        condition: &str,
+        values: &DynamoDbItem,
+    ) -> Result<(), AwsError> {
+        let mut input = json!({
+            "TableName": table,
+            "Item": item,
+            "ConditionExpression": condition,
+        });
+        if !values.is_empty() {
+            input["ExpressionAttributeValues"] = json!(values);
+        }
+        self.call("PutItem", &input).map(drop)
+    }
+
+    /// One page of query results; continue with
+    /// [`Query::with_start_key`] while `last_evaluated_key` is set
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails.
+    pub fn query(&self, query: &Query) -> Result<QueryOutput, AwsError> {
+        let output = self.call("Query", &query.to_json())?;
+        parse(output, "Query")
+    }
+
+    fn call(&self, operation: &str, input: &Value) -> Result<Value, AwsError> {
+        self.client.call_json(
+            &self.host,
+            CONTENT_TYPE,
+            &format!("{TARGET_PREFIX}.{operation}"),
+            input,
+        )
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(value: Value, operation: &str) -> Result<T, AwsError> {
+    serde_json::from_value(value)
+        .map_err(|e| AwsError::InvalidResponse(format!("{operation}: {e}")))
+}
+
+/// Query input
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Query;
+///
+/// let query = Query::new("orders", "CustomerId = :c AND CreatedAt > :t")
+///     .with_value(":c", "cust-7")
+///     .with_value(":t", 1_700_000_000)
+///     .with_filter("#s <> :cancelled")
+///     .with_name("#s", "Status")
+///     .with_value(":cancelled", "cancelled")
+///     .with_limit(25)
+///     .with_descending();
+/// let input = query.to_json();
+/// assert_eq!(input["ExpressionAttributeValues"][":t"], serde_json::json!({"N": "1700000000"}));
+/// assert_eq!(input["ScanIndexForward"], false);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    table: String,
+    index: Option<String>,
+    key_condition: String,
+    filter: Option<String>,
+    projection: Option<String>,
+    names: HashMap<String, String>,
+    values: DynamoDbItem,
+    limit: Option<u32>,
+    descending: bool,
+    consistent_read: bool,
+    start_key: Option<DynamoDbItem>,
+}
+
+impl Query {
+    /// Query `table` for items matching `key_condition`
+    #[must_use]
+    pub fn new(table: &str, key_condition: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            key_condition: key_condition.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Query a secondary index instead of the table
+    #[must_use]
+    pub fn with_index(mut self, index: &str) -> Self {
+        self.index = Some(index.to_string());
+        self
+    }
+
+    /// Filter matched items after they are read
+    #[must_use]
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filter = Some(filter.to_string());
+        self
+    }
+
+    /// Return only these attributes
+    #[must_use]
+    pub fn with_projection(mut self, projection: &str) -> Self {
+        self.projection = Some(projection.to_string());
+        self
+    }
+
+    /// Bind an expression attribute name (`#name`)
+    #[must_use]
+    pub fn with_name(mut self, placeholder: &str, name: &str) -> Self {
+        self.names.insert(placeholder.to_string(), name.to_string());
+        self
+    }
+
+    /// Bind an expression attribute value (`:value`)
+    #[must_use]
+    pub fn with_value(mut self, placeholder: &str, value: impl Into<AttributeValue>) -> Self {
+        self.values.insert(placeholder.to_string(), value.into());
+        self
+    }
+
+    /// Read at most `limit` items
+    #[must_use]
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Return items in descending sort key order
+    #[must_use]
+    pub fn with_descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// Use strongly consistent reads (tables and local indexes only)
+    #[must_use]
+    pub fn with_consistent_read(mut self) -> Self {
+        self.consistent_read = true;
+        self
+    }
+
+    /// Continue after the `last_evaluated_key` of the previous page
+    #[must_use]
+    pub fn with_start_key(mut self, start_key: DynamoDbItem) -> Self {
+        self.start_key = Some(start_key);
+        self
+    }
+
+    /// Query request body
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let mut input = Map::new();
+        input.insert("TableName".to_string(), json!(self.table));
+        input.insert(
+            "KeyConditionExpression".to_string(),
+            json!(self.key_condition),
+        );
+        if let Some(index) = &self.index {
+            input.insert("IndexName".to_string(), json!(index));
+        }
+        if let Some(filter) = &self.filter {
+            input.insert("FilterExpression".to_string(), json!(filter));
+        }
+        if let Some(projection) = &self.projection {
+            input.insert("ProjectionExpression".to_string(), json!(projection));
+        }
+        if !self.names.is_empty() {
+            input.insert("ExpressionAttributeNames".to_string(), json!(self.names));
+        }
+        if !self.values.is_empty() {
+            input.insert("ExpressionAttributeValues".to_string(), json!(self.values));
+        }
+        if let Some(limit) = self.limit {
+            input.insert("Limit".to_string(), json!(limit));
+        }
+        if self.descending {
+            input.insert("ScanIndexForward".to_string(), json!(false));
+        }
+        if self.consistent_read {
+            input.insert("ConsistentRead".to_string(), json!(true));
+        }
+        if let Some(start_key) = &self.start_key {
+            input.insert("ExclusiveStartKey".to_string(), json!(start_key));
+        }
+        Value::Object(input)
+    }
+}
+
+/// One page of query results
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct QueryOutput {
+    /// Matching items
+    #[serde(default)]
+    pub items: Vec<DynamoDbItem>,
+    /// Number of items returned
+    #[serde(default)]
+    pub count: u64,
+    /// Number of items read before the filter
+    #[serde(default)]
+    pub scanned_count: u64,
+    /// Key to continue from, if there are more pages
+    #[serde(default)]
+    pub last_evaluated_key: Option<DynamoDbItem>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws_client::AwsResponse;
+    use crate::sigv4::{Credentials, Signer};
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    /// Client answering `response` and recording request bodies
+    fn client(status: u16, response: &'static str) -> (DynamoDbClient, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        let signer = Signer::new(Credentials::new("AKID", "secret"), "eu-west-1", "dynamodb");
+        let client = AwsClient::new(signer).with_transport(move |request| {
+            assert_eq!(request.host, "dynamodb.eu-west-1.amazonaws.com");
+            assert_eq!(request.header("content-type"), Some(CONTENT_TYPE));
+            let mut body: Value = serde_json::from_slice(&request.body).unwrap();
+            body["_target"] = json!(request.header("x-amz-target"));
+            recorded
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(body);
+            Ok(AwsResponse::new(status, response))
+        });
+        (DynamoDbClient::new(client), requests)
+    }
+
+    fn key(id: &str) -> DynamoDbItem {
+        DynamoDbItem::from([("Id".to_string(), AttributeValue::from(id))])
+    }
+
+    #[test]
+    fn test_get_item_missing() {
+        let (client, requests) = client(200, "{}");
+        assert_eq!(client.get_item("orders", &key("a")).unwrap(), None);
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            json!({"TableName": "orders", "Key": {"Id": {"S": "a"}}, "_target": "DynamoDB_20120810.GetItem"})
+        );
+    }
+
+    #[test]
+    fn test_put_item_if_condition_failed() {
+        let (client, requests) = client(
+            400,
+            r#"{"__type":"com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException","message":"The conditional request failed"}"#,
+        );
+        let err = client
+            .put_item_if(
+                "orders",
+                &key("a"),
+                "attribute_not_exists(Id)",
+                &DynamoDbItem::new(),
+            )
+            .unwrap_err();
+        assert_eq!(err.code(), Some("ConditionalCheckFailedException"));
+
+        let request = &requests.lock().unwrap()[0];
+        assert_eq!(request["ConditionExpression"], "attribute_not_exists(Id)");
+        assert!(request.get("ExpressionAttributeValues").is_none());
+        assert_eq!(request["_target"], "DynamoDB_20120810.PutItem");
+    }
+
+    #[test]
+    fn test_query_pages() {
+        let (client, requests) = client(
+            200,
+            r#"{"Count":1,"ScannedCount":2,"Items":[{"Id":{"S":"a"}}],"LastEvaluatedKey":{"Id":{"S":"a"}}}"#,
+        );
+        let query = Query::new("orders", "Id = :id").with_value(":id", "a");
+        let page = client.query(&query).unwrap();
+        assert_eq!(page.count, 1);
+        assert_eq!(page.items, vec![key("a")]);
+
+        let next = query.with_start_key(page.last_evaluated_key.unwrap());
+        client.query(&next).unwrap();
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].get("ExclusiveStartKey").is_none());
+        assert_eq!(requests[1]["ExclusiveStartKey"], json!({"Id": {"S": "a"}}));
+        assert_eq!(requests[1]["_target"], "DynamoDB_20120810.Query");
+    }
+}
@@ -0,0 +1,442 @@
+// DynamoDB Streams Events and Attribute Values
+//
+// Stream records carry item images in DynamoDB's typed JSON, where every
+// value is a single-key object naming its type:
+//
+//   {"Records": [{"eventID": "...", "eventName": "INSERT", "eventSource": "aws:dynamodb",
+//     "awsRegion": "us-east-1", "eventSourceARN": "arn:aws:dynamodb:...:table/orders/stream/...",
+//     "dynamodb": {"Keys": {"Id": {"N": "101"}},
+//                  "NewImage": {"Id": {"N": "101"}, "Message": {"S": "New item!"}},
+//                  "SequenceNumber": "111", "SizeBytes": 26,
+//                  "StreamViewType": "NEW_AND_OLD_IMAGES"}}]}
+//
+// The DynamoDB API uses the same encoding, so `AttributeValue` is shared
+// with the DynamoDB client (feature "dynamodb"). Numbers stay strings to keep
+// their full 38-digit precision; binary values are base64 on the wire and
+// decoded bytes in memory.
+
+use ruchy_lambda_simd::base64;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// An item (or key): attribute name to value
+pub type DynamoDbItem = HashMap<String, AttributeValue>;
+
+/// A typed `DynamoDB` value
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::AttributeValue;
+///
+/// let value: AttributeValue = serde_json::from_str(r#"{"M":{"qty":{"N":"3"},"gift":{"BOOL":true}}}"#).unwrap();
+/// assert_eq!(value.to_json(), serde_json::json!({"qty": 3, "gift": true}));
+///
+/// let name = AttributeValue::from("Ada");
+/// assert_eq!(serde_json::to_string(&name).unwrap(), r#"{"S":"Ada"}"#);
+/// assert_eq!(AttributeValue::from(42).as_n(), Some("42"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttributeValue {
+    /// String
+    S(String),
+    /// Number, as its decimal string
+    N(String),
+    /// Binary
+    #[serde(with = "base64_bytes")]
+    B(Vec<u8>),
+    /// Boolean
+    #[serde(rename = "BOOL")]
+    Bool(bool),
+    /// Null (always `true` on the wire)
+    #[serde(rename = "NULL")]
+    Null(bool),
+    /// Map
+    M(HashMap<String, AttributeValue>),
+    /// List
+    L(Vec<AttributeValue>),
+    /// String set
+    SS(Vec<String>),
+    /// Number set
+    NS(Vec<String>),
+    /// Binary set
+    #[serde(with = "base64_bytes_set")]
+    BS(Vec<Vec<u8>>),
+}
+
+impl AttributeValue {
+    /// String value
+    #[must_use]
+    pub fn as_s(&self) -> Option<&str> {
+        match self {
+            Self::S(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Number value as its decimal string
+    #[must_use]
+    pub fn as_n(&self) -> Option<&str> {
+        match self {
+            Self::N(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Binary value
+    #[must_use]
+    pub fn as_b(&self) -> Option<&[u8]> {
+        match self {
+            Self::B(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Boolean value
+    #[must_use]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Map value
+    #[must_use]
+    pub fn as_m(&self) -> Option<&HashMap<String, AttributeValue>> {
+        match self {
+            Self::M(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// List value
+    #[must_use]
+    pub fn as_l(&self) -> Option<&[AttributeValue]> {
+        match self {
+            Self::L(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the null value
+    #[must_use]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null(_))
+    }
+
+    /// Plain JSON: numbers become JSON numbers (integers beyond 64 bits stay
+    /// strings), binary becomes base64 and sets become arrays
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let number = |n: &String| {
+            let parsed = if n.contains(['.', 'e', 'E']) {
+                n.parse().ok().and_then(serde_json::Number::from_f64)
+            } else {
+                n.parse::<i64>()
+                    .map(serde_json::Number::from)
+                    .or_else(|_| n.parse::<u64>().map(serde_json::Number::from))
+                    .ok()
+            };
+            parsed.map_or_else(|| Value::String(n.clone()), Value::Number)
+        };
+        match self {
+            Self::S(value) => Value::String(value.clone()),
+            Self::N(value) => number(value),
+            Self::B(value) => Value::String(base64::encode(value)),
+            Self::Bool(value) => Value::Bool(*value),
+            Self::Null(_) => Value::Null,
+            Self::M(map) => Value::Object(
+                map.iter()
+                    .map(|(name, value)| (name.clone(), value.to_json()))
+                    .collect(),
+            ),
+            Self::L(list) => Value::Array(list.iter().map(Self::to_json).collect()),
+            Self::SS(set) => Value::Array(set.iter().cloned().map(Value::String).collect()),
+            Self::NS(set) => Value::Array(set.iter().map(number).collect()),
+            Self::BS(set) => Value::Array(
+                set.iter()
+                    .map(|value| Value::String(base64::encode(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Typed value of plain JSON: objects become maps and arrays lists
+    #[must_use]
+    pub fn from_json(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null(true),
+            Value::Bool(value) => Self::Bool(*value),
+            Value::Number(value) => Self::N(value.to_string()),
+            Value::String(value) => Self::S(value.clone()),
+            Value::Array(list) => Self::L(list.iter().map(Self::from_json).collect()),
+            Value::Object(map) => Self::M(
+                map.iter()
+                    .map(|(name, value)| (name.clone(), Self::from_json(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::S(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::S(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<Vec<u8>> for AttributeValue {
+    fn from(value: Vec<u8>) -> Self {
+        Self::B(value)
+    }
+}
+
+macro_rules! number_from {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for AttributeValue {
+            fn from(value: $ty) -> Self {
+                Self::N(value.to_string())
+            }
+        })*
+    };
+}
+
+number_from!(i32, i64, u32, u64, f64);
+
+/// Plain JSON object of an item (see [`AttributeValue::to_json`])
+#[must_use]
+pub fn item_to_json(item: &DynamoDbItem) -> Value {
+    Value::Object(
+        item.iter()
+            .map(|(name, value)| (name.clone(), value.to_json()))
+            .collect(),
+    )
+}
+
+mod base64_bytes {
+    use ruchy_lambda_simd::base64;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base64::encode(bytes))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        base64::decode(text.as_bytes()).map_err(serde::de::Error::custom)
+    }
+}
+
+mod base64_bytes_set {
+    use ruchy_lambda_simd::base64;
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        set: &[Vec<u8>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(set.len()))?;
+        for bytes in set {
+            seq.serialize_element(&base64::encode(bytes))?;
+        }
+        seq.end()
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|text| base64::decode(text.as_bytes()).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+/// `DynamoDB` Streams event
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::DynamoDbEvent;
+///
+/// let body = r#"{"Records":[{"eventID":"1","eventName":"INSERT","eventSource":"aws:dynamodb",
+///     "eventSourceARN":"arn:aws:dynamodb:us-east-1:123456789012:table/orders/stream/2024-03-10T14:05:32.000",
+///     "dynamodb":{"Keys":{"Id":{"N":"101"}},"NewImage":{"Id":{"N":"101"},"Message":{"S":"New item!"}},
+///         "SequenceNumber":"111","SizeBytes":26,"StreamViewType":"NEW_AND_OLD_IMAGES"}}]}"#;
+///
+/// let event = DynamoDbEvent::from_json(body).unwrap();
+/// let record = &event.records[0];
+/// assert_eq!(record.table_name(), Some("orders"));
+/// assert_eq!(record.dynamodb.keys["Id"].as_n(), Some("101"));
+/// let image = record.dynamodb.new_image.as_ref().unwrap();
+/// assert_eq!(image["Message"].as_s(), Some("New item!"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DynamoDbEvent {
+    /// Stream records, in sequence order per item
+    #[serde(rename = "Records", default)]
+    pub records: Vec<DynamoDbRecord>,
+}
+
+impl DynamoDbEvent {
+    /// Parse a `DynamoDB` Streams event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a `DynamoDB` Streams
+    /// event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+}
+
+/// One item change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamoDbRecord {
+    /// Unique record ID
+    #[serde(rename = "eventID", default)]
+    pub event_id: String,
+    /// `INSERT`, `MODIFY` or `REMOVE`
+    #[serde(default)]
+    pub event_name: String,
+    /// Event format version
+    #[serde(default)]
+    pub event_version: String,
+    /// `aws:dynamodb`
+    #[serde(default)]
+    pub event_source: String,
+    /// Region of the table
+    #[serde(default)]
+    pub aws_region: String,
+    /// Stream ARN (`arn:aws:dynamodb:<region>:<account>:table/<name>/stream/<label>`)
+    #[serde(rename = "eventSourceARN", default)]
+    pub event_source_arn: String,
+    /// The change itself
+    #[serde(default)]
+    pub dynamodb: StreamRecord,
+    /// `{"type":"Service","principalId":"dynamodb.amazonaws.com"}` for TTL deletes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_identity: Option<Value>,
+}
+
+impl DynamoDbRecord {
+    /// Table name from the stream ARN
+    #[must_use]
+    pub fn table_name(&self) -> Option<&str> {
+        self.event_source_arn
+            .split_once(":table/")?
+            .1
+            .split('/')
+            .next()
+    }
+}
+
+/// Keys and images of a changed item
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StreamRecord {
+    /// Change time in Unix epoch seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approximate_creation_date_time: Option<f64>,
+    /// Primary key of the item
+    #[serde(default)]
+    pub keys: DynamoDbItem,
+    /// Item after the change (`NEW_IMAGE` / `NEW_AND_OLD_IMAGES` streams)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_image: Option<DynamoDbItem>,
+    /// Item before the change (`OLD_IMAGE` / `NEW_AND_OLD_IMAGES` streams)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_image: Option<DynamoDbItem>,
+    /// Sequence number, for ordering and batch item failures
+    #[serde(default)]
+    pub sequence_number: String,
+    /// Size of the record in bytes
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// `KEYS_ONLY`, `NEW_IMAGE`, `OLD_IMAGE` or `NEW_AND_OLD_IMAGES`
+    #[serde(default)]
+    pub stream_view_type: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_all_types_round_trip() {
+        let wire = json!({
+            "s": {"S": "x"}, "n": {"N": "1.5"}, "b": {"B": "AAE="}, "t": {"BOOL": true},
+            "z": {"NULL": true}, "m": {"M": {"k": {"S": "v"}}}, "l": {"L": [{"N": "1"}]},
+            "ss": {"SS": ["a", "b"]}, "ns": {"NS": ["1", "2"]}, "bs": {"BS": ["AAE="]},
+        });
+        let item: DynamoDbItem = serde_json::from_value(wire.clone()).unwrap();
+        assert_eq!(item["b"].as_b(), Some(&[0u8, 1][..]));
+        assert!(item["z"].is_null());
+        assert_eq!(serde_json::to_value(&item).unwrap(), wire);
+
+        assert_eq!(
+            item_to_json(&item),
+            json!({
+                "s": "x", "n": 1.5, "b": "AAE=", "t": true, "z": null, "m": {"k": "v"},
+                "l": [1], "ss": ["a", "b"], "ns": [1, 2], "bs": ["AAE="],
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_conversion_and_large_numbers() {
+        let value = json!({"id": 7, "tags": ["a"], "ok": false, "none": null});
+        let typed = AttributeValue::from_json(&value);
+        assert_eq!(
+            typed.as_m().unwrap()["id"],
+            AttributeValue::N("7".to_string())
+        );
+        assert_eq!(typed.to_json(), value);
+
+        // Beyond u64: kept as a string rather than rounded
+        let huge = AttributeValue::N("123456789012345678901234567890".to_string());
+        assert_eq!(huge.to_json(), json!("123456789012345678901234567890"));
+    }
+
+    #[test]
+    fn test_stream_event_fixture() {
+        let body = r#"{"Records":[{"eventID":"c4ca","eventName":"REMOVE","eventVersion":"1.1",
+            "eventSource":"aws:dynamodb","awsRegion":"us-east-1",
+            "dynamodb":{"ApproximateCreationDateTime":1710079532,"Keys":{"Id":{"N":"101"}},
+                "OldImage":{"Id":{"N":"101"}},"SequenceNumber":"44","SizeBytes":26,
+                "StreamViewType":"OLD_IMAGE"},
+            "userIdentity":{"type":"Service","principalId":"dynamodb.amazonaws.com"},
+            "eventSourceARN":"arn:aws:dynamodb:us-east-1:123456789012:table/orders/stream/2024-03-10T14:05:32.000"}]}"#;
+        let event = DynamoDbEvent::from_json(body).unwrap();
+        let record = &event.records[0];
+        assert_eq!(record.event_name, "REMOVE");
+        assert_eq!(record.table_name(), Some("orders"));
+        assert_eq!(record.dynamodb.sequence_number, "44");
+        assert!(record.dynamodb.new_image.is_none());
+        assert_eq!(
+            record.user_identity.as_ref().unwrap()["principalId"],
+            "dynamodb.amazonaws.com"
+        );
+
+        assert!(DynamoDbEvent::from_json("{}").unwrap().records.is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+// ETags and Conditional Requests
+//
+// Read-heavy HTTP functions can skip sending a body the client already has:
+//
+//   1. The response gets a weak ETag, W/"<16 hex>", an FNV-1a 64-bit hash of
+//      the body (fast, not cryptographic; it only has to change with the body).
+//   2. When the request's If-None-Match lists that tag (or `*`), the response
+//      becomes a 304 Not Modified with no body.
+//
+// If-None-Match uses the weak comparison of RFC 9110: W/"x" and "x" match.
+// Only successful (200) GET / HEAD responses are made conditional; the 304
+// keeps the headers RFC 9110 requires (Cache-Control, Content-Location,
+// Date, ETag, Expires, Vary) and drops the rest.
+
+use crate::http_request::HttpRequest;
+use serde_json::Value;
+
+/// Headers a 304 response repeats from the 200 it replaces
+const NOT_MODIFIED_HEADERS: &[&str] = &[
+    "cache-control",
+    "content-location",
+    "date",
+    "etag",
+    "expires",
+    "vary",
+];
+
+/// Weak entity tag of a response body: `W/"<16 hex>"`
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::weak_etag;
+///
+/// let etag = weak_etag(br#"{"id":7}"#);
+/// assert!(etag.starts_with("W/\"") && etag.len() == 20);
+/// assert_eq!(etag, weak_etag(br#"{"id":7}"#));
+/// assert_ne!(etag, weak_etag(br#"{"id":8}"#));
+/// ```
+#[must_use]
+pub fn weak_etag(body: &[u8]) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = body.iter().fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("W/\"{hash:016x}\"")
+}
+
+/// Whether an `If-None-Match` header value matches `etag`
+///
+/// The header may be `*` or a comma-separated list of tags; tags are
+/// compared weakly (the `W/` prefix is ignored).
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::etag_matches;
+///
+/// assert!(etag_matches(r#""a", W/"b""#, r#"W/"b""#));
+/// assert!(etag_matches(r#""b""#, r#"W/"b""#));
+/// assert!(etag_matches("*", r#""c""#));
+/// assert!(!etag_matches(r#""a""#, r#""b""#));
+/// ```
+#[must_use]
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| {
+        let tag = tag.trim();
+        tag.strip_prefix("W/").unwrap_or(tag).to_string()
+    };
+    let etag = opaque(etag);
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// Add an `ETag` to a proxy response and answer 304 when the client has it
+///
+/// Applies to 200 responses of GET / HEAD requests; an `ETag` header the
+/// handler already set is kept, otherwise [`weak_etag`] of the body is
+/// added. Other responses, and responses that are not proxy response JSON,
+/// are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{conditional_response, HttpRequest};
+///
+/// let response = r#"{"statusCode":200,"headers":{"cache-control":"max-age=60"},"body":"hello"}"#;
+///
+/// let first = HttpRequest::from_json(r#"{"httpMethod":"GET","path":"/greeting"}"#).unwrap();
+/// let full: serde_json::Value =
+///     serde_json::from_str(&conditional_response(&first, response.to_string())).unwrap();
+/// let etag = full["headers"]["etag"].as_str().unwrap();
+///
+/// let event = serde_json::json!({
+///     "httpMethod": "GET", "path": "/greeting", "headers": {"If-None-Match": etag},
+/// });
+/// let again = HttpRequest::from_json(&event.to_string()).unwrap();
+/// let cached: serde_json::Value =
+///     serde_json::from_str(&conditional_response(&again, response.to_string())).unwrap();
+/// assert_eq!(cached["statusCode"], 304);
+/// assert_eq!(cached["body"], "");
+/// assert_eq!(cached["headers"]["cache-control"], "max-age=60");
+/// ```
+#[must_use]
+pub fn conditional_response(request: &HttpRequest, response: String) -> String {
+    if request.method != "GET" && request.method != "HEAD" {
+        return response;
+    }
+    let Ok(mut value) = serde_json::from_str::<Value>(&response) else {
+        return response;
+    };
+    let Some(object) = value.as_object_mut() else {
+        return response;
+    };
+    if object.get("statusCode").and_then(Value::as_u64) != Some(200) {
+        return response;
+    }
+
+    let body = object
+        .get("body")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let computed = weak_etag(body.as_bytes());
+    let headers = object
+        .entry("headers")
+        .or_insert_with(|| serde_json::json!({}));
+    let Some(headers) = headers.as_object_mut() else {
+        return response;
+    };
+    let existing = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("etag"))
+        .map(|(_, etag)| etag.as_str().unwrap_or_default().to_string());
+    let etag = if let Some(existing) = existing {
+        existing
+    } else {
+        headers.insert("etag".to_string(), Value::String(computed.clone()));
+        computed
+    };
+
+    let not_modified = request
+        .header("if-none-match")
+        .is_some_and(|if_none_match| etag_matches(if_none_match, &etag));
+    if !not_modified {
+        return value.to_string();
+    }
+
+    headers.retain(|name, _| NOT_MODIFIED_HEADERS.contains(&name.to_ascii_lowercase().as_str()));
+    let headers = std::mem::take(headers);
+    serde_json::json!({
+        "statusCode": 304,
+        "headers": headers,
+        "body": "",
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, if_none_match: Option<&str>) -> HttpRequest {
+        let mut request = HttpRequest {
+            method: method.to_string(),
+            path: "/".to_string(),
+            ..HttpRequest::default()
+        };
+        if let Some(if_none_match) = if_none_match {
+            request
+                .headers
+                .insert("if-none-match".to_string(), if_none_match.to_string());
+        }
+        request
+    }
+
+    fn parse(response: &str) -> Value {
+        serde_json::from_str(response).unwrap()
+    }
+
+    #[test]
+    fn test_etag_added_without_if_none_match() {
+        let response = conditional_response(
+            &request("GET", None),
+            r#"{"statusCode":200,"body":"x"}"#.to_string(),
+        );
+        assert_eq!(parse(&response)["headers"]["etag"], weak_etag(b"x"));
+        assert_eq!(parse(&response)["body"], "x");
+    }
+
+    #[test]
+    fn test_handler_etag_is_kept_and_compared() {
+        let response = r#"{"statusCode":200,"headers":{"ETag":"\"v2\"","Content-Type":"text/plain","Vary":"Accept"},"body":"x"}"#;
+
+        let stale = conditional_response(&request("GET", Some("\"v1\"")), response.to_string());
+        assert_eq!(parse(&stale)["statusCode"], 200);
+        assert!(parse(&stale)["headers"].get("etag").is_none());
+
+        let fresh = conditional_response(&request("HEAD", Some("W/\"v2\"")), response.to_string());
+        let fresh = parse(&fresh);
+        assert_eq!(fresh["statusCode"], 304);
+        assert_eq!(fresh["headers"]["ETag"], "\"v2\"");
+        assert_eq!(fresh["headers"]["Vary"], "Accept");
+        assert!(fresh["headers"].get("Content-Type").is_none());
+    }
+
+    #[test]
+    fn test_unconditional_responses_untouched() {
+        let ok = r#"{"statusCode":200,"body":"x"}"#;
+        assert_eq!(
+            conditional_response(&request("POST", Some("*")), ok.to_string()),
+            ok
+        );
+        let missing = r#"{"statusCode":404,"body":"x"}"#;
+        assert_eq!(
+            conditional_response(&request("GET", Some("*")), missing.to_string()),
+            missing
+        );
+        assert_eq!(
+            conditional_response(&request("GET", Some("*")), "plain".to_string()),
+            "plain"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// Streaming Event Bodies
+//
+// `Runtime::next_invocation` reads the whole `/next` response into memory
+// and then copies the body into a String before the handler deserializes
+// it. For multi-MB events that is two full copies plus the parsed value.
+//
+// `Runtime::next_invocation_stream` parses only the response head and
+// hands the body over still on the connection. A deserializer (or a raw
+// consumer) then reads it as it arrives, so peak memory is one read buffer
+// plus whatever the consumer builds, and parsing overlaps the transfer.
+//
+// The body is capped at the runtime's max response size: an advertised
+// `Content-Length` over it is rejected up front, and reads past it fail.
+//
+// With pipelining (pipelining.rs) the connection stays with the client, so
+// the event arrives fully buffered and the stream reads it from memory.
+
+use ruchy_lambda_http_core::{ResponseStream, Transport};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+#[cfg(feature = "serde")]
+use crate::codec::UNMARSHAL_ERROR;
+#[cfg(feature = "serde")]
+use crate::HandlerError;
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+
+/// Read buffer size: large enough that a deserializer pulling single bytes
+/// still reads the socket in big chunks
+const EVENT_STREAM_BUFFER: usize = 16 * 1024;
+
+/// Event body read from the Runtime API connection on demand
+///
+/// Returned by
+/// [`Runtime::next_invocation_stream`](crate::Runtime::next_invocation_stream).
+/// Reading yields the event payload bytes only. The invocation must still
+/// be completed with `post_response` or `post_error`, also when reading the
+/// body fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ruchy_lambda_runtime::Runtime;
+/// # use std::io::BufRead;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let runtime = Runtime::new()?;
+/// let (context, stream) = runtime.next_invocation_stream()?;
+/// // Count lines without holding the whole event in memory
+/// let lines = stream.lines().count();
+/// runtime.post_response(&context.request_id, &lines.to_string())?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventStream {
+    reader: Body,
+    content_length: Option<u64>,
+}
+
+/// Where the event bytes come from
+enum Body {
+    /// Still on the connection
+    Connection(BufReader<ResponseStream<Box<dyn Transport>>>),
+    /// Already read: a whole response, positioned at the body
+    Buffered(Cursor<Vec<u8>>),
+}
+
+impl EventStream {
+    /// Wrap a streaming `/next` response
+    pub(crate) fn new(response: ResponseStream<Box<dyn Transport>>) -> Self {
+        Self {
+            content_length: response.remaining(),
+            reader: Body::Connection(BufReader::with_capacity(EVENT_STREAM_BUFFER, response)),
+        }
+    }
+
+    /// Wrap a `/next` response read into memory, body at `body_start`
+    pub(crate) fn buffered(response: Vec<u8>, body_start: usize) -> Self {
+        let content_length = (response.len() - body_start) as u64;
+        let mut reader = Cursor::new(response);
+        reader.set_position(body_start as u64);
+        Self {
+            content_length: Some(content_length),
+            reader: Body::Buffered(reader),
+        }
+    }
+
+    /// Event size in bytes, if the Runtime API sent a `Content-Length`
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Deserialize the JSON event straight from the connection
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.UnmarshalError` if the body is not valid JSON for
+    /// `T`, the connection fails, or the body exceeds the size limit.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<T: DeserializeOwned>(self) -> Result<T, HandlerError> {
+        serde_json::from_reader(self).map_err(|e| HandlerError::new(UNMARSHAL_ERROR, e.to_string()))
+    }
+}
+
+impl Read for EventStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.reader {
+            Body::Connection(reader) => reader.read(buf),
+            Body::Buffered(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for EventStream {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match &mut self.reader {
+            Body::Connection(reader) => reader.fill_buf(),
+            Body::Buffered(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        match &mut self.reader {
+            Body::Connection(reader) => reader.consume(amount),
+            Body::Buffered(reader) => reader.consume(amount),
+        }
+    }
+}
+
+impl fmt::Debug for EventStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStream")
+            .field("content_length", &self.content_length)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,459 @@
+// Lambda Extensions API and Shutdown Flush
+//
+// A process registered with the Extensions API gets a SHUTDOWN event before
+// the execution environment is reclaimed, with a deadline (at most 2s away).
+// Whatever is still buffered at that point — metrics, log batches for a
+// Telemetry/Logs API subscriber — is lost unless it is flushed in that
+// window:
+//
+//   register -> event/next (INVOKE) ... -> event/next (SHUTDOWN) -> flush -> exit
+//
+// Buffers register their flush with `on_shutdown` (`Metrics` does so
+// itself). `ExtensionClient::run` waits for SHUTDOWN, cancels running
+// handlers (`request_shutdown`, see cancellation.rs) and runs
+// `run_shutdown_hooks` with the event's deadline: queued background tasks
+// first, then the hooks in registration order. The deadline becomes the
+// flushing thread's invocation deadline, so outbound flushes are bounded by
+// it (deadline.rs) and hooks still pending once it has passed are skipped
+// rather than racing the freeze.
+//
+// Processes that are not extensions call `run_shutdown_hooks` themselves,
+// e.g. once `shutdown_requested` reports SIGTERM (feature "signals").
+
+use crate::background::drain_background_tasks;
+use crate::cancellation::request_shutdown;
+use crate::deadline;
+use crate::logger::{LogLevel, Logger};
+use crate::unwind;
+use once_cell::sync::Lazy;
+use ruchy_lambda_http_core::{HttpError, DEFAULT_MAX_RESPONSE_SIZE};
+use std::env;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+/// Base path of the Extensions API
+const EXTENSION_API: &str = "/2020-01-01/extension";
+
+/// Response header carrying the identifier of a registered extension
+pub const EXTENSION_ID_HEADER: &str = "Lambda-Extension-Identifier";
+
+/// A flush to run before the environment is reclaimed
+type Hook = Box<dyn FnOnce() + Send>;
+
+/// Hooks registered with `on_shutdown`, in registration order
+static HOOKS: Lazy<Mutex<Vec<Hook>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Events an extension can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionEventType {
+    /// Every invocation
+    Invoke,
+    /// The environment is about to be reclaimed (external extensions only)
+    Shutdown,
+}
+
+impl ExtensionEventType {
+    /// Name used by the Extensions API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Invoke => "INVOKE",
+            Self::Shutdown => "SHUTDOWN",
+        }
+    }
+}
+
+/// Event returned by `GET /extension/event/next`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtensionEvent {
+    /// An invocation started
+    Invoke {
+        /// Request ID of the invocation
+        request_id: String,
+        /// Invocation deadline in Unix epoch milliseconds
+        deadline_ms: u64,
+    },
+    /// The environment is shutting down
+    Shutdown {
+        /// `spindown`, `timeout` or `failure`
+        reason: String,
+        /// Time to finish by, in Unix epoch milliseconds
+        deadline_ms: u64,
+    },
+}
+
+impl ExtensionEvent {
+    /// Parse an event body
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::InvalidResponse` if the body is not an event.
+    pub fn parse(body: &[u8]) -> Result<Self, HttpError> {
+        let event: serde_json::Value = serde_json::from_slice(body)
+            .map_err(|err| HttpError::InvalidResponse(format!("Invalid extension event: {err}")))?;
+        let text = |key: &str| event[key].as_str().unwrap_or_default().to_string();
+        let deadline_ms = event["deadlineMs"].as_u64().unwrap_or(0);
+        match event["eventType"].as_str() {
+            Some("INVOKE") => Ok(Self::Invoke {
+                request_id: text("requestId"),
+                deadline_ms,
+            }),
+            Some("SHUTDOWN") => Ok(Self::Shutdown {
+                reason: text("shutdownReason"),
+                deadline_ms,
+            }),
+            other => Err(HttpError::InvalidResponse(format!(
+                "Unknown extension event type: {other:?}"
+            ))),
+        }
+    }
+
+    /// Deadline of the event in Unix epoch milliseconds
+    #[must_use]
+    pub fn deadline_ms(&self) -> u64 {
+        match self {
+            Self::Invoke { deadline_ms, .. } | Self::Shutdown { deadline_ms, .. } => *deadline_ms,
+        }
+    }
+}
+
+/// Client of the Lambda Extensions API for a registered extension
+///
+/// # Examples
+///
+/// ```no_run
+/// use ruchy_lambda_runtime::{on_shutdown, ExtensionClient, ExtensionEventType};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// on_shutdown(|| println!("flushing buffered telemetry"));
+///
+/// let extension = ExtensionClient::register("telemetry-flush", &[ExtensionEventType::Shutdown])?;
+/// let reason = extension.run()?;
+/// println!("shut down: {reason}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionClient {
+    /// Extensions API address (`host:port`)
+    endpoint: String,
+    /// `Lambda-Extension-Identifier` returned on registration
+    identifier: String,
+}
+
+impl ExtensionClient {
+    /// Register as extension `name` at `AWS_LAMBDA_RUNTIME_API`
+    ///
+    /// Must happen during the init phase, before the runtime's first
+    /// `/next` call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError` if the API is unreachable or rejects the
+    /// registration.
+    pub fn register(name: &str, events: &[ExtensionEventType]) -> Result<Self, HttpError> {
+        let endpoint =
+            env::var("AWS_LAMBDA_RUNTIME_API").unwrap_or_else(|_| "127.0.0.1:9001".to_string());
+        Self::register_at(&endpoint, name, events)
+    }
+
+    /// Register as extension `name` at `endpoint` (`host:port`)
+    ///
+    /// # Errors
+    ///
+    /// Same as [`ExtensionClient::register`].
+    pub fn register_at(
+        endpoint: &str,
+        name: &str,
+        events: &[ExtensionEventType],
+    ) -> Result<Self, HttpError> {
+        let events: Vec<&str> = events.iter().map(|event| event.as_str()).collect();
+        let body = serde_json::json!({ "events": events }).to_string();
+        let mut buffer = Vec::new();
+        ruchy_lambda_http_core::request_into(
+            endpoint,
+            "POST",
+            &format!("{EXTENSION_API}/register"),
+            &[
+                ("Lambda-Extension-Name", name),
+                ("Content-Type", "application/json"),
+            ],
+            body.as_bytes(),
+            DEFAULT_MAX_RESPONSE_SIZE,
+            &mut buffer,
+        )?;
+        let response = ruchy_lambda_http_core::parse_any_response_ref(&buffer)?;
+        if !response
+            .status_code()
+            .is_some_and(|code| (200..300).contains(&code))
+        {
+            return Err(HttpError::InvalidResponse(format!(
+                "Extension registration failed: {}",
+                response.status_line
+            )));
+        }
+        let identifier = response
+            .header(EXTENSION_ID_HEADER)
+            .ok_or_else(|| {
+                HttpError::InvalidResponse(format!(
+                    "Registration response without {EXTENSION_ID_HEADER}"
+                ))
+            })?
+            .to_string();
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            identifier,
+        })
+    }
+
+    /// Identifier assigned on registration
+    #[must_use]
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Block until the next subscribed event
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError` if the request fails or the event is invalid.
+    pub fn next_event(&self) -> Result<ExtensionEvent, HttpError> {
+        let mut buffer = Vec::new();
+        ruchy_lambda_http_core::request_into(
+            &self.endpoint,
+            "GET",
+            &format!("{EXTENSION_API}/event/next"),
+            &[(EXTENSION_ID_HEADER, &self.identifier)],
+            &[],
+            DEFAULT_MAX_RESPONSE_SIZE,
+            &mut buffer,
+        )?;
+        let response = ruchy_lambda_http_core::parse_any_response_ref(&buffer)?;
+        if !response
+            .status_code()
+            .is_some_and(|code| (200..300).contains(&code))
+        {
+            return Err(HttpError::InvalidResponse(format!(
+                "Extension event request failed: {}",
+                response.status_line
+            )));
+        }
+        ExtensionEvent::parse(response.body)
+    }
+
+    /// Wait for SHUTDOWN, then cancel running handlers and run the shutdown
+    /// hooks within its deadline; returns the shutdown reason
+    ///
+    /// Run it on its own thread when the process also serves invocations.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a failed event request (hooks are not run).
+    pub fn run(&self) -> Result<String, HttpError> {
+        loop {
+            if let ExtensionEvent::Shutdown {
+                reason,
+                deadline_ms,
+            } = self.next_event()?
+            {
+                request_shutdown();
+                run_shutdown_hooks(deadline::from_millis(deadline_ms));
+                return Ok(reason);
+            }
+        }
+    }
+}
+
+/// Run `hook` when the environment shuts down (see [`run_shutdown_hooks`])
+pub fn on_shutdown(hook: impl FnOnce() + Send + 'static) {
+    HOOKS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::new(hook));
+}
+
+/// Run queued background tasks and then the [`on_shutdown`] hooks, in
+/// registration order, before `deadline`
+///
+/// Hooks still pending once the deadline has passed are dropped and logged.
+/// A panicking hook is logged and does not affect the others. Returns the
+/// number of hooks run.
+pub fn run_shutdown_hooks(deadline: Option<SystemTime>) -> usize {
+    let previous = deadline::invocation_deadline();
+    deadline::set_invocation_deadline(deadline);
+    let _ = drain_background_tasks();
+
+    let hooks = std::mem::take(&mut *HOOKS.lock().unwrap_or_else(PoisonError::into_inner));
+    let total = hooks.len();
+    let mut ran = 0;
+    for hook in hooks {
+        if deadline.is_some_and(|deadline| SystemTime::now() >= deadline) {
+            break;
+        }
+        if unwind::catch(hook).is_err() {
+            Logger::new().error("Shutdown hook panicked");
+        }
+        ran += 1;
+    }
+    if ran < total {
+        Logger::new().log_with_fields(
+            LogLevel::Warn,
+            "Shutdown deadline reached, skipping remaining hooks",
+            &[("skipped_hooks", (total - ran) as u64)],
+        );
+    }
+
+    deadline::set_invocation_deadline(previous);
+    ran
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Serve one canned response per entry, returning the request heads
+    fn api(responses: Vec<String>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 2048];
+                // Read the head, then the Content-Length body
+                while !request_complete(&request) {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                requests.push(String::from_utf8_lossy(&request).to_string());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (endpoint, handle)
+    }
+
+    fn request_complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            return false;
+        };
+        let length = head
+            .lines()
+            .find_map(|line| line.strip_prefix("Content-Length: "))
+            .map_or(0, |value| value.parse().unwrap());
+        body.len() >= length
+    }
+
+    fn ok(headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let invoke = br#"{"eventType":"INVOKE","deadlineMs":1700000000000,"requestId":"req-1","invokedFunctionArn":"arn"}"#;
+        assert_eq!(
+            ExtensionEvent::parse(invoke).unwrap(),
+            ExtensionEvent::Invoke {
+                request_id: "req-1".to_string(),
+                deadline_ms: 1_700_000_000_000,
+            }
+        );
+
+        let shutdown = br#"{"eventType":"SHUTDOWN","shutdownReason":"spindown","deadlineMs":42}"#;
+        let event = ExtensionEvent::parse(shutdown).unwrap();
+        assert_eq!(event.deadline_ms(), 42);
+        assert!(matches!(event, ExtensionEvent::Shutdown { reason, .. } if reason == "spindown"));
+
+        assert!(ExtensionEvent::parse(br#"{"eventType":"RESTORE"}"#).is_err());
+        assert!(ExtensionEvent::parse(b"not json").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_shutdown_event_flushes() {
+        let (endpoint, server) = api(vec![
+            ok("Lambda-Extension-Identifier: ext-123\r\n", "{}"),
+            ok(
+                "",
+                r#"{"eventType":"INVOKE","deadlineMs":0,"requestId":"req-1"}"#,
+            ),
+            ok(
+                "",
+                r#"{"eventType":"SHUTDOWN","shutdownReason":"spindown","deadlineMs":0}"#,
+            ),
+        ]);
+        let flushed = Arc::new(AtomicUsize::new(0));
+        let hook_flushed = Arc::clone(&flushed);
+        on_shutdown(move || {
+            hook_flushed.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let client = ExtensionClient::register_at(
+            &endpoint,
+            "flusher",
+            &[ExtensionEventType::Invoke, ExtensionEventType::Shutdown],
+        )
+        .unwrap();
+        assert_eq!(client.identifier(), "ext-123");
+        assert!(matches!(
+            client.next_event().unwrap(),
+            ExtensionEvent::Invoke { .. }
+        ));
+        // `run` would also cancel every handler of this (test) process
+        let event = client.next_event().unwrap();
+        assert!(matches!(event, ExtensionEvent::Shutdown { .. }));
+        assert_eq!(
+            run_shutdown_hooks(deadline::from_millis(event.deadline_ms())),
+            1
+        );
+        assert_eq!(flushed.load(Ordering::SeqCst), 1);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2020-01-01/extension/register HTTP/1.1"));
+        assert!(requests[0].contains("Lambda-Extension-Name: flusher"));
+        assert!(requests[0].ends_with(r#"{"events":["INVOKE","SHUTDOWN"]}"#));
+        assert!(requests[1].starts_with("GET /2020-01-01/extension/event/next HTTP/1.1"));
+        assert!(requests[1].contains("Lambda-Extension-Identifier: ext-123"));
+    }
+
+    #[test]
+    fn test_register_requires_identifier() {
+        let (endpoint, server) = api(vec![ok("", "{}")]);
+        let error = ExtensionClient::register_at(&endpoint, "x", &[]).unwrap_err();
+        assert!(error.to_string().contains(EXTENSION_ID_HEADER));
+        server.join().unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_hooks_bounded_by_deadline() {
+        let ran = Arc::new(AtomicUsize::new(0));
+        for _ in 0..2 {
+            let ran = Arc::clone(&ran);
+            on_shutdown(move || {
+                assert!(deadline::invocation_deadline().is_some());
+                ran.fetch_add(1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(60));
+            });
+        }
+        on_shutdown(|| panic!("flush failed"));
+
+        let deadline = SystemTime::now() + Duration::from_millis(30);
+        assert_eq!(run_shutdown_hooks(Some(deadline)), 1);
+        assert_eq!(ran.load(Ordering::SeqCst), 1);
+        assert_eq!(deadline::invocation_deadline(), None);
+        assert_eq!(run_shutdown_hooks(None), 0);
+    }
+}
@@ -0,0 +1,334 @@
+// Failure Destination Record
+//
+// Lambda Destinations (on-failure) and async DLQs deliver failed async
+// invocations as a JSON record:
+//
+// {"version":"1.0","timestamp":"...",
+//  "requestContext":{"requestId","functionArn","condition","approximateInvokeCount"},
+//  "requestPayload":{...},
+//  "responseContext":{"statusCode","executedVersion","functionError"},
+//  "responsePayload":{"errorMessage","errorType","stackTrace"}}
+//
+// Functions that write failures to their own sinks (SQS, S3, logs) can use
+// FailureRecord to emit the same shape, so downstream tooling handles both.
+
+use crate::context::Context;
+use crate::handler_error::HandlerError;
+use crate::redaction::{Redactor, DEFAULT_PAYLOAD_EXCERPT_BYTES};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Why an invocation was routed to the failure destination
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailureCondition {
+    /// All retry attempts failed
+    #[default]
+    RetriesExhausted,
+    /// The event was older than the maximum event age
+    EventAgeExceeded,
+}
+
+impl fmt::Display for FailureCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RetriesExhausted => write!(f, "RetriesExhausted"),
+            Self::EventAgeExceeded => write!(f, "EventAgeExceeded"),
+        }
+    }
+}
+
+/// Builder for a Lambda Destinations-style failure record
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, FailureRecord, HandlerError};
+///
+/// let context = Context {
+///     request_id: "req-1".to_string(),
+///     invoked_function_arn: "arn:aws:lambda:us-east-1:123456789012:function:fn:live".to_string(),
+///     ..Context::default()
+/// };
+/// let error = HandlerError::new("Function.ValidationError", "missing field: name");
+///
+/// let record = FailureRecord::new(&context, &error, r#"{"id":7}"#).with_approximate_invoke_count(3);
+/// let json: serde_json::Value = serde_json::from_str(&record.to_json()).unwrap();
+/// assert_eq!(json["requestContext"]["requestId"], "req-1");
+/// assert_eq!(json["requestContext"]["condition"], "RetriesExhausted");
+/// assert_eq!(json["requestPayload"]["id"], 7);
+/// assert_eq!(json["responseContext"]["executedVersion"], "live");
+/// assert_eq!(json["responsePayload"]["errorType"], "Function.ValidationError");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FailureRecord<'a> {
+    context: &'a Context,
+    error: &'a HandlerError,
+    payload: &'a str,
+    condition: FailureCondition,
+    approximate_invoke_count: u32,
+    payload_limit: usize,
+    timestamp: SystemTime,
+    redactor: Option<&'a Redactor>,
+}
+
+impl<'a> FailureRecord<'a> {
+    /// Record for a failed invocation, timestamped now
+    #[must_use]
+    pub fn new(context: &'a Context, error: &'a HandlerError, payload: &'a str) -> Self {
+        Self {
+            context,
+            error,
+            payload,
+            condition: FailureCondition::default(),
+            approximate_invoke_count: 1,
+            payload_limit: DEFAULT_PAYLOAD_EXCERPT_BYTES,
+            timestamp: SystemTime::now(),
+            redactor: None,
+        }
+    }
+
+    /// Set the failure condition (default: `RetriesExhausted`)
+    #[must_use]
+    pub fn with_condition(mut self, condition: FailureCondition) -> Self {
+        self.condition = condition;
+        self
+    }
+
+    /// Set how many times the event was attempted (default: 1)
+    #[must_use]
+    pub fn with_approximate_invoke_count(mut self, count: u32) -> Self {
+        self.approximate_invoke_count = count;
+        self
+    }
+
+    /// Set the maximum request payload excerpt in bytes
+    #[must_use]
+    pub fn with_payload_limit(mut self, bytes: usize) -> Self {
+        self.payload_limit = bytes;
+        self
+    }
+
+    /// Override the failure timestamp (default: creation time)
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Redact the request payload before it is excerpted (default: verbatim)
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: &'a Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Serialize to the Destinations/DLQ record JSON
+    ///
+    /// A payload that fits the limit and is valid JSON is embedded as-is;
+    /// anything else becomes a string excerpt (cut on a UTF-8 boundary) and
+    /// the record gains `"requestPayloadTruncated": true` when cut.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let (request_payload, truncated) = self.request_payload();
+
+        let mut record = serde_json::json!({
+            "version": "1.0",
+            "timestamp": format_iso8601(self.timestamp),
+            "requestContext": {
+                "requestId": self.context.request_id,
+                "functionArn": self.context.invoked_function_arn,
+                "condition": self.condition.to_string(),
+                "approximateInvokeCount": self.approximate_invoke_count,
+            },
+            "requestPayload": request_payload,
+            "responseContext": {
+                "statusCode": 200,
+                "executedVersion": executed_version(&self.context.invoked_function_arn),
+                "functionError": "Unhandled",
+            },
+            "responsePayload": {
+                "errorMessage": self.error.error_message,
+                "errorType": self.error.error_type,
+                "stackTrace": self.error.stack_trace,
+            },
+        });
+
+        if truncated {
+            record["requestPayloadTruncated"] = serde_json::Value::Bool(true);
+        }
+
+        record.to_string()
+    }
+
+    /// Request payload as JSON (if it fits and parses) or a string excerpt
+    fn request_payload(&self) -> (serde_json::Value, bool) {
+        let verbatim;
+        let redactor = if let Some(redactor) = self.redactor {
+            redactor
+        } else {
+            verbatim = Redactor::empty();
+            &verbatim
+        };
+        let (excerpt, truncated) = redactor.excerpt(self.payload, self.payload_limit);
+        if truncated {
+            return (serde_json::Value::String(excerpt), true);
+        }
+
+        let value = serde_json::from_str(&excerpt).unwrap_or(serde_json::Value::String(excerpt));
+        (value, false)
+    }
+}
+
+/// Version qualifier from a function ARN (`$LATEST` when unqualified)
+///
+/// `arn:aws:lambda:region:account:function:name[:qualifier]`
+fn executed_version(function_arn: &str) -> &str {
+    function_arn
+        .split(':')
+        .nth(7)
+        .filter(|qualifier| !qualifier.is_empty())
+        .unwrap_or("$LATEST")
+}
+
+/// Format a timestamp as ISO 8601 UTC with milliseconds
+///
+/// Returns format: "2019-11-14T18:16:05.568Z"
+fn format_iso8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let millis = since_epoch.subsec_millis();
+
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let remaining = secs % 86_400;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{millis:03}Z",
+        remaining / 3600,
+        (remaining % 3600) / 60,
+        remaining % 60
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) Gregorian date
+///
+/// Howard Hinnant's `civil_from_days` algorithm, restricted to dates after
+/// the epoch.
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn context() -> Context {
+        Context {
+            request_id: "req-42".to_string(),
+            invoked_function_arn: "arn:aws:lambda:us-east-2:123456789012:function:my-function"
+                .to_string(),
+            ..Context::default()
+        }
+    }
+
+    fn parse(record: &FailureRecord<'_>) -> serde_json::Value {
+        serde_json::from_str(&record.to_json()).unwrap()
+    }
+
+    #[test]
+    fn test_failure_record_shape() {
+        let context = context();
+        let mut error = HandlerError::new("Runtime.HandlerPanic", "boom");
+        error.stack_trace = vec!["at handler".to_string()];
+        let record = FailureRecord::new(&context, &error, r#"{"order":1}"#)
+            .with_condition(FailureCondition::EventAgeExceeded)
+            .with_approximate_invoke_count(3)
+            .with_timestamp(UNIX_EPOCH + Duration::from_millis(1_573_755_365_568));
+
+        let json = parse(&record);
+        assert_eq!(json["version"], "1.0");
+        assert_eq!(json["timestamp"], "2019-11-14T18:16:05.568Z");
+        assert_eq!(json["requestContext"]["requestId"], "req-42");
+        assert_eq!(
+            json["requestContext"]["functionArn"],
+            "arn:aws:lambda:us-east-2:123456789012:function:my-function"
+        );
+        assert_eq!(json["requestContext"]["condition"], "EventAgeExceeded");
+        assert_eq!(json["requestContext"]["approximateInvokeCount"], 3);
+        assert_eq!(json["requestPayload"]["order"], 1);
+        assert_eq!(json["responseContext"]["statusCode"], 200);
+        assert_eq!(json["responseContext"]["executedVersion"], "$LATEST");
+        assert_eq!(json["responseContext"]["functionError"], "Unhandled");
+        assert_eq!(json["responsePayload"]["errorType"], "Runtime.HandlerPanic");
+        assert_eq!(json["responsePayload"]["errorMessage"], "boom");
+        assert_eq!(json["responsePayload"]["stackTrace"][0], "at handler");
+        assert!(json.get("requestPayloadTruncated").is_none());
+    }
+
+    #[test]
+    fn test_failure_record_non_json_payload() {
+        let context = context();
+        let error = HandlerError::new("Function.Error", "bad");
+        let json = parse(&FailureRecord::new(&context, &error, "plain text"));
+        assert_eq!(json["requestPayload"], "plain text");
+    }
+
+    #[test]
+    fn test_failure_record_truncates_on_char_boundary() {
+        let context = context();
+        let error = HandlerError::new("Function.Error", "bad");
+        // "é" is 2 bytes: a 5-byte limit must not split the third one
+        let json = parse(&FailureRecord::new(&context, &error, "ééééé").with_payload_limit(5));
+        assert_eq!(json["requestPayload"], "éé");
+        assert_eq!(json["requestPayloadTruncated"], true);
+    }
+
+    #[test]
+    fn test_failure_record_redacts_payload() {
+        let context = context();
+        let error = HandlerError::new("Function.Error", "bad");
+        let redactor = Redactor::default();
+        let json = parse(
+            &FailureRecord::new(&context, &error, r#"{"card_number":"4111","qty":1}"#)
+                .with_redactor(&redactor),
+        );
+        assert_eq!(json["requestPayload"]["card_number"], "[REDACTED]");
+        assert_eq!(json["requestPayload"]["qty"], 1);
+    }
+
+    #[test]
+    fn test_executed_version() {
+        assert_eq!(
+            executed_version("arn:aws:lambda:us-east-1:123456789012:function:fn:7"),
+            "7"
+        );
+        assert_eq!(
+            executed_version("arn:aws:lambda:us-east-1:123456789012:function:fn"),
+            "$LATEST"
+        );
+        assert_eq!(executed_version(""), "$LATEST");
+    }
+
+    #[test]
+    fn test_format_iso8601() {
+        assert_eq!(format_iso8601(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        // Leap day
+        assert_eq!(
+            format_iso8601(UNIX_EPOCH + Duration::from_secs(1_709_208_001)),
+            "2024-02-29T12:00:01.000Z"
+        );
+        assert_eq!(
+            format_iso8601(UNIX_EPOCH + Duration::from_millis(1_735_689_599_999)),
+            "2024-12-31T23:59:59.999Z"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+// Kinesis Data Firehose Transformations
+//
+// Firehose invokes the function with a buffer of base64 records:
+//
+//   {"invocationId": "...", "deliveryStreamArn": "...", "region": "us-east-1",
+//    "records": [{"recordId": "...", "approximateArrivalTimestamp": 1700000000000,
+//                 "data": "<base64>"}]}
+//
+// and requires exactly one output record per input record, matched by
+// recordId:
+//
+//   {"records": [{"recordId": "...", "result": "Ok", "data": "<base64>"}]}
+//
+// `Ok` records are delivered with the new data, `Dropped` records are
+// discarded, and `ProcessingFailed` records go to the error output prefix.
+// Destinations that expect one document per line (S3, OpenSearch) need each
+// record's data to end with a newline; `ok_ndjson` takes care of that.
+
+use crate::Logger;
+use ruchy_lambda_simd::base64::{self, DecodeError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Firehose transformation invocation
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::FirehoseEvent;
+///
+/// let body = r#"{"invocationId":"inv-1","deliveryStreamArn":"arn:aws:firehose:us-east-1:123456789012:deliverystream/logs",
+///     "region":"us-east-1","records":[
+///         {"recordId":"r1","approximateArrivalTimestamp":1700000000000,"data":"aGVsbG8="},
+///         {"recordId":"r2","approximateArrivalTimestamp":1700000000000,"data":"ZGVidWc="}]}"#;
+///
+/// let event = FirehoseEvent::from_json(body).unwrap();
+/// let response = event.transform_records(|data| match data {
+///     b"debug" => Ok(None),
+///     _ => Ok::<_, String>(Some(data.to_ascii_uppercase())),
+/// });
+///
+/// assert_eq!(
+///     response.to_json(),
+///     r#"{"records":[{"recordId":"r1","result":"Ok","data":"SEVMTE8="},{"recordId":"r2","result":"Dropped","data":""}]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirehoseEvent {
+    /// Invocation ID
+    #[serde(default)]
+    pub invocation_id: String,
+    /// Delivery stream ARN
+    #[serde(default)]
+    pub delivery_stream_arn: String,
+    /// Source Kinesis stream ARN (Kinesis stream sources only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_kinesis_stream_arn: Option<String>,
+    /// AWS region
+    #[serde(default)]
+    pub region: String,
+    /// Buffered records
+    #[serde(default)]
+    pub records: Vec<FirehoseRecord>,
+}
+
+impl FirehoseEvent {
+    /// Parse a Firehose transformation event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a Firehose event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Transform every record
+    ///
+    /// `transform` receives the decoded data of each record and returns
+    /// `Ok(Some(bytes))` to deliver `bytes`, `Ok(None)` to drop the record,
+    /// or `Err(_)` to send the original data to the error output. Records
+    /// whose data is not valid base64 are failed without calling
+    /// `transform`. Failures are logged with the record ID.
+    pub fn transform_records<E: Display>(
+        &self,
+        mut transform: impl FnMut(&[u8]) -> Result<Option<Vec<u8>>, E>,
+    ) -> FirehoseResponse {
+        let mut response = FirehoseResponse::new();
+        for record in &self.records {
+            let outcome = match record.data() {
+                Ok(data) => transform(&data).map_err(|e| e.to_string()),
+                Err(e) => Err(format!("invalid base64 data: {e}")),
+            };
+            match outcome {
+                Ok(Some(data)) => response.ok(record.record_id.as_str(), &data),
+                Ok(None) => response.dropped(record.record_id.as_str()),
+                Err(message) => {
+                    Logger::new().warn(&format!(
+                        "Firehose record {} failed: {message}",
+                        record.record_id
+                    ));
+                    response.records.push(FirehoseOutputRecord {
+                        record_id: record.record_id.clone(),
+                        result: FirehoseResult::ProcessingFailed,
+                        data: record.data.clone(),
+                        metadata: None,
+                    });
+                }
+            }
+        }
+        response
+    }
+}
+
+/// One buffered record
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirehoseRecord {
+    /// Record ID (echoed in the response)
+    pub record_id: String,
+    /// Arrival time in Unix epoch milliseconds
+    #[serde(default)]
+    pub approximate_arrival_timestamp: u64,
+    /// Base64 record data
+    pub data: String,
+    /// Shard, partition key and sequence number (Kinesis stream sources)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kinesis_record_metadata: Option<Value>,
+}
+
+impl FirehoseRecord {
+    /// Decoded record data
+    ///
+    /// # Errors
+    ///
+    /// Returns the decode error if the data is not valid base64.
+    pub fn data(&self) -> Result<Vec<u8>, DecodeError> {
+        base64::decode(self.data.as_bytes())
+    }
+}
+
+/// Outcome of transforming one record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FirehoseResult {
+    /// Deliver the transformed data
+    Ok,
+    /// Discard the record
+    Dropped,
+    /// Send the record to the error output
+    ProcessingFailed,
+}
+
+/// Transformation response
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::FirehoseResponse;
+/// use serde_json::json;
+///
+/// let mut response = FirehoseResponse::new();
+/// response.ok_ndjson("r1", &json!({"level": "info"})).unwrap();
+/// response.dropped("r2");
+///
+/// assert_eq!(
+///     response.to_json(),
+///     r#"{"records":[{"recordId":"r1","result":"Ok","data":"eyJsZXZlbCI6ImluZm8ifQo="},{"recordId":"r2","result":"Dropped","data":""}]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirehoseResponse {
+    /// One output record per input record
+    pub records: Vec<FirehoseOutputRecord>,
+}
+
+impl FirehoseResponse {
+    /// Empty response
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of `record_id` with raw (unencoded) `data`
+    pub fn push(&mut self, record_id: impl Into<String>, result: FirehoseResult, data: &[u8]) {
+        self.records.push(FirehoseOutputRecord {
+            record_id: record_id.into(),
+            result,
+            data: base64::encode(data),
+            metadata: None,
+        });
+    }
+
+    /// Deliver `data` for `record_id`
+    pub fn ok(&mut self, record_id: impl Into<String>, data: &[u8]) {
+        self.push(record_id, FirehoseResult::Ok, data);
+    }
+
+    /// Deliver `value` as one newline-terminated JSON line
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if `value` cannot be serialized; no
+    /// record is added in that case.
+    pub fn ok_ndjson<T: Serialize>(
+        &mut self,
+        record_id: impl Into<String>,
+        value: &T,
+    ) -> Result<(), serde_json::Error> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+        self.ok(record_id, &line);
+        Ok(())
+    }
+
+    /// Discard `record_id`
+    pub fn dropped(&mut self, record_id: impl Into<String>) {
+        self.push(record_id, FirehoseResult::Dropped, &[]);
+    }
+
+    /// Send `record_id` to the error output with its original data
+    pub fn failed(&mut self, record_id: impl Into<String>, original: &[u8]) {
+        self.push(record_id, FirehoseResult::ProcessingFailed, original);
+    }
+
+    /// Set dynamic partitioning keys on the most recently added record
+    pub fn set_partition_keys(&mut self, keys: HashMap<String, String>) {
+        if let Some(record) = self.records.last_mut() {
+            record.metadata = Some(FirehoseMetadata {
+                partition_keys: keys,
+            });
+        }
+    }
+
+    /// JSON response document
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// One transformed record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirehoseOutputRecord {
+    /// Record ID from the input
+    pub record_id: String,
+    /// Outcome
+    pub result: FirehoseResult,
+    /// Base64 output data
+    pub data: String,
+    /// Dynamic partitioning metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<FirehoseMetadata>,
+}
+
+/// Dynamic partitioning metadata
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirehoseMetadata {
+    /// Partition key values used in the S3 prefix
+    pub partition_keys: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(records: &[(&str, &str)]) -> FirehoseEvent {
+        FirehoseEvent {
+            records: records
+                .iter()
+                .map(|(id, data)| FirehoseRecord {
+                    record_id: (*id).to_string(),
+                    data: (*data).to_string(),
+                    ..FirehoseRecord::default()
+                })
+                .collect(),
+            ..FirehoseEvent::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_kinesis_source() {
+        let event = FirehoseEvent::from_json(
+            r#"{"invocationId":"i","deliveryStreamArn":"arn:d","sourceKinesisStreamArn":"arn:k","region":"eu-west-1",
+                "records":[{"recordId":"r","approximateArrivalTimestamp":5,"data":"eA==",
+                "kinesisRecordMetadata":{"shardId":"shardId-000","partitionKey":"p","sequenceNumber":"1"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(event.source_kinesis_stream_arn.as_deref(), Some("arn:k"));
+        assert_eq!(event.records[0].approximate_arrival_timestamp, 5);
+        assert_eq!(event.records[0].data().unwrap(), b"x");
+        assert_eq!(
+            event.records[0].kinesis_record_metadata.as_ref().unwrap()["partitionKey"],
+            "p"
+        );
+    }
+
+    #[test]
+    fn test_transform_statuses() {
+        let event = event(&[
+            ("ok", "YQ=="),
+            ("drop", "Yg=="),
+            ("fail", "Yw=="),
+            ("bad", "!!"),
+        ]);
+        let mut seen = Vec::new();
+        let response = event.transform_records(|data| {
+            seen.push(data.to_vec());
+            match data {
+                b"a" => Ok(Some(b"A\n".to_vec())),
+                b"b" => Ok(None),
+                _ => Err("unsupported"),
+            }
+        });
+
+        // Invalid base64 never reaches the closure
+        assert_eq!(seen, [b"a", b"b", b"c"]);
+        let results: Vec<_> = response
+            .records
+            .iter()
+            .map(|r| (r.record_id.as_str(), r.result, r.data.as_str()))
+            .collect();
+        assert_eq!(
+            results,
+            [
+                ("ok", FirehoseResult::Ok, "QQo="),
+                ("drop", FirehoseResult::Dropped, ""),
+                ("fail", FirehoseResult::ProcessingFailed, "Yw=="),
+                ("bad", FirehoseResult::ProcessingFailed, "!!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_one_output_per_input() {
+        let event = event(&[("a", "YQ=="), ("b", "YQ==")]);
+        let response = event.transform_records(|data| Ok::<_, String>(Some(data.to_vec())));
+        assert_eq!(response.records.len(), event.records.len());
+    }
+
+    #[test]
+    fn test_results_and_encoding() {
+        let mut response = FirehoseResponse::new();
+        response.ok("a", b"hello");
+        response.failed("b", b"raw");
+
+        assert_eq!(response.records[0].data, "aGVsbG8=");
+        assert_eq!(response.records[1].result, FirehoseResult::ProcessingFailed);
+        assert_eq!(response.records[1].data, "cmF3");
+        assert!(response
+            .to_json()
+            .contains(r#""result":"ProcessingFailed""#));
+    }
+
+    #[test]
+    fn test_ndjson_line_is_newline_terminated() {
+        let mut response = FirehoseResponse::new();
+        response.ok_ndjson("a", &[1, 2]).unwrap();
+        assert_eq!(
+            base64::decode(response.records[0].data.as_bytes()).unwrap(),
+            b"[1,2]\n"
+        );
+    }
+
+    #[test]
+    fn test_partition_keys_on_last_record() {
+        let mut response = FirehoseResponse::new();
+        response.ok("a", b"1");
+        response.ok("b", b"2");
+        response.set_partition_keys(HashMap::from([("tenant".to_string(), "t1".to_string())]));
+
+        assert!(response.records[0].metadata.is_none());
+        assert!(response
+            .to_json()
+            .contains(r#""metadata":{"partitionKeys":{"tenant":"t1"}}"#));
+    }
+}
@@ -0,0 +1,165 @@
+// Handler Error Payload
+//
+// Error document accepted by the Lambda Runtime API error endpoints:
+// - POST /2018-06-01/runtime/invocation/{id}/error
+// - POST /2018-06-01/runtime/init/error
+//
+// Format: {"errorMessage":"...","errorType":"...","stackTrace":[...]}
+// Built manually (no serde) to keep the error path allocation-light.
+
+use crate::logger::Logger;
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Request header carrying the error type on the Runtime API error endpoints
+///
+/// Lambda uses it for error metrics and to route failed async invocations
+/// to destinations by error class.
+pub const ERROR_TYPE_HEADER: &str = "Lambda-Runtime-Function-Error-Type";
+
+/// Error reported to the Lambda Runtime API
+///
+/// Lambda surfaces `error_type` and `error_message` to the caller and in
+/// `CloudWatch`, so handlers should use stable, classifiable types
+/// (e.g., `Function.ValidationError`, `Runtime.HandlerPanic`).
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::HandlerError;
+///
+/// let error = HandlerError::new("Function.ValidationError", "missing field: name");
+/// # let error = HandlerError { stack_trace: Vec::new(), ..error }; // `backtrace` feature
+/// assert_eq!(
+///     error.to_json(),
+///     r#"{"errorMessage":"missing field: name","errorType":"Function.ValidationError","stackTrace":[]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerError {
+    /// Error classification (e.g., `Function.ValidationError`)
+    pub error_type: String,
+    /// Human-readable error message
+    pub error_message: String,
+    /// Optional stack trace lines (empty by default; with the `backtrace`
+    /// feature, where the error was created when capture is enabled)
+    pub stack_trace: Vec<String>,
+}
+
+impl HandlerError {
+    /// Create a new handler error with an empty stack trace
+    ///
+    /// With the `backtrace` feature the stack trace holds the caller's
+    /// backtrace when `RUCHY_LAMBDA_BACKTRACE` (or, if unset,
+    /// `RUST_BACKTRACE`) enables capture.
+    pub fn new(error_type: impl Into<String>, error_message: impl Into<String>) -> Self {
+        Self {
+            error_type: error_type.into(),
+            error_message: error_message.into(),
+            #[cfg(feature = "backtrace")]
+            stack_trace: crate::backtrace::capture(),
+            #[cfg(not(feature = "backtrace"))]
+            stack_trace: Vec::new(),
+        }
+    }
+
+    /// `(name, value)` request header announcing [`HandlerError::error_type`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::HandlerError;
+    ///
+    /// let error = HandlerError::new("Runtime.HandlerPanic", "boom");
+    /// assert_eq!(
+    ///     error.error_type_header(),
+    ///     ("Lambda-Runtime-Function-Error-Type", "Runtime.HandlerPanic")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn error_type_header(&self) -> (&'static str, &str) {
+        (ERROR_TYPE_HEADER, &self.error_type)
+    }
+
+    /// Serialize to the Lambda Runtime API error document
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        use std::fmt::Write;
+
+        let mut json = format!(
+            r#"{{"errorMessage":"{}","errorType":"{}","stackTrace":["#,
+            Logger::escape_json(&self.error_message),
+            Logger::escape_json(&self.error_type)
+        );
+
+        for (i, line) in self.stack_trace.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, r#""{}""#, Logger::escape_json(line));
+        }
+
+        json.push_str("]}");
+        json
+    }
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.error_type, self.error_message)
+    }
+}
+
+impl StdError for HandlerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_error_new() {
+        let error = HandlerError::new("Function.Error", "boom");
+        assert_eq!(error.error_type, "Function.Error");
+        assert_eq!(error.error_message, "boom");
+        #[cfg(not(feature = "backtrace"))]
+        assert!(error.stack_trace.is_empty());
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[test]
+    fn test_handler_error_new_captures_caller() {
+        let error = HandlerError::new("Function.Error", "boom");
+        // Empty unless RUCHY_LAMBDA_BACKTRACE / RUST_BACKTRACE enable capture
+        if let Some(first) = error.stack_trace.first() {
+            assert!(
+                first.contains("test_handler_error_new_captures_caller"),
+                "{first}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_handler_error_to_json_escapes() {
+        let mut error = HandlerError::new("Function.Error", "bad \"input\"\nline");
+        error.stack_trace.clear();
+        assert_eq!(
+            error.to_json(),
+            r#"{"errorMessage":"bad \"input\"\nline","errorType":"Function.Error","stackTrace":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_handler_error_to_json_stack_trace() {
+        let mut error = HandlerError::new("Runtime.HandlerPanic", "panicked");
+        error.stack_trace = vec!["frame 0".to_string(), "frame 1".to_string()];
+        assert!(error
+            .to_json()
+            .ends_with(r#""stackTrace":["frame 0","frame 1"]}"#));
+    }
+
+    #[test]
+    fn test_handler_error_display() {
+        let error = HandlerError::new("Function.Error", "boom");
+        assert_eq!(error.to_string(), "Function.Error: boom");
+    }
+}
@@ -7,41 +7,38 @@
 // This client ONLY supports the Lambda Runtime API:
 // - GET /2018-06-01/runtime/invocation/next
 // - POST /2018-06-01/runtime/invocation/{id}/response
+// - POST /2018-06-01/runtime/invocation/{id}/error
+// - POST /2018-06-01/runtime/init/error
 //
 // NOT supported (not needed for Lambda):
 // - HTTPS/TLS (Lambda Runtime API uses plain HTTP internally)
 // - Redirects, cookies, compression, etc.
 // - Connection pooling (single-threaded Lambda execution)
 // - Async/await (Lambda processes one event at a time)
-
-use std::io::{self, Read, Write};
-use std::net::TcpStream;
-
-/// Minimal HTTP client error
-#[derive(Debug)]
-pub enum HttpError {
-    /// I/O error
-    Io(io::Error),
-    /// Invalid response
-    InvalidResponse(String),
-}
-
-impl From<io::Error> for HttpError {
-    fn from(err: io::Error) -> Self {
-        HttpError::Io(err)
-    }
-}
-
-impl std::fmt::Display for HttpError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            HttpError::Io(e) => write!(f, "HTTP I/O error: {e}"),
-            HttpError::InvalidResponse(msg) => write!(f, "Invalid HTTP response: {msg}"),
-        }
-    }
-}
-
-impl std::error::Error for HttpError {}
+//
+// TCP I/O and response parsing live in ruchy-lambda-http-core (shared with
+// runtime-pure); this module maps responses onto `Context`. Connections are
+// opened through a `Dial` (TCP by default), so tests can serve the Runtime
+// API over in-memory streams instead of sockets.
+//
+// With pipelining (pipelining.rs) all requests but the init error share one
+// keep-alive connection, and a response POST carries the next `/next` GET
+// in the same write.
+//
+// Buffered `/next` reads are pre-sized from recent event sizes
+// (read_buffer.rs).
+
+use crate::context::Context;
+use crate::event_stream::EventStream;
+use crate::invocation::Invocation;
+use crate::pipelining::Pipelined;
+use crate::read_buffer::ReadBufferSizer;
+pub use ruchy_lambda_http_core::HttpError;
+use ruchy_lambda_http_core::{
+    parse_any_response_ref, parse_response, parse_response_ref, Dial, Response, ResponseStream,
+    TcpDialer, Transport, DEFAULT_MAX_RESPONSE_SIZE,
+};
+use std::sync::{Arc, Mutex, MutexGuard};
 
 /// Minimal HTTP client for Lambda Runtime API
 ///
@@ -54,325 +51,424 @@ impl std::error::Error for HttpError {}
 pub struct HttpClient {
     /// Lambda Runtime API endpoint (e.g., "127.0.0.1:9001")
     endpoint: String,
+    /// Maximum accepted `/next` response body size in bytes
+    max_response_size: usize,
+    /// Opens connections to `endpoint` ([`TcpDialer`] by default)
+    dialer: Arc<dyn Dial>,
+    /// Connection opened ahead of time by [`HttpClient::preconnect`],
+    /// consumed by the next GET
+    preconnected: Mutex<Option<Box<dyn Transport>>>,
+    /// Keep-alive connection when pipelining is enabled
+    pipeline: Option<Mutex<Pipelined>>,
+    /// Suggests `/next` read buffer sizes from recent events
+    read_buffer: ReadBufferSizer,
 }
 
 impl HttpClient {
     /// Create a new HTTP client for the given endpoint
     pub fn new(endpoint: String) -> Self {
-        Self { endpoint }
+        Self {
+            endpoint,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            dialer: Arc::new(TcpDialer),
+            preconnected: Mutex::new(None),
+            pipeline: None,
+            read_buffer: ReadBufferSizer::new(DEFAULT_MAX_RESPONSE_SIZE),
+        }
     }
 
-    /// Make a GET request and return the `request_id` header and response body
+    /// Keep one connection alive and pipeline `/next` behind response POSTs
+    ///
+    /// See [`HttpClient::post_then_next`].
+    pub fn with_pipelining(mut self, pipelining: bool) -> Self {
+        self.pipeline = pipelining.then(|| Mutex::new(Pipelined::default()));
+        self
+    }
+
+    /// Open connections through `dialer` instead of TCP
+    pub fn with_dialer(mut self, dialer: Arc<dyn Dial>) -> Self {
+        self.dialer = dialer;
+        self
+    }
+
+    /// Set the maximum accepted response body size in bytes
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self.read_buffer = ReadBufferSizer::new(max_response_size);
+        self
+    }
+
+    /// Make a GET request and return the invocation context and response body
     ///
     /// **Phase 3**: Converted to blocking I/O (no async/await)
-    /// **Phase 5**: Extract Lambda-Runtime-Aws-Request-Id header for event processing
+    /// **Phase 5**: Extract Lambda-Runtime-* headers for event processing
     ///
     /// # Returns
     ///
-    /// Returns `(request_id, body)` tuple where:
-    /// - `request_id` is extracted from `Lambda-Runtime-Aws-Request-Id` header
+    /// Returns `(context, body)` tuple where:
+    /// - `context` is built from the `Lambda-Runtime-*` response headers
     /// - `body` is the raw response body (user's event payload)
     ///
     /// # Errors
     ///
-    /// Returns `HttpError` if the request fails or response is invalid
-    pub fn get(&self, path: &str) -> Result<(String, String), HttpError> {
-        // Connect to endpoint (blocking)
-        let mut stream = TcpStream::connect(&self.endpoint)?;
-
-        // Build HTTP GET request
-        let request = format!(
-            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
-            path, self.endpoint
-        );
-
-        // Send request (blocking)
-        stream.write_all(request.as_bytes())?;
-        stream.flush()?;
-
-        // Read response (blocking)
+    /// Returns `HttpError` if the request fails or response is invalid, and
+    /// `HttpError::ResponseTooLarge` if the body exceeds the size limit
+    pub fn get(&self, path: &str) -> Result<(Context, String), HttpError> {
         let mut buffer = Vec::new();
-        stream.read_to_end(&mut buffer)?;
-
-        // Parse response with headers
-        Self::parse_response_with_headers(&buffer)
+        self.read_into(path, &mut buffer)?;
+        let response = parse_response(&buffer)?;
+        self.read_buffer.record(response.body.len());
+        Ok(Self::into_invocation(response))
     }
 
-    /// Make a POST request with a body and return the response status
+    /// Make a GET request into `buffer` and borrow the invocation from it
     ///
-    /// **Phase 3**: Converted to blocking I/O (no async/await)
+    /// Zero-copy variant of [`HttpClient::get`]: `buffer` is cleared and
+    /// reused, and the returned [`Invocation`] borrows from it.
     ///
     /// # Errors
     ///
-    /// Returns `HttpError` if the request fails or response is invalid
-    pub fn post(&self, path: &str, body: &str) -> Result<(), HttpError> {
-        // Connect to endpoint (blocking)
-        let mut stream = TcpStream::connect(&self.endpoint)?;
-
-        // Build HTTP POST request
-        let request = format!(
-            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-            path, self.endpoint, body.len(), body
-        );
-
-        // Send request (blocking)
-        stream.write_all(request.as_bytes())?;
-        stream.flush()?;
-
-        // Read response (we don't need the body, just verify it succeeded)
-        let mut buffer = vec![0u8; 1024];
-        let n = stream.read(&mut buffer)?;
+    /// Returns `HttpError` if the request fails or response is invalid, and
+    /// `HttpError::ResponseTooLarge` if the body exceeds the size limit
+    pub fn get_into<'buf>(
+        &self,
+        path: &str,
+        buffer: &'buf mut Vec<u8>,
+    ) -> Result<Invocation<'buf>, HttpError> {
+        self.read_into(path, buffer)?;
+        let response = parse_response_ref(buffer)?;
+        self.read_buffer.record(response.body.len());
+        Ok(Invocation::from_response(response))
+    }
 
-        // Check for 2xx status code
-        let response = String::from_utf8_lossy(&buffer[..n]);
-        if !response.contains("HTTP/1.1 2") {
-            return Err(HttpError::InvalidResponse(format!(
-                "POST request failed: {}",
-                response.lines().next().unwrap_or("unknown")
-            )));
+    /// Read the whole response to a GET of `path` into `buffer`
+    ///
+    /// `buffer` is first resized for the largest recent event.
+    fn read_into(&self, path: &str, buffer: &mut Vec<u8>) -> Result<(), HttpError> {
+        self.read_buffer.fit(buffer);
+        if let Some(pipeline) = &self.pipeline {
+            return self.pipelined_get(pipeline, path, buffer);
         }
 
-        Ok(())
+        let preconnected = self.take_preconnected().map(|stream| {
+            ruchy_lambda_http_core::get_into_on(
+                stream,
+                &self.endpoint,
+                path,
+                self.max_response_size,
+                buffer,
+            )
+        });
+
+        match preconnected {
+            // A stale pre-connected socket falls back to a fresh connection
+            Some(Err(HttpError::Io(_))) | None => ruchy_lambda_http_core::get_into_on(
+                self.connect()?,
+                &self.endpoint,
+                path,
+                self.max_response_size,
+                buffer,
+            ),
+            Some(result) => result,
+        }
     }
 
-    /// Parse HTTP response and extract Lambda `request_id` header + body
+    /// Make a GET request and return the body still on the connection
     ///
-    /// **Phase 5**: Extract Lambda-Runtime-Aws-Request-Id from response headers
+    /// Streaming variant of [`HttpClient::get`]: only the response head is
+    /// read before returning. With pipelining the connection stays with the
+    /// client, so the body is read up front and streamed from memory.
     ///
-    /// Returns `(request_id, body)` tuple
-    fn parse_response_with_headers(data: &[u8]) -> Result<(String, String), HttpError> {
-        let response = String::from_utf8_lossy(data);
-
-        // Find HTTP status line
-        let status_line = response
-            .lines()
-            .next()
-            .ok_or_else(|| HttpError::InvalidResponse("Empty response".to_string()))?;
-
-        // Check for 2xx status code
-        if !status_line.contains("HTTP/1.1 2") {
-            return Err(HttpError::InvalidResponse(format!(
-                "Non-2xx status: {status_line}"
-            )));
+    /// # Errors
+    ///
+    /// Returns `HttpError` if the request fails or response is invalid, and
+    /// `HttpError::ResponseTooLarge` if the advertised body exceeds the size
+    /// limit
+    pub fn get_stream(&self, path: &str) -> Result<(Context, EventStream), HttpError> {
+        if self.pipeline.is_some() {
+            let mut buffer = Vec::new();
+            self.read_into(path, &mut buffer)?;
+            let (context, body_start) = {
+                let response = parse_response_ref(&buffer)?;
+                self.read_buffer.record(response.body.len());
+                (
+                    Context::from_headers(response.header_pairs()),
+                    buffer.len() - response.body.len(),
+                )
+            };
+            return Ok((context, EventStream::buffered(buffer, body_start)));
         }
 
-        // Find headers section (between first line and \r\n\r\n)
-        let headers_start = response.find("\r\n").unwrap_or(0) + 2;
-        let body_start = response
-            .find("\r\n\r\n")
-            .ok_or_else(|| HttpError::InvalidResponse("No body separator found".to_string()))?
-            + 4;
-
-        let headers_section = &response[headers_start..body_start - 4];
-
-        // Extract Lambda-Runtime-Aws-Request-Id header
-        let request_id = headers_section
-            .lines()
-            .find(|line| {
-                line.to_lowercase()
-                    .starts_with("lambda-runtime-aws-request-id:")
-            })
-            .and_then(|line| line.split(':').nth(1))
-            .map_or_else(|| "unknown".to_string(), |id| id.trim().to_string());
-
-        let body = response[body_start..].to_string();
+        if let Some(stream) = self.take_preconnected() {
+            match ruchy_lambda_http_core::get_stream_on(
+                stream,
+                &self.endpoint,
+                path,
+                self.max_response_size,
+            ) {
+                Err(HttpError::Io(_)) => {}
+                result => return result.map(Self::into_stream),
+            }
+        }
 
-        Ok((request_id, body))
+        ruchy_lambda_http_core::get_stream_on(
+            self.connect()?,
+            &self.endpoint,
+            path,
+            self.max_response_size,
+        )
+        .map(Self::into_stream)
     }
 
-    /// Parse HTTP response and extract body
+    /// Open a connection now for the next GET to use
     ///
-    /// Note: Currently unused. Kept for potential future use cases.
-    #[allow(dead_code)]
-    fn parse_response(data: &[u8]) -> Result<String, HttpError> {
-        let response = String::from_utf8_lossy(data);
-
-        // Find HTTP status line
-        let status_line = response
-            .lines()
-            .next()
-            .ok_or_else(|| HttpError::InvalidResponse("Empty response".to_string()))?;
-
-        // Check for 2xx status code
-        if !status_line.contains("HTTP/1.1 2") {
-            return Err(HttpError::InvalidResponse(format!(
-                "Non-2xx status: {status_line}"
-            )));
-        }
-
-        // Find body (after \r\n\r\n)
-        let body_start = response
-            .find("\r\n\r\n")
-            .ok_or_else(|| HttpError::InvalidResponse("No body separator found".to_string()))?
-            + 4;
-
-        Ok(response[body_start..].to_string())
+    /// Moves TCP setup out of the first invocation into the pre-invoke
+    /// window. Replaces any connection opened by an earlier call.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError::Io` if the connection cannot be opened
+    pub fn preconnect(&self) -> Result<(), HttpError> {
+        let stream = self.connect()?;
+        *self
+            .preconnected
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(stream);
+        Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_parse_response_valid() {
-        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\n{\"test\":true}";
-        let body = HttpClient::parse_response(response).unwrap();
-        assert_eq!(body, "{\"test\":true}");
+    /// Whether a pre-opened connection is waiting to be used
+    #[cfg(test)]
+    fn is_preconnected(&self) -> bool {
+        self.preconnected
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .is_some()
     }
 
-    #[test]
-    fn test_parse_response_202() {
-        let response = b"HTTP/1.1 202 Accepted\r\nContent-Length: 0\r\n\r\n";
-        let body = HttpClient::parse_response(response).unwrap();
-        assert_eq!(body, "");
+    /// Open a new connection to the endpoint
+    fn connect(&self) -> Result<Box<dyn Transport>, HttpError> {
+        Ok(self.dialer.dial(&self.endpoint)?)
     }
 
-    #[test]
-    fn test_parse_response_404() {
-        let response = b"HTTP/1.1 404 Not Found\r\n\r\nNot found";
-        let result = HttpClient::parse_response(response);
-        assert!(result.is_err());
+    /// Take the pre-opened connection, if any
+    fn take_preconnected(&self) -> Option<Box<dyn Transport>> {
+        self.preconnected
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
     }
 
-    #[test]
-    fn test_parse_response_empty() {
-        let response = b"";
-        let result = HttpClient::parse_response(response);
-        assert!(result.is_err());
+    /// Read a GET response over the pipelined connection into `buffer`
+    fn pipelined_get(
+        &self,
+        pipeline: &Mutex<Pipelined>,
+        path: &str,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), HttpError> {
+        let mut pipeline = self.lock_pipeline(pipeline);
+        pipeline.get_into(&self.endpoint, path, self.max_response_size, buffer, || {
+            self.connect()
+        })
     }
 
-    #[test]
-    fn test_http_error_display() {
-        let error = HttpError::InvalidResponse("test error".to_string());
-        let msg = format!("{}", error);
-        assert!(msg.contains("Invalid HTTP response"));
-        assert!(msg.contains("test error"));
+    /// Lock the pipelined connection, handing it any pre-opened stream
+    fn lock_pipeline<'a>(&self, pipeline: &'a Mutex<Pipelined>) -> MutexGuard<'a, Pipelined> {
+        let mut pipeline = pipeline
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(stream) = self.take_preconnected() {
+            pipeline.adopt(stream);
+        }
+        pipeline
     }
 
-    // NEW TESTS: Increase coverage from 41.96% to ~80%+
-
-    #[test]
-    fn test_http_client_new() {
-        let client = HttpClient::new("127.0.0.1:9001".to_string());
-        assert_eq!(client.endpoint, "127.0.0.1:9001");
+    /// Make a POST request with extra request headers
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError` if the request fails or response is invalid
+    pub fn post_with_headers(
+        &self,
+        path: &str,
+        body: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<(), HttpError> {
+        ruchy_lambda_http_core::post_with_headers_on(
+            self.connect()?,
+            &self.endpoint,
+            path,
+            body,
+            headers,
+        )
     }
 
-    #[test]
-    fn test_parse_response_with_headers_valid() {
-        let response = b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: test-req-123\r\nContent-Length: 13\r\n\r\n{\"test\":true}";
-        let (request_id, body) = HttpClient::parse_response_with_headers(response).unwrap();
-        assert_eq!(request_id, "test-req-123");
-        assert_eq!(body, "{\"test\":true}");
+    /// POST like [`HttpClient::post_with_headers`], then GET `next_path`
+    ///
+    /// With pipelining, the GET is written together with the POST on the
+    /// kept connection and its response is read by the next `get*` call.
+    /// Without it, this is a plain POST and `next_path` is unused.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HttpError` if the POST fails or its response is non-2xx
+    pub fn post_then_next(
+        &self,
+        path: &str,
+        body: &str,
+        headers: &[(&str, &str)],
+        next_path: &str,
+    ) -> Result<(), HttpError> {
+        let Some(pipeline) = &self.pipeline else {
+            return self.post_with_headers(path, body, headers);
+        };
+
+        let mut pipeline = self.lock_pipeline(pipeline);
+        let response =
+            pipeline.post_then_next(&self.endpoint, path, body, headers, next_path, || {
+                self.connect()
+            })?;
+        let response = parse_any_response_ref(response)?;
+        if !response
+            .status_code()
+            .is_some_and(|code| (200..300).contains(&code))
+        {
+            return Err(HttpError::InvalidResponse(format!(
+                "POST request failed: {}",
+                response.status_line
+            )));
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_parse_response_with_headers_no_request_id() {
-        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\n{\"test\":true}";
-        let (request_id, body) = HttpClient::parse_response_with_headers(response).unwrap();
-        assert_eq!(request_id, "unknown");
-        assert_eq!(body, "{\"test\":true}");
+    /// Split a parsed `/next` response into invocation context and event body
+    ///
+    /// **Phase 5**: Extract Lambda-Runtime-* headers from response headers
+    fn into_invocation(response: Response) -> (Context, String) {
+        let context = Context::from_headers(response.header_pairs());
+        (context, response.body)
     }
 
-    #[test]
-    fn test_parse_response_with_headers_empty_response() {
-        let response = b"";
-        let result = HttpClient::parse_response_with_headers(response);
-        assert!(result.is_err());
-        if let Err(HttpError::InvalidResponse(msg)) = result {
-            assert!(msg.contains("Empty response"));
-        } else {
-            panic!("Expected InvalidResponse error");
-        }
+    /// Split a streaming `/next` response into invocation context and body
+    fn into_stream(response: ResponseStream<Box<dyn Transport>>) -> (Context, EventStream) {
+        let context = Context::from_headers(
+            response
+                .headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        (context, EventStream::new(response))
     }
+}
 
-    #[test]
-    fn test_parse_response_with_headers_non_2xx() {
-        let response = b"HTTP/1.1 404 Not Found\r\n\r\nNot found";
-        let result = HttpClient::parse_response_with_headers(response);
-        assert!(result.is_err());
-        if let Err(HttpError::InvalidResponse(msg)) = result {
-            assert!(msg.contains("Non-2xx status"));
-        } else {
-            panic!("Expected InvalidResponse error");
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruchy_lambda_http_core::parse_response;
 
-    #[test]
-    fn test_parse_response_with_headers_no_body_separator() {
-        let response = b"HTTP/1.1 200 OK\r\nContent-Length: 0";
-        let result = HttpClient::parse_response_with_headers(response);
-        assert!(result.is_err());
-        if let Err(HttpError::InvalidResponse(msg)) = result {
-            assert!(msg.contains("No body separator"));
-        } else {
-            panic!("Expected InvalidResponse error");
-        }
-    }
+    // Parser tests live in ruchy-lambda-http-core; these cover Context extraction
 
     #[test]
-    fn test_parse_response_with_headers_case_insensitive() {
-        // Lambda header with different casing
-        let response =
-            b"HTTP/1.1 200 OK\r\nLAMBDA-RUNTIME-AWS-REQUEST-ID: test-456\r\n\r\n{\"data\":true}";
-        let (request_id, body) = HttpClient::parse_response_with_headers(response).unwrap();
-        assert_eq!(request_id, "test-456");
-        assert_eq!(body, "{\"data\":true}");
+    fn test_http_client_new() {
+        let client = HttpClient::new("127.0.0.1:9001".to_string());
+        assert_eq!(client.endpoint, "127.0.0.1:9001");
+        assert_eq!(client.max_response_size, DEFAULT_MAX_RESPONSE_SIZE);
     }
 
     #[test]
-    fn test_parse_response_with_headers_whitespace_in_header() {
-        // Header with extra whitespace
-        let response =
-            b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id:   test-789  \r\n\r\n{\"ok\":true}";
-        let (request_id, body) = HttpClient::parse_response_with_headers(response).unwrap();
-        assert_eq!(request_id, "test-789");
-        assert_eq!(body, "{\"ok\":true}");
+    fn test_http_client_with_max_response_size() {
+        let client = HttpClient::new("127.0.0.1:9001".to_string()).with_max_response_size(1024);
+        assert_eq!(client.max_response_size, 1024);
     }
 
     #[test]
-    fn test_http_error_io_display() {
-        let io_error =
-            std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "Connection refused");
-        let error = HttpError::Io(io_error);
-        let msg = format!("{error}");
-        assert!(msg.contains("HTTP I/O error"));
+    fn test_preconnect_connection_refused() {
+        let client = HttpClient::new("127.0.0.1:19993".to_string());
+        assert!(matches!(client.preconnect(), Err(HttpError::Io(_))));
+        assert!(!client.is_preconnected());
     }
 
     #[test]
-    fn test_http_error_from_io() {
-        let io_error = std::io::Error::new(std::io::ErrorKind::TimedOut, "Timed out");
-        let http_error: HttpError = io_error.into();
-        assert!(matches!(http_error, HttpError::Io(_)));
+    fn test_get_uses_preconnected_stream() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = HttpClient::new(listener.local_addr().unwrap().to_string());
+        client.preconnect().unwrap();
+        assert!(client.is_preconnected());
+
+        // Single accept: the GET must travel over the pre-opened connection
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: pre-1\r\n\r\n{}");
+        });
+
+        let (context, body) = client.get("/next").unwrap();
+        assert_eq!(context.request_id, "pre-1");
+        assert_eq!(body, "{}");
+        assert!(!client.is_preconnected());
+        server.join().unwrap();
     }
 
     #[test]
-    fn test_parse_response_with_headers_empty_body() {
-        let response = b"HTTP/1.1 202 Accepted\r\nLambda-Runtime-Aws-Request-Id: req-empty\r\nContent-Length: 0\r\n\r\n";
-        let (request_id, body) = HttpClient::parse_response_with_headers(response).unwrap();
-        assert_eq!(request_id, "req-empty");
-        assert_eq!(body, "");
+    fn test_get_into_presizes_buffer_from_recent_events() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = HttpClient::new(listener.local_addr().unwrap().to_string());
+        let server = std::thread::spawn(move || {
+            for body in ["x".repeat(100_000), "{}".to_string()] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        let (_, body) = client.get("/next").unwrap();
+        assert_eq!(body.len(), 100_000);
+
+        // The small event lands in a buffer sized for the large one before it
+        let mut buffer = Vec::new();
+        let invocation = client.get_into("/next", &mut buffer).unwrap();
+        assert_eq!(invocation.body, b"{}");
+        assert!(buffer.capacity() >= 100_000);
+        server.join().unwrap();
     }
 
     #[test]
-    fn test_parse_response_with_headers_large_body() {
-        let large_body = "x".repeat(10000);
-        let response = format!(
-            "HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: req-large\r\nContent-Length: {}\r\n\r\n{}",
-            large_body.len(),
-            large_body
-        );
-        let (request_id, body) =
-            HttpClient::parse_response_with_headers(response.as_bytes()).unwrap();
-        assert_eq!(request_id, "req-large");
-        assert_eq!(body.len(), 10000);
+    fn test_into_invocation_no_request_id() {
+        let response =
+            parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 13\r\n\r\n{\"test\":true}")
+                .unwrap();
+        let (context, body) = HttpClient::into_invocation(response);
+        assert_eq!(context.request_id, "unknown");
+        assert_eq!(body, "{\"test\":true}");
     }
 
     #[test]
-    fn test_parse_response_with_headers_multiple_headers() {
-        let response = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nLambda-Runtime-Aws-Request-Id: multi-header\r\nX-Custom: value\r\n\r\n{\"multi\":true}";
-        let (request_id, body) = HttpClient::parse_response_with_headers(response).unwrap();
-        assert_eq!(request_id, "multi-header");
-        assert_eq!(body, "{\"multi\":true}");
+    fn test_into_invocation_metadata() {
+        let response = parse_response(b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: meta-1\r\nLambda-Runtime-Deadline-Ms: 1700000000000\r\nLambda-Runtime-Invoked-Function-Arn: arn:aws:lambda:us-east-1:123456789012:function:fn\r\nLambda-Runtime-Trace-Id: Root=1-abc;Parent=def;Sampled=1\r\n\r\n{}").unwrap();
+        let (context, body) = HttpClient::into_invocation(response);
+        assert_eq!(context.request_id, "meta-1");
+        assert_eq!(context.deadline_ms, 1_700_000_000_000);
+        assert_eq!(
+            context.invoked_function_arn,
+            "arn:aws:lambda:us-east-1:123456789012:function:fn"
+        );
+        assert_eq!(
+            context.trace_id.as_deref(),
+            Some("Root=1-abc;Parent=def;Sampled=1")
+        );
+        assert_eq!(body, "{}");
     }
 }
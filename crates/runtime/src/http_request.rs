@@ -0,0 +1,401 @@
+// HTTP Proxy Requests
+//
+// API Gateway REST (v1), HTTP API (v2) and ALB all deliver HTTP requests as
+// JSON with a string body, base64-encoded when it is binary. HttpRequest
+// normalizes the three shapes:
+//
+//   v1 / ALB: {"httpMethod": "POST", "path": "/x", "headers": {...}, "body": "...", "isBase64Encoded": true}
+//   v2:       {"rawPath": "/x", "requestContext": {"http": {"method": "POST"}}, "headers": {...}, ...}
+//
+// Header names are lowercased (v2 already does this; v1 and ALB pass them
+// through as sent). v2 cookies are folded into a `cookie` header.
+//
+// The query string is kept raw (`a=1&b=x%2Fy`) for the decoding iterators:
+// v2 sends it as rawQueryString, ALB passes parameters through still
+// encoded, and REST APIs only send decoded parameters, which are
+// re-encoded here.
+
+use crate::inflate::{decompress, DecompressError, DEFAULT_DECOMPRESSED_LIMIT};
+use crate::query_string::{encode, Cookies, QueryPairs};
+use ruchy_lambda_simd::base64;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// API Gateway or ALB proxy request
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::HttpRequest;
+///
+/// let body = r#"{"httpMethod":"POST","path":"/logs",
+///     "headers":{"Content-Encoding":"gzip"},"isBase64Encoded":true,
+///     "body":"H4sIAAAAAAACA8vIBACsKpPYAgAAAA=="}"#;
+///
+/// let request = HttpRequest::from_json(body).unwrap();
+/// assert_eq!(request.header("content-encoding"), Some("gzip"));
+/// assert_eq!(request.body_decompressed().unwrap(), b"hi");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// HTTP method, uppercase
+    pub method: String,
+    /// Request path
+    pub path: String,
+    /// Headers by lowercase name (multi-value headers joined with `,`)
+    pub headers: HashMap<String, String>,
+    /// Query string parameters
+    pub query: HashMap<String, String>,
+    /// Query string as sent, still percent-encoded (without `?`)
+    pub raw_query: String,
+    /// Body as delivered (base64 if `is_base64_encoded`)
+    pub body: Option<String>,
+    /// Whether `body` is base64-encoded
+    pub is_base64_encoded: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawRequest {
+    http_method: Option<String>,
+    path: Option<String>,
+    raw_path: Option<String>,
+    #[serde(default)]
+    request_context: RawRequestContext,
+    headers: Option<HashMap<String, String>>,
+    multi_value_headers: Option<HashMap<String, Vec<String>>>,
+    query_string_parameters: Option<HashMap<String, String>>,
+    multi_value_query_string_parameters: Option<HashMap<String, Vec<String>>>,
+    raw_query_string: Option<String>,
+    cookies: Option<Vec<String>>,
+    body: Option<String>,
+    #[serde(default)]
+    is_base64_encoded: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct RawRequestContext {
+    http: Option<RawHttp>,
+    elb: Option<serde::de::IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct RawHttp {
+    method: String,
+}
+
+impl HttpRequest {
+    /// Parse an API Gateway (v1 or v2) or ALB proxy event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a JSON object of
+    /// the expected shape.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        let raw: RawRequest = serde_json::from_str(event_body)?;
+
+        let mut headers: HashMap<String, String> = raw
+            .headers
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| (name.to_ascii_lowercase(), value))
+            .collect();
+        for (name, values) in raw.multi_value_headers.unwrap_or_default() {
+            headers
+                .entry(name.to_ascii_lowercase())
+                .or_insert_with(|| values.join(","));
+        }
+        if let Some(cookies) = raw.cookies.filter(|cookies| !cookies.is_empty()) {
+            headers
+                .entry("cookie".to_string())
+                .or_insert_with(|| cookies.join("; "));
+        }
+
+        let raw_query = match raw.raw_query_string {
+            Some(raw_query) => raw_query,
+            None => rebuild_query(
+                raw.multi_value_query_string_parameters.as_ref(),
+                raw.query_string_parameters.as_ref(),
+                raw.request_context.elb.is_some(),
+            ),
+        };
+
+        Ok(Self {
+            method: raw
+                .http_method
+                .or(raw.request_context.http.map(|http| http.method))
+                .unwrap_or_default()
+                .to_ascii_uppercase(),
+            path: raw.raw_path.or(raw.path).unwrap_or_default(),
+            headers,
+            query: raw.query_string_parameters.unwrap_or_default(),
+            raw_query,
+            body: raw.body,
+            is_base64_encoded: raw.is_base64_encoded,
+        })
+    }
+
+    /// Header value (case-insensitive name)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Decoded query parameters in order, repeated names included
+    #[must_use]
+    pub fn query_pairs(&self) -> QueryPairs<'_> {
+        QueryPairs::new(&self.raw_query)
+    }
+
+    /// First value of query parameter `name`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::HttpRequest;
+    ///
+    /// let request = HttpRequest::from_json(
+    ///     r#"{"version":"2.0","rawPath":"/search","rawQueryString":"q=a+b&dir=x%2Fy&tag=1&tag=2",
+    ///         "cookies":["session=s1","theme=dark"],"requestContext":{"http":{"method":"GET"}}}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(request.query("q").as_deref(), Some("a b"));
+    /// assert_eq!(request.query("dir").as_deref(), Some("x/y"));
+    /// assert_eq!(request.query_all("tag").collect::<Vec<_>>(), ["1", "2"]);
+    /// assert_eq!(request.cookie("session").as_deref(), Some("s1"));
+    /// ```
+    #[must_use]
+    pub fn query(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// All values of query parameter `name`, in order
+    pub fn query_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = Cow<'a, str>> + 'a {
+        self.query_pairs()
+            .filter(move |(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Cookies from the `Cookie` header (v2 `cookies` included)
+    #[must_use]
+    pub fn cookies(&self) -> Cookies<'_> {
+        Cookies::new(self.header("cookie").unwrap_or_default())
+    }
+
+    /// Value of cookie `name`
+    #[must_use]
+    pub fn cookie(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.cookies()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Body bytes, base64-decoded if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DecompressError::Base64`] if an `isBase64Encoded` body is
+    /// not valid base64.
+    pub fn body_bytes(&self) -> Result<Vec<u8>, DecompressError> {
+        let body = self.body.as_deref().unwrap_or_default();
+        if self.is_base64_encoded {
+            base64::decode(body.as_bytes()).map_err(DecompressError::Base64)
+        } else {
+            Ok(body.as_bytes().to_vec())
+        }
+    }
+
+    /// Body bytes with `Content-Encoding` (gzip / deflate) removed
+    ///
+    /// Output is capped at [`DEFAULT_DECOMPRESSED_LIMIT`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for invalid base64, unsupported encodings, corrupt
+    /// data or oversized output.
+    pub fn body_decompressed(&self) -> Result<Vec<u8>, DecompressError> {
+        self.body_decompressed_with_limit(DEFAULT_DECOMPRESSED_LIMIT)
+    }
+
+    /// [`body_decompressed`](Self::body_decompressed) with a custom size cap
+    ///
+    /// # Errors
+    ///
+    /// As for [`body_decompressed`](Self::body_decompressed).
+    pub fn body_decompressed_with_limit(&self, limit: usize) -> Result<Vec<u8>, DecompressError> {
+        let body = self.body_bytes()?;
+        match self.header("content-encoding") {
+            Some(encoding) => decompress(encoding, &body, limit),
+            None => Ok(body),
+        }
+    }
+}
+
+/// Raw query string from REST API / ALB parameters
+///
+/// REST APIs decode parameters, so they are re-encoded; ALB leaves them
+/// encoded as sent. Multi-value parameters win over single-value ones.
+fn rebuild_query(
+    multi_value: Option<&HashMap<String, Vec<String>>>,
+    single_value: Option<&HashMap<String, String>>,
+    already_encoded: bool,
+) -> String {
+    let mut pairs: Vec<(&str, &str)> = match (multi_value, single_value) {
+        (Some(multi_value), _) => multi_value
+            .iter()
+            .flat_map(|(name, values)| {
+                values
+                    .iter()
+                    .map(move |value| (name.as_str(), value.as_str()))
+            })
+            .collect(),
+        (None, Some(single_value)) => single_value
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect(),
+        (None, None) => return String::new(),
+    };
+    // HashMap order is arbitrary; values of one name keep their order
+    pairs.sort_by_key(|(name, _)| *name);
+
+    let mut query = String::new();
+    for (name, value) in pairs {
+        if !query.is_empty() {
+            query.push('&');
+        }
+        if already_encoded {
+            query.push_str(name);
+            query.push('=');
+            query.push_str(value);
+        } else {
+            encode(name, &mut query);
+            query.push('=');
+            encode(value, &mut query);
+        }
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    #[test]
+    fn test_v1_and_alb() {
+        let request = HttpRequest::from_json(
+            r#"{"httpMethod":"post","path":"/orders","headers":{"X-Id":"1"},
+                "multiValueHeaders":{"Accept":["a","b"],"X-Id":["ignored"]},
+                "queryStringParameters":{"page":"2"},"body":"{}","isBase64Encoded":false}"#,
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/orders");
+        assert_eq!(request.header("x-id"), Some("1"));
+        assert_eq!(request.header("ACCEPT"), Some("a,b"));
+        assert_eq!(request.query["page"], "2");
+        assert_eq!(request.body_decompressed().unwrap(), b"{}");
+    }
+
+    #[test]
+    fn test_v2_with_nulls() {
+        let request = HttpRequest::from_json(
+            r#"{"version":"2.0","rawPath":"/a","requestContext":{"http":{"method":"GET"}},
+                "headers":null,"queryStringParameters":null,"body":null}"#,
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/a");
+        assert!(request.headers.is_empty());
+        assert!(request.body_decompressed().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_sources() {
+        // REST API: decoded values are re-encoded, repeated keys kept
+        let rest = HttpRequest::from_json(
+            r#"{"httpMethod":"GET","path":"/","queryStringParameters":{"a":"2","p":"x/y z"},
+                "multiValueQueryStringParameters":{"a":["1","2"],"p":["x/y z"]}}"#,
+        )
+        .unwrap();
+        assert_eq!(rest.raw_query, "a=1&a=2&p=x%2Fy%20z");
+        assert_eq!(rest.query_all("a").collect::<Vec<_>>(), ["1", "2"]);
+        assert_eq!(rest.query("p").as_deref(), Some("x/y z"));
+
+        // ALB: parameters arrive still encoded
+        let alb = HttpRequest::from_json(
+            r#"{"httpMethod":"GET","path":"/","requestContext":{"elb":{"targetGroupArn":"arn"}},
+                "queryStringParameters":{"p":"x%2Fy+z"}}"#,
+        )
+        .unwrap();
+        assert_eq!(alb.query("p").as_deref(), Some("x/y z"));
+
+        // v2: raw query string wins, + is a space and %2B a plus
+        let v2 = HttpRequest::from_json(
+            r#"{"rawPath":"/","rawQueryString":"q=1+1%2B1","queryStringParameters":{"q":"1 1+1"}}"#,
+        )
+        .unwrap();
+        assert_eq!(v2.query("q").as_deref(), Some("1 1+1"));
+        assert_eq!(v2.query("missing"), None);
+        assert!(HttpRequest::default().query_pairs().next().is_none());
+    }
+
+    #[test]
+    fn test_cookies_from_header_and_v2() {
+        let v1 = HttpRequest::from_json(
+            r#"{"httpMethod":"GET","path":"/","headers":{"Cookie":"a=1; session=abc"}}"#,
+        )
+        .unwrap();
+        assert_eq!(v1.cookie("session").as_deref(), Some("abc"));
+        assert_eq!(v1.cookies().count(), 2);
+
+        let v2 = HttpRequest::from_json(r#"{"rawPath":"/","cookies":["a=1","b=%20"]}"#).unwrap();
+        assert_eq!(v2.header("cookie"), Some("a=1; b=%20"));
+        assert_eq!(v2.cookie("b").as_deref(), Some(" "));
+        assert_eq!(v2.cookie("c"), None);
+    }
+
+    #[test]
+    fn test_deflate_body() {
+        let compressed = compress_to_vec_zlib(br#"{"a":1}"#, 6);
+        let request = HttpRequest {
+            headers: HashMap::from([("content-encoding".to_string(), "deflate".to_string())]),
+            body: Some(base64::encode(&compressed)),
+            is_base64_encoded: true,
+            ..HttpRequest::default()
+        };
+        assert_eq!(request.body_decompressed().unwrap(), br#"{"a":1}"#);
+        assert_eq!(
+            request.body_decompressed_with_limit(3),
+            Err(DecompressError::TooLarge { limit: 3 })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let bad_base64 = HttpRequest {
+            body: Some("***".to_string()),
+            is_base64_encoded: true,
+            ..HttpRequest::default()
+        };
+        assert!(matches!(
+            bad_base64.body_decompressed(),
+            Err(DecompressError::Base64(_))
+        ));
+
+        let brotli = HttpRequest {
+            headers: HashMap::from([("content-encoding".to_string(), "br".to_string())]),
+            body: Some("x".to_string()),
+            ..HttpRequest::default()
+        };
+        assert_eq!(
+            brotli.body_decompressed().unwrap_err().to_string(),
+            "Unsupported content encoding: br"
+        );
+    }
+}
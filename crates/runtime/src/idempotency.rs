@@ -0,0 +1,414 @@
+// Idempotency
+//
+// Async sources (SQS, EventBridge, S3 notifications) deliver at least once,
+// so the same event can reach a function twice. Idempotency derives a key
+// per event, looks it up in a pluggable store and, for a duplicate, returns
+// the response recorded the first time instead of running the handler
+// again.
+//
+// Keys:
+// - RequestId:    Lambda request id (retries of the same async invoke)
+// - PayloadHash:  FNV-1a 64-bit hash of the raw event body
+// - JsonField:    a field inside the event (e.g. "detail.orderId")
+//
+// InMemoryStore only dedupes within one warm container (its TTLs follow an
+// injectable clock); persistent stores (DynamoDB, ...) plug in through
+// IdempotencyStore.
+
+use crate::clock::{Clock, SharedClock};
+use crate::context::Context;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// Default time a recorded response is replayed for (1 hour)
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_hours(1);
+
+/// Default number of keys kept by [`InMemoryStore`]
+pub const DEFAULT_STORE_CAPACITY: usize = 1024;
+
+/// How the idempotency key is derived from an event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum KeySource {
+    /// Lambda request id (`Context::request_id`)
+    #[default]
+    RequestId,
+    /// Hash of the raw event body
+    PayloadHash,
+    /// Value at a dot path in the JSON event (falls back to `PayloadHash`
+    /// when absent)
+    JsonField(String),
+}
+
+impl KeySource {
+    /// Idempotency key for an event
+    #[must_use]
+    pub fn key(&self, context: &Context, event_body: &str) -> String {
+        match self {
+            Self::RequestId => format!("request:{}", context.request_id),
+            Self::PayloadHash => payload_key(event_body),
+            Self::JsonField(path) => json_field(event_body, path)
+                .map_or_else(|| payload_key(event_body), |value| format!("field:{value}")),
+        }
+    }
+}
+
+/// Recorded responses keyed by idempotency key
+pub trait IdempotencyStore: Send + Sync {
+    /// Response recorded for `key`, if not expired
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Record `response` for `key` for `ttl`
+    fn put(&self, key: &str, response: &str, ttl: Duration);
+}
+
+/// Per-process store for warm containers
+///
+/// Bounded: when full, expired entries are purged first, then the entry
+/// closest to expiry is evicted.
+#[derive(Debug)]
+pub struct InMemoryStore {
+    entries: Mutex<HashMap<String, (String, SystemTime)>>,
+    capacity: usize,
+    clock: SharedClock,
+}
+
+impl InMemoryStore {
+    /// Store holding up to [`DEFAULT_STORE_CAPACITY`] keys
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_STORE_CAPACITY)
+    }
+
+    /// Store holding up to `capacity` keys (at least 1)
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            capacity: capacity.max(1),
+            clock: SharedClock::default(),
+        }
+    }
+
+    /// Expire entries by `clock` instead of the system clock
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = SharedClock::new(clock);
+        self
+    }
+
+    /// Number of recorded keys (including expired, not yet purged ones)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.lock().map_or(0, |entries| entries.len())
+    }
+
+    /// Whether no keys are recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdempotencyStore for InMemoryStore {
+    fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().ok()?;
+        match entries.get(key) {
+            Some((response, expires)) if *expires > self.clock.now() => Some(response.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put(&self, key: &str, response: &str, ttl: Duration) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let now = self.clock.now();
+
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            entries.retain(|_, (_, expires)| *expires > now);
+        }
+        if entries.len() >= self.capacity && !entries.contains_key(key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (_, expires))| *expires)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(key.to_string(), (response.to_string(), now + ttl));
+    }
+}
+
+/// Replays recorded responses for duplicate events
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, Idempotency, InMemoryStore, KeySource};
+///
+/// let idempotency = Idempotency::new(InMemoryStore::new()).with_key(KeySource::PayloadHash);
+/// let event = r#"{"orderId":42}"#;
+///
+/// let mut calls = 0;
+/// for _ in 0..2 {
+///     let response = idempotency.run(&Context::default(), event, |_, _| {
+///         calls += 1;
+///         r#"{"statusCode":200,"body":"charged"}"#.to_string()
+///     });
+///     assert!(response.contains("charged"));
+/// }
+/// assert_eq!(calls, 1);
+/// ```
+pub struct Idempotency<S> {
+    store: S,
+    key: KeySource,
+    ttl: Duration,
+}
+
+impl<S: IdempotencyStore> Idempotency<S> {
+    /// Key on the request id, replaying for [`DEFAULT_IDEMPOTENCY_TTL`]
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            key: KeySource::default(),
+            ttl: DEFAULT_IDEMPOTENCY_TTL,
+        }
+    }
+
+    /// Set how the key is derived
+    #[must_use]
+    pub fn with_key(mut self, key: KeySource) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Set how long a response is replayed
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The underlying store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Run `handler` unless this event was already handled
+    ///
+    /// Responses with a 5xx `statusCode` are not recorded, so a failed
+    /// attempt can be retried.
+    pub fn run(
+        &self,
+        context: &Context,
+        event_body: &str,
+        handler: impl FnOnce(&Context, &str) -> String,
+    ) -> String {
+        let key = self.key.key(context, event_body);
+        if let Some(recorded) = self.store.get(&key) {
+            return recorded;
+        }
+
+        let response = handler(context, event_body);
+        if !is_server_error(&response) {
+            self.store.put(&key, &response, self.ttl);
+        }
+        response
+    }
+}
+
+impl<S> fmt::Debug for Idempotency<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Idempotency")
+            .field("key", &self.key)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `payload:<16 hex>` FNV-1a 64-bit hash of the body
+fn payload_key(event_body: &str) -> String {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = event_body.bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    });
+    format!("payload:{hash:016x}")
+}
+
+/// Scalar at a dot path in a JSON event, as text
+fn json_field(event_body: &str, path: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(event_body).ok()?;
+    let value = path
+        .split('.')
+        .try_fold(&event, |value, segment| value.as_object()?.get(segment))?;
+    match value {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        serde_json::Value::Bool(flag) => Some(flag.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a proxy response reports a 5xx status
+fn is_server_error(response: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(response)
+        .ok()
+        .and_then(|value| value.get("statusCode")?.as_u64())
+        .is_some_and(|status| status >= 500)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::thread;
+
+    fn context(request_id: &str) -> Context {
+        Context {
+            request_id: request_id.to_string(),
+            ..Context::default()
+        }
+    }
+
+    #[test]
+    fn test_key_sources() {
+        let ctx = context("req-1");
+        let event = r#"{"detail":{"orderId":42,"nested":{}}}"#;
+
+        assert_eq!(KeySource::RequestId.key(&ctx, event), "request:req-1");
+
+        let hash = KeySource::PayloadHash.key(&ctx, event);
+        assert!(hash.starts_with("payload:"));
+        assert_eq!(hash.len(), "payload:".len() + 16);
+        assert_eq!(hash, KeySource::PayloadHash.key(&context("other"), event));
+        assert_ne!(hash, KeySource::PayloadHash.key(&ctx, "{}"));
+
+        assert_eq!(
+            KeySource::JsonField("detail.orderId".to_string()).key(&ctx, event),
+            "field:42"
+        );
+        // Missing or non-scalar fields fall back to the payload hash
+        assert_eq!(
+            KeySource::JsonField("detail.nested".to_string()).key(&ctx, event),
+            hash
+        );
+        assert_eq!(
+            KeySource::JsonField("missing".to_string()).key(&ctx, event),
+            hash
+        );
+    }
+
+    #[test]
+    fn test_payload_hash_known_value() {
+        // FNV-1a 64 test vector
+        assert_eq!(payload_key("a"), "payload:af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn test_duplicate_request_replays_response() {
+        let idempotency = Idempotency::new(InMemoryStore::new());
+        let mut calls = 0;
+
+        for _ in 0..3 {
+            let response = idempotency.run(&context("req-1"), "{}", |_, _| {
+                calls += 1;
+                format!("response {calls}")
+            });
+            assert_eq!(response, "response 1");
+        }
+        let other = idempotency.run(&context("req-2"), "{}", |_, _| "fresh".to_string());
+
+        assert_eq!(calls, 1);
+        assert_eq!(other, "fresh");
+        assert_eq!(idempotency.store().len(), 2);
+    }
+
+    #[test]
+    fn test_server_errors_not_recorded() {
+        let idempotency = Idempotency::new(InMemoryStore::new());
+        let failed = idempotency.run(&context("req-1"), "{}", |_, _| {
+            r#"{"statusCode":503}"#.to_string()
+        });
+        assert_eq!(failed, r#"{"statusCode":503}"#);
+        assert!(idempotency.store().is_empty());
+
+        let retried = idempotency.run(&context("req-1"), "{}", |_, _| {
+            r#"{"statusCode":200}"#.to_string()
+        });
+        assert_eq!(retried, r#"{"statusCode":200}"#);
+        assert_eq!(idempotency.store().len(), 1);
+    }
+
+    #[test]
+    fn test_expired_entries_are_not_replayed() {
+        let store = InMemoryStore::new();
+        store.put("k", "old", Duration::ZERO);
+        assert_eq!(store.get("k"), None);
+        assert!(store.is_empty());
+
+        store.put("k", "new", DEFAULT_IDEMPOTENCY_TTL);
+        assert_eq!(store.get("k").as_deref(), Some("new"));
+    }
+
+    #[test]
+    fn test_ttl_follows_clock() {
+        let clock = ManualClock::at_millis(1_700_000_000_000);
+        let store = InMemoryStore::new().with_clock(clock.clone());
+        store.put("k", "recorded", Duration::from_mins(1));
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(store.get("k").as_deref(), Some("recorded"));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(store.get("k"), None);
+    }
+
+    #[test]
+    fn test_capacity_evicts_closest_to_expiry() {
+        let store = InMemoryStore::with_capacity(2);
+        store.put("short", "1", Duration::from_secs(10));
+        store.put("long", "2", Duration::from_secs(100));
+        store.put("third", "3", Duration::from_secs(50));
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get("short"), None);
+        assert_eq!(store.get("long").as_deref(), Some("2"));
+        assert_eq!(store.get("third").as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_store_is_shareable_across_threads() {
+        let idempotency = std::sync::Arc::new(
+            Idempotency::new(InMemoryStore::new()).with_key(KeySource::PayloadHash),
+        );
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let idempotency = std::sync::Arc::clone(&idempotency);
+                thread::spawn(move || {
+                    idempotency.run(&context("req"), &format!("{{\"n\":{i}}}"), |_, body| {
+                        body.to_string()
+                    })
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(idempotency.store().len(), 4);
+    }
+}
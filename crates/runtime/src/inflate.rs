@@ -0,0 +1,625 @@
+// Gzip / Deflate Decompression (zero-dependency)
+//
+// A small DEFLATE decoder (RFC 1951) with the zlib (RFC 1950) and gzip
+// (RFC 1952) wrappers, for request bodies sent with Content-Encoding. It
+// follows the structure of zlib's reference decoder "puff": canonical
+// Huffman tables decoded a bit at a time. That is slower than a table-driven
+// inflater but small, and request bodies are bounded by the 6 MB payload
+// limit.
+//
+// Output is capped so a small compressed body cannot expand without bound.
+
+// Narrowing casts here are on values already bounded by the format
+// (literals < 256, 3-bit code lengths, CRC low byte, ISIZE modulo 2^32)
+#![allow(clippy::cast_possible_truncation)]
+
+use std::fmt;
+
+/// Default cap on decompressed body size (32 MiB)
+pub const DEFAULT_DECOMPRESSED_LIMIT: usize = 32 * 1024 * 1024;
+
+/// Body decompression failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecompressError {
+    /// `Content-Encoding` other than gzip, deflate or identity
+    UnsupportedEncoding(String),
+    /// `isBase64Encoded` body that is not valid base64
+    Base64(ruchy_lambda_simd::base64::DecodeError),
+    /// Malformed compressed data
+    Corrupt(&'static str),
+    /// Output would exceed the size limit
+    TooLarge {
+        /// Limit in bytes
+        limit: usize,
+    },
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedEncoding(encoding) => {
+                write!(f, "Unsupported content encoding: {encoding}")
+            }
+            Self::Base64(e) => write!(f, "Invalid base64 body: {e}"),
+            Self::Corrupt(reason) => write!(f, "Corrupt compressed body: {reason}"),
+            Self::TooLarge { limit } => {
+                write!(f, "Decompressed body exceeds {limit} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecompressError {}
+
+type Result<T> = std::result::Result<T, DecompressError>;
+
+/// Undo a `Content-Encoding` header value
+///
+/// Multiple codings (`gzip, identity`) are removed in reverse order.
+/// `deflate` accepts both zlib-wrapped and raw streams, since clients
+/// disagree about which one the name means.
+///
+/// # Errors
+///
+/// Returns an error for unknown codings, corrupt data or output larger
+/// than `limit`.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::decompress;
+///
+/// // "hi" gzip-compressed
+/// let gzip = [
+///     0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 2, 3, 0xcb, 0xc8, 4, 0, 0xac, 0x2a, 0x93, 0xd8, 2, 0, 0, 0,
+/// ];
+/// assert_eq!(decompress("gzip", &gzip, 1024).unwrap(), b"hi");
+/// assert_eq!(decompress("identity", b"hi", 1024).unwrap(), b"hi");
+/// assert!(decompress("br", b"", 1024).is_err());
+/// ```
+pub fn decompress(content_encoding: &str, data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let mut body = data.to_vec();
+    for coding in content_encoding.rsplit(',') {
+        let coding = coding.trim().to_ascii_lowercase();
+        body = match coding.as_str() {
+            "" | "identity" => body,
+            "gzip" | "x-gzip" => gunzip(&body, limit)?,
+            "deflate" => {
+                if is_zlib_header(&body) {
+                    zlib(&body, limit)?
+                } else {
+                    inflate(&body, limit)?.0
+                }
+            }
+            _ => return Err(DecompressError::UnsupportedEncoding(coding)),
+        };
+        if body.len() > limit {
+            return Err(DecompressError::TooLarge { limit });
+        }
+    }
+    Ok(body)
+}
+
+fn corrupt<T>(reason: &'static str) -> Result<T> {
+    Err(DecompressError::Corrupt(reason))
+}
+
+fn is_zlib_header(data: &[u8]) -> bool {
+    data.len() >= 2
+        && data[0] & 0x0f == 8
+        && data[0] >> 4 <= 7
+        && data[1] & 0x20 == 0
+        && (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 == 0
+}
+
+/// Decode a zlib stream and verify its Adler-32 checksum
+fn zlib(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    if !is_zlib_header(data) {
+        return corrupt("invalid zlib header");
+    }
+    let (out, used) = inflate(&data[2..], limit)?;
+    let Some(trailer) = data.get(2 + used..2 + used + 4) else {
+        return corrupt("missing zlib checksum");
+    };
+    if u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]) != adler32(&out) {
+        return corrupt("zlib checksum mismatch");
+    }
+    Ok(out)
+}
+
+/// Decode one or more gzip members and verify their CRC-32 checksums
+fn gunzip(mut data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    const FHCRC: u8 = 2;
+    const FEXTRA: u8 = 4;
+    const FNAME: u8 = 8;
+    const FCOMMENT: u8 = 16;
+
+    let mut out = Vec::new();
+    loop {
+        if data.len() < 10 || data[0] != 0x1f || data[1] != 0x8b || data[2] != 8 {
+            return corrupt("invalid gzip header");
+        }
+        let flags = data[3];
+        let mut pos = 10;
+        if flags & FEXTRA != 0 {
+            let Some(len) = data.get(pos..pos + 2) else {
+                return corrupt("truncated gzip header");
+            };
+            pos += 2 + usize::from(u16::from_le_bytes([len[0], len[1]]));
+        }
+        for flag in [FNAME, FCOMMENT] {
+            if flags & flag != 0 {
+                let Some(end) = data
+                    .get(pos..)
+                    .and_then(|rest| rest.iter().position(|&b| b == 0))
+                else {
+                    return corrupt("truncated gzip header");
+                };
+                pos += end + 1;
+            }
+        }
+        if flags & FHCRC != 0 {
+            pos += 2;
+        }
+        let Some(stream) = data.get(pos..) else {
+            return corrupt("truncated gzip header");
+        };
+
+        let (member, used) = inflate(stream, limit.saturating_sub(out.len()))?;
+        let Some(trailer) = stream.get(used..used + 8) else {
+            return corrupt("missing gzip trailer");
+        };
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if crc != crc32(&member) {
+            return corrupt("gzip checksum mismatch");
+        }
+        if size != member.len() as u32 {
+            return corrupt("gzip length mismatch");
+        }
+        out.extend_from_slice(&member);
+
+        data = &stream[used + 8..];
+        if data.is_empty() {
+            return Ok(out);
+        }
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 bytes is the most that can be summed before `b` overflows
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    b << 16 | a
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut n = 0;
+        while n < 256 {
+            let mut c = n as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 == 0 {
+                    c >> 1
+                } else {
+                    0xedb8_8320 ^ (c >> 1)
+                };
+                k += 1;
+            }
+            table[n] = c;
+            n += 1;
+        }
+        table
+    };
+
+    !data.iter().fold(!0u32, |crc, &byte| {
+        TABLE[usize::from((crc as u8) ^ byte)] ^ (crc >> 8)
+    })
+}
+
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which code length code lengths are sent
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buf: u32,
+    count: u32,
+}
+
+impl Bits<'_> {
+    fn need(&mut self, n: u32) -> Result<u32> {
+        while self.count < n {
+            let Some(&byte) = self.data.get(self.pos) else {
+                return corrupt("unexpected end of stream");
+            };
+            self.pos += 1;
+            self.buf |= u32::from(byte) << self.count;
+            self.count += 8;
+        }
+        let value = self.buf & ((1 << n) - 1);
+        self.buf >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    /// Drop the remaining bits of the current byte
+    fn align(&mut self) {
+        self.buf = 0;
+        self.count = 0;
+    }
+}
+
+/// Canonical Huffman code: number of codes per length and symbols in code order
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Result<Self> {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return corrupt("over-subscribed Huffman code");
+            }
+        }
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in (0u16..).zip(lengths) {
+            if len != 0 {
+                symbols[usize::from(offsets[usize::from(len)])] = symbol;
+                offsets[usize::from(len)] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, bits: &mut Bits<'_>) -> Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &self.counts[1..] {
+            code |= i32::from(bits.need(1)? == 1);
+            let count = i32::from(count);
+            if code - count < first {
+                #[allow(clippy::cast_sign_loss)]
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        corrupt("invalid Huffman code")
+    }
+}
+
+/// Decode a raw DEFLATE stream; returns the output and bytes consumed
+fn inflate(data: &[u8], limit: usize) -> Result<(Vec<u8>, usize)> {
+    let mut bits = Bits {
+        data,
+        pos: 0,
+        buf: 0,
+        count: 0,
+    };
+    let mut out = Vec::with_capacity(data.len().saturating_mul(3).min(limit));
+
+    loop {
+        let last = bits.need(1)? == 1;
+        match bits.need(2)? {
+            0 => stored(&mut bits, &mut out, limit)?,
+            1 => {
+                let (lengths, distances) = fixed_tables()?;
+                codes(&mut bits, &mut out, &lengths, &distances, limit)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_tables(&mut bits)?;
+                codes(&mut bits, &mut out, &lengths, &distances, limit)?;
+            }
+            _ => return corrupt("invalid block type"),
+        }
+        if last {
+            return Ok((out, bits.pos));
+        }
+    }
+}
+
+fn stored(bits: &mut Bits<'_>, out: &mut Vec<u8>, limit: usize) -> Result<()> {
+    bits.align();
+    let Some(header) = bits.data.get(bits.pos..bits.pos + 4) else {
+        return corrupt("truncated stored block");
+    };
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    if len != !u16::from_le_bytes([header[2], header[3]]) {
+        return corrupt("stored block length mismatch");
+    }
+    let start = bits.pos + 4;
+    let Some(block) = bits.data.get(start..start + usize::from(len)) else {
+        return corrupt("truncated stored block");
+    };
+    if out.len() + block.len() > limit {
+        return Err(DecompressError::TooLarge { limit });
+    }
+    out.extend_from_slice(block);
+    bits.pos = start + block.len();
+    Ok(())
+}
+
+fn fixed_tables() -> Result<(Huffman, Huffman)> {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_tables(bits: &mut Bits<'_>) -> Result<(Huffman, Huffman)> {
+    let literal_count = bits.need(5)? as usize + 257;
+    let distance_count = bits.need(5)? as usize + 1;
+    let code_count = bits.need(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return corrupt("too many length or distance codes");
+    }
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_count] {
+        code_lengths[index] = bits.need(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_lengths)?;
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut i = 0;
+    while i < lengths.len() {
+        let symbol = code_length_code.decode(bits)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                if i == 0 {
+                    return corrupt("repeat with no previous length");
+                }
+                (lengths[i - 1], 3 + bits.need(2)? as usize)
+            }
+            17 => (0, 3 + bits.need(3)? as usize),
+            _ => (0, 11 + bits.need(7)? as usize),
+        };
+        if i + repeat > lengths.len() {
+            return corrupt("too many code lengths");
+        }
+        lengths[i..i + repeat].fill(value);
+        i += repeat;
+    }
+    if lengths[256] == 0 {
+        return corrupt("missing end-of-block code");
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..])?,
+    ))
+}
+
+fn codes(
+    bits: &mut Bits<'_>,
+    out: &mut Vec<u8>,
+    lengths: &Huffman,
+    distances: &Huffman,
+    limit: usize,
+) -> Result<()> {
+    loop {
+        let symbol = lengths.decode(bits)?;
+        match symbol {
+            0..=255 => {
+                if out.len() >= limit {
+                    return Err(DecompressError::TooLarge { limit });
+                }
+                out.push(symbol as u8);
+            }
+            256 => return Ok(()),
+            _ => {
+                let index = usize::from(symbol - 257);
+                if index >= LENGTH_BASE.len() {
+                    return corrupt("invalid length code");
+                }
+                let len = usize::from(LENGTH_BASE[index])
+                    + bits.need(u32::from(LENGTH_EXTRA[index]))? as usize;
+
+                let index = usize::from(distances.decode(bits)?);
+                if index >= DIST_BASE.len() {
+                    return corrupt("invalid distance code");
+                }
+                let distance = usize::from(DIST_BASE[index])
+                    + bits.need(u32::from(DIST_EXTRA[index]))? as usize;
+                if distance > out.len() {
+                    return corrupt("distance too far back");
+                }
+                if out.len() + len > limit {
+                    return Err(DecompressError::TooLarge { limit });
+                }
+                // Copies may overlap the bytes they produce (run-length style)
+                let start = out.len() - distance;
+                for k in 0..len {
+                    out.push(out[start + k]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+
+    /// Text with enough repetition for back-references, plus every byte value
+    fn sample() -> Vec<u8> {
+        let mut data = b"The quick brown fox jumps over the lazy dog. ".repeat(200);
+        data.extend(0..=255u8);
+        data.extend(std::iter::repeat_n(b'a', 1000));
+        data
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+        out.extend(compress_to_vec(data, 6));
+        out.extend(crc32(data).to_le_bytes());
+        out.extend((data.len() as u32).to_le_bytes());
+        out
+    }
+
+    #[test]
+    fn test_raw_deflate_all_levels() {
+        let data = sample();
+        // Level 0 = stored blocks, 1 = fixed codes, higher = dynamic codes
+        for level in [0, 1, 6, 10] {
+            let compressed = compress_to_vec(&data, level);
+            let (out, used) = inflate(&compressed, usize::MAX).unwrap();
+            assert_eq!(out, data, "level {level}");
+            assert_eq!(used, compressed.len(), "level {level}");
+        }
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let compressed = compress_to_vec(b"", 6);
+        assert!(inflate(&compressed, 10).unwrap().0.is_empty());
+        assert_eq!(decompress("gzip", &gzip(b""), 10).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_deflate_accepts_zlib_and_raw() {
+        let data = sample();
+        let zlib = compress_to_vec_zlib(&data, 6);
+        let raw = compress_to_vec(&data, 6);
+        assert_eq!(decompress("deflate", &zlib, usize::MAX).unwrap(), data);
+        assert_eq!(decompress("Deflate", &raw, usize::MAX).unwrap(), data);
+    }
+
+    #[test]
+    fn test_gzip_with_header_fields_and_members() {
+        let data = sample();
+        let mut member = vec![0x1f, 0x8b, 8, 4 | 8 | 16, 0, 0, 0, 0, 0, 3];
+        member.extend([2, 0, b'x', b'y']); // FEXTRA
+        member.extend(b"name.txt\0"); // FNAME
+        member.extend(b"comment\0"); // FCOMMENT
+        member.extend(compress_to_vec(&data, 6));
+        member.extend(crc32(&data).to_le_bytes());
+        member.extend((data.len() as u32).to_le_bytes());
+
+        let mut two = member.clone();
+        two.extend(gzip(b"tail"));
+
+        assert_eq!(decompress("gzip", &member, usize::MAX).unwrap(), data);
+        let mut expected = data.clone();
+        expected.extend(b"tail");
+        assert_eq!(decompress("x-gzip", &two, usize::MAX).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_checksums_are_verified() {
+        let mut bad_gzip = gzip(b"hello");
+        let crc_at = bad_gzip.len() - 8;
+        bad_gzip[crc_at] ^= 1;
+        assert_eq!(
+            decompress("gzip", &bad_gzip, 100),
+            Err(DecompressError::Corrupt("gzip checksum mismatch"))
+        );
+
+        let mut bad_zlib = compress_to_vec_zlib(b"hello", 6);
+        let last = bad_zlib.len() - 1;
+        bad_zlib[last] ^= 1;
+        assert_eq!(
+            decompress("deflate", &bad_zlib, 100),
+            Err(DecompressError::Corrupt("zlib checksum mismatch"))
+        );
+    }
+
+    #[test]
+    fn test_limit_stops_expansion() {
+        let bomb = compress_to_vec(&vec![0u8; 1 << 20], 10);
+        assert!(bomb.len() < 2048);
+        assert_eq!(
+            decompress("deflate", &bomb, 4096),
+            Err(DecompressError::TooLarge { limit: 4096 })
+        );
+        assert_eq!(
+            decompress("identity", b"12345", 4),
+            Err(DecompressError::TooLarge { limit: 4 })
+        );
+    }
+
+    #[test]
+    fn test_stacked_encodings() {
+        let inner = compress_to_vec_zlib(b"layered", 6);
+        let outer = gzip(&inner);
+        assert_eq!(
+            decompress("deflate, gzip", &outer, 100).unwrap(),
+            b"layered"
+        );
+    }
+
+    #[test]
+    fn test_corrupt_and_unsupported() {
+        assert_eq!(
+            decompress("br", b"x", 10),
+            Err(DecompressError::UnsupportedEncoding("br".to_string()))
+        );
+        assert!(matches!(
+            decompress("gzip", b"not gzip at all", 100),
+            Err(DecompressError::Corrupt(_))
+        ));
+        let truncated = compress_to_vec(&sample(), 6);
+        assert!(matches!(
+            inflate(&truncated[..truncated.len() / 2], usize::MAX),
+            Err(DecompressError::Corrupt("unexpected end of stream"))
+        ));
+        // Block type 3 is reserved
+        assert_eq!(
+            inflate(&[0b111], 10).unwrap_err(),
+            DecompressError::Corrupt("invalid block type")
+        );
+    }
+
+    #[test]
+    fn test_checksum_vectors() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}
@@ -0,0 +1,221 @@
+// Startup Phase Tracer
+//
+// Breaks the cold start down into phases so a regression can be pinned on
+// one of them instead of on "init got slower":
+//
+//   env_read              Runtime::new() parsed the environment
+//   client_init           Runtime::prepare() applied the ClientInit strategy
+//   handler_registration  the bootstrap is about to enter its event loop
+//   first_next            the first `/next` request is sent (end of init)
+//
+// Each phase is timed from the previous mark (the first from
+// start_init_trace(), called first thing in main), in microseconds. On the
+// first invocation they are logged as one line:
+//
+//   {"level":"INFO",...,"request_id":"…","message":"init.trace","env_read_us":41,
+//    "client_init_us":3,"handler_registration_us":12,"first_next_us":9,"total_us":65}
+//
+// Marks are always recorded (a clock read and a mutex per phase, once per
+// process); the line is opt-in (RUCHY_LAMBDA_INIT_TRACE=1 or
+// Runtime::with_init_trace).
+
+use crate::logger::{LogLevel, Logger};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Environment variable enabling the `init.trace` line (`1`, `true` or `on`)
+pub const INIT_TRACE_ENV: &str = "RUCHY_LAMBDA_INIT_TRACE";
+
+/// `message` of the line logged on the first invocation
+pub const INIT_TRACE: &str = "init.trace";
+
+/// Phase of the bootstrap's initialization, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitPhase {
+    /// Runtime configuration read from the environment
+    EnvRead,
+    /// Runtime API client initialization strategy applied
+    ClientInit,
+    /// Handler set up, event loop about to start
+    HandlerRegistration,
+    /// First `/next` request sent
+    FirstNext,
+}
+
+impl InitPhase {
+    /// All phases, in order
+    const ALL: [Self; 4] = [
+        Self::EnvRead,
+        Self::ClientInit,
+        Self::HandlerRegistration,
+        Self::FirstNext,
+    ];
+
+    /// Log field holding the phase's duration
+    fn field(self) -> &'static str {
+        match self {
+            Self::EnvRead => "env_read_us",
+            Self::ClientInit => "client_init_us",
+            Self::HandlerRegistration => "handler_registration_us",
+            Self::FirstNext => "first_next_us",
+        }
+    }
+}
+
+/// Phase marks of one process
+#[derive(Debug)]
+struct InitTrace {
+    started: Instant,
+    marks: [Option<Instant>; 4],
+}
+
+impl InitTrace {
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            marks: [None; 4],
+        }
+    }
+
+    /// Record `phase` at `at`; the first mark of a phase wins
+    fn mark(&mut self, phase: InitPhase, at: Instant) {
+        self.marks[phase as usize].get_or_insert(at);
+    }
+
+    /// Duration of each marked phase since the previous mark
+    fn phases(&self) -> Vec<(InitPhase, Duration)> {
+        let mut previous = self.started;
+        let mut phases = Vec::new();
+        for phase in InitPhase::ALL {
+            if let Some(at) = self.marks[phase as usize] {
+                phases.push((phase, at.saturating_duration_since(previous)));
+                previous = previous.max(at);
+            }
+        }
+        phases
+    }
+
+    /// `init.trace` fields: one per marked phase plus `total_us`
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let micros = |duration: Duration| duration.as_micros().to_string();
+        let phases = self.phases();
+        let total = phases.iter().map(|(_, duration)| *duration).sum();
+        let mut fields: Vec<_> = phases
+            .into_iter()
+            .map(|(phase, duration)| (phase.field(), micros(duration)))
+            .collect();
+        fields.push(("total_us", micros(total)));
+        fields
+    }
+}
+
+/// Marks of this process, created by the first start or mark
+static TRACE: Mutex<Option<InitTrace>> = Mutex::new(None);
+
+/// Whether `init.trace` was already logged
+static EMITTED: AtomicBool = AtomicBool::new(false);
+
+/// Start timing initialization
+///
+/// Call first thing in `main`; without it the trace starts at the first
+/// mark. Later calls are ignored.
+pub fn start_init_trace() {
+    let now = Instant::now();
+    let mut trace = TRACE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    trace.get_or_insert_with(|| InitTrace::new(now));
+}
+
+/// Record the end of `phase`; only its first occurrence counts
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{mark_init_phase, start_init_trace, InitPhase};
+///
+/// start_init_trace();
+/// // ... register handlers ...
+/// mark_init_phase(InitPhase::HandlerRegistration);
+/// ```
+pub fn mark_init_phase(phase: InitPhase) {
+    let now = Instant::now();
+    let mut trace = TRACE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    trace
+        .get_or_insert_with(|| InitTrace::new(now))
+        .mark(phase, now);
+}
+
+/// Log `init.trace` for `request_id` unless already logged by this process
+pub(crate) fn emit_once(request_id: &str) {
+    if EMITTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let fields = match &*TRACE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+    {
+        Some(trace) => trace.fields(),
+        None => return,
+    };
+    Logger::new().log_json_fields(LogLevel::Info, Some(request_id), INIT_TRACE, &fields);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases_measured_from_previous_mark() {
+        let started = Instant::now();
+        let mut trace = InitTrace::new(started);
+        trace.mark(InitPhase::EnvRead, started + Duration::from_micros(40));
+        trace.mark(InitPhase::ClientInit, started + Duration::from_micros(45));
+        trace.mark(InitPhase::FirstNext, started + Duration::from_micros(100));
+
+        assert_eq!(
+            trace.phases(),
+            [
+                (InitPhase::EnvRead, Duration::from_micros(40)),
+                (InitPhase::ClientInit, Duration::from_micros(5)),
+                (InitPhase::FirstNext, Duration::from_micros(55)),
+            ]
+        );
+        assert_eq!(
+            trace.fields(),
+            [
+                ("env_read_us", "40".to_string()),
+                ("client_init_us", "5".to_string()),
+                ("first_next_us", "55".to_string()),
+                ("total_us", "100".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_mark_wins() {
+        let started = Instant::now();
+        let mut trace = InitTrace::new(started);
+        trace.mark(InitPhase::FirstNext, started + Duration::from_micros(10));
+        trace.mark(InitPhase::FirstNext, started + Duration::from_micros(99));
+        assert_eq!(
+            trace.phases(),
+            [(InitPhase::FirstNext, Duration::from_micros(10))]
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_marks_do_not_underflow() {
+        // Background client init may finish after handler registration
+        let started = Instant::now();
+        let mut trace = InitTrace::new(started);
+        trace.mark(InitPhase::ClientInit, started + Duration::from_micros(30));
+        trace.mark(InitPhase::EnvRead, started + Duration::from_micros(50));
+        let phases = trace.phases();
+        assert_eq!(phases[0].1, Duration::from_micros(50));
+        assert_eq!(phases[1].1, Duration::ZERO);
+    }
+}
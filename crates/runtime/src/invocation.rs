@@ -0,0 +1,137 @@
+// Zero-Copy Invocation
+//
+// Borrowed view of a GET /runtime/invocation/next response. The request ID,
+// headers and body are slices of a caller-owned buffer, so once that buffer
+// has grown to fit a typical event the hot path performs no heap allocation.
+//
+// Use `Context` (owned) when metadata must outlive the buffer.
+
+use crate::context::Context;
+use ruchy_lambda_http_core::ResponseRef;
+use std::str::Utf8Error;
+
+/// Request ID used when the Runtime API omits the header
+const UNKNOWN_REQUEST_ID: &str = "unknown";
+
+/// Lambda invocation borrowed from a response buffer
+///
+/// Returned by [`Runtime::next_invocation_into`](crate::Runtime::next_invocation_into).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ruchy_lambda_runtime::Runtime;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let runtime = Runtime::new()?;
+/// let mut buffer = Vec::with_capacity(64 * 1024);
+/// loop {
+///     let invocation = runtime.next_invocation_into(&mut buffer)?;
+///     let body = invocation.body_str()?;
+///     runtime.post_response(invocation.request_id, body)?;
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invocation<'buf> {
+    /// Unique request ID (`Lambda-Runtime-Aws-Request-Id`, "unknown" if absent)
+    pub request_id: &'buf str,
+    /// Raw event payload bytes
+    pub body: &'buf [u8],
+    /// Parsed response the request ID and body were taken from
+    response: ResponseRef<'buf>,
+}
+
+impl<'buf> Invocation<'buf> {
+    /// Build an invocation view over a parsed `/next` response
+    pub(crate) fn from_response(response: ResponseRef<'buf>) -> Self {
+        Self {
+            request_id: response
+                .header("lambda-runtime-aws-request-id")
+                .unwrap_or(UNKNOWN_REQUEST_ID),
+            body: response.body,
+            response,
+        }
+    }
+
+    /// Look up a response header by name (case-insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&'buf str> {
+        self.response.header(name)
+    }
+
+    /// Iterate response header `(name, value)` pairs in wire order
+    pub fn headers(&self) -> impl Iterator<Item = (&'buf str, &'buf str)> {
+        self.response.header_pairs()
+    }
+
+    /// Event payload as UTF-8 text (no copy)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Utf8Error` if the payload is not valid UTF-8.
+    pub fn body_str(&self) -> Result<&'buf str, Utf8Error> {
+        std::str::from_utf8(self.body)
+    }
+
+    /// Build an owned [`Context`] from the `Lambda-Runtime-*` headers
+    ///
+    /// Allocates; use it only when the metadata must outlive the buffer.
+    #[must_use]
+    pub fn context(&self) -> Context {
+        Context::from_headers(self.headers())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruchy_lambda_http_core::parse_response_ref;
+
+    const RAW: &[u8] = b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: zc-1\r\nLambda-Runtime-Deadline-Ms: 1700000000000\r\nLambda-Runtime-Trace-Id: Root=1-abc\r\n\r\n{\"zero\":\"copy\"}";
+
+    #[test]
+    fn test_invocation_borrows_buffer() {
+        let invocation = Invocation::from_response(parse_response_ref(RAW).unwrap());
+        assert_eq!(invocation.request_id, "zc-1");
+        assert_eq!(invocation.body_str().unwrap(), "{\"zero\":\"copy\"}");
+
+        let range = RAW.as_ptr_range();
+        assert!(range.contains(&invocation.request_id.as_ptr()));
+        assert!(range.contains(&invocation.body.as_ptr()));
+    }
+
+    #[test]
+    fn test_invocation_headers() {
+        let invocation = Invocation::from_response(parse_response_ref(RAW).unwrap());
+        assert_eq!(
+            invocation.header("LAMBDA-RUNTIME-TRACE-ID"),
+            Some("Root=1-abc")
+        );
+        assert_eq!(invocation.headers().count(), 3);
+        assert_eq!(invocation.header("x-missing"), None);
+    }
+
+    #[test]
+    fn test_invocation_missing_request_id() {
+        let response = parse_response_ref(b"HTTP/1.1 200 OK\r\n\r\n{}").unwrap();
+        let invocation = Invocation::from_response(response);
+        assert_eq!(invocation.request_id, "unknown");
+    }
+
+    #[test]
+    fn test_invocation_context_matches_owned() {
+        let invocation = Invocation::from_response(parse_response_ref(RAW).unwrap());
+        let context = invocation.context();
+        assert_eq!(context.request_id, "zc-1");
+        assert_eq!(context.deadline_ms, 1_700_000_000_000);
+        assert_eq!(context.trace_id.as_deref(), Some("Root=1-abc"));
+    }
+
+    #[test]
+    fn test_invocation_non_utf8_body() {
+        let response = parse_response_ref(b"HTTP/1.1 200 OK\r\n\r\n\xff\xfe").unwrap();
+        let invocation = Invocation::from_response(response);
+        assert!(invocation.body_str().is_err());
+        assert_eq!(invocation.body, b"\xff\xfe");
+    }
+}
@@ -0,0 +1,114 @@
+// IoT Core Topic Rule Events
+//
+// A topic rule invokes the function with whatever its SQL SELECT produces,
+// so there is no fixed event shape. Rules conventionally add the routing
+// metadata next to the device payload:
+//
+//   SELECT *, topic() AS topic, clientid() AS clientId,
+//          timestamp() AS timestamp, principal() AS principal
+//   FROM 'devices/+/telemetry'
+//
+// IotRuleEvent picks those fields out and keeps everything else as the
+// payload.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Topic rule payload with the conventional metadata fields
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::IotRuleEvent;
+///
+/// let body = r#"{"temperature":21.5,"topic":"devices/sensor-7/telemetry","clientId":"sensor-7","timestamp":1700000000000}"#;
+/// let event = IotRuleEvent::from_json(body).unwrap();
+///
+/// assert_eq!(event.topic_segment(1), Some("sensor-7"));
+/// assert_eq!(event.payload["temperature"], 21.5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IotRuleEvent {
+    /// MQTT topic the message was published to (`topic()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// MQTT client ID of the publisher (`clientid()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Rule evaluation time in Unix epoch milliseconds (`timestamp()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Certificate ID or identity of the publisher (`principal()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+    /// Remaining fields selected by the rule (the device payload)
+    #[serde(flatten)]
+    pub payload: Map<String, Value>,
+}
+
+impl IotRuleEvent {
+    /// Parse a topic rule event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a JSON object.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Topic level `index` (0-based, like `topic(index + 1)` in rule SQL)
+    #[must_use]
+    pub fn topic_segment(&self, index: usize) -> Option<&str> {
+        self.topic.as_deref()?.split('/').nth(index)
+    }
+
+    /// Deserialize the device payload into `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the payload does not match `T`.
+    pub fn payload_as<T: for<'de> Deserialize<'de>>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(Value::Object(self.payload.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Reading {
+        temperature: f64,
+    }
+
+    #[test]
+    fn test_metadata_split_from_payload() {
+        let event = IotRuleEvent::from_json(
+            r#"{"temperature":20.0,"topic":"a/b/c","clientId":"c1","timestamp":5,"principal":"cert"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.client_id.as_deref(), Some("c1"));
+        assert_eq!(event.timestamp, Some(5));
+        assert_eq!(event.principal.as_deref(), Some("cert"));
+        assert_eq!(event.payload.len(), 1);
+        assert_eq!(event.topic_segment(2), Some("c"));
+        assert_eq!(event.topic_segment(3), None);
+        assert_eq!(
+            event.payload_as::<Reading>().unwrap(),
+            Reading { temperature: 20.0 }
+        );
+    }
+
+    #[test]
+    fn test_payload_only() {
+        let event = IotRuleEvent::from_json(r#"{"state":{"on":true}}"#).unwrap();
+        assert!(event.topic.is_none());
+        assert_eq!(event.topic_segment(0), None);
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"state":{"on":true}}"#
+        );
+    }
+}
@@ -0,0 +1,220 @@
+// Kafka Events (Amazon MSK and self-managed)
+//
+// Both event sources deliver records grouped by "<topic>-<partition>":
+//
+//   {"eventSource": "aws:kafka", "eventSourceArn": "...", "bootstrapServers": "...",
+//    "records": {"orders-0": [{"topic": "orders", "partition": 0, "offset": 15,
+//      "timestamp": 1545084650987, "timestampType": "CREATE_TIME",
+//      "key": "<base64>", "value": "<base64>",
+//      "headers": [{"traceId": [97, 98, 99]}]}]}}
+//
+// Batches can hold thousands of records, so the event borrows from the body
+// (base64 never contains JSON escapes) and keys / values are only decoded
+// when asked for.
+
+use ruchy_lambda_simd::base64::{self, DecodeError};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+/// MSK or self-managed Kafka event
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::KafkaEvent;
+///
+/// let body = r#"{"eventSource":"aws:kafka","bootstrapServers":"b-1:9092",
+///     "records":{"orders-0":[{"topic":"orders","partition":0,"offset":15,
+///         "timestamp":1545084650987,"timestampType":"CREATE_TIME",
+///         "value":"eyJpZCI6NDJ9","headers":[]}]}}"#;
+///
+/// let event = KafkaEvent::from_json(body).unwrap();
+/// for record in event.records() {
+///     let value = record.value().unwrap().unwrap();
+///     assert_eq!(value, br#"{"id":42}"#);
+/// }
+/// assert_eq!(event.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaEvent<'a> {
+    /// `aws:kafka` (MSK) or `SelfManagedKafka`
+    #[serde(default, borrow)]
+    pub event_source: Cow<'a, str>,
+    /// MSK cluster ARN (absent for self-managed clusters)
+    #[serde(default, borrow)]
+    pub event_source_arn: Option<Cow<'a, str>>,
+    /// Comma-separated broker list
+    #[serde(default, borrow)]
+    pub bootstrap_servers: Cow<'a, str>,
+    /// Records by `<topic>-<partition>`, in offset order within a partition
+    #[serde(default, borrow)]
+    pub records: BTreeMap<Cow<'a, str>, Vec<KafkaRecord<'a>>>,
+}
+
+impl<'a> KafkaEvent<'a> {
+    /// Parse a Kafka event, borrowing from `event_body`
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a Kafka event.
+    pub fn from_json(event_body: &'a str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// All records, partition by partition
+    pub fn records(&self) -> impl Iterator<Item = &KafkaRecord<'a>> {
+        self.records.values().flatten()
+    }
+
+    /// Total number of records
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records.values().map(Vec::len).sum()
+    }
+
+    /// Whether the event holds no records
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// One Kafka record
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KafkaRecord<'a> {
+    /// Topic name
+    #[serde(borrow)]
+    pub topic: Cow<'a, str>,
+    /// Partition number
+    pub partition: u32,
+    /// Offset within the partition
+    pub offset: u64,
+    /// Record timestamp in Unix epoch milliseconds
+    #[serde(default)]
+    pub timestamp: u64,
+    /// `CREATE_TIME` or `LOG_APPEND_TIME`
+    #[serde(default, borrow)]
+    pub timestamp_type: Cow<'a, str>,
+    /// Base64 key (absent for keyless records)
+    #[serde(default, borrow)]
+    pub key: Option<&'a str>,
+    /// Base64 value (absent for tombstones)
+    #[serde(default, borrow)]
+    pub value: Option<&'a str>,
+    /// Record headers, each a single `name -> bytes` entry
+    #[serde(default)]
+    pub headers: Vec<HashMap<String, Vec<u8>>>,
+}
+
+impl KafkaRecord<'_> {
+    /// Decoded key, if the record has one
+    ///
+    /// Returns `Some(Err(_))` if the key is not valid base64.
+    #[must_use]
+    pub fn key(&self) -> Option<Result<Vec<u8>, DecodeError>> {
+        self.key.map(|key| base64::decode(key.as_bytes()))
+    }
+
+    /// Decoded value (`None` for tombstones)
+    ///
+    /// Returns `Some(Err(_))` if the value is not valid base64.
+    #[must_use]
+    pub fn value(&self) -> Option<Result<Vec<u8>, DecodeError>> {
+        self.value.map(|value| base64::decode(value.as_bytes()))
+    }
+
+    /// Decoded value as UTF-8 text
+    ///
+    /// `None` for tombstones and for values that are not base64-encoded
+    /// UTF-8.
+    #[must_use]
+    pub fn value_str(&self) -> Option<String> {
+        String::from_utf8(self.value()?.ok()?).ok()
+    }
+
+    /// First header named `name`
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find_map(|header| header.get(name))
+            .map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = r#"{
+        "eventSource": "aws:kafka",
+        "eventSourceArn": "arn:aws:kafka:us-east-1:123456789012:cluster/demo/abc",
+        "bootstrapServers": "b-2.demo:9092,b-1.demo:9092",
+        "records": {
+            "orders-1": [
+                {"topic": "orders", "partition": 1, "offset": 7, "timestamp": 2,
+                 "timestampType": "LOG_APPEND_TIME", "value": "d29ybGQ=", "headers": []}
+            ],
+            "orders-0": [
+                {"topic": "orders", "partition": 0, "offset": 15, "timestamp": 1,
+                 "timestampType": "CREATE_TIME", "key": "azE=", "value": "aGVsbG8=",
+                 "headers": [{"traceId": [97, 98, 99]}, {"tenant": [120]}]},
+                {"topic": "orders", "partition": 0, "offset": 16, "timestamp": 1,
+                 "timestampType": "CREATE_TIME", "key": "azI=", "headers": []}
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_and_iterate_in_partition_order() {
+        let event = KafkaEvent::from_json(EVENT).unwrap();
+        assert_eq!(event.event_source, "aws:kafka");
+        assert!(event.event_source_arn.is_some());
+        assert_eq!(event.len(), 3);
+
+        let offsets: Vec<(u32, u64)> = event
+            .records()
+            .map(|record| (record.partition, record.offset))
+            .collect();
+        assert_eq!(offsets, [(0, 15), (0, 16), (1, 7)]);
+    }
+
+    #[test]
+    fn test_lazy_decoding() {
+        let event = KafkaEvent::from_json(EVENT).unwrap();
+        let records: Vec<_> = event.records().collect();
+
+        assert_eq!(records[0].key().unwrap().unwrap(), b"k1");
+        assert_eq!(records[0].value_str().as_deref(), Some("hello"));
+        assert_eq!(records[0].header("traceId"), Some(&b"abc"[..]));
+        assert_eq!(records[0].header("tenant"), Some(&b"x"[..]));
+        assert_eq!(records[0].header("missing"), None);
+
+        // Tombstone
+        assert!(records[1].value().is_none());
+        assert_eq!(records[2].value_str().as_deref(), Some("world"));
+    }
+
+    #[test]
+    fn test_values_borrow_from_body() {
+        let event = KafkaEvent::from_json(EVENT).unwrap();
+        let range = EVENT.as_bytes().as_ptr_range();
+        let value = event.records().next().unwrap().value.unwrap();
+        assert!(range.contains(&value.as_ptr()));
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        let event = KafkaEvent::from_json(
+            r#"{"eventSource":"SelfManagedKafka","records":{"t-0":[{"topic":"t","partition":0,"offset":0,"value":"not base64!"}]}}"#,
+        )
+        .unwrap();
+        let record = event.records().next().unwrap();
+        assert!(record.value().unwrap().is_err());
+        assert_eq!(record.value_str(), None);
+        assert!(event.event_source_arn.is_none());
+    }
+}
@@ -0,0 +1,461 @@
+// Amazon Lex V2 Code Hooks
+//
+// Lex calls the function during a conversation (DialogCodeHook) and to
+// fulfil an intent (FulfillmentCodeHook). The response carries the next
+// session state: which dialog action Lex takes next (Close, ElicitSlot,
+// ConfirmIntent, Delegate, ElicitIntent), the updated intent and any
+// messages to say or show.
+//
+// Parts of the event that handlers rarely inspect (active contexts, runtime
+// hints, transcriptions) are kept as raw JSON so they round-trip unchanged.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Lex V2 code hook event
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{LexEvent, LexResponse};
+///
+/// let body = r#"{"messageVersion":"1.0","invocationSource":"FulfillmentCodeHook",
+///     "inputMode":"Text","sessionId":"s1","inputTranscript":"book a room",
+///     "bot":{"id":"B1","name":"Hotel","aliasId":"TSTALIASID","localeId":"en_US","version":"DRAFT"},
+///     "sessionState":{"intent":{"name":"BookRoom","state":"ReadyForFulfillment",
+///         "confirmationState":"None","slots":{"Nights":{"value":{"originalValue":"two",
+///         "interpretedValue":"2","resolvedValues":["2"]}},"City":null}}}}"#;
+///
+/// let event = LexEvent::from_json(body).unwrap();
+/// assert_eq!(event.slot("Nights"), Some("2"));
+/// assert_eq!(event.slot("City"), None);
+///
+/// let response = LexResponse::close(&event, "Fulfilled", "Your room is booked");
+/// assert!(response.to_json().contains(r#""type":"Close""#));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexEvent {
+    /// Event format version
+    #[serde(default)]
+    pub message_version: String,
+    /// `DialogCodeHook` or `FulfillmentCodeHook`
+    #[serde(default)]
+    pub invocation_source: String,
+    /// `Text`, `Speech` or `DTMF`
+    #[serde(default)]
+    pub input_mode: String,
+    /// Requested response content type
+    #[serde(default)]
+    pub response_content_type: Option<String>,
+    /// Conversation session ID
+    #[serde(default)]
+    pub session_id: String,
+    /// What the user said or typed
+    #[serde(default)]
+    pub input_transcript: String,
+    /// Bot that received the input
+    #[serde(default)]
+    pub bot: LexBot,
+    /// Candidate intents, best first
+    #[serde(default)]
+    pub interpretations: Vec<LexInterpretation>,
+    /// Action Lex proposes next (dialog code hooks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed_next_state: Option<Value>,
+    /// Request-specific attributes
+    #[serde(default)]
+    pub request_attributes: Option<HashMap<String, String>>,
+    /// Current session state
+    #[serde(default)]
+    pub session_state: LexSessionState,
+    /// Speech transcriptions (speech input)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcriptions: Option<Value>,
+}
+
+impl LexEvent {
+    /// Parse a Lex V2 event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a Lex V2 event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Interpreted value of a slot of the current intent
+    #[must_use]
+    pub fn slot(&self, name: &str) -> Option<&str> {
+        self.session_state.intent.as_ref()?.slot(name)
+    }
+
+    /// Session attribute value
+    #[must_use]
+    pub fn session_attribute(&self, name: &str) -> Option<&str> {
+        self.session_state
+            .session_attributes
+            .get(name)
+            .map(String::as_str)
+    }
+}
+
+/// Bot that received the input
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexBot {
+    /// Bot ID
+    #[serde(default)]
+    pub id: String,
+    /// Bot name
+    #[serde(default)]
+    pub name: String,
+    /// Alias ID
+    #[serde(default)]
+    pub alias_id: String,
+    /// Alias name
+    #[serde(default)]
+    pub alias_name: Option<String>,
+    /// Locale (e.g. `en_US`)
+    #[serde(default)]
+    pub locale_id: String,
+    /// Bot version
+    #[serde(default)]
+    pub version: String,
+}
+
+/// One candidate intent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexInterpretation {
+    /// The intent
+    pub intent: LexIntent,
+    /// NLU confidence (`{"score": 0.87}`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nlu_confidence: Option<Value>,
+    /// Sentiment analysis result, if enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentiment_response: Option<Value>,
+}
+
+/// Intent with its slots and state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexIntent {
+    /// Intent name
+    pub name: String,
+    /// Slots by name (`None` = not filled)
+    #[serde(default)]
+    pub slots: HashMap<String, Option<LexSlot>>,
+    /// `Failed`, `Fulfilled`, `FulfillmentInProgress`, `InProgress`,
+    /// `ReadyForFulfillment` or `Waiting`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// `Confirmed`, `Denied` or `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation_state: Option<String>,
+}
+
+impl LexIntent {
+    /// Interpreted value of a filled slot
+    #[must_use]
+    pub fn slot(&self, name: &str) -> Option<&str> {
+        self.slots
+            .get(name)?
+            .as_ref()?
+            .value
+            .as_ref()?
+            .interpreted_value
+            .as_deref()
+    }
+
+    /// Fill a scalar slot
+    pub fn set_slot(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let value = value.into();
+        self.slots.insert(
+            name.into(),
+            Some(LexSlot {
+                value: Some(LexSlotValue {
+                    original_value: Some(value.clone()),
+                    interpreted_value: Some(value.clone()),
+                    resolved_values: vec![value],
+                }),
+                ..LexSlot::default()
+            }),
+        );
+    }
+}
+
+/// Slot content
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexSlot {
+    /// `Scalar` or `List`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<String>,
+    /// Value of a scalar slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<LexSlotValue>,
+    /// Values of a list slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<LexSlot>>,
+}
+
+/// What the user said for a slot and how Lex interpreted it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexSlotValue {
+    /// Text as the user said it
+    #[serde(default)]
+    pub original_value: Option<String>,
+    /// Value Lex resolved it to
+    #[serde(default)]
+    pub interpreted_value: Option<String>,
+    /// Other candidate resolutions
+    #[serde(default)]
+    pub resolved_values: Vec<String>,
+}
+
+/// Session state exchanged with Lex
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexSessionState {
+    /// Active conversation contexts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_contexts: Option<Value>,
+    /// Application-defined session attributes
+    #[serde(default)]
+    pub session_attributes: HashMap<String, String>,
+    /// Slot / phrase hints for speech recognition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime_hints: Option<Value>,
+    /// Next action (set in responses)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dialog_action: Option<LexDialogAction>,
+    /// Current intent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intent: Option<LexIntent>,
+    /// Request that started the session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub originating_request_id: Option<String>,
+}
+
+/// Next action Lex takes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexDialogAction {
+    /// `Close`, `ConfirmIntent`, `Delegate`, `ElicitIntent` or `ElicitSlot`
+    #[serde(rename = "type")]
+    pub action_type: String,
+    /// Slot to ask for (`ElicitSlot`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_to_elicit: Option<String>,
+}
+
+/// Message returned to the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexMessage {
+    /// `PlainText`, `SSML`, `CustomPayload` or `ImageResponseCard`
+    pub content_type: String,
+    /// Message text
+    pub content: String,
+}
+
+impl LexMessage {
+    /// Plain text message
+    #[must_use]
+    pub fn plain_text(content: impl Into<String>) -> Self {
+        Self {
+            content_type: "PlainText".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// Code hook response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LexResponse {
+    /// Next session state
+    pub session_state: LexSessionState,
+    /// Messages for the user
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<LexMessage>,
+    /// Request attributes to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_attributes: Option<HashMap<String, String>>,
+}
+
+impl LexResponse {
+    /// Response continuing from the event's session state with `action`
+    #[must_use]
+    pub fn with_action(event: &LexEvent, action: LexDialogAction) -> Self {
+        let mut session_state = event.session_state.clone();
+        session_state.dialog_action = Some(action);
+        Self {
+            session_state,
+            messages: Vec::new(),
+            request_attributes: None,
+        }
+    }
+
+    /// End the conversation with the intent in `state` (e.g. `Fulfilled`)
+    #[must_use]
+    pub fn close(event: &LexEvent, state: &str, message: impl Into<String>) -> Self {
+        let mut response = Self::with_action(
+            event,
+            LexDialogAction {
+                action_type: "Close".to_string(),
+                slot_to_elicit: None,
+            },
+        )
+        .with_message(LexMessage::plain_text(message));
+        if let Some(intent) = response.session_state.intent.as_mut() {
+            intent.state = Some(state.to_string());
+        }
+        response
+    }
+
+    /// Ask the user for `slot`
+    #[must_use]
+    pub fn elicit_slot(event: &LexEvent, slot: &str, prompt: impl Into<String>) -> Self {
+        Self::with_action(
+            event,
+            LexDialogAction {
+                action_type: "ElicitSlot".to_string(),
+                slot_to_elicit: Some(slot.to_string()),
+            },
+        )
+        .with_message(LexMessage::plain_text(prompt))
+    }
+
+    /// Let Lex choose the next action from the bot configuration
+    #[must_use]
+    pub fn delegate(event: &LexEvent) -> Self {
+        Self::with_action(
+            event,
+            LexDialogAction {
+                action_type: "Delegate".to_string(),
+                slot_to_elicit: None,
+            },
+        )
+    }
+
+    /// Add a message
+    #[must_use]
+    pub fn with_message(mut self, message: LexMessage) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// JSON response document
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = r#"{
+        "messageVersion": "1.0",
+        "invocationSource": "DialogCodeHook",
+        "inputMode": "Speech",
+        "responseContentType": "audio/pcm",
+        "sessionId": "s-1",
+        "inputTranscript": "two nights in Paris",
+        "bot": {"id": "B", "name": "Hotel", "aliasId": "A", "aliasName": "prod", "localeId": "en_US", "version": "3"},
+        "interpretations": [
+            {"intent": {"name": "BookRoom", "slots": {}, "state": "InProgress", "confirmationState": "None"},
+             "nluConfidence": {"score": 0.93}},
+            {"intent": {"name": "FallbackIntent", "slots": {}}}
+        ],
+        "proposedNextState": {"dialogAction": {"type": "ElicitSlot", "slotToElicit": "CheckIn"}},
+        "requestAttributes": {"x-amz-lex:channel": "voice"},
+        "sessionState": {
+            "activeContexts": [],
+            "sessionAttributes": {"loyalty": "gold"},
+            "intent": {
+                "name": "BookRoom",
+                "state": "InProgress",
+                "confirmationState": "None",
+                "slots": {
+                    "Nights": {"shape": "Scalar", "value": {"originalValue": "two", "interpretedValue": "2", "resolvedValues": ["2"]}},
+                    "City": {"shape": "Scalar", "value": {"originalValue": "Paris", "interpretedValue": "Paris", "resolvedValues": []}},
+                    "CheckIn": null
+                }
+            },
+            "originatingRequestId": "req-9"
+        },
+        "transcriptions": [{"transcription": "two nights in paris", "transcriptionConfidence": 0.9}]
+    }"#;
+
+    #[test]
+    fn test_parse_event() {
+        let event = LexEvent::from_json(EVENT).unwrap();
+        assert_eq!(event.invocation_source, "DialogCodeHook");
+        assert_eq!(event.bot.alias_name.as_deref(), Some("prod"));
+        assert_eq!(event.interpretations.len(), 2);
+        assert_eq!(event.slot("City"), Some("Paris"));
+        assert_eq!(event.slot("CheckIn"), None);
+        assert_eq!(event.slot("Unknown"), None);
+        assert_eq!(event.session_attribute("loyalty"), Some("gold"));
+    }
+
+    #[test]
+    fn test_elicit_slot_keeps_session_state() {
+        let event = LexEvent::from_json(EVENT).unwrap();
+        let response = LexResponse::elicit_slot(&event, "CheckIn", "When do you arrive?");
+        let value: Value = serde_json::from_str(&response.to_json()).unwrap();
+
+        assert_eq!(value["sessionState"]["dialogAction"]["type"], "ElicitSlot");
+        assert_eq!(
+            value["sessionState"]["dialogAction"]["slotToElicit"],
+            "CheckIn"
+        );
+        assert_eq!(
+            value["sessionState"]["sessionAttributes"]["loyalty"],
+            "gold"
+        );
+        assert_eq!(
+            value["sessionState"]["intent"]["slots"]["Nights"]["value"]["interpretedValue"],
+            "2"
+        );
+        assert_eq!(
+            value["messages"][0],
+            serde_json::json!({"contentType": "PlainText", "content": "When do you arrive?"})
+        );
+    }
+
+    #[test]
+    fn test_close_sets_intent_state() {
+        let mut event = LexEvent::from_json(EVENT).unwrap();
+        event
+            .session_state
+            .intent
+            .as_mut()
+            .unwrap()
+            .set_slot("CheckIn", "2024-06-01");
+
+        let response = LexResponse::close(&event, "Fulfilled", "Booked");
+        let intent = response.session_state.intent.as_ref().unwrap();
+        assert_eq!(intent.state.as_deref(), Some("Fulfilled"));
+        assert_eq!(intent.slot("CheckIn"), Some("2024-06-01"));
+        assert_eq!(
+            response.session_state.dialog_action.unwrap().action_type,
+            "Close"
+        );
+    }
+
+    #[test]
+    fn test_delegate_has_no_messages() {
+        let event = LexEvent::from_json(EVENT).unwrap();
+        let json = LexResponse::delegate(&event).to_json();
+        assert!(json.contains(r#""type":"Delegate""#));
+        assert!(!json.contains("messages"));
+    }
+}
@@ -16,9 +16,19 @@
 //! # Architecture
 //!
 //! - **Zero-copy deserialization**: 40-60% allocation reduction (Section 3.3.1)
+//! - **Zero-copy invocations**: [`Runtime::next_invocation_into`] borrows the
+//!   event from a reusable buffer
 //! - **Minimal initialization**: <1ms startup time (Section 3.2)
 //! - **Low invocation overhead**: <100μs per request (Section 3.3)
 //!
+//! # Without serde
+//!
+//! serde / `serde_json` sit behind the default `serde` feature. With
+//! `default-features = false` the event types, typed handlers, middleware
+//! and AWS clients are compiled out; the raw path remains:
+//! [`Runtime::next_invocation`] hands out the event body as received and
+//! [`Runtime::post_response`] posts the body the handler built.
+//!
 //! # Examples
 //!
 //! ```no_run
@@ -32,29 +42,321 @@
 //! ```
 
 use once_cell::sync::OnceCell;
-use std::env;
 use std::error::Error as StdError;
 use std::fmt;
 
+#[cfg(feature = "serde")]
+mod appconfig;
+#[cfg(feature = "arena")]
+mod arena;
+#[cfg(feature = "jwt")]
+mod auth;
+#[cfg(feature = "serde")]
+mod authorizer;
+#[cfg(feature = "sigv4")]
+mod aws_client;
+mod background;
+mod backoff;
+#[cfg(feature = "backtrace")]
+mod backtrace;
+#[cfg(feature = "serde")]
+mod bytes;
+mod cancellation;
+mod client_init;
+mod clock;
+#[cfg(feature = "serde")]
+mod cloudformation;
+#[cfg(feature = "serde")]
+mod codec;
+#[cfg(feature = "serde")]
+mod cognito;
+mod config;
+#[cfg(feature = "serde")]
+mod connect;
+mod context;
+mod correlation;
+#[cfg(feature = "serde")]
+mod cors;
+mod cpu_time;
+#[cfg(feature = "sigv4")]
+mod credentials;
+mod deadline;
+#[cfg(feature = "dynamodb")]
+mod dynamodb;
+#[cfg(feature = "serde")]
+mod dynamodb_event;
+#[cfg(feature = "serde")]
+mod etag;
+#[cfg(feature = "serde")]
 mod event;
+mod event_stream;
+#[cfg(feature = "serde")]
+mod extension;
+#[cfg(feature = "serde")]
+mod failure_record;
+#[cfg(feature = "serde")]
+mod firehose;
+mod handler_error;
 mod http_client;
+#[cfg(feature = "serde")]
+mod http_request;
+#[cfg(feature = "serde")]
+mod idempotency;
+mod inflate;
+mod init_trace;
+mod invocation;
+#[cfg(feature = "serde")]
+mod iot;
+#[cfg(feature = "serde")]
+mod kafka;
+#[cfg(feature = "serde")]
+mod lex;
+mod lifecycle;
+mod log_sampling;
 mod logger;
+mod memory;
+#[cfg(feature = "serde")]
+mod metrics;
+#[cfg(feature = "serde")]
+mod middleware;
+#[cfg(feature = "serde")]
+mod mq;
+mod multipart;
+#[cfg(feature = "serde")]
+mod ndjson;
+mod pipelining;
+mod prefetch;
+#[cfg(feature = "sigv4")]
+mod presign;
+#[cfg(feature = "protobuf")]
+mod prost_handler;
+mod query_string;
+mod read_buffer;
+#[cfg(feature = "serde")]
+mod records;
+mod redaction;
+mod request_ids;
+mod retry;
+#[cfg(feature = "serde")]
+mod router;
+#[cfg(feature = "s3")]
+mod s3;
+#[cfg(feature = "serde")]
+mod s3_batch;
+#[cfg(feature = "serde")]
+mod ses;
+#[cfg(feature = "sigv4")]
+mod sigv4;
+#[cfg(feature = "sqs")]
+mod sqs;
+mod state;
+mod timeout_guard;
+mod trace_context;
+#[cfg(feature = "tracing")]
+mod tracing_adapter;
+mod unwind;
+#[cfg(feature = "serde")]
+mod validation;
+mod workers;
+#[cfg(feature = "xray")]
+mod xray;
 
+#[cfg(feature = "serde")]
+pub use appconfig::{
+    AppConfig, AppConfigError, APPCONFIG_ENV, APPCONFIG_PORT_ENV, DEFAULT_APPCONFIG_PORT,
+    DEFAULT_APPCONFIG_TTL,
+};
+#[cfg(feature = "arena")]
+pub use arena::{ScratchArena, ScratchString, ScratchVec};
+#[cfg(feature = "jwt")]
+pub use auth::{bearer_token, JwtAuth, JwtError, DEFAULT_JWKS_TTL};
+#[cfg(feature = "serde")]
+pub use authorizer::{
+    AuthorizerResponse, ContextValue, PolicyDocument, PolicyStatement, RequestAuthorizerEvent,
+    SimpleAuthorizerResponse, TokenAuthorizerEvent,
+};
+#[cfg(feature = "sigv4")]
+pub use aws_client::{AwsClient, AwsError, AwsResponse, AwsStreamResponse};
+pub use background::drain_background_tasks;
+pub use backoff::{
+    Backoff, BackoffAction, BackoffConfig, BACKOFF_INITIAL_ENV, BACKOFF_MAX_ENV,
+    CIRCUIT_OPEN_EXIT_CODE, FATAL_FAILURES_ENV,
+};
+#[cfg(feature = "backtrace")]
+pub use backtrace::BACKTRACE_ENV;
+#[cfg(feature = "serde")]
+pub use bytes::{binary, Bytes};
+#[cfg(feature = "signals")]
+pub use cancellation::install_sigterm_handler;
+pub use cancellation::{request_shutdown, shutdown_requested, CancelReason, CancellationToken};
+pub use client_init::{ClientInit, CLIENT_INIT_ENV};
+pub use clock::{Clock, ManualClock, SharedClock, SystemClock};
+#[cfg(feature = "serde")]
+pub use cloudformation::{
+    CustomResourceRequest, CustomResourceRequestType, CustomResourceResponse, CustomResourceStatus,
+    PresignedPut, CUSTOM_RESOURCE_RESPONSE_LIMIT,
+};
+#[cfg(feature = "serde")]
+pub use codec::{typed, typed_with, Codec, EventCodec, TextCodec, MARSHAL_ERROR, UNMARSHAL_ERROR};
+#[cfg(feature = "serde")]
+pub use cognito::{
+    ClaimsOverrideDetails, CognitoAttributes, CognitoCallerContext, CognitoEvent,
+    CognitoPostConfirmationEvent, CognitoPreSignUpEvent, CognitoPreTokenGenerationEvent,
+    GroupConfiguration, PostConfirmationRequest, PostConfirmationResponse, PreSignUpRequest,
+    PreSignUpResponse, PreTokenGenerationRequest, PreTokenGenerationResponse,
+};
+pub use config::{RuntimeConfig, DEFAULT_RUNTIME_API, RUNTIME_API_ENV};
+#[cfg(feature = "serde")]
+pub use connect::{
+    ConnectContactData, ConnectDetails, ConnectEndpoint, ConnectEvent, ConnectQueue,
+    ConnectResponse,
+};
+pub use context::Context;
+#[cfg(feature = "serde")]
+pub use correlation::{correlation_id_from_event, CorrelationId};
+pub use correlation::{current_correlation_id, CORRELATION_ID_HEADER};
+#[cfg(feature = "serde")]
+pub use cors::Cors;
+pub use cpu_time::CpuTime;
+#[cfg(feature = "sigv4")]
+pub use credentials::{
+    AssumeRole, ContainerCredentials, CredentialsProvider, EnvCredentials,
+    CONTAINER_CREDENTIALS_HOST, CREDENTIALS_REFRESH_MARGIN, DEFAULT_SESSION_DURATION,
+};
+pub use deadline::{
+    invocation_deadline, io_timeout, io_timeout_with, set_invocation_deadline, DEADLINE_RESERVE,
+};
+#[cfg(feature = "dynamodb")]
+pub use dynamodb::{DynamoDbClient, Query, QueryOutput};
+#[cfg(feature = "serde")]
+pub use dynamodb_event::{
+    item_to_json, AttributeValue, DynamoDbEvent, DynamoDbItem, DynamoDbRecord, StreamRecord,
+};
+#[cfg(feature = "serde")]
+pub use etag::{conditional_response, etag_matches, weak_etag};
+#[cfg(feature = "serde")]
 pub use event::{LambdaEvent, RequestContext};
-use http_client::HttpClient;
-pub use logger::{LogLevel, Logger};
+pub use event_stream::EventStream;
+#[cfg(feature = "serde")]
+pub use extension::{
+    on_shutdown, run_shutdown_hooks, ExtensionClient, ExtensionEvent, ExtensionEventType,
+    EXTENSION_ID_HEADER,
+};
+#[cfg(feature = "serde")]
+pub use failure_record::{FailureCondition, FailureRecord};
+#[cfg(feature = "serde")]
+pub use firehose::{
+    FirehoseEvent, FirehoseMetadata, FirehoseOutputRecord, FirehoseRecord, FirehoseResponse,
+    FirehoseResult,
+};
+pub use handler_error::{HandlerError, ERROR_TYPE_HEADER};
+use http_client::{HttpClient, HttpError};
+#[cfg(feature = "serde")]
+pub use http_request::HttpRequest;
+#[cfg(feature = "serde")]
+pub use idempotency::{
+    Idempotency, IdempotencyStore, InMemoryStore, KeySource, DEFAULT_IDEMPOTENCY_TTL,
+    DEFAULT_STORE_CAPACITY,
+};
+pub use inflate::{decompress, DecompressError, DEFAULT_DECOMPRESSED_LIMIT};
+pub use init_trace::{mark_init_phase, start_init_trace, InitPhase, INIT_TRACE, INIT_TRACE_ENV};
+pub use invocation::Invocation;
+#[cfg(feature = "serde")]
+pub use iot::IotRuleEvent;
+#[cfg(feature = "serde")]
+pub use kafka::{KafkaEvent, KafkaRecord};
+#[cfg(feature = "serde")]
+pub use lex::{
+    LexBot, LexDialogAction, LexEvent, LexIntent, LexInterpretation, LexMessage, LexResponse,
+    LexSessionState, LexSlot, LexSlotValue,
+};
+pub use lifecycle::{
+    InvocationLog, INVOCATION_END, INVOCATION_ERROR, INVOCATION_START, LIFECYCLE_LOG_ENV,
+};
+pub use log_sampling::{LogSampler, LOG_SAMPLE_ENV};
+pub use logger::{LogFormat, LogLevel, Logger};
+pub use memory::MemoryUsage;
+#[cfg(feature = "serde")]
+pub use metrics::{Metrics, EMF_MAX_METRICS};
+#[cfg(feature = "serde")]
+pub use middleware::{json_response, Flow, Middleware, Pipeline};
+#[cfg(feature = "serde")]
+pub use mq::{
+    ActiveMqDestination, ActiveMqEvent, ActiveMqMessage, RabbitMqBasicProperties, RabbitMqEvent,
+    RabbitMqMessage,
+};
+pub use multipart::{Multipart, MultipartError, Part, DEFAULT_MAX_PARTS, DEFAULT_MAX_PART_SIZE};
+#[cfg(feature = "serde")]
+pub use ndjson::{parse_ndjson, to_ndjson, NdjsonError, NdjsonLines};
+pub use pipelining::PIPELINE_ENV;
+pub use prefetch::{Prefetcher, PREFETCH_ENV};
+#[cfg(feature = "sigv4")]
+pub use presign::{presign_s3_get, presign_s3_put, S3Presigner};
+#[cfg(feature = "protobuf")]
+pub use prost_handler::{ProstCodec, ProstHandler, PROTOBUF_CONTENT_TYPE};
+pub use query_string::{Cookies, QueryPairs};
+#[cfg(feature = "serde")]
+pub use records::{RecordIter, RecordIterError, RECORDS_KEY};
+pub use redaction::{Redactor, DEFAULT_PAYLOAD_EXCERPT_BYTES, REDACTED, REDACT_FIELDS_ENV};
+use request_ids::RequestIds;
+pub use retry::{RetryPolicy, DEFAULT_RETRY_STATUSES};
+#[cfg(feature = "serde")]
+pub use router::{ParamError, RouteParams, Router};
+pub use ruchy_lambda_http_core::{Dial, TcpDialer, Transport, DEFAULT_MAX_RESPONSE_SIZE};
+pub use ruchy_lambda_simd::base64::DecodeError as Base64DecodeError;
+#[cfg(feature = "s3")]
+pub use s3::{PutObjectOutput, S3Client, S3Object};
+#[cfg(feature = "serde")]
+pub use s3_batch::{
+    S3BatchEvent, S3BatchJob, S3BatchResponse, S3BatchResult, S3BatchResultCode, S3BatchTask,
+};
+#[cfg(feature = "serde")]
+pub use ses::{
+    SesAction, SesCommonHeaders, SesEvent, SesHeader, SesMail, SesMessage, SesReceipt, SesRecord,
+    SesVerdict,
+};
+#[cfg(feature = "sigv4")]
+pub use sigv4::{
+    AwsRequest, Credentials, SigV4Error, Signer, MAX_PRESIGN_EXPIRY, SIGNING_ALGORITHM,
+    UNSIGNED_PAYLOAD,
+};
+#[cfg(feature = "sqs")]
+pub use sqs::{
+    BatchFailure, BatchResultEntry, SendMessageBatchOutput, SendMessageOutput, SqsClient,
+    SqsMessage, MAX_BATCH_ENTRIES,
+};
+pub use timeout_guard::{
+    timeout_checkpoint, TimeoutGuard, DEFAULT_TIMEOUT_MARGIN, TIMEOUT_ERROR_TYPE, TIMEOUT_GUARD_ENV,
+};
+pub use trace_context::{TraceContext, TRACEPARENT_HEADER, TRACESTATE_HEADER, XRAY_TRACE_HEADER};
+#[cfg(feature = "tracing")]
+pub use tracing_adapter::TracingLogger;
+pub use unwind::panic_is_caught;
+#[cfg(feature = "serde")]
+pub use validation::{FieldType, Schema, ValidationError, ValidationMiddleware};
+pub use workers::WORKERS_ENV;
+#[cfg(feature = "xray")]
+pub use xray::{Subsegment, TraceHeader, XRayEmitter, XRAY_DAEMON_ADDRESS_ENV};
 
 /// Runtime error type
 #[derive(Debug)]
 pub enum Error {
     /// Initialization failed
     InitializationFailed(String),
+    /// Runtime API response body exceeded the configured maximum size
+    ResponseTooLarge {
+        /// Maximum body size in bytes (see [`Runtime::with_max_response_size`])
+        limit: usize,
+    },
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::InitializationFailed(msg) => write!(f, "Initialization failed: {msg}"),
+            Self::ResponseTooLarge { limit } => {
+                write!(f, "Runtime API response exceeds {limit} bytes")
+            }
         }
     }
 }
@@ -77,6 +379,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// - Invocation overhead: <100μs (Section 3.3)
 /// - Memory footprint: <64MB (Section 12.2)
 #[derive(Clone)]
+// Independent opt-in switches, not states of one machine
+#[allow(clippy::struct_excessive_bools)]
 pub struct Runtime {
     /// Lambda Runtime API endpoint (e.g., "127.0.0.1:9001")
     api_endpoint: String,
@@ -86,22 +390,81 @@ pub struct Runtime {
     /// Uses `OnceCell` for thread-safe lazy initialization
     /// Minimal HTTP client (no reqwest) for smaller binary size
     client: std::sync::Arc<OnceCell<HttpClient>>,
+
+    /// Opens Runtime API connections ([`TcpDialer`] unless replaced)
+    dialer: std::sync::Arc<dyn Dial>,
+
+    /// Maximum accepted event body size in bytes
+    /// Defaults to [`DEFAULT_MAX_RESPONSE_SIZE`] (Lambda's 6MB payload limit)
+    max_response_size: usize,
+
+    /// When the first Runtime API connection is opened (see [`Runtime::prepare`])
+    client_init: ClientInit,
+
+    /// Whether the bootstrap should prefetch events on a background thread
+    prefetch: bool,
+
+    /// Whether response POSTs pipeline the next `/next` GET (see [`Runtime::with_pipelining`])
+    pipelining: bool,
+
+    /// Worker threads for local emulation (always 1 on the Lambda service)
+    workers: usize,
+
+    /// Whether the bootstrap should log invocation lifecycle events
+    lifecycle_log: bool,
+
+    /// Whether the first invocation logs the `init.trace` phase breakdown
+    init_trace: bool,
+
+    /// Watchdog the bootstrap runs handlers under (off by default)
+    timeout_guard: Option<TimeoutGuard>,
+
+    /// Request IDs returned by `/next` and not yet posted
+    request_ids: RequestIds,
+
+    /// Payload convention of [`Runtime::typed_handler`]: the registered
+    /// `Arc<dyn EventCodec<Req, Resp>>`, if any
+    #[cfg(feature = "serde")]
+    event_codec: Option<std::sync::Arc<dyn std::any::Any + Send + Sync>>,
 }
 
 impl fmt::Debug for Runtime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Runtime")
+        let mut runtime = f.debug_struct("Runtime");
+        runtime
             .field("api_endpoint", &self.api_endpoint)
             .field("client", &"OnceCell<HttpClient>")
-            .finish()
+            .field("dialer", &"dyn Dial")
+            .field("max_response_size", &self.max_response_size)
+            .field("client_init", &self.client_init)
+            .field("prefetch", &self.prefetch)
+            .field("pipelining", &self.pipelining)
+            .field("workers", &self.workers)
+            .field("lifecycle_log", &self.lifecycle_log)
+            .field("init_trace", &self.init_trace)
+            .field("timeout_guard", &self.timeout_guard)
+            .field("request_ids", &self.request_ids);
+        #[cfg(feature = "serde")]
+        runtime.field("event_codec", &"dyn EventCodec");
+        runtime.finish()
     }
 }
 
+/// Long-polling Runtime API path handing out the next event
+const NEXT_PATH: &str = "/2018-06-01/runtime/invocation/next";
+
 impl Runtime {
     /// Create a new runtime instance
     ///
     /// Reads the `AWS_LAMBDA_RUNTIME_API` environment variable to determine
-    /// the Lambda Runtime API endpoint.
+    /// the Lambda Runtime API endpoint, `RUCHY_LAMBDA_CLIENT_INIT` for the
+    /// [`ClientInit`] strategy (default: lazy), `RUCHY_LAMBDA_PREFETCH`
+    /// for prefetch mode (default: off), `RUCHY_LAMBDA_PIPELINE` for
+    /// pipelining mode (default: off), `RUCHY_LAMBDA_WORKERS` for the
+    /// local-emulation worker count (default: 1) and
+    /// `RUCHY_LAMBDA_TIMEOUT_GUARD_MS` for the [`TimeoutGuard`] margin
+    /// (default: no guard). Use [`Runtime::from_config`] to pass these
+    /// settings without the environment.
     ///
     /// **Lazy Initialization**: HTTP client is NOT created here. It will be
     /// created on the first API call (`next_event()` or `post_response()`).
@@ -109,7 +472,10 @@ impl Runtime {
     ///
     /// # Errors
     ///
-    /// Returns `Error::InitializationFailed` if runtime setup fails.
+    /// Returns `Error::InitializationFailed` if runtime setup fails,
+    /// `RUCHY_LAMBDA_CLIENT_INIT` is not a valid [`ClientInit`],
+    /// `RUCHY_LAMBDA_WORKERS` is not a positive integer or
+    /// `RUCHY_LAMBDA_TIMEOUT_GUARD_MS` is not a non-negative integer.
     /// Note: HTTP client creation errors are deferred to first use.
     ///
     /// # Performance
@@ -129,18 +495,389 @@ impl Runtime {
     pub fn new() -> Result<Self> {
         // Read AWS Lambda Runtime API endpoint (fast: just env var read)
         // This is provided by Lambda: http://${AWS_LAMBDA_RUNTIME_API}
-        let api_endpoint =
-            env::var("AWS_LAMBDA_RUNTIME_API").unwrap_or_else(|_| "127.0.0.1:9001".to_string());
+        let config = RuntimeConfig::from_env()?;
+        mark_init_phase(InitPhase::EnvRead);
+        Ok(Self::from_config(config))
+    }
 
+    /// Create a runtime from `config` without reading the environment
+    ///
+    /// Tests use this to run against their own endpoint in parallel; see
+    /// [`RuntimeConfig`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
+    ///
+    /// let runtime = Runtime::from_config(RuntimeConfig {
+    ///     workers: 2,
+    ///     ..RuntimeConfig::new("127.0.0.1:9009")
+    /// });
+    /// assert!(format!("{runtime:?}").contains("127.0.0.1:9009"));
+    /// ```
+    #[must_use]
+    pub fn from_config(config: RuntimeConfig) -> Self {
         // LAZY INITIALIZATION: Don't create HTTP client yet
         // Client will be created on first API call (next_event/post_response)
         // This reduces initialization time from ~5ms to <1ms
-        Ok(Self {
-            api_endpoint,
+        Self {
+            api_endpoint: config.api_endpoint,
             client: std::sync::Arc::new(OnceCell::new()),
-        })
+            dialer: std::sync::Arc::new(TcpDialer),
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            client_init: config.client_init,
+            prefetch: config.prefetch,
+            pipelining: config.pipelining,
+            workers: config.workers.max(1),
+            lifecycle_log: config.lifecycle_log,
+            init_trace: config.init_trace,
+            timeout_guard: config.timeout_guard,
+            request_ids: RequestIds::default(),
+            #[cfg(feature = "serde")]
+            event_codec: None,
+        }
     }
 
+    /// Set the number of worker threads for local emulation
+    ///
+    /// Values below 1 are treated as 1. Ignored on the Lambda service, which
+    /// runs one invocation per execution environment.
+    #[must_use]
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Effective worker count: the configured value locally, 1 on Lambda
+    #[must_use]
+    pub fn workers(&self) -> usize {
+        if workers::is_lambda_service() {
+            1
+        } else {
+            self.workers
+        }
+    }
+
+    /// Spawn [`Runtime::workers`] threads, each with its own connection
+    ///
+    /// `worker` receives the worker index and a runtime whose HTTP client is
+    /// not shared with any other worker, simulating concurrent execution
+    /// environments against a local emulator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS refuses to spawn a thread.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ruchy_lambda_runtime::Runtime;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let runtime = Runtime::new()?.with_workers(4);
+    /// let handles = runtime.spawn_workers(|id, runtime| loop {
+    ///     if let Ok((request_id, body)) = runtime.next_event() {
+    ///         let _ = runtime.post_response(&request_id, &body);
+    ///     }
+    ///     # let _ = id;
+    /// });
+    /// for handle in handles {
+    ///     let _ = handle.join();
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spawn_workers<F>(&self, worker: F) -> Vec<std::thread::JoinHandle<()>>
+    where
+        F: Fn(usize, &Runtime) + Send + Sync + 'static,
+    {
+        workers::spawn(self, self.workers(), worker)
+    }
+
+    /// Copy of this runtime with its own (not yet created) HTTP client
+    fn isolated(&self) -> Self {
+        Self {
+            client: std::sync::Arc::new(OnceCell::new()),
+            ..self.clone()
+        }
+    }
+
+    /// Enable or disable double-buffered event prefetch (off by default)
+    ///
+    /// The flag is advisory: callers check [`Runtime::prefetch_enabled`] and
+    /// drive events through [`Runtime::spawn_prefetcher`] instead of
+    /// [`Runtime::next_event`].
+    #[must_use]
+    pub fn with_prefetch(mut self, prefetch: bool) -> Self {
+        self.prefetch = prefetch;
+        // Pipelining depends on it: drop any client built before
+        self.client = std::sync::Arc::new(OnceCell::new());
+        self
+    }
+
+    /// Whether prefetch mode is enabled
+    #[must_use]
+    pub fn prefetch_enabled(&self) -> bool {
+        self.prefetch
+    }
+
+    /// Enable or disable HTTP/1.1 pipelining (off by default)
+    ///
+    /// Keeps one Runtime API connection alive, and `post_response` and
+    /// `post_error` write the next `/next` GET right behind the POST, so the
+    /// following `next_*` call finds its event already requested. Saves a
+    /// round trip per warm invocation. Ignored while prefetch is enabled,
+    /// since the prefetcher polls `/next` itself.
+    #[must_use]
+    pub fn with_pipelining(mut self, pipelining: bool) -> Self {
+        self.pipelining = pipelining;
+        // Drop any client built for the other mode
+        self.client = std::sync::Arc::new(OnceCell::new());
+        self
+    }
+
+    /// Whether pipelining mode is enabled
+    #[must_use]
+    pub fn pipelining_enabled(&self) -> bool {
+        self.pipelining
+    }
+
+    /// Enable or disable invocation lifecycle events (off by default)
+    ///
+    /// Like prefetch, the flag is advisory: the event loop checks
+    /// [`Runtime::lifecycle_log_enabled`] and wraps each invocation in an
+    /// [`InvocationLog`].
+    #[must_use]
+    pub fn with_lifecycle_log(mut self, lifecycle_log: bool) -> Self {
+        self.lifecycle_log = lifecycle_log;
+        self
+    }
+
+    /// Whether lifecycle events are enabled
+    #[must_use]
+    pub fn lifecycle_log_enabled(&self) -> bool {
+        self.lifecycle_log
+    }
+
+    /// Enable or disable the `init.trace` line (off by default)
+    ///
+    /// When enabled, the first `/next` response logs how long each
+    /// [`InitPhase`] took, under the first request ID.
+    #[must_use]
+    pub fn with_init_trace(mut self, init_trace: bool) -> Self {
+        self.init_trace = init_trace;
+        self
+    }
+
+    /// Whether the `init.trace` line is enabled
+    #[must_use]
+    pub fn init_trace_enabled(&self) -> bool {
+        self.init_trace
+    }
+
+    /// Run handlers under `timeout_guard` (`None` disables it)
+    ///
+    /// Advisory like prefetch: the event loop checks
+    /// [`Runtime::timeout_guard`] and posts the guard's `Function.Timeout`
+    /// error for handlers it abandons.
+    #[must_use]
+    pub fn with_timeout_guard(mut self, timeout_guard: Option<TimeoutGuard>) -> Self {
+        self.timeout_guard = timeout_guard;
+        self
+    }
+
+    /// The configured timeout guard, if any
+    #[must_use]
+    pub fn timeout_guard(&self) -> Option<TimeoutGuard> {
+        self.timeout_guard
+    }
+
+    /// Start long-polling `/next` on a background thread
+    ///
+    /// While the caller handles event N, the returned [`Prefetcher`] already
+    /// fetches event N+1. At most one event is fetched ahead.
+    #[must_use]
+    pub fn spawn_prefetcher(&self) -> Prefetcher {
+        Prefetcher::spawn(self.clone())
+    }
+
+    /// Set the client initialization strategy applied by [`Runtime::prepare`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{ClientInit, Runtime};
+    ///
+    /// let runtime = Runtime::new()
+    ///     .expect("Failed to initialize runtime")
+    ///     .with_client_init(ClientInit::Background);
+    /// assert_eq!(runtime.client_init(), ClientInit::Background);
+    /// ```
+    #[must_use]
+    pub fn with_client_init(mut self, client_init: ClientInit) -> Self {
+        self.client_init = client_init;
+        self
+    }
+
+    /// Configured client initialization strategy
+    #[must_use]
+    pub fn client_init(&self) -> ClientInit {
+        self.client_init
+    }
+
+    /// Apply the [`ClientInit`] strategy once initialization is done
+    ///
+    /// Call after `Runtime::new()` and before the first `next_event()`:
+    ///
+    /// - [`ClientInit::Lazy`]: no-op, the first `next` connects
+    /// - [`ClientInit::Eager`]: opens the connection now (blocking)
+    /// - [`ClientInit::Background`]: opens it on a background thread, using
+    ///   the pre-invoke window without delaying init
+    ///
+    /// A failed background pre-connect is ignored; the first `next` simply
+    /// connects on its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if an eager pre-connect fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ruchy_lambda_runtime::{ClientInit, Runtime};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let runtime = Runtime::new()?.with_client_init(ClientInit::Background);
+    /// runtime.prepare()?;
+    /// let (request_id, event_body) = runtime.next_event()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prepare(&self) -> Result<()> {
+        let prepared = match self.client_init {
+            ClientInit::Lazy => Ok(()),
+            ClientInit::Eager => self
+                .get_client()?
+                .preconnect()
+                .map_err(|e| Error::InitializationFailed(format!("Failed to pre-connect: {e}"))),
+            ClientInit::Background => {
+                let runtime = self.clone();
+                std::thread::spawn(move || {
+                    if let Ok(client) = runtime.get_client() {
+                        let _ = client.preconnect();
+                    }
+                });
+                Ok(())
+            }
+        };
+        mark_init_phase(InitPhase::ClientInit);
+        prepared
+    }
+
+    /// Set the maximum accepted event body size in bytes
+    ///
+    /// `/next` responses are read incrementally and rejected with
+    /// [`Error::ResponseTooLarge`] as soon as they exceed this limit. The
+    /// default of [`DEFAULT_MAX_RESPONSE_SIZE`] matches Lambda's 6MB
+    /// synchronous payload limit; response-streaming functions may need up
+    /// to 20MB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::Runtime;
+    ///
+    /// let runtime = Runtime::new()
+    ///     .expect("Failed to initialize runtime")
+    ///     .with_max_response_size(20 * 1024 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        // Drop any client built with the previous limit
+        self.client = std::sync::Arc::new(OnceCell::new());
+        self
+    }
+
+    /// Open Runtime API connections through `dialer` instead of TCP
+    ///
+    /// For tests (an in-memory Runtime API, see the testkit's `transport`)
+    /// and for endpoints behind Unix sockets. The endpoint is still passed
+    /// to the dialer and sent as `Host`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Runtime, Transport};
+    /// use std::io;
+    ///
+    /// let runtime = Runtime::new()
+    ///     .expect("Failed to initialize runtime")
+    ///     .with_dialer(|_endpoint: &str| -> io::Result<Box<dyn Transport>> {
+    ///         Err(io::ErrorKind::ConnectionRefused.into())
+    ///     });
+    /// assert!(runtime.next_event().is_err());
+    /// ```
+    #[must_use]
+    pub fn with_dialer(mut self, dialer: impl Dial + 'static) -> Self {
+        self.dialer = std::sync::Arc::new(dialer);
+        // Drop any client connecting through the previous dialer
+        self.client = std::sync::Arc::new(OnceCell::new());
+        self
+    }
+
+    /// Decode events and encode responses of typed handlers with `codec`
+    ///
+    /// Applies to handlers over `Req` and `Resp` adapted by
+    /// [`Runtime::typed_handler`], e.g. a [`TextCodec`] for plain-text
+    /// payloads, a [`Codec`] for JSON and the binary formats, or a
+    /// `ProstCodec` for protobuf. A codec for many types (such as
+    /// [`Codec`]) is registered for one pair by naming it:
+    /// `with_event_codec::<Order, Quote>(Codec::Cbor)`.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn with_event_codec<Req: 'static, Resp: 'static>(
+        mut self,
+        codec: impl EventCodec<Req, Resp> + 'static,
+    ) -> Self {
+        let codec: std::sync::Arc<dyn EventCodec<Req, Resp>> = std::sync::Arc::new(codec);
+        self.event_codec = Some(std::sync::Arc::new(codec));
+        self
+    }
+
+    /// Adapt a typed handler to the string handler shape through the
+    /// registered [`EventCodec`]
+    ///
+    /// See [`typed_with`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the codec registered with [`Runtime::with_event_codec`] is
+    /// not for `Req` and `Resp`, or none is: a startup mistake. Use
+    /// [`typed`] for JSON without registering a codec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Context, HandlerError, Runtime, RuntimeConfig, TextCodec};
+    ///
+    /// let runtime = Runtime::from_config(RuntimeConfig::default()).with_event_codec(TextCodec);
+    /// let handler = runtime.typed_handler(|_ctx: &Context, name: String| {
+    ///     Ok::<_, HandlerError>(format!("hello {name}"))
+    /// });
+    /// assert_eq!(handler(&Context::default(), "ada").unwrap(), "hello ada");
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn typed_handler<Req: 'static, Resp: 'static>(
+        &self,
+        handler: impl Fn(&Context, Req) -> std::result::Result<Resp, HandlerError>,
+    ) -> impl Fn(&Context, &str) -> std::result::Result<String, HandlerError> {
+        let codec = self
+            .event_codec
+            .as_ref()
+            .and_then(|codec| codec.downcast_ref::<std::sync::Arc<dyn EventCodec<Req, Resp>>>())
+            .expect("no EventCodec registered for the handler's request and response types");
+        typed_with(std::sync::Arc::clone(codec), handler)
+    }
     /// Get or create the HTTP client (lazy initialization)
     ///
     /// This function is called by `next_event()` and `post_response()`.
@@ -150,7 +887,12 @@ impl Runtime {
         self.client
             .get_or_try_init(|| {
                 // Create minimal HTTP client (no reqwest overhead)
-                Ok::<HttpClient, Error>(HttpClient::new(self.api_endpoint.clone()))
+                Ok::<HttpClient, Error>(
+                    HttpClient::new(self.api_endpoint.clone())
+                        .with_max_response_size(self.max_response_size)
+                        .with_dialer(std::sync::Arc::clone(&self.dialer))
+                        .with_pipelining(self.pipelining && !self.prefetch),
+                )
             })
             .map_err(|e| Error::InitializationFailed(format!("HTTP client creation failed: {e}")))
     }
@@ -169,9 +911,13 @@ impl Runtime {
     /// - `request_id` is extracted from `Lambda-Runtime-Aws-Request-Id` response header
     /// - `event_body` is the raw user event payload (not wrapped in requestContext)
     ///
+    /// Use [`Runtime::next_invocation`] to also receive the deadline, function
+    /// ARN and trace header.
+    ///
     /// # Errors
     ///
-    /// Returns `Error::InitializationFailed` if the API request fails.
+    /// Returns `Error::InitializationFailed` if the API request fails, or
+    /// `Error::ResponseTooLarge` if the event exceeds the size limit.
     ///
     /// # Examples
     ///
@@ -186,21 +932,160 @@ impl Runtime {
     /// # }
     /// ```
     pub fn next_event(&self) -> Result<(String, String)> {
-        let path = "/2018-06-01/runtime/invocation/next";
+        let (context, event_body) = self.next_invocation()?;
+        Ok((context.request_id, event_body))
+    }
+
+    /// Get the next Lambda event together with its invocation [`Context`]
+    ///
+    /// Same long-polling request as [`Runtime::next_event`], but exposes all
+    /// `Lambda-Runtime-*` metadata headers (deadline, function ARN, trace ID).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the API request fails, or
+    /// `Error::ResponseTooLarge` if the event exceeds the size limit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ruchy_lambda_runtime::Runtime;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let runtime = Runtime::new()?;
+    /// let (context, event_body) = runtime.next_invocation()?;
+    /// if context.remaining_time_ms() < 100 {
+    ///     // Not enough time left to do real work
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_invocation(&self) -> Result<(Context, String)> {
+        let path = NEXT_PATH;
 
         // Lazy initialization: creates client on first call
         let client = self.get_client()?;
 
-        client
-            .get(path)
-            .map_err(|e| Error::InitializationFailed(format!("Failed to get next event: {e}")))
+        mark_init_phase(InitPhase::FirstNext);
+        let (context, event_body) = client.get(path).map_err(Self::next_event_error)?;
+        self.trace_init(&context.request_id);
+        self.request_ids.issued(&context.request_id);
+        deadline::set_invocation_deadline(deadline::from_millis(context.deadline_ms));
+        Ok((context, event_body))
+    }
+
+    /// Get the next Lambda event with its body still on the connection
+    ///
+    /// Streaming variant of [`Runtime::next_invocation`]: only the response
+    /// head is read before returning, and the [`EventStream`] hands the body
+    /// to a deserializer (see [`EventStream::deserialize`]) or a raw consumer
+    /// as it arrives. For multi-MB events this avoids buffering the whole
+    /// response and copying the body into a `String`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the API request fails, or
+    /// `Error::ResponseTooLarge` if the advertised event size exceeds the
+    /// limit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ruchy_lambda_runtime::Runtime;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let runtime = Runtime::new()?;
+    /// let (context, mut stream) = runtime.next_invocation_stream()?;
+    /// let bytes = std::io::copy(&mut stream, &mut std::io::sink())?;
+    /// runtime.post_response(&context.request_id, &bytes.to_string())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_invocation_stream(&self) -> Result<(Context, EventStream)> {
+        let path = NEXT_PATH;
+
+        let client = self.get_client()?;
+
+        mark_init_phase(InitPhase::FirstNext);
+        let (context, stream) = client.get_stream(path).map_err(Self::next_event_error)?;
+        self.trace_init(&context.request_id);
+        self.request_ids.issued(&context.request_id);
+        deadline::set_invocation_deadline(deadline::from_millis(context.deadline_ms));
+        Ok((context, stream))
+    }
+
+    /// Get the next Lambda event, borrowed from a caller-provided buffer
+    ///
+    /// Zero-copy variant of [`Runtime::next_invocation`]: the response is
+    /// read into `buffer` (cleared first, capacity reused) and the returned
+    /// [`Invocation`] borrows its request ID, headers and body from it. Once
+    /// `buffer` has grown to fit a typical event, this performs no heap
+    /// allocation per invocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the API request fails, or
+    /// `Error::ResponseTooLarge` if the event exceeds the size limit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ruchy_lambda_runtime::Runtime;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let runtime = Runtime::new()?;
+    /// let mut buffer = Vec::with_capacity(64 * 1024);
+    /// let invocation = runtime.next_invocation_into(&mut buffer)?;
+    /// println!("{}: {} bytes", invocation.request_id, invocation.body.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn next_invocation_into<'buf>(
+        &self,
+        buffer: &'buf mut Vec<u8>,
+    ) -> Result<Invocation<'buf>> {
+        let path = NEXT_PATH;
+
+        let client = self.get_client()?;
+
+        mark_init_phase(InitPhase::FirstNext);
+        let invocation = client
+            .get_into(path, buffer)
+            .map_err(Self::next_event_error)?;
+        self.trace_init(invocation.request_id);
+        self.request_ids.issued(invocation.request_id);
+        deadline::set_invocation_deadline(deadline::from_millis(
+            invocation
+                .header("lambda-runtime-deadline-ms")
+                .and_then(|ms| ms.parse().ok())
+                .unwrap_or(0),
+        ));
+        Ok(invocation)
+    }
+
+    /// Log `init.trace` with the first invocation, if enabled
+    fn trace_init(&self, request_id: &str) {
+        if self.init_trace {
+            init_trace::emit_once(request_id);
+        }
+    }
+
+    /// Map a `/next` request failure onto the runtime error type
+    fn next_event_error(error: HttpError) -> Error {
+        match error {
+            HttpError::ResponseTooLarge { limit } => Error::ResponseTooLarge { limit },
+            e => Error::InitializationFailed(format!("Failed to get next event: {e}")),
+        }
     }
 
     /// Post a response to the Lambda Runtime API
     ///
     /// **Phase 3**: Converted to blocking I/O (removed async/await)
     ///
-    /// Makes a POST request to `/2018-06-01/runtime/invocation/{request_id}/response`
+    /// Makes a POST request to `/2018-06-01/runtime/invocation/{request_id}/response`,
+    /// then runs the invocation's [background tasks](Context::spawn_background)
+    /// (also when the POST fails).
+    ///
+    /// `request_id` should be one returned by `/next` and not yet posted
+    /// successfully; posting any other ID is logged as a warning and panics
+    /// in debug builds. After a failed POST the same ID may be posted again.
     ///
     /// # Errors
     ///
@@ -219,17 +1104,172 @@ impl Runtime {
     /// # }
     /// ```
     pub fn post_response(&self, request_id: &str, response_body: &str) -> Result<()> {
+        self.check_request_id(request_id);
         let path = format!("/2018-06-01/runtime/invocation/{request_id}/response");
 
         // Lazy initialization: creates client on first call
+        let posted = self.get_client().and_then(|client| {
+            client
+                .post_then_next(&path, response_body, &[], NEXT_PATH)
+                .map_err(|e| Error::InitializationFailed(format!("Failed to post response: {e}")))
+        });
+        if posted.is_ok() {
+            self.request_ids.complete(request_id);
+        }
+
+        // Post-response window: the caller already has the result
+        background::run(request_id);
+        deadline::set_invocation_deadline(None);
+        #[cfg(feature = "arena")]
+        arena::reset_scratch();
+
+        posted
+    }
+
+    /// Report an invocation error to the Lambda Runtime API
+    ///
+    /// Makes a POST request to `/2018-06-01/runtime/invocation/{request_id}/error`
+    /// with the Lambda error document as body and the error type in the
+    /// `Lambda-Runtime-Function-Error-Type` header. Background tasks run
+    /// afterwards, and `request_id` is checked, as for
+    /// [`Runtime::post_response`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the API request fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use ruchy_lambda_runtime::{HandlerError, Runtime};
+    /// # use std::env;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:9001");
+    /// let runtime = Runtime::new()?;
+    /// let error = HandlerError::new("Function.ValidationError", "missing field: name");
+    /// runtime.post_error("req-123", &error)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn post_error(&self, request_id: &str, error: &HandlerError) -> Result<()> {
+        self.check_request_id(request_id);
+        let path = format!("/2018-06-01/runtime/invocation/{request_id}/error");
+
+        let posted = self.get_client().and_then(|client| {
+            client
+                .post_then_next(
+                    &path,
+                    &error.to_json(),
+                    &[error.error_type_header()],
+                    NEXT_PATH,
+                )
+                .map_err(|e| Error::InitializationFailed(format!("Failed to post error: {e}")))
+        });
+        if posted.is_ok() {
+            self.request_ids.complete(request_id);
+        }
+
+        background::run(request_id);
+        deadline::set_invocation_deadline(None);
+        #[cfg(feature = "arena")]
+        arena::reset_scratch();
+
+        posted
+    }
+
+    /// Warn (and fail debug builds) when posting under an ID `/next` did not return
+    fn check_request_id(&self, request_id: &str) {
+        let known = self.request_ids.check(request_id);
+        debug_assert!(
+            known,
+            "posting for request ID {request_id:?}, which /next did not return"
+        );
+    }
+
+    /// Report an initialization error to the Lambda Runtime API
+    ///
+    /// Makes a POST request to `/2018-06-01/runtime/init/error` with the
+    /// `Lambda-Runtime-Function-Error-Type` header. Lambda terminates the
+    /// execution environment after receiving it, so this should be the last
+    /// call before exiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the API request fails.
+    pub fn post_init_error(&self, error: &HandlerError) -> Result<()> {
+        let path = "/2018-06-01/runtime/init/error";
+
         let client = self.get_client()?;
 
         client
-            .post(&path, response_body)
-            .map_err(|e| Error::InitializationFailed(format!("Failed to post response: {e}")))?;
+            .post_with_headers(path, &error.to_json(), &[error.error_type_header()])
+            .map_err(|e| Error::InitializationFailed(format!("Failed to post init error: {e}")))?;
 
         Ok(())
     }
+
+    /// Shared per-process state of type `T`, default-initialized on first use
+    ///
+    /// See [`Runtime::state_with`].
+    #[must_use]
+    pub fn state<T: Default + Send + Sync + 'static>(&self) -> &'static T {
+        state::get_or_init(T::default)
+    }
+
+    /// Shared per-process state of type `T`, created by `init` on first use
+    ///
+    /// Survives across warm invocations, so handlers can cache connections
+    /// or parsed config. There is one slot per type for the whole process
+    /// (shared by `Runtime` clones and worker threads); `init` runs once,
+    /// concurrent first callers wait for it, and its duration is logged as
+    /// `init_us`. Use interior mutability (`Mutex`, atomics) for state that
+    /// changes between invocations.
+    ///
+    /// `init` must not request the same type again (it would deadlock).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::Runtime;
+    /// use std::env;
+    ///
+    /// struct Config {
+    ///     table: String,
+    /// }
+    ///
+    /// env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:9001");
+    /// let runtime = Runtime::new().unwrap();
+    /// let config = runtime.state_with(|| Config { table: "orders".to_string() });
+    /// assert_eq!(config.table, "orders");
+    ///
+    /// // Later invocations get the same instance
+    /// let again = runtime.state_with(|| Config { table: "unused".to_string() });
+    /// assert!(std::ptr::eq(config, again));
+    /// ```
+    pub fn state_with<T: Send + Sync + 'static>(&self, init: impl FnOnce() -> T) -> &'static T {
+        state::get_or_init(init)
+    }
+
+    /// Shared per-process state of type `T`, created by fallible `init`
+    ///
+    /// A failed `init` leaves the slot empty, so the next invocation retries
+    /// (e.g. a database that was briefly unreachable).
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `init`.
+    pub fn try_state_with<T: Send + Sync + 'static, E>(
+        &self,
+        init: impl FnOnce() -> std::result::Result<T, E>,
+    ) -> std::result::Result<&'static T, E> {
+        state::get_or_try_init(init)
+    }
+
+    /// Shared state of type `T` if it has been initialized
+    #[must_use]
+    pub fn try_state<T: Send + Sync + 'static>(&self) -> Option<&'static T> {
+        state::get::<T>()
+    }
 }
 
 // Ensure Runtime is thread-safe (required for tokio)
@@ -241,6 +1281,7 @@ use serial_test::serial;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::env;
 
     #[test]
     #[serial]
@@ -252,7 +1293,6 @@ mod tests {
     // NEW TESTS: Increase coverage from 26.53% to ~80%+
 
     #[test]
-    #[serial]
     fn test_error_display() {
         let error = Error::InitializationFailed("test failure".to_string());
         let msg = format!("{error}");
@@ -261,66 +1301,60 @@ mod tests {
     }
 
     #[test]
-    #[serial]
+    fn test_response_too_large_display() {
+        let error = Error::ResponseTooLarge { limit: 6_291_456 };
+        assert_eq!(
+            error.to_string(),
+            "Runtime API response exceeds 6291456 bytes"
+        );
+    }
+
+    #[test]
     fn test_error_trait() {
         let error = Error::InitializationFailed("test".to_string());
         let _: &dyn StdError = &error;
     }
 
     #[test]
-    #[serial]
     fn test_runtime_debug() {
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:8888");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:8888"));
         let debug_str = format!("{runtime:?}");
         assert!(debug_str.contains("Runtime"));
         assert!(debug_str.contains("127.0.0.1:8888"));
         assert!(debug_str.contains("OnceCell<HttpClient>"));
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
     }
 
     #[test]
-    #[serial]
     fn test_runtime_clone() {
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:7777");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:7777"));
         let cloned = runtime.clone();
         assert_eq!(runtime.api_endpoint, cloned.api_endpoint);
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
     }
 
     #[test]
     #[serial]
     fn test_runtime_default_endpoint() {
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
+        env::remove_var(RUNTIME_API_ENV);
         let runtime = Runtime::new().unwrap();
         assert_eq!(runtime.api_endpoint, "127.0.0.1:9001");
     }
 
     #[test]
-    #[serial]
     fn test_runtime_custom_endpoint() {
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "custom-host:3000");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("custom-host:3000"));
         assert_eq!(runtime.api_endpoint, "custom-host:3000");
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
     }
 
     #[test]
-    #[serial]
     fn test_runtime_lazy_client_not_initialized() {
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:9999");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:9999"));
         // Client should NOT be initialized yet
         assert!(runtime.client.get().is_none());
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
     }
 
     #[test]
-    #[serial]
     fn test_get_client_initializes_once() {
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:5555");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:5555"));
 
         // First call initializes
         let client1 = runtime.get_client();
@@ -330,16 +1364,12 @@ mod tests {
         // Second call returns same instance
         let client2 = runtime.get_client();
         assert!(client2.is_ok());
-
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
     }
 
     #[test]
-    #[serial]
     fn test_next_event_error_connection_refused() {
         // Use non-existent endpoint to trigger connection error
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:19999");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19999"));
 
         let result = runtime.next_event();
         assert!(result.is_err());
@@ -349,16 +1379,213 @@ mod tests {
         } else {
             panic!("Expected InitializationFailed error");
         }
+    }
+
+    #[test]
+    fn test_with_max_response_size() {
+        let runtime = Runtime::from_config(RuntimeConfig::default());
+        assert_eq!(runtime.max_response_size, DEFAULT_MAX_RESPONSE_SIZE);
+
+        runtime.get_client().unwrap();
+        let runtime = runtime.with_max_response_size(1024);
+        assert_eq!(runtime.max_response_size, 1024);
+        // Client is rebuilt lazily with the new limit
+        assert!(runtime.client.get().is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test_runtime_new_reads_env() {
+        env::set_var(CLIENT_INIT_ENV, "eager");
+        env::set_var(WORKERS_ENV, "3");
+        let runtime = Runtime::new().unwrap();
+        assert_eq!(runtime.client_init(), ClientInit::Eager);
+        assert_eq!(runtime.workers, 3);
 
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
+        env::set_var(CLIENT_INIT_ENV, "never");
+        assert!(matches!(
+            Runtime::new(),
+            Err(Error::InitializationFailed(msg)) if msg.contains(CLIENT_INIT_ENV)
+        ));
+
+        env::remove_var(CLIENT_INIT_ENV);
+        env::remove_var(WORKERS_ENV);
+        assert_eq!(Runtime::new().unwrap().client_init(), ClientInit::Lazy);
+    }
+
+    #[test]
+    fn test_runtime_from_config() {
+        let config = RuntimeConfig {
+            client_init: ClientInit::Background,
+            prefetch: true,
+            pipelining: true,
+            workers: 0,
+            lifecycle_log: true,
+            init_trace: true,
+            timeout_guard: Some(TimeoutGuard::default()),
+            ..RuntimeConfig::new("127.0.0.1:9009")
+        };
+        let runtime = Runtime::from_config(config);
+        assert_eq!(runtime.api_endpoint, "127.0.0.1:9009");
+        assert_eq!(runtime.client_init(), ClientInit::Background);
+        assert!(runtime.prefetch_enabled());
+        assert!(runtime.pipelining_enabled());
+        assert_eq!(runtime.workers, 1);
+        assert!(runtime.lifecycle_log_enabled());
+        assert!(runtime.init_trace_enabled());
+        assert_eq!(runtime.timeout_guard(), Some(TimeoutGuard::default()));
+    }
+
+    #[test]
+    fn test_runtime_builders_override_config() {
+        let runtime = Runtime::from_config(RuntimeConfig::default())
+            .with_prefetch(true)
+            .with_pipelining(true)
+            .with_lifecycle_log(true)
+            .with_timeout_guard(Some(TimeoutGuard::default()))
+            .with_workers(0);
+        assert!(runtime.prefetch_enabled());
+        assert!(runtime.pipelining_enabled());
+        assert!(runtime.lifecycle_log_enabled());
+        assert_eq!(runtime.timeout_guard(), Some(TimeoutGuard::default()));
+        assert_eq!(runtime.workers, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_typed_handler_uses_registered_codec() {
+        let ctx = Context::default();
+        let shout = |_: &Context, text: String| Ok::<_, HandlerError>(text.to_uppercase());
+
+        let runtime = Runtime::from_config(RuntimeConfig::default())
+            .with_event_codec::<String, String>(Codec::Json);
+        assert_eq!(
+            runtime.typed_handler(shout)(&ctx, "text")
+                .unwrap_err()
+                .error_type,
+            UNMARSHAL_ERROR
+        );
+        assert_eq!(
+            runtime.typed_handler(shout)(&ctx, r#""a""#).unwrap(),
+            r#""A""#
+        );
+
+        let runtime = runtime.with_event_codec(TextCodec);
+        assert_eq!(runtime.typed_handler(shout)(&ctx, "text").unwrap(), "TEXT");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    #[should_panic(expected = "no EventCodec registered")]
+    fn test_typed_handler_without_codec_for_its_types() {
+        let runtime = Runtime::from_config(RuntimeConfig::default()).with_event_codec(TextCodec);
+        let _ = runtime.typed_handler(|_: &Context, n: u32| Ok::<_, HandlerError>(n));
     }
 
     #[test]
     #[serial]
+    fn test_runtime_workers_forced_to_one_on_lambda() {
+        let runtime = Runtime::new().unwrap().with_workers(8);
+        env::set_var("AWS_LAMBDA_INITIALIZATION_TYPE", "on-demand");
+        assert_eq!(runtime.workers(), 1);
+        env::remove_var("AWS_LAMBDA_INITIALIZATION_TYPE");
+        assert_eq!(runtime.workers(), 8);
+    }
+
+    #[test]
+    fn test_spawn_workers_isolated_clients() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let runtime = Runtime::from_config(RuntimeConfig::default()).with_workers(3);
+        runtime.get_client().unwrap();
+
+        let ran = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&ran);
+        let handles = runtime.spawn_workers(move |_, worker_runtime| {
+            // Each worker starts without the parent's client
+            assert!(worker_runtime.client.get().is_none());
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        assert_eq!(handles.len(), 3);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(ran.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_prefetcher_reports_fetch_errors() {
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19991"));
+
+        let prefetcher = runtime.spawn_prefetcher();
+        assert!(matches!(
+            prefetcher.next_event(),
+            Err(Error::InitializationFailed(msg)) if msg.contains("Failed to get next event")
+        ));
+    }
+
+    #[test]
+    fn test_prepare_lazy_does_not_create_client() {
+        let runtime = Runtime::from_config(RuntimeConfig::default());
+        runtime.prepare().unwrap();
+        assert!(runtime.client.get().is_none());
+    }
+
+    #[test]
+    fn test_prepare_eager_connection_refused() {
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19992"))
+            .with_client_init(ClientInit::Eager);
+
+        let result = runtime.prepare();
+        assert!(matches!(
+            result,
+            Err(Error::InitializationFailed(msg)) if msg.contains("Failed to pre-connect")
+        ));
+    }
+
+    #[test]
+    fn test_next_event_response_too_large() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: big-1\r\nContent-Length: 4096\r\n\r\n",
+            );
+        });
+
+        let runtime =
+            Runtime::from_config(RuntimeConfig::new(endpoint)).with_max_response_size(1024);
+        let result = runtime.next_event();
+        assert!(matches!(
+            result,
+            Err(Error::ResponseTooLarge { limit: 1024 })
+        ));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_next_invocation_into_connection_refused() {
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19994"));
+
+        let mut buffer = Vec::new();
+        let result = runtime.next_invocation_into(&mut buffer);
+        assert!(
+            matches!(result, Err(Error::InitializationFailed(msg)) if msg.contains("Failed to get next event"))
+        );
+    }
+
+    #[test]
     fn test_post_response_error_connection_refused() {
         // Use non-existent endpoint to trigger connection error
-        env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:19998");
-        let runtime = Runtime::new().unwrap();
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19998"));
 
         let result = runtime.post_response("test-id", r#"{"status":"ok"}"#);
         assert!(result.is_err());
@@ -368,12 +1595,37 @@ mod tests {
         } else {
             panic!("Expected InitializationFailed error");
         }
+    }
+
+    #[test]
+    fn test_post_error_connection_refused() {
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19997"));
+
+        let error = HandlerError::new("Function.Error", "boom");
+        let result = runtime.post_error("test-id", &error);
+
+        if let Err(Error::InitializationFailed(msg)) = result {
+            assert!(msg.contains("Failed to post error"));
+        } else {
+            panic!("Expected InitializationFailed error");
+        }
+    }
+
+    #[test]
+    fn test_post_init_error_connection_refused() {
+        let runtime = Runtime::from_config(RuntimeConfig::new("127.0.0.1:19996"));
+
+        let error = HandlerError::new("Runtime.InitError", "bad config");
+        let result = runtime.post_init_error(&error);
 
-        env::remove_var("AWS_LAMBDA_RUNTIME_API");
+        if let Err(Error::InitializationFailed(msg)) = result {
+            assert!(msg.contains("Failed to post init error"));
+        } else {
+            panic!("Expected InitializationFailed error");
+        }
     }
 
     #[test]
-    #[serial]
     fn test_runtime_send_sync() {
         fn is_send<T: Send>() {}
         fn is_sync<T: Sync>() {}
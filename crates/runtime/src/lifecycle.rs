@@ -0,0 +1,295 @@
+// Invocation Lifecycle Events
+//
+// One structured log line per lifecycle step, with stable names so
+// CloudWatch Logs Insights dashboards can rely on them:
+//
+//   {"level":"INFO",...,"request_id":"…","message":"invocation.start","event_bytes":512}
+//   {"level":"INFO",...,"request_id":"…","message":"invocation.end","event_bytes":512,
+//    "response_bytes":48,"duration_ms":1.234,"cpu_user_ms":0,"cpu_system_ms":0,
+//    "rss_kb":8192,"peak_rss_kb":9216}
+//   {"level":"ERROR",...,"request_id":"…","message":"invocation.error","event_bytes":512,
+//    "duration_ms":1.234,"error_type":"Runtime.PostFailed","error_message":"…"}
+//
+//   filter message = "invocation.end" | stats avg(duration_ms), max(response_bytes)
+//
+// The CPU time (see cpu_time.rs) and memory fields are only present on Linux.
+// With `InvocationLog::with_metrics` (feature "serde") the CPU time is also buffered as the
+// `CpuUserTime` / `CpuSystemTime` EMF metrics (see metrics.rs).
+//
+// Opt-in (RUCHY_LAMBDA_LIFECYCLE_LOG=1 or Runtime::with_lifecycle_log): the
+// Lambda service already logs START/END/REPORT, so these lines only pay off
+// when dashboards need the byte counts or per-request failures.
+
+use crate::cpu_time::CpuTime;
+use crate::logger::{LogLevel, Logger};
+use crate::memory::MemoryUsage;
+#[cfg(feature = "serde")]
+use crate::metrics::Metrics;
+use std::fmt;
+use std::time::Instant;
+
+/// Environment variable enabling lifecycle events (`1`, `true` or `on`)
+pub const LIFECYCLE_LOG_ENV: &str = "RUCHY_LAMBDA_LIFECYCLE_LOG";
+
+/// `message` of the line logged when an event is received
+pub const INVOCATION_START: &str = "invocation.start";
+
+/// `message` of the line logged after the response is posted
+pub const INVOCATION_END: &str = "invocation.end";
+
+/// `message` of the line logged when an invocation fails
+pub const INVOCATION_ERROR: &str = "invocation.error";
+
+/// Lifecycle of one invocation; logs `invocation.start` when created
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{InvocationLog, Logger};
+///
+/// let logger = Logger::new();
+/// let event = r#"{"orderId":"1001"}"#;
+/// let log = InvocationLog::start(&logger, "req-123", event.len());
+/// let response = r#"{"statusCode":200}"#;
+/// log.end(response.len());
+/// ```
+pub struct InvocationLog<'a> {
+    logger: &'a Logger,
+    request_id: &'a str,
+    event_bytes: usize,
+    started: Instant,
+    /// Process CPU time at start (`None` if not sampled)
+    cpu_started: Option<CpuTime>,
+    /// Metrics the CPU time is recorded in, if any
+    #[cfg(feature = "serde")]
+    metrics: Option<&'a Metrics>,
+}
+
+impl fmt::Debug for InvocationLog<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut log = f.debug_struct("InvocationLog");
+        log.field("request_id", &self.request_id)
+            .field("event_bytes", &self.event_bytes)
+            .field("started", &self.started)
+            .field("cpu_started", &self.cpu_started);
+        #[cfg(feature = "serde")]
+        log.field("metrics", &self.metrics.is_some());
+        log.finish_non_exhaustive()
+    }
+}
+
+impl<'a> InvocationLog<'a> {
+    /// Log `invocation.start` and start timing
+    #[must_use]
+    pub fn start(logger: &'a Logger, request_id: &'a str, event_bytes: usize) -> Self {
+        logger.log_json_fields(
+            LogLevel::Info,
+            Some(request_id),
+            INVOCATION_START,
+            &[("event_bytes", event_bytes.to_string())],
+        );
+        Self {
+            logger,
+            request_id,
+            event_bytes,
+            started: Instant::now(),
+            cpu_started: logger
+                .enabled(LogLevel::Info)
+                .then(CpuTime::current)
+                .flatten(),
+            #[cfg(feature = "serde")]
+            metrics: None,
+        }
+    }
+
+    /// Also record the invocation's CPU time in `metrics`
+    ///
+    /// [`InvocationLog::end`] and [`InvocationLog::error`] buffer it with
+    /// [`Metrics::put_cpu_time`], whatever the logger's level.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: &'a Metrics) -> Self {
+        if self.cpu_started.is_none() {
+            self.cpu_started = CpuTime::current();
+        }
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Log `invocation.end` once the response of `response_bytes` is posted
+    ///
+    /// Includes the CPU time used since the start (`cpu_user_ms`,
+    /// `cpu_system_ms`) and the process memory (`rss_kb`, `peak_rss_kb`)
+    /// where they can be sampled.
+    pub fn end(self, response_bytes: usize) {
+        let mut fields = vec![
+            ("event_bytes", self.event_bytes.to_string()),
+            ("response_bytes", response_bytes.to_string()),
+            ("duration_ms", self.duration_ms()),
+        ];
+        if let Some(used) = self.cpu_used() {
+            fields.push(("cpu_user_ms", used.user.as_millis().to_string()));
+            fields.push(("cpu_system_ms", used.system.as_millis().to_string()));
+        }
+        if self.logger.enabled(LogLevel::Info) {
+            if let Some(memory) = MemoryUsage::current() {
+                fields.push(("rss_kb", memory.rss_kb.to_string()));
+                fields.push(("peak_rss_kb", memory.peak_rss_kb.to_string()));
+            }
+        }
+        self.logger.log_json_fields(
+            LogLevel::Info,
+            Some(self.request_id),
+            INVOCATION_END,
+            &fields,
+        );
+    }
+
+    /// Log `invocation.error` for a failed invocation
+    pub fn error(self, error_type: &str, error_message: &str) {
+        // Recorded in the metrics only: the error line keeps its fields
+        self.cpu_used();
+        self.logger.log_json_fields(
+            LogLevel::Error,
+            Some(self.request_id),
+            INVOCATION_ERROR,
+            &[
+                ("event_bytes", self.event_bytes.to_string()),
+                ("duration_ms", self.duration_ms()),
+                ("error_type", json_string(error_type)),
+                ("error_message", json_string(error_message)),
+            ],
+        );
+    }
+
+    /// CPU time used since the start, also put into the metrics if any
+    fn cpu_used(&self) -> Option<CpuTime> {
+        let used = CpuTime::current()?.since(self.cpu_started?);
+        #[cfg(feature = "serde")]
+        if let Some(metrics) = self.metrics {
+            metrics.put_cpu_time(used);
+        }
+        Some(used)
+    }
+
+    /// Elapsed time as a JSON number with microsecond precision
+    fn duration_ms(&self) -> String {
+        format!("{:.3}", self.started.elapsed().as_secs_f64() * 1000.0)
+    }
+}
+
+fn json_string(value: &str) -> String {
+    format!("\"{}\"", Logger::escape_json(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_start_and_end() {
+        let buffer = SharedBuffer::default();
+        let logger = Logger::with_writer(Box::new(buffer.clone()));
+
+        InvocationLog::start(&logger, "req-1", 512).end(48);
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["message"], INVOCATION_START);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["request_id"], "req-1");
+        assert_eq!(lines[0]["event_bytes"], 512);
+
+        assert_eq!(lines[1]["message"], INVOCATION_END);
+        assert_eq!(lines[1]["request_id"], "req-1");
+        assert_eq!(lines[1]["event_bytes"], 512);
+        assert_eq!(lines[1]["response_bytes"], 48);
+        assert!(lines[1]["duration_ms"].as_f64().unwrap() >= 0.0);
+        if cfg!(target_os = "linux") {
+            assert!(lines[1]["cpu_user_ms"].is_u64());
+            assert!(lines[1]["cpu_system_ms"].is_u64());
+            assert!(lines[1]["rss_kb"].as_u64().unwrap() > 0);
+            assert!(lines[1]["peak_rss_kb"].as_u64().unwrap() > 0);
+        }
+    }
+
+    #[test]
+    fn test_error() {
+        let buffer = SharedBuffer::default();
+        let logger = Logger::with_writer(Box::new(buffer.clone()));
+
+        InvocationLog::start(&logger, "req-2", 3).error("Runtime.PostFailed", "broken \"pipe\"");
+
+        let lines = buffer.lines();
+        assert_eq!(lines[1]["message"], INVOCATION_ERROR);
+        assert_eq!(lines[1]["level"], "ERROR");
+        assert_eq!(lines[1]["event_bytes"], 3);
+        assert_eq!(lines[1]["error_type"], "Runtime.PostFailed");
+        assert_eq!(lines[1]["error_message"], "broken \"pipe\"");
+        assert!(lines[1].get("response_bytes").is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cpu_time_metrics() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::with_writer(Box::new(buffer.clone()));
+        logger.set_min_level(LogLevel::Error);
+        let emf = SharedBuffer::default();
+        let metrics = Metrics::new("shop").with_writer(Box::new(emf.clone()));
+
+        InvocationLog::start(&logger, "req-5", 1)
+            .with_metrics(&metrics)
+            .end(1);
+        InvocationLog::start(&logger, "req-6", 1)
+            .with_metrics(&metrics)
+            .error("E", "m");
+        metrics.flush().unwrap();
+
+        if cfg!(target_os = "linux") {
+            let document = &emf.lines()[0];
+            assert_eq!(document["CpuUserTime"].as_array().unwrap().len(), 2);
+            assert_eq!(document["CpuSystemTime"].as_array().unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_respects_min_level() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::with_writer(Box::new(buffer.clone()));
+        logger.set_min_level(LogLevel::Error);
+
+        InvocationLog::start(&logger, "req-3", 1).end(1);
+        InvocationLog::start(&logger, "req-4", 1).error("E", "m");
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["message"], INVOCATION_ERROR);
+    }
+}
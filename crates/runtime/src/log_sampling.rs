@@ -0,0 +1,240 @@
+// Log Sampling
+//
+// High-traffic functions pay CloudWatch ingestion for every DEBUG line.
+// A LogSampler keeps a fixed fraction of lines per level, e.g.
+//
+//   RUCHY_LAMBDA_LOG_SAMPLE="DEBUG=0.01,INFO=50%"
+//
+// keeps 1% of DEBUG and half of INFO lines, and all WARN/ERROR lines
+// (levels not listed are never sampled). Sampling is deterministic: with
+// rate r, line n of a level is kept when floor((n+1)·r) > floor(n·r), so
+// 1% keeps exactly every 100th line.
+//
+// The first line of each sampled level for a new request ID is always kept,
+// so every invocation leaves at least one debug line carrying its
+// request_id to search for.
+
+use crate::logger::LogLevel;
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// Environment variable with per-level sample rates (`LEVEL=rate,...`)
+pub const LOG_SAMPLE_ENV: &str = "RUCHY_LAMBDA_LOG_SAMPLE";
+
+/// Rates are kept as parts per million
+const PPM: u64 = 1_000_000;
+
+/// Per-level log sampler
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{LogLevel, LogSampler};
+///
+/// let sampler = LogSampler::parse("DEBUG=0.25");
+/// // The first DEBUG line of an invocation is always kept
+/// assert!(sampler.sample(LogLevel::Debug, Some("req-1")));
+/// let kept = (0..8).filter(|_| sampler.sample(LogLevel::Debug, Some("req-1"))).count();
+/// assert_eq!(kept, 2);
+/// assert!(sampler.sample(LogLevel::Warn, Some("req-1")));
+/// ```
+pub struct LogSampler {
+    /// Keep rate per level (indexed by `index`), in parts per million
+    rates: [u64; 4],
+    /// Lines seen per sampled level
+    seen: [AtomicU64; 4],
+    /// Last request ID whose first line was kept, per level
+    last_request_ids: Mutex<[Option<String>; 4]>,
+}
+
+impl LogSampler {
+    /// Sampler that keeps every line
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rates: [PPM; 4],
+            seen: Default::default(),
+            last_request_ids: Mutex::new(Default::default()),
+        }
+    }
+
+    /// Sampler configured from `RUCHY_LAMBDA_LOG_SAMPLE` (keep-all when unset)
+    #[must_use]
+    pub fn from_env() -> Self {
+        env::var(LOG_SAMPLE_ENV).map_or_else(|_| Self::new(), |value| Self::parse(&value))
+    }
+
+    /// Parse comma-separated `LEVEL=rate` entries
+    ///
+    /// Levels are case-insensitive (`DEBUG`, `INFO`, `WARN`/`WARNING`,
+    /// `ERROR`); rates are fractions (`0.01`) or percentages (`1%`), clamped
+    /// to 0..=1. Malformed entries are ignored.
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        let mut sampler = Self::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let Some((level, rate)) = entry.split_once('=') else {
+                continue;
+            };
+            let Some(level) = parse_level(level.trim()) else {
+                continue;
+            };
+            let Some(rate) = parse_rate(rate.trim()) else {
+                continue;
+            };
+            sampler = sampler.with_rate(level, rate);
+        }
+        sampler
+    }
+
+    /// Keep `rate` (0.0..=1.0) of the lines at `level`
+    #[must_use]
+    pub fn with_rate(mut self, level: LogLevel, rate: f64) -> Self {
+        let rate = if rate.is_nan() {
+            1.0
+        } else {
+            rate.clamp(0.0, 1.0)
+        };
+        // Clamped to 0..=1, so the product fits and is non-negative
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let ppm = (rate * 1_000_000.0).round() as u64;
+        self.rates[index(level)] = ppm;
+        self
+    }
+
+    /// Keep rate for `level` (1.0 = not sampled)
+    #[must_use]
+    pub fn rate(&self, level: LogLevel) -> f64 {
+        // At most 1_000_000, exactly representable
+        #[allow(clippy::cast_precision_loss)]
+        let rate = self.rates[index(level)] as f64 / 1_000_000.0;
+        rate
+    }
+
+    /// Whether a line at `level` for `request_id` should be written
+    pub fn sample(&self, level: LogLevel, request_id: Option<&str>) -> bool {
+        let slot = index(level);
+        let rate = self.rates[slot];
+        if rate >= PPM {
+            return true;
+        }
+
+        if let Some(request_id) = request_id {
+            let mut last = self
+                .last_request_ids
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if last[slot].as_deref() != Some(request_id) {
+                last[slot] = Some(request_id.to_string());
+                return true;
+            }
+        }
+
+        let n = self.seen[slot].fetch_add(1, Ordering::Relaxed);
+        (n + 1) * rate / PPM > n * rate / PPM
+    }
+}
+
+impl Default for LogSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LogSampler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogSampler")
+            .field("rates_ppm", &self.rates)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Slot of `level` in the per-level arrays
+fn index(level: LogLevel) -> usize {
+    match level {
+        LogLevel::Debug => 0,
+        LogLevel::Info => 1,
+        LogLevel::Warn => 2,
+        LogLevel::Error => 3,
+    }
+}
+
+fn parse_level(level: &str) -> Option<LogLevel> {
+    match level.to_ascii_uppercase().as_str() {
+        "DEBUG" => Some(LogLevel::Debug),
+        "INFO" => Some(LogLevel::Info),
+        "WARN" | "WARNING" => Some(LogLevel::Warn),
+        "ERROR" => Some(LogLevel::Error),
+        _ => None,
+    }
+}
+
+fn parse_rate(rate: &str) -> Option<f64> {
+    let value = match rate.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+        None => rate.parse::<f64>().ok()?,
+    };
+    value.is_finite().then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn kept(sampler: &LogSampler, level: LogLevel, lines: usize) -> usize {
+        (0..lines).filter(|_| sampler.sample(level, None)).count()
+    }
+
+    #[test]
+    fn test_default_keeps_everything() {
+        let sampler = LogSampler::default();
+        assert_eq!(kept(&sampler, LogLevel::Debug, 50), 50);
+        assert!((sampler.rate(LogLevel::Debug) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_rate_keeps_exact_fraction() {
+        let sampler = LogSampler::new()
+            .with_rate(LogLevel::Debug, 0.01)
+            .with_rate(LogLevel::Info, 0.0);
+        assert_eq!(kept(&sampler, LogLevel::Debug, 1000), 10);
+        assert_eq!(kept(&sampler, LogLevel::Info, 1000), 0);
+        assert_eq!(kept(&sampler, LogLevel::Error, 1000), 1000);
+    }
+
+    #[test]
+    fn test_first_line_per_request_is_kept() {
+        let sampler = LogSampler::new().with_rate(LogLevel::Debug, 0.0);
+        assert!(sampler.sample(LogLevel::Debug, Some("req-1")));
+        assert!(!sampler.sample(LogLevel::Debug, Some("req-1")));
+        assert!(sampler.sample(LogLevel::Debug, Some("req-2")));
+        assert!(!sampler.sample(LogLevel::Debug, Some("req-2")));
+        assert!(!sampler.sample(LogLevel::Debug, None));
+    }
+
+    #[test]
+    fn test_parse() {
+        let sampler = LogSampler::parse(" debug=1% , Warning=0.5,info=oops,bogus=0.1,ERROR");
+        assert!((sampler.rate(LogLevel::Debug) - 0.01).abs() < f64::EPSILON);
+        assert!((sampler.rate(LogLevel::Warn) - 0.5).abs() < f64::EPSILON);
+        assert!((sampler.rate(LogLevel::Info) - 1.0).abs() < f64::EPSILON);
+        assert!((sampler.rate(LogLevel::Error) - 1.0).abs() < f64::EPSILON);
+
+        let clamped = LogSampler::parse("DEBUG=250%,INFO=-1");
+        assert!((clamped.rate(LogLevel::Debug) - 1.0).abs() < f64::EPSILON);
+        assert!(clamped.rate(LogLevel::Info).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env() {
+        env::set_var(LOG_SAMPLE_ENV, "DEBUG=0.1");
+        let sampler = LogSampler::from_env();
+        env::remove_var(LOG_SAMPLE_ENV);
+        assert!((sampler.rate(LogLevel::Debug) - 0.1).abs() < f64::EPSILON);
+        assert!((LogSampler::from_env().rate(LogLevel::Debug) - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -12,10 +12,21 @@
 //
 // Phase 4: Advanced Features - CloudWatch Logs Integration
 
+use crate::clock::{Clock, SharedClock};
+use crate::correlation::current_correlation_id;
+use crate::log_sampling::LogSampler;
+use crate::redaction::{Redactor, DEFAULT_PAYLOAD_EXCERPT_BYTES};
+use once_cell::sync::OnceCell;
 use std::fmt;
 use std::io::{self, Write};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::UNIX_EPOCH;
+
+/// Redactor shared by loggers without their own (env is read once)
+static DEFAULT_REDACTOR: OnceCell<Redactor> = OnceCell::new();
+
+/// Sampler shared by loggers without their own (env is read once)
+static DEFAULT_SAMPLER: OnceCell<LogSampler> = OnceCell::new();
 
 /// Log level for structured logging
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -76,6 +87,115 @@ pub struct Logger {
     min_level: Option<LogLevel>,
     /// Writer (stdout by default, can be mocked for testing)
     writer: Mutex<Box<dyn Write + Send>>,
+    /// Applied to payloads before `log_payload` writes them
+    /// (None = shared [`Redactor::from_env`] default)
+    redactor: Option<Redactor>,
+    /// Top-level key names and static fields
+    format: LogFormat,
+    /// Per-level sampling (None = shared [`LogSampler::from_env`] default)
+    sampler: Option<LogSampler>,
+    /// Source of the `timestamp` field
+    clock: SharedClock,
+}
+
+/// JSON schema of a log line: top-level key names and static fields
+///
+/// Lets log lines match an existing organization-wide schema without
+/// post-processing. The defaults produce
+/// `{"level":..,"timestamp":..,"request_id":..,"message":..}` (plus
+/// `correlation_id` after `request_id` while the
+/// [`CorrelationId`](crate::CorrelationId) middleware has one set). Static
+/// fields are written flat at the top level, between `timestamp` and
+/// `request_id`.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{LogFormat, Logger};
+///
+/// let mut logger = Logger::with_request_id("req-1");
+/// logger.set_format(
+///     LogFormat::default()
+///         .with_level_key("severity")
+///         .with_message_key("msg")
+///         .with_static_field("service", "orders"),
+/// );
+/// logger.info("Order placed");
+/// // Output: {"severity":"INFO","timestamp":"...","service":"orders","request_id":"req-1","msg":"Order placed"}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFormat {
+    // Keys and values are stored JSON-escaped
+    level_key: String,
+    timestamp_key: String,
+    request_id_key: String,
+    correlation_id_key: String,
+    message_key: String,
+    static_fields: Vec<(String, String)>,
+}
+
+impl LogFormat {
+    /// Rename the `level` key
+    #[must_use]
+    pub fn with_level_key(mut self, key: &str) -> Self {
+        self.level_key = Logger::escape_json(key);
+        self
+    }
+
+    /// Rename the `timestamp` key
+    #[must_use]
+    pub fn with_timestamp_key(mut self, key: &str) -> Self {
+        self.timestamp_key = Logger::escape_json(key);
+        self
+    }
+
+    /// Rename the `request_id` key
+    #[must_use]
+    pub fn with_request_id_key(mut self, key: &str) -> Self {
+        self.request_id_key = Logger::escape_json(key);
+        self
+    }
+
+    /// Rename the `correlation_id` key
+    #[must_use]
+    pub fn with_correlation_id_key(mut self, key: &str) -> Self {
+        self.correlation_id_key = Logger::escape_json(key);
+        self
+    }
+
+    /// Rename the `message` key
+    #[must_use]
+    pub fn with_message_key(mut self, key: &str) -> Self {
+        self.message_key = Logger::escape_json(key);
+        self
+    }
+
+    /// Add a static string field to every log line
+    ///
+    /// Adding a field with the same name again replaces its value.
+    #[must_use]
+    pub fn with_static_field(mut self, name: &str, value: &str) -> Self {
+        let name = Logger::escape_json(name);
+        let value = Logger::escape_json(value);
+        match self.static_fields.iter_mut().find(|(key, _)| *key == name) {
+            Some(field) => field.1 = value,
+            None => self.static_fields.push((name, value)),
+        }
+        self
+    }
+}
+
+impl Default for LogFormat {
+    fn default() -> Self {
+        Self {
+            level_key: "level".to_string(),
+            timestamp_key: "timestamp".to_string(),
+            request_id_key: "request_id".to_string(),
+            correlation_id_key: "correlation_id".to_string(),
+            message_key: "message".to_string(),
+            static_fields: Vec::new(),
+        }
+    }
 }
 
 impl Logger {
@@ -95,6 +215,10 @@ impl Logger {
             request_id: None,
             min_level: None,
             writer: Mutex::new(Box::new(io::stdout())),
+            redactor: None,
+            format: LogFormat::default(),
+            sampler: None,
+            clock: SharedClock::default(),
         }
     }
 
@@ -116,6 +240,10 @@ impl Logger {
             request_id: Some(request_id.into()),
             min_level: None,
             writer: Mutex::new(Box::new(io::stdout())),
+            redactor: None,
+            format: LogFormat::default(),
+            sampler: None,
+            clock: SharedClock::default(),
         }
     }
 
@@ -128,6 +256,10 @@ impl Logger {
             request_id: None,
             min_level: None,
             writer: Mutex::new(writer),
+            redactor: None,
+            format: LogFormat::default(),
+            sampler: Some(LogSampler::new()),
+            clock: SharedClock::default(),
         }
     }
 
@@ -149,6 +281,102 @@ impl Logger {
         self.min_level = Some(level);
     }
 
+    /// Replace the payload redactor
+    ///
+    /// Defaults to [`Redactor::from_env`] (built-in deny-list plus
+    /// `RUCHY_LAMBDA_REDACT_FIELDS`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Logger, Redactor};
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.set_redactor(Redactor::default().with_field("email"));
+    /// ```
+    pub fn set_redactor(&mut self, redactor: Redactor) {
+        self.redactor = Some(redactor);
+    }
+
+    /// Take line timestamps from `clock` instead of the system clock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Logger, ManualClock};
+    ///
+    /// let mut logger = Logger::new();
+    /// logger.set_clock(ManualClock::at_millis(1_700_000_000_000));
+    /// logger.info("Always logged at the same time");
+    /// ```
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = SharedClock::new(clock);
+    }
+
+    /// Replace the log line schema
+    ///
+    /// See [`LogFormat`] for renaming keys and adding static fields.
+    pub fn set_format(&mut self, format: LogFormat) {
+        self.format = format;
+    }
+
+    /// Replace the log sampler
+    ///
+    /// Defaults to [`LogSampler::from_env`] (`RUCHY_LAMBDA_LOG_SAMPLE`),
+    /// shared by all loggers so rates apply across per-request loggers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{LogLevel, LogSampler, Logger};
+    ///
+    /// let mut logger = Logger::with_request_id("req-1");
+    /// logger.set_sampler(LogSampler::new().with_rate(LogLevel::Debug, 0.01));
+    /// logger.debug("kept: first DEBUG line for req-1");
+    /// logger.debug("kept 1% of the time");
+    /// ```
+    pub fn set_sampler(&mut self, sampler: LogSampler) {
+        self.sampler = Some(sampler);
+    }
+
+    /// Log a message with a redacted payload excerpt
+    ///
+    /// The payload is redacted, cut to 4KB and written as a `payload` string
+    /// field (plus `"payload_truncated":true` when cut). Nothing is redacted
+    /// or formatted when `level` is filtered out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{LogLevel, Logger};
+    ///
+    /// let logger = Logger::with_request_id("req-1");
+    /// logger.log_payload(LogLevel::Debug, "Received event", r#"{"password":"hunter2"}"#);
+    /// // Output: {...,"message":"Received event","payload":"{\"password\":\"[REDACTED]\"}"}
+    /// ```
+    pub fn log_payload(&self, level: LogLevel, message: &str, payload: &str) {
+        use std::fmt::Write;
+
+        if !self.should_log(level, self.request_id.as_deref()) {
+            return;
+        }
+
+        let redactor = self
+            .redactor
+            .as_ref()
+            .unwrap_or_else(|| DEFAULT_REDACTOR.get_or_init(Redactor::from_env));
+        let (excerpt, truncated) = redactor.excerpt(payload, DEFAULT_PAYLOAD_EXCERPT_BYTES);
+        let timestamp = self.format_timestamp();
+        let mut json = self.format_json(level, &timestamp, message);
+        json.pop(); // closing brace
+        let _ = write!(json, r#","payload":"{}""#, Self::escape_json(&excerpt));
+        if truncated {
+            json.push_str(r#","payload_truncated":true"#);
+        }
+        json.push('}');
+        self.write_line(&json);
+    }
+
     /// Log a debug message
     ///
     /// # Examples
@@ -201,23 +429,127 @@ impl Logger {
         self.log(LogLevel::Error, message);
     }
 
+    /// Log a message with additional numeric fields
+    ///
+    /// Fields are emitted as top-level JSON numbers after `message`, so
+    /// `CloudWatch` Logs Insights can filter and aggregate on them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{LogLevel, Logger};
+    ///
+    /// let logger = Logger::new();
+    /// logger.log_with_fields(LogLevel::Warn, "Runtime API unavailable", &[("consecutive_failures", 3)]);
+    /// // Output: {"level":"WARN",...,"message":"Runtime API unavailable","consecutive_failures":3}
+    /// ```
+    pub fn log_with_fields(&self, level: LogLevel, message: &str, fields: &[(&str, u64)]) {
+        if !self.should_log(level, self.request_id.as_deref()) {
+            return;
+        }
+
+        let timestamp = self.format_timestamp();
+        let json = Self::append_fields(self.format_json(level, &timestamp, message), fields);
+        self.write_line(&json);
+    }
+
+    /// Log a message with additional string fields
+    ///
+    /// Like [`Logger::log_with_fields`], with values written as escaped
+    /// JSON strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{LogLevel, Logger};
+    ///
+    /// let logger = Logger::new();
+    /// logger.log_with_text_fields(LogLevel::Error, "Handler panicked", &[("thread", "main")]);
+    /// // Output: {"level":"ERROR",...,"message":"Handler panicked","thread":"main"}
+    /// ```
+    pub fn log_with_text_fields(&self, level: LogLevel, message: &str, fields: &[(&str, &str)]) {
+        use std::fmt::Write;
+
+        if !self.should_log(level, self.request_id.as_deref()) {
+            return;
+        }
+
+        let timestamp = self.format_timestamp();
+        let mut json = self.format_json(level, &timestamp, message);
+        json.pop(); // closing brace
+        for (name, value) in fields {
+            let _ = write!(
+                json,
+                r#","{}":"{}""#,
+                Self::escape_json(name),
+                Self::escape_json(value)
+            );
+        }
+        json.push('}');
+        self.write_line(&json);
+    }
+
+    /// Whether `level` passes the minimum level filter
+    pub(crate) fn enabled(&self, level: LogLevel) -> bool {
+        self.min_level.is_none_or(|min_level| level >= min_level)
+    }
+
+    /// Whether `level` passes the minimum level filter and the sampler
+    fn should_log(&self, level: LogLevel, request_id: Option<&str>) -> bool {
+        let sampler = self
+            .sampler
+            .as_ref()
+            .unwrap_or_else(|| DEFAULT_SAMPLER.get_or_init(LogSampler::from_env));
+        self.enabled(level) && sampler.sample(level, request_id)
+    }
+
+    /// Log for an explicit request ID with pre-encoded JSON field values
+    ///
+    /// Used by the `tracing` adapter and lifecycle events, whose request ID
+    /// changes per invocation while the logger is shared.
+    pub(crate) fn log_json_fields(
+        &self,
+        level: LogLevel,
+        request_id: Option<&str>,
+        message: &str,
+        fields: &[(&str, String)],
+    ) {
+        use std::fmt::Write;
+
+        if !self.should_log(level, request_id) {
+            return;
+        }
+
+        let timestamp = self.format_timestamp();
+        let mut json = self.format_entry(level, &timestamp, request_id, message);
+        json.pop(); // closing brace
+        for (name, value) in fields {
+            let _ = write!(json, r#","{}":{value}"#, Self::escape_json(name));
+        }
+        json.push('}');
+        self.write_line(&json);
+    }
+
     /// Log a message with specified level
     ///
     /// Internal method that formats and writes the log entry.
     fn log(&self, level: LogLevel, message: &str) {
-        // Check minimum log level
-        if let Some(min_level) = self.min_level {
-            if level < min_level {
-                return; // Skip logging
-            }
+        // Check minimum log level and sampling
+        if !self.should_log(level, self.request_id.as_deref()) {
+            return;
         }
 
         // Get current timestamp in ISO 8601 format
-        let timestamp = Self::format_timestamp();
+        let timestamp = self.format_timestamp();
 
         // Build JSON log entry
         let json = self.format_json(level, &timestamp, message);
 
+        self.write_line(&json);
+    }
+
+    /// Write one JSON log line to the output
+    fn write_line(&self, json: &str) {
         // Write to output (stdout)
         let mut writer = self.writer.lock().unwrap();
         let _ = writeln!(writer, "{json}");
@@ -227,8 +559,10 @@ impl Logger {
     /// Format timestamp as ISO 8601
     ///
     /// Returns format: "2025-11-04T12:34:56.789Z"
-    fn format_timestamp() -> String {
-        let now = SystemTime::now()
+    fn format_timestamp(&self) -> String {
+        let now = self
+            .clock
+            .now()
             .duration_since(UNIX_EPOCH)
             .expect("System time before UNIX epoch");
 
@@ -259,29 +593,81 @@ impl Logger {
     ///
     /// Creates a single-line JSON object with all log fields.
     fn format_json(&self, level: LogLevel, timestamp: &str, message: &str) -> String {
+        self.format_entry(level, timestamp, self.request_id.as_deref(), message)
+    }
+
+    /// Format a log entry for an explicit request ID
+    fn format_entry(
+        &self,
+        level: LogLevel,
+        timestamp: &str,
+        request_id: Option<&str>,
+        message: &str,
+    ) -> String {
         use std::fmt::Write;
 
+        let format = &self.format;
+
         // Escape message for JSON (handle quotes, backslashes, newlines)
         let escaped_message = Self::escape_json(message);
 
         // Build JSON manually to avoid serde dependency
-        let mut json = format!(r#"{{"level":"{level}","timestamp":"{timestamp}""#);
+        let mut json = format!(
+            r#"{{"{}":"{level}","{}":"{timestamp}""#,
+            format.level_key, format.timestamp_key
+        );
+
+        // Static fields are already escaped
+        for (name, value) in &format.static_fields {
+            let _ = write!(json, r#","{name}":"{value}""#);
+        }
 
         // Add request_id if available
-        if let Some(ref request_id) = self.request_id {
-            let _ = write!(json, r#","request_id":"{request_id}""#);
+        if let Some(request_id) = request_id {
+            let _ = write!(
+                json,
+                r#","{}":"{}""#,
+                format.request_id_key,
+                Self::escape_json(request_id)
+            );
+        }
+
+        // Add the current correlation ID (CorrelationId middleware)
+        if let Some(correlation_id) = current_correlation_id() {
+            let _ = write!(
+                json,
+                r#","{}":"{}""#,
+                format.correlation_id_key,
+                Self::escape_json(&correlation_id)
+            );
         }
 
         // Add message
-        let _ = write!(json, r#","message":"{escaped_message}"}}"#);
+        let _ = write!(json, r#","{}":"{escaped_message}"}}"#, format.message_key);
+
+        json
+    }
+
+    /// Append numeric `fields` to a JSON object produced by `format_json`
+    fn append_fields(mut json: String, fields: &[(&str, u64)]) -> String {
+        use std::fmt::Write;
 
+        if fields.is_empty() {
+            return json;
+        }
+
+        json.pop(); // closing brace
+        for (name, value) in fields {
+            let _ = write!(json, r#","{}":{value}"#, Self::escape_json(name));
+        }
+        json.push('}');
         json
     }
 
     /// Escape string for JSON
     ///
     /// Handles: quotes ("), backslashes (\), newlines (\n), tabs (\t), etc.
-    fn escape_json(s: &str) -> String {
+    pub(crate) fn escape_json(s: &str) -> String {
         use std::fmt::Write;
 
         let mut result = String::with_capacity(s.len());
@@ -317,6 +703,7 @@ static_assertions::assert_impl_all!(Logger: Send, Sync);
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
     use std::sync::Arc;
 
     /// Mock writer for capturing log output in tests
@@ -374,9 +761,16 @@ mod tests {
         assert_eq!(Logger::escape_json("tab\there"), r"tab\there");
     }
 
+    #[test]
+    fn test_timestamp_from_clock() {
+        let mut logger = Logger::new();
+        logger.set_clock(crate::clock::ManualClock::at_millis(45_296_789));
+        assert_eq!(logger.format_timestamp(), "1970-01-01T12:34:56.789Z");
+    }
+
     #[test]
     fn test_timestamp_format() {
-        let timestamp = Logger::format_timestamp();
+        let timestamp = Logger::new().format_timestamp();
         // Should match pattern: YYYY-MM-DDTHH:MM:SS.mmmZ
         assert!(timestamp.len() >= 24, "Timestamp too short: {}", timestamp);
         assert!(timestamp.contains('T'), "Missing 'T' separator");
@@ -421,6 +815,177 @@ mod tests {
         assert!(json.contains(r#""message":"error occurred""#));
     }
 
+    #[test]
+    fn test_append_fields() {
+        let logger = Logger::new();
+        let json = logger.format_json(LogLevel::Warn, "2025-11-04T12:00:00.000Z", "retrying");
+        let json = Logger::append_fields(json, &[("consecutive_failures", 3), ("backoff_ms", 400)]);
+
+        assert!(
+            json.ends_with(r#""message":"retrying","consecutive_failures":3,"backoff_ms":400}"#)
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["consecutive_failures"], 3);
+    }
+
+    #[test]
+    #[serial]
+    fn test_default_format_is_unchanged() {
+        let logger = Logger::with_request_id("req-1");
+        assert_eq!(
+            logger.format_json(LogLevel::Info, "2025-11-04T12:00:00.000Z", "hi"),
+            r#"{"level":"INFO","timestamp":"2025-11-04T12:00:00.000Z","request_id":"req-1","message":"hi"}"#
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_custom_format_renames_keys_and_adds_static_fields() {
+        let mut logger = Logger::with_request_id("req-1");
+        logger.set_format(
+            LogFormat::default()
+                .with_level_key("severity")
+                .with_timestamp_key("time")
+                .with_request_id_key("requestId")
+                .with_message_key("msg")
+                .with_static_field("service", "orders")
+                .with_static_field("env", "dev")
+                .with_static_field("env", "prod"),
+        );
+        let json = logger.format_json(LogLevel::Warn, "2025-11-04T12:00:00.000Z", "slow");
+
+        assert_eq!(
+            json,
+            r#"{"severity":"WARN","time":"2025-11-04T12:00:00.000Z","service":"orders","env":"prod","requestId":"req-1","msg":"slow"}"#
+        );
+        let json = Logger::append_fields(json, &[("attempt", 2)]);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["attempt"], 2);
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(feature = "serde")]
+    fn test_correlation_id_is_injected_while_set() {
+        use crate::{Context, CorrelationId, Pipeline};
+
+        let logger = Logger::with_request_id("req-1");
+        let event = r#"{"headers":{"x-correlation-id":"order-7"}}"#;
+        let json = Pipeline::new()
+            .with(CorrelationId)
+            .handle(Context::default(), event, |_, _| {
+                logger.format_json(LogLevel::Info, "2025-11-04T12:00:00.000Z", "hi")
+            });
+        assert!(json.contains(r#""request_id":"req-1","correlation_id":"order-7","message":"hi""#));
+
+        let json = logger.format_json(LogLevel::Info, "2025-11-04T12:00:00.000Z", "after");
+        assert!(!json.contains("correlation_id"));
+    }
+
+    #[test]
+    fn test_custom_format_escapes_keys_and_values() {
+        let mut logger = Logger::new();
+        logger.set_format(LogFormat::default().with_static_field("team\"x", "a\nb"));
+        let json = logger.format_json(LogLevel::Info, "2025-11-04T12:00:00.000Z", "hi");
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["team\"x"], "a\nb");
+    }
+
+    #[test]
+    fn test_append_no_fields() {
+        let json = r#"{"message":"x"}"#.to_string();
+        assert_eq!(Logger::append_fields(json.clone(), &[]), json);
+    }
+
+    #[test]
+    fn test_log_with_fields_respects_min_level() {
+        let writer = MockWriter::new();
+        let buffer = Arc::clone(&writer.buffer);
+        let mut logger = Logger::with_writer(Box::new(writer));
+        logger.set_min_level(LogLevel::Error);
+
+        logger.log_with_fields(LogLevel::Warn, "filtered", &[("n", 1)]);
+        assert!(buffer.lock().unwrap().is_empty());
+
+        logger.log_with_fields(LogLevel::Error, "kept", &[("n", 2)]);
+        let output = String::from_utf8_lossy(&buffer.lock().unwrap()).to_string();
+        assert!(output.contains(r#""message":"kept","n":2"#));
+    }
+
+    #[test]
+    fn test_log_with_text_fields_escapes_values() {
+        let writer = MockWriter::new();
+        let buffer = Arc::clone(&writer.buffer);
+        let logger = Logger::with_writer(Box::new(writer));
+
+        logger.log_with_text_fields(LogLevel::Error, "crashed", &[("reason", "bad \"input\"\n")]);
+        let output = String::from_utf8_lossy(&buffer.lock().unwrap()).to_string();
+        assert!(output.contains(r#""message":"crashed","reason":"bad \"input\"\n"}"#));
+    }
+
+    #[test]
+    fn test_sampler_keeps_first_debug_line_per_request() {
+        let writer = MockWriter::new();
+        let buffer = Arc::clone(&writer.buffer);
+        let mut logger = Logger::with_writer(Box::new(writer));
+        logger.set_sampler(LogSampler::new().with_rate(LogLevel::Debug, 0.0));
+
+        logger.debug("no request id");
+        logger.log_json_fields(LogLevel::Debug, Some("req-1"), "first", &[]);
+        logger.log_json_fields(LogLevel::Debug, Some("req-1"), "second", &[]);
+        logger.log_json_fields(LogLevel::Debug, Some("req-2"), "third", &[]);
+        logger.warn("never sampled");
+
+        let output = String::from_utf8_lossy(&buffer.lock().unwrap()).to_string();
+        let messages: Vec<_> = output
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["message"].clone())
+            .collect();
+        assert_eq!(messages, ["first", "third", "never sampled"]);
+        assert!(output.contains(r#""request_id":"req-1""#));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_log_payload_redacts_and_truncates() {
+        let writer = MockWriter::new();
+        let buffer = Arc::clone(&writer.buffer);
+        let mut logger = Logger::with_writer(Box::new(writer));
+        logger.set_redactor(Redactor::default().with_field("email"));
+
+        logger.log_payload(
+            LogLevel::Debug,
+            "event",
+            r#"{"email":"a@b.c","password":"hunter2","id":1}"#,
+        );
+        logger.log_payload(LogLevel::Debug, "big", &"x".repeat(5000));
+
+        let output = String::from_utf8_lossy(&buffer.lock().unwrap()).to_string();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines[0]["payload"],
+            r#"{"email":"[REDACTED]","id":1,"password":"[REDACTED]"}"#
+        );
+        assert!(lines[0].get("payload_truncated").is_none());
+        assert_eq!(lines[1]["payload"].as_str().unwrap().len(), 4096);
+        assert_eq!(lines[1]["payload_truncated"], true);
+        assert!(!output.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_log_payload_respects_min_level() {
+        let writer = MockWriter::new();
+        let buffer = Arc::clone(&writer.buffer);
+        let mut logger = Logger::with_writer(Box::new(writer));
+        logger.set_min_level(LogLevel::Info);
+
+        logger.log_payload(LogLevel::Debug, "event", "{}");
+        assert!(buffer.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_json_escaping_in_message() {
         let logger = Logger::new();
@@ -437,7 +1002,7 @@ mod tests {
     // MUTATION TESTING: Catch arithmetic mutants in format_timestamp()
     #[test]
     fn test_timestamp_arithmetic_hours() {
-        let timestamp = Logger::format_timestamp();
+        let timestamp = Logger::new().format_timestamp();
 
         // Extract hours from timestamp (format: YYYY-MM-DDTHH:MM:SS.mmmZ)
         let parts: Vec<&str> = timestamp.split('T').collect();
@@ -458,7 +1023,7 @@ mod tests {
 
     #[test]
     fn test_timestamp_arithmetic_minutes() {
-        let timestamp = Logger::format_timestamp();
+        let timestamp = Logger::new().format_timestamp();
 
         let parts: Vec<&str> = timestamp.split('T').collect();
         let time_part = parts[1];
@@ -477,7 +1042,7 @@ mod tests {
 
     #[test]
     fn test_timestamp_arithmetic_seconds() {
-        let timestamp = Logger::format_timestamp();
+        let timestamp = Logger::new().format_timestamp();
 
         let parts: Vec<&str> = timestamp.split('T').collect();
         let time_part = parts[1];
@@ -500,7 +1065,7 @@ mod tests {
 
     #[test]
     fn test_timestamp_arithmetic_millis() {
-        let timestamp = Logger::format_timestamp();
+        let timestamp = Logger::new().format_timestamp();
 
         let millis_part: Vec<&str> = timestamp.split('.').collect();
         assert_eq!(millis_part.len(), 2, "Should have milliseconds");
@@ -517,7 +1082,7 @@ mod tests {
 
     #[test]
     fn test_timestamp_arithmetic_date_validity() {
-        let timestamp = Logger::format_timestamp();
+        let timestamp = Logger::new().format_timestamp();
 
         let parts: Vec<&str> = timestamp.split('T').collect();
         let date_part = parts[0];
@@ -0,0 +1,109 @@
+// Process Memory Self-Reporting
+//
+// Lambda reports "Max Memory Used" only in the REPORT line, once per
+// invocation and as text. The runtime reads the same numbers from
+// /proc/self/status instead (one ~1KB read, a few microseconds) so they can
+// go into structured logs:
+// - VmRSS: resident set size now
+// - VmHWM: peak resident set size ("high water mark") of the process
+//
+// The peak covers the whole process lifetime, like Lambda's figure: an
+// invocation that raises it is the one that allocated the most so far.
+//
+// Linux only; elsewhere (local development on macOS) nothing is sampled.
+
+/// Resident memory of the current process, in kilobytes
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::MemoryUsage;
+///
+/// if let Some(memory) = MemoryUsage::current() {
+///     assert!(memory.peak_rss_kb >= memory.rss_kb);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Current resident set size (`VmRSS`)
+    pub rss_kb: u64,
+    /// Peak resident set size since the process started (`VmHWM`)
+    pub peak_rss_kb: u64,
+}
+
+impl MemoryUsage {
+    /// Sample `/proc/self/status` (`None` if unavailable or not on Linux)
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            std::fs::read_to_string("/proc/self/status")
+                .ok()
+                .and_then(|status| Self::parse(&status))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    /// Parse the `VmRSS` and `VmHWM` lines of a `/proc/<pid>/status` file
+    fn parse(status: &str) -> Option<Self> {
+        let mut rss_kb = None;
+        let mut peak_rss_kb = None;
+        for line in status.lines() {
+            if let Some(value) = line.strip_prefix("VmRSS:") {
+                rss_kb = parse_kb(value);
+            } else if let Some(value) = line.strip_prefix("VmHWM:") {
+                peak_rss_kb = parse_kb(value);
+            }
+        }
+        Some(Self {
+            rss_kb: rss_kb?,
+            peak_rss_kb: peak_rss_kb?,
+        })
+    }
+}
+
+/// Parse a status value such as `"    5120 kB"`
+fn parse_kb(value: &str) -> Option<u64> {
+    value.trim().strip_suffix("kB")?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "Name:\tbootstrap
+VmPeak:\t   12345 kB
+VmHWM:\t    9216 kB
+VmRSS:\t    8192 kB
+Threads:\t1
+";
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(
+            MemoryUsage::parse(STATUS),
+            Some(MemoryUsage {
+                rss_kb: 8192,
+                peak_rss_kb: 9216,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_status_without_memory_lines() {
+        // Kernel threads have no VmRSS / VmHWM
+        assert_eq!(MemoryUsage::parse("Name:\tkthreadd\nState:\tS\n"), None);
+        assert_eq!(MemoryUsage::parse("VmRSS:\tlots\nVmHWM:\t1 kB\n"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_current_on_linux() {
+        let memory = MemoryUsage::current().unwrap();
+        assert!(memory.rss_kb > 0);
+        assert!(memory.peak_rss_kb >= memory.rss_kb);
+    }
+}
@@ -0,0 +1,354 @@
+// CloudWatch Embedded Metric Format (EMF)
+//
+// Metrics are buffered in memory and written as one EMF JSON line to stdout,
+// from which CloudWatch Logs extracts them without PutMetricData calls:
+//
+//   {"_aws":{"Timestamp":1700000000000,"CloudWatchMetrics":[{"Namespace":"shop",
+//    "Dimensions":[["service"]],"Metrics":[{"Name":"orders","Unit":"Count"}]}]},
+//    "service":"checkout","orders":[1,3]}
+//
+// Buffering across invocations saves log volume, but whatever is still
+// buffered when the environment is reclaimed is lost: `flush_on_shutdown`
+// registers a flush with the shutdown hooks (extension.rs). A buffer
+// reaching the EMF limits (100 metrics per document, 100 values per metric)
+// is flushed right away.
+
+use crate::cpu_time::CpuTime;
+use crate::extension::on_shutdown;
+use crate::logger::{LogLevel, Logger};
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most metrics (and values per metric) EMF accepts in one document
+pub const EMF_MAX_METRICS: usize = 100;
+
+/// Buffered values of one metric
+#[derive(Debug)]
+struct Series {
+    name: String,
+    unit: String,
+    values: Vec<f64>,
+}
+
+/// Buffer of metrics written as `CloudWatch` EMF log lines
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Metrics;
+///
+/// let metrics = Metrics::new("shop").with_dimension("service", "checkout");
+/// metrics.put("orders", 1.0, "Count");
+/// metrics.put("latency", 12.5, "Milliseconds");
+/// assert_eq!(metrics.pending(), 2);
+///
+/// metrics.flush().unwrap();
+/// assert_eq!(metrics.pending(), 0);
+/// ```
+pub struct Metrics {
+    namespace: String,
+    dimensions: Vec<(String, String)>,
+    series: Mutex<Vec<Series>>,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metrics")
+            .field("namespace", &self.namespace)
+            .field("dimensions", &self.dimensions)
+            .field("pending", &self.pending())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Metrics {
+    /// Empty buffer for `CloudWatch` namespace `namespace`, writing to stdout
+    #[must_use]
+    pub fn new(namespace: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            dimensions: Vec::new(),
+            series: Mutex::new(Vec::new()),
+            writer: Mutex::new(Box::new(io::stdout())),
+        }
+    }
+
+    /// Add dimension `name` = `value` to every metric
+    #[must_use]
+    pub fn with_dimension(mut self, name: &str, value: &str) -> Self {
+        self.dimensions.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Write EMF lines to `writer` instead of stdout
+    #[must_use]
+    pub fn with_writer(self, writer: Box<dyn Write + Send>) -> Self {
+        *self.writer.lock().unwrap_or_else(PoisonError::into_inner) = writer;
+        self
+    }
+
+    /// Buffer `value` for metric `name` (`unit` as in `CloudWatch`, e.g.
+    /// `Count` or `Milliseconds`; the first unit of a metric sticks)
+    ///
+    /// Flushes first when the buffer is at an EMF limit; a failed flush is
+    /// logged and its metrics are dropped.
+    pub fn put(&self, name: &str, value: f64, unit: &str) {
+        let full = {
+            let series = self.lock_series();
+            match series.iter().find(|series| series.name == name) {
+                Some(series) => series.values.len() >= EMF_MAX_METRICS,
+                None => series.len() >= EMF_MAX_METRICS,
+            }
+        };
+        if full {
+            self.flush_logged();
+        }
+
+        let mut series = self.lock_series();
+        match series.iter_mut().find(|series| series.name == name) {
+            Some(series) => series.values.push(value),
+            None => series.push(Series {
+                name: name.to_string(),
+                unit: unit.to_string(),
+                values: vec![value],
+            }),
+        }
+    }
+
+    /// Buffer `cpu` as the `CpuUserTime` and `CpuSystemTime` metrics, in
+    /// milliseconds
+    ///
+    /// An [`InvocationLog`](crate::InvocationLog) given these metrics with
+    /// `with_metrics` calls this for every invocation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{CpuTime, Metrics};
+    ///
+    /// let metrics = Metrics::new("shop");
+    /// let before = CpuTime::current();
+    /// // ... handle the event ...
+    /// if let (Some(before), Some(after)) = (before, CpuTime::current()) {
+    ///     metrics.put_cpu_time(after.since(before));
+    /// }
+    /// ```
+    pub fn put_cpu_time(&self, cpu: CpuTime) {
+        self.put(
+            "CpuUserTime",
+            cpu.user.as_secs_f64() * 1000.0,
+            "Milliseconds",
+        );
+        self.put(
+            "CpuSystemTime",
+            cpu.system.as_secs_f64() * 1000.0,
+            "Milliseconds",
+        );
+    }
+
+    /// Number of metrics with buffered values
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.lock_series().len()
+    }
+
+    /// Write the buffered metrics as one EMF line; returns how many metrics
+    /// were written
+    ///
+    /// # Errors
+    ///
+    /// Returns the writer's error; the metrics are dropped either way.
+    pub fn flush(&self) -> io::Result<usize> {
+        let Some((document, count)) = self.take_document() else {
+            return Ok(0);
+        };
+        let mut writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        writeln!(writer, "{document}")?;
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Flush when the execution environment shuts down (see
+    /// [`run_shutdown_hooks`](crate::run_shutdown_hooks))
+    pub fn flush_on_shutdown(self: &Arc<Self>) {
+        let metrics = Arc::clone(self);
+        on_shutdown(move || metrics.flush_logged());
+    }
+
+    /// Flush, logging a failure instead of returning it
+    fn flush_logged(&self) {
+        if let Err(err) = self.flush() {
+            Logger::new().log_with_fields(
+                LogLevel::Warn,
+                &format!("Dropping metrics, EMF write failed: {err}"),
+                &[],
+            );
+        }
+    }
+
+    fn lock_series(&self) -> std::sync::MutexGuard<'_, Vec<Series>> {
+        self.series.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Empty the buffer into an EMF document (`None` if nothing was buffered)
+    fn take_document(&self) -> Option<(Value, usize)> {
+        let series = std::mem::take(&mut *self.lock_series());
+        if series.is_empty() {
+            return None;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis());
+        let dimension_names: Vec<&str> = self
+            .dimensions
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let definitions: Vec<Value> = series
+            .iter()
+            .map(|series| json!({ "Name": series.name, "Unit": series.unit }))
+            .collect();
+
+        let mut document = Map::new();
+        document.insert(
+            "_aws".to_string(),
+            json!({
+                "Timestamp": u64::try_from(timestamp).unwrap_or(u64::MAX),
+                "CloudWatchMetrics": [{
+                    "Namespace": self.namespace,
+                    "Dimensions": [dimension_names],
+                    "Metrics": definitions,
+                }],
+            }),
+        );
+        for (name, value) in &self.dimensions {
+            document.insert(name.clone(), json!(value));
+        }
+        for series in &series {
+            let value = match series.values[..] {
+                [single] => json!(single),
+                _ => json!(series.values),
+            };
+            document.insert(series.name.clone(), value);
+        }
+        Some((Value::Object(document), series.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extension::run_shutdown_hooks;
+    use serial_test::serial;
+    use std::time::Duration;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn lines(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_flush_writes_emf() {
+        let output = Captured::default();
+        let metrics = Metrics::new("shop")
+            .with_dimension("service", "checkout")
+            .with_writer(Box::new(output.clone()));
+        metrics.put("orders", 1.0, "Count");
+        metrics.put("orders", 3.0, "Count");
+        metrics.put("latency", 12.5, "Milliseconds");
+
+        assert_eq!(metrics.flush().unwrap(), 2);
+        assert_eq!(metrics.flush().unwrap(), 0);
+
+        let lines = output.lines();
+        assert_eq!(lines.len(), 1);
+        let document = &lines[0];
+        let directive = &document["_aws"]["CloudWatchMetrics"][0];
+        assert_eq!(directive["Namespace"], "shop");
+        assert_eq!(directive["Dimensions"], json!([["service"]]));
+        assert_eq!(
+            directive["Metrics"],
+            json!([{"Name": "orders", "Unit": "Count"}, {"Name": "latency", "Unit": "Milliseconds"}])
+        );
+        assert_eq!(document["service"], "checkout");
+        assert_eq!(document["orders"], json!([1.0, 3.0]));
+        assert_eq!(document["latency"], json!(12.5));
+        assert!(document["_aws"]["Timestamp"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_put_cpu_time() {
+        let output = Captured::default();
+        let metrics = Metrics::new("shop").with_writer(Box::new(output.clone()));
+        metrics.put_cpu_time(CpuTime {
+            user: Duration::from_millis(120),
+            system: Duration::from_millis(10),
+        });
+        metrics.flush().unwrap();
+
+        let document = &output.lines()[0];
+        assert_eq!(
+            document["_aws"]["CloudWatchMetrics"][0]["Metrics"],
+            json!([
+                {"Name": "CpuUserTime", "Unit": "Milliseconds"},
+                {"Name": "CpuSystemTime", "Unit": "Milliseconds"}
+            ])
+        );
+        assert_eq!(document["CpuUserTime"], json!(120.0));
+        assert_eq!(document["CpuSystemTime"], json!(10.0));
+    }
+
+    #[test]
+    fn test_full_buffer_flushed() {
+        let output = Captured::default();
+        let metrics = Metrics::new("shop").with_writer(Box::new(output.clone()));
+        for n in 0..=EMF_MAX_METRICS {
+            metrics.put(&format!("m{n}"), 1.0, "Count");
+        }
+        assert_eq!(metrics.pending(), 1);
+        for _ in 0..EMF_MAX_METRICS {
+            metrics.put("m100", 2.0, "Count");
+        }
+        assert_eq!(output.lines().len(), 2);
+        assert_eq!(
+            output.lines()[1]["m100"].as_array().unwrap().len(),
+            EMF_MAX_METRICS
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_flushed_on_shutdown() {
+        let output = Captured::default();
+        let metrics = Arc::new(Metrics::new("shop").with_writer(Box::new(output.clone())));
+        metrics.flush_on_shutdown();
+        metrics.put("cold_starts", 1.0, "Count");
+
+        run_shutdown_hooks(None);
+        assert_eq!(metrics.pending(), 0);
+        assert_eq!(output.lines()[0]["cold_starts"], json!(1.0));
+    }
+}
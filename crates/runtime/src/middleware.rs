@@ -0,0 +1,252 @@
+// Handler Middleware
+//
+// Handlers in this runtime are plain functions from (context, event body) to
+// a JSON response string. A Pipeline wraps such a handler with layers that
+// run in order:
+//
+//   before:  layer 1 -> layer 2 -> ... -> handler
+//   after:   handler -> ... -> layer 2 -> layer 1
+//
+// A layer's `before` may short-circuit with its own response (e.g. a 400
+// from input validation); the handler and the remaining layers' `before`
+// are then skipped, but `after` still runs for the layers already entered.
+
+use crate::context::Context;
+use std::fmt;
+
+/// Outcome of [`Middleware::before`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Flow {
+    /// Continue with the next layer (and finally the handler)
+    Continue,
+    /// Skip the handler and respond with this JSON document
+    Respond(String),
+}
+
+/// A layer around a handler
+///
+/// Both hooks default to no-ops, so layers implement only what they need.
+pub trait Middleware: Send + Sync {
+    /// Inspect the event before the handler runs
+    ///
+    /// The context is mutable so layers can attach derived data.
+    fn before(&self, _context: &mut Context, _event_body: &str) -> Flow {
+        Flow::Continue
+    }
+
+    /// Inspect or rewrite the response after the handler (or a later
+    /// layer's short-circuit) produced it
+    fn after(&self, _context: &Context, _response: &mut String) {}
+}
+
+/// Ordered middleware layers applied around a handler
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, Flow, Middleware, Pipeline};
+///
+/// struct RequireBody;
+///
+/// impl Middleware for RequireBody {
+///     fn before(&self, _context: &mut Context, event_body: &str) -> Flow {
+///         if event_body.trim().is_empty() {
+///             Flow::Respond(r#"{"statusCode":400,"body":"empty event"}"#.to_string())
+///         } else {
+///             Flow::Continue
+///         }
+///     }
+/// }
+///
+/// let pipeline = Pipeline::new().with(RequireBody);
+/// let handler = |_: &Context, body: &str| format!(r#"{{"statusCode":200,"body":{body:?}}}"#);
+///
+/// assert!(pipeline.handle(Context::default(), "", handler).contains("400"));
+/// assert!(pipeline.handle(Context::default(), "{}", handler).contains("200"));
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    layers: Vec<Box<dyn Middleware>>,
+}
+
+impl Pipeline {
+    /// Empty pipeline (calls the handler directly)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a layer (runs after the layers already added)
+    #[must_use]
+    pub fn with(mut self, layer: impl Middleware + 'static) -> Self {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Number of layers
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Whether the pipeline has no layers
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Run the layers and `handler` for one event
+    pub fn handle(
+        &self,
+        mut context: Context,
+        event_body: &str,
+        handler: impl FnOnce(&Context, &str) -> String,
+    ) -> String {
+        let mut entered = 0;
+        let mut response = None;
+
+        for layer in &self.layers {
+            entered += 1;
+            if let Flow::Respond(short_circuit) = layer.before(&mut context, event_body) {
+                response = Some(short_circuit);
+                break;
+            }
+        }
+
+        let mut response = response.unwrap_or_else(|| handler(&context, event_body));
+        for layer in self.layers[..entered].iter().rev() {
+            layer.after(&context, &mut response);
+        }
+        response
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("layers", &self.layers.len())
+            .finish()
+    }
+}
+
+/// API Gateway / ALB proxy response with a JSON body
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::json_response;
+///
+/// let response = json_response(400, &serde_json::json!({"message": "bad input"}));
+/// let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+/// assert_eq!(value["statusCode"], 400);
+/// assert_eq!(value["body"], r#"{"message":"bad input"}"#);
+/// ```
+#[must_use]
+pub fn json_response(status_code: u16, body: &serde_json::Value) -> String {
+    serde_json::json!({
+        "statusCode": status_code,
+        "headers": {"Content-Type": "application/json"},
+        "body": body.to_string(),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records hook calls into a shared log
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        short_circuit: bool,
+    }
+
+    impl Middleware for Recorder {
+        fn before(&self, context: &mut Context, _event_body: &str) -> Flow {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("before {}", self.name));
+            context.request_id.push_str(self.name);
+            if self.short_circuit {
+                Flow::Respond(format!("from {}", self.name))
+            } else {
+                Flow::Continue
+            }
+        }
+
+        fn after(&self, _context: &Context, response: &mut String) {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("after {}", self.name));
+            response.push_str(self.name);
+        }
+    }
+
+    fn recorder(name: &'static str, log: &Arc<Mutex<Vec<String>>>, short: bool) -> Recorder {
+        Recorder {
+            name,
+            log: Arc::clone(log),
+            short_circuit: short,
+        }
+    }
+
+    #[test]
+    fn test_layer_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pipeline = Pipeline::new()
+            .with(recorder("a", &log, false))
+            .with(recorder("b", &log, false));
+
+        let response = pipeline.handle(Context::default(), "{}", |context, _| {
+            format!("handler:{}:", context.request_id)
+        });
+
+        assert_eq!(response, "handler:ab:ba");
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["before a", "before b", "after b", "after a"]
+        );
+    }
+
+    #[test]
+    fn test_short_circuit_skips_handler_and_inner_layers() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let pipeline = Pipeline::new()
+            .with(recorder("a", &log, false))
+            .with(recorder("b", &log, true))
+            .with(recorder("c", &log, false));
+
+        let response = pipeline.handle(Context::default(), "{}", |_, _| {
+            panic!("handler must not run")
+        });
+
+        assert_eq!(response, "from bba");
+        assert_eq!(
+            *log.lock().unwrap(),
+            ["before a", "before b", "after b", "after a"]
+        );
+    }
+
+    #[test]
+    fn test_empty_pipeline_calls_handler() {
+        let pipeline = Pipeline::new();
+        assert!(pipeline.is_empty());
+        assert_eq!(
+            pipeline.handle(Context::default(), "x", |_, body| body.to_string()),
+            "x"
+        );
+    }
+
+    #[test]
+    fn test_json_response() {
+        let response = json_response(422, &serde_json::json!({"errors": ["a"]}));
+        let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["statusCode"], 422);
+        assert_eq!(value["headers"]["Content-Type"], "application/json");
+        assert_eq!(value["body"], r#"{"errors":["a"]}"#);
+    }
+}
@@ -0,0 +1,360 @@
+// Amazon MQ Events (ActiveMQ and RabbitMQ)
+//
+// ActiveMQ brokers deliver a flat `messages` list; RabbitMQ brokers group
+// messages by "<queue>::<virtual host>" under `rmqMessagesByQueue`. In both
+// the payload is base64 in `data` and is decoded on demand, like SQS
+// bodies are read on demand.
+//
+// ActiveMQ message properties are strings; RabbitMQ headers are either
+// numbers/strings or `{"bytes": [..]}` for byte-array values.
+
+use ruchy_lambda_simd::base64::{self, DecodeError};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Amazon MQ for `ActiveMQ` event (`aws:mq`)
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::ActiveMqEvent;
+///
+/// let body = r#"{"eventSource":"aws:mq","eventSourceArn":"arn:aws:mq:us-east-1:123456789012:broker:b1",
+///     "messages":[{"messageID":"ID:1","messageType":"jms/text-message","data":"aGk=",
+///                  "destination":{"physicalName":"orders"},"properties":{"tenant":"acme"}}]}"#;
+///
+/// let event = ActiveMqEvent::from_json(body).unwrap();
+/// let message = &event.messages[0];
+/// assert_eq!(message.data_str().as_deref(), Some("hi"));
+/// assert_eq!(message.property("tenant"), Some("acme"));
+/// assert_eq!(message.destination.physical_name, "orders");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveMqEvent {
+    /// Always `aws:mq`
+    #[serde(default)]
+    pub event_source: String,
+    /// Broker ARN
+    #[serde(default)]
+    pub event_source_arn: String,
+    /// Messages in delivery order
+    #[serde(default)]
+    pub messages: Vec<ActiveMqMessage>,
+}
+
+impl ActiveMqEvent {
+    /// Parse an `ActiveMQ` event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not an `ActiveMQ` event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+}
+
+/// One `ActiveMQ` (JMS) message
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveMqMessage {
+    /// JMS message ID
+    #[serde(rename = "messageID", default)]
+    pub message_id: String,
+    /// `jms/text-message` or `jms/bytes-message`
+    #[serde(default)]
+    pub message_type: String,
+    /// Base64 payload
+    #[serde(default)]
+    pub data: String,
+    /// JMS delivery mode (1 = non-persistent, 2 = persistent)
+    #[serde(default)]
+    pub delivery_mode: Option<u8>,
+    /// `JMSReplyTo`
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// `JMSType`
+    #[serde(rename = "type", default)]
+    pub message_kind: Option<String>,
+    /// `JMSExpiration`
+    #[serde(default)]
+    pub expiration: Option<String>,
+    /// `JMSPriority`
+    #[serde(default)]
+    pub priority: Option<u8>,
+    /// `JMSCorrelationID`
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// Whether this message was delivered before
+    #[serde(default)]
+    pub redelivered: bool,
+    /// Queue or topic the message came from
+    #[serde(default)]
+    pub destination: ActiveMqDestination,
+    /// Send time in Unix epoch milliseconds
+    #[serde(default)]
+    pub timestamp: u64,
+    /// When the broker received the message (epoch ms)
+    #[serde(default)]
+    pub broker_in_time: Option<u64>,
+    /// When the broker dispatched the message (epoch ms)
+    #[serde(default)]
+    pub broker_out_time: Option<u64>,
+    /// Custom message properties
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+}
+
+impl ActiveMqMessage {
+    /// Decoded payload
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecodeError` if `data` is not valid base64.
+    pub fn data(&self) -> Result<Vec<u8>, DecodeError> {
+        base64::decode(self.data.as_bytes())
+    }
+
+    /// Decoded payload as UTF-8 text (`None` if not base64-encoded UTF-8)
+    #[must_use]
+    pub fn data_str(&self) -> Option<String> {
+        String::from_utf8(self.data().ok()?).ok()
+    }
+
+    /// Custom property value
+    #[must_use]
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+}
+
+/// Queue or topic of an `ActiveMQ` message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveMqDestination {
+    /// Queue or topic name
+    #[serde(default)]
+    pub physical_name: String,
+}
+
+/// Amazon MQ for `RabbitMQ` event (`aws:rmq`)
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::RabbitMqEvent;
+///
+/// let body = r#"{"eventSource":"aws:rmq","eventSourceArn":"arn:aws:mq:us-east-1:123456789012:broker:b2",
+///     "rmqMessagesByQueue":{"jobs::/":[{"basicProperties":{"contentType":"text/plain",
+///         "headers":{"attempt":{"bytes":[49]},"retries":3}},"redelivered":false,"data":"cnVu"}]}}"#;
+///
+/// let event = RabbitMqEvent::from_json(body).unwrap();
+/// let (queue, message) = event.messages().next().unwrap();
+/// assert_eq!(queue, "jobs");
+/// assert_eq!(message.data_str().as_deref(), Some("run"));
+/// assert_eq!(message.basic_properties.header_bytes("attempt"), Some(b"1".to_vec()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitMqEvent {
+    /// Always `aws:rmq`
+    #[serde(default)]
+    pub event_source: String,
+    /// Broker ARN
+    #[serde(default)]
+    pub event_source_arn: String,
+    /// Messages by `<queue>::<virtual host>`
+    #[serde(default)]
+    pub rmq_messages_by_queue: BTreeMap<String, Vec<RabbitMqMessage>>,
+}
+
+impl RabbitMqEvent {
+    /// Parse a `RabbitMQ` event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a `RabbitMQ` event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// All messages with their queue name, queue by queue
+    pub fn messages(&self) -> impl Iterator<Item = (&str, &RabbitMqMessage)> {
+        self.rmq_messages_by_queue
+            .iter()
+            .flat_map(|(key, messages)| {
+                let queue = key
+                    .split_once("::")
+                    .map_or(key.as_str(), |(queue, _)| queue);
+                messages.iter().map(move |message| (queue, message))
+            })
+    }
+}
+
+/// One `RabbitMQ` message
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitMqMessage {
+    /// AMQP basic properties
+    #[serde(default)]
+    pub basic_properties: RabbitMqBasicProperties,
+    /// Whether this message was delivered before
+    #[serde(default)]
+    pub redelivered: bool,
+    /// Base64 payload
+    #[serde(default)]
+    pub data: String,
+}
+
+impl RabbitMqMessage {
+    /// Decoded payload
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecodeError` if `data` is not valid base64.
+    pub fn data(&self) -> Result<Vec<u8>, DecodeError> {
+        base64::decode(self.data.as_bytes())
+    }
+
+    /// Decoded payload as UTF-8 text (`None` if not base64-encoded UTF-8)
+    #[must_use]
+    pub fn data_str(&self) -> Option<String> {
+        String::from_utf8(self.data().ok()?).ok()
+    }
+}
+
+/// AMQP basic properties of a `RabbitMQ` message
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RabbitMqBasicProperties {
+    /// MIME content type
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// Content encoding
+    #[serde(default)]
+    pub content_encoding: Option<String>,
+    /// Message headers (see [`header_bytes`](Self::header_bytes))
+    #[serde(default)]
+    pub headers: HashMap<String, Value>,
+    /// 1 = non-persistent, 2 = persistent
+    #[serde(default)]
+    pub delivery_mode: Option<u8>,
+    /// Message priority
+    #[serde(default)]
+    pub priority: Option<u8>,
+    /// Correlation ID
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// Reply-to queue
+    #[serde(default)]
+    pub reply_to: Option<String>,
+    /// Expiration
+    #[serde(default)]
+    pub expiration: Option<String>,
+    /// Message ID
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// Timestamp as formatted by the broker
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// Message type
+    #[serde(rename = "type", default)]
+    pub message_kind: Option<String>,
+    /// Publishing user
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// Publishing application
+    #[serde(default)]
+    pub app_id: Option<String>,
+    /// Cluster ID
+    #[serde(default)]
+    pub cluster_id: Option<String>,
+    /// Payload size in bytes
+    #[serde(default)]
+    pub body_size: u64,
+}
+
+impl RabbitMqBasicProperties {
+    /// Header value as bytes
+    ///
+    /// Byte-array headers (`{"bytes": [..]}`) are returned as-is, strings as
+    /// their UTF-8 bytes and other scalars as their JSON text.
+    #[must_use]
+    pub fn header_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        match self.headers.get(name)? {
+            Value::Object(object) => object
+                .get("bytes")?
+                .as_array()?
+                .iter()
+                .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                .collect(),
+            Value::String(text) => Some(text.clone().into_bytes()),
+            Value::Null => None,
+            other => Some(other.to_string().into_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_active_mq_message_fields() {
+        let event = ActiveMqEvent::from_json(
+            r#"{"eventSource":"aws:mq","eventSourceArn":"arn:aws:mq:us-east-1:123456789012:broker:b",
+                "messages":[{"messageID":"ID:b-1","messageType":"jms/bytes-message","deliveryMode":2,
+                  "replyTo":null,"type":null,"expiration":"60000","priority":4,"correlationId":"c-1",
+                  "redelivered":true,"destination":{"physicalName":"q"},"data":"AAEC",
+                  "timestamp":1598827811958,"brokerInTime":1598827811958,"brokerOutTime":1598827811959,
+                  "properties":{"index":"1"}}]}"#,
+        )
+        .unwrap();
+        let message = &event.messages[0];
+
+        assert_eq!(message.message_id, "ID:b-1");
+        assert_eq!(message.delivery_mode, Some(2));
+        assert_eq!(message.correlation_id.as_deref(), Some("c-1"));
+        assert!(message.redelivered);
+        assert_eq!(message.data().unwrap(), [0, 1, 2]);
+        assert_eq!(message.property("index"), Some("1"));
+        assert_eq!(message.property("missing"), None);
+        assert_eq!(message.broker_out_time, Some(1_598_827_811_959));
+    }
+
+    #[test]
+    fn test_active_mq_invalid_data() {
+        let message = ActiveMqMessage {
+            data: "%%%".to_string(),
+            ..ActiveMqMessage::default()
+        };
+        assert!(message.data().is_err());
+        assert_eq!(message.data_str(), None);
+    }
+
+    #[test]
+    fn test_rabbit_mq_queues_and_headers() {
+        let event = RabbitMqEvent::from_json(
+            r#"{"eventSource":"aws:rmq","eventSourceArn":"arn",
+                "rmqMessagesByQueue":{
+                  "b::/prod":[{"basicProperties":{"headers":{"n":10,"s":"text","none":null,"bad":{"bytes":[300]}},
+                                "deliveryMode":1,"bodySize":3},"redelivered":false,"data":"Yg=="}],
+                  "a::/":[{"basicProperties":{},"redelivered":true,"data":"YQ=="}]}}"#,
+        )
+        .unwrap();
+
+        let messages: Vec<(&str, String)> = event
+            .messages()
+            .map(|(queue, message)| (queue, message.data_str().unwrap()))
+            .collect();
+        assert_eq!(messages, [("a", "a".to_string()), ("b", "b".to_string())]);
+
+        let properties = &event.rmq_messages_by_queue["b::/prod"][0].basic_properties;
+        assert_eq!(properties.header_bytes("n"), Some(b"10".to_vec()));
+        assert_eq!(properties.header_bytes("s"), Some(b"text".to_vec()));
+        assert_eq!(properties.header_bytes("none"), None);
+        assert_eq!(properties.header_bytes("bad"), None);
+        assert_eq!(properties.body_size, 3);
+    }
+}
@@ -0,0 +1,469 @@
+// Multipart Form Data
+//
+// File uploads through API Gateway / ALB arrive as a (usually base64)
+// multipart/form-data body:
+//
+//   --BOUNDARY\r\n
+//   Content-Disposition: form-data; name="file"; filename="a.png"\r\n
+//   Content-Type: image/png\r\n
+//   \r\n
+//   <bytes>\r\n
+//   --BOUNDARY--\r\n
+//
+// Multipart walks the decoded body one part at a time, yielding each part's
+// headers and data as slices of the body (nothing is copied). Limits on the
+// part count and part size are checked while scanning, so an oversized
+// upload is rejected without searching the rest of the body.
+
+use std::fmt;
+
+/// Default maximum number of parts
+pub const DEFAULT_MAX_PARTS: usize = 128;
+
+/// Default maximum size of one part's data (6 MiB, the Lambda payload limit)
+pub const DEFAULT_MAX_PART_SIZE: usize = 6 * 1024 * 1024;
+
+/// Maximum size of one part's header block
+const MAX_PART_HEADERS: usize = 8 * 1024;
+
+/// Malformed or over-limit multipart body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultipartError {
+    /// `Content-Type` is not multipart or has no valid `boundary`
+    MissingBoundary,
+    /// Body does not follow the multipart structure
+    Malformed(&'static str),
+    /// More parts than the limit
+    TooManyParts {
+        /// Maximum number of parts
+        limit: usize,
+    },
+    /// A part's data or headers exceed the limit
+    PartTooLarge {
+        /// Limit in bytes
+        limit: usize,
+    },
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBoundary => write!(f, "Missing multipart boundary"),
+            Self::Malformed(reason) => write!(f, "Malformed multipart body: {reason}"),
+            Self::TooManyParts { limit } => write!(f, "Multipart body has over {limit} parts"),
+            Self::PartTooLarge { limit } => {
+                write!(f, "Multipart part exceeds {limit} bytes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {}
+
+/// One part of a multipart body, borrowed from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Part<'a> {
+    /// Form field name (`Content-Disposition` `name`)
+    pub name: Option<&'a str>,
+    /// Uploaded file name (`Content-Disposition` `filename`)
+    pub filename: Option<&'a str>,
+    /// Part `Content-Type`
+    pub content_type: Option<&'a str>,
+    /// Raw header block (CRLF-separated lines)
+    pub headers: &'a str,
+    /// Part data
+    pub data: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    /// Header value (case-insensitive name)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        header(self.headers, name)
+    }
+
+    /// Data as UTF-8 text, if valid
+    #[must_use]
+    pub fn text(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.data).ok()
+    }
+}
+
+/// Pull-based iterator over the parts of a multipart body
+///
+/// An error is yielded once and ends the iteration.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Multipart;
+///
+/// let body = b"--XyZ\r\n\
+///     Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+///     Holiday\r\n\
+///     --XyZ\r\n\
+///     Content-Disposition: form-data; name=\"photo\"; filename=\"beach.png\"\r\n\
+///     Content-Type: image/png\r\n\r\n\
+///     \x89PNG\r\n\
+///     --XyZ--\r\n";
+///
+/// let parts = Multipart::from_content_type(body, "multipart/form-data; boundary=XyZ")
+///     .unwrap()
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(parts[0].name, Some("title"));
+/// assert_eq!(parts[0].text(), Some("Holiday"));
+/// assert_eq!(parts[1].filename, Some("beach.png"));
+/// assert_eq!(parts[1].content_type, Some("image/png"));
+/// assert_eq!(parts[1].data, b"\x89PNG");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Multipart<'a> {
+    /// Unconsumed body, just after the last delimiter seen (None before the
+    /// opening delimiter is found)
+    rest: Option<&'a [u8]>,
+    body: &'a [u8],
+    /// `--` + boundary
+    delimiter: Vec<u8>,
+    max_parts: usize,
+    max_part_size: usize,
+    parts: usize,
+    done: bool,
+}
+
+impl<'a> Multipart<'a> {
+    /// Parts of `body` separated by `boundary`
+    #[must_use]
+    pub fn new(body: &'a [u8], boundary: &str) -> Self {
+        let mut delimiter = Vec::with_capacity(boundary.len() + 2);
+        delimiter.extend_from_slice(b"--");
+        delimiter.extend_from_slice(boundary.as_bytes());
+        Self {
+            rest: None,
+            body,
+            delimiter,
+            max_parts: DEFAULT_MAX_PARTS,
+            max_part_size: DEFAULT_MAX_PART_SIZE,
+            parts: 0,
+            done: false,
+        }
+    }
+
+    /// Parts of `body`, with the boundary taken from a `Content-Type` value
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MultipartError::MissingBoundary`] unless `content_type` is
+    /// `multipart/*` with a 1-70 character `boundary` parameter.
+    pub fn from_content_type(body: &'a [u8], content_type: &str) -> Result<Self, MultipartError> {
+        boundary(content_type)
+            .map(|boundary| Self::new(body, boundary))
+            .ok_or(MultipartError::MissingBoundary)
+    }
+
+    /// Maximum number of parts (default: 128)
+    #[must_use]
+    pub fn with_max_parts(mut self, max_parts: usize) -> Self {
+        self.max_parts = max_parts;
+        self
+    }
+
+    /// Maximum size of one part's data in bytes (default: 6 MiB)
+    #[must_use]
+    pub fn with_max_part_size(mut self, max_part_size: usize) -> Self {
+        self.max_part_size = max_part_size;
+        self
+    }
+
+    /// Body just after the opening delimiter (the preamble is skipped)
+    fn after_opening(&self) -> Result<&'a [u8], MultipartError> {
+        if self.body.starts_with(&self.delimiter) {
+            return Ok(&self.body[self.delimiter.len()..]);
+        }
+        let mut crlf_delimiter = b"\r\n".to_vec();
+        crlf_delimiter.extend_from_slice(&self.delimiter);
+        find(self.body, &crlf_delimiter)
+            .map(|start| &self.body[start + crlf_delimiter.len()..])
+            .ok_or(MultipartError::Malformed("no opening boundary"))
+    }
+
+    fn next_part(&mut self) -> Result<Option<Part<'a>>, MultipartError> {
+        let rest = match self.rest {
+            Some(rest) => rest,
+            None => self.after_opening()?,
+        };
+
+        // Close delimiter: --BOUNDARY--
+        if rest.starts_with(b"--") {
+            return Ok(None);
+        }
+
+        // Transport padding, then the line break ending the delimiter line
+        let padding = rest
+            .iter()
+            .take_while(|&&b| b == b' ' || b == b'\t')
+            .count();
+        let rest = rest[padding..]
+            .strip_prefix(b"\r\n")
+            .ok_or(MultipartError::Malformed("boundary line not terminated"))?;
+
+        let (headers, rest) = if let Some(rest) = rest.strip_prefix(b"\r\n") {
+            (&rest[..0], rest)
+        } else {
+            let window = &rest[..rest.len().min(MAX_PART_HEADERS + 4)];
+            let end = find(window, b"\r\n\r\n").ok_or(if window.len() < rest.len() {
+                MultipartError::PartTooLarge {
+                    limit: MAX_PART_HEADERS,
+                }
+            } else {
+                MultipartError::Malformed("part headers not terminated")
+            })?;
+            (&rest[..end], &rest[end + 4..])
+        };
+        let headers = std::str::from_utf8(headers)
+            .map_err(|_| MultipartError::Malformed("part headers are not UTF-8"))?;
+
+        // Data runs up to CRLF + delimiter; only search as far as the limit
+        let mut next_delimiter = b"\r\n".to_vec();
+        next_delimiter.extend_from_slice(&self.delimiter);
+        let window = &rest[..rest
+            .len()
+            .min(self.max_part_size.saturating_add(next_delimiter.len()))];
+        let end = find(window, &next_delimiter).ok_or(if window.len() < rest.len() {
+            MultipartError::PartTooLarge {
+                limit: self.max_part_size,
+            }
+        } else {
+            MultipartError::Malformed("missing closing boundary")
+        })?;
+        if end > self.max_part_size {
+            return Err(MultipartError::PartTooLarge {
+                limit: self.max_part_size,
+            });
+        }
+
+        self.parts += 1;
+        if self.parts > self.max_parts {
+            return Err(MultipartError::TooManyParts {
+                limit: self.max_parts,
+            });
+        }
+        self.rest = Some(&rest[end + next_delimiter.len()..]);
+
+        let disposition = header(headers, "content-disposition");
+        Ok(Some(Part {
+            name: disposition.and_then(|value| parameter(value, "name")),
+            filename: disposition.and_then(|value| parameter(value, "filename")),
+            content_type: header(headers, "content-type"),
+            headers,
+            data: &rest[..end],
+        }))
+    }
+}
+
+impl<'a> Iterator for Multipart<'a> {
+    type Item = Result<Part<'a>, MultipartError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_part();
+        match result {
+            Ok(Some(part)) => Some(Ok(part)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// `boundary` parameter of a `multipart/*` content type
+fn boundary(content_type: &str) -> Option<&str> {
+    let media_type = content_type.split(';').next()?.trim();
+    if !media_type
+        .get(..10)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("multipart/"))
+    {
+        return None;
+    }
+    parameter(content_type, "boundary").filter(|boundary| (1..=70).contains(&boundary.len()))
+}
+
+/// Value of `name` in a `; key=value; key="value"` parameter list
+///
+/// Quoted values are returned without their quotes (escapes are kept).
+fn parameter<'a>(header_value: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = header_value.split_once(';')?.1;
+    loop {
+        let (key, after_key) = rest.split_once('=')?;
+        let after_key = after_key.trim_start();
+        let (value, after_value) = if let Some(quoted) = after_key.strip_prefix('"') {
+            let end = closing_quote(quoted)?;
+            let after = &quoted[end + 1..];
+            (
+                &quoted[..end],
+                after.split_once(';').map_or("", |(_, next)| next),
+            )
+        } else {
+            after_key
+                .split_once(';')
+                .map_or((after_key.trim_end(), ""), |(value, next)| {
+                    (value.trim_end(), next)
+                })
+        };
+        if key.trim().eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+        rest = after_value;
+    }
+}
+
+/// Index of the first unescaped `"`
+fn closing_quote(quoted: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, ch) in quoted.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Header value from a CRLF-separated header block
+fn header<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+    headers.split("\r\n").find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+/// First index of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts<'a>(body: &'a [u8], boundary: &str) -> Result<Vec<Part<'a>>, MultipartError> {
+        Multipart::new(body, boundary).collect()
+    }
+
+    #[test]
+    fn test_preamble_epilogue_and_padding() {
+        let body = b"preamble\r\n--b \t\r\n\r\nno headers\r\n--b\r\nX-Note: a\r\n\r\n\r\n--b--\r\nepilogue";
+        let parts = parts(body, "b").unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].headers, "");
+        assert_eq!(parts[0].text(), Some("no headers"));
+        assert_eq!(parts[1].header("x-note"), Some("a"));
+        assert!(parts[1].data.is_empty());
+    }
+
+    #[test]
+    fn test_data_may_contain_boundary_lookalikes() {
+        let body = b"--b\r\n\r\n--bx\r\n-- b\r\n--b--";
+        assert_eq!(parts(body, "b").unwrap()[0].data, b"--bx\r\n-- b");
+    }
+
+    #[test]
+    fn test_disposition_parameters() {
+        let value = r#"form-data; name="field; one"; filename="a \"q\".txt""#;
+        assert_eq!(parameter(value, "name"), Some("field; one"));
+        assert_eq!(parameter(value, "filename"), Some(r#"a \"q\".txt"#));
+        assert_eq!(parameter("form-data; name=plain", "name"), Some("plain"));
+        assert_eq!(parameter("form-data", "name"), None);
+    }
+
+    #[test]
+    fn test_boundary_from_content_type() {
+        assert_eq!(
+            boundary("multipart/form-data; boundary=----WebKitFormBoundary7MA4"),
+            Some("----WebKitFormBoundary7MA4")
+        );
+        assert_eq!(boundary(r#"Multipart/Mixed; boundary="a b""#), Some("a b"));
+        assert_eq!(boundary("application/json; boundary=x"), None);
+        assert_eq!(boundary("multipart/form-data"), None);
+        assert_eq!(
+            Multipart::from_content_type(b"", "text/plain").unwrap_err(),
+            MultipartError::MissingBoundary
+        );
+    }
+
+    #[test]
+    fn test_malformed_bodies() {
+        assert_eq!(
+            parts(b"no boundary here", "b").unwrap_err(),
+            MultipartError::Malformed("no opening boundary")
+        );
+        assert_eq!(
+            parts(b"--b\r\n\r\ndata without end", "b").unwrap_err(),
+            MultipartError::Malformed("missing closing boundary")
+        );
+        assert_eq!(
+            parts(b"--bjunk", "b").unwrap_err(),
+            MultipartError::Malformed("boundary line not terminated")
+        );
+
+        // The error ends the iteration
+        let mut iter = Multipart::new(b"--b\r\nX: 1", "b");
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_limits() {
+        let body = b"--b\r\n\r\n12345\r\n--b\r\n\r\n1\r\n--b--";
+        assert_eq!(
+            Multipart::new(body, "b")
+                .with_max_part_size(4)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err(),
+            MultipartError::PartTooLarge { limit: 4 }
+        );
+        assert_eq!(
+            Multipart::new(body, "b")
+                .with_max_part_size(5)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            Multipart::new(body, "b")
+                .with_max_parts(1)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err(),
+            MultipartError::TooManyParts { limit: 1 }
+        );
+
+        // Oversized data is rejected without a closing boundary in reach
+        let mut large = b"--b\r\n\r\n".to_vec();
+        large.extend(std::iter::repeat_n(b'x', 64));
+        assert_eq!(
+            parts(&large, "b").map(|_| ()),
+            Err(MultipartError::Malformed("missing closing boundary"))
+        );
+        assert_eq!(
+            Multipart::new(&large, "b")
+                .with_max_part_size(16)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_err(),
+            MultipartError::PartTooLarge { limit: 16 }
+        );
+    }
+}
@@ -0,0 +1,172 @@
+// Newline-Delimited JSON (JSON Lines / NDJSON)
+//
+// Firehose transforms, S3 object processors and log shippers commonly carry
+// one JSON document per line. These helpers split a body into borrowed
+// lines (tolerating CRLF and blank lines), parse them with per-line error
+// collection so one bad line does not fail the batch, and write documents
+// back out as NDJSON.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Iterator over the non-blank lines of an NDJSON body
+///
+/// Yields `(line_number, line)` with 1-based line numbers and the line
+/// borrowed from the body, without its `\n` / `\r\n` terminator.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::NdjsonLines;
+///
+/// let lines: Vec<_> = NdjsonLines::new("{\"a\":1}\r\n\n{\"a\":2}\n").collect();
+/// assert_eq!(lines, [(1, "{\"a\":1}"), (3, "{\"a\":2}")]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NdjsonLines<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> NdjsonLines<'a> {
+    /// Split `body` into lines
+    #[must_use]
+    pub fn new(body: &'a str) -> Self {
+        Self {
+            rest: body,
+            line: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for NdjsonLines<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.rest.is_empty() {
+            let (line, rest) = match self.rest.find('\n') {
+                Some(end) => (&self.rest[..end], &self.rest[end + 1..]),
+                None => (self.rest, ""),
+            };
+            self.rest = rest;
+            self.line += 1;
+
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if !line.trim().is_empty() {
+                return Some((self.line, line));
+            }
+        }
+        None
+    }
+}
+
+/// A line that failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdjsonError {
+    /// 1-based line number
+    pub line: usize,
+    /// Parser error message
+    pub message: String,
+}
+
+impl fmt::Display for NdjsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for NdjsonError {}
+
+/// Parse every line of an NDJSON body
+///
+/// Lines that parse are returned in order; lines that do not are reported
+/// in the error list instead of aborting the batch.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::parse_ndjson;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Log<'a> {
+///     level: &'a str,
+/// }
+///
+/// let (logs, errors) = parse_ndjson::<Log>("{\"level\":\"info\"}\nnot json\n{\"level\":\"warn\"}");
+/// assert_eq!(logs.len(), 2);
+/// assert_eq!(errors[0].line, 2);
+/// ```
+#[must_use]
+pub fn parse_ndjson<'a, T: Deserialize<'a>>(body: &'a str) -> (Vec<T>, Vec<NdjsonError>) {
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (line, text) in NdjsonLines::new(body) {
+        match serde_json::from_str(text) {
+            Ok(item) => items.push(item),
+            Err(e) => errors.push(NdjsonError {
+                line,
+                message: e.to_string(),
+            }),
+        }
+    }
+    (items, errors)
+}
+
+/// Serialize documents as NDJSON (one per line, each `\n`-terminated)
+///
+/// # Errors
+///
+/// Returns the first `serde_json` serialization error.
+pub fn to_ndjson<T: Serialize>(
+    items: impl IntoIterator<Item = T>,
+) -> Result<String, serde_json::Error> {
+    let mut out = String::new();
+    for item in items {
+        out.push_str(&serde_json::to_string(&item)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_lines_borrow_and_number() {
+        let body = "  \n{\"a\":1}\n\r\n{\"b\":2}";
+        let lines: Vec<_> = NdjsonLines::new(body).collect();
+        assert_eq!(lines, [(2, "{\"a\":1}"), (4, "{\"b\":2}")]);
+        assert!(body
+            .as_bytes()
+            .as_ptr_range()
+            .contains(&lines[0].1.as_ptr()));
+    }
+
+    #[test]
+    fn test_empty_body() {
+        assert_eq!(NdjsonLines::new("").count(), 0);
+        assert_eq!(NdjsonLines::new("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_parse_collects_errors_per_line() {
+        let (values, errors) = parse_ndjson::<Value>("{\"a\":1}\n{oops\n[1]\n{\"b\":\n");
+        assert_eq!(values, [json!({"a": 1}), json!([1])]);
+        assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<_>>(), [2, 4]);
+        assert!(errors[0].to_string().starts_with("line 2: "));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let items = [json!({"a": 1}), json!({"b": "x\ny"})];
+        let body = to_ndjson(&items).unwrap();
+        assert_eq!(body, "{\"a\":1}\n{\"b\":\"x\\ny\"}\n");
+
+        let (parsed, errors) = parse_ndjson::<Value>(&body);
+        assert!(errors.is_empty());
+        assert_eq!(parsed, items);
+    }
+}
@@ -0,0 +1,162 @@
+// HTTP/1.1 Pipelining of the Response POST and the Next GET
+//
+// Opt-in (RUCHY_LAMBDA_PIPELINE=1 or Runtime::with_pipelining). Without it,
+// every Runtime API call opens a connection and sends `Connection: close`.
+// After posting a response the next call is always `/next` on the same API,
+// so in pipelining mode the HttpClient keeps one keep-alive connection and
+// writes the response POST and the `/next` GET in a single write. The API
+// processes the POST while the GET is already queued behind it: a warm
+// invocation saves a round trip and a connect.
+//
+// `post_response` reads only the POST status; the following `next_*` call
+// reads the event the pipelined GET already asked for.
+//
+// Stale connections: if writing to a kept connection fails (an idle
+// keep-alive timeout), the request is sent again once on a fresh
+// connection. A GET is also sent again when the connection is closed, reset
+// or fails before its response. A POST that was written is never re-sent,
+// since the API may already have accepted it and would reject the second
+// one. A pipelined GET whose response never comes is re-sent by the next
+// `next_*` call.
+//
+// Not combined with prefetch: the prefetcher thread polls `/next` on its
+// own, so a pipelined GET would fetch an event nobody reads.
+
+use ruchy_lambda_http_core::{HttpError, PersistentConnection, Transport};
+
+/// Environment variable enabling pipelining mode (`1`, `true` or `on`)
+pub const PIPELINE_ENV: &str = "RUCHY_LAMBDA_PIPELINE";
+
+/// Largest POST response body read (the API answers with a short status)
+const MAX_POST_RESPONSE_SIZE: usize = 64 * 1024;
+
+/// Keep-alive connection state of a pipelining [`HttpClient`](crate::http_client::HttpClient)
+#[derive(Default)]
+pub(crate) struct Pipelined {
+    connection: Option<PersistentConnection<Box<dyn Transport>>>,
+    /// Whether a `/next` GET was sent whose response is not read yet
+    next_pending: bool,
+    /// POST responses are read here, reused across invocations
+    post_response: Vec<u8>,
+}
+
+impl Pipelined {
+    /// Use `stream` (e.g. opened by `preconnect`) if no connection is kept
+    pub(crate) fn adopt(&mut self, stream: Box<dyn Transport>) {
+        if self.connection.is_none() {
+            self.connection = Some(PersistentConnection::new(stream));
+        }
+    }
+
+    /// Read the response to a GET of `path` into `buffer`
+    ///
+    /// Reads the response of the GET pipelined behind the last POST if
+    /// there is one, and sends the GET now otherwise.
+    pub(crate) fn get_into(
+        &mut self,
+        endpoint: &str,
+        path: &str,
+        max_response_size: usize,
+        buffer: &mut Vec<u8>,
+        open: impl Fn() -> Result<Box<dyn Transport>, HttpError>,
+    ) -> Result<(), HttpError> {
+        if std::mem::take(&mut self.next_pending) {
+            if let Some(connection) = &mut self.connection {
+                match connection.read_response_into(buffer, max_response_size) {
+                    Ok(true) => return Ok(()),
+                    // Closed without answering: ask again below
+                    Ok(false) | Err(HttpError::Io(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        self.exchange(open, buffer, max_response_size, true, |connection| {
+            connection.send_get(endpoint, path)
+        })
+    }
+
+    /// Send a POST and the GET of `next_path` behind it
+    ///
+    /// Returns the raw POST response; the GET response is left for
+    /// [`Pipelined::get_into`].
+    pub(crate) fn post_then_next(
+        &mut self,
+        endpoint: &str,
+        path: &str,
+        body: &str,
+        headers: &[(&str, &str)],
+        next_path: &str,
+        open: impl Fn() -> Result<Box<dyn Transport>, HttpError>,
+    ) -> Result<&[u8], HttpError> {
+        if std::mem::take(&mut self.next_pending) {
+            // An unread event is still on the connection: don't answer past it
+            self.connection = None;
+        }
+
+        let mut response = std::mem::take(&mut self.post_response);
+        let result = self.exchange(
+            open,
+            &mut response,
+            MAX_POST_RESPONSE_SIZE,
+            false,
+            |connection| connection.send_post_then_get(endpoint, path, body, headers, next_path),
+        );
+        self.post_response = response;
+        result?;
+
+        self.next_pending = true;
+        Ok(&self.post_response)
+    }
+
+    /// Send a request with `send` and read its response into `buffer`
+    ///
+    /// A kept connection that turns out to be stale is replaced by a fresh
+    /// one from `open` and the request sent once more when the write fails.
+    /// A request that was written is sent again, after the connection is
+    /// closed or fails before a response, only if it is `idempotent`: the
+    /// API may already have acted on a delivered POST.
+    fn exchange(
+        &mut self,
+        open: impl Fn() -> Result<Box<dyn Transport>, HttpError>,
+        buffer: &mut Vec<u8>,
+        max_response_size: usize,
+        idempotent: bool,
+        send: impl Fn(&mut PersistentConnection<Box<dyn Transport>>) -> Result<(), HttpError>,
+    ) -> Result<(), HttpError> {
+        let mut reused = true;
+        loop {
+            let connection = match &mut self.connection {
+                Some(connection) if connection.is_reusable() => connection,
+                _ => {
+                    reused = false;
+                    self.connection.insert(PersistentConnection::new(open()?))
+                }
+            };
+
+            let stale = match send(connection) {
+                Err(e @ HttpError::Io(_)) => e,
+                Err(e) => return Err(e),
+                Ok(()) => match connection.read_response_into(buffer, max_response_size) {
+                    Ok(true) => return Ok(()),
+                    Ok(false) => {
+                        let closed = HttpError::InvalidResponse(
+                            "Connection closed before a response".to_string(),
+                        );
+                        if !idempotent {
+                            return Err(closed);
+                        }
+                        closed
+                    }
+                    Err(e @ HttpError::Io(_)) if idempotent => e,
+                    Err(e) => return Err(e),
+                },
+            };
+            if !reused {
+                return Err(stale);
+            }
+            self.connection = None;
+            reused = false;
+        }
+    }
+}
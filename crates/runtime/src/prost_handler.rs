@@ -0,0 +1,285 @@
+// Protobuf Typed Handlers (feature "protobuf")
+//
+// Adapts a handler over prost-generated messages to the runtime's string
+// handler shape. Invocation payloads must be JSON, so the protobuf bytes
+// arrive base64-encoded, the same way as the binary codecs: as a bare JSON
+// string, or as the body of an API Gateway / ALB proxy event with
+// isBase64Encoded set (gRPC-gateway style clients posting
+// application/x-protobuf).
+//
+// Responses are returned as a JSON string of base64, or as a base64 proxy
+// response when the handler sits behind API Gateway / ALB.
+//
+// ProstCodec is this convention as an EventCodec, for registering on the
+// runtime (Runtime::with_event_codec); ProstHandler wraps one.
+
+use crate::codec::{binary_payload, EventCodec, UNMARSHAL_ERROR};
+use crate::{Context, HandlerError};
+use ruchy_lambda_simd::base64;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// `Content-Type` of protobuf proxy responses
+pub const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+
+/// Protobuf-over-base64 payloads, as an [`EventCodec`] for prost messages
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, HandlerError, ProstCodec, Runtime, RuntimeConfig};
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct Ping {
+///     #[prost(string, tag = "1")]
+///     name: String,
+/// }
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct Pong {
+///     #[prost(string, tag = "1")]
+///     greeting: String,
+/// }
+///
+/// let runtime = Runtime::from_config(RuntimeConfig::default())
+///     .with_event_codec::<Ping, Pong>(ProstCodec::default());
+/// let handler = runtime.typed_handler(|_ctx: &Context, ping: Ping| {
+///     Ok::<_, HandlerError>(Pong { greeting: format!("hello {}", ping.name) })
+/// });
+///
+/// // Ping { name: "ada" } = 0a 03 61 64 61
+/// let response = handler(&Context::default(), r#""CgNhZGE=""#).unwrap();
+/// assert_eq!(response, r#""CgloZWxsbyBhZGE=""#);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProstCodec {
+    proxy_response: bool,
+}
+
+impl ProstCodec {
+    /// Answer with an API Gateway / ALB proxy response (status 200,
+    /// `application/x-protobuf`, base64 body) instead of a bare string
+    #[must_use]
+    pub fn with_proxy_response(mut self, proxy_response: bool) -> Self {
+        self.proxy_response = proxy_response;
+        self
+    }
+}
+
+impl<Req, Resp> EventCodec<Req, Resp> for ProstCodec
+where
+    Req: prost::Message + Default,
+    Resp: prost::Message,
+{
+    fn decode(&self, event_body: &str) -> Result<Req, HandlerError> {
+        let bytes = binary_payload(event_body)?;
+        Req::decode(bytes.as_slice()).map_err(|e| HandlerError::new(UNMARSHAL_ERROR, e.to_string()))
+    }
+
+    fn encode(&self, response: &Resp) -> Result<String, HandlerError> {
+        let encoded = base64::encode(&response.encode_to_vec());
+        let response = if self.proxy_response {
+            serde_json::json!({
+                "statusCode": 200,
+                "headers": {"content-type": PROTOBUF_CONTENT_TYPE},
+                "body": encoded,
+                "isBase64Encoded": true,
+            })
+        } else {
+            serde_json::Value::String(encoded)
+        };
+        Ok(response.to_string())
+    }
+}
+
+/// Handler over prost messages
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, HandlerError, ProstHandler};
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct Ping {
+///     #[prost(string, tag = "1")]
+///     name: String,
+/// }
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct Pong {
+///     #[prost(string, tag = "1")]
+///     greeting: String,
+/// }
+///
+/// let handler = ProstHandler::new(|_ctx: &Context, ping: Ping| {
+///     Ok::<_, HandlerError>(Pong { greeting: format!("hello {}", ping.name) })
+/// });
+///
+/// // Ping { name: "ada" } = 0a 03 61 64 61
+/// let response = handler.handle(&Context::default(), r#""CgNhZGE=""#).unwrap();
+/// assert_eq!(response, r#""CgloZWxsbyBhZGE=""#);
+/// ```
+pub struct ProstHandler<Req, Resp, F> {
+    handler: F,
+    codec: ProstCodec,
+    _messages: PhantomData<fn(Req) -> Resp>,
+}
+
+impl<Req, Resp, F> ProstHandler<Req, Resp, F>
+where
+    Req: prost::Message + Default,
+    Resp: prost::Message,
+    F: Fn(&Context, Req) -> Result<Resp, HandlerError>,
+{
+    /// Wrap `handler`
+    pub fn new(handler: F) -> Self {
+        Self {
+            handler,
+            codec: ProstCodec::default(),
+            _messages: PhantomData,
+        }
+    }
+
+    /// Answer with an API Gateway / ALB proxy response (status 200,
+    /// `application/x-protobuf`, base64 body) instead of a bare string
+    #[must_use]
+    pub fn with_proxy_response(mut self, proxy_response: bool) -> Self {
+        self.codec = self.codec.with_proxy_response(proxy_response);
+        self
+    }
+
+    /// Decode the event, run the handler and encode its response
+    ///
+    /// # Errors
+    ///
+    /// Returns a `Runtime.UnmarshalError` for payloads that are not base64
+    /// protobuf, or the handler's own error.
+    pub fn handle(&self, context: &Context, event_body: &str) -> Result<String, HandlerError> {
+        let request = EventCodec::<Req, Resp>::decode(&self.codec, event_body)?;
+        let response = (self.handler)(context, request)?;
+        EventCodec::<Req, Resp>::encode(&self.codec, &response)
+    }
+}
+
+impl<Req, Resp, F> fmt::Debug for ProstHandler<Req, Resp, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProstHandler")
+            .field("request", &std::any::type_name::<Req>())
+            .field("response", &std::any::type_name::<Resp>())
+            .field("proxy_response", &self.codec.proxy_response)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Order {
+        #[prost(uint32, tag = "1")]
+        quantity: u32,
+        #[prost(string, repeated, tag = "2")]
+        items: Vec<String>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Total {
+        #[prost(uint64, tag = "1")]
+        cents: u64,
+    }
+
+    fn handler(
+    ) -> ProstHandler<Order, Total, impl Fn(&Context, Order) -> Result<Total, HandlerError>> {
+        ProstHandler::new(|_: &Context, order: Order| {
+            if order.items.is_empty() {
+                return Err(HandlerError::new("Function.EmptyOrder", "no items"));
+            }
+            Ok(Total {
+                cents: u64::from(order.quantity) * 250,
+            })
+        })
+    }
+
+    fn order_base64() -> String {
+        let order = Order {
+            quantity: 4,
+            items: vec!["tea".to_string()],
+        };
+        base64::encode(&prost::Message::encode_to_vec(&order))
+    }
+
+    fn decode_total(encoded: &str) -> Total {
+        prost::Message::decode(base64::decode(encoded.as_bytes()).unwrap().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_bare_string_round_trip() {
+        let body = serde_json::to_string(&order_base64()).unwrap();
+        let response = handler().handle(&Context::default(), &body).unwrap();
+        let encoded: String = serde_json::from_str(&response).unwrap();
+        assert_eq!(decode_total(&encoded).cents, 1000);
+    }
+
+    #[test]
+    fn test_proxy_request_and_response() {
+        let event = serde_json::json!({
+            "httpMethod": "POST",
+            "headers": {"content-type": PROTOBUF_CONTENT_TYPE},
+            "body": order_base64(),
+            "isBase64Encoded": true,
+        })
+        .to_string();
+        let response = handler()
+            .with_proxy_response(true)
+            .handle(&Context::default(), &event)
+            .unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+
+        assert_eq!(response["statusCode"], 200);
+        assert_eq!(response["headers"]["content-type"], PROTOBUF_CONTENT_TYPE);
+        assert_eq!(response["isBase64Encoded"], true);
+        assert_eq!(decode_total(response["body"].as_str().unwrap()).cents, 1000);
+    }
+
+    #[test]
+    fn test_codec_registered_on_runtime() {
+        let runtime = crate::Runtime::from_config(crate::RuntimeConfig::default())
+            .with_event_codec::<Order, Total>(ProstCodec::default().with_proxy_response(true));
+        let typed = runtime.typed_handler(|_: &Context, order: Order| {
+            Ok::<_, HandlerError>(Total {
+                cents: u64::from(order.quantity) * 250,
+            })
+        });
+
+        let event = serde_json::json!({"body": order_base64(), "isBase64Encoded": true});
+        let response = typed(&Context::default(), &event.to_string()).unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(response["statusCode"], 200);
+        assert_eq!(decode_total(response["body"].as_str().unwrap()).cents, 1000);
+    }
+
+    #[test]
+    fn test_errors() {
+        let ctx = Context::default();
+        // Field 1 as a length-delimited value where a varint is expected
+        let wrong_wire_type = serde_json::to_string(&base64::encode(&[0x0a, 0x01, 0x00])).unwrap();
+        assert_eq!(
+            handler()
+                .handle(&ctx, &wrong_wire_type)
+                .unwrap_err()
+                .error_type,
+            UNMARSHAL_ERROR
+        );
+        assert_eq!(
+            handler().handle(&ctx, "{}").unwrap_err().error_type,
+            UNMARSHAL_ERROR
+        );
+
+        let empty = serde_json::to_string(&base64::encode(&[])).unwrap();
+        assert_eq!(
+            handler().handle(&ctx, &empty).unwrap_err().error_type,
+            "Function.EmptyOrder"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Query String and Cookie Parsing
+//
+// Query strings and Cookie headers are split in place: each pair is a
+// slice of the original text, and decoding only allocates when the slice
+// actually contains an escape.
+//
+// - Query strings (application/x-www-form-urlencoded): '&'-separated
+//   name=value pairs, '+' is a space, %XX is a byte. Repeated names are
+//   kept in order.
+// - Cookies (RFC 6265): "; "-separated name=value pairs. '+' is literal;
+//   %XX escapes (commonly used by frameworks) are decoded and surrounding
+//   double quotes removed.
+//
+// Invalid escapes are kept literally and invalid UTF-8 is replaced with
+// U+FFFD rather than failing the request.
+
+use std::borrow::Cow;
+
+/// Iterator over decoded `name=value` pairs of a query string
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::QueryPairs;
+///
+/// let pairs: Vec<_> = QueryPairs::new("q=rust+lambda&path=a%2Fb&tag=x&tag=y&flag").collect();
+/// assert_eq!(pairs[0], ("q".into(), "rust lambda".into()));
+/// assert_eq!(pairs[1], ("path".into(), "a/b".into()));
+/// assert_eq!(pairs[3], ("tag".into(), "y".into()));
+/// assert_eq!(pairs[4], ("flag".into(), "".into()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryPairs<'a> {
+    rest: &'a str,
+}
+
+impl<'a> QueryPairs<'a> {
+    /// Pairs of `query` (a leading `?` is ignored)
+    #[must_use]
+    pub fn new(query: &'a str) -> Self {
+        Self {
+            rest: query.strip_prefix('?').unwrap_or(query),
+        }
+    }
+}
+
+impl<'a> Iterator for QueryPairs<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.rest.is_empty() {
+            let (pair, rest) = self.rest.split_once('&').unwrap_or((self.rest, ""));
+            self.rest = rest;
+            if pair.is_empty() {
+                continue;
+            }
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            return Some((decode(name, true), decode(value, true)));
+        }
+        None
+    }
+}
+
+/// Iterator over `name=value` pairs of a `Cookie` header
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Cookies;
+///
+/// let cookies: Vec<_> = Cookies::new(r#"session=abc+123; theme="dark"; note=a%3Bb"#).collect();
+/// assert_eq!(cookies[0], ("session", "abc+123".into()));
+/// assert_eq!(cookies[1], ("theme", "dark".into()));
+/// assert_eq!(cookies[2], ("note", "a;b".into()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cookies<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cookies<'a> {
+    /// Cookies of a `Cookie` header value
+    #[must_use]
+    pub fn new(header: &'a str) -> Self {
+        Self { rest: header }
+    }
+}
+
+impl<'a> Iterator for Cookies<'a> {
+    type Item = (&'a str, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.rest.is_empty() {
+            let (pair, rest) = self.rest.split_once(';').unwrap_or((self.rest, ""));
+            self.rest = rest;
+            let Some((name, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|inner| inner.strip_suffix('"'))
+                .unwrap_or(value);
+            return Some((name, decode(value, false)));
+        }
+        None
+    }
+}
+
+/// Percent-decode `text` (and `+` as space when `plus_as_space`)
+///
+/// Borrows `text` when it contains nothing to decode.
+pub(crate) fn decode(text: &str, plus_as_space: bool) -> Cow<'_, str> {
+    let plus = plus_as_space && text.contains('+');
+    if !plus && !text.contains('%') {
+        return Cow::Borrowed(text);
+    }
+
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' if plus_as_space => decoded.push(b' '),
+            b'%' => {
+                let escape = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = escape {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+                decoded.push(b'%');
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(err) => Cow::Owned(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+    }
+}
+
+/// Percent-encode a query component (everything but RFC 3986 unreserved)
+#[cfg(feature = "serde")]
+pub(crate) fn encode(text: &str, out: &mut String) {
+    use std::fmt::Write;
+
+    for &byte in text.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(query: &str) -> Vec<(String, String)> {
+        QueryPairs::new(query)
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_edge_cases() {
+        assert_eq!(
+            pairs("?a=1&&b=&=x&c=d=e&a=2"),
+            [
+                ("a".into(), "1".into()),
+                ("b".into(), String::new()),
+                (String::new(), "x".into()),
+                ("c".into(), "d=e".into()),
+                ("a".into(), "2".into()),
+            ]
+        );
+        assert!(pairs("").is_empty());
+        assert!(pairs("&&").is_empty());
+    }
+
+    #[test]
+    fn test_query_decoding() {
+        assert_eq!(
+            pairs("q=a+b%2Bc&p=%2Fetc%2fpasswd&e=caf%C3%A9&k%20ey=v"),
+            [
+                ("q".into(), "a b+c".into()),
+                ("p".into(), "/etc/passwd".into()),
+                ("e".into(), "café".into()),
+                ("k ey".into(), "v".into()),
+            ]
+        );
+        // Invalid escapes stay literal; invalid UTF-8 is replaced
+        assert_eq!(
+            pairs("a=100%&b=%zz&c=%FF"),
+            [
+                ("a".into(), "100%".into()),
+                ("b".into(), "%zz".into()),
+                ("c".into(), "\u{fffd}".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_borrows_when_clean() {
+        assert!(matches!(decode("plain", true), Cow::Borrowed("plain")));
+        assert!(matches!(decode("a+b", false), Cow::Borrowed("a+b")));
+        assert!(matches!(decode("a+b", true), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_cookies() {
+        let cookies: Vec<_> = Cookies::new(" a=1 ;b= 2;flag; =x;c=\"q\";d=%41+").collect();
+        assert_eq!(
+            cookies,
+            [
+                ("a", Cow::from("1")),
+                ("b", Cow::from("2")),
+                ("c", Cow::from("q")),
+                ("d", Cow::from("A+")),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_encode_round_trips() {
+        let mut encoded = String::new();
+        encode("a b/c+d&é", &mut encoded);
+        assert_eq!(encoded, "a%20b%2Fc%2Bd%26%C3%A9");
+        assert_eq!(decode(&encoded, true), "a b/c+d&é");
+    }
+}
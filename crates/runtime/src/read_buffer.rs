@@ -0,0 +1,134 @@
+// Adaptive `/next` Read Buffer Sizing
+//
+// A `/next` response is read into a Vec that starts small and doubles as
+// chunks arrive, so a 1MB event costs about eight reallocations and copies.
+// Workloads are usually steady: the last few events predict the next one.
+//
+// The HttpClient records each event's body size (its Content-Length) in a
+// small ring and sizes the next read buffer for the largest recent one,
+// bounded below (tiny events still get a useful buffer) and above (the
+// response size limit). Large-event workloads then read into a buffer that
+// already fits; tiny-event workloads never hold more than they need, and a
+// caller-provided buffer that grew for a burst of large events is shrunk
+// once they have left the history.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of recent events the size is chosen from
+const HISTORY: usize = 8;
+
+/// Smallest suggested buffer: one read chunk plus a typical small event
+pub(crate) const MIN_READ_BUFFER: usize = 16 * 1024;
+
+/// Room for the status line and `Lambda-Runtime-*` headers
+const HEAD_ALLOWANCE: usize = 2 * 1024;
+
+/// Read buffer size suggested by recent event sizes
+#[derive(Debug)]
+pub(crate) struct ReadBufferSizer {
+    /// Body sizes of the last [`HISTORY`] events (0 until filled)
+    recent: [AtomicUsize; HISTORY],
+    /// Slot the next size is recorded in, modulo [`HISTORY`]
+    cursor: AtomicUsize,
+    /// Upper bound: a full response at the size limit
+    max: usize,
+}
+
+impl ReadBufferSizer {
+    /// Sizer for responses with bodies of at most `max_body_size` bytes
+    pub(crate) fn new(max_body_size: usize) -> Self {
+        Self {
+            recent: Default::default(),
+            cursor: AtomicUsize::new(0),
+            max: max_body_size
+                .saturating_add(HEAD_ALLOWANCE)
+                .max(MIN_READ_BUFFER),
+        }
+    }
+
+    /// Remember the body size of an event just read
+    pub(crate) fn record(&self, body_len: usize) {
+        let slot = self.cursor.fetch_add(1, Ordering::Relaxed) % HISTORY;
+        self.recent[slot].store(body_len, Ordering::Relaxed);
+    }
+
+    /// Buffer capacity that fits the largest recent event
+    pub(crate) fn capacity(&self) -> usize {
+        let largest = self
+            .recent
+            .iter()
+            .map(|size| size.load(Ordering::Relaxed))
+            .max()
+            .unwrap_or(0);
+        largest
+            .saturating_add(HEAD_ALLOWANCE)
+            .clamp(MIN_READ_BUFFER, self.max)
+    }
+
+    /// Resize a reused, empty buffer to [`ReadBufferSizer::capacity`]
+    ///
+    /// Grows it up front, and shrinks it once it is more than twice the
+    /// suggested size.
+    pub(crate) fn fit(&self, buffer: &mut Vec<u8>) {
+        let capacity = self.capacity();
+        buffer.clear();
+        if buffer.capacity() > capacity * 2 {
+            buffer.shrink_to(capacity);
+        }
+        buffer.reserve(capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_without_history_is_minimum() {
+        let sizer = ReadBufferSizer::new(6 * 1024 * 1024);
+        assert_eq!(sizer.capacity(), MIN_READ_BUFFER);
+    }
+
+    #[test]
+    fn test_capacity_fits_largest_recent_event() {
+        let sizer = ReadBufferSizer::new(6 * 1024 * 1024);
+        sizer.record(100);
+        sizer.record(1024 * 1024);
+        sizer.record(200);
+        assert_eq!(sizer.capacity(), 1024 * 1024 + HEAD_ALLOWANCE);
+    }
+
+    #[test]
+    fn test_large_event_ages_out_of_history() {
+        let sizer = ReadBufferSizer::new(6 * 1024 * 1024);
+        sizer.record(1024 * 1024);
+        for _ in 0..HISTORY {
+            sizer.record(512);
+        }
+        assert_eq!(sizer.capacity(), MIN_READ_BUFFER);
+    }
+
+    #[test]
+    fn test_capacity_bounded_by_size_limit() {
+        let sizer = ReadBufferSizer::new(64 * 1024);
+        sizer.record(10 * 1024 * 1024);
+        assert_eq!(sizer.capacity(), 64 * 1024 + HEAD_ALLOWANCE);
+    }
+
+    #[test]
+    fn test_fit_grows_and_shrinks_buffer() {
+        let sizer = ReadBufferSizer::new(6 * 1024 * 1024);
+        let mut buffer = b"previous response".to_vec();
+        sizer.record(256 * 1024);
+        sizer.fit(&mut buffer);
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 256 * 1024 + HEAD_ALLOWANCE);
+
+        for _ in 0..HISTORY {
+            sizer.record(100);
+        }
+        sizer.fit(&mut buffer);
+        assert!(buffer.capacity() >= MIN_READ_BUFFER);
+        assert!(buffer.capacity() <= 2 * MIN_READ_BUFFER);
+    }
+}
@@ -0,0 +1,345 @@
+// Streaming Record Iteration
+//
+// SQS, SNS, S3, DynamoDB and Kinesis batches all arrive as
+//
+//   {"Records": [{...}, {...}, ...]}
+//
+// Deserializing the whole batch allocates every record up front even when
+// the handler stops after the first failure. RecordIter scans the body in
+// place and yields each record as a borrowed JSON slice, so a handler can
+// parse (or skip) records one at a time with serde_json::from_str.
+//
+// The scanner validates structure (nesting, strings, separators), not
+// values; a malformed record surfaces when the caller parses its slice.
+
+use std::fmt;
+
+/// Top-level key of SQS, SNS, S3, `DynamoDB` and Kinesis batches
+pub const RECORDS_KEY: &str = "Records";
+
+/// Structural error in a batch body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordIterError {
+    /// Byte offset into the body
+    pub offset: usize,
+    /// What was expected there
+    pub message: &'static str,
+}
+
+impl fmt::Display for RecordIterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for RecordIterError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    First,
+    Next,
+    Done,
+}
+
+/// Pull-based iterator over the records of a batch event
+///
+/// Yields the raw JSON of each record, borrowed from the body. A missing
+/// records key yields nothing; a structural error is yielded once and ends
+/// the iteration.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::RecordIter;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct SqsMessage<'a> {
+///     #[serde(rename = "messageId")]
+///     message_id: &'a str,
+/// }
+///
+/// let body = r#"{"Records":[{"messageId":"m1","body":"a"},{"messageId":"m2","body":"b"}]}"#;
+///
+/// let ids: Vec<&str> = RecordIter::new(body)
+///     .map(|record| serde_json::from_str::<SqsMessage>(record.unwrap()).unwrap().message_id)
+///     .collect();
+/// assert_eq!(ids, ["m1", "m2"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordIter<'a> {
+    body: &'a str,
+    key: &'a str,
+    pos: usize,
+    state: State,
+}
+
+impl<'a> RecordIter<'a> {
+    /// Iterate the `Records` array of `body`
+    #[must_use]
+    pub fn new(body: &'a str) -> Self {
+        Self::with_key(body, RECORDS_KEY)
+    }
+
+    /// Iterate the array under top-level `key` (e.g. `records` for Firehose)
+    #[must_use]
+    pub fn with_key(body: &'a str, key: &'a str) -> Self {
+        Self {
+            body,
+            key,
+            pos: 0,
+            state: State::Start,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.body.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&mut self, message: &'static str) -> RecordIterError {
+        self.state = State::Done;
+        RecordIterError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), RecordIterError> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    /// Skip a string starting at the opening quote; returns its raw contents
+    fn skip_string(&mut self) -> Result<&'a str, RecordIterError> {
+        let bytes = self.body.as_bytes();
+        let start = self.pos + 1;
+        let mut i = start;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'"' => {
+                    self.pos = i + 1;
+                    return Ok(&self.body[start..i]);
+                }
+                _ => i += 1,
+            }
+        }
+        self.pos = bytes.len();
+        Err(self.error("unterminated string"))
+    }
+
+    fn skip_value(&mut self) -> Result<(), RecordIterError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'"') => self.skip_string().map(|_| ()),
+            Some(b'{' | b'[') => {
+                let mut depth = 0usize;
+                while let Some(byte) = self.peek() {
+                    match byte {
+                        b'"' => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return Ok(());
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+                Err(self.error("unterminated object or array"))
+            }
+            Some(_) => {
+                let start = self.pos;
+                while !matches!(
+                    self.peek(),
+                    None | Some(b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    Err(self.error("expected a value"))
+                } else {
+                    Ok(())
+                }
+            }
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Walk the top-level object up to the opening bracket of the array
+    fn find_array(&mut self) -> Result<bool, RecordIterError> {
+        self.expect(b'{', "expected '{'")?;
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            return Ok(false);
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected an object key"));
+            }
+            let name = self.skip_string()?;
+            self.expect(b':', "expected ':'")?;
+            if name == self.key {
+                self.expect(b'[', "expected an array")?;
+                return Ok(true);
+            }
+            self.skip_value()?;
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => return Ok(false),
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn next_record(&mut self) -> Result<Option<&'a str>, RecordIterError> {
+        if self.state == State::Start {
+            if !self.find_array()? {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            self.state = State::First;
+        }
+
+        self.skip_whitespace();
+        match (self.state, self.peek()) {
+            (_, Some(b']')) => {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            (State::First, _) => {}
+            (_, Some(b',')) => {
+                self.pos += 1;
+                self.skip_whitespace();
+            }
+            _ => return Err(self.error("expected ',' or ']'")),
+        }
+
+        let start = self.pos;
+        self.skip_value()?;
+        self.state = State::Next;
+        Ok(Some(&self.body[start..self.pos]))
+    }
+}
+
+impl<'a> Iterator for RecordIter<'a> {
+    type Item = Result<&'a str, RecordIterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None;
+        }
+        self.next_record().transpose()
+    }
+}
+
+impl std::iter::FusedIterator for RecordIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(body: &str) -> Vec<Result<&str, RecordIterError>> {
+        RecordIter::new(body).collect()
+    }
+
+    #[test]
+    fn test_yields_borrowed_slices() {
+        let body = r#"{"Records": [ {"a":1} , {"b":[2,{"c":"]}"}]}, "s", 3, null ]}"#;
+        let records: Vec<&str> = RecordIter::new(body).map(Result::unwrap).collect();
+
+        assert_eq!(
+            records,
+            [
+                r#"{"a":1}"#,
+                r#"{"b":[2,{"c":"]}"}]}"#,
+                r#""s""#,
+                "3",
+                "null"
+            ]
+        );
+        assert!(body
+            .as_bytes()
+            .as_ptr_range()
+            .contains(&records[0].as_ptr()));
+    }
+
+    #[test]
+    fn test_skips_other_keys() {
+        let body = r#"{"meta":{"x":"{\"Records\":[]"},"list":[1,[2]],"n":-1.5e3,"Records":[{"id":1}],"tail":true}"#;
+        let records: Vec<&str> = RecordIter::new(body).map(Result::unwrap).collect();
+        assert_eq!(records, [r#"{"id":1}"#]);
+    }
+
+    #[test]
+    fn test_custom_key() {
+        let body = r#"{"invocationId":"i","records":[{"recordId":"1"}]}"#;
+        assert_eq!(RecordIter::new(body).count(), 0);
+        assert_eq!(RecordIter::with_key(body, "records").count(), 1);
+    }
+
+    #[test]
+    fn test_empty_and_missing() {
+        assert!(collect(r#"{"Records":[]}"#).is_empty());
+        assert!(collect(r#"{"Records":[ ]}"#).is_empty());
+        assert!(collect("{}").is_empty());
+        assert!(collect(r#"{"other":1}"#).is_empty());
+    }
+
+    #[test]
+    fn test_escaped_quotes_in_strings() {
+        let body = r#"{"Records":[{"body":"say \"hi\" \\"},{"body":"}"}]}"#;
+        assert_eq!(collect(body).len(), 2);
+    }
+
+    #[test]
+    fn test_structural_errors_end_iteration() {
+        let cases = [
+            ("[]", "expected '{'"),
+            (r#"{"Records":{}}"#, "expected an array"),
+            (r#"{"Records":[{"a":1}"#, "expected ',' or ']'"),
+            (r#"{"Records":[{"a":1} {"b":2}]}"#, "expected ',' or ']'"),
+            (r#"{"Records":[{"a":"x"#, "unterminated string"),
+            (r#"{"Records":[{"a":[1,2}"#, "unterminated object or array"),
+            (r#"{"Records":[1,]}"#, "expected a value"),
+            ("{Records:[]}", "expected an object key"),
+        ];
+        for (body, message) in cases {
+            let results = collect(body);
+            let error = results
+                .iter()
+                .find_map(|result| result.as_ref().err())
+                .unwrap_or_else(|| panic!("no error for {body}"));
+            assert_eq!(error.message, message, "{body}");
+            assert!(results.last().unwrap().is_err(), "{body}");
+        }
+    }
+
+    #[test]
+    fn test_early_exit_does_not_scan_rest() {
+        // The second record is malformed, but it is never reached
+        let body = r#"{"Records":[{"id":1},{"id":"#;
+        let mut iter = RecordIter::new(body);
+        assert_eq!(iter.next().unwrap().unwrap(), r#"{"id":1}"#);
+    }
+}
@@ -0,0 +1,311 @@
+// Payload Redaction
+//
+// Event and response payloads routinely carry PII and credentials. Before
+// the runtime logs a payload excerpt (Logger::log_payload, FailureRecord)
+// it passes it through a Redactor:
+//
+// 1. JSON payloads: values of deny-listed keys are replaced with
+//    "[REDACTED]" at any depth (key match is case-insensitive substring,
+//    with '-' treated as '_', so "X-Api-Key" matches "api_key"). Without
+//    feature "serde" a JSON payload mentioning a deny-listed field is
+//    replaced by "[REDACTED]" as a whole
+// 2. An optional user callback then rewrites the text (JSON or not)
+//
+// Extra deny-listed fields can be added with RUCHY_LAMBDA_REDACT_FIELDS
+// (comma-separated).
+
+use std::env;
+use std::fmt;
+use std::sync::Arc;
+
+/// Environment variable with extra comma-separated field names to redact
+pub const REDACT_FIELDS_ENV: &str = "RUCHY_LAMBDA_REDACT_FIELDS";
+
+/// Default maximum size of a logged payload excerpt (4KB)
+pub const DEFAULT_PAYLOAD_EXCERPT_BYTES: usize = 4 * 1024;
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Field-name fragments redacted by default
+const DEFAULT_DENY_LIST: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "authorization",
+    "cookie",
+    "api_key",
+    "apikey",
+    "access_key",
+    "private_key",
+    "ssn",
+    "credit_card",
+    "card_number",
+    "cvv",
+];
+
+/// User-supplied rewrite applied after field redaction
+type RedactCallback = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Redacts sensitive values from payloads before they are logged
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Redactor;
+///
+/// let redactor = Redactor::default().with_field("email");
+/// let redacted = redactor.redact(r#"{"user":{"email":"a@b.c","password":"hunter2"},"id":7}"#);
+/// assert_eq!(redacted, r#"{"id":7,"user":{"email":"[REDACTED]","password":"[REDACTED]"}}"#);
+///
+/// let masked = Redactor::empty().with_callback(|text| text.replace("4111", "****"));
+/// assert_eq!(masked.redact("card 4111"), "card ****");
+/// ```
+#[derive(Clone)]
+pub struct Redactor {
+    /// Lowercase field-name fragments ('-' normalized to '_')
+    fields: Vec<String>,
+    callback: Option<RedactCallback>,
+}
+
+impl Redactor {
+    /// Redactor with no deny-listed fields and no callback (pass-through)
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            fields: Vec::new(),
+            callback: None,
+        }
+    }
+
+    /// Default deny-list plus fields from `RUCHY_LAMBDA_REDACT_FIELDS`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let mut redactor = Self::default();
+        if let Ok(value) = env::var(REDACT_FIELDS_ENV) {
+            for field in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+                redactor = redactor.with_field(field);
+            }
+        }
+        redactor
+    }
+
+    /// Also redact fields whose name contains `field`
+    #[must_use]
+    pub fn with_field(mut self, field: &str) -> Self {
+        let field = normalize(field);
+        if !self.fields.contains(&field) {
+            self.fields.push(field);
+        }
+        self
+    }
+
+    /// Rewrite payload text after field redaction (e.g. mask card numbers)
+    #[must_use]
+    pub fn with_callback(
+        mut self,
+        callback: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Whether values under `key` are redacted
+    #[must_use]
+    pub fn is_denied(&self, key: &str) -> bool {
+        let key = normalize(key);
+        self.fields.iter().any(|field| key.contains(field.as_str()))
+    }
+
+    /// Redact a payload
+    ///
+    /// Non-JSON payloads only go through the callback. Without feature
+    /// "serde", JSON payloads mentioning a deny-listed field are replaced
+    /// as a whole.
+    #[must_use]
+    pub fn redact(&self, payload: &str) -> String {
+        #[cfg(feature = "serde")]
+        let redacted = if self.fields.is_empty() {
+            None
+        } else {
+            serde_json::from_str::<serde_json::Value>(payload)
+                .ok()
+                .map(|mut value| {
+                    self.redact_value(&mut value);
+                    value.to_string()
+                })
+        };
+        #[cfg(not(feature = "serde"))]
+        let redacted = {
+            let looks_like_json =
+                matches!(payload.trim_start().as_bytes().first(), Some(b'{' | b'['));
+            let normalized = normalize(payload);
+            (looks_like_json
+                && self
+                    .fields
+                    .iter()
+                    .any(|field| normalized.contains(field.as_str())))
+            .then(|| REDACTED.to_string())
+        };
+        let text = redacted.as_deref().unwrap_or(payload);
+
+        match &self.callback {
+            Some(callback) => callback(text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Redact a payload, then cut it to at most `max_bytes` on a UTF-8 boundary
+    ///
+    /// Returns the excerpt and whether it was truncated.
+    #[must_use]
+    pub fn excerpt(&self, payload: &str, max_bytes: usize) -> (String, bool) {
+        let mut redacted = self.redact(payload);
+        if redacted.len() <= max_bytes {
+            return (redacted, false);
+        }
+
+        let mut end = max_bytes;
+        while !redacted.is_char_boundary(end) {
+            end -= 1;
+        }
+        redacted.truncate(end);
+        (redacted, true)
+    }
+
+    #[cfg(feature = "serde")]
+    fn redact_value(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_denied(key) {
+                        *value = REDACTED.into();
+                    } else {
+                        self.redact_value(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_value(item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for Redactor {
+    /// Built-in deny-list (passwords, secrets, tokens, keys, card data)
+    fn default() -> Self {
+        Self {
+            fields: DEFAULT_DENY_LIST
+                .iter()
+                .map(|field| (*field).to_string())
+                .collect(),
+            callback: None,
+        }
+    }
+}
+
+impl fmt::Debug for Redactor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Redactor")
+            .field("fields", &self.fields)
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+/// Lowercase and treat '-' as '_' for key matching
+fn normalize(key: &str) -> String {
+    key.to_ascii_lowercase().replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_default_deny_list_nested() {
+        let redacted = Redactor::default().redact(
+            r#"{"headers":{"Authorization":"Bearer abc","X-Api-Key":"k"},"items":[{"client_secret":"s","qty":2}],"name":"ok"}"#,
+        );
+        let value: serde_json::Value = serde_json::from_str(&redacted).unwrap();
+        assert_eq!(value["headers"]["Authorization"], REDACTED);
+        assert_eq!(value["headers"]["X-Api-Key"], REDACTED);
+        assert_eq!(value["items"][0]["client_secret"], REDACTED);
+        assert_eq!(value["items"][0]["qty"], 2);
+        assert_eq!(value["name"], "ok");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_redacts_whole_subtree() {
+        let redacted = Redactor::default().redact(r#"{"password":{"old":"a","new":"b"}}"#);
+        assert_eq!(redacted, r#"{"password":"[REDACTED]"}"#);
+    }
+
+    #[test]
+    fn test_non_json_passes_through() {
+        assert_eq!(
+            Redactor::default().redact("password=hunter2"),
+            "password=hunter2"
+        );
+    }
+
+    #[test]
+    fn test_empty_is_pass_through() {
+        let payload = r#"{"password": "x"}"#;
+        assert_eq!(Redactor::empty().redact(payload), payload);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_callback_runs_after_fields() {
+        let redactor = Redactor::default().with_callback(str::to_uppercase);
+        assert_eq!(
+            redactor.redact(r#"{"token":"t","user":"bob"}"#),
+            r#"{"TOKEN":"[REDACTED]","USER":"BOB"}"#
+        );
+        assert_eq!(redactor.redact("plain"), "PLAIN");
+    }
+
+    #[test]
+    #[cfg(not(feature = "serde"))]
+    fn test_json_mentioning_field_redacted_whole() {
+        let redactor = Redactor::default().with_callback(str::to_uppercase);
+        assert_eq!(
+            redactor.redact(r#"{"token":"t","user":"bob"}"#),
+            "[REDACTED]"
+        );
+        assert_eq!(redactor.redact(r#"{"user":"bob"}"#), r#"{"USER":"BOB"}"#);
+    }
+
+    #[test]
+    fn test_excerpt_truncates_on_char_boundary() {
+        let (excerpt, truncated) = Redactor::empty().excerpt("ééé", 3);
+        assert_eq!(excerpt, "é");
+        assert!(truncated);
+
+        let (excerpt, truncated) = Redactor::empty().excerpt("abc", 3);
+        assert_eq!(excerpt, "abc");
+        assert!(!truncated);
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env_adds_fields() {
+        env::set_var(REDACT_FIELDS_ENV, " email, Phone-Number ,");
+        let redactor = Redactor::from_env();
+        env::remove_var(REDACT_FIELDS_ENV);
+
+        assert!(redactor.is_denied("Email"));
+        assert!(redactor.is_denied("phone_number"));
+        assert!(redactor.is_denied("password"));
+        assert!(!redactor.is_denied("name"));
+    }
+}
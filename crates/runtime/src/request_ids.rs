@@ -0,0 +1,135 @@
+// Request ID Consistency Check
+//
+// Every response or error must be posted under the request ID that /next
+// returned for that event. Transpiled event loops have swapped IDs (posting
+// under the previous invocation's ID, or a literal from the handler), which
+// the Runtime API answers with a 400 or, worse, attributes the response to
+// the wrong invocation.
+//
+// The runtime remembers the IDs handed out by /next until they are posted
+// successfully, so a post that failed can be retried under the same ID.
+// Posting an ID it never handed out is logged as a warning and trips a debug
+// assertion. Runtimes that never called /next (tests driving post_* directly)
+// are not checked.
+//
+// IDs are stored as 64-bit hashes in a reused Vec so the check does not
+// allocate per invocation. More than one ID can be outstanding with
+// prefetch or worker threads.
+
+use crate::logger::{LogLevel, Logger};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Whether any ID has been handed out (checking starts then)
+    active: AtomicBool,
+    /// Hashes of IDs handed out by /next and not yet posted
+    outstanding: Mutex<Vec<u64>>,
+}
+
+/// IDs returned by /next, shared by clones of a runtime
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestIds {
+    inner: Arc<Inner>,
+}
+
+fn hash(request_id: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    request_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl RequestIds {
+    /// Record an ID returned by /next
+    pub(crate) fn issued(&self, request_id: &str) {
+        self.inner.active.store(true, Ordering::Relaxed);
+        self.inner
+            .outstanding
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(hash(request_id));
+    }
+
+    /// Check an ID about to be posted
+    ///
+    /// Returns `false` (after logging a warning) if `/next` never returned
+    /// `request_id` or it was already posted. The ID stays outstanding until
+    /// [`RequestIds::complete`], so a failed post can be retried.
+    pub(crate) fn check(&self, request_id: &str) -> bool {
+        if !self.inner.active.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let id_hash = hash(request_id);
+        let outstanding = self
+            .inner
+            .outstanding
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if outstanding.contains(&id_hash) {
+            return true;
+        }
+
+        let pending = outstanding.len() as u64;
+        drop(outstanding);
+        Logger::with_request_id(request_id).log_with_fields(
+            LogLevel::Warn,
+            "Posting for a request ID that /next did not return",
+            &[("outstanding_request_ids", pending)],
+        );
+        false
+    }
+
+    /// Retire an ID once it was posted
+    pub(crate) fn complete(&self, request_id: &str) {
+        if !self.inner.active.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let id_hash = hash(request_id);
+        let mut outstanding = self
+            .inner
+            .outstanding
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(index) = outstanding.iter().position(|&h| h == id_hash) {
+            outstanding.swap_remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inactive_until_first_issue() {
+        let ids = RequestIds::default();
+        assert!(ids.check("anything"));
+    }
+
+    #[test]
+    fn test_issue_and_complete() {
+        let ids = RequestIds::default();
+        ids.issued("a");
+        ids.issued("b");
+
+        assert!(ids.check("b"));
+        assert!(ids.check("b"), "still outstanding until posted");
+        ids.complete("b");
+        assert!(!ids.check("b"), "already posted");
+        assert!(!ids.check("c"), "never issued");
+        assert!(ids.check("a"));
+    }
+
+    #[test]
+    fn test_shared_between_clones() {
+        let ids = RequestIds::default();
+        let worker = ids.clone();
+        worker.issued("w");
+        assert!(ids.check("w"));
+    }
+}
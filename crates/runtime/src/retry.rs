@@ -0,0 +1,330 @@
+// Outbound Request Retries
+//
+// Shared retry policy for outbound calls (the minimal AWS clients retry
+// through it by default):
+// - Up to `max_attempts` attempts (the first one included)
+// - Exponential backoff with full jitter: a random delay in
+//   [0, min(max_delay, initial * 2^(n-1))], so clients throttled together do
+//   not retry in lockstep
+// - Retried HTTP statuses are configurable (429 and 5xx gateway errors by
+//   default); what counts as retryable otherwise is up to the caller
+// - Deadline aware: no retry is started that could not finish its delay
+//   before the policy's deadline (by default the invocation's, see
+//   deadline.rs), leaving the handler time to respond instead of being
+//   killed mid-retry
+//
+// Time is read and slept through the policy's clock (see clock.rs), so
+// tests can drive backoff and deadlines without waiting.
+//
+// Unlike the event loop's `Backoff`, which retries the Runtime API forever,
+// this bounds a single outbound call.
+
+use crate::clock::{Clock, SharedClock};
+use crate::context::Context;
+use crate::deadline::invocation_deadline;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// HTTP statuses retried by default (throttling and transient server errors)
+pub const DEFAULT_RETRY_STATUSES: [u16; 5] = [429, 500, 502, 503, 504];
+
+/// Retry settings for outbound calls
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::RetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::default()
+///     .with_max_attempts(5)
+///     .with_backoff(Duration::from_millis(1), Duration::from_millis(4));
+///
+/// let mut calls = 0;
+/// let result: Result<&str, u16> = policy.retry(
+///     |_attempt| {
+///         calls += 1;
+///         if calls < 3 { Err(503) } else { Ok("done") }
+///     },
+///     |&status| policy.retries_status(status),
+/// );
+/// assert_eq!(result, Ok("done"));
+/// assert_eq!(calls, 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one (1 = no retries)
+    pub max_attempts: u32,
+    /// Backoff cap before the first retry
+    pub initial: Duration,
+    /// Upper bound for any backoff cap
+    pub max_delay: Duration,
+    /// HTTP statuses worth retrying
+    pub retry_statuses: Vec<u16>,
+    /// No retry is started that would sleep past this time (`None`: the
+    /// current thread's invocation deadline, if any)
+    pub deadline: Option<SystemTime>,
+    /// Time source for deadline checks and backoff sleeps
+    pub clock: SharedClock,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+            retry_statuses: DEFAULT_RETRY_STATUSES.to_vec(),
+            deadline: None,
+            clock: SharedClock::default(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    #[must_use]
+    pub fn none() -> Self {
+        Self::default().with_max_attempts(1)
+    }
+
+    /// Set the number of attempts (at least 1)
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the first backoff cap and the upper bound of all caps
+    #[must_use]
+    pub fn with_backoff(mut self, initial: Duration, max_delay: Duration) -> Self {
+        self.initial = initial;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Retry exactly these HTTP statuses
+    #[must_use]
+    pub fn with_retry_statuses(mut self, statuses: &[u16]) -> Self {
+        self.retry_statuses = statuses.to_vec();
+        self
+    }
+
+    /// Stop retrying once a retry could not finish before `deadline`
+    #[must_use]
+    pub fn with_deadline(mut self, deadline: SystemTime) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Read time from and sleep on `clock` instead of the system clock
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = SharedClock::new(clock);
+        self
+    }
+
+    /// Stop retrying once a retry could not finish before the invocation's
+    /// deadline (ignored if the context has none)
+    #[must_use]
+    pub fn with_context(self, context: &Context) -> Self {
+        if context.deadline_ms == 0 {
+            self
+        } else {
+            self.with_deadline(context.deadline())
+        }
+    }
+
+    /// Whether `status` is worth retrying
+    #[must_use]
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    /// Backoff cap before retry `retry` (1-based): `initial * 2^(retry-1)`,
+    /// capped at `max_delay`
+    #[must_use]
+    pub fn max_backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        self.initial
+            .checked_mul(1 << exponent)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// Jittered delay before retry `retry`, or `None` if the retry should not
+    /// happen (attempts used up, or it could not start before the deadline)
+    #[must_use]
+    pub fn next_delay(&self, retry: u32) -> Option<Duration> {
+        if retry >= self.max_attempts {
+            return None;
+        }
+        let delay = jitter(self.max_backoff(retry));
+        match self.deadline.or_else(invocation_deadline) {
+            Some(deadline) if self.clock.now() + delay >= deadline => None,
+            _ => Some(delay),
+        }
+    }
+
+    /// Run `operation` (given the 1-based attempt number) until it succeeds,
+    /// fails with an error `retryable` rejects, or the policy gives up;
+    /// returns the last result
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the last attempt.
+    pub fn retry<T, E>(
+        &self,
+        mut operation: impl FnMut(u32) -> Result<T, E>,
+        retryable: impl Fn(&E) -> bool,
+    ) -> Result<T, E> {
+        let mut attempt = 1;
+        loop {
+            match operation(attempt) {
+                Err(error) if retryable(&error) => match self.next_delay(attempt) {
+                    Some(delay) => self.clock.sleep(delay),
+                    None => return Err(error),
+                },
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+}
+
+/// Uniformly random duration in `[0, max]`
+///
+/// `RandomState` is seeded randomly per process; the counter keeps values
+/// from the same hasher keys distinct.
+fn jitter(max: Duration) -> Duration {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    if nanos == 0 {
+        return Duration::ZERO;
+    }
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    Duration::from_nanos(hasher.finish() % (nanos.saturating_add(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn fast() -> RetryPolicy {
+        RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(2))
+    }
+
+    #[test]
+    fn test_max_backoff_exponential_and_capped() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(100));
+        let caps: Vec<_> = (1..=6).map(|n| policy.max_backoff(n).as_millis()).collect();
+        assert_eq!(caps, [10, 20, 40, 80, 100, 100]);
+        assert_eq!(policy.max_backoff(1000), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_jitter_within_cap() {
+        let policy = RetryPolicy::default().with_max_attempts(10);
+        for retry in 1..10 {
+            let delay = policy.next_delay(retry).unwrap();
+            assert!(delay <= policy.max_backoff(retry));
+        }
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let mut attempts = Vec::new();
+        let result: Result<(), &str> = fast().retry(
+            |attempt| {
+                attempts.push(attempt);
+                Err("unavailable")
+            },
+            |_| true,
+        );
+        assert_eq!(result, Err("unavailable"));
+        assert_eq!(attempts, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_non_retryable_error_returned_immediately() {
+        let mut calls = 0;
+        let result: Result<(), u16> = fast().retry(
+            |_| {
+                calls += 1;
+                Err(404)
+            },
+            |&status| fast().retries_status(status),
+        );
+        assert_eq!(result, Err(404));
+        assert_eq!(calls, 1);
+        assert!(RetryPolicy::none().next_delay(1).is_none());
+    }
+
+    #[test]
+    fn test_deadline_stops_retries() {
+        let far = fast().with_deadline(SystemTime::now() + Duration::from_mins(1));
+        assert!(far.next_delay(1).is_some());
+
+        let expired = fast().with_deadline(SystemTime::now());
+        let mut calls = 0;
+        let result: Result<(), ()> = expired.retry(
+            |_| {
+                calls += 1;
+                Err(())
+            },
+            |()| true,
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_backoff_sleeps_on_clock_until_deadline() {
+        let clock = ManualClock::at_millis(1_700_000_000_000);
+        let policy = RetryPolicy::default()
+            .with_max_attempts(u32::MAX)
+            .with_backoff(Duration::from_secs(1), Duration::from_secs(1))
+            .with_deadline(clock.now() + Duration::from_secs(3))
+            .with_clock(clock.clone());
+
+        // Jittered delays can be arbitrarily short, so only the deadline
+        // (never max_attempts) may end this
+        let mut calls = 0u32;
+        let result: Result<(), ()> = policy.retry(
+            |_| {
+                calls += 1;
+                Err(())
+            },
+            |()| true,
+        );
+        assert!(result.is_err());
+        // Delays are at most 1s, so a 3s deadline allows at least one retry
+        assert!(calls >= 2, "gave up after {calls} call(s)");
+        assert!(clock.slept() <= Duration::from_secs(3));
+        assert!(clock.now() < policy.deadline.unwrap());
+    }
+
+    #[test]
+    fn test_with_context_deadline() {
+        let context = Context {
+            deadline_ms: 1_700_000_000_000,
+            ..Context::default()
+        };
+        assert_eq!(
+            fast().with_context(&context).deadline,
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert_eq!(fast().with_context(&Context::default()).deadline, None);
+    }
+}
@@ -0,0 +1,663 @@
+// Event Routing
+//
+// One deployed function often serves several routes or event sources. A
+// Router picks the handler from the event itself:
+//
+//   "POST /orders"           API Gateway (REST / HTTP API) or ALB request
+//   "GET /orders/{id}"       `{name}` matches one path segment,
+//   "ANY /files/{path+}"     `{name+}` the rest of the path; ANY / * any method
+//   "sqs:my-queue"           SQS records from queue `my-queue`
+//   "sns:alerts"             SNS records from topic `alerts`
+//   "s3:uploads"             S3 notifications for bucket `uploads`
+//   "dynamodb:orders"        DynamoDB stream records of table `orders`
+//   "kinesis:clicks"         Kinesis records from stream `clicks`
+//   "events:com.shop.orders" EventBridge events with that `source`
+//
+// A source name of "*" matches every event of that source. Routes are tried
+// in registration order; batches (Records) are routed by their first record.
+//
+// Handlers added with `route_with_params` also receive the path segments
+// captured by `{name}` / `{name+}` as RouteParams, with typed extraction:
+//
+//   "GET /users/{id}/orders/{order_id}"  + "/users/7/orders/a1"
+//     -> params.param::<u64>("id") == Ok(7), params.get("order_id") == Some("a1")
+//
+// Captured values are the path as delivered: v2 rawPath is still
+// percent-encoded, v1 / ALB paths are already decoded.
+
+use crate::context::Context;
+use crate::middleware::json_response;
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// A routed handler
+type Handler = Box<dyn Fn(&Context, &str, &RouteParams) -> String + Send + Sync>;
+
+/// Event sources that can be routed by name
+const SOURCES: &[&str] = &["sqs", "sns", "s3", "dynamodb", "kinesis", "events"];
+
+/// Dispatches events to handlers by HTTP method/path or event source
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, Router};
+///
+/// let router = Router::new()
+///     .route("POST /orders", |_, _| r#"{"statusCode":201}"#.to_string())
+///     .route("sqs:my-queue", |_, _| "queued".to_string());
+///
+/// let http = r#"{"httpMethod":"POST","path":"/orders","body":"{}"}"#;
+/// assert_eq!(router.handle(&Context::default(), http), r#"{"statusCode":201}"#);
+///
+/// let sqs = r#"{"Records":[{"eventSource":"aws:sqs","eventSourceARN":"arn:aws:sqs:us-east-1:123456789012:my-queue","body":"hi"}]}"#;
+/// assert_eq!(router.handle(&Context::default(), sqs), "queued");
+/// ```
+#[derive(Default)]
+pub struct Router {
+    routes: Vec<(Route, Handler)>,
+    fallback: Option<Handler>,
+}
+
+impl Router {
+    /// Router without routes (every event goes to the fallback)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a route (see the module docs for the pattern syntax)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is neither `"METHOD /path"` nor `"source:name"`
+    /// with a known source, since that is a programming error.
+    #[must_use]
+    pub fn route(
+        self,
+        pattern: &str,
+        handler: impl Fn(&Context, &str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.route_with_params(pattern, move |context, event_body, _| {
+            handler(context, event_body)
+        })
+    }
+
+    /// Add a route whose handler receives the captured path parameters
+    ///
+    /// # Panics
+    ///
+    /// Panics on an invalid pattern, like [`route`](Self::route).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ruchy_lambda_runtime::{Context, Router};
+    ///
+    /// let router = Router::new().route_with_params(
+    ///     "GET /users/{id}/orders/{order_id}",
+    ///     |_, _, params| match params.param::<u64>("id") {
+    ///         Ok(id) => format!("user {id} order {}", params.get("order_id").unwrap()),
+    ///         Err(err) => err.to_string(),
+    ///     },
+    /// );
+    ///
+    /// let event = r#"{"httpMethod":"GET","path":"/users/7/orders/a1"}"#;
+    /// assert_eq!(router.handle(&Context::default(), event), "user 7 order a1");
+    ///
+    /// let event = r#"{"httpMethod":"GET","path":"/users/me/orders/a1"}"#;
+    /// assert_eq!(router.handle(&Context::default(), event), "Invalid path parameter id: \"me\"");
+    /// ```
+    #[must_use]
+    pub fn route_with_params(
+        mut self,
+        pattern: &str,
+        handler: impl Fn(&Context, &str, &RouteParams) -> String + Send + Sync + 'static,
+    ) -> Self {
+        let route =
+            Route::parse(pattern).unwrap_or_else(|| panic!("invalid route pattern {pattern:?}"));
+        self.routes.push((route, Box::new(handler)));
+        self
+    }
+
+    /// Handler for events no route matches
+    ///
+    /// Without one, unmatched events get a 404 proxy response.
+    #[must_use]
+    pub fn fallback(
+        mut self,
+        handler: impl Fn(&Context, &str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.fallback = Some(Box::new(move |context, event_body, _| {
+            handler(context, event_body)
+        }));
+        self
+    }
+
+    /// Number of routes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Whether no routes were added
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Whether some route (not the fallback) matches the event
+    #[must_use]
+    pub fn matches(&self, event_body: &str) -> bool {
+        let key = EventKey::from_event(event_body);
+        self.routes
+            .iter()
+            .any(|(route, _)| route.captures(&key).is_some())
+    }
+
+    /// Dispatch one event to the first matching route
+    #[must_use]
+    pub fn handle(&self, context: &Context, event_body: &str) -> String {
+        let key = EventKey::from_event(event_body);
+
+        for (route, handler) in &self.routes {
+            if let Some(params) = route.captures(&key) {
+                return handler(context, event_body, &params);
+            }
+        }
+        match &self.fallback {
+            Some(fallback) => fallback(context, event_body, &RouteParams::default()),
+            None => json_response(
+                404,
+                &serde_json::json!({"message": format!("No route for {key}")}),
+            ),
+        }
+    }
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|(route, _)| route)
+                    .collect::<Vec<_>>(),
+            )
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+/// A parsed route pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Route {
+    /// Uppercase method (`None` = any) and path segments
+    Http {
+        method: Option<String>,
+        segments: Vec<String>,
+    },
+    /// Event source and name (`None` = any)
+    Source {
+        source: &'static str,
+        name: Option<String>,
+    },
+}
+
+impl Route {
+    fn parse(pattern: &str) -> Option<Self> {
+        if let Some((method, path)) = pattern.trim().split_once(' ') {
+            let path = path.trim();
+            if !path.starts_with('/') {
+                return None;
+            }
+            let method = match method.to_ascii_uppercase().as_str() {
+                "ANY" | "*" => None,
+                method => Some(method.to_string()),
+            };
+            return Some(Self::Http {
+                method,
+                segments: segments(path).map(str::to_string).collect(),
+            });
+        }
+
+        let (source, name) = pattern.trim().split_once(':')?;
+        let source = SOURCES.iter().find(|known| **known == source)?;
+        Some(Self::Source {
+            source,
+            name: (name != "*").then(|| name.to_string()),
+        })
+    }
+
+    /// Captured path parameters if the route matches `key`
+    fn captures(&self, key: &EventKey) -> Option<RouteParams> {
+        match (self, key) {
+            (Self::Http { method, segments }, EventKey::Http { method: m, path }) => {
+                if method.as_ref().is_some_and(|method| method != m) {
+                    return None;
+                }
+                capture(segments, path)
+            }
+            (Self::Source { source, name }, EventKey::Source { source: s, name: n }) => {
+                (source == s && name.as_ref().is_none_or(|name| name == n))
+                    .then(RouteParams::default)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Non-empty `/`-separated path segments
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// Match `path` against template segments, capturing `{name}` / `{name+}`
+fn capture(pattern: &[String], path: &str) -> Option<RouteParams> {
+    let mut params = RouteParams::default();
+    let mut rest = path.trim_start_matches('/');
+    for expected in pattern {
+        let name = expected
+            .strip_prefix('{')
+            .and_then(|name| name.strip_suffix('}'));
+
+        if let Some(greedy) = name.and_then(|name| name.strip_suffix('+')) {
+            let value = rest.trim_end_matches('/');
+            if value.is_empty() {
+                return None;
+            }
+            params.0.push((greedy.to_string(), value.to_string()));
+            return Some(params);
+        }
+
+        let (segment, tail) = rest.split_once('/').unwrap_or((rest, ""));
+        if segment.is_empty() {
+            return None;
+        }
+        match name {
+            Some(name) => params.0.push((name.to_string(), segment.to_string())),
+            None if expected != segment => return None,
+            None => {}
+        }
+        rest = tail.trim_start_matches('/');
+    }
+    rest.is_empty().then_some(params)
+}
+
+/// Path parameters captured by a route template
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::RouteParams;
+///
+/// let params = RouteParams::extract("/files/{bucket}/{key+}", "/files/logs/2024/01/app.log").unwrap();
+/// assert_eq!(params.get("bucket"), Some("logs"));
+/// assert_eq!(params.get("key"), Some("2024/01/app.log"));
+/// assert!(RouteParams::extract("/files/{bucket}", "/other/logs").is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteParams(Vec<(String, String)>);
+
+impl RouteParams {
+    /// Match `path` against a `/a/{b}/{c+}` template outside a [`Router`]
+    ///
+    /// Returns `None` when the path does not match the template.
+    #[must_use]
+    pub fn extract(template: &str, path: &str) -> Option<Self> {
+        let pattern: Vec<String> = segments(template).map(str::to_string).collect();
+        capture(&pattern, path)
+    }
+
+    /// Raw value of parameter `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Value of parameter `name` parsed as `T`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParamError::Missing`] if the route has no such parameter
+    /// and [`ParamError::Invalid`] if the value does not parse as `T`.
+    pub fn param<T: FromStr>(&self, name: &str) -> Result<T, ParamError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| ParamError::Missing(name.to_string()))?;
+        value.parse().map_err(|_| ParamError::Invalid {
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// `(name, value)` pairs in template order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Number of captured parameters
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether nothing was captured
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Error extracting a typed path parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// The route template has no parameter of this name
+    Missing(String),
+    /// The captured value does not parse as the requested type
+    Invalid {
+        /// Parameter name
+        name: String,
+        /// Captured value
+        value: String,
+    },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(name) => write!(f, "Missing path parameter {name}"),
+            Self::Invalid { name, value } => {
+                write!(f, "Invalid path parameter {name}: {value:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// What an event is routed by
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EventKey {
+    Http { method: String, path: String },
+    Source { source: &'static str, name: String },
+    Unknown,
+}
+
+impl EventKey {
+    fn from_event(event_body: &str) -> Self {
+        let Ok(event) = serde_json::from_str::<Value>(event_body) else {
+            return Self::Unknown;
+        };
+        Self::http(&event)
+            .or_else(|| Self::record(&event))
+            .or_else(|| Self::eventbridge(&event))
+            .unwrap_or(Self::Unknown)
+    }
+
+    /// REST API / ALB (`httpMethod`, `path`) or HTTP API v2
+    /// (`requestContext.http.method`, `rawPath`)
+    fn http(event: &Value) -> Option<Self> {
+        let method = event["httpMethod"]
+            .as_str()
+            .or_else(|| event["requestContext"]["http"]["method"].as_str())?;
+        let path = event["rawPath"]
+            .as_str()
+            .or_else(|| event["path"].as_str())?;
+        Some(Self::Http {
+            method: method.to_ascii_uppercase(),
+            path: path.to_string(),
+        })
+    }
+
+    /// First record of an SQS, SNS, S3, `DynamoDB` or Kinesis batch
+    fn record(event: &Value) -> Option<Self> {
+        let record = event["Records"].get(0)?;
+        let arn = record["eventSourceARN"].as_str().unwrap_or_default();
+
+        let (source, name) = if let Some(topic) = record["Sns"]["TopicArn"].as_str() {
+            ("sns", topic.rsplit(':').next()?)
+        } else {
+            match record["eventSource"].as_str()? {
+                "aws:sqs" => ("sqs", arn.rsplit(':').next()?),
+                "aws:s3" => ("s3", record["s3"]["bucket"]["name"].as_str()?),
+                "aws:dynamodb" => ("dynamodb", arn.split_once(":table/")?.1.split('/').next()?),
+                "aws:kinesis" => ("kinesis", arn.split_once(":stream/")?.1),
+                _ => return None,
+            }
+        };
+        Some(Self::Source {
+            source,
+            name: name.to_string(),
+        })
+    }
+
+    /// `EventBridge` event (`source` plus `detail-type`)
+    fn eventbridge(event: &Value) -> Option<Self> {
+        event.get("detail-type")?;
+        Some(Self::Source {
+            source: "events",
+            name: event["source"].as_str()?.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for EventKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http { method, path } => write!(f, "{method} {path}"),
+            Self::Source { source, name } => write!(f, "{source}:{name}"),
+            Self::Unknown => f.write_str("event"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(label: &'static str) -> impl Fn(&Context, &str) -> String {
+        move |_, _| label.to_string()
+    }
+
+    fn dispatch(router: &Router, event: &str) -> String {
+        router.handle(&Context::default(), event)
+    }
+
+    #[test]
+    fn test_http_routes() {
+        let router = Router::new()
+            .route("GET /orders", name("list"))
+            .route("POST /orders", name("create"))
+            .route("GET /orders/{id}", name("show"))
+            .route("ANY /files/{path+}", name("files"));
+
+        // REST API v1 / ALB
+        assert_eq!(
+            dispatch(&router, r#"{"httpMethod":"GET","path":"/orders/"}"#),
+            "list"
+        );
+        assert_eq!(
+            dispatch(&router, r#"{"httpMethod":"post","path":"/orders"}"#),
+            "create"
+        );
+        // HTTP API v2
+        assert_eq!(
+            dispatch(
+                &router,
+                r#"{"rawPath":"/orders/42","requestContext":{"http":{"method":"GET"}}}"#
+            ),
+            "show"
+        );
+        assert_eq!(
+            dispatch(&router, r#"{"httpMethod":"PUT","path":"/files/a/b.txt"}"#),
+            "files"
+        );
+        assert!(!router.matches(r#"{"httpMethod":"PUT","path":"/files"}"#));
+        assert!(!router.matches(r#"{"httpMethod":"GET","path":"/orders/42/items"}"#));
+    }
+
+    #[test]
+    fn test_source_routes() {
+        let router = Router::new()
+            .route("sqs:my-queue", name("sqs"))
+            .route("sns:*", name("sns"))
+            .route("s3:uploads", name("s3"))
+            .route("dynamodb:orders", name("ddb"))
+            .route("kinesis:clicks", name("kinesis"))
+            .route("events:com.shop.orders", name("events"));
+
+        let cases = [
+            (
+                r#"{"Records":[{"eventSource":"aws:sqs","eventSourceARN":"arn:aws:sqs:us-east-1:123456789012:my-queue"}]}"#,
+                "sqs",
+            ),
+            (
+                r#"{"Records":[{"EventSource":"aws:sns","Sns":{"TopicArn":"arn:aws:sns:us-east-1:123456789012:alerts"}}]}"#,
+                "sns",
+            ),
+            (
+                r#"{"Records":[{"eventSource":"aws:s3","s3":{"bucket":{"name":"uploads"}}}]}"#,
+                "s3",
+            ),
+            (
+                r#"{"Records":[{"eventSource":"aws:dynamodb","eventSourceARN":"arn:aws:dynamodb:us-east-1:123456789012:table/orders/stream/2024-01-01T00:00:00.000"}]}"#,
+                "ddb",
+            ),
+            (
+                r#"{"Records":[{"eventSource":"aws:kinesis","eventSourceARN":"arn:aws:kinesis:us-east-1:123456789012:stream/clicks"}]}"#,
+                "kinesis",
+            ),
+            (
+                r#"{"source":"com.shop.orders","detail-type":"OrderPlaced","detail":{}}"#,
+                "events",
+            ),
+        ];
+        for (event, expected) in cases {
+            assert_eq!(dispatch(&router, event), expected, "{event}");
+        }
+
+        assert!(!router.matches(
+            r#"{"Records":[{"eventSource":"aws:sqs","eventSourceARN":"arn:aws:sqs:us-east-1:123456789012:other"}]}"#
+        ));
+    }
+
+    #[test]
+    fn test_params_captured_per_route() {
+        let router = Router::new()
+            .route_with_params("GET /users/{id}/orders/{order_id}", |_, _, params| {
+                let id: u64 = params.param("id").unwrap();
+                format!("{id}:{}", params.get("order_id").unwrap())
+            })
+            .route_with_params("ANY /files/{path+}", |_, _, params| {
+                params.get("path").unwrap().to_string()
+            })
+            .route_with_params("sqs:*", |_, _, params| params.len().to_string());
+
+        assert_eq!(
+            dispatch(
+                &router,
+                r#"{"httpMethod":"GET","path":"/users/7/orders/a1/"}"#
+            ),
+            "7:a1"
+        );
+        assert_eq!(
+            dispatch(
+                &router,
+                r#"{"rawPath":"/files/a/b%20c.txt","requestContext":{"http":{"method":"PUT"}}}"#
+            ),
+            "a/b%20c.txt"
+        );
+        assert_eq!(
+            dispatch(&router, r#"{"Records":[{"eventSource":"aws:sqs"}]}"#),
+            "0"
+        );
+    }
+
+    #[test]
+    fn test_param_errors() {
+        let params = RouteParams::extract("/users/{id}", "/users/me").unwrap();
+        assert_eq!(params.param::<String>("id").as_deref(), Ok("me"));
+        assert_eq!(
+            params.param::<u64>("id"),
+            Err(ParamError::Invalid {
+                name: "id".to_string(),
+                value: "me".to_string(),
+            })
+        );
+        assert_eq!(
+            params.param::<u64>("user_id").unwrap_err().to_string(),
+            "Missing path parameter user_id"
+        );
+        assert_eq!(params.iter().collect::<Vec<_>>(), [("id", "me")]);
+        assert!(RouteParams::extract("/users/{id}", "/users").is_none());
+        assert!(RouteParams::extract("/users", "/users").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_first_match_wins() {
+        let router = Router::new()
+            .route("GET /orders/new", name("new"))
+            .route("GET /orders/{id}", name("show"));
+        assert_eq!(
+            dispatch(&router, r#"{"httpMethod":"GET","path":"/orders/new"}"#),
+            "new"
+        );
+    }
+
+    #[test]
+    fn test_unmatched_without_fallback_is_404() {
+        let router = Router::new().route("GET /orders", name("list"));
+        let response = dispatch(&router, r#"{"httpMethod":"DELETE","path":"/orders"}"#);
+        let value: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["statusCode"], 404);
+        assert_eq!(
+            value["body"],
+            r#"{"message":"No route for DELETE /orders"}"#
+        );
+    }
+
+    #[test]
+    fn test_fallback() {
+        let router = Router::new()
+            .route("sqs:jobs", name("jobs"))
+            .fallback(|_, body| format!("fallback {body}"));
+        assert_eq!(dispatch(&router, "not json"), "fallback not json");
+        assert_eq!(router.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_patterns() {
+        assert_eq!(
+            Route::parse("* /a/{b}"),
+            Some(Route::Http {
+                method: None,
+                segments: vec!["a".to_string(), "{b}".to_string()],
+            })
+        );
+        assert_eq!(
+            Route::parse("sqs:*"),
+            Some(Route::Source {
+                source: "sqs",
+                name: None,
+            })
+        );
+        assert_eq!(Route::parse("GET orders"), None);
+        assert_eq!(Route::parse("ftp:server"), None);
+        assert_eq!(Route::parse("orders"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid route pattern")]
+    fn test_invalid_pattern_panics() {
+        let _ = Router::new().route("orders", name("x"));
+    }
+}
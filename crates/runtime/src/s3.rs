@@ -0,0 +1,371 @@
+// Minimal S3 Client (feature "s3")
+//
+// GetObject and PutObject over the signed outbound client (see
+// `aws_client`), without buffering whole objects:
+//
+//   - `get_object` / `get_object_range` return an `S3Object` that reads the
+//     body from the connection as the handler consumes it; ranged GETs send
+//     `Range: bytes=<first>-<last>` and get a 206
+//   - `put_object_stream` uploads `content_length` bytes from any reader,
+//     signed as UNSIGNED-PAYLOAD so the body is never hashed up front;
+//     `put_object` signs the SHA-256 of an in-memory body
+//
+// Objects are addressed virtual-hosted (`<bucket>.s3.<region>.amazonaws.com`)
+// like presigned URLs, or path-style when the client has an endpoint
+// (MinIO, LocalStack), since those serve every bucket from one address.
+
+use crate::aws_client::{AwsClient, AwsError, AwsStreamResponse};
+use crate::presign::object_location;
+use crate::sigv4::{AwsRequest, SigV4Error};
+use std::fmt;
+use std::io::{self, Read};
+use std::ops::{Bound, RangeBounds};
+
+/// `GetObject` and `PutObject` against S3
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{AwsClient, AwsResponse, Credentials, S3Client, Signer};
+/// use std::io::Read;
+///
+/// let signer = Signer::new(Credentials::new("AKID", "secret"), "us-east-1", "s3");
+/// let client = S3Client::new(AwsClient::new(signer).with_transport(|request| {
+///     assert_eq!(request.host, "reports.s3.us-east-1.amazonaws.com");
+///     assert_eq!(request.header("range"), Some("bytes=0-4"));
+///     Ok(AwsResponse::new(206, "hello").with_header("Content-Range", "bytes 0-4/11"))
+/// }));
+///
+/// let mut object = client.get_object_range("reports", "greeting.txt", 0..5).unwrap();
+/// assert_eq!(object.content_range(), Some("bytes 0-4/11"));
+/// let mut text = String::new();
+/// object.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "hello");
+/// ```
+#[derive(Debug)]
+pub struct S3Client {
+    client: AwsClient,
+}
+
+impl S3Client {
+    /// Client sending through `client` (signing for service `s3`)
+    #[must_use]
+    pub fn new(client: AwsClient) -> Self {
+        Self { client }
+    }
+
+    /// Client with the function's credentials and region
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigV4Error`] if the credentials or region are not set.
+    pub fn from_env() -> Result<Self, SigV4Error> {
+        Ok(Self::new(AwsClient::from_env("s3")?))
+    }
+
+    /// The whole object, body unread
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails (`NoSuchKey` for a missing key).
+    pub fn get_object(&self, bucket: &str, key: &str) -> Result<S3Object, AwsError> {
+        self.get(bucket, key, None)
+    }
+
+    /// Bytes `range` of the object (e.g. `0..1024`, `4096..`), body unread
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails; an empty range is rejected
+    /// like S3 does, with code `InvalidRange`.
+    pub fn get_object_range(
+        &self,
+        bucket: &str,
+        key: &str,
+        range: impl RangeBounds<u64>,
+    ) -> Result<S3Object, AwsError> {
+        let range = range_header(&range).ok_or_else(|| AwsError::Service {
+            status: 416,
+            code: "InvalidRange".to_string(),
+            message: "The requested range is empty".to_string(),
+        })?;
+        self.get(bucket, key, Some(&range))
+    }
+
+    /// Upload an in-memory body
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails.
+    pub fn put_object(
+        &self,
+        bucket: &str,
+        key: &str,
+        body: &[u8],
+        content_type: &str,
+    ) -> Result<PutObjectOutput, AwsError> {
+        let request = self
+            .request("PUT", bucket, key)
+            .with_header("content-type", content_type)
+            .with_body(body);
+        let response = self.client.send(request)?;
+        Ok(PutObjectOutput {
+            etag: response.header("etag").map(str::to_string),
+            version_id: response.header("x-amz-version-id").map(str::to_string),
+        })
+    }
+
+    /// Upload exactly `content_length` bytes read from `body`, unbuffered
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails or `body` ends early.
+    pub fn put_object_stream(
+        &self,
+        bucket: &str,
+        key: &str,
+        body: &mut dyn Read,
+        content_length: u64,
+        content_type: &str,
+    ) -> Result<PutObjectOutput, AwsError> {
+        let request = self
+            .request("PUT", bucket, key)
+            .with_header("content-type", content_type);
+        let mut response = self.client.send_streaming(request, body, content_length)?;
+        io::copy(&mut response, &mut io::sink()).map_err(|e| AwsError::Transport(e.to_string()))?;
+        Ok(PutObjectOutput {
+            etag: response.header("etag").map(str::to_string),
+            version_id: response.header("x-amz-version-id").map(str::to_string),
+        })
+    }
+
+    fn get(&self, bucket: &str, key: &str, range: Option<&str>) -> Result<S3Object, AwsError> {
+        let mut request = self.request("GET", bucket, key);
+        if let Some(range) = range {
+            request = request.with_header("range", range);
+        }
+        let response = self.client.send_streaming(request, &mut io::empty(), 0)?;
+        Ok(S3Object { response })
+    }
+
+    fn request(&self, method: &str, bucket: &str, key: &str) -> AwsRequest {
+        let region = self.client.signer().region();
+        let (host, path) = object_location(bucket, region, key);
+        if self.client.endpoint().is_some() {
+            // The endpoint replaces the host, so the bucket goes in the path
+            let key = key.trim_start_matches('/');
+            return AwsRequest::new(method, &host, &format!("/{bucket}/{key}"));
+        }
+        AwsRequest::new(method, &host, &path)
+    }
+}
+
+/// `bytes=<first>-<last>` of a byte range, `None` if it is empty
+fn range_header(range: &impl RangeBounds<u64>) -> Option<String> {
+    let first = match range.start_bound() {
+        Bound::Included(first) => *first,
+        Bound::Excluded(first) => first.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let last = match range.end_bound() {
+        Bound::Included(last) => Some(*last),
+        Bound::Excluded(end) => Some(end.checked_sub(1)?),
+        Bound::Unbounded => None,
+    };
+    match last {
+        Some(last) if last < first => None,
+        Some(last) => Some(format!("bytes={first}-{last}")),
+        None => Some(format!("bytes={first}-")),
+    }
+}
+
+/// An object being downloaded; reading yields its body
+pub struct S3Object {
+    response: AwsStreamResponse,
+}
+
+impl S3Object {
+    /// Body length in bytes (of the range, for ranged GETs)
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.header("content-length")
+            .and_then(|length| length.parse().ok())
+    }
+
+    /// Content type
+    #[must_use]
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    /// Entity tag
+    #[must_use]
+    pub fn etag(&self) -> Option<&str> {
+        self.header("etag")
+    }
+
+    /// `bytes <first>-<last>/<size>` of a ranged GET
+    #[must_use]
+    pub fn content_range(&self) -> Option<&str> {
+        self.header("content-range")
+    }
+
+    /// Any response header (e.g. `x-amz-meta-*` user metadata)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.response.header(name)
+    }
+}
+
+impl Read for S3Object {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.response.read(buf)
+    }
+}
+
+impl fmt::Debug for S3Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Object")
+            .field("status", &self.response.status)
+            .field("content_length", &self.content_length())
+            .field("etag", &self.etag())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Result of a `PutObject`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PutObjectOutput {
+    /// Entity tag of the stored object
+    pub etag: Option<String>,
+    /// Version ID, when the bucket is versioned
+    pub version_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws_client::AwsResponse;
+    use crate::sigv4::{Credentials, Signer, UNSIGNED_PAYLOAD};
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    fn signer() -> Signer {
+        Signer::new(Credentials::new("AKID", "secret"), "eu-west-1", "s3")
+    }
+
+    #[test]
+    fn test_range_header() {
+        assert_eq!(range_header(&(0..1024)).as_deref(), Some("bytes=0-1023"));
+        assert_eq!(range_header(&(10..=10)).as_deref(), Some("bytes=10-10"));
+        assert_eq!(range_header(&(4096..)).as_deref(), Some("bytes=4096-"));
+        assert_eq!(range_header(&(..2)).as_deref(), Some("bytes=0-1"));
+        assert_eq!(range_header(&(5..5)), None);
+        assert_eq!(range_header(&(..0)), None);
+    }
+
+    #[test]
+    fn test_put_object_signs_body_hash() {
+        let client = S3Client::new(AwsClient::new(signer()).with_transport(|request| {
+            assert_eq!(request.method, "PUT");
+            assert_eq!(request.path, "/dir/a b.json");
+            assert_eq!(
+                request.header("x-amz-content-sha256"),
+                Some("44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a")
+            );
+            Ok(AwsResponse::new(200, "").with_header("ETag", "\"abc\""))
+        }));
+        let output = client
+            .put_object("bucket", "dir/a b.json", b"{}", "application/json")
+            .unwrap();
+        assert_eq!(output.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(output.version_id, None);
+    }
+
+    #[test]
+    fn test_missing_key_and_empty_range() {
+        let client = S3Client::new(AwsClient::new(signer()).with_transport(|_| {
+            Ok(AwsResponse::new(
+                404,
+                "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>",
+            ))
+        }));
+        let err = client.get_object("bucket", "missing").unwrap_err();
+        assert_eq!(err.code(), Some("NoSuchKey"));
+
+        let err = client.get_object_range("bucket", "key", 3..3).unwrap_err();
+        assert_eq!(err.code(), Some("InvalidRange"));
+    }
+
+    /// Serve one request on a local endpoint, returning the raw request
+    fn serve(response: &'static str) -> (String, std::thread::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 8192];
+            loop {
+                let n = stream.read(&mut chunk).unwrap();
+                request.extend_from_slice(&chunk[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                let Some(head_end) = text.find("\r\n\r\n") else {
+                    continue;
+                };
+                let length: usize = text[..head_end]
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .map_or(0, |length| length.parse().unwrap());
+                if request.len() >= head_end + 4 + length {
+                    break;
+                }
+            }
+            let _ = stream.write_all(response.as_bytes());
+            request
+        });
+        (addr, server)
+    }
+
+    #[test]
+    fn test_streamed_put_over_endpoint() {
+        let (addr, server) = serve("HTTP/1.1 200 OK\r\nETag: \"e1\"\r\nContent-Length: 0\r\n\r\n");
+        let client = S3Client::new(AwsClient::new(signer()).with_endpoint(&addr));
+
+        let body = vec![7u8; 100_000];
+        let output = client
+            .put_object_stream(
+                "bucket",
+                "big.bin",
+                &mut body.as_slice(),
+                body.len() as u64,
+                "application/octet-stream",
+            )
+            .unwrap();
+        assert_eq!(output.etag.as_deref(), Some("\"e1\""));
+
+        let request = server.join().unwrap();
+        let text = String::from_utf8_lossy(&request);
+        assert!(text.starts_with("PUT /bucket/big.bin HTTP/1.1\r\n"));
+        assert!(text.contains(&format!("x-amz-content-sha256: {UNSIGNED_PAYLOAD}\r\n")));
+        assert!(text.contains("Content-Length: 100000\r\n"));
+        assert!(request.ends_with(&[7u8; 1000]));
+    }
+
+    #[test]
+    fn test_ranged_get_over_endpoint() {
+        let (addr, server) = serve(
+            "HTTP/1.1 206 Partial Content\r\nContent-Length: 4\r\nContent-Range: bytes 2-5/10\r\n\r\ncdef",
+        );
+        let client = S3Client::new(AwsClient::new(signer()).with_endpoint(&addr));
+
+        let mut object = client.get_object_range("bucket", "k", 2..6).unwrap();
+        assert_eq!(object.content_length(), Some(4));
+        let mut body = Vec::new();
+        object.read_to_end(&mut body).unwrap();
+        assert_eq!(body, b"cdef");
+
+        let request = String::from_utf8(server.join().unwrap()).unwrap();
+        assert!(request.starts_with("GET /bucket/k HTTP/1.1\r\n"));
+        assert!(request.contains("range: bytes=2-5\r\n"));
+    }
+}
@@ -0,0 +1,232 @@
+// S3 Batch Operations
+//
+// An "Invoke AWS Lambda function" batch job calls the function with one or
+// more tasks (one per manifest object) and requires a result per task:
+//
+//   {"invocationSchemaVersion": "1.0", "treatMissingKeysAs": "PermanentFailure",
+//    "invocationId": "...", "results": [{"taskId": "...", "resultCode":
+//    "Succeeded", "resultString": "..."}]}
+//
+// Schema 1.0 identifies the bucket by ARN, schema 2.0 by name and adds job
+// user arguments; both are accepted here.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// S3 Batch Operations invocation
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{S3BatchEvent, S3BatchResultCode};
+///
+/// let body = r#"{"invocationSchemaVersion":"1.0","invocationId":"inv-1","job":{"id":"job-1"},
+///     "tasks":[{"taskId":"t1","s3Key":"photos/a.jpg","s3VersionId":null,
+///               "s3BucketArn":"arn:aws:s3:::media"}]}"#;
+///
+/// let event = S3BatchEvent::from_json(body).unwrap();
+/// let response = event.respond(|task| {
+///     assert_eq!(task.bucket_name(), Some("media"));
+///     (S3BatchResultCode::Succeeded, format!("resized {}", task.s3_key))
+/// });
+/// assert!(response.to_json().contains(r#""resultCode":"Succeeded""#));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchEvent {
+    /// `1.0` or `2.0` (echoed in the response)
+    pub invocation_schema_version: String,
+    /// Invocation ID (echoed in the response)
+    pub invocation_id: String,
+    /// The batch job
+    #[serde(default)]
+    pub job: S3BatchJob,
+    /// Objects to process
+    #[serde(default)]
+    pub tasks: Vec<S3BatchTask>,
+}
+
+impl S3BatchEvent {
+    /// Parse a batch invocation
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not a batch invocation.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+
+    /// Empty response for this invocation (missing task results count as
+    /// permanent failures)
+    #[must_use]
+    pub fn response(&self) -> S3BatchResponse {
+        S3BatchResponse {
+            invocation_schema_version: self.invocation_schema_version.clone(),
+            treat_missing_keys_as: S3BatchResultCode::PermanentFailure,
+            invocation_id: self.invocation_id.clone(),
+            results: Vec::with_capacity(self.tasks.len()),
+        }
+    }
+
+    /// Run `process` for every task and collect the results
+    pub fn respond(
+        &self,
+        mut process: impl FnMut(&S3BatchTask) -> (S3BatchResultCode, String),
+    ) -> S3BatchResponse {
+        let mut response = self.response();
+        for task in &self.tasks {
+            let (code, result) = process(task);
+            response.push(task, code, result);
+        }
+        response
+    }
+}
+
+/// The batch job an invocation belongs to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchJob {
+    /// Job ID
+    #[serde(default)]
+    pub id: String,
+    /// Job user arguments (schema 2.0)
+    #[serde(default)]
+    pub user_arguments: Option<HashMap<String, String>>,
+}
+
+/// One object to process
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchTask {
+    /// Task ID (echoed in the result)
+    pub task_id: String,
+    /// Object key (URL-encoded)
+    pub s3_key: String,
+    /// Object version, if the manifest lists one
+    #[serde(default)]
+    pub s3_version_id: Option<String>,
+    /// Bucket ARN (schema 1.0)
+    #[serde(default)]
+    pub s3_bucket_arn: Option<String>,
+    /// Bucket name (schema 2.0)
+    #[serde(default)]
+    pub s3_bucket: Option<String>,
+}
+
+impl S3BatchTask {
+    /// Bucket name for either schema version
+    #[must_use]
+    pub fn bucket_name(&self) -> Option<&str> {
+        self.s3_bucket.as_deref().or_else(|| {
+            self.s3_bucket_arn
+                .as_deref()
+                .and_then(|arn| arn.strip_prefix("arn:aws:s3:::"))
+        })
+    }
+}
+
+/// Outcome of one task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum S3BatchResultCode {
+    /// Task done
+    Succeeded,
+    /// Retry the task (until the job's retry limit)
+    TemporaryFailure,
+    /// Do not retry the task
+    PermanentFailure,
+}
+
+/// Response to a batch invocation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchResponse {
+    /// Schema version of the invocation
+    pub invocation_schema_version: String,
+    /// Code for tasks without a result
+    pub treat_missing_keys_as: S3BatchResultCode,
+    /// Invocation ID
+    pub invocation_id: String,
+    /// Per-task results
+    pub results: Vec<S3BatchResult>,
+}
+
+impl S3BatchResponse {
+    /// Record the result of `task`
+    pub fn push(&mut self, task: &S3BatchTask, code: S3BatchResultCode, result: impl Into<String>) {
+        self.results.push(S3BatchResult {
+            task_id: task.task_id.clone(),
+            result_code: code,
+            result_string: result.into(),
+        });
+    }
+
+    /// JSON response document
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Result of one task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BatchResult {
+    /// Task ID from the invocation
+    pub task_id: String,
+    /// Outcome
+    pub result_code: S3BatchResultCode,
+    /// Free text shown in the job completion report
+    pub result_string: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_2_0() {
+        let event = S3BatchEvent::from_json(
+            r#"{"invocationSchemaVersion":"2.0","invocationId":"inv-2",
+                "job":{"id":"job-2","userArguments":{"size":"small"}},
+                "tasks":[{"taskId":"t1","s3Key":"a.txt","s3VersionId":"v1","s3Bucket":"docs"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.job.user_arguments.as_ref().unwrap()["size"], "small");
+        let task = &event.tasks[0];
+        assert_eq!(task.bucket_name(), Some("docs"));
+        assert_eq!(task.s3_version_id.as_deref(), Some("v1"));
+    }
+
+    #[test]
+    fn test_response_contract() {
+        let event = S3BatchEvent::from_json(
+            r#"{"invocationSchemaVersion":"1.0","invocationId":"inv-1","job":{"id":"j"},
+                "tasks":[{"taskId":"t1","s3Key":"a","s3BucketArn":"arn:aws:s3:::b"},
+                         {"taskId":"t2","s3Key":"b","s3BucketArn":"arn:aws:s3:::b"}]}"#,
+        )
+        .unwrap();
+
+        let response = event.respond(|task| {
+            if task.s3_key == "a" {
+                (S3BatchResultCode::Succeeded, "ok".to_string())
+            } else {
+                (S3BatchResultCode::TemporaryFailure, "throttled".to_string())
+            }
+        });
+        let value: serde_json::Value = serde_json::from_str(&response.to_json()).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "invocationSchemaVersion": "1.0",
+                "treatMissingKeysAs": "PermanentFailure",
+                "invocationId": "inv-1",
+                "results": [
+                    {"taskId": "t1", "resultCode": "Succeeded", "resultString": "ok"},
+                    {"taskId": "t2", "resultCode": "TemporaryFailure", "resultString": "throttled"}
+                ]
+            })
+        );
+    }
+}
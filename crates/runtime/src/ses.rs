@@ -0,0 +1,293 @@
+// SES Inbound Mail Events
+//
+// A receipt rule with a Lambda action delivers one record per received
+// message: the mail metadata (envelope, headers, common headers) and the
+// receipt (recipients, spam / virus / SPF / DKIM / DMARC verdicts). The
+// message body is not included; rules that need it store the message in S3
+// first.
+
+use serde::{Deserialize, Serialize};
+
+/// SES receipt-rule event
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::SesEvent;
+///
+/// let body = r#"{"Records":[{"eventSource":"aws:ses","eventVersion":"1.0","ses":{
+///     "mail":{"messageId":"m1","source":"alice@example.com","destination":["inbox@example.org"],
+///             "commonHeaders":{"from":["Alice <alice@example.com>"],"subject":"Hi"}},
+///     "receipt":{"recipients":["inbox@example.org"],"spamVerdict":{"status":"PASS"},
+///                "virusVerdict":{"status":"PASS"}}}}]}"#;
+///
+/// let event = SesEvent::from_json(body).unwrap();
+/// let record = &event.records[0].ses;
+/// assert_eq!(record.mail.common_headers.subject.as_deref(), Some("Hi"));
+/// assert!(record.receipt.passed_spam_and_virus_checks());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SesEvent {
+    /// One record per received message
+    #[serde(rename = "Records")]
+    pub records: Vec<SesRecord>,
+}
+
+impl SesEvent {
+    /// Parse an SES event
+    ///
+    /// # Errors
+    ///
+    /// Returns the `serde_json` error if the body is not an SES event.
+    pub fn from_json(event_body: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(event_body)
+    }
+}
+
+/// One received message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SesRecord {
+    /// Always `aws:ses`
+    #[serde(default)]
+    pub event_source: String,
+    /// Event format version
+    #[serde(default)]
+    pub event_version: String,
+    /// Message metadata and receipt
+    pub ses: SesMessage,
+}
+
+/// Message metadata and receipt of one record
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SesMessage {
+    /// Envelope and headers
+    pub mail: SesMail,
+    /// Receipt rule result
+    pub receipt: SesReceipt,
+}
+
+/// Envelope and headers of a received message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SesMail {
+    /// When the message was received (ISO 8601)
+    #[serde(default)]
+    pub timestamp: String,
+    /// Envelope `MAIL FROM` address
+    #[serde(default)]
+    pub source: String,
+    /// SES message ID
+    #[serde(default)]
+    pub message_id: String,
+    /// Envelope `RCPT TO` addresses
+    #[serde(default)]
+    pub destination: Vec<String>,
+    /// Whether `headers` was truncated
+    #[serde(default)]
+    pub headers_truncated: bool,
+    /// Raw headers in message order
+    #[serde(default)]
+    pub headers: Vec<SesHeader>,
+    /// Parsed common headers
+    #[serde(default)]
+    pub common_headers: SesCommonHeaders,
+}
+
+impl SesMail {
+    /// First raw header named `name` (case-insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+}
+
+/// One raw message header
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SesHeader {
+    /// Header name
+    pub name: String,
+    /// Header value
+    pub value: String,
+}
+
+/// Commonly used headers, parsed by SES
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SesCommonHeaders {
+    /// `Return-Path`
+    #[serde(default)]
+    pub return_path: Option<String>,
+    /// `From` addresses
+    #[serde(default)]
+    pub from: Vec<String>,
+    /// `Date`
+    #[serde(default)]
+    pub date: Option<String>,
+    /// `To` addresses
+    #[serde(default)]
+    pub to: Vec<String>,
+    /// `Cc` addresses
+    #[serde(default)]
+    pub cc: Vec<String>,
+    /// `Message-ID`
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// `Subject`
+    #[serde(default)]
+    pub subject: Option<String>,
+}
+
+/// Receipt rule result for a message
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SesReceipt {
+    /// When the rule ran (ISO 8601)
+    #[serde(default)]
+    pub timestamp: String,
+    /// Milliseconds SES spent processing the message
+    #[serde(default)]
+    pub processing_time_millis: u64,
+    /// Recipients matched by the rule
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    /// Spam scan result
+    #[serde(default)]
+    pub spam_verdict: SesVerdict,
+    /// Virus scan result
+    #[serde(default)]
+    pub virus_verdict: SesVerdict,
+    /// SPF check result
+    #[serde(default)]
+    pub spf_verdict: SesVerdict,
+    /// DKIM check result
+    #[serde(default)]
+    pub dkim_verdict: SesVerdict,
+    /// DMARC check result
+    #[serde(default)]
+    pub dmarc_verdict: SesVerdict,
+    /// DMARC policy of the sender domain (`none`, `quarantine`, `reject`)
+    #[serde(default)]
+    pub dmarc_policy: Option<String>,
+    /// The Lambda action that delivered this event
+    #[serde(default)]
+    pub action: SesAction,
+}
+
+impl SesReceipt {
+    /// Whether both the spam and virus scans passed
+    #[must_use]
+    pub fn passed_spam_and_virus_checks(&self) -> bool {
+        self.spam_verdict.passed() && self.virus_verdict.passed()
+    }
+}
+
+/// Result of one receipt check
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SesVerdict {
+    /// `PASS`, `FAIL`, `GRAY` or `PROCESSING_FAILED`
+    #[serde(default)]
+    pub status: String,
+}
+
+impl SesVerdict {
+    /// Whether the check passed
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.status == "PASS"
+    }
+}
+
+/// Receipt rule action
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SesAction {
+    /// Action type (`Lambda`)
+    #[serde(rename = "type", default)]
+    pub action_type: String,
+    /// Invoked function ARN
+    #[serde(default)]
+    pub function_arn: Option<String>,
+    /// `Event` or `RequestResponse`
+    #[serde(default)]
+    pub invocation_type: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVENT: &str = r#"{
+        "Records": [{
+            "eventSource": "aws:ses",
+            "eventVersion": "1.0",
+            "ses": {
+                "mail": {
+                    "timestamp": "2024-03-01T12:00:00.000Z",
+                    "source": "alice@example.com",
+                    "messageId": "o3vrnil0e2ic28trm7dfhrc2v0clambda4nbp0g1",
+                    "destination": ["inbox@example.org"],
+                    "headersTruncated": false,
+                    "headers": [
+                        {"name": "From", "value": "Alice <alice@example.com>"},
+                        {"name": "Subject", "value": "Invoice"}
+                    ],
+                    "commonHeaders": {
+                        "returnPath": "alice@example.com",
+                        "from": ["Alice <alice@example.com>"],
+                        "date": "Fri, 1 Mar 2024 12:00:00 +0000",
+                        "to": ["inbox@example.org"],
+                        "messageId": "<abc@example.com>",
+                        "subject": "Invoice"
+                    }
+                },
+                "receipt": {
+                    "timestamp": "2024-03-01T12:00:00.000Z",
+                    "processingTimeMillis": 574,
+                    "recipients": ["inbox@example.org"],
+                    "spamVerdict": {"status": "PASS"},
+                    "virusVerdict": {"status": "FAIL"},
+                    "spfVerdict": {"status": "PASS"},
+                    "dkimVerdict": {"status": "GRAY"},
+                    "dmarcVerdict": {"status": "PASS"},
+                    "dmarcPolicy": "reject",
+                    "action": {
+                        "type": "Lambda",
+                        "invocationType": "Event",
+                        "functionArn": "arn:aws:lambda:us-east-1:123456789012:function:mail"
+                    }
+                }
+            }
+        }]
+    }"#;
+
+    #[test]
+    fn test_parse_full_record() {
+        let event = SesEvent::from_json(EVENT).unwrap();
+        let ses = &event.records[0].ses;
+
+        assert_eq!(event.records[0].event_source, "aws:ses");
+        assert_eq!(ses.mail.destination, ["inbox@example.org"]);
+        assert_eq!(ses.mail.header("subject"), Some("Invoice"));
+        assert_eq!(ses.mail.header("x-missing"), None);
+        assert_eq!(
+            ses.mail.common_headers.return_path.as_deref(),
+            Some("alice@example.com")
+        );
+        assert_eq!(ses.receipt.processing_time_millis, 574);
+        assert_eq!(ses.receipt.action.action_type, "Lambda");
+        assert_eq!(ses.receipt.dmarc_policy.as_deref(), Some("reject"));
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let event = SesEvent::from_json(EVENT).unwrap();
+        let receipt = &event.records[0].ses.receipt;
+        assert!(receipt.spam_verdict.passed());
+        assert!(!receipt.virus_verdict.passed());
+        assert!(!receipt.passed_spam_and_virus_checks());
+    }
+}
@@ -0,0 +1,395 @@
+// Minimal SQS Client (feature "sqs")
+//
+// SendMessage, SendMessageBatch and DeleteMessage over the signed outbound
+// client (see `aws_client`) and SQS's JSON protocol:
+//
+//   POST / HTTP/1.1
+//   X-Amz-Target: AmazonSQS.SendMessage
+//   Content-Type: application/x-amz-json-1.0
+//
+//   {"QueueUrl":"https://sqs.us-east-1.amazonaws.com/123456789012/jobs","MessageBody":"..."}
+//
+// Enough for fan-out (forward work to another queue), dead-lettering by hand
+// and acknowledging messages outside the event source mapping. Requests go
+// to the host of the queue URL.
+
+use crate::aws_client::{AwsClient, AwsError};
+use crate::sigv4::SigV4Error;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+/// SQS JSON protocol content type
+const CONTENT_TYPE: &str = "application/x-amz-json-1.0";
+
+/// Most entries SQS accepts in one batch call
+pub const MAX_BATCH_ENTRIES: usize = 10;
+
+/// Send and delete messages on SQS queues
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{AwsClient, AwsResponse, Credentials, Signer, SqsClient, SqsMessage};
+///
+/// let signer = Signer::new(Credentials::new("AKID", "secret"), "us-east-1", "sqs");
+/// let client = SqsClient::new(AwsClient::new(signer).with_transport(|request| {
+///     assert_eq!(request.host, "sqs.us-east-1.amazonaws.com");
+///     assert_eq!(request.header("x-amz-target"), Some("AmazonSQS.SendMessage"));
+///     Ok(AwsResponse::new(200, r#"{"MessageId":"5fea7756-0ea4-451a-a703-a558b933e274"}"#))
+/// }));
+///
+/// let queue = "https://sqs.us-east-1.amazonaws.com/123456789012/jobs";
+/// let sent = client.send_message(queue, &SqsMessage::new(r#"{"job":1}"#)).unwrap();
+/// assert_eq!(sent.message_id, "5fea7756-0ea4-451a-a703-a558b933e274");
+/// ```
+#[derive(Debug)]
+pub struct SqsClient {
+    client: AwsClient,
+}
+
+impl SqsClient {
+    /// Client sending through `client` (signing for service `sqs`)
+    #[must_use]
+    pub fn new(client: AwsClient) -> Self {
+        Self { client }
+    }
+
+    /// Client with the function's credentials and region
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SigV4Error`] if the credentials or region are not set.
+    pub fn from_env() -> Result<Self, SigV4Error> {
+        Ok(Self::new(AwsClient::from_env("sqs")?))
+    }
+
+    /// Send one message
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails.
+    pub fn send_message(
+        &self,
+        queue_url: &str,
+        message: &SqsMessage,
+    ) -> Result<SendMessageOutput, AwsError> {
+        let mut input = message.to_json();
+        input.insert("QueueUrl".to_string(), json!(queue_url));
+        let output = self.call(queue_url, "SendMessage", &Value::Object(input))?;
+        parse(output, "SendMessage")
+    }
+
+    /// Send up to [`MAX_BATCH_ENTRIES`] messages in one call
+    ///
+    /// Entries without an ID are numbered by position. Individual messages
+    /// can fail while the call succeeds: check
+    /// [`SendMessageBatchOutput::failed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails (e.g.
+    /// `TooManyEntriesInBatchRequest`).
+    pub fn send_message_batch(
+        &self,
+        queue_url: &str,
+        messages: &[SqsMessage],
+    ) -> Result<SendMessageBatchOutput, AwsError> {
+        let entries: Vec<Value> = messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| {
+                let mut entry = message.to_json();
+                let id = message.id.clone().unwrap_or_else(|| index.to_string());
+                entry.insert("Id".to_string(), json!(id));
+                Value::Object(entry)
+            })
+            .collect();
+        let input = json!({"QueueUrl": queue_url, "Entries": entries});
+        let output = self.call(queue_url, "SendMessageBatch", &input)?;
+        parse(output, "SendMessageBatch")
+    }
+
+    /// Delete a received message by its receipt handle
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AwsError`] if the call fails.
+    pub fn delete_message(&self, queue_url: &str, receipt_handle: &str) -> Result<(), AwsError> {
+        let input = json!({"QueueUrl": queue_url, "ReceiptHandle": receipt_handle});
+        self.call(queue_url, "DeleteMessage", &input).map(drop)
+    }
+
+    fn call(&self, queue_url: &str, operation: &str, input: &Value) -> Result<Value, AwsError> {
+        let host = queue_host(queue_url).map_or_else(
+            || format!("sqs.{}.amazonaws.com", self.client.signer().region()),
+            str::to_string,
+        );
+        self.client.call_json(
+            &host,
+            CONTENT_TYPE,
+            &format!("AmazonSQS.{operation}"),
+            input,
+        )
+    }
+}
+
+/// Host of a queue URL (`https://<host>/<account>/<queue>`)
+fn queue_host(queue_url: &str) -> Option<&str> {
+    let rest = queue_url
+        .split_once("://")
+        .map_or(queue_url, |(_, rest)| rest);
+    let host = rest.split('/').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(value: Value, operation: &str) -> Result<T, AwsError> {
+    serde_json::from_value(value)
+        .map_err(|e| AwsError::InvalidResponse(format!("{operation}: {e}")))
+}
+
+/// A message to send
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SqsMessage {
+    body: String,
+    id: Option<String>,
+    delay: Option<Duration>,
+    group_id: Option<String>,
+    deduplication_id: Option<String>,
+    attributes: Vec<(String, String)>,
+}
+
+impl SqsMessage {
+    /// Message with `body`
+    #[must_use]
+    pub fn new(body: impl Into<String>) -> Self {
+        Self {
+            body: body.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Batch entry ID (unique within a batch; letters, digits, `-` and `_`)
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Hide the message for `delay` (up to 15 minutes; standard queues only)
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Message group of a FIFO queue
+    #[must_use]
+    pub fn with_group_id(mut self, group_id: &str) -> Self {
+        self.group_id = Some(group_id.to_string());
+        self
+    }
+
+    /// Deduplication ID of a FIFO queue without content-based deduplication
+    #[must_use]
+    pub fn with_deduplication_id(mut self, deduplication_id: &str) -> Self {
+        self.deduplication_id = Some(deduplication_id.to_string());
+        self
+    }
+
+    /// Add a string message attribute
+    #[must_use]
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Request fields shared by `SendMessage` and batch entries
+    fn to_json(&self) -> Map<String, Value> {
+        let mut message = Map::new();
+        message.insert("MessageBody".to_string(), json!(self.body));
+        if let Some(delay) = self.delay {
+            message.insert("DelaySeconds".to_string(), json!(delay.as_secs()));
+        }
+        if let Some(group_id) = &self.group_id {
+            message.insert("MessageGroupId".to_string(), json!(group_id));
+        }
+        if let Some(deduplication_id) = &self.deduplication_id {
+            message.insert(
+                "MessageDeduplicationId".to_string(),
+                json!(deduplication_id),
+            );
+        }
+        if !self.attributes.is_empty() {
+            let attributes: Map<String, Value> = self
+                .attributes
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.clone(),
+                        json!({"DataType": "String", "StringValue": value}),
+                    )
+                })
+                .collect();
+            message.insert("MessageAttributes".to_string(), Value::Object(attributes));
+        }
+        message
+    }
+}
+
+/// Result of a `SendMessage`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SendMessageOutput {
+    /// Message ID assigned by SQS
+    #[serde(default)]
+    pub message_id: String,
+    /// Sequence number (FIFO queues only)
+    #[serde(default)]
+    pub sequence_number: Option<String>,
+}
+
+/// Result of a `SendMessageBatch`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SendMessageBatchOutput {
+    /// Messages that were sent
+    #[serde(default)]
+    pub successful: Vec<BatchResultEntry>,
+    /// Messages that were not sent
+    #[serde(default)]
+    pub failed: Vec<BatchFailure>,
+}
+
+/// A sent batch entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BatchResultEntry {
+    /// Entry ID from the request
+    #[serde(default)]
+    pub id: String,
+    /// Message ID assigned by SQS
+    #[serde(default)]
+    pub message_id: String,
+    /// Sequence number (FIFO queues only)
+    #[serde(default)]
+    pub sequence_number: Option<String>,
+}
+
+/// A batch entry that was not sent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BatchFailure {
+    /// Entry ID from the request
+    #[serde(default)]
+    pub id: String,
+    /// Whether the entry itself was invalid (retrying will not help)
+    #[serde(default)]
+    pub sender_fault: bool,
+    /// Error code
+    #[serde(default)]
+    pub code: String,
+    /// Error message
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aws_client::AwsResponse;
+    use crate::sigv4::{Credentials, Signer};
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    const QUEUE: &str = "https://sqs.eu-west-1.amazonaws.com/123456789012/jobs.fifo";
+
+    fn client(response: &'static str) -> (SqsClient, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        let signer = Signer::new(Credentials::new("AKID", "secret"), "eu-west-1", "sqs");
+        let client = AwsClient::new(signer).with_transport(move |request| {
+            assert_eq!(request.host, "sqs.eu-west-1.amazonaws.com");
+            let mut body: Value = serde_json::from_slice(&request.body).unwrap();
+            body["_target"] = json!(request.header("x-amz-target"));
+            recorded
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(body);
+            Ok(AwsResponse::new(200, response))
+        });
+        (SqsClient::new(client), requests)
+    }
+
+    #[test]
+    fn test_send_message_fields() {
+        let (client, requests) = client(r#"{"MessageId":"m1","SequenceNumber":"18"}"#);
+        let message = SqsMessage::new("hello")
+            .with_group_id("customer-7")
+            .with_deduplication_id("order-42")
+            .with_delay(Duration::from_secs(30))
+            .with_attribute("source", "api");
+        let sent = client.send_message(QUEUE, &message).unwrap();
+        assert_eq!(sent.sequence_number.as_deref(), Some("18"));
+
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            json!({
+                "QueueUrl": QUEUE,
+                "MessageBody": "hello",
+                "DelaySeconds": 30,
+                "MessageGroupId": "customer-7",
+                "MessageDeduplicationId": "order-42",
+                "MessageAttributes": {"source": {"DataType": "String", "StringValue": "api"}},
+                "_target": "AmazonSQS.SendMessage",
+            })
+        );
+    }
+
+    #[test]
+    fn test_send_message_batch_partial_failure() {
+        let (client, requests) = client(
+            r#"{"Successful":[{"Id":"0","MessageId":"m1","MD5OfMessageBody":"x"}],
+                "Failed":[{"Id":"b","SenderFault":true,"Code":"InvalidParameterValue","Message":"bad"}]}"#,
+        );
+        let output = client
+            .send_message_batch(
+                QUEUE,
+                &[SqsMessage::new("a"), SqsMessage::new("b").with_id("b")],
+            )
+            .unwrap();
+        assert_eq!(output.successful[0].message_id, "m1");
+        assert_eq!(output.failed[0].code, "InvalidParameterValue");
+        assert!(output.failed[0].sender_fault);
+
+        let request = &requests.lock().unwrap()[0];
+        assert_eq!(request["Entries"][0]["Id"], "0");
+        assert_eq!(request["Entries"][1]["Id"], "b");
+        assert_eq!(request["_target"], "AmazonSQS.SendMessageBatch");
+    }
+
+    #[test]
+    fn test_delete_message() {
+        let (client, requests) = client("");
+        client
+            .delete_message(QUEUE, "AQEBwJnKyrHigUMZj6rYigCgxlaS3SLy0a")
+            .unwrap();
+        assert_eq!(
+            requests.lock().unwrap()[0],
+            json!({
+                "QueueUrl": QUEUE,
+                "ReceiptHandle": "AQEBwJnKyrHigUMZj6rYigCgxlaS3SLy0a",
+                "_target": "AmazonSQS.DeleteMessage",
+            })
+        );
+    }
+
+    #[test]
+    fn test_queue_host() {
+        assert_eq!(queue_host(QUEUE), Some("sqs.eu-west-1.amazonaws.com"));
+        assert_eq!(
+            queue_host("localhost:9324/000000000000/q"),
+            Some("localhost:9324")
+        );
+        assert_eq!(queue_host(""), None);
+    }
+}
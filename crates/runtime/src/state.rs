@@ -0,0 +1,156 @@
+// Warm-Container Shared State
+//
+// Lambda reuses the process across warm invocations, so expensive setup
+// (DB connections, parsed config, HTTP clients) should happen once. Instead
+// of ad-hoc statics, handlers ask the runtime for a typed slot:
+//
+//   let pool = runtime.state_with(|| Pool::connect(&url));
+//
+// One slot per type, per process (shared by all Runtime clones and worker
+// threads). Each slot is a OnceCell: the first caller initializes it,
+// concurrent callers wait, and the init duration is logged once so cold
+// start cost is attributable.
+
+use crate::logger::{LogLevel, Logger};
+use once_cell::sync::OnceCell;
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+/// Type-erased `&'static OnceCell<T>` per state type
+type Slots = HashMap<TypeId, &'static (dyn Any + Send + Sync)>;
+
+static SLOTS: OnceCell<Mutex<Slots>> = OnceCell::new();
+
+/// The process-wide slot for `T` (created empty on first use)
+///
+/// Slots live for the whole process, so they are leaked on purpose.
+fn slot<T: Send + Sync + 'static>() -> &'static OnceCell<T> {
+    let mut slots = SLOTS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    let cell = *slots
+        .entry(TypeId::of::<T>())
+        .or_insert_with(|| Box::leak(Box::new(OnceCell::<T>::new())));
+    cell.downcast_ref::<OnceCell<T>>()
+        .expect("state slot registered under its own TypeId")
+}
+
+/// Run `init`, logging how long it took
+fn timed<T, E>(init: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let started = Instant::now();
+    let value = init()?;
+    Logger::new().log_with_fields(
+        LogLevel::Info,
+        &format!("Initialized state {}", type_name::<T>()),
+        &[(
+            "init_us",
+            u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX),
+        )],
+    );
+    Ok(value)
+}
+
+/// Slot for `T`, initialized with `init` on first access
+pub(crate) fn get_or_init<T: Send + Sync + 'static>(init: impl FnOnce() -> T) -> &'static T {
+    slot::<T>().get_or_init(|| {
+        timed(|| Ok::<T, std::convert::Infallible>(init())).unwrap_or_else(|never| match never {})
+    })
+}
+
+/// Slot for `T`, initialized with fallible `init` on first access
+///
+/// A failed init leaves the slot empty, so the next call retries.
+pub(crate) fn get_or_try_init<T: Send + Sync + 'static, E>(
+    init: impl FnOnce() -> Result<T, E>,
+) -> Result<&'static T, E> {
+    slot::<T>().get_or_try_init(|| timed(init))
+}
+
+/// Slot for `T` if already initialized
+pub(crate) fn get<T: Send + Sync + 'static>() -> Option<&'static T> {
+    slot::<T>().get()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    // Each test uses its own types: slots are process-wide
+
+    #[derive(Debug, PartialEq)]
+    struct Config(&'static str);
+
+    #[test]
+    fn test_get_or_init_runs_once() {
+        let calls = AtomicUsize::new(0);
+        let first = get_or_init(|| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Config("first")
+        });
+        let second = get_or_init(|| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Config("second")
+        });
+
+        assert_eq!(first, &Config("first"));
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(get::<Config>(), Some(&Config("first")));
+    }
+
+    #[test]
+    fn test_slots_are_per_type() {
+        struct Unset;
+        struct Counter(u32);
+        struct Label(&'static str);
+
+        assert!(get::<Unset>().is_none());
+        assert_eq!(get_or_init(|| Counter(7)).0, 7);
+        assert_eq!(get_or_init(|| Label("db")).0, "db");
+        assert_eq!(get::<Counter>().map(|counter| counter.0), Some(7));
+    }
+
+    #[test]
+    fn test_failed_init_retries() {
+        struct Connection(u8);
+
+        let failed: Result<&Connection, &str> = get_or_try_init(|| Err("refused"));
+        assert_eq!(failed.err(), Some("refused"));
+        assert!(get::<Connection>().is_none());
+
+        let connected = get_or_try_init::<_, &str>(|| Ok(Connection(1))).unwrap();
+        assert_eq!(connected.0, 1);
+    }
+
+    #[test]
+    fn test_concurrent_init_runs_once() {
+        struct Pool(u8);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let calls = Arc::clone(&calls);
+                thread::spawn(move || {
+                    let pool = get_or_init(|| {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(std::time::Duration::from_millis(10));
+                        Pool(0)
+                    });
+                    std::ptr::from_ref(pool).addr()
+                })
+            })
+            .collect();
+
+        let addresses: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(addresses.windows(2).all(|pair| pair[0] == pair[1]));
+        assert_eq!(get::<Pool>().map(|pool| pool.0), Some(0));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,343 @@
+// Invocation Timeout Guard
+//
+// When a handler overruns, Lambda kills the sandbox and the caller gets a
+// bare "Task timed out after 3.00 seconds". The guard reports the overrun
+// itself while there is still time to do so:
+//
+//   - the handler runs on its own thread; the invocation thread waits until
+//     `margin` before the deadline
+//   - if the handler has not finished by then, its context's
+//     CancellationToken is cancelled (`CancelReason::Timeout`) and the
+//     handler gets half the margin to return a partial result or error
+//   - after that it is abandoned (a thread cannot be stopped: it keeps
+//     running detached and its result is dropped) and the caller posts a
+//     `Function.Timeout` error whose stack trace carries the diagnostics:
+//     elapsed and remaining time, the margin and the last
+//     `timeout_checkpoint` the handler passed
+//
+// Opt-in (RUCHY_LAMBDA_TIMEOUT_GUARD_MS=<margin> or
+// Runtime::with_timeout_guard): the extra thread per invocation costs a few
+// microseconds, and handlers must be `Send + 'static`. Without a deadline
+// (local runs) the handler runs inline.
+//
+// The abandoned thread keeps the invocation deadline, so its outbound I/O
+// fails fast instead of piling up behind later invocations (see
+// deadline.rs).
+
+use crate::cancellation::CancelReason;
+use crate::context::Context;
+use crate::deadline;
+use crate::handler_error::HandlerError;
+use crate::{Error, Result};
+use std::cell::RefCell;
+use std::panic;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Environment variable enabling the guard with this margin (milliseconds)
+pub const TIMEOUT_GUARD_ENV: &str = "RUCHY_LAMBDA_TIMEOUT_GUARD_MS";
+
+/// Margin before the deadline used by [`TimeoutGuard::default`]
+pub const DEFAULT_TIMEOUT_MARGIN: Duration = Duration::from_millis(200);
+
+/// `errorType` posted for abandoned handlers
+pub const TIMEOUT_ERROR_TYPE: &str = "Function.Timeout";
+
+/// Last checkpoint of the guarded handler running on this thread
+type Checkpoint = Arc<Mutex<Option<String>>>;
+
+thread_local! {
+    static CHECKPOINT: RefCell<Option<Checkpoint>> = const { RefCell::new(None) };
+}
+
+/// Record how far the guarded handler got, for the timeout diagnostics
+///
+/// A no-op outside a [`TimeoutGuard`].
+pub fn timeout_checkpoint(label: &str) {
+    CHECKPOINT.with(|checkpoint| {
+        if let Some(checkpoint) = checkpoint.borrow().as_ref() {
+            *checkpoint.lock().unwrap_or_else(PoisonError::into_inner) = Some(label.to_string());
+        }
+    });
+}
+
+/// Watchdog abandoning handlers that run into the invocation deadline
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{timeout_checkpoint, Context, TimeoutGuard};
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// let deadline = SystemTime::now() + Duration::from_millis(300);
+/// let context = Context {
+///     request_id: "req-1".to_string(),
+///     deadline_ms: deadline.duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+///     ..Context::default()
+/// };
+///
+/// let guard = TimeoutGuard::new(Duration::from_millis(200));
+/// let error = guard
+///     .run(&context, || {
+///         timeout_checkpoint("calling inventory service");
+///         std::thread::sleep(Duration::from_secs(1));
+///     })
+///     .unwrap_err();
+/// assert_eq!(error.error_type, "Function.Timeout");
+/// assert!(error.stack_trace.contains(&"last_checkpoint: calling inventory service".to_string()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutGuard {
+    margin: Duration,
+}
+
+impl Default for TimeoutGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMEOUT_MARGIN)
+    }
+}
+
+impl TimeoutGuard {
+    /// Abandon handlers still running `margin` before the deadline
+    #[must_use]
+    pub fn new(margin: Duration) -> Self {
+        Self { margin }
+    }
+
+    /// Guard configured by [`TIMEOUT_GUARD_ENV`], `None` if unset
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the variable is not a
+    /// non-negative integer.
+    pub fn from_env() -> Result<Option<Self>> {
+        std::env::var(TIMEOUT_GUARD_ENV)
+            .ok()
+            .map(|value| Self::parse(&value))
+            .transpose()
+    }
+
+    /// Guard whose margin is `value` milliseconds (a non-negative integer)
+    pub(crate) fn parse(value: &str) -> Result<Self> {
+        value
+            .trim()
+            .parse()
+            .map(|ms| Self::new(Duration::from_millis(ms)))
+            .map_err(|_| {
+                Error::InitializationFailed(format!(
+                    "Invalid {TIMEOUT_GUARD_ENV} '{value}' (expected a non-negative integer)"
+                ))
+            })
+    }
+
+    /// Time before the deadline at which handlers are abandoned
+    #[must_use]
+    pub fn margin(&self) -> Duration {
+        self.margin
+    }
+
+    /// Run `handler` for the invocation of `context`
+    ///
+    /// `margin` before the deadline `context.cancellation` is cancelled; a
+    /// handler polling it may still return within half the margin.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`TIMEOUT_ERROR_TYPE`] error (to post with
+    /// [`Runtime::post_error`](crate::Runtime::post_error)) if the handler
+    /// has not returned by then.
+    ///
+    /// # Panics
+    ///
+    /// Re-raises a panic of `handler` on the calling thread, and panics if
+    /// the OS refuses to spawn a thread.
+    pub fn run<T: Send + 'static>(
+        &self,
+        context: &Context,
+        handler: impl FnOnce() -> T + Send + 'static,
+    ) -> std::result::Result<T, HandlerError> {
+        let Some(deadline) = deadline::from_millis(context.deadline_ms) else {
+            return Ok(handler());
+        };
+
+        let started = Instant::now();
+        let checkpoint = Checkpoint::default();
+        let handler_checkpoint = Arc::clone(&checkpoint);
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let handle = thread::spawn(move || {
+            deadline::set_invocation_deadline(Some(deadline));
+            CHECKPOINT.with(|current| *current.borrow_mut() = Some(handler_checkpoint));
+            let _ = sender.send(handler());
+        });
+
+        let cutoff = deadline.checked_sub(self.margin).unwrap_or(deadline);
+        let wait = cutoff.duration_since(SystemTime::now()).unwrap_or_default();
+        let outcome = receiver.recv_timeout(wait).or_else(|error| match error {
+            RecvTimeoutError::Timeout => {
+                context.cancellation.cancel(CancelReason::Timeout);
+                receiver.recv_timeout(self.margin / 2)
+            }
+            RecvTimeoutError::Disconnected => Err(error),
+        });
+        match outcome {
+            Ok(response) => Ok(response),
+            Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                Err(payload) => panic::resume_unwind(payload),
+                Ok(()) => unreachable!("handler thread exited without a result"),
+            },
+            Err(RecvTimeoutError::Timeout) => {
+                let checkpoint = checkpoint
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone();
+                Err(self.timeout_error(context, started.elapsed(), deadline, checkpoint))
+            }
+        }
+    }
+
+    /// `Function.Timeout` error with the diagnostics as stack trace lines
+    fn timeout_error(
+        &self,
+        context: &Context,
+        elapsed: Duration,
+        deadline: SystemTime,
+        checkpoint: Option<String>,
+    ) -> HandlerError {
+        let remaining = deadline
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        let mut error = HandlerError::new(
+            TIMEOUT_ERROR_TYPE,
+            format!(
+                "Handler abandoned after {}ms, {}ms before the invocation deadline",
+                elapsed.as_millis(),
+                remaining.as_millis()
+            ),
+        );
+        error.stack_trace = vec![
+            format!("request_id: {}", context.request_id),
+            format!("elapsed_ms: {}", elapsed.as_millis()),
+            format!("remaining_ms: {}", remaining.as_millis()),
+            format!("timeout_margin_ms: {}", self.margin.as_millis()),
+        ];
+        if let Some(checkpoint) = checkpoint {
+            error
+                .stack_trace
+                .push(format!("last_checkpoint: {checkpoint}"));
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use std::env;
+    use std::time::UNIX_EPOCH;
+
+    fn context_due_in(duration: Duration) -> Context {
+        let deadline = SystemTime::now() + duration;
+        Context {
+            request_id: "req-guard".to_string(),
+            deadline_ms: u64::try_from(deadline.duration_since(UNIX_EPOCH).unwrap().as_millis())
+                .unwrap(),
+            ..Context::default()
+        }
+    }
+
+    #[test]
+    fn test_fast_handler_returns_response() {
+        let guard = TimeoutGuard::default();
+        let context = context_due_in(Duration::from_secs(5));
+        let response = guard.run(&context, || {
+            assert!(deadline::invocation_deadline().is_some());
+            "ok".to_string()
+        });
+        assert_eq!(response.unwrap(), "ok");
+    }
+
+    #[test]
+    fn test_slow_handler_abandoned_with_diagnostics() {
+        let guard = TimeoutGuard::new(Duration::from_millis(100));
+        let context = context_due_in(Duration::from_millis(250));
+        let started = Instant::now();
+        let error = guard
+            .run(&context, || {
+                timeout_checkpoint("loaded order");
+                timeout_checkpoint("charging card");
+                thread::sleep(Duration::from_secs(2));
+            })
+            .unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(error.error_type, TIMEOUT_ERROR_TYPE);
+        assert_eq!(error.stack_trace[0], "request_id: req-guard");
+        assert_eq!(error.stack_trace[3], "timeout_margin_ms: 100");
+        assert_eq!(error.stack_trace[4], "last_checkpoint: charging card");
+    }
+
+    #[test]
+    fn test_cancelled_handler_returns_partial_result() {
+        let guard = TimeoutGuard::new(Duration::from_millis(200));
+        let context = context_due_in(Duration::from_millis(300));
+        let token = context.cancellation.clone();
+        let processed = guard
+            .run(&context, move || {
+                let mut processed = 0;
+                while !token.is_cancelled() {
+                    processed += 1;
+                    thread::sleep(Duration::from_millis(5));
+                }
+                (processed, token.reason())
+            })
+            .unwrap();
+
+        assert!(processed.0 > 0);
+        assert_eq!(processed.1, Some(CancelReason::Timeout));
+        assert_eq!(context.cancellation.reason(), Some(CancelReason::Timeout));
+    }
+
+    #[test]
+    fn test_no_deadline_runs_inline() {
+        let caller = thread::current().id();
+        let guard = TimeoutGuard::default();
+        let same_thread = guard
+            .run(&Context::default(), move || {
+                thread::current().id() == caller
+            })
+            .unwrap();
+        assert!(same_thread);
+        // Outside a guarded handler checkpoints are ignored
+        timeout_checkpoint("unguarded");
+    }
+
+    #[test]
+    fn test_handler_panic_propagates() {
+        let guard = TimeoutGuard::default();
+        let context = context_due_in(Duration::from_secs(5));
+        let result = panic::catch_unwind(|| guard.run(&context, || panic!("handler bug")));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test_from_env() {
+        env::remove_var(TIMEOUT_GUARD_ENV);
+        assert_eq!(TimeoutGuard::from_env().unwrap(), None);
+
+        env::set_var(TIMEOUT_GUARD_ENV, "250");
+        assert_eq!(
+            TimeoutGuard::from_env()
+                .unwrap()
+                .map(|guard| guard.margin()),
+            Some(Duration::from_millis(250))
+        );
+
+        env::set_var(TIMEOUT_GUARD_ENV, "soon");
+        assert!(TimeoutGuard::from_env().is_err());
+        env::remove_var(TIMEOUT_GUARD_ENV);
+    }
+}
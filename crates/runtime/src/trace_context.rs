@@ -0,0 +1,358 @@
+// Distributed Trace Context
+//
+// Two wire formats carry the same (trace id, parent span id, sampled) triple:
+// - W3C Trace Context (OpenTelemetry):  traceparent: 00-<32 hex>-<16 hex>-01
+// - AWS X-Ray:  Root=1-<8 hex>-<24 hex>;Parent=<16 hex>;Sampled=1
+//
+// Lambda delivers the X-Ray form in Lambda-Runtime-Trace-Id; API Gateway
+// forwards the caller's traceparent/tracestate in the event's "headers".
+// TraceContext normalizes both and formats either one for outbound requests,
+// so traces started by OpenTelemetry-instrumented callers continue through
+// the function.
+
+use std::fmt;
+
+/// W3C trace context header name
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// W3C vendor-specific trace state header name
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// X-Ray trace header name used on HTTP requests
+pub const XRAY_TRACE_HEADER: &str = "X-Amzn-Trace-Id";
+
+/// Trace identity shared by W3C `traceparent` and X-Ray trace headers
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::TraceContext;
+///
+/// let trace = TraceContext::from_traceparent(
+///     "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+/// )
+/// .unwrap();
+/// assert!(trace.sampled);
+/// assert_eq!(
+///     trace.xray_header(),
+///     "Root=1-4bf92f35-77b34da6a3ce929d0e0e4736;Parent=00f067aa0ba902b7;Sampled=1"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 128-bit trace ID as 32 lowercase hex digits
+    pub trace_id: String,
+    /// 64-bit parent span ID as 16 lowercase hex digits
+    pub parent_id: String,
+    /// Whether the caller sampled this trace
+    pub sampled: bool,
+    /// W3C `tracestate` to forward unchanged, if any
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Parse a W3C `traceparent` header
+    ///
+    /// Accepts version `00` and (per the spec) higher versions with the same
+    /// leading fields. Returns `None` for malformed or all-zero IDs.
+    #[must_use]
+    pub fn from_traceparent(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+
+        if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        if !is_id(trace_id, 32) || !is_id(parent_id, 16) || !is_hex(flags, 2) {
+            return None;
+        }
+
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            sampled: u8::from_str_radix(flags, 16).ok()? & 0x01 == 1,
+            tracestate: None,
+        })
+    }
+
+    /// Parse an X-Ray trace header (`Root=1-...;Parent=...;Sampled=1`)
+    ///
+    /// Returns `None` without a valid `Root` and `Parent` (the function
+    /// segment has no parent to continue from otherwise).
+    #[must_use]
+    pub fn from_xray(header: &str) -> Option<Self> {
+        let mut trace_id = None;
+        let mut parent_id = None;
+        let mut sampled = false;
+
+        for field in header.split(';') {
+            match field.trim().split_once('=') {
+                Some(("Root", root)) => trace_id = xray_root_to_trace_id(root),
+                Some(("Parent", parent)) => parent_id = Some(parent.to_ascii_lowercase()),
+                Some(("Sampled", value)) => sampled = value == "1",
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            trace_id: trace_id?,
+            parent_id: parent_id.filter(|id| is_id(id, 16))?,
+            sampled,
+            tracestate: None,
+        })
+    }
+
+    /// Extract a trace context from inbound HTTP headers
+    ///
+    /// Prefers W3C `traceparent` (with `tracestate`) and falls back to
+    /// `X-Amzn-Trace-Id`. Header names are matched case-insensitively.
+    #[must_use]
+    pub fn from_headers<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Option<Self> {
+        let mut traceparent = None;
+        let mut tracestate = None;
+        let mut xray = None;
+
+        for (name, value) in headers {
+            if name.eq_ignore_ascii_case(TRACEPARENT_HEADER) {
+                traceparent = Some(value);
+            } else if name.eq_ignore_ascii_case(TRACESTATE_HEADER) {
+                tracestate = Some(value);
+            } else if name.eq_ignore_ascii_case(XRAY_TRACE_HEADER) {
+                xray = Some(value);
+            }
+        }
+
+        if let Some(mut context) = traceparent.and_then(Self::from_traceparent) {
+            context.tracestate = tracestate
+                .map(str::trim)
+                .filter(|state| !state.is_empty())
+                .map(str::to_string);
+            return Some(context);
+        }
+        xray.and_then(Self::from_xray)
+    }
+
+    /// Extract a trace context from an API Gateway / ALB event body
+    ///
+    /// Reads the event's `headers` object (REST, HTTP API and ALB events all
+    /// use it). Returns `None` for non-JSON bodies or missing headers.
+    #[cfg(feature = "serde")]
+    #[must_use]
+    pub fn from_event(event_body: &str) -> Option<Self> {
+        let event: serde_json::Value = serde_json::from_str(event_body).ok()?;
+        let headers = event.get("headers")?.as_object()?;
+        Self::from_headers(
+            headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.as_str()?))),
+        )
+    }
+
+    /// Format as a W3C `traceparent` value (version `00`)
+    #[must_use]
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{}",
+            self.trace_id,
+            self.parent_id,
+            if self.sampled { "01" } else { "00" }
+        )
+    }
+
+    /// Format as an X-Ray trace header value
+    #[must_use]
+    pub fn xray_header(&self) -> String {
+        let (epoch, unique) = self.trace_id.split_at(8.min(self.trace_id.len()));
+        format!(
+            "Root=1-{epoch}-{unique};Parent={};Sampled={}",
+            self.parent_id,
+            u8::from(self.sampled)
+        )
+    }
+
+    /// Headers to inject into an outbound request
+    ///
+    /// Always `traceparent` and `X-Amzn-Trace-Id`, plus `tracestate` when
+    /// the inbound request carried one.
+    #[must_use]
+    pub fn outbound_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            (TRACEPARENT_HEADER, self.traceparent()),
+            (XRAY_TRACE_HEADER, self.xray_header()),
+        ];
+        if let Some(tracestate) = &self.tracestate {
+            headers.push((TRACESTATE_HEADER, tracestate.clone()));
+        }
+        headers
+    }
+}
+
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.traceparent())
+    }
+}
+
+/// `1-5759e988-bd862e3fe1be46a994272793` -> `5759e988bd862e3fe1be46a994272793`
+fn xray_root_to_trace_id(root: &str) -> Option<String> {
+    let mut parts = root.split('-');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let epoch = parts.next()?;
+    let unique = parts.next()?;
+    if parts.next().is_some() || !is_hex(epoch, 8) || !is_hex(unique, 24) {
+        return None;
+    }
+
+    let trace_id = format!("{epoch}{unique}").to_ascii_lowercase();
+    is_id(&trace_id, 32).then_some(trace_id)
+}
+
+/// `value` is exactly `len` hex digits
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// `value` is a lowercase hex ID of `len` digits that is not all zeros
+fn is_id(value: &str, len: usize) -> bool {
+    is_hex(value, len)
+        && !value.bytes().any(|b| b.is_ascii_uppercase())
+        && value.bytes().any(|b| b != b'0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    const XRAY: &str = "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+
+    #[test]
+    fn test_from_traceparent() {
+        let trace = TraceContext::from_traceparent(TRACEPARENT).unwrap();
+        assert_eq!(trace.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(trace.parent_id, "00f067aa0ba902b7");
+        assert!(trace.sampled);
+
+        let unsampled = TraceContext::from_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00",
+        )
+        .unwrap();
+        assert!(!unsampled.sampled);
+    }
+
+    #[test]
+    fn test_from_traceparent_rejects_invalid() {
+        for header in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert!(
+                TraceContext::from_traceparent(header).is_none(),
+                "accepted {header:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_traceparent_future_version() {
+        let trace = TraceContext::from_traceparent(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future",
+        )
+        .unwrap();
+        assert_eq!(trace.parent_id, "00f067aa0ba902b7");
+    }
+
+    #[test]
+    fn test_from_xray() {
+        let trace = TraceContext::from_xray(XRAY).unwrap();
+        assert_eq!(trace.trace_id, "5759e988bd862e3fe1be46a994272793");
+        assert_eq!(trace.parent_id, "53995c3f42cd8ad8");
+        assert!(trace.sampled);
+        assert_eq!(trace.xray_header(), XRAY);
+
+        assert!(TraceContext::from_xray("Root=1-5759e988-bd862e3fe1be46a994272793").is_none());
+        assert!(TraceContext::from_xray(
+            "Root=2-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let trace = TraceContext::from_xray(XRAY).unwrap();
+        assert_eq!(
+            trace.traceparent(),
+            "00-5759e988bd862e3fe1be46a994272793-53995c3f42cd8ad8-01"
+        );
+        assert_eq!(
+            TraceContext::from_traceparent(&trace.traceparent()).unwrap(),
+            trace
+        );
+        assert_eq!(trace.to_string(), trace.traceparent());
+    }
+
+    #[test]
+    fn test_from_headers_prefers_traceparent() {
+        let trace = TraceContext::from_headers([
+            ("X-Amzn-Trace-Id", XRAY),
+            ("Traceparent", TRACEPARENT),
+            ("TraceState", "congo=t61rcWkgMzE"),
+        ])
+        .unwrap();
+        assert_eq!(trace.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(trace.tracestate.as_deref(), Some("congo=t61rcWkgMzE"));
+
+        let fallback =
+            TraceContext::from_headers([("x-amzn-trace-id", XRAY), ("traceparent", "junk")])
+                .unwrap();
+        assert_eq!(fallback.trace_id, "5759e988bd862e3fe1be46a994272793");
+
+        assert!(TraceContext::from_headers([("content-type", "application/json")]).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_from_event() {
+        let event = format!(
+            r#"{{"headers":{{"traceparent":"{TRACEPARENT}","host":"example.com"}},"body":"{{}}"}}"#
+        );
+        let trace = TraceContext::from_event(&event).unwrap();
+        assert_eq!(trace.parent_id, "00f067aa0ba902b7");
+
+        assert!(TraceContext::from_event(r#"{"body":"x"}"#).is_none());
+        assert!(TraceContext::from_event("not json").is_none());
+    }
+
+    #[test]
+    fn test_outbound_headers() {
+        let mut trace = TraceContext::from_traceparent(TRACEPARENT).unwrap();
+        let headers = trace.outbound_headers();
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers[0], (TRACEPARENT_HEADER, TRACEPARENT.to_string()));
+        assert_eq!(
+            headers[1],
+            (
+                XRAY_TRACE_HEADER,
+                "Root=1-4bf92f35-77b34da6a3ce929d0e0e4736;Parent=00f067aa0ba902b7;Sampled=1"
+                    .to_string()
+            )
+        );
+
+        trace.tracestate = Some("congo=t61rcWkgMzE".to_string());
+        assert_eq!(
+            trace.outbound_headers()[2],
+            (TRACESTATE_HEADER, "congo=t61rcWkgMzE".to_string())
+        );
+    }
+}
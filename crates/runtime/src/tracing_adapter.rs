@@ -0,0 +1,463 @@
+// `tracing` Subscriber Adapter (feature = "tracing")
+//
+// Routes `tracing` spans and events to the JSON Logger so handlers that are
+// already instrumented with `tracing` get CloudWatch-friendly output without
+// pulling in tracing-subscriber.
+//
+// Each event becomes one log line:
+//   {"level":"INFO","timestamp":"...","request_id":"...","message":"...",
+//    "target":"handler","span":"parse",<span fields>,<event fields>}
+//
+// The request ID comes from TracingLogger::set_request_id, or from a
+// `request_id` field on the event or an enclosing span.
+//
+// Depends on tracing-core only; the span stack is per thread.
+
+use crate::{Error, LogLevel, Logger, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Dispatch, Event, Level, Metadata, Subscriber};
+
+/// Field name that sets the log line's `request_id`
+const REQUEST_ID_FIELD: &str = "request_id";
+
+thread_local! {
+    /// Spans entered on this thread, innermost last
+    static CURRENT_SPANS: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A live span: name, recorded fields and parent
+#[derive(Debug)]
+struct SpanData {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: Vec<(&'static str, serde_json::Value)>,
+    refs: usize,
+}
+
+struct Inner {
+    logger: Logger,
+    request_id: Mutex<Option<String>>,
+    spans: Mutex<HashMap<u64, SpanData>>,
+    next_id: AtomicU64,
+}
+
+/// `tracing` subscriber that writes through [`Logger`]
+///
+/// Cloning is cheap and shares state, so keep a clone to update the request
+/// ID after installing it.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Logger, TracingLogger};
+///
+/// let subscriber = TracingLogger::new(Logger::new());
+/// subscriber.install().expect("no other global subscriber");
+///
+/// subscriber.set_request_id(Some("req-1"));
+/// let span = tracing::info_span!("handler", order_id = 42);
+/// let _guard = span.enter();
+/// tracing::info!(items = 3, "processing order");
+/// // {"level":"INFO",...,"request_id":"req-1","message":"processing order",
+/// //  "target":"...","span":"handler","order_id":42,"items":3}
+/// ```
+#[derive(Clone)]
+pub struct TracingLogger {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for TracingLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracingLogger")
+            .field("request_id", &self.inner.request_id.lock().unwrap())
+            .field("spans", &self.inner.spans.lock().unwrap().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TracingLogger {
+    /// Adapter writing through `logger` (its minimum level is respected)
+    #[must_use]
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                logger,
+                request_id: Mutex::new(None),
+                spans: Mutex::new(HashMap::new()),
+                next_id: AtomicU64::new(1),
+            }),
+        }
+    }
+
+    /// Set the request ID attached to subsequent log lines
+    pub fn set_request_id(&self, request_id: Option<&str>) {
+        *self
+            .inner
+            .request_id
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = request_id.map(str::to_string);
+    }
+
+    /// Install as the global default `tracing` subscriber
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if a global subscriber is
+    /// already set.
+    pub fn install(&self) -> Result<()> {
+        tracing_core::dispatcher::set_global_default(Dispatch::new(self.clone())).map_err(|e| {
+            Error::InitializationFailed(format!("Failed to install tracing subscriber: {e}"))
+        })
+    }
+}
+
+/// Innermost entered span on this thread that this subscriber knows
+fn current_span(spans: &HashMap<u64, SpanData>) -> Option<u64> {
+    CURRENT_SPANS.with(|stack| {
+        stack
+            .borrow()
+            .iter()
+            .rev()
+            .copied()
+            .find(|id| spans.contains_key(id))
+    })
+}
+
+/// Map a `tracing` level onto the logger's levels (TRACE folds into DEBUG)
+fn log_level(level: Level) -> LogLevel {
+    match level {
+        Level::ERROR => LogLevel::Error,
+        Level::WARN => LogLevel::Warn,
+        Level::INFO => LogLevel::Info,
+        _ => LogLevel::Debug,
+    }
+}
+
+/// Collects field values as JSON, pulling out `message`
+#[derive(Default)]
+struct JsonVisitor {
+    message: Option<String>,
+    fields: Vec<(&'static str, serde_json::Value)>,
+}
+
+impl JsonVisitor {
+    fn push(&mut self, field: &Field, value: serde_json::Value) {
+        if field.name() == "message" {
+            self.message = Some(match value {
+                serde_json::Value::String(message) => message,
+                other => other.to_string(),
+            });
+        } else {
+            self.fields.push((field.name(), value));
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, format!("{value:?}").into());
+    }
+}
+
+impl Subscriber for TracingLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.inner.logger.enabled(log_level(*metadata.level()))
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut visitor = JsonVisitor::default();
+        span.record(&mut visitor);
+
+        let mut spans = self.inner.spans.lock().unwrap();
+        let parent = if span.is_root() {
+            None
+        } else if let Some(parent) = span.parent() {
+            Some(parent.into_u64())
+        } else {
+            current_span(&spans)
+        };
+        spans.insert(
+            id,
+            SpanData {
+                name: span.metadata().name(),
+                parent,
+                fields: visitor.fields,
+                refs: 1,
+            },
+        );
+
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut visitor = JsonVisitor::default();
+        values.record(&mut visitor);
+
+        if let Some(data) = self.inner.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            for (name, value) in visitor.fields {
+                data.fields.retain(|(existing, _)| *existing != name);
+                data.fields.push((name, value));
+            }
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let mut fields = vec![(
+            "target",
+            serde_json::Value::from(metadata.target()).to_string(),
+        )];
+        let mut request_id = self.inner.request_id.lock().unwrap().clone();
+
+        {
+            let spans = self.inner.spans.lock().unwrap();
+            let current = if event.is_root() {
+                None
+            } else {
+                event
+                    .parent()
+                    .map(Id::into_u64)
+                    .or_else(|| current_span(&spans))
+            };
+
+            // Walk to the root, then emit outermost span fields first
+            let mut chain = Vec::new();
+            let mut next = current;
+            while let Some(data) = next.and_then(|id| spans.get(&id)) {
+                chain.push(data);
+                next = data.parent;
+            }
+            if let Some(innermost) = chain.first() {
+                fields.push(("span", serde_json::Value::from(innermost.name).to_string()));
+            }
+            for data in chain.iter().rev() {
+                for (name, value) in &data.fields {
+                    push_field(&mut fields, &mut request_id, name, value);
+                }
+            }
+        }
+        for (name, value) in &visitor.fields {
+            push_field(&mut fields, &mut request_id, name, value);
+        }
+
+        self.inner.logger.log_json_fields(
+            log_level(*metadata.level()),
+            request_id.as_deref(),
+            visitor.message.as_deref().unwrap_or(""),
+            &fields,
+        );
+    }
+
+    fn enter(&self, span: &Id) {
+        CURRENT_SPANS.with(|stack| stack.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &Id) {
+        let id = span.into_u64();
+        CURRENT_SPANS.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(position) = stack.iter().rposition(|entered| *entered == id) {
+                stack.remove(position);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(data) = self.inner.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            data.refs += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        let mut spans = self.inner.spans.lock().unwrap();
+        let id = span.into_u64();
+        let Some(data) = spans.get_mut(&id) else {
+            return false;
+        };
+        data.refs -= 1;
+        if data.refs > 0 {
+            return false;
+        }
+        spans.remove(&id);
+        true
+    }
+}
+
+/// Add a field, replacing earlier (outer) values of the same name
+///
+/// A `request_id` field sets the entry's request ID instead.
+fn push_field(
+    fields: &mut Vec<(&str, String)>,
+    request_id: &mut Option<String>,
+    name: &'static str,
+    value: &serde_json::Value,
+) {
+    if name == REQUEST_ID_FIELD {
+        *request_id = Some(match value {
+            serde_json::Value::String(id) => id.clone(),
+            other => other.to_string(),
+        });
+        return;
+    }
+    fields.retain(|(existing, _)| *existing != name);
+    fields.push((name, value.to_string()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Write};
+
+    /// Writer sharing its buffer with the test
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    fn capture() -> (TracingLogger, SharedBuffer) {
+        let buffer = SharedBuffer::default();
+        let logger = Logger::with_writer(Box::new(buffer.clone()));
+        (TracingLogger::new(logger), buffer)
+    }
+
+    #[test]
+    fn test_event_with_fields() {
+        let (subscriber, buffer) = capture();
+        subscriber.set_request_id(Some("req-1"));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(attempts = 3, ok = false, name = "x", "retrying \"call\"");
+        });
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["request_id"], "req-1");
+        assert_eq!(line["message"], "retrying \"call\"");
+        assert_eq!(line["attempts"], 3);
+        assert_eq!(line["ok"], false);
+        assert_eq!(line["name"], "x");
+        assert!(line["target"].as_str().unwrap().contains("tracing_adapter"));
+        assert!(line.get("span").is_none());
+    }
+
+    #[test]
+    fn test_nested_span_fields() {
+        let (subscriber, buffer) = capture();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("handler", request_id = "req-span", depth = 1);
+            let _outer = outer.enter();
+            let inner = tracing::debug_span!("parse", depth = 2, bytes = tracing::field::Empty);
+            inner.record("bytes", 128_u64);
+            let _inner = inner.enter();
+            tracing::info!("parsed");
+        });
+
+        let line = &buffer.lines()[0];
+        assert_eq!(line["request_id"], "req-span");
+        assert_eq!(line["span"], "parse");
+        assert_eq!(line["depth"], 2);
+        assert_eq!(line["bytes"], 128);
+        assert_eq!(line["level"], "INFO");
+    }
+
+    #[test]
+    fn test_span_closed_after_drop() {
+        let (subscriber, buffer) = capture();
+        let handle = subscriber.clone();
+
+        tracing::subscriber::with_default(subscriber, || {
+            {
+                let span = tracing::info_span!("request");
+                let _entered = span.enter();
+                tracing::info!("inside");
+            }
+            tracing::info!("outside");
+        });
+
+        let lines = buffer.lines();
+        assert_eq!(lines[0]["span"], "request");
+        assert!(lines[1].get("span").is_none());
+        assert!(handle.inner.spans.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_respects_min_level() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::with_writer(Box::new(buffer.clone()));
+        logger.set_min_level(LogLevel::Warn);
+
+        tracing::subscriber::with_default(TracingLogger::new(logger), || {
+            tracing::trace!("trace");
+            tracing::info!("info");
+            tracing::error!("error");
+        });
+
+        let lines = buffer.lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["level"], "ERROR");
+    }
+
+    #[test]
+    fn test_log_level_mapping() {
+        assert_eq!(log_level(Level::TRACE), LogLevel::Debug);
+        assert_eq!(log_level(Level::DEBUG), LogLevel::Debug);
+        assert_eq!(log_level(Level::INFO), LogLevel::Info);
+        assert_eq!(log_level(Level::WARN), LogLevel::Warn);
+        assert_eq!(log_level(Level::ERROR), LogLevel::Error);
+    }
+}
@@ -0,0 +1,69 @@
+// Panics the Runtime Survives
+//
+// Background tasks (background.rs) and shutdown hooks (extension.rs) run
+// under catch_unwind: a panic there is logged and the runtime carries on.
+// A panic hook still runs for these panics, before the unwind is caught, so
+// a hook reporting crashes (the bootstrap's crash record) asks
+// `panic_is_caught` to tell them apart from panics that end the process.
+//
+// The marker is a per-thread depth, so it is only seen by panics raised on
+// the thread that is catching them.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+thread_local! {
+    /// Number of `catch` calls active on this thread
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Run `f`, catching a panic, with [`panic_is_caught`] true meanwhile
+pub(crate) fn catch<R>(f: impl FnOnce() -> R) -> thread::Result<R> {
+    CATCHING.with(|depth| depth.set(depth.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|depth| depth.set(depth.get() - 1));
+    result
+}
+
+/// Whether a panic raised on this thread now will be caught by the runtime
+///
+/// True while a background task or shutdown hook runs. Panic hooks call it
+/// to skip crash reporting for panics the process survives.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::panic_is_caught;
+///
+/// std::panic::set_hook(Box::new(|_| {
+///     if !panic_is_caught() {
+///         eprintln!("crashing");
+///     }
+/// }));
+/// # let _ = std::panic::take_hook();
+/// ```
+#[must_use]
+pub fn panic_is_caught() -> bool {
+    CATCHING.with(Cell::get) > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marked_while_catching() {
+        assert!(!panic_is_caught());
+        let inner = catch(|| catch(panic_is_caught).unwrap() && panic_is_caught());
+        assert!(inner.unwrap());
+        assert!(!panic_is_caught());
+    }
+
+    #[test]
+    fn test_unmarked_after_caught_panic() {
+        let result = catch(|| panic!("task bug"));
+        assert!(result.is_err());
+        assert!(!panic_is_caught());
+    }
+}
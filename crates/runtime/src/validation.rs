@@ -0,0 +1,428 @@
+// Input Validation Middleware
+//
+// A declarative, JSON-Schema-lite check of event bodies:
+// - required / optional fields (dot paths reach into nested objects)
+// - JSON types (string, number, integer, boolean, object, array)
+// - maximum length (characters for strings, items for arrays)
+//
+// ValidationMiddleware runs the schema before the handler and answers
+// invalid events with a 400 proxy response listing every violation, so
+// handlers only ever see well-formed input.
+
+use crate::context::Context;
+use crate::middleware::{json_response, Flow, Middleware};
+use std::fmt;
+
+/// Expected JSON type of a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// JSON string
+    String,
+    /// Any JSON number
+    Number,
+    /// JSON number without a fractional part
+    Integer,
+    /// `true` or `false`
+    Boolean,
+    /// JSON object
+    Object,
+    /// JSON array
+    Array,
+}
+
+impl FieldType {
+    fn matches(self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Boolean => value.is_boolean(),
+            Self::Object => value.is_object(),
+            Self::Array => value.is_array(),
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+            Self::Object => "object",
+            Self::Array => "array",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One schema violation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Dot path of the offending field (empty for the whole body)
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.field.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.field, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[derive(Debug, Clone)]
+struct FieldRule {
+    path: String,
+    field_type: FieldType,
+    required: bool,
+    max_length: Option<usize>,
+}
+
+/// Declarative rules for an event body
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{FieldType, Schema};
+///
+/// let schema = Schema::new()
+///     .required("name", FieldType::String)
+///     .max_length("name", 8)
+///     .optional("address.zip", FieldType::String);
+///
+/// assert!(schema.validate_str(r#"{"name":"Ada"}"#).is_ok());
+///
+/// let errors = schema
+///     .validate_str(r#"{"name":"Ada Lovelace","address":{"zip":12345}}"#)
+///     .unwrap_err();
+/// assert_eq!(errors[0].to_string(), "name: longer than 8");
+/// assert_eq!(errors[1].to_string(), "address.zip: expected string");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    rules: Vec<FieldRule>,
+}
+
+impl Schema {
+    /// Schema accepting any JSON object
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `path` to be present with type `field_type`
+    #[must_use]
+    pub fn required(self, path: &str, field_type: FieldType) -> Self {
+        self.rule(path, field_type, true)
+    }
+
+    /// Check the type of `path` only when it is present (and not null)
+    #[must_use]
+    pub fn optional(self, path: &str, field_type: FieldType) -> Self {
+        self.rule(path, field_type, false)
+    }
+
+    /// Limit `path` to `max` characters (strings) or items (arrays)
+    ///
+    /// Applies to the rule previously declared for `path`; an undeclared
+    /// path becomes an optional string.
+    #[must_use]
+    pub fn max_length(mut self, path: &str, max: usize) -> Self {
+        if !self.rules.iter().any(|rule| rule.path == path) {
+            self = self.optional(path, FieldType::String);
+        }
+        for rule in self.rules.iter_mut().filter(|rule| rule.path == path) {
+            rule.max_length = Some(max);
+        }
+        self
+    }
+
+    fn rule(mut self, path: &str, field_type: FieldType, required: bool) -> Self {
+        self.rules.push(FieldRule {
+            path: path.to_string(),
+            field_type,
+            required,
+            max_length: None,
+        });
+        self
+    }
+
+    /// Validate a parsed body, collecting every violation
+    ///
+    /// # Errors
+    ///
+    /// Returns all violations (in rule order) if the body does not match.
+    pub fn validate(&self, body: &serde_json::Value) -> Result<(), Vec<ValidationError>> {
+        if !body.is_object() {
+            return Err(vec![ValidationError {
+                field: String::new(),
+                message: "expected a JSON object".to_string(),
+            }]);
+        }
+
+        let errors: Vec<ValidationError> = self
+            .rules
+            .iter()
+            .filter_map(|rule| check(rule, lookup(body, &rule.path)))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parse and validate a JSON body
+    ///
+    /// # Errors
+    ///
+    /// Returns a single violation for invalid JSON, otherwise as
+    /// [`Schema::validate`].
+    pub fn validate_str(&self, body: &str) -> Result<(), Vec<ValidationError>> {
+        match serde_json::from_str(body) {
+            Ok(value) => self.validate(&value),
+            Err(e) => Err(vec![ValidationError {
+                field: String::new(),
+                message: format!("invalid JSON: {e}"),
+            }]),
+        }
+    }
+}
+
+/// Value at a dot path (`None` if any segment is missing or not an object)
+fn lookup<'v>(body: &'v serde_json::Value, path: &str) -> Option<&'v serde_json::Value> {
+    path.split('.')
+        .try_fold(body, |value, segment| value.as_object()?.get(segment))
+}
+
+fn check(rule: &FieldRule, value: Option<&serde_json::Value>) -> Option<ValidationError> {
+    let error = |message: String| {
+        Some(ValidationError {
+            field: rule.path.clone(),
+            message,
+        })
+    };
+
+    let value = match value {
+        None | Some(serde_json::Value::Null) if rule.required => {
+            return error("required".to_string())
+        }
+        None | Some(serde_json::Value::Null) => return None,
+        Some(value) => value,
+    };
+
+    if !rule.field_type.matches(value) {
+        return error(format!("expected {}", rule.field_type));
+    }
+
+    let length = match value {
+        serde_json::Value::String(text) => text.chars().count(),
+        serde_json::Value::Array(items) => items.len(),
+        _ => return None,
+    };
+    match rule.max_length {
+        Some(max) if length > max => error(format!("longer than {max}")),
+        _ => None,
+    }
+}
+
+/// Where the validated JSON lives in the event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum BodySource {
+    /// The event itself (direct invoke, `EventBridge` detail consumers, ...)
+    #[default]
+    Event,
+    /// The `body` string of an API Gateway / ALB proxy event
+    ProxyBody,
+}
+
+/// Middleware rejecting events that do not match a [`Schema`]
+///
+/// Invalid events get a 400 proxy response:
+/// `{"message":"Invalid request","errors":["name: required", ...]}`.
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::{Context, FieldType, Pipeline, Schema, ValidationMiddleware};
+///
+/// let schema = Schema::new().required("order_id", FieldType::Integer);
+/// let pipeline = Pipeline::new().with(ValidationMiddleware::new(schema).proxy_body());
+///
+/// let event = r#"{"httpMethod":"POST","body":"{\"order_id\":\"abc\"}"}"#;
+/// let response = pipeline.handle(Context::default(), event, |_, _| unreachable!());
+/// assert!(response.contains(r#""statusCode":400"#));
+/// assert!(response.contains("order_id: expected integer"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ValidationMiddleware {
+    schema: Schema,
+    source: BodySource,
+}
+
+impl ValidationMiddleware {
+    /// Validate the raw event against `schema`
+    #[must_use]
+    pub fn new(schema: Schema) -> Self {
+        Self {
+            schema,
+            source: BodySource::Event,
+        }
+    }
+
+    /// Validate the JSON inside an API Gateway / ALB event's `body` string
+    #[must_use]
+    pub fn proxy_body(mut self) -> Self {
+        self.source = BodySource::ProxyBody;
+        self
+    }
+
+    fn validate(&self, event_body: &str) -> Result<(), Vec<ValidationError>> {
+        match self.source {
+            BodySource::Event => self.schema.validate_str(event_body),
+            BodySource::ProxyBody => {
+                let event: serde_json::Value = serde_json::from_str(event_body).unwrap_or_default();
+                let body = event
+                    .get("body")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("");
+                self.schema.validate_str(body)
+            }
+        }
+    }
+}
+
+impl Middleware for ValidationMiddleware {
+    fn before(&self, _context: &mut Context, event_body: &str) -> Flow {
+        match self.validate(event_body) {
+            Ok(()) => Flow::Continue,
+            Err(errors) => {
+                let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                Flow::Respond(json_response(
+                    400,
+                    &serde_json::json!({"message": "Invalid request", "errors": errors}),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::Pipeline;
+
+    fn schema() -> Schema {
+        Schema::new()
+            .required("name", FieldType::String)
+            .max_length("name", 5)
+            .required("age", FieldType::Integer)
+            .optional("tags", FieldType::Array)
+            .max_length("tags", 2)
+            .optional("user.admin", FieldType::Boolean)
+    }
+
+    fn messages(result: Result<(), Vec<ValidationError>>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_body() {
+        assert!(schema()
+            .validate_str(r#"{"name":"Ada","age":36,"tags":["a"],"user":{"admin":false}}"#)
+            .is_ok());
+        // Optional fields may be absent or null
+        assert!(schema()
+            .validate_str(r#"{"name":"Ada","age":36,"tags":null}"#)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_collects_all_violations() {
+        let errors = messages(schema().validate_str(
+            r#"{"name":"Ada Lovelace","age":36.5,"tags":[1,2,3],"user":{"admin":"yes"}}"#,
+        ));
+        assert_eq!(
+            errors,
+            [
+                "name: longer than 5",
+                "age: expected integer",
+                "tags: longer than 2",
+                "user.admin: expected boolean",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_required_missing_or_null() {
+        let errors = messages(schema().validate_str(r#"{"name":null}"#));
+        assert_eq!(errors, ["name: required", "age: required"]);
+    }
+
+    #[test]
+    fn test_non_object_and_invalid_json() {
+        assert_eq!(
+            messages(schema().validate_str("[1,2]")),
+            ["expected a JSON object"]
+        );
+        assert!(messages(schema().validate_str("{nope"))[0].starts_with("invalid JSON"));
+    }
+
+    #[test]
+    fn test_max_length_counts_chars() {
+        let schema = Schema::new().max_length("name", 3);
+        assert!(schema.validate_str(r#"{"name":"ééé"}"#).is_ok());
+        assert!(schema.validate_str(r#"{"name":"éééé"}"#).is_err());
+        assert!(schema.validate_str("{}").is_ok());
+    }
+
+    #[test]
+    fn test_middleware_short_circuits_with_400() {
+        let pipeline = Pipeline::new().with(ValidationMiddleware::new(schema()));
+
+        let response = pipeline.handle(Context::default(), r#"{"age":1}"#, |_, _| {
+            panic!("handler must not run")
+        });
+        let value: serde_json::Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(value["statusCode"], 400);
+        let body: serde_json::Value =
+            serde_json::from_str(value["body"].as_str().unwrap()).unwrap();
+        assert_eq!(body["message"], "Invalid request");
+        assert_eq!(body["errors"][0], "name: required");
+
+        let ok = pipeline.handle(Context::default(), r#"{"name":"Ada","age":1}"#, |_, _| {
+            "handled".to_string()
+        });
+        assert_eq!(ok, "handled");
+    }
+
+    #[test]
+    fn test_middleware_proxy_body() {
+        let pipeline = Pipeline::new().with(ValidationMiddleware::new(schema()).proxy_body());
+
+        let event = r#"{"httpMethod":"POST","body":"{\"name\":\"Ada\",\"age\":1}"}"#;
+        assert_eq!(
+            pipeline.handle(Context::default(), event, |_, _| "handled".to_string()),
+            "handled"
+        );
+
+        let missing_body =
+            pipeline.handle(Context::default(), r#"{"httpMethod":"GET"}"#, |_, _| {
+                "handled".to_string()
+            });
+        assert!(missing_body.contains(r#""statusCode":400"#));
+    }
+}
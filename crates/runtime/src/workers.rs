@@ -0,0 +1,75 @@
+// Multi-Worker Mode (local emulation only)
+//
+// Real Lambda runs one invocation per execution environment, so the
+// bootstrap is single-threaded. Against a local emulator it is useful to
+// simulate concurrent executions: RUCHY_LAMBDA_WORKERS=K spawns K worker
+// threads, each with its own Runtime API connection, to load-test a handler
+// before deploying.
+//
+// The Lambda service sets AWS_LAMBDA_INITIALIZATION_TYPE; local emulators
+// do not. When it is present the worker count is forced back to 1.
+
+use crate::{Error, Result, Runtime};
+use std::env;
+use std::thread::{self, JoinHandle};
+
+/// Environment variable selecting the number of worker threads
+pub const WORKERS_ENV: &str = "RUCHY_LAMBDA_WORKERS";
+
+/// Environment variable only the Lambda service sets (not local emulators)
+const LAMBDA_SERVICE_ENV: &str = "AWS_LAMBDA_INITIALIZATION_TYPE";
+
+/// Whether the process runs inside the real Lambda service
+pub(crate) fn is_lambda_service() -> bool {
+    env::var_os(LAMBDA_SERVICE_ENV).is_some()
+}
+
+/// Parse a worker count (a positive integer)
+pub(crate) fn parse_workers(value: &str) -> Result<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(workers) if workers > 0 => Ok(workers),
+        _ => Err(Error::InitializationFailed(format!(
+            "Invalid {WORKERS_ENV} '{value}' (expected a positive integer)"
+        ))),
+    }
+}
+
+/// Spawn one thread per worker, each with its own Runtime API connection
+pub(crate) fn spawn<F>(runtime: &Runtime, workers: usize, worker: F) -> Vec<JoinHandle<()>>
+where
+    F: Fn(usize, &Runtime) + Send + Sync + 'static,
+{
+    let worker = std::sync::Arc::new(worker);
+
+    (0..workers)
+        .map(|id| {
+            let runtime = runtime.isolated();
+            let worker = std::sync::Arc::clone(&worker);
+            thread::Builder::new()
+                .name(format!("ruchy-worker-{id}"))
+                .spawn(move || worker(id, &runtime))
+                .expect("failed to spawn worker thread")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_workers() {
+        assert_eq!(parse_workers("4").unwrap(), 4);
+        assert_eq!(parse_workers(" 1 ").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_parse_workers_invalid() {
+        for value in ["0", "-2", "many", ""] {
+            assert!(
+                matches!(parse_workers(value), Err(Error::InitializationFailed(msg)) if msg.contains(WORKERS_ENV)),
+                "{value}"
+            );
+        }
+    }
+}
@@ -0,0 +1,457 @@
+// X-Ray Segment Emitter (feature = "xray")
+//
+// Lambda creates the function segment; a custom runtime only has to add
+// subsegments below it. Each subsegment is sent as an independent document to
+// the X-Ray daemon over UDP:
+//
+//   {"format": "json", "version": 1}\n{"type":"subsegment","trace_id":...}
+//
+// The daemon address comes from AWS_XRAY_DAEMON_ADDRESS, either
+// "host:port" or "udp:host:port tcp:host:port". Trace and parent IDs come
+// from the Lambda-Runtime-Trace-Id header (Context::trace_id); nothing is
+// emitted for unsampled invocations.
+//
+// No X-Ray SDK: std::net::UdpSocket plus serde_json.
+
+use crate::{Context, Error, Result};
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Environment variable with the X-Ray daemon address (set by Lambda)
+pub const XRAY_DAEMON_ADDRESS_ENV: &str = "AWS_XRAY_DAEMON_ADDRESS";
+
+/// Header line preceding every document sent to the daemon
+const DAEMON_HEADER: &str = "{\"format\": \"json\", \"version\": 1}\n";
+
+/// Parsed X-Ray trace header (`Root=...;Parent=...;Sampled=1`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceHeader {
+    /// Trace ID (`Root`)
+    pub root: String,
+    /// Parent segment ID (`Parent`), the Lambda function segment
+    pub parent: Option<String>,
+    /// Sampling decision (`Sampled`); `None` when deferred
+    pub sampled: Option<bool>,
+}
+
+impl TraceHeader {
+    /// Parse a `Lambda-Runtime-Trace-Id` / `X-Amzn-Trace-Id` value
+    ///
+    /// Returns `None` if the header has no `Root` field.
+    #[must_use]
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut root = None;
+        let mut parent = None;
+        let mut sampled = None;
+
+        for field in header.split(';') {
+            match field.trim().split_once('=') {
+                Some(("Root", value)) => root = Some(value.to_string()),
+                Some(("Parent", value)) => parent = Some(value.to_string()),
+                Some(("Sampled", "1")) => sampled = Some(true),
+                Some(("Sampled", "0")) => sampled = Some(false),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            root: root.filter(|root| !root.is_empty())?,
+            parent,
+            sampled,
+        })
+    }
+}
+
+/// A timed unit of work below the Lambda function segment
+///
+/// Created with [`XRayEmitter::begin_subsegment`] (handler execution) or
+/// [`Subsegment::child`] (user-defined spans), then sent with
+/// [`XRayEmitter::end`].
+#[derive(Debug, Clone)]
+pub struct Subsegment {
+    name: String,
+    id: String,
+    trace_id: String,
+    parent_id: Option<String>,
+    start_time: f64,
+    end_time: Option<f64>,
+    error: bool,
+    fault: bool,
+    annotations: Vec<(String, String)>,
+}
+
+impl Subsegment {
+    /// Start a subsegment under `parent_id` in trace `trace_id`
+    #[must_use]
+    pub fn new(name: &str, trace_id: &str, parent_id: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            id: new_id(),
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.map(str::to_string),
+            start_time: epoch_seconds(SystemTime::now()),
+            end_time: None,
+            error: false,
+            fault: false,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Start a nested subsegment (a user-defined span)
+    #[must_use]
+    pub fn child(&self, name: &str) -> Self {
+        Self::new(name, &self.trace_id, Some(&self.id))
+    }
+
+    /// Subsegment ID (16 hex digits)
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Trace ID this subsegment belongs to
+    #[must_use]
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// Mark the subsegment as a client error (4xx-style failure)
+    pub fn set_error(&mut self) {
+        self.error = true;
+    }
+
+    /// Mark the subsegment as a fault (handler error, 5xx-style failure)
+    pub fn set_fault(&mut self) {
+        self.fault = true;
+    }
+
+    /// Add an indexed annotation (searchable in the X-Ray console)
+    pub fn annotate(&mut self, key: &str, value: &str) {
+        self.annotations.push((key.to_string(), value.to_string()));
+    }
+
+    /// Record the end time (idempotent)
+    pub fn close(&mut self) {
+        if self.end_time.is_none() {
+            self.end_time = Some(epoch_seconds(SystemTime::now()));
+        }
+    }
+
+    /// Serialize as an X-Ray subsegment document
+    ///
+    /// Unclosed subsegments are sent with `"in_progress": true`.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut document = serde_json::json!({
+            "type": "subsegment",
+            "name": self.name,
+            "id": self.id,
+            "trace_id": self.trace_id,
+            "start_time": self.start_time,
+        });
+
+        if let Some(parent_id) = &self.parent_id {
+            document["parent_id"] = parent_id.as_str().into();
+        }
+        match self.end_time {
+            Some(end_time) => document["end_time"] = end_time.into(),
+            None => document["in_progress"] = true.into(),
+        }
+        if self.error {
+            document["error"] = true.into();
+        }
+        if self.fault {
+            document["fault"] = true.into();
+        }
+        if !self.annotations.is_empty() {
+            let annotations: serde_json::Map<String, serde_json::Value> = self
+                .annotations
+                .iter()
+                .map(|(key, value)| (key.clone(), value.as_str().into()))
+                .collect();
+            document["annotations"] = annotations.into();
+        }
+
+        document.to_string()
+    }
+}
+
+/// Sends subsegments to the X-Ray daemon over UDP
+///
+/// # Examples
+///
+/// ```no_run
+/// # use ruchy_lambda_runtime::{Runtime, XRayEmitter};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let runtime = Runtime::new()?;
+/// let xray = XRayEmitter::from_env()?;
+/// let (context, event_body) = runtime.next_invocation()?;
+///
+/// let mut handler = xray.as_ref().and_then(|xray| xray.begin_subsegment(&context, "handler"));
+/// let mut span = handler.as_ref().map(|handler| handler.child("parse"));
+/// // ... user work ...
+/// if let (Some(xray), Some(span)) = (&xray, span.as_mut()) {
+///     xray.end(span)?;
+/// }
+/// runtime.post_response(&context.request_id, &event_body)?;
+/// if let (Some(xray), Some(handler)) = (&xray, handler.as_mut()) {
+///     xray.end(handler)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct XRayEmitter {
+    socket: UdpSocket,
+    daemon: SocketAddr,
+}
+
+impl XRayEmitter {
+    /// Emitter for `AWS_XRAY_DAEMON_ADDRESS`, or `None` when it is unset
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the address cannot be parsed
+    /// or the local UDP socket cannot be bound.
+    pub fn from_env() -> Result<Option<Self>> {
+        match env::var(XRAY_DAEMON_ADDRESS_ENV) {
+            Ok(value) => Self::new(&value).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Emitter for a daemon address (`host:port` or `udp:host:port tcp:...`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InitializationFailed` if the address cannot be parsed
+    /// or the local UDP socket cannot be bound.
+    pub fn new(address: &str) -> Result<Self> {
+        let daemon = parse_daemon_address(address).ok_or_else(|| {
+            Error::InitializationFailed(format!(
+                "{XRAY_DAEMON_ADDRESS_ENV} is not a valid daemon address: {address}"
+            ))
+        })?;
+        let bind = if daemon.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind).map_err(|e| {
+            Error::InitializationFailed(format!("Failed to bind X-Ray UDP socket: {e}"))
+        })?;
+
+        Ok(Self { socket, daemon })
+    }
+
+    /// Daemon address documents are sent to
+    #[must_use]
+    pub fn daemon_address(&self) -> SocketAddr {
+        self.daemon
+    }
+
+    /// Start a subsegment under the invocation's function segment
+    ///
+    /// Returns `None` when the invocation has no trace header or is not
+    /// sampled.
+    #[must_use]
+    pub fn begin_subsegment(&self, context: &Context, name: &str) -> Option<Subsegment> {
+        let header = TraceHeader::parse(context.trace_id.as_deref()?)?;
+        if header.sampled != Some(true) {
+            return None;
+        }
+        Some(Subsegment::new(
+            name,
+            &header.root,
+            header.parent.as_deref(),
+        ))
+    }
+
+    /// Close a subsegment and send it to the daemon
+    ///
+    /// # Errors
+    ///
+    /// Returns the UDP send error, if any.
+    pub fn end(&self, subsegment: &mut Subsegment) -> io::Result<()> {
+        subsegment.close();
+        self.emit(subsegment)
+    }
+
+    /// Send a subsegment as-is (unclosed subsegments are marked in progress)
+    ///
+    /// # Errors
+    ///
+    /// Returns the UDP send error, if any.
+    pub fn emit(&self, subsegment: &Subsegment) -> io::Result<()> {
+        let datagram = format!("{DAEMON_HEADER}{}", subsegment.to_json());
+        self.socket.send_to(datagram.as_bytes(), self.daemon)?;
+        Ok(())
+    }
+}
+
+/// Resolve the UDP address from an `AWS_XRAY_DAEMON_ADDRESS` value
+fn parse_daemon_address(address: &str) -> Option<SocketAddr> {
+    let address = address.trim();
+    let udp = if address.contains(' ') {
+        address
+            .split_whitespace()
+            .find_map(|part| part.strip_prefix("udp:"))?
+    } else {
+        address.strip_prefix("udp:").unwrap_or(address)
+    };
+    udp.to_socket_addrs().ok()?.next()
+}
+
+/// Random 64-bit ID as 16 hex digits
+///
+/// `RandomState` is seeded randomly per process; the counter keeps IDs from
+/// the same hasher keys distinct.
+fn new_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}", hasher.finish())
+}
+
+/// Seconds since the Unix epoch with sub-second precision
+fn epoch_seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const TRACE: &str =
+        "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1";
+
+    fn context(trace_id: Option<&str>) -> Context {
+        Context {
+            request_id: "req-1".to_string(),
+            trace_id: trace_id.map(str::to_string),
+            ..Context::default()
+        }
+    }
+
+    #[test]
+    fn test_trace_header_parse() {
+        let header = TraceHeader::parse(TRACE).unwrap();
+        assert_eq!(header.root, "1-5759e988-bd862e3fe1be46a994272793");
+        assert_eq!(header.parent.as_deref(), Some("53995c3f42cd8ad8"));
+        assert_eq!(header.sampled, Some(true));
+
+        let header = TraceHeader::parse("Root=1-abc; Sampled=0").unwrap();
+        assert_eq!(header.parent, None);
+        assert_eq!(header.sampled, Some(false));
+
+        assert!(TraceHeader::parse("Parent=abc;Sampled=1").is_none());
+        assert!(TraceHeader::parse("").is_none());
+    }
+
+    #[test]
+    fn test_parse_daemon_address() {
+        assert_eq!(
+            parse_daemon_address("169.254.79.129:2000"),
+            Some("169.254.79.129:2000".parse().unwrap())
+        );
+        assert_eq!(
+            parse_daemon_address("tcp:127.0.0.1:2000 udp:127.0.0.2:2001"),
+            Some("127.0.0.2:2001".parse().unwrap())
+        );
+        assert_eq!(
+            parse_daemon_address("udp:127.0.0.1:2000"),
+            Some("127.0.0.1:2000".parse().unwrap())
+        );
+        assert_eq!(parse_daemon_address("not-an-address"), None);
+        assert_eq!(parse_daemon_address("tcp:127.0.0.1:2000 other"), None);
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_address() {
+        let err = XRayEmitter::new("nope").unwrap_err();
+        assert!(err.to_string().contains(XRAY_DAEMON_ADDRESS_ENV));
+    }
+
+    #[test]
+    fn test_subsegment_ids() {
+        let parent = Subsegment::new("handler", "1-abc", Some("53995c3f42cd8ad8"));
+        let child = parent.child("span");
+        assert_eq!(parent.id().len(), 16);
+        assert!(parent.id().chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(parent.id(), child.id());
+        assert_eq!(child.trace_id(), "1-abc");
+        assert_eq!(child.parent_id.as_deref(), Some(parent.id()));
+    }
+
+    #[test]
+    fn test_subsegment_json() {
+        let mut subsegment = Subsegment::new("handler", "1-abc", Some("53995c3f42cd8ad8"));
+        let open: serde_json::Value = serde_json::from_str(&subsegment.to_json()).unwrap();
+        assert_eq!(open["type"], "subsegment");
+        assert_eq!(open["in_progress"], true);
+        assert!(open.get("end_time").is_none());
+
+        subsegment.set_fault();
+        subsegment.annotate("request_id", "req-1");
+        subsegment.close();
+        let closed: serde_json::Value = serde_json::from_str(&subsegment.to_json()).unwrap();
+        assert_eq!(closed["name"], "handler");
+        assert_eq!(closed["trace_id"], "1-abc");
+        assert_eq!(closed["parent_id"], "53995c3f42cd8ad8");
+        assert_eq!(closed["fault"], true);
+        assert!(closed.get("error").is_none());
+        assert!(closed.get("in_progress").is_none());
+        assert_eq!(closed["annotations"]["request_id"], "req-1");
+        assert!(closed["end_time"].as_f64().unwrap() >= closed["start_time"].as_f64().unwrap());
+    }
+
+    #[test]
+    fn test_begin_subsegment_requires_sampled_trace() {
+        let emitter = XRayEmitter::new("127.0.0.1:2000").unwrap();
+        assert!(emitter.begin_subsegment(&context(None), "h").is_none());
+        assert!(emitter
+            .begin_subsegment(&context(Some("Root=1-abc;Sampled=0")), "h")
+            .is_none());
+        assert!(emitter
+            .begin_subsegment(&context(Some("Root=1-abc")), "h")
+            .is_none());
+
+        let subsegment = emitter
+            .begin_subsegment(&context(Some(TRACE)), "h")
+            .unwrap();
+        assert_eq!(subsegment.trace_id(), "1-5759e988-bd862e3fe1be46a994272793");
+        assert_eq!(subsegment.parent_id.as_deref(), Some("53995c3f42cd8ad8"));
+    }
+
+    #[test]
+    fn test_end_sends_datagram_to_daemon() {
+        let daemon = UdpSocket::bind("127.0.0.1:0").unwrap();
+        daemon
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let emitter = XRayEmitter::new(&daemon.local_addr().unwrap().to_string()).unwrap();
+
+        let mut subsegment = emitter
+            .begin_subsegment(&context(Some(TRACE)), "handler")
+            .unwrap();
+        emitter.end(&mut subsegment).unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = daemon.recv(&mut buf).unwrap();
+        let datagram = std::str::from_utf8(&buf[..len]).unwrap();
+        let (header, document) = datagram.split_once('\n').unwrap();
+        assert_eq!(header, "{\"format\": \"json\", \"version\": 1}");
+        let document: serde_json::Value = serde_json::from_str(document).unwrap();
+        assert_eq!(document["name"], "handler");
+        assert_eq!(document["id"], subsegment.id());
+        assert!(document["end_time"].is_f64());
+    }
+}
@@ -10,13 +10,11 @@
 // 5. next_event() - returns "xyzzy"
 // 6. post_response() - returns early without sending
 
-use ruchy_lambda_runtime::{Error, LambdaEvent, Runtime};
-use serial_test::serial;
+use ruchy_lambda_runtime::{Error, LambdaEvent, Runtime, RuntimeConfig};
 use std::env;
 
 /// Test: Error Display trait produces correct message
 #[test]
-#[serial]
 fn test_error_display_message() {
     let error = Error::InitializationFailed("test failure".to_string());
     let message = format!("{}", error);
@@ -36,11 +34,8 @@ fn test_error_display_message() {
 
 /// Test: Runtime Debug trait produces correct format
 #[test]
-#[serial]
 fn test_runtime_debug_format() {
-    env::set_var("AWS_LAMBDA_RUNTIME_API", "test-endpoint:9001");
-
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::new("test-endpoint:9001"));
     let debug_str = format!("{:?}", runtime);
 
     // This will catch mutant #2 (returns Ok(Default::default()))
@@ -55,20 +50,15 @@ fn test_runtime_debug_format() {
         debug_str
     );
 
-    // Accept either custom or default endpoint (parallel test tolerance)
-    // The key is that Debug shows SOME endpoint value, not that it returns wrong format
     assert!(
-        debug_str.contains("test-endpoint:9001") || debug_str.contains("127.0.0.1:9001"),
+        debug_str.contains("test-endpoint:9001"),
         "Debug format should show endpoint value, got: {}",
         debug_str
     );
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: LambdaEvent can be serialized and deserialized correctly
 #[test]
-#[serial]
 fn test_lambda_event_roundtrip() {
     // Create an event
     let json = r#"{"requestContext":{"requestId":"test-123","accountId":"123456","stage":"prod"},"body":"test-body"}"#;
@@ -91,14 +81,11 @@ fn test_lambda_event_roundtrip() {
     assert!(serialized.contains("test-body"));
 }
 
-/// Test: Runtime stores correct endpoint from environment
+/// Test: Runtime stores the configured endpoint
 #[test]
-#[serial]
 fn test_runtime_stores_correct_endpoint() {
     let custom_endpoint = "custom-api.lambda.amazonaws.com:8080";
-    env::set_var("AWS_LAMBDA_RUNTIME_API", custom_endpoint);
-
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::new(custom_endpoint));
 
     // Validate via Debug format (shows endpoint)
     let debug = format!("{:?}", runtime);
@@ -107,13 +94,10 @@ fn test_runtime_stores_correct_endpoint() {
         "Runtime should store the correct endpoint, got: {}",
         debug
     );
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: Runtime uses default endpoint when env var not set
 #[test]
-#[serial]
 fn test_runtime_default_endpoint() {
     env::remove_var("AWS_LAMBDA_RUNTIME_API");
 
@@ -130,7 +114,6 @@ fn test_runtime_default_endpoint() {
 
 /// Test: Error can be converted to Box<dyn Error>
 #[test]
-#[serial]
 fn test_error_type_conversion() {
     let error: Box<dyn std::error::Error> =
         Box::new(Error::InitializationFailed("test".to_string()));
@@ -141,7 +124,6 @@ fn test_error_type_conversion() {
 
 /// Test: Error implements Send and Sync
 #[test]
-#[serial]
 fn test_error_is_send_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
     assert_send_sync::<Error>();
@@ -149,13 +131,9 @@ fn test_error_is_send_sync() {
 
 /// Test: Multiple Runtime instances can coexist
 #[test]
-#[serial]
 fn test_multiple_runtimes() {
-    env::set_var("AWS_LAMBDA_RUNTIME_API", "endpoint1:9001");
-    let runtime1 = Runtime::new().expect("Runtime 1 should initialize");
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", "endpoint2:9002");
-    let runtime2 = Runtime::new().expect("Runtime 2 should initialize");
+    let runtime1 = Runtime::from_config(RuntimeConfig::new("endpoint1:9001"));
+    let runtime2 = Runtime::from_config(RuntimeConfig::new("endpoint2:9002"));
 
     // Each should have its own endpoint
     let debug1 = format!("{:?}", runtime1);
@@ -163,17 +141,12 @@ fn test_multiple_runtimes() {
 
     assert!(debug1.contains("endpoint1:9001"));
     assert!(debug2.contains("endpoint2:9002"));
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: Runtime can be cloned
 #[test]
-#[serial]
 fn test_runtime_clone() {
-    env::set_var("AWS_LAMBDA_RUNTIME_API", "clone-test:9001");
-
-    let runtime1 = Runtime::new().expect("Runtime should initialize");
+    let runtime1 = Runtime::from_config(RuntimeConfig::new("clone-test:9001"));
     let runtime2 = runtime1.clone();
 
     // Both should have same endpoint
@@ -195,13 +168,10 @@ fn test_runtime_clone() {
     // Both should produce similar output structure
     assert!(debug1.contains("Runtime"));
     assert!(debug2.contains("Runtime"));
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: LambdaEvent validates required fields
 #[test]
-#[serial]
 fn test_lambda_event_required_fields() {
     // Minimal event with only required fields
     let json = r#"{"requestContext":{"requestId":"min"},"body":""}"#;
@@ -214,7 +184,6 @@ fn test_lambda_event_required_fields() {
 
 /// Test: LambdaEvent handles optional fields
 #[test]
-#[serial]
 fn test_lambda_event_optional_fields() {
     // Event with optional fields
     let json = r#"{"requestContext":{"requestId":"id","accountId":"","stage":""},"body":"data"}"#;
@@ -230,7 +199,6 @@ fn test_lambda_event_optional_fields() {
 
 /// Test: Error Debug trait shows useful information
 #[test]
-#[serial]
 fn test_error_debug_format() {
     let error = Error::InitializationFailed("detailed error info".to_string());
     let debug = format!("{:?}", error);
@@ -249,12 +217,9 @@ fn test_error_debug_format() {
 
 /// Test: Runtime initialization is deterministic
 #[test]
-#[serial]
 fn test_runtime_initialization_deterministic() {
-    env::set_var("AWS_LAMBDA_RUNTIME_API", "test:9001");
-
-    let runtime1 = Runtime::new().expect("Runtime 1");
-    let runtime2 = Runtime::new().expect("Runtime 2");
+    let runtime1 = Runtime::from_config(RuntimeConfig::new("test:9001"));
+    let runtime2 = Runtime::from_config(RuntimeConfig::new("test:9001"));
 
     // Both should produce same debug output
     let debug1 = format!("{:?}", runtime1);
@@ -263,8 +228,6 @@ fn test_runtime_initialization_deterministic() {
     // Should both contain the same endpoint
     assert!(debug1.contains("test:9001"));
     assert!(debug2.contains("test:9001"));
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 // NOTE: Tests for next_event() and post_response() actual HTTP behavior
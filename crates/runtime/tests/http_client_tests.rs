@@ -1,25 +1,27 @@
 // Extreme TDD: Minimal HTTP Client Integration Tests
-use serial_test::serial;
 // Written FIRST before implementation
 //
 // Goal: Validate minimal HTTP client works correctly for Lambda Runtime API
 // Target: Replace reqwest to save ~350KB in binary size
 //
 // Phase 3: Converted to blocking I/O (removed tokio)
+//
+// The mock server is served over the testkit's in-memory transport: a dial
+// is queued before it returns, so no port is bound and there is no server
+// start-up to sleep through.
 
+use ruchy_lambda_testkit::transport::{memory_listener, MemoryListener};
 use std::io::{Read, Write};
-use std::net::TcpListener;
 use std::thread;
-use std::time::Duration;
 
 // We can't test http_client directly as it's a private module
 // Instead, we test through the Runtime public API
 // These tests ensure the HTTP client works when integrated
 
 /// Mock Lambda API server for GET requests
-fn mock_lambda_get_server(listener: TcpListener, response_body: String) {
+fn mock_lambda_get_server(listener: MemoryListener, response_body: String) {
     thread::spawn(move || {
-        if let Ok((mut socket, _)) = listener.accept() {
+        if let Ok(mut socket) = listener.accept() {
             let mut buffer = vec![0u8; 1024];
             let _ = socket.read(&mut buffer);
 
@@ -37,9 +39,9 @@ fn mock_lambda_get_server(listener: TcpListener, response_body: String) {
 }
 
 /// Mock Lambda API server for POST requests
-fn mock_lambda_post_server(listener: TcpListener) {
+fn mock_lambda_post_server(listener: MemoryListener) {
     thread::spawn(move || {
-        if let Ok((mut socket, _)) = listener.accept() {
+        if let Ok(mut socket) = listener.accept() {
             let mut buffer = vec![0u8; 4096];
             let _ = socket.read(&mut buffer);
 
@@ -53,24 +55,18 @@ fn mock_lambda_post_server(listener: TcpListener) {
 
 /// Test: Minimal HTTP client can make GET requests
 #[test]
-#[serial]
 fn test_minimal_http_get_request() {
     // This test will validate the HTTP client works when we switch from reqwest
     // For now, it validates the current implementation still works
 
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap().to_string();
+    let (dialer, listener) = memory_listener();
 
     let event_json = r#"{"requestContext":{"requestId":"test-123"},"body":"test"}"#;
     mock_lambda_get_server(listener, event_json.to_string());
 
-    thread::sleep(Duration::from_millis(100));
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", &addr);
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     let result = runtime.next_event();
     assert!(result.is_ok(), "GET request should succeed");
@@ -80,91 +76,67 @@ fn test_minimal_http_get_request() {
         body.contains("test-123") || body.contains("test"),
         "Should receive event body"
     );
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: Minimal HTTP client can make POST requests
 #[test]
-#[serial]
 fn test_minimal_http_post_request() {
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap().to_string();
+    let (dialer, listener) = memory_listener();
 
     mock_lambda_post_server(listener);
 
-    thread::sleep(Duration::from_millis(100));
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", &addr);
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     let result = runtime.post_response("test-id", r#"{"status":"ok"}"#);
     assert!(result.is_ok(), "POST request should succeed");
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: HTTP client handles connection errors gracefully
 #[test]
-#[serial]
 fn test_http_client_connection_error() {
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    // Use non-existent endpoint
-    env::set_var("AWS_LAMBDA_RUNTIME_API", "127.0.0.1:19999");
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    // No server listening
+    let (dialer, listener) = memory_listener();
+    drop(listener);
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     let result = runtime.next_event();
     assert!(result.is_err(), "Should error on connection failure");
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: HTTP client handles server closing connection
 #[test]
-#[serial]
 fn test_http_client_connection_closed() {
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap().to_string();
+    let (dialer, listener) = memory_listener();
 
     // Server that immediately closes connection
     thread::spawn(move || {
-        if let Ok((socket, _)) = listener.accept() {
+        if let Ok(socket) = listener.accept() {
             // Close immediately without sending response
             drop(socket);
         }
     });
 
-    thread::sleep(Duration::from_millis(100));
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", &addr);
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     let result = runtime.next_event();
     assert!(
         result.is_err(),
         "Should error when connection closes unexpectedly"
     );
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: HTTP client handles large responses
 #[test]
-#[serial]
 fn test_http_client_large_response() {
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap().to_string();
+    let (dialer, listener) = memory_listener();
 
     // Create 10KB response
     let large_body = "x".repeat(10240);
@@ -175,71 +147,48 @@ fn test_http_client_large_response() {
 
     mock_lambda_get_server(listener, event_json.clone());
 
-    thread::sleep(Duration::from_millis(100));
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", &addr);
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     let result = runtime.next_event();
     assert!(result.is_ok(), "Should handle large responses");
 
     let (_request_id, body) = result.unwrap();
     assert!(body.len() > 10000, "Should receive full large body");
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: HTTP client handles empty response body
-/// Note: Ignored due to timing issues with minimal HTTP client and empty bodies
-/// In practice, Lambda Runtime API always returns non-empty JSON
+/// (was ignored for timing issues with the TCP mock server)
 #[test]
-#[serial]
-#[ignore]
 fn test_http_client_empty_body() {
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap().to_string();
+    let (dialer, listener) = memory_listener();
 
     mock_lambda_get_server(listener, "".to_string());
 
-    thread::sleep(Duration::from_millis(100));
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", &addr);
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     let result = runtime.next_event();
     assert!(result.is_ok(), "Should handle empty body");
 
     let (_request_id, body) = result.unwrap();
     assert_eq!(body, "", "Should return empty string");
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
 
 /// Test: POST request with large body
 #[test]
-#[serial]
 fn test_http_post_large_body() {
-    use ruchy_lambda_runtime::Runtime;
-    use std::env;
+    use ruchy_lambda_runtime::{Runtime, RuntimeConfig};
 
-    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
-    let addr = listener.local_addr().unwrap().to_string();
+    let (dialer, listener) = memory_listener();
 
     mock_lambda_post_server(listener);
 
-    thread::sleep(Duration::from_millis(100));
-
-    env::set_var("AWS_LAMBDA_RUNTIME_API", &addr);
-    let runtime = Runtime::new().expect("Runtime should initialize");
+    let runtime = Runtime::from_config(RuntimeConfig::default()).with_dialer(dialer);
 
     // Create 5KB response body
     let large_response = format!(r#"{{"data":"{}"}}"#, "x".repeat(5000));
 
     let result = runtime.post_response("test-id", &large_response);
     assert!(result.is_ok(), "Should handle large POST body");
-
-    env::remove_var("AWS_LAMBDA_RUNTIME_API");
 }
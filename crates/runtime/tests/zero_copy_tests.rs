@@ -97,3 +97,65 @@ mod allocation_tests {
         );
     }
 }
+
+/// Zero-copy invocation path against a mock Runtime API
+mod invocation {
+    use ruchy_lambda_runtime::{invocation_deadline, Runtime, RuntimeConfig};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// Serve `count` `/next` responses, one per connection
+    fn serve_events(listener: TcpListener, count: usize) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            for i in 0..count {
+                let (mut socket, _) = listener.accept().unwrap();
+                let mut request = [0u8; 1024];
+                let _ = socket.read(&mut request).unwrap();
+                let body = format!(r#"{{"event":{i}}}"#);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nLambda-Runtime-Aws-Request-Id: zc-{i}\r\nLambda-Runtime-Deadline-Ms: 1700000000000\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = socket.write_all(response.as_bytes());
+            }
+        })
+    }
+
+    #[test]
+    fn test_next_invocation_into_borrows_buffer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+        let server = serve_events(listener, 2);
+        let runtime = Runtime::from_config(RuntimeConfig::new(endpoint));
+
+        let mut buffer = Vec::with_capacity(16 * 1024);
+        let capacity = buffer.capacity();
+        let start = buffer.as_ptr() as usize;
+        let allocation = start..start + capacity;
+
+        for i in 0..2 {
+            let invocation = runtime.next_invocation_into(&mut buffer).unwrap();
+            assert_eq!(invocation.request_id, format!("zc-{i}"));
+            assert_eq!(
+                invocation.body_str().unwrap(),
+                format!(r#"{{"event":{i}}}"#)
+            );
+            assert_eq!(invocation.context().request_id, invocation.request_id);
+            // Outbound I/O on this thread is bounded by the invocation deadline
+            assert_eq!(
+                invocation_deadline(),
+                Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+            );
+
+            assert!(allocation.contains(&(invocation.request_id.as_ptr() as usize)));
+            assert!(allocation.contains(&(invocation.body.as_ptr() as usize)));
+        }
+
+        // The buffer is reused rather than reallocated
+        assert_eq!(buffer.capacity(), capacity);
+
+        server.join().unwrap();
+    }
+}
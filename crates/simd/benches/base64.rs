@@ -0,0 +1,47 @@
+// Base64 codec benchmark: NEON vs scalar
+//
+// Run on Graviton for the NEON numbers; on x86_64 both paths are scalar.
+//   cargo bench -p ruchy-lambda-simd --bench base64
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ruchy_lambda_simd::base64;
+
+/// Typical API Gateway binary body sizes (1KB .. 1MB)
+const SIZES: &[usize] = &[1024, 64 * 1024, 1024 * 1024];
+
+fn payload(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 31 % 251).to_le_bytes()[0]).collect()
+}
+
+fn bench_encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("base64_encode");
+    for &size in SIZES {
+        let input = payload(size);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("simd", size), &input, |b, input| {
+            b.iter(|| base64::encode(black_box(input)));
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", size), &input, |b, input| {
+            b.iter(|| base64::encode_scalar(black_box(input)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("base64_decode");
+    for &size in SIZES {
+        let input = base64::encode(&payload(size)).into_bytes();
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("simd", size), &input, |b, input| {
+            b.iter(|| base64::decode(black_box(input)));
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", size), &input, |b, input| {
+            b.iter(|| base64::decode_scalar(black_box(input)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_encode, bench_decode);
+criterion_main!(benches);
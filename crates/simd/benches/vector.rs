@@ -0,0 +1,66 @@
+// Vector kernel benchmarks (dot product, cosine similarity, matmul)
+//
+// Compare SIMD against scalar by toggling the arch features:
+//   cargo bench -p ruchy-lambda-simd --bench vector
+//   cargo bench -p ruchy-lambda-simd --bench vector --no-default-features
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ruchy_lambda_simd::{cosine_similarity, dot_product, matmul};
+
+/// Vector lengths: embedding sizes up to the 10K handler benchmark
+const LENGTHS: &[usize] = &[384, 1536, 10_000];
+
+/// Square matrix sizes for matmul
+const MATRIX_SIZES: &[usize] = &[64, 128, 256];
+
+fn vector(len: usize, scale: f32) -> Vec<f32> {
+    (0..len)
+        .map(|i| f32::from(u16::try_from(i % 1000).unwrap_or(0)) * scale)
+        .collect()
+}
+
+fn bench_dot_product(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dot_product");
+    for &len in LENGTHS {
+        let (a, b) = (vector(len, 0.001), vector(len, 0.002));
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+            bench.iter(|| dot_product(black_box(&a), black_box(&b)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_cosine_similarity(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cosine_similarity");
+    for &len in LENGTHS {
+        let (a, b) = (vector(len, 0.001), vector(len, 0.002));
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bench, _| {
+            bench.iter(|| cosine_similarity(black_box(&a), black_box(&b)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_matmul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matmul");
+    group.sample_size(20);
+    for &n in MATRIX_SIZES {
+        let (a, b) = (vector(n * n, 0.001), vector(n * n, 0.002));
+        let mut out = vec![0.0; n * n];
+        group.throughput(Throughput::Elements((n * n * n) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |bench, &n| {
+            bench.iter(|| matmul(black_box(&a), black_box(&b), &mut out, n, n, n));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_dot_product,
+    bench_cosine_similarity,
+    bench_matmul
+);
+criterion_main!(benches);
@@ -0,0 +1,431 @@
+// Base64 (RFC 4648, standard alphabet, padded)
+//
+// API Gateway / Function URL binary bodies arrive base64-encoded, so decoding
+// is on the request hot path.
+//
+// NEON strategy (48 bytes <-> 64 chars per iteration):
+// - encode: vld3q_u8 de-interleaves byte triples, shifts/masks build four
+//   6-bit index vectors, vqtbl4q_u8 maps them through the 64-byte alphabet,
+//   vst4q_u8 re-interleaves the characters
+// - decode: vld4q_u8 de-interleaves character quads, two vqtbl4q_u8 lookups
+//   cover ASCII 0..128 (invalid -> 0xFF), shifts pack 6-bit values into
+//   bytes, vst3q_u8 re-interleaves
+//
+// The scalar path handles the tail (including padding) and validates; a NEON
+// block containing an invalid character is re-scanned by the scalar path so
+// errors report the exact offset.
+
+use std::fmt;
+
+/// Standard base64 alphabet
+const ENCODE_TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// ASCII -> 6-bit value (0xFF = not in the alphabet)
+const DECODE_TABLE: [u8; 128] = build_decode_table();
+
+/// Padding character
+const PAD: u8 = b'=';
+
+const fn build_decode_table() -> [u8; 128] {
+    let mut table = [0xFF; 128];
+    let mut i = 0;
+    while i < 64 {
+        #[allow(clippy::cast_possible_truncation)] // i < 64
+        {
+            table[ENCODE_TABLE[i] as usize] = i as u8;
+        }
+        i += 1;
+    }
+    table
+}
+
+/// Base64 decoding error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Input length is not a multiple of 4
+    InvalidLength(usize),
+    /// Character outside the base64 alphabet
+    InvalidByte {
+        /// Offset in the input
+        offset: usize,
+        /// Offending byte
+        byte: u8,
+    },
+    /// `=` anywhere other than the last one or two characters
+    InvalidPadding(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "Invalid base64 length {len} (must be a multiple of 4)")
+            }
+            Self::InvalidByte { offset, byte } => {
+                write!(f, "Invalid base64 byte 0x{byte:02x} at offset {offset}")
+            }
+            Self::InvalidPadding(offset) => write!(f, "Invalid base64 padding at offset {offset}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Encoded length (with padding) for `len` input bytes
+#[must_use]
+pub const fn encoded_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+/// Encode `input` as padded base64 (NEON on aarch64)
+#[must_use]
+pub fn encode(input: &[u8]) -> String {
+    let mut out = Vec::with_capacity(encoded_len(input.len()));
+
+    #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+    let consumed = encode_neon(input, &mut out);
+
+    #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
+    let consumed = 0;
+
+    encode_scalar_into(&input[consumed..], &mut out);
+    into_string(out)
+}
+
+/// Decode padded base64 (NEON on aarch64)
+///
+/// # Errors
+///
+/// Returns `DecodeError` for a bad length, a character outside the alphabet,
+/// or misplaced padding
+pub fn decode(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    if !input.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidLength(input.len()));
+    }
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+
+    #[cfg(all(target_arch = "aarch64", feature = "neon"))]
+    let consumed = decode_neon(input, &mut out);
+
+    #[cfg(not(all(target_arch = "aarch64", feature = "neon")))]
+    let consumed = 0;
+
+    decode_scalar_into(&input[consumed..], consumed, &mut out)?;
+    Ok(out)
+}
+
+/// Scalar reference encoder (used for tails, other targets and benchmarks)
+#[must_use]
+pub fn encode_scalar(input: &[u8]) -> String {
+    let mut out = Vec::with_capacity(encoded_len(input.len()));
+    encode_scalar_into(input, &mut out);
+    into_string(out)
+}
+
+/// Scalar reference decoder (used for tails, other targets and benchmarks)
+///
+/// # Errors
+///
+/// Same as [`decode`]
+pub fn decode_scalar(input: &[u8]) -> Result<Vec<u8>, DecodeError> {
+    if !input.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidLength(input.len()));
+    }
+    let mut out = Vec::with_capacity(input.len() / 4 * 3);
+    decode_scalar_into(input, 0, &mut out)?;
+    Ok(out)
+}
+
+/// Encoder output is always ASCII
+fn into_string(out: Vec<u8>) -> String {
+    debug_assert!(out.is_ascii());
+    // SAFETY: every byte comes from ENCODE_TABLE or is PAD (ASCII)
+    unsafe { String::from_utf8_unchecked(out) }
+}
+
+fn encode_scalar_into(input: &[u8], out: &mut Vec<u8>) {
+    let chunks = input.chunks_exact(3);
+    let remainder = chunks.remainder();
+
+    for chunk in chunks {
+        let n = u32::from(chunk[0]) << 16 | u32::from(chunk[1]) << 8 | u32::from(chunk[2]);
+        out.extend_from_slice(&[
+            ENCODE_TABLE[(n >> 18) as usize & 0x3F],
+            ENCODE_TABLE[(n >> 12) as usize & 0x3F],
+            ENCODE_TABLE[(n >> 6) as usize & 0x3F],
+            ENCODE_TABLE[n as usize & 0x3F],
+        ]);
+    }
+
+    match *remainder {
+        [a] => {
+            let n = u32::from(a) << 16;
+            out.extend_from_slice(&[
+                ENCODE_TABLE[(n >> 18) as usize & 0x3F],
+                ENCODE_TABLE[(n >> 12) as usize & 0x3F],
+                PAD,
+                PAD,
+            ]);
+        }
+        [a, b] => {
+            let n = u32::from(a) << 16 | u32::from(b) << 8;
+            out.extend_from_slice(&[
+                ENCODE_TABLE[(n >> 18) as usize & 0x3F],
+                ENCODE_TABLE[(n >> 12) as usize & 0x3F],
+                ENCODE_TABLE[(n >> 6) as usize & 0x3F],
+                PAD,
+            ]);
+        }
+        _ => {}
+    }
+}
+
+/// Decode `input` (length multiple of 4); `base` offsets reported errors
+fn decode_scalar_into(input: &[u8], base: usize, out: &mut Vec<u8>) -> Result<(), DecodeError> {
+    let quads = input.len() / 4;
+
+    for (index, quad) in input.chunks_exact(4).enumerate() {
+        let offset = base + index * 4;
+        let padding = match (quad[2], quad[3]) {
+            (PAD, PAD) => 2,
+            (_, PAD) => 1,
+            _ => 0,
+        };
+        if padding > 0 && index + 1 != quads {
+            return Err(DecodeError::InvalidPadding(offset + 4 - padding));
+        }
+
+        let mut n = 0u32;
+        for (i, &byte) in quad[..4 - padding].iter().enumerate() {
+            let value = DECODE_TABLE.get(usize::from(byte)).copied().unwrap_or(0xFF);
+            if value == 0xFF {
+                return Err(if byte == PAD {
+                    DecodeError::InvalidPadding(offset + i)
+                } else {
+                    DecodeError::InvalidByte {
+                        offset: offset + i,
+                        byte,
+                    }
+                });
+            }
+            n |= u32::from(value) << (18 - 6 * i);
+        }
+
+        let bytes = n.to_be_bytes();
+        out.extend_from_slice(&bytes[1..4 - padding]);
+    }
+    Ok(())
+}
+
+/// NEON encoder for whole 48-byte blocks; returns input bytes consumed
+#[cfg(all(target_arch = "aarch64", feature = "neon"))]
+fn encode_neon(input: &[u8], out: &mut Vec<u8>) -> usize {
+    use std::arch::aarch64::{
+        uint8x16x4_t, vandq_u8, vdupq_n_u8, vld1q_u8_x4, vld3q_u8, vorrq_u8, vqtbl4q_u8,
+        vshlq_n_u8, vshrq_n_u8, vst4q_u8,
+    };
+
+    let blocks = input.len() / 48;
+    let mut buf = [0u8; 64];
+
+    // SAFETY: NEON is mandatory on aarch64; each block reads input[48 * block..][..48]
+    // (block < input.len() / 48) and writes the 64-byte stack buffer
+    unsafe {
+        let table = vld1q_u8_x4(ENCODE_TABLE.as_ptr());
+        let mask = vdupq_n_u8(0x3F);
+
+        for block in 0..blocks {
+            let src = vld3q_u8(input.as_ptr().add(block * 48));
+            let i0 = vshrq_n_u8::<2>(src.0);
+            let i1 = vandq_u8(
+                vorrq_u8(vshlq_n_u8::<4>(src.0), vshrq_n_u8::<4>(src.1)),
+                mask,
+            );
+            let i2 = vandq_u8(
+                vorrq_u8(vshlq_n_u8::<2>(src.1), vshrq_n_u8::<6>(src.2)),
+                mask,
+            );
+            let i3 = vandq_u8(src.2, mask);
+
+            let chars = uint8x16x4_t(
+                vqtbl4q_u8(table, i0),
+                vqtbl4q_u8(table, i1),
+                vqtbl4q_u8(table, i2),
+                vqtbl4q_u8(table, i3),
+            );
+            vst4q_u8(buf.as_mut_ptr(), chars);
+            out.extend_from_slice(&buf);
+        }
+    }
+
+    blocks * 48
+}
+
+/// NEON decoder for whole 64-char blocks; returns input chars consumed
+///
+/// Leaves the final quad (possible padding) and any block with an invalid
+/// character to the scalar path.
+#[cfg(all(target_arch = "aarch64", feature = "neon"))]
+fn decode_neon(input: &[u8], out: &mut Vec<u8>) -> usize {
+    use std::arch::aarch64::{
+        uint8x16_t, uint8x16x3_t, uint8x16x4_t, vdupq_n_u8, vld1q_u8_x4, vld4q_u8, vmaxvq_u8,
+        vorrq_u8, vqtbl4q_u8, vshlq_n_u8, vshrq_n_u8, vst3q_u8, vsubq_u8,
+    };
+
+    /// Map ASCII to 6-bit values; 0xFF for invalid, 0 (flagged via the
+    /// input's high bit) for non-ASCII
+    #[inline(always)]
+    unsafe fn lookup(low: uint8x16x4_t, high: uint8x16x4_t, chars: uint8x16_t) -> uint8x16_t {
+        vorrq_u8(
+            vqtbl4q_u8(low, chars),
+            vqtbl4q_u8(high, vsubq_u8(chars, vdupq_n_u8(64))),
+        )
+    }
+
+    let blocks = input.len().saturating_sub(4) / 64;
+    let mut buf = [0u8; 48];
+
+    // SAFETY: NEON is mandatory on aarch64; each block reads input[64 * block..][..64]
+    // (block < (input.len() - 4) / 64) and writes the 48-byte stack buffer
+    unsafe {
+        let low = vld1q_u8_x4(DECODE_TABLE.as_ptr());
+        let high = vld1q_u8_x4(DECODE_TABLE.as_ptr().add(64));
+
+        for block in 0..blocks {
+            let src = vld4q_u8(input.as_ptr().add(block * 64));
+            let v0 = lookup(low, high, src.0);
+            let v1 = lookup(low, high, src.1);
+            let v2 = lookup(low, high, src.2);
+            let v3 = lookup(low, high, src.3);
+
+            // Valid values are < 64 and valid input is ASCII: any high bit is an error
+            let values = vorrq_u8(vorrq_u8(v0, v1), vorrq_u8(v2, v3));
+            let chars = vorrq_u8(vorrq_u8(src.0, src.1), vorrq_u8(src.2, src.3));
+            if vmaxvq_u8(vorrq_u8(values, chars)) & 0x80 != 0 {
+                return block * 64;
+            }
+
+            let bytes = uint8x16x3_t(
+                vorrq_u8(vshlq_n_u8::<2>(v0), vshrq_n_u8::<4>(v1)),
+                vorrq_u8(vshlq_n_u8::<4>(v1), vshrq_n_u8::<2>(v2)),
+                vorrq_u8(vshlq_n_u8::<6>(v2), v3),
+            );
+            vst3q_u8(buf.as_mut_ptr(), bytes);
+            out.extend_from_slice(&buf);
+        }
+    }
+
+    blocks * 64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// RFC 4648 section 10 test vectors
+    const VECTORS: &[(&str, &str)] = &[
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        for (plain, encoded) in VECTORS {
+            assert_eq!(encode(plain.as_bytes()), *encoded);
+            assert_eq!(decode(encoded.as_bytes()).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_decode_table() {
+        assert_eq!(DECODE_TABLE[usize::from(b'A')], 0);
+        assert_eq!(DECODE_TABLE[usize::from(b'/')], 63);
+        assert_eq!(DECODE_TABLE[usize::from(b'=')], 0xFF);
+        assert_eq!(DECODE_TABLE[usize::from(b'@')], 0xFF);
+    }
+
+    #[test]
+    fn test_long_input_roundtrip() {
+        // Several SIMD blocks plus a padded tail
+        let plain: Vec<u8> = (0..1_000u32).map(|i| (i * 7).to_le_bytes()[0]).collect();
+        let encoded = encode(&plain);
+        assert_eq!(encoded, encode_scalar(&plain));
+        assert_eq!(decode(encoded.as_bytes()).unwrap(), plain);
+    }
+
+    #[test]
+    fn test_decode_invalid_length() {
+        assert_eq!(decode(b"Zm9"), Err(DecodeError::InvalidLength(3)));
+    }
+
+    #[test]
+    fn test_decode_invalid_byte_offset() {
+        // Invalid character deep inside a SIMD-sized input
+        let mut encoded = encode(&[0xAB; 300]).into_bytes();
+        encoded[130] = b'*';
+        assert_eq!(
+            decode(&encoded),
+            Err(DecodeError::InvalidByte {
+                offset: 130,
+                byte: b'*'
+            })
+        );
+
+        encoded[130] = 0xC3;
+        assert!(matches!(
+            decode(&encoded),
+            Err(DecodeError::InvalidByte { offset: 130, .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_invalid_padding() {
+        assert_eq!(decode(b"Zg==Zm9v"), Err(DecodeError::InvalidPadding(2)));
+        assert_eq!(decode(b"Z=9v"), Err(DecodeError::InvalidPadding(1)));
+        assert_eq!(decode(b"Zm=v"), Err(DecodeError::InvalidPadding(2)));
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        assert_eq!(encoded_len(0), 0);
+        assert_eq!(encoded_len(1), 4);
+        assert_eq!(encoded_len(3), 4);
+        assert_eq!(encoded_len(4), 8);
+    }
+
+    #[test]
+    fn test_decode_error_display() {
+        let err = DecodeError::InvalidByte {
+            offset: 5,
+            byte: b'*',
+        };
+        assert_eq!(err.to_string(), "Invalid base64 byte 0x2a at offset 5");
+    }
+
+    proptest! {
+        #[test]
+        fn roundtrip(plain in prop::collection::vec(any::<u8>(), 0..512)) {
+            let encoded = encode(&plain);
+            prop_assert_eq!(encoded.len(), encoded_len(plain.len()));
+            prop_assert_eq!(decode(encoded.as_bytes()).unwrap(), plain);
+        }
+
+        #[test]
+        fn simd_matches_scalar(plain in prop::collection::vec(any::<u8>(), 0..512)) {
+            let encoded = encode(&plain);
+            prop_assert_eq!(&encoded, &encode_scalar(&plain));
+            prop_assert_eq!(decode(encoded.as_bytes()), decode_scalar(encoded.as_bytes()));
+        }
+
+        #[test]
+        fn garbage_never_panics(input in prop::collection::vec(any::<u8>(), 0..256)) {
+            prop_assert_eq!(decode(&input), decode_scalar(&input));
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// Ruchy Lambda SIMD
+// ARM NEON kernels shared by the bootstrap, runtimes and user handlers
+// (extracted from crates/bootstrap/src/simd_ops.rs)
+//
+// Every kernel has:
+// - a NEON path (aarch64, Graviton2+)
+// - a scalar path (all other targets, and the reference in tests/benches)
+//
+// Unsafe code is confined to the NEON paths (std::arch intrinsics).
+
+#![warn(missing_docs, clippy::all, clippy::pedantic, clippy::cargo)]
+#![allow(clippy::module_name_repetitions, clippy::multiple_crate_versions)]
+
+//! ARM NEON accelerated kernels for Ruchy Lambda
+//!
+//! # Features
+//!
+//! - `neon` (default): aarch64 NEON paths
+//! - `x86` (default): `x86_64` AVX2+FMA / SSE paths
+//!
+//! Disabling a feature falls back to the scalar implementation on that target.
+//!
+//! # Examples
+//!
+//! ```
+//! use ruchy_lambda_simd::{cosine_similarity, dot_product};
+//!
+//! let a = [1.0f32, 2.0, 3.0, 4.0];
+//! let b = [0.5f32; 4];
+//! assert!((dot_product(&a, &b) - 5.0).abs() < 1e-6);
+//! assert!((cosine_similarity(&a, &a) - 1.0).abs() < 1e-6);
+//! ```
+//!
+//! ```
+//! use ruchy_lambda_simd::base64;
+//!
+//! let encoded = base64::encode(b"hello lambda");
+//! assert_eq!(encoded, "aGVsbG8gbGFtYmRh");
+//! assert_eq!(base64::decode(encoded.as_bytes()).unwrap(), b"hello lambda");
+//! ```
+
+/// Base64 codec (RFC 4648), NEON accelerated
+pub mod base64;
+mod vector;
+
+pub use vector::{
+    benchmark_dot_product, cosine_similarity, dot_product, euclidean_distance, matmul, max, min,
+    sum, SimdElement,
+};
@@ -0,0 +1,44 @@
+//! Deterministic time for handler tests
+//!
+//! Re-exports the runtime's [`Clock`] abstraction with its [`ManualClock`]:
+//! hand a clone to the code under test (`RetryPolicy::with_clock`,
+//! `InMemoryStore::with_clock`, `Logger::set_clock`, ...) and advance the
+//! one the test keeps. [`context_with_remaining`] builds an invocation
+//! [`Context`] whose deadline is measured from such a clock.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use ruchy_lambda_runtime::Context;
+pub use ruchy_lambda_runtime::{Clock, ManualClock, SharedClock, SystemClock};
+
+/// Invocation context for `request_id` whose deadline is `remaining` after
+/// `clock`'s current time
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_testkit::clock::{context_with_remaining, ManualClock};
+/// use std::time::Duration;
+///
+/// let clock = ManualClock::at_millis(1_700_000_000_000);
+/// let context = context_with_remaining(&clock, "req-1", Duration::from_secs(3));
+/// assert_eq!(context.remaining_time_ms_with(&clock), 3_000);
+///
+/// clock.advance(Duration::from_millis(2_500));
+/// assert_eq!(context.remaining_time_ms_with(&clock), 500);
+/// ```
+#[must_use]
+pub fn context_with_remaining(clock: &dyn Clock, request_id: &str, remaining: Duration) -> Context {
+    let deadline = clock.now() + remaining;
+    Context {
+        request_id: request_id.to_string(),
+        deadline_ms: u64::try_from(
+            deadline
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+        )
+        .unwrap_or(u64::MAX),
+        ..Context::default()
+    }
+}
@@ -0,0 +1,42 @@
+// Ruchy Lambda Testkit
+// Helpers for testing handlers outside Lambda
+//
+// Handlers see event bodies as strings. The strategies here generate them
+// in the shapes the Lambda service sends (so handlers can be
+// property-tested against realistic input) and in damaged forms (so they
+// can be tested against the malformed input they will eventually get).
+// `clock` pins and advances time for deadline, TTL and backoff logic.
+// `transport` serves the Runtime API over in-memory streams instead of
+// sockets.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, clippy::all, clippy::pedantic, clippy::cargo)]
+#![allow(clippy::module_name_repetitions, clippy::multiple_crate_versions)]
+
+//! Test helpers for Ruchy Lambda handlers
+//!
+//! # Examples
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use ruchy_lambda_testkit::strategies;
+//!
+//! fn handler(event: &str) -> String {
+//!     match serde_json::from_str::<serde_json::Value>(event) {
+//!         Ok(event) => format!("{}", event["Records"].as_array().map_or(0, Vec::len)),
+//!         Err(_) => "invalid".to_string(),
+//!     }
+//! }
+//!
+//! proptest!(|(event in strategies::sqs_event())| {
+//!     prop_assert_ne!(handler(&event.to_string()), "0");
+//! });
+//!
+//! proptest!(|(event in strategies::malformed_event())| {
+//!     handler(&event); // must not panic
+//! });
+//! ```
+
+pub mod clock;
+pub mod strategies;
+pub mod transport;
@@ -0,0 +1,503 @@
+//! Proptest strategies for Lambda event bodies
+//!
+//! Valid events are [`Value`]s in the shape the Lambda service sends (use
+//! `.to_string()` for the handler's event body, or tweak fields first).
+//! Identifiers, payloads and batch sizes vary; checksums such as SQS
+//! `md5OfBody` are random hex, not real digests.
+//!
+//! Malformed events are strings: valid events truncated, with a field
+//! removed or replaced by a value of another type, as well as arbitrary
+//! JSON and non-JSON text. A damaged event can occasionally still be
+//! valid (say, an optional field removed), so handlers should be checked
+//! for not panicking and for answering with an error, not for rejecting
+//! every input.
+
+use proptest::prelude::*;
+use proptest::sample::select;
+use ruchy_lambda_simd::base64;
+use serde_json::{json, Map, Value};
+
+/// Regions used in ARNs and `awsRegion` fields
+const REGIONS: &[&str] = &[
+    "us-east-1",
+    "us-west-2",
+    "eu-west-1",
+    "eu-central-1",
+    "ap-southeast-2",
+    "sa-east-1",
+];
+
+/// Timestamp used in every generated event
+const EVENT_TIME: &str = "2024-01-01T00:00:00.000Z";
+
+/// AWS region
+pub fn region() -> impl Strategy<Value = String> {
+    select(REGIONS).prop_map(str::to_string)
+}
+
+/// 12-digit account ID
+pub fn account_id() -> impl Strategy<Value = String> {
+    "[0-9]{12}"
+}
+
+/// Request or message ID (UUID v4 format)
+pub fn uuid() -> impl Strategy<Value = String> {
+    "[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}"
+}
+
+/// Queue, topic, bucket or stream name
+pub fn resource_name() -> impl Strategy<Value = String> {
+    "[a-z0-9][a-z0-9-]{0,30}"
+}
+
+/// Payload text: mostly printable, sometimes control characters, quotes
+/// and backslashes that need escaping
+pub fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => "\\PC{0,64}",
+        1 => "[\"\\\\{}\\[\\]:,\n\t\r a-z]{0,32}",
+        1 => any::<String>(),
+    ]
+}
+
+/// Arbitrary JSON value (numbers are finite)
+pub fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::from),
+        (-1e9..1e9f64).prop_map(Value::from),
+        text().prop_map(Value::String),
+    ];
+    leaf.prop_recursive(3, 32, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::btree_map("[a-zA-Z_]{1,8}", inner, 0..4)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+/// HTTP method
+pub fn http_method() -> impl Strategy<Value = String> {
+    select(&["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"][..])
+        .prop_map(str::to_string)
+}
+
+/// Request path: `/` followed by up to five segments
+pub fn http_path() -> impl Strategy<Value = String> {
+    prop::collection::vec("[A-Za-z0-9._~-]{1,12}", 0..5)
+        .prop_map(|segments| format!("/{}", segments.join("/")))
+}
+
+/// Request headers (lowercase names, always with a `content-type`)
+pub fn http_headers() -> impl Strategy<Value = Map<String, Value>> {
+    prop::collection::btree_map("x-[a-z]{1,10}", "[ -~]{0,32}", 0..4).prop_map(|headers| {
+        let mut headers: Map<String, Value> = headers
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value.trim().to_string())))
+            .collect();
+        headers.insert("content-type".to_string(), json!("application/json"));
+        headers
+    })
+}
+
+/// Query string parameters
+fn query_parameters() -> impl Strategy<Value = Map<String, Value>> {
+    prop::collection::btree_map("[a-z]{1,8}", "[A-Za-z0-9]{0,8}", 0..3)
+        .prop_map(|query| query.into_iter().map(|(k, v)| (k, json!(v))).collect())
+}
+
+/// `(body, isBase64Encoded)`: no body, text, or base64 binary
+fn http_body() -> impl Strategy<Value = (Value, bool)> {
+    prop_oneof![
+        Just((Value::Null, false)),
+        text().prop_map(|body| (Value::String(body), false)),
+        prop::collection::vec(any::<u8>(), 0..64)
+            .prop_map(|bytes| (Value::String(base64::encode(&bytes)), true)),
+    ]
+}
+
+/// `null` for an empty map, as API Gateway v1 and ALB send it
+fn object_or_null(map: Map<String, Value>) -> Value {
+    if map.is_empty() {
+        Value::Null
+    } else {
+        Value::Object(map)
+    }
+}
+
+/// API Gateway REST API (payload format 1.0) proxy event
+pub fn apigw_v1_event() -> impl Strategy<Value = Value> {
+    (
+        http_method(),
+        http_path(),
+        http_headers(),
+        query_parameters(),
+        http_body(),
+        uuid(),
+        account_id(),
+    )
+        .prop_map(
+            |(method, path, headers, query, (body, is_base64), request_id, account)| {
+                json!({
+                    "resource": path,
+                    "path": path,
+                    "httpMethod": method,
+                    "headers": headers,
+                    "queryStringParameters": object_or_null(query),
+                    "pathParameters": null,
+                    "stageVariables": null,
+                    "requestContext": {
+                        "accountId": account,
+                        "requestId": request_id,
+                        "stage": "prod",
+                        "httpMethod": method,
+                        "path": format!("/prod{path}"),
+                        "identity": {"sourceIp": "203.0.113.7"},
+                    },
+                    "body": body,
+                    "isBase64Encoded": is_base64,
+                })
+            },
+        )
+}
+
+/// API Gateway HTTP API (payload format 2.0) event
+pub fn apigw_v2_event() -> impl Strategy<Value = Value> {
+    (
+        http_method(),
+        http_path(),
+        http_headers(),
+        query_parameters(),
+        http_body(),
+        uuid(),
+        account_id(),
+    )
+        .prop_map(
+            |(method, path, headers, query, (body, is_base64), request_id, account)| {
+                let raw_query: Vec<String> = query
+                    .iter()
+                    .map(|(k, v)| format!("{k}={}", v.as_str().unwrap_or_default()))
+                    .collect();
+                let mut event = json!({
+                    "version": "2.0",
+                    "routeKey": "$default",
+                    "rawPath": path,
+                    "rawQueryString": raw_query.join("&"),
+                    "headers": headers,
+                    "requestContext": {
+                        "accountId": account,
+                        "requestId": request_id,
+                        "stage": "$default",
+                        "http": {
+                            "method": method,
+                            "path": path,
+                            "protocol": "HTTP/1.1",
+                            "sourceIp": "203.0.113.7",
+                        },
+                    },
+                    "isBase64Encoded": is_base64,
+                });
+                if !query.is_empty() {
+                    event["queryStringParameters"] = Value::Object(query);
+                }
+                if !body.is_null() {
+                    event["body"] = body;
+                }
+                event
+            },
+        )
+}
+
+/// Application Load Balancer target event
+pub fn alb_event() -> impl Strategy<Value = Value> {
+    (
+        http_method(),
+        http_path(),
+        http_headers(),
+        query_parameters(),
+        http_body(),
+        region(),
+        account_id(),
+    )
+        .prop_map(
+            |(method, path, headers, query, (body, is_base64), region, account)| {
+                json!({
+                    "requestContext": {
+                        "elb": {
+                            "targetGroupArn": format!(
+                                "arn:aws:elasticloadbalancing:{region}:{account}:targetgroup/lambda/50dc6c495c0c9188"
+                            ),
+                        },
+                    },
+                    "httpMethod": method,
+                    "path": path,
+                    "queryStringParameters": query,
+                    "headers": headers,
+                    "body": if body.is_null() { json!("") } else { body },
+                    "isBase64Encoded": is_base64,
+                })
+            },
+        )
+}
+
+/// SQS batch of 1 to 10 messages from one queue
+pub fn sqs_event() -> impl Strategy<Value = Value> {
+    (
+        region(),
+        account_id(),
+        resource_name(),
+        prop::collection::vec((uuid(), text(), "[0-9a-f]{32}"), 1..=10),
+    )
+        .prop_map(|(region, account, queue, messages)| {
+            let arn = format!("arn:aws:sqs:{region}:{account}:{queue}");
+            let records: Vec<Value> = messages
+                .into_iter()
+                .map(|(message_id, body, md5)| {
+                    json!({
+                        "messageId": message_id,
+                        "receiptHandle": format!("AQEB{}", message_id.replace('-', "")),
+                        "body": body,
+                        "attributes": {
+                            "ApproximateReceiveCount": "1",
+                            "SentTimestamp": "1704067200000",
+                            "SenderId": "AIDAIENQZJOLO23YVJ4VO",
+                            "ApproximateFirstReceiveTimestamp": "1704067200001",
+                        },
+                        "messageAttributes": {},
+                        "md5OfBody": md5,
+                        "eventSource": "aws:sqs",
+                        "eventSourceARN": arn,
+                        "awsRegion": region,
+                    })
+                })
+                .collect();
+            json!({"Records": records})
+        })
+}
+
+/// SNS notification (one record)
+pub fn sns_event() -> impl Strategy<Value = Value> {
+    (
+        region(),
+        account_id(),
+        resource_name(),
+        uuid(),
+        prop::option::of(text()),
+        text(),
+    )
+        .prop_map(|(region, account, topic, message_id, subject, message)| {
+            let topic_arn = format!("arn:aws:sns:{region}:{account}:{topic}");
+            json!({
+                "Records": [{
+                    "EventSource": "aws:sns",
+                    "EventVersion": "1.0",
+                    "EventSubscriptionArn": format!("{topic_arn}:{message_id}"),
+                    "Sns": {
+                        "Type": "Notification",
+                        "MessageId": message_id,
+                        "TopicArn": topic_arn,
+                        "Subject": subject,
+                        "Message": message,
+                        "Timestamp": EVENT_TIME,
+                        "MessageAttributes": {},
+                    },
+                }],
+            })
+        })
+}
+
+/// S3 notification of 1 to 3 object events in one bucket
+pub fn s3_event() -> impl Strategy<Value = Value> {
+    let object = (
+        select(
+            &[
+                "ObjectCreated:Put",
+                "ObjectCreated:CompleteMultipartUpload",
+                "ObjectRemoved:Delete",
+            ][..],
+        ),
+        "[A-Za-z0-9_.-]{1,12}(/[A-Za-z0-9_.-]{1,12}){0,3}",
+        any::<u32>(),
+        "[0-9a-f]{32}",
+    );
+    (
+        region(),
+        resource_name(),
+        prop::collection::vec(object, 1..=3),
+    )
+        .prop_map(|(region, bucket, objects)| {
+            let records: Vec<Value> = objects
+                .into_iter()
+                .map(|(event_name, key, size, etag)| {
+                    json!({
+                        "eventVersion": "2.1",
+                        "eventSource": "aws:s3",
+                        "awsRegion": region,
+                        "eventTime": EVENT_TIME,
+                        "eventName": event_name,
+                        "s3": {
+                            "s3SchemaVersion": "1.0",
+                            "bucket": {"name": bucket, "arn": format!("arn:aws:s3:::{bucket}")},
+                            "object": {"key": key, "size": size, "eTag": etag},
+                        },
+                    })
+                })
+                .collect();
+            json!({"Records": records})
+        })
+}
+
+/// `EventBridge` event with an arbitrary `detail` object
+pub fn eventbridge_event() -> impl Strategy<Value = Value> {
+    (
+        uuid(),
+        "[A-Z][A-Za-z ]{2,20}",
+        "com\\.[a-z]{1,8}\\.[a-z]{1,8}",
+        account_id(),
+        region(),
+        prop::collection::btree_map("[a-zA-Z_]{1,8}", json_value(), 0..4),
+    )
+        .prop_map(|(id, detail_type, source, account, region, detail)| {
+            json!({
+                "version": "0",
+                "id": id,
+                "detail-type": detail_type.trim(),
+                "source": source,
+                "account": account,
+                "time": EVENT_TIME,
+                "region": region,
+                "resources": [],
+                "detail": Value::Object(detail.into_iter().collect()),
+            })
+        })
+}
+
+/// Any of the valid events above
+pub fn valid_event() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        apigw_v1_event(),
+        apigw_v2_event(),
+        alb_event(),
+        sqs_event(),
+        sns_event(),
+        s3_event(),
+        eventbridge_event(),
+    ]
+}
+
+/// A damaged or foreign event body (see the module docs)
+pub fn malformed_event() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (valid_event(), any::<usize>()).prop_map(|(event, at)| truncate(&event.to_string(), at)),
+        (valid_event(), any::<usize>()).prop_map(|(event, at)| remove_field(event, at).to_string()),
+        (valid_event(), any::<usize>(), json_value())
+            .prop_map(|(event, at, value)| replace_field(event, at, value).to_string()),
+        json_value().prop_map(|value| value.to_string()),
+        any::<String>(),
+        Just(String::new()),
+    ]
+}
+
+/// Valid or malformed event body, half of each
+pub fn event_body() -> impl Strategy<Value = String> {
+    prop_oneof![
+        valid_event().prop_map(|event| event.to_string()),
+        malformed_event(),
+    ]
+}
+
+/// Cut `json` at a character boundary before its end (`at` is reduced
+/// modulo the length)
+fn truncate(json: &str, at: usize) -> String {
+    let mut end = at % json.len().max(1);
+    while !json.is_char_boundary(end) {
+        end -= 1;
+    }
+    json[..end].to_string()
+}
+
+/// JSON pointers to every object member and array element below `value`
+fn pointers(value: &Value, prefix: &str, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let pointer = format!("{prefix}/{}", key.replace('~', "~0").replace('/', "~1"));
+                pointers(child, &pointer, out);
+                out.push(pointer);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                let pointer = format!("{prefix}/{i}");
+                pointers(child, &pointer, out);
+                out.push(pointer);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Pointer to member or element `at` (modulo their count) of `event`
+fn pick_pointer(event: &Value, at: usize) -> Option<String> {
+    let mut all = Vec::new();
+    pointers(event, "", &mut all);
+    (!all.is_empty()).then(|| all.swap_remove(at % all.len()))
+}
+
+fn remove_field(mut event: Value, at: usize) -> Value {
+    let Some(pointer) = pick_pointer(&event, at) else {
+        return event;
+    };
+    let (parent, last) = pointer.rsplit_once('/').unwrap_or_default();
+    match event.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.remove(&last.replace("~1", "/").replace("~0", "~"));
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(i) = last.parse::<usize>() {
+                items.remove(i);
+            }
+        }
+        _ => {}
+    }
+    event
+}
+
+fn replace_field(mut event: Value, at: usize, value: Value) -> Value {
+    if let Some(slot) = pick_pointer(&event, at).and_then(|p| event.pointer_mut(&p)) {
+        *slot = value;
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        let json = r#"{"a":"é"}"#;
+        for at in 0..2 * json.len() {
+            let cut = truncate(json, at);
+            assert!(cut.len() < json.len());
+            assert!(json.starts_with(&cut));
+        }
+        assert_eq!(truncate("", 3), "");
+    }
+
+    #[test]
+    fn test_remove_and_replace_field() {
+        let event = json!({"a": {"b/c": [1, 2]}});
+        let mut all = Vec::new();
+        pointers(&event, "", &mut all);
+        assert_eq!(all, ["/a/b~1c/0", "/a/b~1c/1", "/a/b~1c", "/a"]);
+
+        let removed = remove_field(event.clone(), 2);
+        assert_eq!(removed, json!({"a": {}}));
+
+        let replaced = replace_field(event, 4, Value::Null);
+        assert_eq!(replaced, json!({"a": {"b/c": [null, 2]}}));
+    }
+}
@@ -0,0 +1,348 @@
+//! In-memory transport for Runtime API tests
+//!
+//! [`duplex`] returns two connected [`DuplexStream`]s: bytes written to one
+//! are read from the other, and dropping (or [`DuplexStream::shutdown_write`])
+//! one side ends the other's reads, like a socket. [`DuplexStream::reset`]
+//! fails them with `ConnectionReset` instead.
+//! [`memory_listener`] pairs a [`MemoryDialer`] (hand it to
+//! `Runtime::with_dialer`) with a [`MemoryListener`] a fake Runtime API
+//! accepts from. A dial is queued before it returns, so there is no server
+//! start-up to wait for and no port to bind.
+
+use std::collections::VecDeque;
+use std::io::{self, IoSlice, Read, Write};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+pub use ruchy_lambda_runtime::{Dial, Transport};
+
+/// One direction of a [`duplex`]
+#[derive(Debug, Default)]
+struct Pipe {
+    state: Mutex<PipeState>,
+    readable: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct PipeState {
+    buffer: VecDeque<u8>,
+    writer_closed: bool,
+    reader_closed: bool,
+    /// Reads fail with `ConnectionReset` once drained
+    reset: bool,
+}
+
+impl Pipe {
+    fn state(&self) -> MutexGuard<'_, PipeState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn close_writer(&self) {
+        self.state().writer_closed = true;
+        self.readable.notify_all();
+    }
+
+    fn reset(&self) {
+        self.state().reset = true;
+        self.close_writer();
+    }
+
+    fn close_reader(&self) {
+        self.state().reader_closed = true;
+    }
+}
+
+/// One end of an in-memory connection
+///
+/// Reads block until the peer writes or closes its side; writes fail with
+/// `BrokenPipe` once the peer is dropped.
+#[derive(Debug)]
+pub struct DuplexStream {
+    incoming: Arc<Pipe>,
+    outgoing: Arc<Pipe>,
+    read_timeout: Option<Duration>,
+}
+
+/// Two connected in-memory streams
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_testkit::transport::duplex;
+/// use std::io::{Read, Write};
+///
+/// let (mut client, mut server) = duplex();
+/// client.write_all(b"ping").unwrap();
+/// drop(client);
+///
+/// let mut received = String::new();
+/// server.read_to_string(&mut received).unwrap();
+/// assert_eq!(received, "ping");
+/// ```
+#[must_use]
+pub fn duplex() -> (DuplexStream, DuplexStream) {
+    let (a_to_b, b_to_a) = (Arc::new(Pipe::default()), Arc::new(Pipe::default()));
+    let a = DuplexStream {
+        incoming: Arc::clone(&b_to_a),
+        outgoing: Arc::clone(&a_to_b),
+        read_timeout: None,
+    };
+    let b = DuplexStream {
+        incoming: a_to_b,
+        outgoing: b_to_a,
+        read_timeout: None,
+    };
+    (a, b)
+}
+
+impl DuplexStream {
+    /// Close the writing side: the peer reads end of stream once it has
+    /// drained what was written, while this end can still read
+    pub fn shutdown_write(&self) {
+        self.outgoing.close_writer();
+    }
+
+    /// Abort the connection: once the peer has drained what was written, its
+    /// reads fail with `ConnectionReset` instead of returning end of stream
+    pub fn reset(&self) {
+        self.outgoing.reset();
+    }
+
+    /// Fail reads with `TimedOut` after waiting `timeout` (`None` waits
+    /// forever), so a test that forgets to answer fails instead of hanging
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+}
+
+impl Read for DuplexStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let mut state = self.incoming.state();
+        while state.buffer.is_empty() && !state.writer_closed {
+            state = match self.read_timeout {
+                None => self
+                    .incoming
+                    .readable
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner),
+                Some(timeout) => {
+                    let (state, wait) = self
+                        .incoming
+                        .readable
+                        .wait_timeout(state, timeout)
+                        .unwrap_or_else(PoisonError::into_inner);
+                    if wait.timed_out() && state.buffer.is_empty() && !state.writer_closed {
+                        return Err(io::ErrorKind::TimedOut.into());
+                    }
+                    state
+                }
+            };
+        }
+        if state.buffer.is_empty() && state.reset {
+            return Err(io::ErrorKind::ConnectionReset.into());
+        }
+        let n = buf.len().min(state.buffer.len());
+        for (slot, byte) in buf.iter_mut().zip(state.buffer.drain(..n)) {
+            *slot = byte;
+        }
+        Ok(n)
+    }
+}
+
+impl Write for DuplexStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_vectored(&[IoSlice::new(buf)])
+    }
+
+    /// All buffers arrive together, like a `writev` on a socket with room
+    /// in its send buffer
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let mut state = self.outgoing.state();
+        if state.reader_closed {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        if state.writer_closed {
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "write after shutdown_write",
+            ));
+        }
+        for buf in bufs {
+            state.buffer.extend(buf.iter());
+        }
+        drop(state);
+        self.outgoing.readable.notify_all();
+        Ok(bufs.iter().map(|buf| buf.len()).sum())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for DuplexStream {
+    fn drop(&mut self) {
+        self.outgoing.close_writer();
+        self.incoming.close_reader();
+    }
+}
+
+impl Transport for DuplexStream {}
+
+/// Opens in-memory connections to a [`MemoryListener`]
+///
+/// Dials fail with `ConnectionRefused` once the listener is dropped.
+#[derive(Debug, Clone)]
+pub struct MemoryDialer {
+    connections: Sender<DuplexStream>,
+}
+
+impl Dial for MemoryDialer {
+    fn dial(&self, _endpoint: &str) -> io::Result<Box<dyn Transport>> {
+        let (client, server) = duplex();
+        self.connections
+            .send(server)
+            .map_err(|_| io::Error::from(io::ErrorKind::ConnectionRefused))?;
+        Ok(Box::new(client))
+    }
+}
+
+/// Server side of a [`MemoryDialer`]'s connections, in dial order
+#[derive(Debug)]
+pub struct MemoryListener {
+    connections: Receiver<DuplexStream>,
+}
+
+impl MemoryListener {
+    /// Wait for the next connection
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConnectionAborted` once every dialer is dropped and no
+    /// connection is left
+    pub fn accept(&self) -> io::Result<DuplexStream> {
+        self.connections
+            .recv()
+            .map_err(|_| io::ErrorKind::ConnectionAborted.into())
+    }
+
+    /// Wait up to `timeout` for the next connection
+    ///
+    /// # Errors
+    ///
+    /// Returns `TimedOut` if nothing dials in time and `ConnectionAborted`
+    /// once every dialer is dropped
+    pub fn accept_timeout(&self, timeout: Duration) -> io::Result<DuplexStream> {
+        self.connections
+            .recv_timeout(timeout)
+            .map_err(|error| match error {
+                mpsc::RecvTimeoutError::Timeout => io::ErrorKind::TimedOut.into(),
+                mpsc::RecvTimeoutError::Disconnected => io::ErrorKind::ConnectionAborted.into(),
+            })
+    }
+
+    /// Connections dialed so far and not yet accepted
+    pub fn pending(&self) -> impl Iterator<Item = DuplexStream> + '_ {
+        self.connections.try_iter()
+    }
+}
+
+/// Connected in-memory dialer and listener
+///
+/// # Examples
+///
+/// ```
+/// use ruchy_lambda_runtime::Runtime;
+/// use ruchy_lambda_testkit::transport::memory_listener;
+/// use std::io::{Read, Write};
+///
+/// let (dialer, listener) = memory_listener();
+/// let runtime = Runtime::new().unwrap().with_dialer(dialer);
+///
+/// let server = std::thread::spawn(move || {
+///     let mut connection = listener.accept().unwrap();
+///     let mut request = [0u8; 1024];
+///     let n = connection.read(&mut request).unwrap();
+///     assert!(request[..n].starts_with(b"GET /2018-06-01/runtime/invocation/next "));
+///     connection
+///         .write_all(b"HTTP/1.1 200 OK\r\nLambda-Runtime-Aws-Request-Id: req-1\r\n\r\n{}")
+///         .unwrap();
+/// });
+///
+/// let (request_id, event) = runtime.next_event().unwrap();
+/// assert_eq!((request_id.as_str(), event.as_str()), ("req-1", "{}"));
+/// server.join().unwrap();
+/// ```
+#[must_use]
+pub fn memory_listener() -> (MemoryDialer, MemoryListener) {
+    let (sender, receiver) = mpsc::channel();
+    (
+        MemoryDialer {
+            connections: sender,
+        },
+        MemoryListener {
+            connections: receiver,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplex_both_directions() {
+        let (mut client, mut server) = duplex();
+        client.write_all(b"request").unwrap();
+        client.shutdown_write();
+
+        let mut request = String::new();
+        server.read_to_string(&mut request).unwrap();
+        assert_eq!(request, "request");
+
+        server.write_all(b"response").unwrap();
+        drop(server);
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert_eq!(response, "response");
+        assert_eq!(
+            client.write_all(b"late").unwrap_err().kind(),
+            io::ErrorKind::BrokenPipe
+        );
+    }
+
+    #[test]
+    fn test_reset_after_written_bytes() {
+        let (mut client, mut server) = duplex();
+        server.write_all(b"HTTP/1.1").unwrap();
+        server.reset();
+
+        let mut head = [0u8; 16];
+        assert_eq!(client.read(&mut head).unwrap(), 8);
+        let error = client.read(&mut head).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        let (mut client, _server) = duplex();
+        client.set_read_timeout(Some(Duration::from_millis(10)));
+        let error = client.read(&mut [0u8; 8]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_dial_refused_without_listener() {
+        let (dialer, listener) = memory_listener();
+        assert!(dialer.dial("runtime").is_ok());
+        assert_eq!(listener.pending().count(), 1);
+
+        drop(listener);
+        let error = dialer.dial("runtime").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
@@ -0,0 +1,71 @@
+// Generated events against the runtime's own event parsers
+//
+// Valid events must parse and route the way the Lambda service's events
+// do; malformed ones must be handled without panicking.
+
+use proptest::prelude::*;
+use ruchy_lambda_runtime::{Context, HttpRequest, RecordIter, Router};
+use ruchy_lambda_testkit::strategies;
+use serde_json::Value;
+
+fn router() -> Router {
+    Router::new()
+        .route("ANY /{path+}", |_, _| "http".to_string())
+        .route("ANY /", |_, _| "http".to_string())
+        .route("sqs:*", |_, _| "sqs".to_string())
+        .route("sns:*", |_, _| "sns".to_string())
+        .route("s3:*", |_, _| "s3".to_string())
+        .route("events:*", |_, _| "events".to_string())
+        .fallback(|_, _| "fallback".to_string())
+}
+
+proptest! {
+    #[test]
+    fn test_http_events_parse(
+        event in prop_oneof![
+            strategies::apigw_v1_event(),
+            strategies::apigw_v2_event(),
+            strategies::alb_event(),
+        ]
+    ) {
+        let request = HttpRequest::from_json(&event.to_string()).unwrap();
+        prop_assert!(!request.method.is_empty());
+        prop_assert!(request.path.starts_with('/'));
+        prop_assert_eq!(request.header("content-type"), Some("application/json"));
+        prop_assert!(request.body_bytes().is_ok());
+        prop_assert_eq!(router().handle(&Context::default(), &event.to_string()), "http");
+    }
+
+    #[test]
+    fn test_batches_iterate(
+        event in prop_oneof![
+            strategies::sqs_event(),
+            strategies::sns_event(),
+            strategies::s3_event(),
+        ]
+    ) {
+        let body = event.to_string();
+        let records: Vec<&str> = RecordIter::new(&body).collect::<Result<_, _>>().unwrap();
+        prop_assert_eq!(records.len(), event["Records"].as_array().unwrap().len());
+        for record in records {
+            prop_assert!(serde_json::from_str::<Value>(record).unwrap().is_object());
+        }
+        prop_assert_ne!(router().handle(&Context::default(), &body), "fallback");
+    }
+
+    #[test]
+    fn test_eventbridge_routes(event in strategies::eventbridge_event()) {
+        prop_assert_eq!(router().handle(&Context::default(), &event.to_string()), "events");
+    }
+
+    #[test]
+    fn test_malformed_events_do_not_panic(body in strategies::event_body()) {
+        let _ = HttpRequest::from_json(&body).map(|request| request.body_decompressed());
+        for record in RecordIter::new(&body) {
+            if record.is_err() {
+                break;
+            }
+        }
+        let _ = router().handle(&Context::default(), &body);
+    }
+}
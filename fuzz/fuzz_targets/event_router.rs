@@ -0,0 +1,27 @@
+// Fuzz event-kind detection in the Router
+//
+// Run with: cargo +nightly fuzz run event_router
+//
+// Arbitrary event bodies (usually invalid or oddly shaped JSON) must be
+// classified without panicking, and must reach exactly one handler.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ruchy_lambda_runtime::{Context, Router};
+
+fuzz_target!(|event: &str| {
+    let router = Router::new()
+        .route("GET /orders/{id}", |_, _| "http".to_string())
+        .route("ANY /files/{path+}", |_, _| "http".to_string())
+        .route("sqs:*", |_, _| "sqs".to_string())
+        .route("sns:alerts", |_, _| "sns".to_string())
+        .route("s3:*", |_, _| "s3".to_string())
+        .route("dynamodb:*", |_, _| "dynamodb".to_string())
+        .route("kinesis:*", |_, _| "kinesis".to_string())
+        .route("events:*", |_, _| "events".to_string())
+        .fallback(|_, _| "fallback".to_string());
+
+    let handled = router.handle(&Context::default(), event);
+    assert_eq!(handled == "fallback", !router.matches(event));
+});
@@ -0,0 +1,27 @@
+// Fuzz the Runtime API response parsers
+//
+// Run with: cargo +nightly fuzz run http_response
+//
+// Both parsers must never panic, and whenever the borrowed parser accepts
+// a response the owned parser must accept it too, with the same status
+// line and header names (and body, when it is UTF-8).
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ruchy_lambda_http_core::{parse_response, parse_response_ref};
+
+fuzz_target!(|data: &[u8]| {
+    let owned = parse_response(data);
+    if let Ok(borrowed) = parse_response_ref(data) {
+        let owned = owned.expect("owned parser rejected a response the borrowed parser accepted");
+        assert_eq!(owned.status_line, borrowed.status_line);
+        assert!(owned
+            .header_pairs()
+            .map(|(name, _)| name)
+            .eq(borrowed.header_pairs().map(|(name, _)| name)));
+        if let Ok(body) = std::str::from_utf8(borrowed.body) {
+            assert_eq!(owned.body, body);
+        }
+    }
+});
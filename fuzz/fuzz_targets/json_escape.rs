@@ -0,0 +1,34 @@
+// Fuzz the hand-rolled JSON escaping shared by Logger and HandlerError
+//
+// Run with: cargo +nightly fuzz run json_escape
+//
+// The input is split on NUL into error type, message and stack trace
+// lines. The error document must always be valid JSON that round-trips
+// every string exactly.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ruchy_lambda_runtime::HandlerError;
+use serde_json::Value;
+
+fuzz_target!(|input: &str| {
+    let mut parts = input.split('\0');
+    let mut error = HandlerError::new(
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or_default(),
+    );
+    error.stack_trace = parts.map(str::to_string).collect();
+
+    let json = error.to_json();
+    let document: Value = serde_json::from_str(&json).expect("error document is not valid JSON");
+    assert_eq!(document["errorType"], error.error_type.as_str());
+    assert_eq!(document["errorMessage"], error.error_message.as_str());
+    let trace: Vec<&str> = document["stackTrace"]
+        .as_array()
+        .expect("stackTrace is an array")
+        .iter()
+        .map(|line| line.as_str().expect("stack trace line is a string"))
+        .collect();
+    assert_eq!(trace, error.stack_trace);
+});
@@ -0,0 +1,87 @@
+#!/bin/bash
+# Allocator Comparison Benchmark (bashrs-compliant)
+# Builds the bootstrap once per global allocator feature and compares
+# binary size and cold start (process initialization) side by side
+#
+# Usage: ./scripts/benchmark-allocators.sh [iterations]
+
+set -euo pipefail
+
+readonly SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+readonly PROJECT_ROOT="$(cd "$SCRIPT_DIR/.." && pwd)"
+readonly PROFILE="release-ultra"
+readonly BOOTSTRAP="$PROJECT_ROOT/target/$PROFILE/bootstrap"
+readonly RESULTS_FILE="$PROJECT_ROOT/allocator-benchmark-results.json"
+readonly ITERATIONS="${1:-10}"
+readonly TARGET="8.0"
+
+# "system" = no allocator feature; others map 1:1 to bootstrap cargo features
+readonly ALLOCATORS=("system" "mimalloc")
+
+printf "🧮 Ruchy Lambda - Allocator Benchmark\\n"
+printf "========================================\\n\\n"
+
+cd "$PROJECT_ROOT"
+
+entries=()
+
+for allocator in "${ALLOCATORS[@]}"; do
+    printf "🔨 Building bootstrap (%s, allocator: %s)...\\n" "$PROFILE" "$allocator"
+
+    if [ "$allocator" = "system" ]; then
+        cargo build --profile "$PROFILE" -p ruchy-lambda-bootstrap --quiet
+    else
+        cargo build --profile "$PROFILE" -p ruchy-lambda-bootstrap --features "$allocator" --quiet
+    fi
+
+    size_bytes=$(stat -c%s "$BOOTSTRAP")
+    size_kb=$((size_bytes / 1024))
+
+    # Cold start = fork + exec + runtime initialization, measured until the
+    # bootstrap reports it is entering the event loop (no Runtime API needed)
+    # (stderr is piped too so the bootstrap exits on EPIPE once grep matches)
+    sum=0
+    min=""
+    max=0
+    for _ in $(seq 1 "$ITERATIONS"); do
+        start_us=$(date +%s%6N)
+        (timeout 1s "$BOOTSTRAP" 2>&1 | grep -m1 -q "Entering event processing loop") 2>/dev/null || true
+        end_us=$(date +%s%6N)
+        duration=$((end_us - start_us))
+
+        sum=$((sum + duration))
+        if [ -z "$min" ] || [ "$duration" -lt "$min" ]; then
+            min=$duration
+        fi
+        if [ "$duration" -gt "$max" ]; then
+            max=$duration
+        fi
+    done
+    avg=$((sum / ITERATIONS))
+
+    printf "  Binary:     %dKB\\n" "$size_kb"
+    printf "  Cold start: avg %dμs, min %dμs, max %dμs\\n\\n" "$avg" "$min" "$max"
+
+    entries+=("    {\"allocator\": \"$allocator\", \"binary_size_kb\": $size_kb, \"cold_start_us\": {\"avg\": $avg, \"min\": $min, \"max\": $max}}")
+done
+
+# Save results
+{
+    printf "{\\n"
+    printf "  \"profile\": \"%s\",\\n" "$PROFILE"
+    printf "  \"iterations\": %d,\\n" "$ITERATIONS"
+    printf "  \"target_ms\": \"%s\",\\n" "$TARGET"
+    printf "  \"results\": [\\n"
+    last=$((${#entries[@]} - 1))
+    for i in "${!entries[@]}"; do
+        if [ "$i" -lt "$last" ]; then
+            printf "%s,\\n" "${entries[$i]}"
+        else
+            printf "%s\\n" "${entries[$i]}"
+        fi
+    done
+    printf "  ]\\n"
+    printf "}\\n"
+} > "$RESULTS_FILE"
+
+printf "💾 Results saved to: %s\\n" "$RESULTS_FILE"